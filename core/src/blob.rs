@@ -0,0 +1,294 @@
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Defines a storage of raw values by key, such as an NVS namespace, that
+/// [load] and [save] serialize values into.
+pub trait BlobStore {
+    /// Reads the value of a key into `buf`.
+    ///
+    /// ## Returns
+    /// - `Ok(Some(&[u8]))`: The bytes of the value, at the start of `buf`.
+    /// - `Ok(None)`: If the key is not set.
+    /// - `Err(String)`: If the value can't be read, e.g. `buf` is too small.
+    fn get_blob<'a>(&mut self, key: &str, buf: &'a mut [u8]) -> Result<Option<&'a [u8]>, String>;
+
+    /// Writes the value of a key, replacing the previous one.
+    fn set_blob(&mut self, key: &str, data: &[u8]) -> Result<(), String>;
+}
+
+/// Reads a value serialized with postcard, of up to `N` bytes.
+///
+/// ## Returns
+/// - `Ok(None)`: If the key is not set.
+/// - `Err(String)`: If the value can't be read or deserialized.
+///
+/// ## Example
+/// ```rust
+/// let settings = blob::load::<Settings, 192>(&mut app_storage, "settings")?.unwrap_or_default();
+/// ```
+pub fn load<T: DeserializeOwned, const N: usize>(
+    store: &mut impl BlobStore,
+    key: &str,
+) -> Result<Option<T>, String> {
+    let mut buf = [0u8; N];
+
+    match store.get_blob(key, &mut buf)? {
+        Some(bytes) => postcard::from_bytes(bytes)
+            .map(Some)
+            .map_err(|e| format!("Failed to deserialize '{key}': {e:?}")),
+        None => Ok(None),
+    }
+}
+
+/// Serializes a value with postcard, in up to `N` bytes, and writes it.
+///
+/// ## Returns
+/// - `Err(String)`: If the value doesn't fit in `N` bytes or can't be written.
+///
+/// ## Example
+/// ```rust
+/// blob::save::<Settings, 192>(&mut app_storage, "settings", &settings)?;
+/// ```
+pub fn save<T: Serialize, const N: usize>(
+    store: &mut impl BlobStore,
+    key: &str,
+    value: &T,
+) -> Result<(), String> {
+    let mut buf = [0u8; N];
+    let bytes = postcard::to_slice(value, &mut buf)
+        .map_err(|e| format!("Failed to serialize '{key}': {e:?}"))?;
+
+    store.set_blob(key, bytes)
+}
+
+/// Serializes a value with postcard after a version byte, in up to `N` bytes,
+/// and writes it, see [load_versioned].
+///
+/// ## Returns
+/// - `Err(String)`: If the value doesn't fit in `N` bytes or can't be written.
+///
+/// ## Example
+/// ```rust
+/// blob::save_versioned::<Settings, 192>(&mut app_storage, "settings", 1, &settings)?;
+/// ```
+pub fn save_versioned<T: Serialize, const N: usize>(
+    store: &mut impl BlobStore,
+    key: &str,
+    version: u8,
+    value: &T,
+) -> Result<(), String> {
+    let mut buf = [0u8; N];
+    let (version_byte, value_buf) = buf
+        .split_first_mut()
+        .ok_or_else(|| format!("Failed to serialize '{key}': no room for the version"))?;
+    *version_byte = version;
+
+    let len = postcard::to_slice(value, value_buf)
+        .map_err(|e| format!("Failed to serialize '{key}': {e:?}"))?
+        .len();
+
+    store.set_blob(key, &buf[..len + 1])
+}
+
+/// Reads a value saved with [save_versioned], of up to `N` bytes.
+///
+/// The version and the postcard bytes are passed to `decode`, so a value saved
+/// by an older firmware can still be read with its own layout once fields are
+/// added.
+///
+/// ## Returns
+/// - `Ok(None)`: If the key is not set.
+/// - `Err(String)`: If the value is empty, or can't be read or decoded.
+///
+/// ## Example
+/// ```rust
+/// let settings = blob::load_versioned::<_, 192>(
+///     &mut app_storage,
+///     "settings",
+///     |version, bytes| match version {
+///         1 => postcard::from_bytes(bytes).map_err(|e| e.to_string()),
+///         _ => Err(format!("Unknown version {version}")),
+///     },
+/// )?;
+/// ```
+pub fn load_versioned<T, const N: usize>(
+    store: &mut impl BlobStore,
+    key: &str,
+    decode: impl FnOnce(u8, &[u8]) -> Result<T, String>,
+) -> Result<Option<T>, String> {
+    let mut buf = [0u8; N];
+
+    let Some(bytes) = store.get_blob(key, &mut buf)? else {
+        return Ok(None);
+    };
+    let (version, value_bytes) = bytes
+        .split_first()
+        .ok_or_else(|| format!("Failed to deserialize '{key}': empty value"))?;
+
+    decode(*version, value_bytes)
+        .map(Some)
+        .map_err(|e| format!("Failed to deserialize '{key}' version {version}: {e}"))
+}
+
+/// An in-memory [BlobStore], standing in for NVS in the tests.
+#[cfg(any(test, feature = "mock"))]
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    blobs: std::collections::HashMap<String, Vec<u8>>,
+}
+
+#[cfg(any(test, feature = "mock"))]
+impl MemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether a key is set.
+    pub fn contains(&self, key: &str) -> bool {
+        self.blobs.contains_key(key)
+    }
+}
+
+#[cfg(any(test, feature = "mock"))]
+impl BlobStore for MemoryStore {
+    fn get_blob<'a>(&mut self, key: &str, buf: &'a mut [u8]) -> Result<Option<&'a [u8]>, String> {
+        let Some(blob) = self.blobs.get(key) else {
+            return Ok(None);
+        };
+
+        let target = buf
+            .get_mut(..blob.len())
+            .ok_or_else(|| format!("Buffer too small for key '{key}'"))?;
+        target.copy_from_slice(blob);
+
+        Ok(Some(target))
+    }
+
+    fn set_blob(&mut self, key: &str, data: &[u8]) -> Result<(), String> {
+        self.blobs.insert(key.to_string(), data.to_vec());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Prefs {
+        timezone: Option<String>,
+        theme: Option<u8>,
+        brightness: Option<u8>,
+    }
+
+    #[test]
+    fn round_trips_a_value() {
+        let mut store = MemoryStore::new();
+        let prefs = Prefs {
+            timezone: Some("America/Sao_Paulo".to_string()),
+            theme: Some(3),
+            brightness: None,
+        };
+
+        save::<_, 64>(&mut store, "prefs", &prefs).unwrap();
+
+        assert!(store.contains("prefs"));
+        assert_eq!(load::<Prefs, 64>(&mut store, "prefs"), Ok(Some(prefs)));
+    }
+
+    #[test]
+    fn loads_none_for_a_missing_key() {
+        let mut store = MemoryStore::new();
+
+        assert_eq!(load::<Prefs, 64>(&mut store, "prefs"), Ok(None));
+    }
+
+    #[test]
+    fn fails_to_save_a_value_too_large() {
+        let mut store = MemoryStore::new();
+        let prefs = Prefs {
+            timezone: Some("CET-1CEST,M3.5.0,M10.5.0/3".to_string()),
+            ..Default::default()
+        };
+
+        assert!(save::<_, 8>(&mut store, "prefs", &prefs).is_err());
+        assert!(!store.contains("prefs"));
+    }
+
+    /// Decodes the [Prefs] of version 2, and the ones of version 1, which had
+    /// no brightness yet.
+    fn decode_prefs(version: u8, bytes: &[u8]) -> Result<Prefs, String> {
+        #[derive(Deserialize)]
+        struct PrefsV1 {
+            timezone: Option<String>,
+            theme: Option<u8>,
+        }
+
+        match version {
+            1 => postcard::from_bytes::<PrefsV1>(bytes)
+                .map(|prefs| Prefs {
+                    timezone: prefs.timezone,
+                    theme: prefs.theme,
+                    brightness: None,
+                })
+                .map_err(|e| e.to_string()),
+            2 => postcard::from_bytes(bytes).map_err(|e| e.to_string()),
+            _ => Err(format!("Unknown version {version}")),
+        }
+    }
+
+    #[test]
+    fn round_trips_a_versioned_value() {
+        let mut store = MemoryStore::new();
+        let prefs = Prefs {
+            timezone: Some("Europe/Berlin".to_string()),
+            theme: Some(1),
+            brightness: Some(5),
+        };
+
+        save_versioned::<_, 64>(&mut store, "prefs", 2, &prefs).unwrap();
+
+        assert_eq!(
+            load_versioned::<_, 64>(&mut store, "prefs", decode_prefs),
+            Ok(Some(prefs))
+        );
+    }
+
+    #[test]
+    fn loads_an_older_version_with_its_layout() {
+        let mut store = MemoryStore::new();
+        let mut buf = [1u8; 64];
+        let len = postcard::to_slice(&(Some("UTC"), Some(2u8)), &mut buf[1..])
+            .unwrap()
+            .len();
+        store.set_blob("prefs", &buf[..len + 1]).unwrap();
+
+        assert_eq!(
+            load_versioned::<_, 64>(&mut store, "prefs", decode_prefs),
+            Ok(Some(Prefs {
+                timezone: Some("UTC".to_string()),
+                theme: Some(2),
+                brightness: None,
+            }))
+        );
+    }
+
+    #[test]
+    fn fails_to_load_an_unknown_or_empty_version() {
+        let mut store = MemoryStore::new();
+        store.set_blob("unknown", &[9, 0, 0, 0]).unwrap();
+        store.set_blob("empty", &[]).unwrap();
+
+        assert!(load_versioned::<_, 64>(&mut store, "unknown", decode_prefs).is_err());
+        assert!(load_versioned::<_, 64>(&mut store, "empty", decode_prefs).is_err());
+    }
+
+    #[test]
+    fn fails_to_load_a_corrupted_value() {
+        let mut store = MemoryStore::new();
+        store.set_blob("prefs", &[0xff, 0xff, 0xff]).unwrap();
+
+        assert!(load::<Prefs, 64>(&mut store, "prefs").is_err());
+    }
+}
@@ -0,0 +1,263 @@
+use std::net::Ipv4Addr;
+
+/// Length of the header of a DNS message.
+const HEADER_LEN: usize = 12;
+
+/// Record type of an IPv4 address.
+pub const TYPE_A: u16 = 1;
+
+/// The Internet class, the only one answered.
+const CLASS_IN: u16 = 1;
+
+/// Response code of a query that was answered, even without records.
+const RCODE_NO_ERROR: u8 = 0;
+
+/// Response code of a malformed query.
+const RCODE_FORMAT_ERROR: u8 = 1;
+
+/// Response code of a query of a kind that isn't supported.
+const RCODE_NOT_IMPLEMENTED: u8 = 4;
+
+/// Max length of a label of a domain name.
+const MAX_LABEL_LEN: usize = 63;
+
+/// Seconds the clients may cache the answers, unless configured otherwise.
+pub const DEFAULT_TTL_SECS: u32 = 10;
+
+/// The question of a DNS query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Question {
+    /// The queried domain name, e.g. `connectivitycheck.gstatic.com`.
+    pub name: String,
+    /// The record type, e.g. [TYPE_A].
+    pub qtype: u16,
+    /// The class, `1` for the Internet.
+    pub qclass: u16,
+    /// Offset of the end of the question in the query.
+    end: usize,
+}
+
+/// Reads a big-endian `u16` at the given offset.
+fn read_u16(packet: &[u8], offset: usize) -> Option<u16> {
+    packet
+        .get(offset..offset + 2)
+        .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+/// Parses the first question of a DNS query, right after the header.
+///
+/// ## Returns
+/// - `Some(Question)`: The question, its name lowercased.
+/// - `None`: If the question is truncated or its name is malformed, e.g. it
+///   uses a compression pointer, which queries have no reason to.
+pub fn parse_question(packet: &[u8]) -> Option<Question> {
+    let mut labels = Vec::new();
+    let mut offset = HEADER_LEN;
+
+    loop {
+        let len = *packet.get(offset)? as usize;
+        offset += 1;
+
+        if len == 0 {
+            break;
+        }
+        if len > MAX_LABEL_LEN {
+            return None;
+        }
+
+        let label = packet.get(offset..offset + len)?;
+        labels.push(String::from_utf8_lossy(label).to_lowercase());
+        offset += len;
+    }
+
+    Some(Question {
+        name: labels.join("."),
+        qtype: read_u16(packet, offset)?,
+        qclass: read_u16(packet, offset + 2)?,
+        end: offset + 4,
+    })
+}
+
+/// Builds the response of the captive portal to a DNS query: every `A`
+/// query of the Internet class is answered with the given address, so any
+/// domain leads to the clock. The clients may cache the answer for
+/// `ttl_secs` seconds.
+///
+/// ## Behavior
+/// - Other record types, e.g. `AAAA`, get an empty `NOERROR` answer, so the
+///   clients fall back to IPv4.
+/// - Other opcodes and classes, and queries with more than one question, get
+///   `NOTIMP`.
+/// - Malformed questions get `FORMERR`.
+/// - Only the question is echoed, without the additional records of the query,
+///   e.g. EDNS options.
+///
+/// ## Returns
+/// - `Some(Vec<u8>)`: The response to send back.
+/// - `None`: If the packet is too short to be a query, or is a response.
+///
+/// ## Example
+/// ```rust
+/// if let Some(response) = dns::respond(&buffer[..length], ip_address, dns::DEFAULT_TTL_SECS) {
+///     udp_socket.send_to(&response, client_addr)?;
+/// }
+/// ```
+pub fn respond(query: &[u8], ip_address: Ipv4Addr, ttl_secs: u32) -> Option<Vec<u8>> {
+    if query.len() < HEADER_LEN || query[2] & 0x80 != 0 {
+        return None;
+    }
+
+    let opcode = (query[2] >> 3) & 0x0f;
+    let question_count = read_u16(query, 4)?;
+
+    let question = if opcode == 0 && question_count == 1 {
+        parse_question(query).ok_or(RCODE_FORMAT_ERROR)
+    } else {
+        Err(RCODE_NOT_IMPLEMENTED)
+    };
+
+    let (rcode, question, answer) = match question {
+        Ok(question) if question.qclass != CLASS_IN => {
+            (RCODE_NOT_IMPLEMENTED, Some(question), false)
+        }
+        Ok(question) => {
+            let answer = question.qtype == TYPE_A;
+            (RCODE_NO_ERROR, Some(question), answer)
+        }
+        Err(rcode) => (rcode, None, false),
+    };
+
+    let mut response = Vec::with_capacity(query.len() + 16);
+    response.extend_from_slice(&query[0..2]);
+    // Response, same opcode, authoritative, recursion desired as queried
+    response.push(0x80 | (opcode << 3) | 0x04 | (query[2] & 0x01));
+    response.push(rcode);
+    response.extend_from_slice(&(question.is_some() as u16).to_be_bytes());
+    response.extend_from_slice(&(answer as u16).to_be_bytes());
+    response.extend_from_slice(&[0, 0, 0, 0]);
+
+    if let Some(question) = question {
+        response.extend_from_slice(&query[HEADER_LEN..question.end]);
+    }
+
+    if answer {
+        // A pointer to the name of the question, right after the header
+        response.extend_from_slice(&[0xc0, HEADER_LEN as u8]);
+        response.extend_from_slice(&TYPE_A.to_be_bytes());
+        response.extend_from_slice(&CLASS_IN.to_be_bytes());
+        response.extend_from_slice(&ttl_secs.to_be_bytes());
+        response.extend_from_slice(&4u16.to_be_bytes());
+        response.extend_from_slice(&ip_address.octets());
+    }
+
+    Some(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AP_IP: Ipv4Addr = Ipv4Addr::new(192, 168, 71, 1);
+
+    /// Builds a standard query for a name, with recursion desired.
+    fn query(name: &str, qtype: u16) -> Vec<u8> {
+        let mut packet = vec![0x12, 0x34, 0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0];
+        for label in name.split('.') {
+            packet.push(label.len() as u8);
+            packet.extend_from_slice(label.as_bytes());
+        }
+        packet.push(0);
+        packet.extend_from_slice(&qtype.to_be_bytes());
+        packet.extend_from_slice(&CLASS_IN.to_be_bytes());
+        packet
+    }
+
+    #[test]
+    fn parses_the_question() {
+        let question = parse_question(&query("Captive.Apple.com", 28)).unwrap();
+
+        assert_eq!(question.name, "captive.apple.com");
+        assert_eq!(question.qtype, 28);
+        assert_eq!(question.qclass, CLASS_IN);
+    }
+
+    #[test]
+    fn answers_a_queries_with_the_address() {
+        let query = query("connectivitycheck.gstatic.com", TYPE_A);
+        let response = respond(&query, AP_IP, DEFAULT_TTL_SECS).unwrap();
+
+        assert_eq!(&response[0..2], &[0x12, 0x34]);
+        assert_eq!(response[2], 0x85);
+        assert_eq!(response[3], RCODE_NO_ERROR);
+        assert_eq!(&response[4..8], &[0, 1, 0, 1]);
+        assert_eq!(&response[HEADER_LEN..query.len()], &query[HEADER_LEN..]);
+        assert_eq!(&response[response.len() - 4..], &AP_IP.octets());
+    }
+
+    #[test]
+    fn answers_with_the_given_ttl() {
+        let response = respond(&query("example.com", TYPE_A), AP_IP, 300).unwrap();
+        let ttl = &response[response.len() - 10..response.len() - 6];
+
+        assert_eq!(ttl, &300u32.to_be_bytes());
+    }
+
+    #[test]
+    fn answers_other_types_without_records() {
+        let query = query("captive.apple.com", 28);
+        let response = respond(&query, AP_IP, DEFAULT_TTL_SECS).unwrap();
+
+        assert_eq!(response[3], RCODE_NO_ERROR);
+        assert_eq!(&response[4..8], &[0, 1, 0, 0]);
+        assert_eq!(response.len(), query.len());
+    }
+
+    #[test]
+    fn drops_the_additional_records() {
+        let mut query = query("example.com", TYPE_A);
+        let question_len = query.len();
+        query[11] = 1;
+        // An EDNS OPT record
+        query.extend_from_slice(&[0, 0, 41, 0x10, 0, 0, 0, 0, 0, 0, 0]);
+
+        let response = respond(&query, AP_IP, DEFAULT_TTL_SECS).unwrap();
+
+        assert_eq!(&response[8..12], &[0, 0, 0, 0]);
+        assert_eq!(response.len(), question_len + 16);
+    }
+
+    #[test]
+    fn rejects_unsupported_queries() {
+        let mut two_questions = query("example.com", TYPE_A);
+        two_questions[5] = 2;
+        let mut inverse = query("example.com", TYPE_A);
+        inverse[2] |= 1 << 3;
+
+        for query in [two_questions, inverse] {
+            let response = respond(&query, AP_IP, DEFAULT_TTL_SECS).unwrap();
+
+            assert_eq!(response[3], RCODE_NOT_IMPLEMENTED);
+            assert_eq!(response.len(), HEADER_LEN);
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_questions() {
+        let mut truncated = query("example.com", TYPE_A);
+        truncated.truncate(HEADER_LEN + 4);
+
+        let response = respond(&truncated, AP_IP, DEFAULT_TTL_SECS).unwrap();
+
+        assert_eq!(response[3], RCODE_FORMAT_ERROR);
+        assert_eq!(&response[4..6], &[0, 0]);
+    }
+
+    #[test]
+    fn ignores_short_packets_and_responses() {
+        let mut response = query("example.com", TYPE_A);
+        response[2] |= 0x80;
+
+        assert_eq!(respond(&[0x12, 0x34], AP_IP, DEFAULT_TTL_SECS), None);
+        assert_eq!(respond(&response, AP_IP, DEFAULT_TTL_SECS), None);
+    }
+}
@@ -0,0 +1,15 @@
+//! The logic of the clock that doesn't touch the hardware: the seven-segment
+//! encoding, the time formatting, the theme colors, the query strings, the DNS
+//! answers of the captive portal, the NTP answers of the LAN time server and
+//! the serialization of the settings stored in NVS.
+//!
+//! It only depends on `std`, so `just test` runs its tests on the host, while
+//! the firmware re-exports it from the modules it came from.
+
+pub mod blob;
+pub mod dns;
+pub mod ntp;
+pub mod query;
+pub mod segments;
+pub mod theme;
+pub mod time;
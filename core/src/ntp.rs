@@ -0,0 +1,157 @@
+use std::time::Duration;
+
+/// Length of an NTP packet without extensions.
+pub const PACKET_LEN: usize = 48;
+
+/// Seconds from the NTP epoch, 1900, to the Unix epoch, 1970.
+const NTP_UNIX_OFFSET_SECS: u64 = 2_208_988_800;
+
+/// Mode of a request sent by a client.
+const MODE_CLIENT: u8 = 3;
+
+/// Mode of the response of a server.
+const MODE_SERVER: u8 = 4;
+
+/// Stratum announced to the clients. The stratum of the upstream server isn't
+/// known, so one a few hops away from a reference clock is assumed.
+pub const STRATUM: u8 = 3;
+
+/// Precision of the clock, as a power of two in seconds, about a millisecond.
+const PRECISION: i8 = -10;
+
+/// Root dispersion announced to the clients, 1/16 of a second in the 16.16
+/// fixed-point format, for the drift of the clock between two syncs.
+const ROOT_DISPERSION: u32 = 0x0000_1000;
+
+/// Converts a time since the Unix epoch to an NTP timestamp: seconds since
+/// 1900 and their fraction, both big-endian.
+pub fn ntp_timestamp(since_unix_epoch: Duration) -> [u8; 8] {
+    let seconds = (since_unix_epoch.as_secs() + NTP_UNIX_OFFSET_SECS) as u32;
+    let fraction = ((since_unix_epoch.subsec_nanos() as u64) << 32) / 1_000_000_000;
+
+    let mut timestamp = [0; 8];
+    timestamp[..4].copy_from_slice(&seconds.to_be_bytes());
+    timestamp[4..].copy_from_slice(&(fraction as u32).to_be_bytes());
+    timestamp
+}
+
+/// Builds the response of the clock to an NTP request, as a server synced
+/// from SNTP. All times are since the Unix epoch.
+///
+/// ## Arguments
+/// - `request` - The datagram received from the client.
+/// - `reference` - When the clock was last synced.
+/// - `received` - When the request was received.
+/// - `transmit` - Now, when the response is sent.
+///
+/// ## Returns
+/// - `Some([u8; PACKET_LEN])`: The response, with the version of the request.
+/// - `None`: If the datagram is too short or not sent by a client.
+///
+/// ## Example
+/// ```rust
+/// if let Some(response) = ntp::respond(&buffer[..length], last_sync, received, now()) {
+///     udp_socket.send_to(&response, client_addr)?;
+/// }
+/// ```
+pub fn respond(
+    request: &[u8],
+    reference: Duration,
+    received: Duration,
+    transmit: Duration,
+) -> Option<[u8; PACKET_LEN]> {
+    if request.len() < PACKET_LEN || request[0] & 0x07 != MODE_CLIENT {
+        return None;
+    }
+
+    let version = (request[0] >> 3) & 0x07;
+
+    let mut response = [0; PACKET_LEN];
+    // No leap second warning
+    response[0] = (version << 3) | MODE_SERVER;
+    response[1] = STRATUM;
+    response[2] = request[2];
+    response[3] = PRECISION as u8;
+    response[8..12].copy_from_slice(&ROOT_DISPERSION.to_be_bytes());
+    response[16..24].copy_from_slice(&ntp_timestamp(reference));
+    // The transmit time of the client, so it can match the response
+    response[24..32].copy_from_slice(&request[40..48]);
+    response[32..40].copy_from_slice(&ntp_timestamp(received));
+    response[40..48].copy_from_slice(&ntp_timestamp(transmit));
+
+    Some(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Builds an NTPv4 client request with the given transmit timestamp.
+    fn request(transmit: [u8; 8]) -> [u8; PACKET_LEN] {
+        let mut packet = [0; PACKET_LEN];
+        packet[0] = (4 << 3) | MODE_CLIENT;
+        packet[2] = 6;
+        packet[40..48].copy_from_slice(&transmit);
+        packet
+    }
+
+    #[test]
+    fn converts_unix_times_to_ntp_timestamps() {
+        assert_eq!(
+            ntp_timestamp(Duration::ZERO),
+            [0x83, 0xaa, 0x7e, 0x80, 0, 0, 0, 0]
+        );
+        assert_eq!(
+            ntp_timestamp(Duration::from_millis(500)),
+            [0x83, 0xaa, 0x7e, 0x80, 0x80, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn answers_client_requests() {
+        let client_transmit = [1, 2, 3, 4, 5, 6, 7, 8];
+        let reference = Duration::from_secs(1_700_000_000);
+        let received = reference + Duration::from_secs(60);
+        let transmit = received + Duration::from_millis(1);
+
+        let response = respond(&request(client_transmit), reference, received, transmit).unwrap();
+
+        assert_eq!(response[0], (4 << 3) | MODE_SERVER);
+        assert_eq!(response[1], STRATUM);
+        assert_eq!(response[2], 6);
+        assert_eq!(&response[16..24], &ntp_timestamp(reference));
+        assert_eq!(&response[24..32], &client_transmit);
+        assert_eq!(&response[32..40], &ntp_timestamp(received));
+        assert_eq!(&response[40..48], &ntp_timestamp(transmit));
+    }
+
+    #[test]
+    fn keeps_the_version_of_the_request() {
+        let mut request = request([0; 8]);
+        request[0] = (3 << 3) | MODE_CLIENT;
+
+        let response = respond(&request, Duration::ZERO, Duration::ZERO, Duration::ZERO).unwrap();
+
+        assert_eq!(response[0], (3 << 3) | MODE_SERVER);
+    }
+
+    #[test]
+    fn ignores_short_packets_and_other_modes() {
+        let mut server_packet = request([0; 8]);
+        server_packet[0] = (4 << 3) | MODE_SERVER;
+
+        assert_eq!(
+            respond(&[0x23; 12], Duration::ZERO, Duration::ZERO, Duration::ZERO),
+            None
+        );
+        assert_eq!(
+            respond(
+                &server_packet,
+                Duration::ZERO,
+                Duration::ZERO,
+                Duration::ZERO
+            ),
+            None
+        );
+    }
+}
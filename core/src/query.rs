@@ -0,0 +1,150 @@
+/// The parsed query string of a request URI.
+///
+/// Pairs are split on `&` and `=`, and keys and values are URL-decoded. A pair
+/// without `=` is kept as a bare value, which is how most handlers receive a
+/// single setting (e.g. `/set_brightness?5`).
+///
+/// ## Example
+/// ```rust
+/// let query = QueryParams::from_uri("/set_theme?theme=custom&r=10&g=20&b=30");
+/// assert_eq!(query.get("theme"), Some("custom"));
+/// ```
+pub struct QueryParams {
+    pairs: Vec<(String, Option<String>)>,
+}
+
+impl QueryParams {
+    /// Parses the query string of a request URI. A URI without `?` gives an
+    /// empty [QueryParams].
+    pub fn from_uri(uri: &str) -> Self {
+        let query = uri.split_once('?').map(|(_, query)| query).unwrap_or("");
+
+        Self::from_form(query)
+    }
+
+    /// Parses an `application/x-www-form-urlencoded` body, as sent by a plain
+    /// HTML form, which is encoded like a query string.
+    ///
+    /// ## Example
+    /// ```rust
+    /// let form = QueryParams::from_form("ssid=My+Network&password=p%40ss");
+    /// assert_eq!(form.get("ssid"), Some("My Network"));
+    /// ```
+    pub fn from_form(body: &str) -> Self {
+        let pairs = body
+            .split('&')
+            .filter(|pair| !pair.is_empty())
+            .map(|pair| match pair.split_once('=') {
+                Some((key, value)) => (url_decode(key), Some(url_decode(value))),
+                None => (url_decode(pair), None),
+            })
+            .collect();
+
+        Self { pairs }
+    }
+
+    /// Returns whether the URI had no query parameters.
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Returns the value of the first `key=value` pair with the given key.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.pairs
+            .iter()
+            .find(|(pair_key, value)| pair_key == key && value.is_some())
+            .and_then(|(_, value)| value.as_deref())
+    }
+
+    /// Returns the first value given without a key, e.g. `5` in `?5`.
+    pub fn bare(&self) -> Option<&str> {
+        self.pairs
+            .iter()
+            .find(|(_, value)| value.is_none())
+            .map(|(bare, _)| bare.as_str())
+    }
+
+    /// Returns the value of the given key, falling back to the bare value, so
+    /// both `?brightness=5` and `?5` are accepted.
+    pub fn get_or_bare(&self, key: &str) -> Option<&str> {
+        self.get(key).or_else(|| self.bare())
+    }
+}
+
+/// Decodes a URL-encoded string, turning `+` into a space and `%XX` escapes
+/// into their bytes. Malformed escapes are kept as they are.
+pub fn url_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => decoded.push(b' '),
+            b'%' => {
+                let hex = bytes
+                    .get(i + 1..i + 3)
+                    .and_then(|hex| std::str::from_utf8(hex).ok())
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+                match hex {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 2;
+                    }
+                    None => decoded.push(b'%'),
+                }
+            }
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+
+    String::from_utf8_lossy(&decoded).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_key_value_pairs() {
+        let query = QueryParams::from_uri("/set_theme?theme=custom&r=10&g=20&b=30");
+
+        assert_eq!(query.get("theme"), Some("custom"));
+        assert_eq!(query.get("b"), Some("30"));
+        assert_eq!(query.get("missing"), None);
+    }
+
+    #[test]
+    fn falls_back_to_the_bare_value() {
+        let query = QueryParams::from_uri("/set_brightness?5");
+
+        assert_eq!(query.get("brightness"), None);
+        assert_eq!(query.bare(), Some("5"));
+        assert_eq!(query.get_or_bare("brightness"), Some("5"));
+    }
+
+    #[test]
+    fn parses_a_form_body() {
+        let form = QueryParams::from_form("ssid=My+Network&password=p%40ss%26word&hidden=on");
+
+        assert_eq!(form.get("ssid"), Some("My Network"));
+        assert_eq!(form.get("password"), Some("p@ss&word"));
+        assert_eq!(form.get("hidden"), Some("on"));
+    }
+
+    #[test]
+    fn is_empty_without_a_query_string() {
+        assert!(QueryParams::from_uri("/api/health").is_empty());
+        assert!(QueryParams::from_uri("/api/health?").is_empty());
+    }
+
+    #[test]
+    fn decodes_escapes_and_keeps_malformed_ones() {
+        assert_eq!(url_decode("America%2FSao_Paulo"), "America/Sao_Paulo");
+        assert_eq!(url_decode("hello+world"), "hello world");
+        assert_eq!(url_decode("100%"), "100%");
+        assert_eq!(url_decode("%zz"), "%zz");
+    }
+}
@@ -0,0 +1,138 @@
+/// A lookup table for displaying digits (0-9) on a 7-segment display.
+pub const DISPLAY_DIGIT: [u8; 10] = [
+    0b00111111, // 0
+    0b00000110, // 1
+    0b01011011, // 2
+    0b01001111, // 3
+    0b01100110, // 4
+    0b01101101, // 5
+    0b01111101, // 6
+    0b00000111, // 7
+    0b01111111, // 8
+    0b01101111, // 9
+];
+
+/// Converts a character into its seven-segment pattern.
+///
+/// Digits use [DISPLAY_DIGIT] and letters are case-insensitive. Besides
+/// letters and digits, a few symbols like the dash and the degree sign are
+/// supported. Characters without a readable pattern are shown as a blank
+/// digit.
+///
+/// ## Example
+/// ```rust
+/// let segments: Vec<u8> = "CLOCKS".chars().map(encode_char).collect();
+/// ```
+pub fn encode_char(c: char) -> u8 {
+    match c.to_ascii_uppercase() {
+        '0'..='9' => DISPLAY_DIGIT[c as usize - '0' as usize],
+        'A' => 0b01110111,
+        'B' => 0b01111100,
+        'C' => 0b00111001,
+        'D' => 0b01011110,
+        'E' => 0b01111001,
+        'F' => 0b01110001,
+        'G' => 0b00111101,
+        'H' => 0b01110110,
+        'I' => 0b00000110,
+        'J' => 0b00011110,
+        'K' => 0b01110101,
+        'L' => 0b00111000,
+        'M' => 0b00010101,
+        'N' => 0b01010100,
+        'O' => 0b00111111,
+        'P' => 0b01110011,
+        'Q' => 0b01100111,
+        'R' => 0b01010000,
+        'S' => 0b01101101,
+        'T' => 0b01111000,
+        'U' => 0b00111110,
+        'V' => 0b00011100,
+        'W' => 0b00101010,
+        'X' => 0b01001001,
+        'Y' => 0b01101110,
+        'Z' => 0b01011011,
+        '-' => 0b01000000,
+        '+' => 0b01000110, // -|
+        '_' => 0b00001000,
+        '=' => 0b01001000,
+        '\'' => 0b00000010,
+        '"' => 0b00100010,
+        '°' => 0b01100011,
+        ' ' => 0,
+        _ => 0,
+    }
+}
+
+/// Converts a number from 0 to 99 into the seven-segment patterns of two
+/// digits.
+///
+/// With `leading_zero` off, the tens digit of a single-digit number is left
+/// blank, e.g. ` 9` instead of `09`.
+///
+/// ## Example
+/// ```rust
+/// let [tens, units] = encode_two_digits(9, false);
+/// ```
+pub fn encode_two_digits(value: u8, leading_zero: bool) -> [u8; 2] {
+    let tens = match value / 10 {
+        0 if !leading_zero => 0,
+        tens => DISPLAY_DIGIT[(tens % 10) as usize],
+    };
+
+    [tens, DISPLAY_DIGIT[(value % 10) as usize]]
+}
+
+/// Converts the first four characters of a string into the seven-segment
+/// patterns of a display, padding shorter strings with blank digits.
+///
+/// ## Example
+/// ```rust
+/// let segments = encode_str("21°C");
+/// ```
+pub fn encode_str(text: &str) -> [u8; 4] {
+    let mut segments = [0u8; 4];
+
+    for (segment, c) in segments.iter_mut().zip(text.chars()) {
+        *segment = encode_char(c);
+    }
+
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_digits_and_letters() {
+        assert_eq!(encode_char('8'), DISPLAY_DIGIT[8]);
+        assert_eq!(encode_char('a'), encode_char('A'));
+        assert_eq!(encode_char('-'), 0b01000000);
+    }
+
+    #[test]
+    fn encodes_unknown_characters_as_blank() {
+        assert_eq!(encode_char('~'), 0);
+        assert_eq!(encode_char(' '), 0);
+    }
+
+    #[test]
+    fn encodes_two_digits_with_and_without_leading_zero() {
+        assert_eq!(
+            encode_two_digits(9, true),
+            [DISPLAY_DIGIT[0], DISPLAY_DIGIT[9]]
+        );
+        assert_eq!(encode_two_digits(9, false), [0, DISPLAY_DIGIT[9]]);
+        assert_eq!(
+            encode_two_digits(42, false),
+            [DISPLAY_DIGIT[4], DISPLAY_DIGIT[2]]
+        );
+    }
+
+    #[test]
+    fn pads_and_truncates_strings_to_four_digits() {
+        assert_eq!(encode_str("HI"), [encode_char('H'), encode_char('I'), 0, 0]);
+        assert_eq!(encode_str("CLOCKS"), encode_str("CLOC"));
+    }
+}
@@ -0,0 +1,188 @@
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+
+/// Represents the different visual themes available for the LED strip.
+///
+/// Themes are serialized by their [Theme::name] and deserialized with
+/// [Theme::from_str].
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase", try_from = "String")]
+pub enum Theme {
+    /// Inspired by the official logo.
+    #[default]
+    Original = 0,
+    /// Inspired by Marty's iconic hoverboard.
+    Hoverboard = 1,
+    /// Referencing the DeLorean's original fuel.
+    Plutonium = 2,
+    /// Evokes the rustic, sepia-toned era of Back to the Future Part III.
+    OldWest = 3,
+    /// A retro, neon-soaked palette reminiscent of the 2015 "Cafe 80s" diner.
+    Cafe80s = 4,
+}
+
+/// Allows converting a u8 integer into a [`Theme`] enum.
+impl From<u8> for Theme {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => Theme::Original,
+            1 => Theme::Hoverboard,
+            2 => Theme::Plutonium,
+            3 => Theme::OldWest,
+            4 => Theme::Cafe80s,
+            _ => Theme::default(),
+        }
+    }
+}
+
+impl Theme {
+    /// Every available theme, in the order they are listed by `/api/themes`.
+    pub const ALL: [Theme; 5] = [
+        Theme::Original,
+        Theme::Hoverboard,
+        Theme::Plutonium,
+        Theme::OldWest,
+        Theme::Cafe80s,
+    ];
+
+    /// Returns the identifier of the theme, as used by the API and NVS exports.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Theme::Original => "original",
+            Theme::Hoverboard => "hoverboard",
+            Theme::Plutonium => "plutonium",
+            Theme::OldWest => "oldwest",
+            Theme::Cafe80s => "cafe80s",
+        }
+    }
+
+    /// Returns the human-readable name of the theme.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Theme::Original => "Original",
+            Theme::Hoverboard => "Hoverboard",
+            Theme::Plutonium => "Plutonium",
+            Theme::OldWest => "Old West",
+            Theme::Cafe80s => "Cafe 80's",
+        }
+    }
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Parses a theme from any spelling of its variant name, ignoring case and
+/// separators, so `"oldwest"`, `"OldWest"` and `"old_west"` are all accepted.
+impl FromStr for Theme {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let normalized: String = value
+            .chars()
+            .filter(char::is_ascii_alphanumeric)
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+
+        Theme::ALL
+            .into_iter()
+            .find(|theme| theme.name() == normalized)
+            .ok_or_else(|| format!("Unknown theme: '{value}'"))
+    }
+}
+
+impl TryFrom<String> for Theme {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        Theme::from_str(&value)
+    }
+}
+
+/// Returns the colors of the bottom, middle and top thirds of the LED strip
+/// for a theme, at full brightness.
+pub fn palette(theme: &Theme) -> [[u8; 3]; 3] {
+    match theme {
+        // Red, yellow and red
+        Theme::Original => [[255, 0, 0], [160, 160, 0], [255, 0, 0]],
+        // Vibrant yellow, shocking pink and lime green
+        Theme::Hoverboard => [[255, 255, 0], [255, 20, 147], [50, 205, 50]],
+        // Caution yellow, radioactive green and lead gray
+        Theme::Plutonium => [[255, 255, 0], [124, 252, 0], [119, 136, 153]],
+        // Bronze, steam white and sepia
+        Theme::OldWest => [[205, 127, 50], [245, 245, 245], [112, 66, 20]],
+        // Turquoise, neon pink and electric purple
+        Theme::Cafe80s => [[64, 224, 208], [255, 105, 180], [128, 0, 128]],
+    }
+}
+
+/// Generates the `[r, g, b]` color of every LED of a strip for a theme, with
+/// the [palette] scaled by the brightness multiplier, from `0.0` to `1.0`.
+///
+/// ## Example
+/// ```rust
+/// let colors = theme_colors(&Theme::Original, 30, 0.25);
+/// ```
+pub fn theme_colors(theme: &Theme, num_leds: u8, brightness: f32) -> Vec<[u8; 3]> {
+    let [bottom, middle, top] =
+        palette(theme).map(|color| color.map(|c| (c as f32 * brightness) as u8));
+
+    let bottom_end = num_leds / 3;
+    let middle_end = 2 * num_leds / 3;
+
+    (0..num_leds)
+        .map(|i| {
+            if i < bottom_end {
+                bottom
+            } else if i < middle_end {
+                middle
+            } else {
+                top
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_from_u8_with_a_default() {
+        assert_eq!(Theme::from(2), Theme::Plutonium);
+        assert_eq!(Theme::from(200), Theme::Original);
+    }
+
+    #[test]
+    fn parses_any_spelling_of_the_name() {
+        assert_eq!("old_west".parse(), Ok(Theme::OldWest));
+        assert_eq!("OldWest".parse(), Ok(Theme::OldWest));
+        assert!("outatime".parse::<Theme>().is_err());
+    }
+
+    #[test]
+    fn round_trips_every_name() {
+        for theme in Theme::ALL {
+            assert_eq!(theme.name().parse(), Ok(theme));
+        }
+    }
+
+    #[test]
+    fn splits_the_strip_in_thirds() {
+        let colors = theme_colors(&Theme::Hoverboard, 9, 1.0);
+        let [bottom, middle, top] = palette(&Theme::Hoverboard);
+
+        assert_eq!(colors.len(), 9);
+        assert_eq!(&colors[..3], &[bottom; 3]);
+        assert_eq!(&colors[3..6], &[middle; 3]);
+        assert_eq!(&colors[6..], &[top; 3]);
+    }
+
+    #[test]
+    fn scales_the_colors_by_the_brightness() {
+        assert_eq!(theme_colors(&Theme::Original, 1, 0.5), [[127, 0, 0]]);
+        assert_eq!(theme_colors(&Theme::Cafe80s, 3, 0.0), [[0, 0, 0]; 3]);
+    }
+}
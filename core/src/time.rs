@@ -0,0 +1,179 @@
+/// Splits an hour and a minute into the 4 digits of the hour display, e.g.
+/// `[2, 1, 0, 7]` for 21:07.
+///
+/// ## Example
+/// ```rust
+/// assert_eq!(hour_min_digits(21, 7), [2, 1, 0, 7]);
+/// ```
+pub fn hour_min_digits(hour: u32, minute: u32) -> [u8; 4] {
+    [
+        (hour / 10) as u8,
+        (hour % 10) as u8,
+        (minute / 10) as u8,
+        (minute % 10) as u8,
+    ]
+}
+
+/// Splits a year into its last 4 digits, e.g. `[1, 9, 8, 5]` for 1985.
+///
+/// ## Example
+/// ```rust
+/// assert_eq!(year_digits(1985), [1, 9, 8, 5]);
+/// ```
+pub fn year_digits(year: i32) -> [u8; 4] {
+    [
+        ((year / 1000) % 10) as u8,
+        ((year / 100) % 10) as u8,
+        ((year / 10) % 10) as u8,
+        (year % 10) as u8,
+    ]
+}
+
+/// Converts an hour from 0 to 23 into the 12-hour clock.
+///
+/// ## Returns
+/// A tuple `(hour, is_pm)`, where midnight is `(12, false)` and noon is
+/// `(12, true)`.
+///
+/// ## Example
+/// ```rust
+/// assert_eq!(to_12_hour(21), (9, true));
+/// ```
+pub fn to_12_hour(hour: u8) -> (u8, bool) {
+    let display_hour = match hour {
+        0 => 12,
+        1..=12 => hour,
+        _ => hour - 12,
+    };
+
+    (display_hour, hour >= 12)
+}
+
+/// Words of the numbers used in a time phrase, from zero to twenty-nine.
+const NUMBER_WORDS: [&str; 30] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+    "twenty",
+    "twenty-one",
+    "twenty-two",
+    "twenty-three",
+    "twenty-four",
+    "twenty-five",
+    "twenty-six",
+    "twenty-seven",
+    "twenty-eight",
+    "twenty-nine",
+];
+
+/// Languages of the time phrases. Only English is available for now.
+pub const PHRASE_LANGUAGES: [&str; 1] = ["en"];
+
+/// Returns the hour as said in a phrase, with the part of the day, e.g.
+/// `nine in the evening`, or `noon` and `midnight`.
+fn hour_words(hour: u32) -> String {
+    let part_of_day = match hour {
+        0 => return "midnight".to_string(),
+        12 => return "noon".to_string(),
+        5..=11 => "in the morning",
+        13..=17 => "in the afternoon",
+        18..=21 => "in the evening",
+        _ => "at night",
+    };
+
+    format!("{} {part_of_day}", NUMBER_WORDS[(hour % 12) as usize])
+}
+
+/// Returns the minutes as said before `past` or `to`, e.g. `quarter`,
+/// `twenty` or `seven minutes`.
+fn minute_words(minutes: u32) -> String {
+    match minutes {
+        15 => "quarter".to_string(),
+        30 => "half".to_string(),
+        1 => "one minute".to_string(),
+        m if m % 5 == 0 => NUMBER_WORDS[m as usize].to_string(),
+        m => format!("{} minutes", NUMBER_WORDS[m as usize]),
+    }
+}
+
+/// Returns a time of the day as spoken English, e.g. `quarter past nine in
+/// the evening` or `twenty to noon`.
+///
+/// Up to half past, the minutes are said past the hour, and after that to the
+/// next hour.
+///
+/// ## Example
+/// ```rust
+/// assert_eq!(time_phrase(21, 15), "quarter past nine in the evening");
+/// assert_eq!(time_phrase(11, 40), "twenty to noon");
+/// ```
+pub fn time_phrase(hour: u32, minute: u32) -> String {
+    match minute {
+        0 => match hour {
+            0 | 12 => hour_words(hour),
+            _ => {
+                let part_of_day = hour_words(hour);
+                part_of_day.replacen(' ', " o'clock ", 1)
+            }
+        },
+        1..=30 => format!("{} past {}", minute_words(minute), hour_words(hour)),
+        _ => format!(
+            "{} to {}",
+            minute_words(60 - minute),
+            hour_words((hour + 1) % 24)
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn splits_hour_min_and_year_digits() {
+        assert_eq!(hour_min_digits(0, 0), [0, 0, 0, 0]);
+        assert_eq!(hour_min_digits(21, 7), [2, 1, 0, 7]);
+        assert_eq!(year_digits(1885), [1, 8, 8, 5]);
+        assert_eq!(year_digits(2015), [2, 0, 1, 5]);
+    }
+
+    #[test]
+    fn converts_to_the_12_hour_clock() {
+        assert_eq!(to_12_hour(0), (12, false));
+        assert_eq!(to_12_hour(9), (9, false));
+        assert_eq!(to_12_hour(12), (12, true));
+        assert_eq!(to_12_hour(23), (11, true));
+    }
+
+    #[test]
+    fn says_the_time_past_and_to_the_hour() {
+        assert_eq!(time_phrase(21, 15), "quarter past nine in the evening");
+        assert_eq!(time_phrase(11, 40), "twenty to noon");
+        assert_eq!(time_phrase(23, 53), "seven minutes to midnight");
+        assert_eq!(time_phrase(14, 1), "one minute past two in the afternoon");
+    }
+
+    #[test]
+    fn says_o_clock_on_the_hour() {
+        assert_eq!(time_phrase(7, 0), "seven o'clock in the morning");
+        assert_eq!(time_phrase(0, 0), "midnight");
+        assert_eq!(time_phrase(12, 0), "noon");
+    }
+}
@@ -0,0 +1,126 @@
+use super::{melody::Note, Melody, AUDIO_QUEUE};
+use crate::{error::AppError, prefs::volume};
+use esp_idf_svc::{
+    hal::gpio::Gpio25,
+    sys::{
+        dac_channel_t_DAC_CHAN_0, dac_cosine_atten_t, dac_cosine_atten_t_DAC_COSINE_ATTEN_DB_0,
+        dac_cosine_atten_t_DAC_COSINE_ATTEN_DB_12, dac_cosine_atten_t_DAC_COSINE_ATTEN_DB_18,
+        dac_cosine_atten_t_DAC_COSINE_ATTEN_DB_6, dac_cosine_config_t, dac_cosine_del_channel,
+        dac_cosine_handle_t, dac_cosine_new_channel, dac_cosine_phase_t_DAC_COSINE_PHASE_0,
+        dac_cosine_start, dac_cosine_stop, esp,
+        soc_periph_dac_cosine_clk_src_t_DAC_COSINE_CLK_SRC_DEFAULT,
+    },
+};
+use std::{sync::mpsc, time::Duration};
+
+/// Max number of melodies waiting to be played. Melodies requested while the
+/// queue is full are dropped.
+const QUEUE_LEN: usize = 4;
+
+/// Silence between two notes, so repeated notes are heard apart.
+const NOTE_GAP: Duration = Duration::from_millis(20);
+
+/// Represents the DAC channel 1 of the ESP32, on `GPIO25`, feeding a small
+/// amplifier, e.g. a PAM8403, and a speaker.
+///
+/// The notes are played with the cosine generator of the DAC, so the CPU is
+/// free while a note is held.
+pub struct Dac {
+    _pin: Gpio25,
+}
+
+impl Dac {
+    /// Creates a new [Dac].
+    ///
+    /// ## Arguments
+    /// - `pin` - `GPIO25`, the only pin of DAC channel 1.
+    ///
+    /// ## Example
+    /// ```rust
+    /// let dac = Dac::new(peripherals.pins.gpio25);
+    /// ```
+    pub fn new(pin: Gpio25) -> Self {
+        Self { _pin: pin }
+    }
+
+    /// Plays a note at the given volume, holding the thread meanwhile.
+    fn play_note(&self, note: &Note, volume: u8) -> Result<(), AppError> {
+        let duration = Duration::from_millis(note.duration_ms);
+
+        if note.frequency == 0 {
+            std::thread::sleep(duration);
+            return Ok(());
+        }
+
+        let config = dac_cosine_config_t {
+            chan_id: dac_channel_t_DAC_CHAN_0,
+            freq_hz: note.frequency,
+            clk_src: soc_periph_dac_cosine_clk_src_t_DAC_COSINE_CLK_SRC_DEFAULT,
+            atten: attenuation(volume),
+            phase: dac_cosine_phase_t_DAC_COSINE_PHASE_0,
+            offset: 0,
+            ..Default::default()
+        };
+
+        let mut handle: dac_cosine_handle_t = std::ptr::null_mut();
+        esp!(unsafe { dac_cosine_new_channel(&config, &mut handle) })?;
+
+        let started = esp!(unsafe { dac_cosine_start(handle) });
+        if started.is_ok() {
+            std::thread::sleep(duration);
+            unsafe { dac_cosine_stop(handle) };
+        }
+
+        // The channel is freed even if it didn't start, for the next note
+        unsafe { dac_cosine_del_channel(handle) };
+        std::thread::sleep(NOTE_GAP);
+
+        started?;
+
+        Ok(())
+    }
+}
+
+/// Maps a volume, from `1` to [MAX_VOLUME](volume::MAX_VOLUME), to the
+/// attenuation of the cosine generator.
+fn attenuation(volume: u8) -> dac_cosine_atten_t {
+    match volume {
+        1 => dac_cosine_atten_t_DAC_COSINE_ATTEN_DB_18,
+        2 => dac_cosine_atten_t_DAC_COSINE_ATTEN_DB_12,
+        3 => dac_cosine_atten_t_DAC_COSINE_ATTEN_DB_6,
+        _ => dac_cosine_atten_t_DAC_COSINE_ATTEN_DB_0,
+    }
+}
+
+/// Plays the [Melody]s queued with [play](super::play), one after the other.
+/// The volume is read again for every melody, and a melody stops when it is
+/// muted meanwhile. Runs forever, so it is meant to be spawned on its own
+/// thread.
+///
+/// ## Arguments
+/// - `dac` - The [Dac] the amplifier is wired to.
+///
+/// ## Example
+/// ```rust
+/// std::thread::spawn(move || audio::dac::run_audio_task(dac));
+/// ```
+pub fn run_audio_task(dac: Dac) {
+    let (sender, receiver) = mpsc::sync_channel::<Melody>(QUEUE_LEN);
+    *AUDIO_QUEUE.lock().unwrap() = Some(sender);
+
+    for melody in receiver {
+        log::info!("Playing {melody:?}");
+
+        for note in melody.notes() {
+            let volume = volume::get_volume();
+            if volume == 0 {
+                break;
+            }
+
+            if let Err(e) = dac.play_note(note, volume) {
+                log::error!("Failed to play {melody:?}: {e:?}");
+                break;
+            }
+        }
+    }
+}
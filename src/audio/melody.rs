@@ -0,0 +1,61 @@
+use super::Melody;
+
+/// Represents a note of a [Melody].
+#[derive(Clone, Copy, Debug)]
+pub struct Note {
+    /// The pitch, in Hz, or `0` for a rest.
+    pub frequency: u32,
+    /// How long the note is held, in milliseconds.
+    pub duration_ms: u64,
+}
+
+/// Shorthand for the [Note]s of the melodies below.
+const fn note(frequency: u32, duration_ms: u64) -> Note {
+    Note {
+        frequency,
+        duration_ms,
+    }
+}
+
+/// Three short beeps, played three times.
+const ALARM: &[Note] = &[
+    note(1760, 120),
+    note(0, 80),
+    note(1760, 120),
+    note(0, 80),
+    note(1760, 120),
+    note(0, 600),
+    note(1760, 120),
+    note(0, 80),
+    note(1760, 120),
+    note(0, 80),
+    note(1760, 120),
+    note(0, 600),
+    note(1760, 120),
+    note(0, 80),
+    note(1760, 120),
+    note(0, 80),
+    note(1760, 120),
+];
+
+/// The opening of the Back to the Future theme.
+const THEME: &[Note] = &[
+    note(392, 250),
+    note(523, 250),
+    note(784, 900),
+    note(740, 200),
+    note(659, 200),
+    note(740, 200),
+    note(784, 700),
+    note(587, 900),
+];
+
+impl Melody {
+    /// The notes of the melody, in order.
+    pub fn notes(&self) -> &'static [Note] {
+        match self {
+            Self::Alarm => ALARM,
+            Self::Theme => THEME,
+        }
+    }
+}
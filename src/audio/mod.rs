@@ -0,0 +1,95 @@
+use crate::{event::Event, prefs::volume};
+use serde::{Deserialize, Serialize};
+use std::sync::{
+    mpsc::{SyncSender, TrySendError},
+    Mutex,
+};
+
+#[cfg(feature = "audio")]
+pub mod dac;
+#[cfg(feature = "audio")]
+mod melody;
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable to hold the sender of the audio task, once it runs.
+    static ref AUDIO_QUEUE: Mutex<Option<SyncSender<Melody>>> = Mutex::new(None);
+}
+
+/// Represents the melodies stored in the firmware.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Melody {
+    /// Played when the alarm fires.
+    Alarm,
+    /// A sting of the Back to the Future theme, played with the time travel
+    /// animation.
+    Theme,
+}
+
+/// Represents the body of a request playing a [Melody], `alarm` or `theme`.
+///
+/// ## Example
+/// ```rust
+/// let audio_request: AudioRequest = serde_json::from_str("{\"melody\":\"theme\"}").unwrap();
+/// ```
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct AudioRequest {
+    pub melody: Melody,
+}
+
+/// Represents the state of the audio output returned by the `/api/audio`
+/// endpoint.
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct AudioStatus {
+    /// Whether the audio task runs, i.e. the firmware was built with the
+    /// `audio` feature.
+    pub enabled: bool,
+    /// The volume, from `0` (muted) to [MAX_VOLUME](volume::MAX_VOLUME).
+    pub volume: u8,
+}
+
+/// Retrieves the state of the audio output.
+pub fn get_status() -> AudioStatus {
+    AudioStatus {
+        enabled: AUDIO_QUEUE.lock().unwrap().is_some(),
+        volume: volume::get_volume(),
+    }
+}
+
+/// Queues a melody for the audio task without waiting for it to be played.
+///
+/// The melody is dropped when the volume is `0`, and, with a warning, when
+/// the queue is full or the audio task is not running.
+///
+/// ## Example
+/// ```rust
+/// audio::play(Melody::Theme);
+/// ```
+pub fn play(melody: Melody) {
+    if volume::get_volume() == 0 {
+        return;
+    }
+
+    let queue = AUDIO_QUEUE.lock().unwrap();
+
+    let Some(sender) = queue.as_ref() else {
+        log::warn!("Audio task not running, dropping {melody:?}");
+        return;
+    };
+
+    match sender.try_send(melody) {
+        Ok(()) => {}
+        Err(TrySendError::Full(melody)) => log::warn!("Audio queue full, dropping {melody:?}"),
+        Err(TrySendError::Disconnected(melody)) => {
+            log::warn!("Audio task stopped, dropping {melody:?}")
+        }
+    }
+}
+
+/// Plays the alarm melody when the alarm fires, see
+/// [subscribe](crate::event::subscribe).
+pub fn on_event(event: &Event) {
+    if *event == Event::AlarmFired && get_status().enabled {
+        play(Melody::Alarm);
+    }
+}
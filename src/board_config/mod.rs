@@ -0,0 +1,200 @@
+use crate::{error::AppError, nvs::AppStorage, service::app_storage::AppStoragePrefsService};
+use esp_idf_svc::{
+    hal::gpio::{AnyIOPin, AnyInputPin, AnyOutputPin},
+    nvs::{EspNvsPartition, NvsDefault},
+};
+use serde::{Deserialize, Serialize};
+use std::sync::PoisonError;
+
+/// GPIOs of the ESP32 that can't be used: 20, 24 and 28 to 31 don't exist,
+/// and 6 to 11 are wired to the SPI flash.
+const RESERVED_PINS: &[u8] = &[6, 7, 8, 9, 10, 11, 20, 24, 28, 29, 30, 31];
+
+/// GPIOs that stay fixed: the UART console on 1 and 3, the SD card on 4, 18,
+/// 23 and 25, which is also the audio output, the touch pad on 15, the battery
+/// on 34 and the PIR sensor on 36.
+const FIXED_PINS: &[u8] = &[1, 3, 4, 15, 18, 23, 25, 34, 36];
+
+/// Highest GPIO of the ESP32.
+const MAX_PIN: u8 = 39;
+
+/// First GPIO of the ESP32 that is input only.
+const FIRST_INPUT_ONLY_PIN: u8 = 34;
+
+/// Represents the GPIOs the displays, the LED strip, the AM/PM LEDs and the
+/// optional sensors and buttons are wired to.
+///
+/// The defaults match the reference board. Other boards save their own pins
+/// in NVS from `POST /api/board`, and they are used after a restart, since
+/// the pins are only taken at boot. The UART, the SD card, the touch pad, the
+/// battery and the PIR sensor pins stay fixed.
+///
+/// ## Example
+/// ```rust
+/// let config: BoardConfig =
+///     serde_json::from_str("{\"led_strip\":2,\"hour_display_dio\":5}").unwrap();
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BoardConfig {
+    /// The clock line shared by the TM1637 displays, also the SPI clock of
+    /// the MAX7219 modules.
+    pub display_clk: u8,
+    /// The data line of the date display, also its chip select with MAX7219
+    /// modules.
+    pub date_display_dio: u8,
+    /// The data line of the year display, also its chip select with MAX7219
+    /// modules.
+    pub year_display_dio: u8,
+    /// The data line of the hour display, also its chip select with MAX7219
+    /// modules.
+    pub hour_display_dio: u8,
+    /// The SPI data line of the MAX7219 modules.
+    pub max7219_din: u8,
+    /// The data line of the WS2812 LED strip.
+    pub led_strip: u8,
+    pub am_led: u8,
+    pub pm_led: u8,
+    /// The I2C data line of the HT16K33 backpacks and the BME280.
+    pub i2c_sda: u8,
+    /// The I2C clock line of the HT16K33 backpacks and the BME280.
+    pub i2c_scl: u8,
+    /// The data line of the DHT22.
+    pub dht22: u8,
+    pub stopwatch_button: u8,
+    /// The button held to start WPS, the BOOT button by default.
+    pub wps_button: u8,
+    /// The power good signal of the power supply, which may be input only.
+    pub power_good: u8,
+}
+
+impl Default for BoardConfig {
+    fn default() -> Self {
+        Self {
+            display_clk: 16,
+            date_display_dio: 17,
+            year_display_dio: 19,
+            hour_display_dio: 26,
+            max7219_din: 13,
+            led_strip: 5,
+            am_led: 32,
+            pm_led: 33,
+            i2c_sda: 21,
+            i2c_scl: 22,
+            dht22: 27,
+            stopwatch_button: 14,
+            wps_button: 0,
+            power_good: 35,
+        }
+    }
+}
+
+impl BoardConfig {
+    /// Checks that every pin is a usable GPIO that isn't one of the
+    /// [FIXED_PINS], that only the power good signal is on an input only GPIO,
+    /// and that no pin is used twice.
+    pub fn is_valid(&self) -> bool {
+        let output_pins = self.output_pins();
+
+        let mut pins = output_pins.to_vec();
+        pins.push(self.power_good);
+        pins.sort_unstable();
+
+        output_pins.iter().all(|pin| *pin < FIRST_INPUT_ONLY_PIN)
+            && pins.iter().all(|pin| is_usable(*pin))
+            && pins.windows(2).all(|pair| pair[0] != pair[1])
+    }
+
+    /// Checks that a pin can drive an output, e.g. a relay, without clashing
+    /// with the pins of the board.
+    pub fn is_free_output_pin(&self, pin: u8) -> bool {
+        is_usable(pin)
+            && pin < FIRST_INPUT_ONLY_PIN
+            && pin != self.power_good
+            && !self.output_pins().contains(&pin)
+    }
+
+    /// Returns the pins of the board that may drive an output.
+    fn output_pins(&self) -> [u8; 13] {
+        [
+            self.display_clk,
+            self.date_display_dio,
+            self.year_display_dio,
+            self.hour_display_dio,
+            self.max7219_din,
+            self.led_strip,
+            self.am_led,
+            self.pm_led,
+            self.i2c_sda,
+            self.i2c_scl,
+            self.dht22,
+            self.stopwatch_button,
+            self.wps_button,
+        ]
+    }
+
+    /// Reads the board configuration from NVS, before the rest of the storage
+    /// is set up, falling back to the defaults when none is saved or it is
+    /// invalid.
+    ///
+    /// ## Example
+    /// ```rust
+    /// let board = BoardConfig::load(nvs_default_partition.clone());
+    /// ```
+    pub fn load(nvs_default_partition: EspNvsPartition<NvsDefault>) -> Self {
+        let config = AppStorage::new(nvs_default_partition).and_then(|storage| {
+            storage
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .get_maybe_board()
+                .map_err(AppError::Config)
+        });
+
+        match config {
+            Ok(Some(config)) if config.is_valid() => {
+                log::info!("Using the board config saved in NVS: {config:?}");
+                config
+            }
+            Ok(Some(config)) => {
+                log::warn!("Invalid board config in NVS, using the defaults: {config:?}");
+                Self::default()
+            }
+            Ok(None) => Self::default(),
+            Err(e) => {
+                log::error!("Failed to read the board config, using the defaults: {e:?}");
+                Self::default()
+            }
+        }
+    }
+}
+
+/// Checks that a pin is a GPIO of the ESP32 that isn't one of the
+/// [FIXED_PINS].
+fn is_usable(pin: u8) -> bool {
+    pin <= MAX_PIN && !RESERVED_PINS.contains(&pin) && !FIXED_PINS.contains(&pin)
+}
+
+/// Takes a GPIO that can be read and driven, e.g. the data line of a display.
+///
+/// ## Safety
+/// The pin must not be taken anywhere else, which [BoardConfig::is_valid]
+/// ensures for the pins of the board configuration.
+pub unsafe fn io_pin(pin: u8) -> AnyIOPin {
+    AnyIOPin::new(pin as i32)
+}
+
+/// Takes a GPIO that is driven, e.g. an LED.
+///
+/// ## Safety
+/// See [io_pin].
+pub unsafe fn output_pin(pin: u8) -> AnyOutputPin {
+    AnyOutputPin::new(pin as i32)
+}
+
+/// Takes a GPIO that is read, e.g. a button.
+///
+/// ## Safety
+/// See [io_pin].
+pub unsafe fn input_pin(pin: u8) -> AnyInputPin {
+    AnyInputPin::new(pin as i32)
+}
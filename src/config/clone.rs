@@ -0,0 +1,101 @@
+use super::DeviceConfig;
+use crate::{error::AppError, nvs::SharedAppStorage, server::client};
+use serde::Deserialize;
+
+/// Max size of a config export, fetched from another clock or imported through
+/// `/api/config`.
+pub const MAX_CONFIG_LEN: usize = 1024;
+
+/// The token required as a bearer token to read the settings of this clock
+/// from `/api/config` and by every route that changes its state, e.g. from
+/// the other clocks or the web portal. Empty leaves them open and disables the
+/// firmware upload.
+const ADMIN_TOKEN: &str = env!("ADMIN_TOKEN");
+
+/// Represents a request to clone the settings of another clock.
+///
+/// ## Example
+/// ```rust
+/// let clone_request: CloneRequest =
+///     serde_json::from_str("{\"host\":\"bttf-2.local\",\"token\":\"secret\"}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct CloneRequest {
+    pub host: String,
+    #[serde(default)]
+    pub token: Option<String>,
+}
+
+/// Checks the `Authorization` header of a request against [ADMIN_TOKEN], e.g.
+/// `Bearer secret`.
+pub fn is_authorized(authorization: Option<&str>) -> bool {
+    ADMIN_TOKEN.is_empty()
+        || authorization.and_then(|value| value.strip_prefix("Bearer ")) == Some(ADMIN_TOKEN)
+}
+
+/// Whether the firmware was built with an [ADMIN_TOKEN], without which the
+/// firmware upload is refused.
+pub fn has_admin_token() -> bool {
+    !ADMIN_TOKEN.is_empty()
+}
+
+/// Checks that a host can be used in a URL, e.g. `bttf-2.local` or
+/// `192.168.0.42:8080`.
+pub fn is_valid_host(host: &str) -> bool {
+    !host.is_empty()
+        && host.len() <= 64
+        && host
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | ':'))
+}
+
+/// Pulls the settings from another clock on the LAN and applies them locally.
+///
+/// The settings are read from its `/api/config` endpoint, which never includes
+/// the Wi-Fi credentials and requires the [ADMIN_TOKEN] of the other clock
+/// when it has one. Its network identity, e.g. its static IP, is dropped too,
+/// see [for_clone](DeviceConfig::for_clone), so this clock stays on its own
+/// network and address.
+///
+/// ## Arguments
+/// - `host`: The host name or IP address of the other clock.
+/// - `token`: The admin token of the other clock, sent as a bearer token.
+/// - `storage`: A [SharedAppStorage] instance used to save the settings.
+///
+/// ## Returns
+/// - `Ok(())`: If the settings were fetched and saved.
+/// - `Err(AppError)`: If the other clock can't be reached or its settings are
+///   invalid.
+///
+/// ## Example
+/// ```rust
+/// clone_from("bttf-2.local", None, &app_storage)?;
+/// ```
+pub fn clone_from(
+    host: &str,
+    token: Option<&str>,
+    storage: &SharedAppStorage,
+) -> Result<(), AppError> {
+    let url = format!("http://{host}/api/config");
+    let authorization = token.map(|token| format!("Bearer {token}"));
+
+    let mut headers = Vec::new();
+    if let Some(authorization) = &authorization {
+        headers.push(("Authorization", authorization.as_str()));
+    }
+
+    let (status, body) = client::get(&url, &headers, MAX_CONFIG_LEN)?;
+    if status != 200 {
+        return Err(AppError::Server(format!(
+            "{url} answered with status {status}"
+        )));
+    }
+
+    let config: DeviceConfig = serde_json::from_slice(&body)
+        .map_err(|e| AppError::Config(format!("Invalid config from {host}: {e}")))?;
+
+    config.for_clone().apply(storage)?;
+    log::info!("Settings cloned from {host}");
+
+    Ok(())
+}
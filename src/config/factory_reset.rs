@@ -0,0 +1,108 @@
+use crate::{
+    error::AppError,
+    module::display_queue::{self, DisplayCommand},
+    nvs::SharedAppStorage,
+    service::app_storage::{
+        AppStoragePrefsService, AppStorageSettingsService, AppStorageTzService,
+        AppStorageWifiService,
+    },
+    util::encode_str,
+};
+use esp_idf_svc::sys::{esp_random, esp_restart, esp_wifi_disconnect};
+use serde::Deserialize;
+use std::{
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+/// How long a confirmation token can be used after it was issued.
+pub const TOKEN_LIFETIME: Duration = Duration::from_secs(60);
+
+/// Number of seconds counted down on the hour display before the settings are
+/// deleted.
+pub const COUNTDOWN_SECONDS: u8 = 5;
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable to hold the last confirmation token issued, with the time it was issued.
+    static ref PENDING_TOKEN: Mutex<Option<(String, Instant)>> = Mutex::new(None);
+}
+
+/// Represents a request to restore the factory settings, confirmed with a
+/// token from [issue_token].
+///
+/// ## Example
+/// ```rust
+/// let factory_reset_request: FactoryResetRequest =
+///     serde_json::from_str("{\"token\":\"3f2a9c41d07be865\"}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct FactoryResetRequest {
+    pub token: String,
+}
+
+/// Issues a new confirmation token for the factory reset, replacing the
+/// previous one.
+///
+/// ## Example
+/// ```rust
+/// let token = config::factory_reset::issue_token();
+/// ```
+pub fn issue_token() -> String {
+    let token = unsafe { format!("{:08x}{:08x}", esp_random(), esp_random()) };
+    *PENDING_TOKEN.lock().unwrap() = Some((token.clone(), Instant::now()));
+
+    token
+}
+
+/// Checks a token against the last one issued, within [TOKEN_LIFETIME].
+///
+/// The pending token is cleared by any attempt, right or wrong, so each token
+/// can only be tried once.
+pub fn take_token(token: &str) -> bool {
+    let pending = PENDING_TOKEN.lock().unwrap().take();
+
+    matches!(pending, Some((pending, issued)) if pending == token && issued.elapsed() < TOKEN_LIFETIME)
+}
+
+/// Counts down from [COUNTDOWN_SECONDS] on the hour display, e.g. `RST3`, then
+/// deletes the Wi-Fi credentials, the WPA2-Enterprise credentials, the static
+/// IP, the country code, the timezone, the settings, the board pins, the
+/// snapshot URL and the webhook URL, and restarts the device.
+///
+/// ## Returns
+/// - `Err(AppError)`: If a setting can't be deleted. Otherwise, it does not
+///   return, as the device restarts.
+///
+/// ## Example
+/// ```rust
+/// config::factory_reset::factory_reset(&app_storage)?;
+/// ```
+pub fn factory_reset(storage: &SharedAppStorage) -> Result<(), AppError> {
+    log::warn!("Factory reset in {COUNTDOWN_SECONDS} seconds...");
+
+    for remaining in (1..=COUNTDOWN_SECONDS).rev() {
+        display_queue::send(DisplayCommand::ShowStatus(encode_str(&format!(
+            "RST{remaining}"
+        ))));
+        std::thread::sleep(Duration::from_secs(1));
+    }
+
+    let mut storage = storage.lock().unwrap_or_else(PoisonError::into_inner);
+    storage.delete_wifi_credentials()?;
+    storage.delete_enterprise_config()?;
+    storage.delete_static_ip()?;
+    storage.delete_country_code()?;
+    storage.delete_timezone()?;
+    storage.delete_settings()?;
+    storage.delete_board()?;
+    storage.delete_snapshot_url()?;
+    storage.delete_webhook_url()?;
+
+    log::info!("Factory reset done!");
+    log::info!("Restarting...");
+
+    unsafe {
+        esp_wifi_disconnect();
+        esp_restart();
+    }
+}
@@ -0,0 +1,220 @@
+use crate::{
+    error::AppError,
+    nvs::SharedAppStorage,
+    prefs,
+    service::app_storage::{
+        AppStoragePrefsService, AppStorageSettingsService, AppStorageTzService,
+        AppStorageWifiService,
+    },
+    state,
+    theme::Theme,
+    time::{self, tz::TimezoneRequest},
+    wifi::{self, AuthMode, StaticIpConfig, WifiCredentials},
+};
+use serde::{Deserialize, Serialize};
+use std::sync::PoisonError;
+
+pub mod clone;
+pub mod factory_reset;
+#[cfg(feature = "sd-card")]
+pub mod sd_card;
+pub mod serial;
+pub mod snapshot;
+
+/// Represents the full set of user settings stored in NVS.
+///
+/// Every field is optional, so a partial object only updates the settings it
+/// contains when applied.
+///
+/// ## Example
+/// ```rust
+/// let config: DeviceConfig =
+///     serde_json::from_str("{\"timezone\":\"Europe/Berlin\",\"theme\":\"plutonium\"}").unwrap();
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeviceConfig {
+    #[serde(default)]
+    pub wifi: Option<WifiCredentials>,
+    /// Whether the Wi-Fi network doesn't broadcast its SSID.
+    #[serde(default)]
+    pub hidden_ssid: Option<bool>,
+    /// The security of the Wi-Fi network, e.g. `open` or `wpa3`.
+    #[serde(default)]
+    pub auth_mode: Option<AuthMode>,
+    #[serde(default)]
+    pub static_ip: Option<StaticIpConfig>,
+    #[serde(default)]
+    pub timezone: Option<String>,
+    /// `0` for 12h, `1` for 24h.
+    #[serde(default)]
+    pub hour_format: Option<u8>,
+    /// `0` for `DD.MM`, `1` for `MM.DD`.
+    #[serde(default)]
+    pub date_format: Option<u8>,
+    /// Whether single-digit hours and dates have a leading zero.
+    #[serde(default)]
+    pub leading_zero: Option<bool>,
+    #[serde(default)]
+    pub theme: Option<Theme>,
+}
+
+impl DeviceConfig {
+    /// Reads the current settings from NVS.
+    ///
+    /// ## Returns
+    /// - `Ok(DeviceConfig)`: The stored settings. Settings that were never
+    ///   saved are `None`.
+    /// - `Err(AppError)`: If any of the settings can't be read.
+    pub fn export(storage: &SharedAppStorage) -> Result<Self, AppError> {
+        let mut storage = storage.lock().unwrap_or_else(PoisonError::into_inner);
+        let settings = storage.get_settings().map_err(AppError::Config)?;
+
+        Ok(Self {
+            wifi: storage
+                .get_maybe_wifi_credentials()
+                .map_err(AppError::Config)?,
+            hidden_ssid: storage.get_maybe_hidden_ssid().map_err(AppError::Config)?,
+            auth_mode: storage.get_maybe_auth_mode().map_err(AppError::Config)?,
+            static_ip: storage.get_maybe_static_ip().map_err(AppError::Config)?,
+            timezone: settings.timezone().map(str::to_string),
+            hour_format: settings.hour_format().map(|hour_format| hour_format as u8),
+            date_format: storage
+                .get_maybe_date_format()
+                .map_err(AppError::Config)?
+                .map(|date_format| date_format as u8),
+            leading_zero: storage.get_maybe_leading_zero().map_err(AppError::Config)?,
+            theme: settings.theme(),
+        })
+    }
+
+    /// Removes the settings that must not leave the device, such as the Wi-Fi
+    /// credentials.
+    pub fn without_secrets(self) -> Self {
+        Self { wifi: None, ..self }
+    }
+
+    /// Removes the settings that only fit the device they were read from, for
+    /// a clone: the Wi-Fi credentials and the network identity, i.e. the
+    /// hidden SSID flag, the auth mode and the static IP, so the clone keeps
+    /// its own network settings instead of taking the address of the other
+    /// clock.
+    pub fn for_clone(self) -> Self {
+        Self {
+            hidden_ssid: None,
+            auth_mode: None,
+            static_ip: None,
+            ..self.without_secrets()
+        }
+    }
+
+    /// Validates the settings that are present and persists them in NVS.
+    ///
+    /// Timezone, hour format, date format and leading zero take effect
+    /// immediately. Wi-Fi credentials, hidden SSID flag, auth mode, static IP
+    /// and theme are applied on the next boot.
+    ///
+    /// The credentials are also published to the runtime
+    /// [Settings](state::Settings), so a device waiting in the captive portal
+    /// finishes provisioning right away.
+    ///
+    /// ## Returns
+    /// - `Ok(())`: If every present setting was valid and saved.
+    /// - `Err(AppError)`: If a setting is invalid, in which case nothing is
+    ///   saved, or if NVS fails to save one. The settings saved before that one
+    ///   are kept, so they may be applied only in part.
+    pub fn apply(self, storage: &SharedAppStorage) -> Result<(), AppError> {
+        self.validate()?;
+
+        let mut storage = storage.lock().unwrap_or_else(PoisonError::into_inner);
+
+        if let Some(hidden) = self.hidden_ssid {
+            storage.save_hidden_ssid(hidden)?;
+            // Saved again with the credentials when they finish provisioning
+            *wifi::HIDDEN_SSID.lock().unwrap() = hidden;
+        }
+
+        if let Some(auth_mode) = self.auth_mode {
+            storage.save_auth_mode(auth_mode)?;
+            *wifi::AUTH_MODE.lock().unwrap() = auth_mode;
+        }
+
+        if let Some(static_ip) = self.static_ip {
+            storage.save_static_ip(static_ip)?;
+        }
+
+        if let Some(timezone) = self.timezone {
+            storage.save_timezone(TimezoneRequest {
+                timezone: timezone.clone(),
+            })?;
+            time::tz::set_timezone(timezone);
+        }
+
+        if let Some(hour_format) = self.hour_format {
+            storage.save_hour_format(hour_format.into())?;
+            prefs::hour_format::set_hour_format(hour_format.into());
+        }
+
+        if let Some(date_format) = self.date_format {
+            storage.save_date_format(date_format.into())?;
+            prefs::date_format::set_date_format(date_format.into());
+        }
+
+        if let Some(leading_zero) = self.leading_zero {
+            storage.save_leading_zero(leading_zero)?;
+            prefs::leading_zero::set_leading_zero(leading_zero);
+        }
+
+        if let Some(theme) = self.theme {
+            storage.save_theme(theme)?;
+        }
+
+        if let Some(credentials) = self.wifi {
+            storage.save_wifi_credentials(credentials.ssid.clone(), credentials.password.clone());
+            state::update_settings(|settings| settings.wifi_credentials = Some(credentials));
+        }
+
+        Ok(())
+    }
+
+    /// Checks every present setting before anything is written to NVS.
+    fn validate(&self) -> Result<(), AppError> {
+        if let Some(credentials) = &self.wifi {
+            if credentials.ssid.is_empty() || credentials.ssid.len() > 32 {
+                return Err(AppError::Config("Invalid Wi-Fi SSID".to_string()));
+            }
+            if credentials.password.len() > 64 {
+                return Err(AppError::Config("Invalid Wi-Fi password".to_string()));
+            }
+        }
+
+        if let Some(static_ip) = &self.static_ip {
+            if !static_ip.is_valid() {
+                return Err(AppError::Config("Invalid static IP".to_string()));
+            }
+        }
+
+        if let Some(timezone) = &self.timezone {
+            if !time::tz::is_valid_timezone(timezone) {
+                return Err(AppError::Config(format!("Invalid timezone: {timezone}")));
+            }
+        }
+
+        if let Some(hour_format) = self.hour_format {
+            if !(0..=1).contains(&hour_format) {
+                return Err(AppError::Config(format!(
+                    "Invalid hour_format: {hour_format}"
+                )));
+            }
+        }
+
+        if let Some(date_format) = self.date_format {
+            if !(0..=1).contains(&date_format) {
+                return Err(AppError::Config(format!(
+                    "Invalid date_format: {date_format}"
+                )));
+            }
+        }
+
+        Ok(())
+    }
+}
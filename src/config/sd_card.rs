@@ -0,0 +1,186 @@
+use super::DeviceConfig;
+use crate::{error::AppError, nvs::SharedAppStorage};
+use esp_idf_svc::{
+    fs::fatfs::Fatfs,
+    hal::{
+        gpio::{AnyIOPin, InputPin, OutputPin},
+        peripheral::Peripheral,
+        sd::{spi::SdSpiHostDriver, SdCardConfiguration, SdCardDriver},
+        spi::{config::DriverConfig, Dma, SpiAnyPins, SpiDriver},
+    },
+    io::vfs::MountedFatfs,
+};
+use std::{
+    fs,
+    io::ErrorKind,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+/// Where the SD card is mounted in the VFS.
+pub const MOUNT_POINT: &str = "/sdcard";
+
+/// The provisioning file read at boot.
+const CONFIG_FILE: &str = "/sdcard/config.json";
+
+/// The name the provisioning file is renamed to after a successful import, so
+/// it isn't applied again on every boot.
+const IMPORTED_CONFIG_FILE: &str = "/sdcard/config.imported.json";
+
+/// The file the settings are exported to.
+const EXPORTED_CONFIG_FILE: &str = "/sdcard/config.export.json";
+
+/// Whether the SD card is mounted.
+static MOUNTED: AtomicBool = AtomicBool::new(false);
+
+/// Mounts an SPI SD card at [MOUNT_POINT], for the provisioning file, the
+/// exported settings and the log files.
+///
+/// The card stays mounted until the clock restarts. A missing card is not an
+/// error.
+///
+/// ## Arguments
+/// - `spi`: The SPI peripheral connected to the SD card.
+/// - `sclk`, `mosi`, `miso`, `cs`: The SPI pins of the SD card.
+///
+/// ## Returns
+/// - `Ok(true)`: If the card was mounted.
+/// - `Ok(false)`: If there is no SD card.
+/// - `Err(AppError)`: If the card can't be mounted.
+///
+/// ## Example
+/// ```rust
+/// config::sd_card::mount(
+///     peripherals.spi3,
+///     peripherals.pins.gpio18,
+///     peripherals.pins.gpio23,
+///     peripherals.pins.gpio25,
+///     peripherals.pins.gpio4,
+/// )?;
+/// ```
+pub fn mount<SPI: SpiAnyPins>(
+    spi: impl Peripheral<P = SPI> + 'static,
+    sclk: impl Peripheral<P = impl OutputPin> + 'static,
+    mosi: impl Peripheral<P = impl OutputPin> + 'static,
+    miso: impl Peripheral<P = impl InputPin> + 'static,
+    cs: impl Peripheral<P = impl OutputPin> + 'static,
+) -> Result<bool, AppError> {
+    let spi_driver = SpiDriver::new(
+        spi,
+        sclk,
+        mosi,
+        Some(miso),
+        &DriverConfig::default().dma(Dma::Auto(4096)),
+    )?;
+
+    let sd_card_driver = match SdSpiHostDriver::new(
+        spi_driver,
+        Some(cs),
+        AnyIOPin::none(),
+        AnyIOPin::none(),
+        AnyIOPin::none(),
+        None,
+    )
+    .and_then(|host| SdCardDriver::new_spi(host, &SdCardConfiguration::new()))
+    {
+        Ok(driver) => driver,
+        Err(e) => {
+            log::info!("No SD card found: {e:?}");
+            return Ok(false);
+        }
+    };
+
+    let mounted_fatfs = MountedFatfs::mount(Fatfs::new_sdcard(0, sd_card_driver)?, MOUNT_POINT, 4)
+        .inspect_err(|e| {
+            log::error!("Failed to mount SD card: {e:#?}");
+        })?;
+
+    // The card would be unmounted when `mounted_fatfs` is dropped, while the
+    // log files are written until the clock restarts
+    std::mem::forget(mounted_fatfs);
+    MOUNTED.store(true, Ordering::Relaxed);
+    log::info!("SD card mounted at {MOUNT_POINT}");
+
+    Ok(true)
+}
+
+/// Whether an SD card is mounted, see [mount].
+pub fn is_mounted() -> bool {
+    MOUNTED.load(Ordering::Relaxed)
+}
+
+/// Imports a `config.json` from the SD card into NVS, if present.
+///
+/// The file contains a [DeviceConfig] object (Wi-Fi credentials, timezone,
+/// prefs). After it is applied, the file is renamed to
+/// `config.imported.json`. A missing card or file is not an error.
+///
+/// ## Arguments
+/// - `storage`: A [SharedAppStorage] instance used to save the settings.
+///
+/// ## Returns
+/// - `Ok(true)`: If a config file was found and imported.
+/// - `Ok(false)`: If there is no SD card or no config file.
+/// - `Err(AppError)`: If the config file is invalid or can't be applied.
+///
+/// ## Example
+/// ```rust
+/// config::sd_card::import_config(&app_storage)?;
+/// ```
+pub fn import_config(storage: &SharedAppStorage) -> Result<bool, AppError> {
+    if !is_mounted() {
+        return Ok(false);
+    }
+
+    let config_data = match fs::read(CONFIG_FILE) {
+        Ok(data) => data,
+        Err(e) if e.kind() == ErrorKind::NotFound => {
+            log::info!("No {CONFIG_FILE} found on SD card");
+            return Ok(false);
+        }
+        Err(e) => return Err(AppError::StdIO(e)),
+    };
+
+    let config: DeviceConfig = serde_json::from_slice(&config_data)
+        .map_err(|e| AppError::Config(format!("Invalid {CONFIG_FILE}: {e}")))?;
+
+    config.apply(storage)?;
+
+    // A previous import may have left the renamed file behind
+    fs::remove_file(IMPORTED_CONFIG_FILE).ok();
+    fs::rename(CONFIG_FILE, IMPORTED_CONFIG_FILE)?;
+    log::info!("Imported {CONFIG_FILE} from SD card");
+
+    Ok(true)
+}
+
+/// Exports the stored settings, without secrets, to `config.export.json` on
+/// the SD card, replacing the previous export.
+///
+/// The file holds the same [DeviceConfig] object as `config.json`, so
+/// renaming it imports the settings onto another clock.
+///
+/// ## Arguments
+/// - `storage`: A [SharedAppStorage] instance used to read the settings.
+///
+/// ## Returns
+/// - `Err(AppError)`: If no SD card is mounted, or the settings can't be read
+///   or written.
+///
+/// ## Example
+/// ```rust
+/// config::sd_card::export_config(&app_storage)?;
+/// ```
+pub fn export_config(storage: &SharedAppStorage) -> Result<(), AppError> {
+    if !is_mounted() {
+        return Err(AppError::Config("No SD card mounted".to_string()));
+    }
+
+    let config = DeviceConfig::export(storage)?.without_secrets();
+    let config_data =
+        serde_json::to_vec_pretty(&config).map_err(|e| AppError::Config(e.to_string()))?;
+
+    fs::write(EXPORTED_CONFIG_FILE, config_data)?;
+    log::info!("Exported the settings to {EXPORTED_CONFIG_FILE}");
+
+    Ok(())
+}
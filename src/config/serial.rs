@@ -0,0 +1,202 @@
+use super::DeviceConfig;
+use crate::{error::AppError, nvs::SharedAppStorage};
+use esp_idf_svc::{
+    hal::{
+        delay::BLOCK,
+        gpio::{AnyIOPin, InputPin, OutputPin},
+        peripheral::Peripheral,
+        uart::{config::Config as UartConfig, Uart, UartDriver},
+        units::Hertz,
+    },
+    sys::esp_restart,
+};
+use serde::{Deserialize, Serialize};
+
+/// Baud rate of the serial console, matching the default ESP-IDF monitor.
+const BAUD_RATE: u32 = 115_200;
+
+/// Max length of a single JSON line.
+const MAX_LINE_LEN: usize = 512;
+
+/// Prefix of every response line. The protocol shares UART0 with the ESP-IDF
+/// log console, so a configurator reads the lines starting with it and skips
+/// the logs.
+const RESPONSE_MARKER: &[u8] = b"@bttf ";
+
+/// A request received over the serial console, one JSON object per line.
+///
+/// ## Example
+/// ```json
+/// {"cmd":"get"}
+/// {"cmd":"set","config":{"timezone":"America/New_York","theme":"hoverboard"}}
+/// {"cmd":"restart"}
+/// ```
+#[derive(Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+enum SerialCommand {
+    Get,
+    Set { config: DeviceConfig },
+    Restart,
+}
+
+/// The response written back for every request line, after the
+/// [RESPONSE_MARKER].
+#[derive(Serialize, Default)]
+struct SerialResponse {
+    ok: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    config: Option<DeviceConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Serves a line-delimited JSON configuration protocol over the UART console,
+/// so a desktop configurator can read and write the settings of a clock
+/// connected via USB.
+pub struct SerialConfigurator {
+    uart: UartDriver<'static>,
+    storage: SharedAppStorage,
+}
+
+impl SerialConfigurator {
+    /// Creates a new [SerialConfigurator] on the given UART.
+    ///
+    /// ## Arguments
+    /// - `uart`: The UART peripheral connected to the USB-serial bridge.
+    /// - `tx`: The TX pin of the UART.
+    /// - `rx`: The RX pin of the UART.
+    /// - `storage`: A [SharedAppStorage] instance used to read and save the
+    ///   settings.
+    ///
+    /// ## Returns
+    /// - `Ok(Self)`: If the UART driver is installed successfully.
+    /// - `Err(AppError)`: If the UART driver can't be installed.
+    ///
+    /// ## Example
+    /// ```rust
+    /// let mut configurator = SerialConfigurator::new(
+    ///     peripherals.uart0,
+    ///     peripherals.pins.gpio1,
+    ///     peripherals.pins.gpio3,
+    ///     app_storage.clone(),
+    /// )?;
+    /// ```
+    pub fn new<UART: Uart>(
+        uart: impl Peripheral<P = UART> + 'static,
+        tx: impl Peripheral<P = impl OutputPin> + 'static,
+        rx: impl Peripheral<P = impl InputPin> + 'static,
+        storage: SharedAppStorage,
+    ) -> Result<Self, AppError> {
+        let uart = UartDriver::new(
+            uart,
+            tx,
+            rx,
+            Option::<AnyIOPin>::None,
+            Option::<AnyIOPin>::None,
+            &UartConfig::default().baudrate(Hertz(BAUD_RATE)),
+        )?;
+
+        Ok(Self { uart, storage })
+    }
+
+    /// Reads request lines forever and answers each one with a single JSON
+    /// line, prefixed with the [RESPONSE_MARKER].
+    ///
+    /// Lines that are not valid JSON requests are answered with
+    /// `{"ok":false,"error":"..."}`. Lines longer than [MAX_LINE_LEN] are
+    /// discarded.
+    pub fn run(&mut self) {
+        let mut line: Vec<u8> = Vec::with_capacity(MAX_LINE_LEN);
+        let mut byte = [0u8; 1];
+        let mut overflow = false;
+
+        loop {
+            match self.uart.read(&mut byte, BLOCK) {
+                Ok(1) => {}
+                Ok(_) => continue,
+                Err(e) => {
+                    log::error!("Failed to read from serial: {e:?}");
+                    continue;
+                }
+            }
+
+            match byte[0] {
+                b'\n' => {
+                    if overflow {
+                        self.respond(SerialResponse {
+                            error: Some("Line too long".to_string()),
+                            ..Default::default()
+                        });
+                    } else if !line.is_empty() {
+                        let response = self.handle_line(&line);
+                        self.respond(response);
+                    }
+
+                    line.clear();
+                    overflow = false;
+                }
+                b'\r' => {}
+                b => {
+                    if line.len() < MAX_LINE_LEN {
+                        line.push(b);
+                    } else {
+                        overflow = true;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Parses and executes a single request line.
+    fn handle_line(&self, line: &[u8]) -> SerialResponse {
+        let command = match serde_json::from_slice::<SerialCommand>(line) {
+            Ok(command) => command,
+            Err(e) => {
+                return SerialResponse {
+                    error: Some(format!("Invalid request: {e}")),
+                    ..Default::default()
+                }
+            }
+        };
+
+        let result = match command {
+            SerialCommand::Get => DeviceConfig::export(&self.storage).map(Some),
+            SerialCommand::Set { config } => config.apply(&self.storage).map(|_| None),
+            SerialCommand::Restart => {
+                log::info!("Restart requested over serial. Restarting...");
+                self.respond(SerialResponse {
+                    ok: true,
+                    ..Default::default()
+                });
+
+                unsafe {
+                    esp_restart();
+                }
+            }
+        };
+
+        match result {
+            Ok(config) => SerialResponse {
+                ok: true,
+                config,
+                error: None,
+            },
+            Err(e) => SerialResponse {
+                error: Some(e.to_string()),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Writes a response as a single JSON line, prefixed with the
+    /// [RESPONSE_MARKER].
+    fn respond(&self, response: SerialResponse) {
+        let mut payload = RESPONSE_MARKER.to_vec();
+        payload.extend(serde_json::to_vec(&response).unwrap_or_default());
+        payload.push(b'\n');
+
+        if let Err(e) = self.uart.write(&payload) {
+            log::error!("Failed to write to serial: {e:?}");
+        }
+    }
+}
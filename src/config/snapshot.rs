@@ -0,0 +1,95 @@
+use super::DeviceConfig;
+use crate::{
+    error::AppError, nvs::SharedAppStorage, server::client,
+    service::app_storage::AppStoragePrefsService,
+};
+use serde::Deserialize;
+use std::sync::PoisonError;
+
+/// The local hour at which the nightly snapshot is sent.
+pub const SNAPSHOT_HOUR: u32 = 3;
+
+/// Represents a request to set or clear the snapshot URL.
+///
+/// ## Example
+/// ```rust
+/// let snapshot_request: SnapshotRequest =
+///     serde_json::from_str("{\"url\":\"http://192.168.0.10/bttf\"}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct SnapshotRequest {
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+/// Checks that a snapshot URL can be used by the HTTP client and stored in
+/// NVS.
+///
+/// Only HTTP(S) URLs are accepted. Publishing the snapshot to an MQTT retained
+/// topic is out of scope, as the firmware has no MQTT client.
+pub fn is_valid_url(url: &str) -> bool {
+    (url.starts_with("http://") || url.starts_with("https://")) && url.len() < 256
+}
+
+/// Sends the exported settings, without secrets, to the given URL.
+///
+/// The body is the same [DeviceConfig] object accepted by the import endpoint,
+/// so the snapshot can be restored onto a replacement board as is.
+///
+/// ## Arguments
+/// - `url`: The URL the snapshot is `POST`ed to.
+/// - `storage`: A [SharedAppStorage] instance used to read the settings.
+///
+/// ## Returns
+/// - `Ok(())`: If the server answered with a `2xx` status.
+/// - `Err(AppError)`: If the settings can't be read or the upload fails.
+///
+/// ## Example
+/// ```rust
+/// upload_snapshot("http://192.168.0.10/bttf", &app_storage)?;
+/// ```
+pub fn upload_snapshot(url: &str, storage: &SharedAppStorage) -> Result<(), AppError> {
+    let config = DeviceConfig::export(storage)?.without_secrets();
+    let body = serde_json::to_vec(&config).map_err(|e| AppError::Config(e.to_string()))?;
+
+    let status = client::post_json(url, &body)?;
+    if !(200..300).contains(&status) {
+        return Err(AppError::Server(format!(
+            "Snapshot upload failed with status {status}"
+        )));
+    }
+
+    log::info!("Settings snapshot sent to {url}");
+
+    Ok(())
+}
+
+/// Sends a settings snapshot, if a snapshot URL is stored in NVS. It is meant
+/// to run every night at [SNAPSHOT_HOUR], as a
+/// [Schedule::Daily](crate::scheduler::Schedule::Daily) job, so the first
+/// snapshot is only sent once the clock synced with SNTP.
+///
+/// ## Example
+/// ```rust
+/// scheduler.add("snapshot", Schedule::Daily(SNAPSHOT_HOUR), move || {
+///     config::snapshot::send_nightly_snapshot(&app_storage)
+/// });
+/// ```
+pub fn send_nightly_snapshot(storage: &SharedAppStorage) {
+    let url = storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_snapshot_url();
+
+    match url {
+        Ok(Some(url)) => {
+            upload_snapshot(&url, storage)
+                .inspect_err(|e| {
+                    log::error!("Failed to send settings snapshot: {e:#?}");
+                })
+                .ok();
+        }
+        Ok(None) => {}
+        Err(e) => log::error!("Failed to read snapshot URL: {e}"),
+    }
+}
@@ -0,0 +1,151 @@
+use super::AppError;
+use crate::{
+    health::Subsystem, module::display::SharedSevenSegmentDisplay,
+    service::display::SevenSegmentDisplayService, util::encode_str,
+};
+use esp_idf_svc::hal::{
+    delay::FreeRtos,
+    gpio::{IOPin, OutputPin},
+};
+use std::sync::{Mutex, TryLockError};
+
+/// Code shown on the hour display after `FAIL` when the firmware panics: the
+/// tens digit `9` is the firmware itself, and the units digit `0` a panic.
+pub const PANIC_CODE: u8 = 90;
+
+/// How long `FAIL` and the [PANIC_CODE] are each shown, in milliseconds.
+const PANIC_FRAME_DURATION: u32 = 2000;
+
+/// Writes a frame to a display that initialized successfully.
+type ErrorDisplay = Box<dyn Fn([u8; 4]) -> Result<(), AppError> + Send>;
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable to hold the displays that can show an error code, with the subsystem of each.
+    static ref ERROR_DISPLAYS: Mutex<Vec<(Subsystem, ErrorDisplay)>> = Mutex::new(Vec::new());
+}
+
+/// Maps a failure to the two-digit code shown on the displays.
+///
+/// The tens digit identifies the [Subsystem], and the units digit the kind of
+/// [AppError], so `E 23` is a system error of the Wi-Fi.
+///
+/// | Tens | Subsystem    | Units | Error     |
+/// |------|--------------|-------|-----------|
+/// | 1    | Storage      | 1     | Esp I/O   |
+/// | 2    | Wi-Fi        | 2     | Std I/O   |
+/// | 3    | SNTP         | 3     | System    |
+/// | 4    | Date display | 4     | Display   |
+/// | 5    | Year display | 5     | LED strip |
+/// | 6    | Hour display | 6     | Server    |
+/// | 7    | LED strip    | 7     | Config    |
+/// | 8    | Web portal   | 8     | Sensor    |
+/// | 9    | Firmware     | 9     | Timezone  |
+/// |      |              | 0     | Panic     |
+///
+/// A panic of the firmware is always shown as [PANIC_CODE], see
+/// [show_panic].
+pub fn error_code(subsystem: Subsystem, error: &AppError) -> u8 {
+    let subsystem_code = match subsystem {
+        Subsystem::Storage => 1,
+        Subsystem::Wifi => 2,
+        Subsystem::Sntp => 3,
+        Subsystem::DateDisplay => 4,
+        Subsystem::YearDisplay => 5,
+        Subsystem::HourDisplay => 6,
+        Subsystem::LedStrip => 7,
+        Subsystem::WebPortal => 8,
+    };
+
+    let error_code = match error {
+        AppError::EspIO(_) => 1,
+        AppError::StdIO(_) => 2,
+        AppError::System(_) => 3,
+        AppError::Display(_) => 4,
+        AppError::LedStrip(_) => 5,
+        AppError::Server(_) => 6,
+        AppError::Config(_) => 7,
+        AppError::Sensor(_) => 8,
+        AppError::Timezone(_) => 9,
+    };
+
+    subsystem_code * 10 + error_code
+}
+
+/// Registers a display that initialized successfully, so it can show the
+/// error codes of the other subsystems.
+///
+/// The display is written even if a thread panicked while holding it, but not
+/// while it is held, so a panic of the thread holding it can still be shown
+/// by [show_panic] on the others.
+///
+/// ## Example
+/// ```rust
+/// error::code::register_display(Subsystem::HourDisplay, hour_display.clone());
+/// ```
+pub fn register_display<CLK, DIO>(
+    subsystem: Subsystem,
+    display: SharedSevenSegmentDisplay<'static, CLK, DIO>,
+) where
+    CLK: OutputPin,
+    DIO: IOPin,
+{
+    ERROR_DISPLAYS.lock().unwrap().push((
+        subsystem,
+        Box::new(move |frame| match display.try_lock() {
+            Ok(mut display) => display.write(&frame),
+            Err(TryLockError::Poisoned(display)) => display.into_inner().write(&frame),
+            Err(TryLockError::WouldBlock) => Err(AppError::Display("Display busy".to_string())),
+        }),
+    ));
+}
+
+/// Shows the code of a failure, e.g. `E 23`, on every registered display that
+/// still works, so the failure can be told without a serial console.
+///
+/// ## Example
+/// ```rust
+/// time::sntp::init_sntp(&sntp).inspect_err(|e| error::code::show_error(Subsystem::Sntp, e))?;
+/// ```
+pub fn show_error(subsystem: Subsystem, error: &AppError) {
+    let code = error_code(subsystem, error);
+    log::error!("Showing error code E {code:02} for {subsystem:?}: {error}");
+
+    let frame = encode_str(&format!("E {code:02}"));
+
+    for (_, display) in ERROR_DISPLAYS.lock().unwrap().iter() {
+        display(frame)
+            .inspect_err(|e| log::warn!("Failed to show the error code: {e:?}"))
+            .ok();
+    }
+}
+
+/// Shows `FAIL` and then the [PANIC_CODE], e.g. `E 90`, on the hour display,
+/// for 2 seconds each. Called by the panic hook before it restarts, see
+/// [install_panic_hook](super::panic::install_panic_hook).
+///
+/// Nothing is shown if the displays are busy, so a panic while they are
+/// registered never deadlocks.
+pub fn show_panic() {
+    let displays = match ERROR_DISPLAYS.try_lock() {
+        Ok(displays) => displays,
+        Err(TryLockError::Poisoned(displays)) => displays.into_inner(),
+        Err(TryLockError::WouldBlock) => return,
+    };
+
+    let Some((_, hour_display)) = displays
+        .iter()
+        .find(|(subsystem, _)| *subsystem == Subsystem::HourDisplay)
+    else {
+        return;
+    };
+
+    for frame in [
+        encode_str("FAIL"),
+        encode_str(&format!("E {PANIC_CODE:02}")),
+    ] {
+        if hour_display(frame).is_err() {
+            return;
+        }
+        FreeRtos::delay_ms(PANIC_FRAME_DURATION);
+    }
+}
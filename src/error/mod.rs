@@ -1,3 +1,6 @@
+pub mod code;
+pub mod panic;
+
 /// Represents errors that can occur in the application.
 #[derive(thiserror::Error, Debug)]
 pub enum AppError {
@@ -18,6 +21,15 @@ pub enum AppError {
 
     #[error("Server error: {0}")]
     Server(String),
+
+    #[error("Config error: {0}")]
+    Config(String),
+
+    #[error("Sensor error: {0}")]
+    Sensor(String),
+
+    #[error("Timezone error: {0}")]
+    Timezone(String),
 }
 
 impl From<tm1637::Error<esp_idf_svc::sys::EspError>> for AppError {
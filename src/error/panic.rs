@@ -0,0 +1,106 @@
+use super::code;
+use crate::{nvs::SharedAppStorage, service::app_storage::AppStoragePrefsService};
+use esp_idf_svc::sys::esp_restart;
+use std::{
+    backtrace::Backtrace,
+    cell::Cell,
+    panic::{self, UnwindSafe},
+    sync::{Mutex, TryLockError},
+};
+
+/// Max length in bytes of the panic message saved in NVS, so it fits the
+/// buffer it is read back into.
+const MAX_PANIC_MESSAGE_LEN: usize = 255;
+
+thread_local! {
+    /// Whether the current thread runs a closure through [catch], whose panics are handled by the caller.
+    static CATCHING: Cell<bool> = const { Cell::new(false) };
+}
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable to hold the message of the panic that restarted the firmware last, read from NVS at boot.
+    static ref LAST_PANIC: Mutex<Option<String>> = Mutex::new(None);
+}
+
+/// Retrieves the message of the panic that restarted the firmware last, if
+/// any, e.g. `panicked at src/main.rs:1093:14: ...`.
+pub fn get_last_panic() -> Option<String> {
+    LAST_PANIC.lock().unwrap().clone()
+}
+
+/// Updates the message of the last panic in a thread-safe way.
+pub fn set_last_panic(message: String) {
+    *LAST_PANIC.lock().unwrap() = Some(message);
+}
+
+/// Installs the panic hook of the firmware.
+///
+/// Every panic is logged with its backtrace. A panic outside of [catch]
+/// would leave its thread dead and the clock frozen, so the hook also shows
+/// `FAIL` and the [PANIC_CODE](code::PANIC_CODE) on the hour display, saves
+/// the panic message in NVS and restarts.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the panic message.
+///   It is skipped if a thread holds it, so the hook never deadlocks.
+///
+/// ## Example
+/// ```rust
+/// error::panic::install_panic_hook(app_storage.clone());
+/// ```
+pub fn install_panic_hook(storage: SharedAppStorage) {
+    let default_hook = panic::take_hook();
+
+    panic::set_hook(Box::new(move |info| {
+        default_hook(info);
+        log::error!("Backtrace:\n{}", Backtrace::force_capture());
+
+        if CATCHING.with(Cell::get) {
+            return;
+        }
+
+        let mut message = info.to_string();
+        if message.len() > MAX_PANIC_MESSAGE_LEN {
+            let end = (0..=MAX_PANIC_MESSAGE_LEN)
+                .rev()
+                .find(|end| message.is_char_boundary(*end))
+                .unwrap_or(0);
+            message.truncate(end);
+        }
+
+        match storage.try_lock() {
+            Ok(mut storage) => {
+                storage.save_last_panic(message).ok();
+            }
+            Err(TryLockError::Poisoned(_) | TryLockError::WouldBlock) => {
+                log::error!("Storage unavailable, the panic message is not saved")
+            }
+        }
+
+        code::show_panic();
+
+        log::error!("Restarting after a panic...");
+        unsafe { esp_restart() };
+    }));
+}
+
+/// Runs a closure, catching its panic instead of restarting, see
+/// [install_panic_hook].
+///
+/// This relies on the firmware unwinding on panics, see the `panic` setting of
+/// the profiles in `Cargo.toml` and `panic_unwind` in the `build-std` of
+/// `.cargo/config.toml`. A build with `panic = "abort"` still compiles, but
+/// there this function catches nothing: the panic aborts right after the
+/// hook, which skips the restart path for it.
+///
+/// ## Example
+/// ```rust
+/// let result = error::panic::catch(AssertUnwindSafe(|| handler(request)));
+/// ```
+pub fn catch<F: FnOnce() -> R + UnwindSafe, R>(f: F) -> std::thread::Result<R> {
+    let was_catching = CATCHING.with(|catching| catching.replace(true));
+    let result = panic::catch_unwind(f);
+    CATCHING.with(|catching| catching.set(was_catching));
+
+    result
+}
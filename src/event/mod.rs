@@ -0,0 +1,103 @@
+use crate::{theme::Theme, wifi::DisconnectReason};
+use std::sync::{
+    mpsc::{self, SyncSender, TrySendError},
+    Mutex,
+};
+
+/// Max number of events waiting for the subscribers. Events published while
+/// the queue is full are dropped, so publishers never block.
+const QUEUE_LEN: usize = 16;
+
+/// A function called with every published [Event], see [subscribe].
+type Subscriber = Box<dyn Fn(&Event) + Send>;
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable to hold the sender of the event bus, once it runs.
+    static ref EVENT_QUEUE: Mutex<Option<SyncSender<Event>>> = Mutex::new(None);
+
+    /// A global, thread-safe static variable to hold the subscribers of the event bus.
+    static ref SUBSCRIBERS: Mutex<Vec<Subscriber>> = Mutex::new(Vec::new());
+}
+
+/// Represents something that happened in a subsystem, which other subsystems
+/// may react to.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    /// The firmware finished starting up.
+    Booted,
+    /// The station connected to the Wi-Fi network.
+    WifiConnected,
+    /// The station lost the Wi-Fi network.
+    WifiDisconnected(DisconnectReason),
+    /// The time was synchronized with the SNTP server.
+    SntpSynced,
+    /// The time is still not synchronized after
+    /// [SYNC_TIMEOUT](crate::time::sntp::SYNC_TIMEOUT).
+    SntpFailed,
+    /// A new theme was applied to the LED strip.
+    ThemeChanged(Theme),
+    /// The sunrise of the wake light started.
+    AlarmFired,
+}
+
+/// Registers a function called with every [Event] published from then on, on
+/// the thread of [run_event_bus].
+///
+/// Subscribers are called one after the other, so they must not block, e.g.
+/// queue a [DisplayCommand](crate::module::display_queue::DisplayCommand)
+/// instead of drawing.
+///
+/// ## Example
+/// ```rust
+/// event::subscribe(health::on_event);
+/// ```
+pub fn subscribe(subscriber: impl Fn(&Event) + Send + 'static) {
+    SUBSCRIBERS.lock().unwrap().push(Box::new(subscriber));
+}
+
+/// Publishes an event to the subscribers without waiting for them, so it is
+/// safe to call from the ESP-IDF callbacks.
+///
+/// The event is dropped, with a warning, when the queue is full or the event
+/// bus is not running yet.
+///
+/// ## Example
+/// ```rust
+/// event::publish(Event::SntpSynced);
+/// ```
+pub fn publish(event: Event) {
+    let queue = EVENT_QUEUE.lock().unwrap();
+
+    let Some(sender) = queue.as_ref() else {
+        log::warn!("Event bus not running, dropping {event:?}");
+        return;
+    };
+
+    match sender.try_send(event) {
+        Ok(()) => {}
+        Err(TrySendError::Full(event)) => log::warn!("Event queue full, dropping {event:?}"),
+        Err(TrySendError::Disconnected(event)) => {
+            log::warn!("Event bus stopped, dropping {event:?}")
+        }
+    }
+}
+
+/// Calls every subscriber with the events sent with [publish], one after the
+/// other. Runs forever, so it is meant to be spawned on its own thread.
+///
+/// ## Example
+/// ```rust
+/// std::thread::spawn(event::run_event_bus);
+/// ```
+pub fn run_event_bus() {
+    let (sender, receiver) = mpsc::sync_channel(QUEUE_LEN);
+    *EVENT_QUEUE.lock().unwrap() = Some(sender);
+
+    for event in receiver {
+        log::info!("Event: {event:?}");
+
+        for subscriber in SUBSCRIBERS.lock().unwrap().iter() {
+            subscriber(&event);
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use esp_idf_svc::sys::{
+    esp_get_free_heap_size, esp_get_minimum_free_heap_size, heap_caps_get_largest_free_block,
+    uxTaskGetNumberOfTasks, uxTaskGetSystemState, TaskStatus_t, MALLOC_CAP_8BIT,
+};
+use serde::Serialize;
+use std::{collections::BTreeSet, ffi::CStr, sync::Mutex, time::Duration};
+
+/// How often the heap and the stacks are sampled.
+pub const SAMPLE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Free heap under which a warning is logged.
+const LOW_HEAP_BYTES: u32 = 16 * 1024;
+
+/// Largest free block under which a warning is logged, as the TLS client and
+/// the JSON bodies need contiguous buffers.
+const LOW_LARGEST_BLOCK_BYTES: u32 = 8 * 1024;
+
+/// Unused stack of a task under which a warning is logged.
+const LOW_STACK_BYTES: u32 = 512;
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable to hold the last [Diagnostics] sampled.
+    static ref DIAGNOSTICS: Mutex<Option<Diagnostics>> = Mutex::new(None);
+}
+
+/// Represents the stack of a FreeRTOS task.
+#[derive(Clone, Debug, Serialize)]
+pub struct TaskStack {
+    /// The name of the task, e.g. `httpd` for the HTTP server. The threads
+    /// spawned from Rust are all named `pthread`.
+    pub name: String,
+    /// The least unused stack since the task started, in bytes.
+    pub stack_high_water_mark: u32,
+}
+
+/// Represents the memory usage returned by the `/api/diagnostics` endpoint.
+#[derive(Clone, Debug, Serialize)]
+pub struct Diagnostics {
+    /// The free heap, in bytes.
+    pub free_heap: u32,
+    /// The least free heap since boot, in bytes.
+    pub min_free_heap: u32,
+    /// The largest block that can be allocated, in bytes.
+    pub largest_free_block: u32,
+    /// The stack of every task, from the least unused.
+    pub tasks: Vec<TaskStack>,
+}
+
+/// Retrieves the last [Diagnostics] sampled by [diagnostics_job], if any.
+pub fn get_diagnostics() -> Option<Diagnostics> {
+    DIAGNOSTICS.lock().unwrap().clone()
+}
+
+/// Samples the heap and the stack of every task.
+fn sample() -> Diagnostics {
+    let (free_heap, min_free_heap, largest_free_block) = unsafe {
+        (
+            esp_get_free_heap_size(),
+            esp_get_minimum_free_heap_size(),
+            heap_caps_get_largest_free_block(MALLOC_CAP_8BIT) as u32,
+        )
+    };
+
+    Diagnostics {
+        free_heap,
+        min_free_heap,
+        largest_free_block,
+        tasks: sample_tasks(),
+    }
+}
+
+/// Reads the stack high-water mark of every task, which needs
+/// `CONFIG_FREERTOS_USE_TRACE_FACILITY`.
+fn sample_tasks() -> Vec<TaskStack> {
+    // Leave room for the tasks started while the list is allocated
+    let capacity = unsafe { uxTaskGetNumberOfTasks() } as usize + 4;
+    let mut statuses: Vec<TaskStatus_t> = vec![unsafe { std::mem::zeroed() }; capacity];
+
+    let count =
+        unsafe { uxTaskGetSystemState(statuses.as_mut_ptr(), capacity as _, std::ptr::null_mut()) }
+            as usize;
+
+    let mut tasks: Vec<TaskStack> = statuses[..count]
+        .iter()
+        .map(|status| TaskStack {
+            name: unsafe { CStr::from_ptr(status.pcTaskName) }
+                .to_string_lossy()
+                .into_owned(),
+            stack_high_water_mark: status.usStackHighWaterMark as u32,
+        })
+        .collect();
+
+    tasks.sort_by_key(|task| task.stack_high_water_mark);
+
+    tasks
+}
+
+/// Returns the job that samples the heap and the stacks, keeps the numbers
+/// for `/api/diagnostics` and logs a warning when one of them drops under
+/// its threshold. It is meant to run every [SAMPLE_INTERVAL].
+///
+/// Each warning is logged once, when the threshold is crossed, and again
+/// only after the number went back over it.
+///
+/// ## Example
+/// ```rust
+/// scheduler.add(
+///     "diagnostics",
+///     Schedule::Every(health::diagnostics::SAMPLE_INTERVAL),
+///     health::diagnostics::diagnostics_job(),
+/// );
+/// ```
+pub fn diagnostics_job() -> impl FnMut() + Send {
+    let mut warned: BTreeSet<String> = BTreeSet::new();
+
+    move || {
+        let diagnostics = sample();
+
+        let mut levels = vec![
+            (
+                "free heap".to_string(),
+                diagnostics.free_heap,
+                LOW_HEAP_BYTES,
+            ),
+            (
+                "largest free block".to_string(),
+                diagnostics.largest_free_block,
+                LOW_LARGEST_BLOCK_BYTES,
+            ),
+        ];
+
+        // Tasks are sorted from the least unused stack, so only the first of
+        // the tasks sharing a name, like the `pthread` ones, is checked
+        let mut names = BTreeSet::new();
+        levels.extend(
+            diagnostics
+                .tasks
+                .iter()
+                .filter(|task| names.insert(task.name.as_str()))
+                .map(|task| {
+                    (
+                        format!("stack of task '{}'", task.name),
+                        task.stack_high_water_mark,
+                        LOW_STACK_BYTES,
+                    )
+                }),
+        );
+
+        for (name, value, threshold) in levels {
+            if value < threshold {
+                if warned.insert(name.clone()) {
+                    log::warn!("Low {name}: {value} bytes left, under {threshold} bytes");
+                }
+            } else if warned.remove(&name) {
+                log::info!("The {name} is back to {value} bytes");
+            }
+        }
+
+        *DIAGNOSTICS.lock().unwrap() = Some(diagnostics);
+    }
+}
@@ -0,0 +1,220 @@
+use crate::config::sd_card;
+use chrono::{DateTime, Utc};
+use esp_idf_svc::log::EspLogger;
+use log::{Log, Metadata, Record};
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{BufWriter, Write},
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        mpsc::{self, Receiver, SyncSender},
+        Mutex,
+    },
+    time::SystemTime,
+};
+
+/// Max number of log lines waiting to be written. Lines logged while the
+/// queue is full are dropped, and counted in the log file.
+const QUEUE_LEN: usize = 64;
+
+/// Size from which the log file is rotated, in bytes.
+const MAX_LOG_FILE_SIZE: u64 = 256 * 1024;
+
+/// Number of rotated log files kept next to the current one.
+const MAX_ROTATED_FILES: u8 = 4;
+
+/// The folder of the log files on the SD card.
+const LOG_DIR: &str = "/sdcard/logs";
+
+lazy_static::lazy_static! {
+    /// The logger of the firmware, printing to the console and queuing the lines for the log file.
+    static ref FILE_LOGGER: FileLogger = FileLogger {
+        esp_logger: EspLogger::new(),
+    };
+
+    /// A global, thread-safe static variable to hold the sender of the log lines.
+    static ref LOG_QUEUE: Mutex<Option<SyncSender<String>>> = Mutex::new(None);
+
+    /// A global, thread-safe static variable to hold the receiver of the log lines, until the writer runs.
+    static ref LOG_RECEIVER: Mutex<Option<Receiver<String>>> = Mutex::new(None);
+}
+
+/// Number of log lines dropped since the last one written.
+static DROPPED_LINES: AtomicU32 = AtomicU32::new(0);
+
+/// A logger that prints to the console like [EspLogger], with the same
+/// levels, and queues every line for [run_log_writer].
+struct FileLogger {
+    esp_logger: EspLogger,
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.esp_logger.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        self.esp_logger.log(record);
+
+        let timestamp: DateTime<Utc> = SystemTime::now().into();
+        let line = format!(
+            "{} {:<5} {}: {}\n",
+            timestamp.format("%Y-%m-%d %H:%M:%S"),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+
+        // Logging from here would call the logger again, so a line that can't
+        // be queued is only counted
+        if let Some(sender) = LOG_QUEUE.lock().unwrap().as_ref() {
+            if sender.try_send(line).is_err() {
+                DROPPED_LINES.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+
+    fn flush(&self) {
+        self.esp_logger.flush();
+    }
+}
+
+/// Installs the logger of the firmware in place of
+/// [EspLogger::initialize_default], so the lines logged from boot on are
+/// queued for the log file until [run_log_writer] writes them.
+///
+/// The levels set per tag, see [log_level](crate::prefs::log_level), apply to
+/// the log file too.
+pub fn init_logger() {
+    let (sender, receiver) = mpsc::sync_channel(QUEUE_LEN);
+    *LOG_QUEUE.lock().unwrap() = Some(sender);
+    *LOG_RECEIVER.lock().unwrap() = Some(receiver);
+
+    log::set_logger(&*FILE_LOGGER)
+        .map(|()| FILE_LOGGER.esp_logger.initialize())
+        .unwrap();
+}
+
+/// Stops queuing the log lines, e.g. when no SD card is mounted.
+pub fn disable() {
+    *LOG_QUEUE.lock().unwrap() = None;
+    *LOG_RECEIVER.lock().unwrap() = None;
+}
+
+/// Returns the path of a log file, `0` being the current one.
+fn log_path(index: u8) -> String {
+    match index {
+        0 => format!("{LOG_DIR}/bttf.log"),
+        _ => format!("{LOG_DIR}/bttf.{index}.log"),
+    }
+}
+
+/// Opens the current log file to append to it.
+fn open_log_file() -> std::io::Result<BufWriter<File>> {
+    let file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_path(0))?;
+
+    Ok(BufWriter::new(file))
+}
+
+/// Renames the current log file to `bttf.1.log`, shifting the older ones and
+/// deleting the oldest, so at most [MAX_ROTATED_FILES] are kept.
+fn rotate() {
+    fs::remove_file(log_path(MAX_ROTATED_FILES)).ok();
+
+    for index in (0..MAX_ROTATED_FILES).rev() {
+        // The older files may not exist yet
+        fs::rename(log_path(index), log_path(index + 1)).ok();
+    }
+}
+
+/// Writes a line to the log file, after the number of lines dropped since the
+/// previous one, if any.
+fn write_line(file: &mut BufWriter<File>, line: &str) -> std::io::Result<()> {
+    let dropped = DROPPED_LINES.swap(0, Ordering::Relaxed);
+    if dropped > 0 {
+        writeln!(file, "... {dropped} lines dropped")?;
+    }
+
+    file.write_all(line.as_bytes())
+}
+
+/// Flushes the log file, and rotates it once it reached [MAX_LOG_FILE_SIZE].
+///
+/// ## Returns
+/// The file to write the next lines to.
+fn flush_or_rotate(mut file: BufWriter<File>) -> std::io::Result<BufWriter<File>> {
+    file.flush()?;
+
+    if file.get_ref().metadata()?.len() < MAX_LOG_FILE_SIZE {
+        return Ok(file);
+    }
+
+    // FAT can't rename an open file
+    drop(file);
+    rotate();
+
+    open_log_file()
+}
+
+/// Writes the queued lines to the log file until the logger stops queuing.
+fn write_lines(receiver: Receiver<String>) -> std::io::Result<()> {
+    fs::create_dir_all(LOG_DIR)?;
+    let mut file = open_log_file()?;
+
+    loop {
+        let line = match receiver.try_recv() {
+            Ok(line) => line,
+            Err(_) => {
+                // The queue is empty, so write the lines to the card before
+                // waiting for the next one
+                file = flush_or_rotate(file)?;
+
+                match receiver.recv() {
+                    Ok(line) => line,
+                    Err(_) => return Ok(()),
+                }
+            }
+        };
+
+        write_line(&mut file, &line)?;
+    }
+}
+
+/// Writes the lines queued by the logger, see [init_logger], to `bttf.log`
+/// in the `logs` folder of the SD card, rotating it every
+/// [MAX_LOG_FILE_SIZE] bytes.
+///
+/// The file is flushed whenever the queue is empty, so a line reaches the
+/// card soon after it is logged. Runs until the card can't be written
+/// anymore, so it is meant to be spawned on its own thread once the card is
+/// mounted, see [mount](sd_card::mount).
+///
+/// ## Example
+/// ```rust
+/// std::thread::spawn(health::log_file::run_log_writer);
+/// ```
+pub fn run_log_writer() {
+    let Some(receiver) = LOG_RECEIVER.lock().unwrap().take() else {
+        return;
+    };
+
+    log::info!("Logging to {} on the SD card", log_path(0));
+
+    if let Err(e) = write_lines(receiver) {
+        // Stop queuing first, so this error isn't queued for nothing
+        disable();
+        log::error!("Failed to write the log file, stopping: {e:?}");
+    }
+}
+
+/// Whether the log lines are written to the SD card.
+pub fn is_logging() -> bool {
+    sd_card::is_mounted() && LOG_QUEUE.lock().unwrap().is_some()
+}
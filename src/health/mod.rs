@@ -0,0 +1,169 @@
+use crate::{
+    error,
+    event::{self, Event},
+};
+use serde::Serialize;
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+pub mod diagnostics;
+#[cfg(feature = "sd-card")]
+pub mod log_file;
+pub mod recovery;
+pub mod safe_mode;
+pub mod watchdog;
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe registry holding the last reported health of every subsystem.
+    pub static ref HEALTH_REGISTRY: Arc<Mutex<BTreeMap<Subsystem, SubsystemHealth>>> =
+        Arc::new(Mutex::new(BTreeMap::new()));
+}
+
+/// Represents the subsystems that report their health.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Subsystem {
+    Storage,
+    Wifi,
+    Sntp,
+    DateDisplay,
+    YearDisplay,
+    HourDisplay,
+    LedStrip,
+    WebPortal,
+}
+
+/// Represents the health state of a subsystem, ordered from best to worst.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HealthState {
+    Ok,
+    Degraded,
+    Failed,
+}
+
+/// Represents the last reported health of a subsystem.
+#[derive(Clone, Debug, Serialize)]
+pub struct SubsystemHealth {
+    pub subsystem: Subsystem,
+    pub state: HealthState,
+    /// The last error reported by the subsystem, kept after it recovers.
+    pub last_error: Option<String>,
+    /// The number of failures reported in a row since the subsystem last
+    /// worked.
+    pub consecutive_failures: u32,
+}
+
+/// Represents the health report returned by the `/api/health` endpoint.
+#[derive(Clone, Debug, Serialize)]
+pub struct HealthReport {
+    /// The worst state among all subsystems.
+    pub status: HealthState,
+    pub subsystems: Vec<SubsystemHealth>,
+    /// The message of the panic that restarted the firmware last, if any.
+    pub last_panic: Option<String>,
+    /// Whether the firmware booted into safe mode after resetting too often.
+    pub safe_mode: bool,
+}
+
+/// Reports a subsystem as working.
+pub fn report_ok(subsystem: Subsystem) {
+    report(subsystem, HealthState::Ok, None);
+}
+
+/// Reports a subsystem as working with reduced functionality.
+pub fn report_degraded(subsystem: Subsystem, error: impl ToString) {
+    report(subsystem, HealthState::Degraded, Some(error.to_string()));
+}
+
+/// Reports a subsystem as not working.
+pub fn report_failed(subsystem: Subsystem, error: impl ToString) {
+    report(subsystem, HealthState::Failed, Some(error.to_string()));
+}
+
+/// Updates the entry of a subsystem in the [HEALTH_REGISTRY] in a thread-safe
+/// way.
+fn report(subsystem: Subsystem, state: HealthState, error: Option<String>) {
+    let mut registry = HEALTH_REGISTRY.lock().unwrap();
+
+    let entry = registry.entry(subsystem).or_insert(SubsystemHealth {
+        subsystem,
+        state,
+        last_error: None,
+        consecutive_failures: 0,
+    });
+
+    if entry.state != state {
+        log::info!("Health of {subsystem:?} changed to {state:?}");
+    }
+
+    entry.state = state;
+    if error.is_some() {
+        entry.last_error = error;
+    }
+
+    if state == HealthState::Ok {
+        entry.consecutive_failures = 0;
+    } else {
+        entry.consecutive_failures += 1;
+    }
+}
+
+/// Keeps the health of the Wi-Fi and SNTP up to date from the [Event]s of
+/// the event bus, see [subscribe](event::subscribe).
+pub fn on_event(event: &Event) {
+    match event {
+        Event::WifiConnected => report_ok(Subsystem::Wifi),
+        Event::WifiDisconnected(reason) => report_degraded(Subsystem::Wifi, reason.description()),
+        Event::SntpSynced => report_ok(Subsystem::Sntp),
+        Event::SntpFailed => report_degraded(Subsystem::Sntp, "Not synchronized yet"),
+        _ => {}
+    }
+}
+
+/// Retrieves the last reported state of a subsystem, if it reported at least
+/// once.
+pub fn get_state(subsystem: Subsystem) -> Option<HealthState> {
+    HEALTH_REGISTRY
+        .lock()
+        .unwrap()
+        .get(&subsystem)
+        .map(|health| health.state)
+}
+
+/// Retrieves the number of failures a subsystem reported in a row since it
+/// last worked.
+pub fn get_consecutive_failures(subsystem: Subsystem) -> u32 {
+    HEALTH_REGISTRY
+        .lock()
+        .unwrap()
+        .get(&subsystem)
+        .map_or(0, |health| health.consecutive_failures)
+}
+
+/// Builds a [HealthReport] from the current state of the [HEALTH_REGISTRY].
+///
+/// ## Example
+/// ```rust
+/// let report = health::get_report();
+/// println!("{:?}", report.status);
+/// ```
+pub fn get_report() -> HealthReport {
+    let registry = HEALTH_REGISTRY.lock().unwrap();
+    let subsystems: Vec<SubsystemHealth> = registry.values().cloned().collect();
+
+    let status = subsystems
+        .iter()
+        .map(|subsystem| subsystem.state)
+        .max()
+        .unwrap_or(HealthState::Ok);
+
+    HealthReport {
+        status,
+        subsystems,
+        last_panic: error::panic::get_last_panic(),
+        safe_mode: safe_mode::is_safe_mode(),
+    }
+}
@@ -0,0 +1,88 @@
+use super::Subsystem;
+use crate::error::{self, AppError};
+use esp_idf_svc::{hal::delay::FreeRtos, sys::esp_restart};
+use std::time::Duration;
+
+/// Number of attempts of an operation before it counts as a failure.
+const MAX_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry, doubled before each next one.
+const INITIAL_BACKOFF: Duration = Duration::from_millis(50);
+
+/// Number of failures in a row after which the firmware restarts. The
+/// displays are updated every minute, so they get about 10 minutes to
+/// recover.
+pub const MAX_CONSECUTIVE_FAILURES: u32 = 10;
+
+/// Runs an operation, retrying it with an exponential backoff, starting at
+/// [INITIAL_BACKOFF], while it fails.
+///
+/// ## Returns
+/// - `Ok(T)`: The result of the first attempt that succeeds.
+/// - `Err(AppError)`: The error of the last attempt, after [MAX_ATTEMPTS].
+///
+/// ## Example
+/// ```rust
+/// let reading = health::recovery::retry_with_backoff(|| sensor.read())?;
+/// ```
+pub fn retry_with_backoff<T>(
+    mut operation: impl FnMut() -> Result<T, AppError>,
+) -> Result<T, AppError> {
+    let mut backoff = INITIAL_BACKOFF;
+    let mut attempt = 1;
+
+    loop {
+        match operation() {
+            Err(e) if attempt < MAX_ATTEMPTS => {
+                log::warn!(
+                    "Attempt {attempt}/{MAX_ATTEMPTS} failed, retrying in {backoff:?}: {e:?}"
+                );
+                FreeRtos::delay_ms(backoff.as_millis() as u32);
+
+                backoff *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Runs a periodic operation of a subsystem with [retry_with_backoff] and
+/// reports its health, so a transient error, e.g. a TM1637 missing an ACK,
+/// only skips one update.
+///
+/// The subsystem is reported as degraded while it fails. Once it failed
+/// [MAX_CONSECUTIVE_FAILURES] times in a row, it is reported as failed, its
+/// error code is shown on the displays that still work, and the firmware
+/// restarts.
+///
+/// ## Example
+/// ```rust
+/// health::recovery::run_with_recovery(Subsystem::DateDisplay, || {
+///     message_display_group.lock().unwrap().update_date()
+/// });
+/// ```
+pub fn run_with_recovery(subsystem: Subsystem, operation: impl FnMut() -> Result<(), AppError>) {
+    let e = match retry_with_backoff(operation) {
+        Ok(()) => {
+            super::report_ok(subsystem);
+            return;
+        }
+        Err(e) => e,
+    };
+
+    super::report_degraded(subsystem, &e);
+
+    let failures = super::get_consecutive_failures(subsystem);
+    log::error!(
+        "Failed to update {subsystem:?} ({failures}/{MAX_CONSECUTIVE_FAILURES} in a row): {e:#?}"
+    );
+
+    if failures >= MAX_CONSECUTIVE_FAILURES {
+        super::report_failed(subsystem, &e);
+        error::code::show_error(subsystem, &e);
+
+        log::error!("Restarting after persistent failures of {subsystem:?}...");
+        unsafe { esp_restart() };
+    }
+}
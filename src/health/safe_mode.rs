@@ -0,0 +1,215 @@
+use crate::{
+    error::{self, AppError},
+    nvs::{AppStorage, SharedAppStorage},
+    server::web_portal::WebPortal,
+    service::app_storage::{AppStoragePrefsService, AppStorageWifiService},
+    wifi,
+};
+use esp_idf_svc::{
+    eventloop::EspSystemEventLoop,
+    hal::modem::Modem,
+    nvs::{EspDefaultNvsPartition, EspNvsPartition, NvsDefault},
+    sys::{esp_reset_reason, esp_reset_reason_t_ESP_RST_POWERON},
+};
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU8, Ordering},
+        PoisonError,
+    },
+    time::Duration,
+};
+
+/// Number of resets in a row, each before [STABLE_UPTIME], after which the
+/// firmware boots into safe mode.
+pub const CRASH_LIMIT: u8 = 5;
+
+/// How long the firmware has to run before the crash counter is cleared.
+const STABLE_UPTIME: Duration = Duration::from_secs(60);
+
+/// Whether this boot is in safe mode, see [record_boot].
+static SAFE_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Number of resets in a row counted at boot.
+static CRASH_COUNT: AtomicU8 = AtomicU8::new(0);
+
+/// Retrieves whether the firmware booted into safe mode.
+pub fn is_safe_mode() -> bool {
+    SAFE_MODE.load(Ordering::Relaxed)
+}
+
+/// Retrieves the number of resets in a row counted at boot.
+pub fn get_crash_count() -> u8 {
+    CRASH_COUNT.load(Ordering::Relaxed)
+}
+
+/// Counts this boot in NVS, before the drivers are started, and decides
+/// whether to boot into safe mode.
+///
+/// Every reset other than a power-on counts, e.g. a panic, a watchdog or a
+/// brown-out, and the count is cleared by [run_stable_timer] once the
+/// firmware ran for [STABLE_UPTIME]. After [CRASH_LIMIT] resets in a row, the
+/// firmware stays in safe mode until it is powered off, or the count is
+/// cleared from the web portal.
+///
+/// ## Returns
+/// `true` if the firmware must boot into safe mode.
+///
+/// ## Example
+/// ```rust
+/// if health::safe_mode::record_boot(nvs_default_partition.clone()) {
+///     return health::safe_mode::run_safe_mode(modem, sysloop, nvs_default_partition);
+/// }
+/// ```
+pub fn record_boot(nvs_default_partition: EspNvsPartition<NvsDefault>) -> bool {
+    let storage = match AppStorage::new(nvs_default_partition) {
+        Ok(storage) => storage,
+        Err(e) => {
+            log::error!("Failed to count the boot: {e:?}");
+            return false;
+        }
+    };
+    let mut storage = storage.lock().unwrap_or_else(PoisonError::into_inner);
+
+    let crash_count = if unsafe { esp_reset_reason() } == esp_reset_reason_t_ESP_RST_POWERON {
+        0
+    } else {
+        storage
+            .get_maybe_crash_count()
+            .unwrap_or(None)
+            .unwrap_or(0)
+            .saturating_add(1)
+    };
+
+    storage
+        .save_crash_count(crash_count)
+        .inspect_err(|e| log::error!("Failed to save the crash count: {e:?}"))
+        .ok();
+
+    let safe_mode = crash_count >= CRASH_LIMIT;
+    CRASH_COUNT.store(crash_count, Ordering::Relaxed);
+    SAFE_MODE.store(safe_mode, Ordering::Relaxed);
+
+    if safe_mode {
+        log::error!("{crash_count} resets in a row, booting into safe mode");
+    } else if crash_count > 0 {
+        log::warn!("{crash_count}/{CRASH_LIMIT} resets in a row");
+    }
+
+    safe_mode
+}
+
+/// Clears the crash count once the firmware ran for [STABLE_UPTIME].
+///
+/// It is spawned on its own thread right after the crash count is read,
+/// rather than run by the [Scheduler](crate::scheduler::Scheduler), which only
+/// starts once the Wi-Fi and the web portal are up. The uptime is counted
+/// from the boot, so the restarts of a device waiting in the captive portal
+/// or for its router aren't taken for a firmware that keeps crashing.
+///
+/// ## Example
+/// ```rust
+/// std::thread::spawn(move || health::safe_mode::run_stable_timer(app_storage));
+/// ```
+pub fn run_stable_timer(storage: SharedAppStorage) {
+    std::thread::sleep(STABLE_UPTIME);
+
+    if get_crash_count() > 0 {
+        log::info!("Running for {STABLE_UPTIME:?}, clearing the crash count");
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_crash_count(0)
+            .inspect_err(|e| log::error!("Failed to clear the crash count: {e:?}"))
+            .ok();
+    }
+}
+
+/// Brings up only the Wi-Fi and a minimal web portal, without the displays,
+/// the LED strip or the sensors, so a firmware that keeps crashing can still
+/// be inspected and the crash count cleared.
+///
+/// The station connects with the stored credentials, or the access point is
+/// started if there are none. Never returns, unless the Wi-Fi or the web
+/// portal can't be started.
+///
+/// ## Arguments
+/// - `modem` - The Wi-Fi modem.
+/// - `sysloop` - The system event loop.
+/// - `nvs_default_partition` - The default NVS partition.
+///
+/// ## Returns
+/// - `Err(AppError)`: If the Wi-Fi or the web portal can't be started.
+pub fn run_safe_mode(
+    modem: Modem,
+    sysloop: EspSystemEventLoop,
+    nvs_default_partition: EspDefaultNvsPartition,
+) -> Result<(), AppError> {
+    let storage = AppStorage::new(nvs_default_partition.clone())?;
+
+    error::panic::install_panic_hook(storage.clone());
+
+    if let Some(last_panic) = storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_last_panic()
+        .unwrap_or(None)
+    {
+        log::warn!("Last restart after a panic: {last_panic}");
+        error::panic::set_last_panic(last_panic);
+    }
+
+    let credentials = storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_wifi_credentials()
+        .unwrap_or(None);
+
+    let _wifi = match credentials {
+        Some(credentials) => {
+            let mut storage = storage.lock().unwrap_or_else(PoisonError::into_inner);
+            let static_ip = storage.get_maybe_static_ip().unwrap_or(None);
+            let hidden = storage
+                .get_maybe_hidden_ssid()
+                .unwrap_or(None)
+                .unwrap_or(false);
+            let auth_mode = storage
+                .get_maybe_auth_mode()
+                .unwrap_or(None)
+                .unwrap_or_default();
+            let enterprise = storage.get_maybe_enterprise_config().unwrap_or(None);
+            drop(storage);
+
+            let mut wifi_station = wifi::station::get_station(
+                modem,
+                sysloop,
+                Some(nvs_default_partition),
+                credentials,
+                static_ip,
+                hidden,
+                auth_mode,
+                enterprise,
+            )?;
+
+            if wifi::station::connect_wifi(&mut wifi_station)? {
+                let ip = wifi::station::get_station_ip(&wifi_station)?;
+                log::warn!("Safe mode web portal at http://{ip}");
+            }
+
+            wifi_station
+        }
+        None => {
+            let mut wifi_ap = wifi::ap::get_ap(modem, sysloop, Some(nvs_default_partition))?;
+            wifi::ap::start_wifi_ap(&mut wifi_ap)?;
+            log::warn!("Safe mode web portal at http://{}", wifi::ap::AP_IP_ADDRESS);
+
+            wifi_ap
+        }
+    };
+
+    let mut web_portal = WebPortal::new()?;
+    web_portal.create_safe_mode_routes(storage)?;
+
+    loop {
+        std::thread::sleep(Duration::from_secs(60));
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{error::AppError, server::client};
+use esp_idf_svc::sys::{
+    esp, esp_task_wdt_add, esp_task_wdt_config_t, esp_task_wdt_delete, esp_task_wdt_init,
+    esp_task_wdt_reconfigure, esp_task_wdt_reset, ESP_ERR_INVALID_STATE,
+};
+use std::{marker::PhantomData, time::Duration};
+
+/// How long a watched task may go without feeding the watchdog before the
+/// firmware panics and restarts. It covers the minute the update loop waits
+/// between two renders.
+pub const WATCHDOG_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// How often the HTTP server is checked, see [run_http_watchdog].
+const HTTP_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// The endpoint requested over the loopback interface to check the HTTP
+/// server.
+const HTTP_CHECK_URL: &str = "http://127.0.0.1/api/health";
+
+/// Max size of the response of [HTTP_CHECK_URL].
+const MAX_RESPONSE_LEN: usize = 2048;
+
+/// Configures the task watchdog with the [WATCHDOG_TIMEOUT], panicking when
+/// a watched task stops feeding it.
+///
+/// ESP-IDF starts the task watchdog at boot to watch the idle tasks with a
+/// 5 second timeout, and only logs a warning when it triggers. Only the tasks
+/// watched with [watch_current_task] are checked from then on.
+pub fn init() -> Result<(), AppError> {
+    let config = esp_task_wdt_config_t {
+        timeout_ms: WATCHDOG_TIMEOUT.as_millis() as u32,
+        idle_core_mask: 0,
+        trigger_panic: true,
+    };
+
+    match esp!(unsafe { esp_task_wdt_reconfigure(&config) }) {
+        Err(e) if e.code() == ESP_ERR_INVALID_STATE as i32 => {
+            esp!(unsafe { esp_task_wdt_init(&config) })?;
+        }
+        result => result?,
+    }
+
+    log::info!("Task watchdog armed with a {WATCHDOG_TIMEOUT:?} timeout");
+
+    Ok(())
+}
+
+/// A subscription of the current task to the task watchdog, which has to be
+/// fed at least every [WATCHDOG_TIMEOUT]. Dropping it stops watching the
+/// task.
+pub struct WatchdogSubscription {
+    /// The subscription belongs to the task that created it.
+    _task: PhantomData<*const ()>,
+}
+
+impl WatchdogSubscription {
+    /// Tells the watchdog that the task is still running.
+    pub fn feed(&self) {
+        unsafe { esp_task_wdt_reset() };
+    }
+}
+
+impl Drop for WatchdogSubscription {
+    fn drop(&mut self) {
+        unsafe { esp_task_wdt_delete(std::ptr::null_mut()) };
+    }
+}
+
+/// Subscribes the current task to the task watchdog.
+///
+/// ## Returns
+/// - `Ok(WatchdogSubscription)`: The subscription to feed.
+/// - `Err(AppError)`: If the task watchdog is not initialized, see [init].
+///
+/// ## Example
+/// ```rust
+/// let watchdog = health::watchdog::watch_current_task()?;
+/// loop {
+///     watchdog.feed();
+///     // ...
+/// }
+/// ```
+pub fn watch_current_task() -> Result<WatchdogSubscription, AppError> {
+    esp!(unsafe { esp_task_wdt_add(std::ptr::null_mut()) })?;
+
+    Ok(WatchdogSubscription { _task: PhantomData })
+}
+
+/// Watches the HTTP server, whose task belongs to ESP-IDF and can't feed the
+/// watchdog itself.
+///
+/// `/api/health` is requested over the loopback interface every
+/// [HTTP_CHECK_INTERVAL], and the watchdog is only fed when it answers, so a
+/// handler stuck on a lock restarts the firmware. Runs forever, so it is
+/// meant to be spawned on its own thread.
+///
+/// It isn't a [Scheduler](crate::scheduler::Scheduler) job, as the watchdog is
+/// subscribed per task: a slow job would then restart the firmware as if the
+/// HTTP server was stuck, and a stuck server would go unnoticed while the
+/// jobs keep feeding it.
+pub fn run_http_watchdog() {
+    let watchdog = match watch_current_task() {
+        Ok(watchdog) => watchdog,
+        Err(e) => {
+            log::error!("Failed to watch the HTTP server: {e:?}");
+            return;
+        }
+    };
+
+    loop {
+        std::thread::sleep(HTTP_CHECK_INTERVAL);
+
+        match check_http_server() {
+            Ok(()) => watchdog.feed(),
+            Err(e) => log::warn!("HTTP server not answering: {e:?}"),
+        }
+    }
+}
+
+/// Requests `/api/health` over the loopback interface, to check that the HTTP
+/// server answers.
+///
+/// ## Returns
+/// - `Err(AppError)`: If the HTTP server doesn't answer.
+pub fn check_http_server() -> Result<(), AppError> {
+    client::get(HTTP_CHECK_URL, &[], MAX_RESPONSE_LEN).map(|_| ())
+}
@@ -1,141 +1,700 @@
 use crate::{
-    module::display::{DisplayGroup, SharedDisplayGroup},
-    prefs::hour_format::{get_hour_format, HourFormat},
+    config::serial::SerialConfigurator,
+    health::{HealthState, Subsystem},
+    module::{
+        display::{DisplayGroup, SevenSegmentDisplay, SharedSevenSegmentDisplay},
+        display_queue::DisplayCommand,
+        led_strip::SharedLedStrip,
+    },
+    prefs::hour_format::get_hour_format,
     service::app_storage::AppStoragePrefsService,
+    util::messages::DisplayMessage,
 };
+use board_config::BoardConfig;
 use esp_idf_svc::{
     eventloop::EspSystemEventLoop,
-    hal::{delay::FreeRtos, gpio::OutputPin, peripheral::Peripheral, prelude::Peripherals},
+    hal::{
+        delay::FreeRtos,
+        gpio::{IOPin, OutputPin},
+        prelude::Peripherals,
+    },
     nvs::EspDefaultNvsPartition,
     sys::esp_restart,
+    wifi::{BlockingWifi, EspWifi, WifiEvent},
 };
 use nvs::AppStorage;
+use scheduler::Schedule;
 use server::{dns_responder::DnsResponder, web_portal::WebPortal};
 use service::{
-    app_storage::{AppStorageTzService, AppStorageWifiService},
+    app_storage::{AppStorageSettingsService, AppStorageWifiService},
     display::SevenSegmentDisplayService,
     led::AmPmIndicatorService,
     led_strip::LedStripService,
 };
-use std::{net::Ipv4Addr, str::FromStr, sync::Mutex, time::Duration};
-use theme::{AppTheme, Theme};
+use std::{
+    net::Ipv4Addr,
+    str::FromStr,
+    sync::{Mutex, PoisonError},
+    time::Duration,
+};
 use wifi::ap::AP_IP_ADDRESS;
 
+#[cfg(all(feature = "ht16k33", feature = "max7219"))]
+compile_error!("The ht16k33 and max7219 features select different displays, enable only one");
+
+#[cfg(all(
+    feature = "six-digit-hour",
+    any(feature = "ht16k33", feature = "max7219")
+))]
+compile_error!("The six-digit-hour feature needs TM1637 displays");
+
+#[cfg(all(feature = "dht22", feature = "bme280"))]
+compile_error!("The dht22 and bme280 features both read the room, enable only one");
+
+#[cfg(all(feature = "tzdb", feature = "posix-tz"))]
+compile_error!("The tzdb and posix-tz features select different time backends, build posix-tz with --no-default-features");
+
+#[cfg(all(feature = "audio", feature = "sd-card"))]
+compile_error!("The audio and sd-card features both use GPIO25, enable only one");
+
+#[cfg(not(any(feature = "tzdb", feature = "posix-tz")))]
+compile_error!("A time backend is needed, enable the tzdb or posix-tz feature");
+
+mod audio;
+mod board_config;
+mod config;
 mod error;
+mod event;
+mod health;
 mod module;
 mod nvs;
+mod ota;
+mod power;
 mod prefs;
+mod presence;
+mod rules;
+mod scheduler;
 mod server;
 mod service;
+mod state;
 mod theme;
 mod time;
 mod util;
+mod weather;
+mod webhook;
 mod wifi;
 
 fn main() -> Result<(), error::AppError> {
     esp_idf_svc::sys::link_patches();
+    #[cfg(not(feature = "sd-card"))]
     esp_idf_svc::log::EspLogger::initialize_default();
+    // Queue the log lines from boot on, for the log files on the SD card
+    #[cfg(feature = "sd-card")]
+    health::log_file::init_logger();
 
     let peripherals = Peripherals::take()?;
 
-    // Pins
-    let led_strip_rmt = peripherals.rmt.channel0;
-    let led_strip_dio = peripherals.pins.gpio5.downgrade_output();
-    let am_led_pin = peripherals.pins.gpio32.downgrade_output();
-    let pm_led_pin = peripherals.pins.gpio33.downgrade_output();
-    let mut display_clk = peripherals.pins.gpio16.downgrade_output();
-    let date_display_dio = peripherals.pins.gpio17;
-    let year_display_dio = peripherals.pins.gpio19;
-    let hour_display_dio = peripherals.pins.gpio26;
+    // Restart when the display update loop, the display task or the HTTP server
+    // hang
+    health::watchdog::init()?;
 
     let sysloop = EspSystemEventLoop::take()?;
     let nvs_default_partition = EspDefaultNvsPartition::take()?;
 
-    let app_storage = AppStorage::new(nvs_default_partition.clone())?;
+    // Only bring up the Wi-Fi and a minimal web portal after too many resets
+    // in a row, before any driver that may be the cause is started
+    if health::safe_mode::record_boot(nvs_default_partition.clone()) {
+        return health::safe_mode::run_safe_mode(peripherals.modem, sysloop, nvs_default_partition);
+    }
+
+    // Pins, read from NVS so other boards don't need their own build. They are
+    // taken by number, so none of them may come from `peripherals.pins`
+    let board = BoardConfig::load(nvs_default_partition.clone());
+    let led_strip_rmt = peripherals.rmt.channel0;
+    let led_strip_dio = unsafe { board_config::output_pin(board.led_strip) };
+    let am_led_pin = unsafe { board_config::output_pin(board.am_led) };
+    let pm_led_pin = unsafe { board_config::output_pin(board.pm_led) };
+    #[cfg(not(any(feature = "ht16k33", feature = "max7219")))]
+    let mut display_clk = unsafe { board_config::output_pin(board.display_clk) };
+    #[cfg(not(feature = "ht16k33"))]
+    let date_display_dio = unsafe { board_config::io_pin(board.date_display_dio) };
+    #[cfg(not(feature = "ht16k33"))]
+    let year_display_dio = unsafe { board_config::io_pin(board.year_display_dio) };
+    #[cfg(not(feature = "ht16k33"))]
+    let hour_display_dio = unsafe { board_config::io_pin(board.hour_display_dio) };
+
+    // Initialize AM/PM leds
+    let am_pm_indicator = module::led::AmPmIndicator::new(am_led_pin, pm_led_pin)?;
+    am_pm_indicator.lock().unwrap().clear()?;
+
+    // Initialize the displays. When one fails, its error code is shown on the
+    // others
+    #[cfg(not(any(feature = "ht16k33", feature = "max7219")))]
+    let (date_display, year_display, hour_display) = {
+        use esp_idf_svc::hal::peripheral::Peripheral;
+
+        (
+            init_display(
+                SevenSegmentDisplay::new(
+                    unsafe { display_clk.clone_unchecked() },
+                    date_display_dio,
+                ),
+                Subsystem::DateDisplay,
+            ),
+            init_display(
+                SevenSegmentDisplay::new(
+                    unsafe { display_clk.clone_unchecked() },
+                    year_display_dio,
+                ),
+                Subsystem::YearDisplay,
+            ),
+            init_display(
+                #[cfg(not(feature = "six-digit-hour"))]
+                SevenSegmentDisplay::new(
+                    unsafe { display_clk.clone_unchecked() },
+                    hour_display_dio,
+                ),
+                #[cfg(feature = "six-digit-hour")]
+                SevenSegmentDisplay::new_six_digit(
+                    unsafe { display_clk.clone_unchecked() },
+                    hour_display_dio,
+                ),
+                Subsystem::HourDisplay,
+            ),
+        )
+    };
+
+    // The HT16K33 backpacks and the BME280 share the I2C bus, on GPIO21 (SDA) and
+    // GPIO22 (SCL) by default, and are told apart by their address
+    #[cfg(any(feature = "ht16k33", feature = "bme280"))]
+    let i2c_bus = module::i2c::new_bus(
+        peripherals.i2c0,
+        unsafe { board_config::io_pin(board.i2c_sda) },
+        unsafe { board_config::io_pin(board.i2c_scl) },
+    )?;
+
+    // With HT16K33 backpacks, the displays keep the pin types of the TM1637
+    // displays, which are left unused
+    #[cfg(feature = "ht16k33")]
+    let (date_display, year_display, hour_display) = {
+        use esp_idf_svc::hal::gpio::{AnyIOPin, AnyOutputPin};
+        use module::ht16k33::BASE_ADDRESS;
+
+        (
+            init_display(
+                SevenSegmentDisplay::<AnyOutputPin, AnyIOPin>::new_ht16k33(
+                    i2c_bus.clone(),
+                    BASE_ADDRESS,
+                ),
+                Subsystem::DateDisplay,
+            ),
+            init_display(
+                SevenSegmentDisplay::<AnyOutputPin, AnyIOPin>::new_ht16k33(
+                    i2c_bus.clone(),
+                    BASE_ADDRESS + 1,
+                ),
+                Subsystem::YearDisplay,
+            ),
+            init_display(
+                SevenSegmentDisplay::<AnyOutputPin, AnyIOPin>::new_ht16k33(
+                    i2c_bus.clone(),
+                    BASE_ADDRESS + 2,
+                ),
+                Subsystem::HourDisplay,
+            ),
+        )
+    };
+
+    // With MAX7219 modules, the displays share the SPI bus, on GPIO16 (CLK) and
+    // GPIO13 (DIN) by default, and the DIO pins of the TM1637 displays select them
+    // (CS)
+    #[cfg(feature = "max7219")]
+    let (date_display, year_display, hour_display) = {
+        use esp_idf_svc::hal::gpio::AnyOutputPin;
+
+        let spi_bus = module::max7219::new_bus(
+            peripherals.spi2,
+            unsafe { board_config::output_pin(board.display_clk) },
+            unsafe { board_config::output_pin(board.max7219_din) },
+        )?;
+
+        (
+            init_display(
+                SevenSegmentDisplay::<AnyOutputPin, _>::new_max7219(
+                    spi_bus.clone(),
+                    date_display_dio,
+                ),
+                Subsystem::DateDisplay,
+            ),
+            init_display(
+                SevenSegmentDisplay::<AnyOutputPin, _>::new_max7219(
+                    spi_bus.clone(),
+                    year_display_dio,
+                ),
+                Subsystem::YearDisplay,
+            ),
+            init_display(
+                SevenSegmentDisplay::<AnyOutputPin, _>::new_max7219(spi_bus, hour_display_dio),
+                Subsystem::HourDisplay,
+            ),
+        )
+    };
+
+    let date_display =
+        date_display.inspect_err(|e| error::code::show_error(Subsystem::DateDisplay, e))?;
+    let year_display =
+        year_display.inspect_err(|e| error::code::show_error(Subsystem::YearDisplay, e))?;
+    let hour_display =
+        hour_display.inspect_err(|e| error::code::show_error(Subsystem::HourDisplay, e))?;
+
+    // Everything else drives the displays through the group
+    let display_group = DisplayGroup::new(date_display, year_display, hour_display);
+
+    // Draw the commands queued by the HTTP handlers and the SNTP callback, so they
+    // never wait for the displays
+    let queue_display_group = display_group.clone();
+    let queue_am_pm_indicator = am_pm_indicator.clone();
+    std::thread::Builder::new()
+        .stack_size(4096)
+        .spawn(move || {
+            module::display_queue::run_display_task(queue_display_group, queue_am_pm_indicator)
+        })?;
+
+    // Let the subsystems react to the events of the others, e.g. the display
+    // task rendering the time again once SNTP syncs
+    event::subscribe(health::on_event);
+    event::subscribe(module::display_queue::on_event);
+    event::subscribe(webhook::on_event);
+    event::subscribe(module::relay::on_event);
+    event::subscribe(audio::on_event);
+    event::subscribe(wifi::on_event);
+    event::subscribe(server::ntp::on_event);
+    event::subscribe(scheduler::on_event);
+    std::thread::Builder::new()
+        .stack_size(4096)
+        .spawn(event::run_event_bus)?;
+
+    // Send the webhook notifications without blocking the event bus, as the
+    // requests wait for the server
+    std::thread::Builder::new()
+        .stack_size(8192)
+        .spawn(webhook::run_webhooks)?;
+
+    let app_storage = AppStorage::new(nvs_default_partition.clone())
+        .inspect(|_| health::report_ok(Subsystem::Storage))
+        .inspect_err(|e| {
+            log::error!("Failed to initialize the storage: {e:#?}");
+            health::report_failed(Subsystem::Storage, e);
+            error::code::show_error(Subsystem::Storage, e);
+        })?;
+
+    // Apply the log levels set from the web portal, as early as possible
+    if let Some(log_levels) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_log_levels()
+        .unwrap_or(None)
+    {
+        log_levels.apply();
+        prefs::log_level::set_log_levels(log_levels);
+    }
+
+    // Read the webhook URL from NVS
+    if let Some(webhook_url) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_webhook_url()
+        .unwrap_or(None)
+    {
+        webhook::set_webhook_url(Some(webhook_url));
+    }
+
+    // Show a panic on the hour display, save it in NVS and restart, instead of
+    // leaving a dead thread behind
+    error::panic::install_panic_hook(app_storage.clone());
+
+    // Clear the crash count once the firmware runs stable. On its own task, as
+    // the uptime is counted from the boot, long before the scheduler starts
+    let crash_count_storage = app_storage.clone();
+    std::thread::Builder::new()
+        .stack_size(4096)
+        .spawn(move || health::safe_mode::run_stable_timer(crash_count_storage))?;
+
+    if let Some(last_panic) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_last_panic()
+        .unwrap_or(None)
+    {
+        log::warn!("Last restart after a panic: {last_panic}");
+        error::panic::set_last_panic(last_panic);
+    }
+
+    // Mount the SD card, if present, to import the provisioning settings and
+    // write the log files
+    #[cfg(feature = "sd-card")]
+    match config::sd_card::mount(
+        peripherals.spi3,
+        peripherals.pins.gpio18,
+        peripherals.pins.gpio23,
+        peripherals.pins.gpio25,
+        peripherals.pins.gpio4,
+    ) {
+        Ok(true) => {
+            config::sd_card::import_config(&app_storage)
+                .inspect_err(|e| {
+                    log::error!("Failed to import config from SD card: {e:#?}");
+                })
+                .ok();
+
+            std::thread::Builder::new()
+                .stack_size(4096)
+                .spawn(health::log_file::run_log_writer)?;
+        }
+        Ok(false) => health::log_file::disable(),
+        Err(e) => {
+            log::error!("Failed to mount the SD card: {e:#?}");
+            health::log_file::disable();
+        }
+    }
+
+    // Start the serial configuration protocol on the USB console
+    let mut serial_configurator = SerialConfigurator::new(
+        peripherals.uart0,
+        peripherals.pins.gpio1,
+        peripherals.pins.gpio3,
+        app_storage.clone(),
+    )?;
+
+    // Need lots of stack to parse JSON
+    std::thread::Builder::new()
+        .stack_size(8192)
+        .spawn(move || serial_configurator.run())?;
+
+    // Show the time saved before a power loss until SNTP syncs
+    power::power_loss::restore_state(&app_storage)
+        .inspect_err(|e| log::error!("Failed to restore the state saved on power loss: {e:?}"))
+        .ok();
+
+    // Save the volatile state as soon as the power supply reports a loss
+    #[cfg(feature = "power-loss")]
+    {
+        let power_loss_storage = app_storage.clone();
+        let power_good_pin = unsafe { board_config::input_pin(board.power_good) };
+
+        std::thread::Builder::new()
+            .stack_size(4096)
+            .spawn(move || {
+                power::power_loss::run_power_loss_monitor(power_good_pin, power_loss_storage)
+                    .inspect_err(|e| log::error!("Power loss monitor stopped: {e:?}"))
+            })?;
+    }
+
+    // Restart into WPS provisioning when the BOOT button is held
+    #[cfg(feature = "wps")]
+    {
+        let wps_storage = app_storage.clone();
+        let wps_button_pin = unsafe { board_config::input_pin(board.wps_button) };
+
+        std::thread::Builder::new()
+            .stack_size(4096)
+            .spawn(move || {
+                wifi::wps::run_wps_button(wps_button_pin, wps_storage)
+                    .inspect_err(|e| log::error!("WPS button stopped: {e:?}"))
+            })?;
+    }
+
+    // Read whether the displays are mounted upside down from NVS, before
+    // anything is drawn
+    if let Some(display_flip) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_display_flip()
+        .unwrap_or(None)
+    {
+        prefs::display_flip::set_display_flip(display_flip);
+    }
+
+    // Read the physical display showing each role from NVS, before anything is
+    // drawn
+    if let Some(display_roles) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_display_roles()
+        .unwrap_or(None)
+    {
+        prefs::display_roles::set_display_roles(display_roles);
+    }
+
+    // Read the settings stored together in NVS
+    let settings = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_settings()
+        .inspect_err(|e| log::error!("Failed to read the settings: {e}"))
+        .unwrap_or_default();
+
+    // Restore the displays right away after a watchdog or brown-out reset, or
+    // play the boot animation stored in NVS
+    if let Some(state) = power::resume::take_after_reset() {
+        log::info!("Resuming the displays after an unexpected reset");
+
+        let display_group = display_group.lock().unwrap();
+        display_group.set_brightness(state.brightness)?;
+        display_group.write_frames(state.frames)?;
+    } else {
+        let boot_animation = app_storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_maybe_boot_animation()
+            .unwrap_or(None)
+            .unwrap_or_default();
+        display_group
+            .lock()
+            .unwrap()
+            .play_boot_animation(boot_animation)
+            .inspect_err(|e| {
+                log::error!("Failed to play boot animation: {e:#?}");
+            })?;
+
+        if let Some(brightness) = settings.brightness() {
+            display_group.lock().unwrap().set_brightness(brightness)?;
+        }
+    }
+
+    // Keep the reason of the last disconnection up to date right away, as the
+    // connection attempts read it, and publish when the connection drops or
+    // comes back
+    let _wifi_subscription = sysloop.subscribe::<WifiEvent, _>(|wifi_event| match wifi_event {
+        WifiEvent::StaConnected(_) => {
+            wifi::set_disconnect_reason(None);
+            event::publish(event::Event::WifiConnected);
+        }
+        WifiEvent::StaDisconnected(disconnected) => {
+            let reason = wifi::DisconnectReason::from_code(disconnected.reason());
+            wifi::set_disconnect_reason(Some(reason));
+            event::publish(event::Event::WifiDisconnected(reason));
+        }
+        _ => {}
+    })?;
+
+    // Initialize the led strip, before the Wi-Fi so it can show the
+    // provisioning mode
+    let mut led_strip = module::led_strip::LedStrip::new(led_strip_rmt, led_strip_dio, 18)
+        .inspect_err(|e| {
+            log::error!("Failed to get led strip: {e:#?}");
+            health::report_failed(Subsystem::LedStrip, e);
+        })?;
+    led_strip.init().inspect_err(|e| {
+        log::error!("Failed to initialize led strip: {e:#?}");
+        health::report_failed(Subsystem::LedStrip, e);
+    })?;
+    let led_strip: SharedLedStrip = SharedLedStrip::new(Mutex::new(led_strip));
 
     let credentials = app_storage
         .lock()
-        .unwrap()
+        .unwrap_or_else(PoisonError::into_inner)
         .get_maybe_wifi_credentials()
         .unwrap();
 
-    let is_ap_mode: bool;
+    let is_provisioning_mode: bool;
+    let wps_requested = wifi::wps::take_wps_request(&app_storage);
 
-    // If no credentials are found, get the Access Point (AP) instance
-    let mut wifi = if credentials.is_none() {
-        is_ap_mode = true;
+    // If WPS was requested or no credentials are found, get the provisioning
+    // Wi-Fi instance
+    let mut wifi = if wps_requested {
+        is_provisioning_mode = true;
 
-        log::warn!("Credentials not found. Starting Wifi Access Point...");
+        log::warn!("WPS requested. Waiting for the router...");
+        display_group
+            .lock()
+            .unwrap()
+            .write_hour(DisplayMessage::Wps.as_bytes())?;
 
-        // Initialize the Wi-Fi Access Point
-        let mut wifi_ap = wifi::ap::get_ap(
+        // Initialize a Wi-Fi Station that receives the credentials via WPS
+        let mut wifi_station = wifi::smartconfig::get_smartconfig_station(
             peripherals.modem,
             sysloop.clone(),
             Some(nvs_default_partition),
-        )?;
+        )
+        .inspect_err(|e| error::code::show_error(Subsystem::Wifi, e))?;
+
+        wifi::wps::wait_for_wps_credentials(&mut wifi_station)?;
+
+        wifi_station
+    } else if credentials.is_none() {
+        is_provisioning_mode = true;
+
+        // Blink the LED strip while waiting for the credentials
+        let provisioning_led_strip = led_strip.clone();
+        std::thread::Builder::new()
+            .stack_size(4096)
+            .spawn(move || module::led_strip::run_provisioning_blink(provisioning_led_strip))?;
+
+        // Keep the failure that sent the device back to provisioning, if any,
+        // to explain it in the captive portal
+        wifi::set_disconnect_reason(
+            app_storage
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .get_maybe_wifi_failure()
+                .unwrap_or(None),
+        );
+
+        if wifi::use_smartconfig() {
+            log::warn!("Credentials not found. Starting SmartConfig...");
+
+            // Initialize a Wi-Fi Station that receives the credentials via SmartConfig
+            let mut wifi_station = wifi::smartconfig::get_smartconfig_station(
+                peripherals.modem,
+                sysloop.clone(),
+                Some(nvs_default_partition),
+            )
+            .inspect_err(|e| error::code::show_error(Subsystem::Wifi, e))?;
+
+            wifi::smartconfig::wait_for_smartconfig_credentials(&mut wifi_station)?;
 
-        // Starts the AP
-        wifi::ap::start_wifi_ap(&mut wifi_ap)?;
+            wifi_station
+        } else {
+            log::warn!("Credentials not found. Starting Wifi Access Point...");
 
-        wifi_ap
+            // Initialize the Wi-Fi Access Point
+            let mut wifi_ap = wifi::ap::get_ap(
+                peripherals.modem,
+                sysloop.clone(),
+                Some(nvs_default_partition),
+            )
+            .inspect_err(|e| error::code::show_error(Subsystem::Wifi, e))?;
+
+            // Starts the AP
+            wifi::ap::start_wifi_ap(&mut wifi_ap)
+                .inspect_err(|e| error::code::show_error(Subsystem::Wifi, e))?;
+
+            // Show what to connect to on the displays while waiting for the credentials
+            let setup_display_group = display_group.clone();
+            std::thread::Builder::new()
+                .stack_size(4096)
+                .spawn(move || wifi::ap::run_setup_hint(setup_display_group))?;
+
+            wifi_ap
+        }
     } else {
         // If credentials are found, start the Station mode to connect to a network
-        is_ap_mode = false;
+        is_provisioning_mode = false;
 
         let credentials = credentials.unwrap();
-        let ssid = credentials.ssid;
-        let password = credentials.password;
 
         log::info!("Credentials found. Starting Wifi Station...");
-        log::info!("Wi-Fi SSID: {ssid}");
-        log::info!("WIFI PASS: {password}");
+        log::info!("Wi-Fi SSID: {}", credentials.ssid);
+        log::info!("WIFI PASS: {}", credentials.password);
+
+        let static_ip = app_storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_maybe_static_ip()
+            .unwrap_or(None);
+        let hidden = app_storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_maybe_hidden_ssid()
+            .unwrap_or(None)
+            .unwrap_or(false);
+        let auth_mode = app_storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_maybe_auth_mode()
+            .unwrap_or(None)
+            .unwrap_or_default();
+        let enterprise = app_storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_maybe_enterprise_config()
+            .unwrap_or(None);
 
         // Initialize the Wi-Fi Station
         let mut wifi_station = wifi::station::get_station(
             peripherals.modem,
             sysloop.clone(),
             Some(nvs_default_partition),
-            ssid,
-            password,
-        )?;
+            credentials,
+            static_ip,
+            hidden,
+            auth_mode,
+            enterprise,
+        )
+        .inspect_err(|e| error::code::show_error(Subsystem::Wifi, e))?;
 
-        // Connect to the Wi-Fi network
-        wifi::station::connect_wifi_or_restart(&mut wifi_station, app_storage.clone())?;
+        // Apply the regulatory domain before connecting, so the channels of the region
+        // can be used
+        let country_code = app_storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_maybe_country_code()
+            .unwrap_or(None)
+            .unwrap_or(wifi::DEFAULT_COUNTRY_CODE.to_string());
+        wifi::set_country_code(&country_code)
+            .inspect_err(|e| log::error!("Failed to set the Wi-Fi country code: {e:?}"))
+            .ok();
+
+        // Connect to the Wi-Fi network, or go on offline if the network is unreachable
+        if wifi::station::connect_wifi(&mut wifi_station)
+            .inspect_err(|e| error::code::show_error(Subsystem::Wifi, e))?
+        {
+            health::report_ok(Subsystem::Wifi);
+        } else {
+            let reason = wifi::get_disconnect_reason().unwrap_or(wifi::DisconnectReason::Other);
+            health::report_degraded(
+                Subsystem::Wifi,
+                format!("Offline: {}", reason.description()),
+            );
+        }
 
         wifi_station
     };
 
     log::info!("Wi-Fi Config: {:?}", wifi.get_configuration().unwrap());
 
-    // If the device is in AP mode, start the captive portal to capture credentials
-    if is_ap_mode {
-        let ap_ip_address = Ipv4Addr::from_str(AP_IP_ADDRESS).expect("Error reading AP_IP_ADDRESS");
+    // If the device is in provisioning mode, wait for the credentials
+    if is_provisioning_mode {
+        // In AP mode, start the captive portal to capture credentials
+        if !wps_requested && !wifi::use_smartconfig() {
+            if !start_captive_portal(&mut wifi)? {
+                // Restart to retry the stored credentials, if any were set
+                // meanwhile, or to provision again
+                log::warn!("Provisioning timed out. Restarting...");
+                wifi.stop()?;
 
-        // Starts the DNS server for the Captive Portal
-        log::info!("Starting DNS Responder...");
-        let mut dns_responder =
-            DnsResponder::init(ap_ip_address).expect("Failed to initialize DNS Responder");
+                unsafe {
+                    esp_restart();
+                }
+            }
+        }
 
-        // Runs the DNS server on another thread and accepts the timeout error with
-        // .ok().
-        std::thread::spawn(move || loop {
-            dns_responder.handle_requests().ok();
-            std::thread::sleep(Duration::from_millis(100));
-        });
+        // If new credentials are received, store them in NVS
+        if let Some(credentials) =
+            state::read_settings(|settings| settings.wifi_credentials.clone())
+        {
+            let mut storage = app_storage.lock().unwrap_or_else(PoisonError::into_inner);
+            storage.save_wifi_credentials(credentials.ssid, credentials.password);
+            storage.save_hidden_ssid(*wifi::HIDDEN_SSID.lock().unwrap())?;
+            storage.save_auth_mode(*wifi::AUTH_MODE.lock().unwrap())?;
 
-        // Starts the server with the Wi-Fi configuration handler and the captive portal
-        // redirection handlers
-        server::captive_portal::start_captive_portal()?;
+            match wifi::ENTERPRISE_CONFIG.lock().unwrap().clone() {
+                Some(enterprise) => storage.save_enterprise_config(enterprise)?,
+                None => storage.delete_enterprise_config()?,
+            }
+            storage.delete_wifi_failure()?;
+        }
 
-        // If new credentials are received, store them in NVS
-        if let Some(credentials) = wifi::WIFI_CREDENTIALS.lock().unwrap().clone() {
+        // If a static IP configuration was received, store it in NVS as well
+        if let Some(static_ip) = wifi::STATIC_IP_CONFIG.lock().unwrap().clone() {
             app_storage
                 .lock()
-                .unwrap()
-                .save_wifi_credentials(credentials.ssid, credentials.password);
+                .unwrap_or_else(PoisonError::into_inner)
+                .save_static_ip(static_ip)?;
         }
 
-        // Stop the AP Wi-Fi interface
+        // Stop the provisioning Wi-Fi interface
         wifi.stop()?;
 
         // Restart the device after the configuration
@@ -145,93 +704,409 @@ fn main() -> Result<(), error::AppError> {
     }
 
     // Initialize mDNS
+    let device_info = server::discovery::DeviceInfo::new(wifi.wifi().sta_netif().get_mac()?);
     let mut mdns = esp_idf_svc::mdns::EspMdns::take()?;
-    mdns.set_hostname("bttf")?;
-    mdns.set_instance_name("bttf")?;
-    mdns.add_service(None, "_http", "_tcp", 80, &[])?;
+    server::discovery::register_mdns(&mut mdns, &device_info)?;
 
-    // Initialize AM/PM leds
-    let am_pm_indicator = module::led::AmPmIndicator::new(am_led_pin, pm_led_pin)?;
-    am_pm_indicator.lock().unwrap().clear()?;
+    // Answer the discovery broadcasts, for networks where mDNS is filtered
+    std::thread::Builder::new()
+        .stack_size(4096)
+        .spawn(move || server::discovery::run_discovery_responder(device_info))?;
 
-    // Initialize the day/month display
-    let date_display = module::display::SevenSegmentDisplay::new(
-        unsafe { display_clk.clone_unchecked() },
-        date_display_dio,
-    )
-    .inspect_err(|e| {
-        log::error!("Failed to get date display: {e:#?}");
-    })?;
-    date_display.lock().unwrap().init().inspect_err(|e| {
-        log::error!("Failed to initialize date display: {e:#?}");
-    })?;
+    // Serve the time to the LAN once synced, if enabled
+    std::thread::Builder::new()
+        .stack_size(4096)
+        .spawn(server::ntp::run_ntp_server)?;
 
-    // Initialize the year display
-    let year_display = module::display::SevenSegmentDisplay::new(
-        unsafe { display_clk.clone_unchecked() },
-        year_display_dio,
-    )
-    .inspect_err(|e| {
-        log::error!("Failed to get year display: {e:#?}");
-    })?;
-    year_display.lock().unwrap().init().inspect_err(|e| {
-        log::error!("Failed to initialize year display: {e:#?}");
-    })?;
+    // Show the IP of the clock once connected, so the Web portal can be reached
+    // without checking the router. Otherwise, show why the clock is offline, and go
+    // back to provisioning only when the network rejected the password
+    if health::get_state(Subsystem::Wifi) == Some(HealthState::Ok) {
+        let ip = wifi::station::get_station_ip(&wifi)?;
+        log::info!("Web portal available at http://{ip}");
 
-    // Initialize the hour/min display
-    let hour_display = module::display::SevenSegmentDisplay::new(
-        unsafe { display_clk.clone_unchecked() },
-        hour_display_dio,
-    )
-    .inspect_err(|e| {
-        log::error!("Failed to get hour display: {e:#?}");
-    })?;
-    hour_display.lock().unwrap().init().inspect_err(|e| {
-        log::error!("Failed to initialize hour display: {e:#?}");
-    })?;
+        display_group
+            .lock()
+            .unwrap()
+            .scroll_text(&wifi::station::ip_display_text(ip))
+            .inspect_err(|e| log::error!("Failed to show the IP address: {e:#?}"))
+            .ok();
+    } else {
+        if let Some(reason) = wifi::get_disconnect_reason() {
+            display_group
+                .lock()
+                .unwrap()
+                .scroll_text(reason.display_text())
+                .inspect_err(|e| log::error!("Failed to show the Wi-Fi failure: {e:#?}"))
+                .ok();
 
-    let display_group = SharedDisplayGroup::new(Mutex::new(DisplayGroup {
-        date: date_display.clone(),
-        year: year_display.clone(),
-        hour: hour_display.clone(),
-    }));
+            if reason == wifi::DisconnectReason::AuthFailed {
+                log::warn!("Wrong Wi-Fi password. Restarting into provisioning...");
 
-    // Initialize the led strip
-    let mut led_strip = module::led_strip::LedStrip::new(led_strip_rmt, led_strip_dio, 18)
-        .inspect_err(|e| {
-            log::error!("Failed to get led strip: {e:#?}");
-        })?;
-    led_strip.init()?;
+                let mut storage = app_storage.lock().unwrap_or_else(PoisonError::into_inner);
+                storage.save_wifi_failure(reason)?;
+                storage.delete_wifi_credentials()?;
+
+                unsafe {
+                    esp_restart();
+                }
+            }
+        }
+    }
 
     // Initialize SNTP
     let sntp = time::sntp::get_sntp().inspect_err(|e| {
         log::error!("Failed to get SNTP: {e:#?}");
+        health::report_failed(Subsystem::Sntp, e);
+        error::code::show_error(Subsystem::Sntp, e);
     })?;
     time::sntp::init_sntp(&sntp).inspect_err(|e| {
         log::error!("Failed to initialize SNTP: {e:#?}");
+        health::report_failed(Subsystem::Sntp, e);
+        error::code::show_error(Subsystem::Sntp, e);
     })?;
+    health::report_ok(Subsystem::Sntp);
 
-    // Read timezone from NVS
-    let timezone = app_storage.lock().unwrap().get_maybe_timezone();
+    // Apply the timezone, the hour format, what the year display shows and
+    // the auto-dimming from the settings
+    time::tz::set_timezone(
+        settings
+            .timezone()
+            .unwrap_or(env!("DEFAULT_TIMEZONE"))
+            .to_string(),
+    );
+    prefs::hour_format::set_hour_format(settings.hour_format().unwrap_or_default());
 
-    if let Some(tz) = timezone.unwrap_or(None) {
-        time::tz::set_timezone(tz);
-    } else {
-        time::tz::set_timezone(env!("DEFAULT_TIMEZONE").to_string());
+    if let Some(seconds_mode) = settings.seconds_mode() {
+        prefs::seconds_mode::set_seconds_mode(seconds_mode);
     }
 
-    // Read hour_format from NVS
-    let hour_format = app_storage.lock().unwrap().get_maybe_hour_format();
+    if let Some(auto_dim_config) = settings.auto_dim() {
+        power::auto_dim::set_auto_dim_config(auto_dim_config);
+    }
 
-    if let Some(hour_format) = hour_format.unwrap_or(Some(HourFormat::default())) {
-        prefs::hour_format::set_hour_format(hour_format);
+    // Read date_format from NVS
+    if let Some(date_format) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_date_format()
+        .unwrap_or(None)
+    {
+        prefs::date_format::set_date_format(date_format);
     }
 
-    // Set the LED strip theme to default
-    led_strip.apply_theme(&Theme::default())?;
+    // Read the leading zero setting from NVS
+    if let Some(leading_zero) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_leading_zero()
+        .unwrap_or(None)
+    {
+        prefs::leading_zero::set_leading_zero(leading_zero);
+    }
+
+    // Read the custom messages of the display rotation from NVS
+    if let Some(custom_messages) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_custom_messages()
+        .unwrap_or(None)
+    {
+        prefs::custom_messages::set_custom_messages(custom_messages);
+    }
+
+    // Read the "days since" trackers of the display rotation from NVS
+    if let Some(trackers) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_trackers()
+        .unwrap_or(None)
+    {
+        prefs::trackers::set_trackers(trackers);
+    }
+
+    // Read the rules that color the LED strip zones from NVS
+    if let Some(rules) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_rules()
+        .unwrap_or(None)
+    {
+        rules::set_rules(rules);
+    }
+
+    // Read the macros run by the rules from NVS
+    if let Some(macros) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_macros()
+        .unwrap_or(None)
+    {
+        rules::macros::set_macros(macros);
+    }
+
+    // Read the visual hour strike setting from NVS
+    if let Some(hour_strike) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_hour_strike()
+        .unwrap_or(None)
+    {
+        prefs::hour_strike::set_hour_strike(hour_strike);
+    }
+
+    // Read the colon blink setting from NVS
+    if let Some(colon_blink) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_colon_blink()
+        .unwrap_or(None)
+    {
+        prefs::colon_blink::set_colon_blink(colon_blink);
+    }
+
+    // Read the temperature display setting from NVS
+    if let Some(temperature_display) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_temperature_display()
+        .unwrap_or(None)
+    {
+        prefs::temperature_display::set_temperature_display(temperature_display);
+    }
+
+    // Read the humidity display setting from NVS
+    if let Some(humidity_display) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_humidity_display()
+        .unwrap_or(None)
+    {
+        prefs::humidity_display::set_humidity_display(humidity_display);
+    }
+
+    // Read how long each view of the date display is shown from NVS
+    if let Some(alternate_interval) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_alternate_interval()
+        .unwrap_or(None)
+    {
+        prefs::alternate_interval::set_alternate_interval(alternate_interval);
+    }
+
+    // Read how long the clock waits without motion before it is blanked at night
+    // from NVS
+    if let Some(motion_timeout) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_motion_timeout()
+        .unwrap_or(None)
+    {
+        prefs::motion_timeout::set_motion_timeout(motion_timeout);
+    }
+
+    // Read the low-power display mode from NVS
+    if let Some(low_power) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_low_power()
+        .unwrap_or(None)
+    {
+        prefs::low_power::set_low_power(low_power);
+    }
+
+    // Read the power-save mode from NVS
+    if let Some(power_save) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_power_save()
+        .unwrap_or(None)
+    {
+        prefs::power_save::set_power_save(power_save);
+
+        if power_save {
+            if let Err(e) = power::sleep::apply_power_save(true) {
+                log::error!("Failed to enable the power-save mode: {e:?}");
+            }
+        }
+    }
+
+    // Read the Wi-Fi power save from NVS. It is applied again once the station
+    // connects, as it may not be started yet
+    if let Some(wifi_power_save) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_wifi_power_save()
+        .unwrap_or(None)
+    {
+        prefs::wifi_power_save::set_wifi_power_save(wifi_power_save);
+
+        if let Err(e) = wifi::set_power_save(wifi_power_save) {
+            log::warn!("Failed to set the Wi-Fi power save: {e:?}");
+        }
+    }
+
+    // Read whether the LAN time server is enabled from NVS
+    if let Some(ntp_server) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_ntp_server()
+        .unwrap_or(None)
+    {
+        prefs::ntp_server::set_ntp_server(ntp_server);
+    }
+
+    // Read the digit transition of each display from NVS
+    if let Some(transitions) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_digit_transitions()
+        .unwrap_or(None)
+    {
+        prefs::digit_transition::set_digit_transitions(transitions);
+        display_group.lock().unwrap().set_transitions(transitions);
+    }
+
+    // Read how the weekday is shown on the date display from NVS
+    if let Some(weekday_mode) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_weekday_mode()
+        .unwrap_or(None)
+    {
+        prefs::weekday_mode::set_weekday_mode(weekday_mode);
+    }
+
+    // Read what a tap on the touch pad does from NVS
+    if let Some(touch_action) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_touch_action()
+        .unwrap_or(None)
+    {
+        prefs::touch_action::set_touch_action(touch_action);
+    }
+
+    // Read the volume of the melodies from NVS
+    if let Some(volume) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_volume()
+        .unwrap_or(None)
+    {
+        prefs::volume::set_volume(volume);
+    }
+
+    // Read the relay from NVS and take its pin, unless the board uses it
+    if let Some(relay_config) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_relay()
+        .unwrap_or(None)
+    {
+        module::relay::set_relay_config(relay_config);
+
+        if relay_config.pin.is_some() {
+            if relay_config.is_valid(&board) {
+                module::relay::init_relay(relay_config)
+                    .inspect_err(|e| log::error!("Failed to initialize the relay: {e:?}"))
+                    .ok();
+            } else {
+                log::error!("Relay pin clashes with the board, ignoring it: {relay_config:?}");
+            }
+        }
+    }
+
+    // Read the DST pre-announcement setting from NVS
+    if let Some(dst_notice) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_dst_notice()
+        .unwrap_or(None)
+    {
+        prefs::dst_notice::set_dst_notice(dst_notice);
+    }
+
+    // Read the time of the daily summary from NVS
+    if let Some(daily_summary) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_daily_summary()
+        .unwrap_or(None)
+    {
+        prefs::daily_summary::set_daily_summary(Some(daily_summary));
+    }
+
+    // Read the wake light from NVS
+    if let Some(wake_light) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_wake_light()
+        .unwrap_or(None)
+    {
+        prefs::wake_light::set_wake_light(Some(wake_light));
+    }
+
+    // Read the target date of the countdown from NVS
+    if let Some(countdown) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_countdown()
+        .unwrap_or(None)
+    {
+        prefs::countdown::set_countdown(Some(countdown));
+    }
+
+    // Read the phone watched by the presence service from NVS
+    if let Some(presence_config) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_presence()
+        .unwrap_or(None)
+    {
+        presence::set_presence_config(presence_config);
+    }
+
+    // Read the location of the weather client from NVS
+    if let Some(weather_config) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_weather()
+        .unwrap_or(None)
+    {
+        weather::set_weather_config(weather_config);
+    }
+
+    // Read the astronomical events setting from NVS
+    if let Some(astro_events) = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_astro_events()
+        .unwrap_or(None)
+    {
+        prefs::astro_events::set_astro_events(astro_events);
+    }
+
+    // Read the boot effect from NVS and apply it with the theme to the LED
+    // strip
+    let theme = settings.theme().unwrap_or_default();
+    let boot_led_effect = app_storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_boot_led_effect()
+        .unwrap_or(None)
+        .unwrap_or_default();
+    led_strip
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .play_boot_effect(boot_led_effect, &theme)
+        .inspect(|_| health::report_ok(Subsystem::LedStrip))
+        .inspect_err(|e| health::report_degraded(Subsystem::LedStrip, e))?;
 
     // Start the Web portal HTTP server
-    let mut web_portal = WebPortal::new()?;
+    let mut web_portal =
+        WebPortal::new().inspect_err(|e| health::report_failed(Subsystem::WebPortal, e))?;
 
     let wifi_ssid = wifi
         .wifi()
@@ -242,54 +1117,450 @@ fn main() -> Result<(), error::AppError> {
         .ssid
         .to_string();
 
+    // Monitor the backup battery, dimming the displays when it runs low
+    #[cfg(feature = "battery")]
+    let battery = power::battery::BatteryMonitor::new(peripherals.adc1, peripherals.pins.gpio34)?;
+
+    // Read the room temperature, humidity and pressure from the BME280 on the I2C
+    // bus
+    #[cfg(feature = "bme280")]
+    let bme280 =
+        module::sensor::bme280::Bme280::new(i2c_bus.clone(), module::sensor::bme280::ADDRESS)
+            .inspect_err(|e| log::error!("Failed to initialize the BME280: {e:?}"))
+            .ok();
+
+    // Read the room temperature from the DHT22
+    #[cfg(feature = "dht22")]
+    let dht22 = module::sensor::dht::Dht22::new(unsafe { board_config::io_pin(board.dht22) })?;
+
+    // Switch the night mode or play the time travel animation with a tap on the
+    // touch pad
+    #[cfg(feature = "touch")]
+    match module::touch::TouchPad::new(peripherals.pins.gpio15) {
+        Ok(touch_pad) => {
+            let touch_led_strip = led_strip.clone();
+            let touch_storage = app_storage.clone();
+
+            std::thread::Builder::new()
+                .stack_size(4096)
+                .spawn(move || {
+                    module::touch::run_touch_pad(touch_pad, touch_led_strip, touch_storage)
+                        .inspect_err(|e| log::error!("Touch pad stopped: {e:?}"))
+                })?;
+        }
+        Err(e) => log::error!("Failed to initialize the touch pad: {e:?}"),
+    }
+
+    // Blank the displays and the LED strip at night while no one is in the room
+    #[cfg(feature = "pir")]
+    {
+        let sensor = presence::motion::MotionSensor::new(peripherals.pins.gpio36)?;
+        let motion_led_strip = led_strip.clone();
+        let motion_storage = app_storage.clone();
+
+        std::thread::Builder::new()
+            .stack_size(4096)
+            .spawn(move || {
+                presence::motion::run_motion_sensor(sensor, motion_led_strip, motion_storage)
+            })?;
+    }
+
+    // Play the alarm and the theme through the DAC
+    #[cfg(feature = "audio")]
+    {
+        let dac = audio::dac::Dac::new(peripherals.pins.gpio25);
+
+        std::thread::Builder::new()
+            .stack_size(4096)
+            .spawn(move || audio::dac::run_audio_task(dac))?;
+    }
+
+    let snapshot_storage = app_storage.clone();
+    let live_status_storage = app_storage.clone();
+    let message_display_group = display_group.clone();
+    let stopwatch_display_group = display_group.clone();
+    let stopwatch_am_pm_indicator = am_pm_indicator.clone();
+    let overlay_display_group = display_group.clone();
+    let overlay_am_pm_indicator = am_pm_indicator.clone();
+    let strike_led_strip = led_strip.clone();
+    let rules_led_strip = led_strip.clone();
+    let rules_storage = app_storage.clone();
+    let strike_storage = app_storage.clone();
+    let presence_led_strip = led_strip.clone();
+    let presence_storage = app_storage.clone();
+    let wake_light_led_strip = led_strip.clone();
+    let auto_dim_led_strip = led_strip.clone();
+    let auto_dim_storage = app_storage.clone();
+    let wake_light_storage = app_storage.clone();
+
     // Define HTTP routes
-    web_portal.create_routes(
-        display_group,
-        am_pm_indicator.clone(),
-        led_strip,
-        app_storage,
-        sntp,
-        wifi_ssid,
-    )?;
+    let state = state::AppState::new(display_group, led_strip, app_storage);
+    web_portal
+        .create_routes(state, sntp, wifi_ssid)
+        .inspect(|_| health::report_ok(Subsystem::WebPortal))
+        .inspect_err(|e| health::report_failed(Subsystem::WebPortal, e))?;
+
+    // Tick the stopwatch on the hour display while it is active
+    std::thread::Builder::new()
+        .stack_size(4096)
+        .spawn(move || {
+            module::display::run_stopwatch(stopwatch_display_group, stopwatch_am_pm_indicator)
+        })?;
+
+    // Start, stop and reset the stopwatch with a button
+    #[cfg(feature = "stopwatch-button")]
+    {
+        let stopwatch_button_pin = unsafe { board_config::input_pin(board.stopwatch_button) };
+
+        std::thread::Builder::new()
+            .stack_size(4096)
+            .spawn(move || {
+                time::stopwatch::run_stopwatch_button(stopwatch_button_pin)
+                    .inspect_err(|e| log::error!("Stopwatch button stopped: {e:?}"))
+            })?;
+    }
+
+    // Show the messages sent to /display_message, rendering the time again after
+    // them
+    std::thread::Builder::new()
+        .stack_size(4096)
+        .spawn(move || {
+            module::display::run_display_messages(overlay_display_group, overlay_am_pm_indicator)
+        })?;
+
+    // Run the periodic jobs from a single task, one after the other, instead of
+    // a thread per feature. Its stack is sized for the HTTP requests of the
+    // weather and the snapshot jobs. The tasks spawned above either block on a
+    // queue or a socket, poll an input faster than every second, or start
+    // before the scheduler
+    let mut scheduler = scheduler::Scheduler::new();
+
+    // Color the LED strip zones with the rules that match, every minute
+    scheduler.add(
+        "rules",
+        Schedule::EveryMinute,
+        rules::rules_job(rules_led_strip, rules_storage),
+    );
+
+    // Play a sunrise on the LED strip before the wake time, if one is set
+    scheduler.add(
+        "wake light",
+        Schedule::Every(module::led_strip::SUNRISE_STEP_INTERVAL),
+        module::led_strip::wake_light_job(wake_light_led_strip, wake_light_storage),
+    );
+
+    // Dim the displays and the LED strip between sunset and sunrise, if a
+    // location is configured
+    scheduler.add(
+        "auto-dim",
+        Schedule::EveryMinute,
+        power::auto_dim::auto_dim_job(auto_dim_led_strip, auto_dim_storage),
+    );
+
+    // Fetch the outdoor temperature, if a weather location is configured
+    scheduler.add(
+        "weather",
+        Schedule::Every(weather::CHECK_INTERVAL),
+        weather::weather_job(),
+    );
+
+    // Send the nightly settings snapshot, if a snapshot URL is configured
+    scheduler.add(
+        "snapshot",
+        Schedule::Daily(config::snapshot::SNAPSHOT_HOUR),
+        move || config::snapshot::send_nightly_snapshot(&snapshot_storage),
+    );
+
+    // Switch the relay off once the duration set by the alarm or /gpio elapsed
+    scheduler.add(
+        "relay",
+        Schedule::Every(module::relay::CHECK_INTERVAL),
+        module::relay::relay_job(),
+    );
+
+    // Sample the heap and the task stacks for /api/diagnostics
+    scheduler.add(
+        "diagnostics",
+        Schedule::Every(health::diagnostics::SAMPLE_INTERVAL),
+        health::diagnostics::diagnostics_job(),
+    );
+
+    // Reconnect whenever the station is offline
+    scheduler.add(
+        "wifi reconnect",
+        Schedule::Every(wifi::station::RECONNECT_INTERVAL),
+        wifi::station::reconnect_job(),
+    );
+
+    // Dim the displays while the backup battery is low
+    #[cfg(feature = "battery")]
+    scheduler.add(
+        "battery",
+        Schedule::Every(power::battery::READ_INTERVAL),
+        power::battery::battery_job(battery),
+    );
+
+    // Read the room temperature, humidity and pressure from the BME280
+    #[cfg(feature = "bme280")]
+    if let Some(sensor) = bme280 {
+        scheduler.add(
+            "bme280",
+            Schedule::Every(module::sensor::bme280::READ_INTERVAL),
+            module::sensor::bme280::bme280_job(sensor),
+        );
+    }
+
+    // Read the room temperature from the DHT22
+    #[cfg(feature = "dht22")]
+    scheduler.add(
+        "dht22",
+        Schedule::Every(module::sensor::dht::READ_INTERVAL),
+        module::sensor::dht::dht_job(dht22),
+    );
+
+    // Enable the away mode while the phone of the presence service is absent
+    scheduler.add(
+        "presence",
+        Schedule::Every(presence::PING_INTERVAL),
+        presence::presence_job(presence_led_strip, presence_storage),
+    );
+
+    // Push the clock state to the Web portal clients when it changes
+    scheduler.add(
+        "live status",
+        Schedule::Every(server::live_status::POLL_INTERVAL),
+        server::live_status::live_status_job(live_status_storage),
+    );
+
+    // Validate a firmware booting for the first time after an update, or roll
+    // it back
+    scheduler.add(
+        "first boot check",
+        Schedule::Every(ota::CHECK_INTERVAL),
+        ota::first_boot_check_job(),
+    );
+
+    std::thread::Builder::new()
+        .stack_size(8192)
+        .spawn(move || scheduler.run())?;
+
+    // Queue the updates of the displays every second from a task of their own,
+    // so they never wait for the HTTP requests or the pings of the jobs above.
+    // They are drawn by the display task, which alone locks the displays
+    let mut display_scheduler = scheduler::Scheduler::new();
+
+    // Alternate the date display with the weekday and the room temperature, when
+    // enabled
+    display_scheduler.add(
+        "date alternation",
+        Schedule::EverySecond,
+        module::display::date_alternation_job(),
+    );
+
+    // Tick the year display every second, when it shows the seconds
+    display_scheduler.add(
+        "seconds display",
+        Schedule::EverySecond,
+        module::display::seconds_display_job(),
+    );
+
+    // Tick the seconds of the 6-digit hour display every second
+    #[cfg(feature = "six-digit-hour")]
+    display_scheduler.add(
+        "hour seconds",
+        Schedule::EverySecond,
+        module::display::hour_seconds_job(),
+    );
+
+    // Blink the colon of the hour display as a heartbeat, when enabled. Added
+    // last, as it waits for the colon to turn off
+    display_scheduler.add(
+        "colon blink",
+        Schedule::EverySecond,
+        module::display::colon_blink_job(),
+    );
+
+    std::thread::Builder::new()
+        .stack_size(4096)
+        .spawn(move || display_scheduler.run())?;
+
+    // Watch the HTTP server, which can't feed the task watchdog itself. On its own
+    // task, as the watchdog is subscribed per task and a slow job of the
+    // scheduler would be taken for a stuck server
+    std::thread::Builder::new()
+        .stack_size(8192)
+        .spawn(health::watchdog::run_http_watchdog)?;
+
+    // Everything is up, e.g. for the boot webhook
+    event::publish(event::Event::Booted);
 
     // Create a thread for updating the time in display
-    std::thread::spawn(move || loop {
-        let wait_time = time::calculate_time_until_next_minute();
+    std::thread::spawn(move || {
+        let watchdog = health::watchdog::watch_current_task()
+            .inspect_err(|e| log::error!("Failed to watch the update loop: {e:?}"))
+            .ok();
 
-        let hour_format = get_hour_format();
+        loop {
+            if let Some(watchdog) = &watchdog {
+                watchdog.feed();
+            }
 
-        date_display
-            .lock()
-            .unwrap()
-            .update_display_date()
-            .inspect_err(|e| {
-                log::error!("Failed to update date display: {e:#?}");
-            })
-            .unwrap();
+            let hour_format = get_hour_format();
 
-        year_display
-            .lock()
-            .unwrap()
-            .update_display_year()
-            .inspect_err(|e| {
-                log::error!("Failed to update year display: {e:#?}");
-            })
-            .unwrap();
+            // Leave a message sent to /display_message on the displays, it renders the
+            // time again once it is over
+            if prefs::display_message::is_message_shown() {
+                let wait_time = time::calculate_time_until_next_minute();
+                FreeRtos::delay_ms(wait_time.as_millis() as u32);
+                continue;
+            }
 
-        hour_display
-            .lock()
-            .unwrap()
-            .update_display_hour(am_pm_indicator.clone(), hour_format)
-            .inspect_err(|e| {
-                log::error!("Failed to update hour/min display: {e:#?}");
-            })
-            .unwrap();
+            // Keep the last frames while the timezone is unknown, e.g. a corrupted
+            // NVS value, until a valid one is set from the web portal
+            let (minute_of_day, today) = match (time::get_minute_of_day(), time::get_local_date()) {
+                (Ok(minute_of_day), Ok(today)) => (minute_of_day, today),
+                (Err(e), _) | (_, Err(e)) => {
+                    log::error!("Failed to read the local time: {e}");
+                    let wait_time = time::calculate_time_until_next_minute();
+                    FreeRtos::delay_ms(wait_time.as_millis() as u32);
+                    continue;
+                }
+            };
+
+            // Retry a display that fails, e.g. a TM1637 missing an ACK, and only restart
+            // when it keeps failing
+            health::recovery::run_with_recovery(Subsystem::DateDisplay, || {
+                message_display_group.lock().unwrap().update_date()
+            });
+
+            health::recovery::run_with_recovery(Subsystem::YearDisplay, || {
+                message_display_group.lock().unwrap().update_year()
+            });
+
+            health::recovery::run_with_recovery(Subsystem::HourDisplay, || {
+                message_display_group
+                    .lock()
+                    .unwrap()
+                    .update_hour(am_pm_indicator.clone(), hour_format)
+            });
+
+            // Strike the hour with the LED strip at the top of every hour
+            if minute_of_day % 60 == 0
+                && prefs::hour_strike::is_hour_strike_enabled()
+                && !prefs::photo_mode::is_photo_mode()
+                && !presence::is_away()
+                && !presence::is_idle()
+                && !time::get_second_of_day().is_ok_and(prefs::wake_light::is_active)
+            {
+                let theme = strike_storage
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .get_maybe_theme()
+                    .unwrap_or(None)
+                    .unwrap_or_default();
+                let count = prefs::hour_strike::strike_count(minute_of_day / 60);
 
-        // Wait until the next minute
-        FreeRtos::delay_ms(wait_time.as_millis() as u32);
+                strike_led_strip
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .play_hour_strike(count, &theme)
+                    .inspect_err(|e| {
+                        log::error!("Failed to strike the hour: {e:#?}");
+                        health::report_degraded(Subsystem::LedStrip, e);
+                    })
+                    .ok();
+            }
+
+            // Scroll the daily summary, the DST reminder on the evening before a DST
+            // change, the low battery warning, the next astronomical event, and the
+            // next custom message and tracker of the rotation
+            let messages: Vec<String> = [
+                prefs::daily_summary::get_due_summary(minute_of_day, today),
+                time::dst::get_due_notice(),
+                time::astro::get_due_event(),
+                power::battery::get_due_warning(minute_of_day),
+                prefs::custom_messages::get_due_message(minute_of_day),
+                prefs::trackers::get_due_tracker(minute_of_day, today),
+            ]
+            .into_iter()
+            .flatten()
+            .collect();
+
+            if !prefs::photo_mode::is_photo_mode() {
+                for message in messages {
+                    module::display_queue::send(DisplayCommand::ShowMessage(message));
+                }
+            }
+
+            // Keep what is shown in RTC memory, to resume from it after a reset
+            {
+                let display_group = message_display_group.lock().unwrap();
+                power::resume::save(display_group.frames(), display_group.brightness());
+            }
+
+            // Wait until the next minute
+            let wait_time = time::calculate_time_until_next_minute();
+            FreeRtos::delay_ms(wait_time.as_millis() as u32);
+        }
     });
 
     loop {
         FreeRtos::delay_ms(1000);
     }
 }
+
+/// Initializes one of the seven-segment displays, whatever its controller, and
+/// reports its health.
+///
+/// A display that works is registered to show the error codes of the
+/// subsystems that fail, see [error::code::show_error].
+fn init_display<CLK, DIO>(
+    display: Result<SharedSevenSegmentDisplay<'static, CLK, DIO>, error::AppError>,
+    subsystem: Subsystem,
+) -> Result<SharedSevenSegmentDisplay<'static, CLK, DIO>, error::AppError>
+where
+    CLK: OutputPin,
+    DIO: IOPin,
+{
+    let display = display
+        .and_then(|display| {
+            display.lock().unwrap().init()?;
+            Ok(display)
+        })
+        .inspect_err(|e| {
+            log::error!("Failed to initialize {subsystem:?}: {e:#?}");
+            health::report_failed(subsystem, e);
+        })?;
+
+    health::report_ok(subsystem);
+    error::code::register_display(subsystem, display.clone());
+
+    Ok(display)
+}
+
+/// Starts the DNS responder and the captive portal, and blocks until the
+/// network accepted the Wi-Fi credentials received, or returns `false` once
+/// the [provisioning timeout](wifi::ap::provisioning_timeout) elapsed.
+fn start_captive_portal(
+    wifi: &mut BlockingWifi<EspWifi<'static>>,
+) -> Result<bool, error::AppError> {
+    let ap_ip_address = Ipv4Addr::from_str(AP_IP_ADDRESS).expect("Error reading AP_IP_ADDRESS");
+
+    // Starts the DNS server for the Captive Portal
+    log::info!("Starting DNS Responder...");
+    let mut dns_responder =
+        DnsResponder::init(ap_ip_address).expect("Failed to initialize DNS Responder");
+
+    // Runs the DNS server on another thread and accepts the timeout error with
+    // .ok(). The stack fits a whole DNS packet
+    std::thread::Builder::new()
+        .stack_size(4096)
+        .spawn(move || loop {
+            dns_responder.handle_requests().ok();
+            std::thread::sleep(Duration::from_millis(100));
+        })?;
+
+    // Starts the server with the Wi-Fi configuration handler and the captive portal
+    // redirection handlers
+    server::captive_portal::start_captive_portal(wifi)
+}
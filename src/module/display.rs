@@ -1,37 +1,633 @@
+use super::display_queue::{self, DisplayCommand};
 use super::led::SharedAmPmIndicator;
+#[cfg(feature = "max7219")]
+use super::max7219::{self, Max7219, SharedSpiBus};
+use super::sensor;
+#[cfg(feature = "ht16k33")]
+use super::{ht16k33::Ht16k33, i2c::SharedI2cBus};
 use crate::{
     error::AppError,
-    prefs::hour_format::HourFormat,
+    power,
+    prefs::{
+        self,
+        boot_animation::BootAnimation,
+        date_format::DateFormat,
+        digit_transition::{DigitTransition, DigitTransitions},
+        display_message::{self, DisplayMessageRequest},
+        display_roles::{self, DisplayPosition},
+        hour_format::HourFormat,
+        seconds_mode::SecondsMode,
+        weekday_mode::WeekdayMode,
+    },
     service::{display::SevenSegmentDisplayService, led::AmPmIndicatorService},
-    time,
-    util::{messages::DisplayMessage, DISPLAY_DIGIT},
+    time::{self, stopwatch},
+    util::{encode_char, encode_str, encode_two_digits, messages::DisplayMessage, DISPLAY_DIGIT},
+    weather,
 };
 use esp_idf_svc::hal::{
-    delay::Ets,
+    delay::{Ets, FreeRtos},
     gpio::{IOPin, InputOutput, Output, OutputPin, PinDriver},
 };
-use std::sync::{Arc, Mutex};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
 use tm1637::TM1637;
 
+/// On/off durations in milliseconds of the [BootAnimation::Flicker] frames.
+const FLICKER_TIMINGS: [(u32, u32); 8] = [
+    (40, 120),
+    (30, 60),
+    (80, 200),
+    (20, 40),
+    (120, 80),
+    (40, 30),
+    (200, 60),
+    (400, 0),
+];
+
+/// Delay in milliseconds between the [BootAnimation::Cascade] frames.
+const CASCADE_FRAME_DELAY: u32 = 30;
+
+/// Delay in milliseconds between the frames of a scrolling text.
+const SCROLL_FRAME_DELAY: u32 = 250;
+
+/// Whether the displays are blanked, see [set_blanked].
+static BLANKED: AtomicBool = AtomicBool::new(false);
+
+/// Retrieves whether the displays are blanked.
+pub fn is_blanked() -> bool {
+    BLANKED.load(Ordering::Relaxed)
+}
+
+/// Blanks the displays, or shows them again.
+///
+/// While blanked, the frames keep being updated but every segment is sent off,
+/// so the time is right as soon as they are shown again. It applies from the
+/// next write, so queue a
+/// [Redraw](super::display_queue::DisplayCommand::Redraw) right after.
+pub fn set_blanked(blanked: bool) {
+    BLANKED.store(blanked, Ordering::Relaxed);
+}
+
+/// What the date display shows while it alternates, see
+/// [date_alternation_job].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DateView {
+    Date,
+    Weekday,
+    Temperature,
+    Humidity,
+    Outdoor,
+    LowBattery,
+}
+
+/// How often [run_display_messages] checks for a new message, in
+/// milliseconds.
+const MESSAGE_POLL_INTERVAL: u32 = 200;
+
+/// How often [run_stopwatch] checks the stopwatch, in milliseconds, so it
+/// ticks within a fraction of a second of every second.
+const STOPWATCH_POLL_INTERVAL: u32 = 100;
+
+/// Time in milliseconds the colon stays on in every second while it blinks.
+const COLON_ON_TIME: u32 = 500;
+
+/// Number of digits of a [DisplayGroup], used as the width of scrolling texts.
+pub const GROUP_DIGITS: usize = 12;
+
+/// Delay in milliseconds between the frames of a [DigitTransition].
+const TRANSITION_FRAME_DELAY: u32 = 60;
+
+/// Segment patterns flashed by the [DigitTransition::Roll] transition, from
+/// the bottom of the digit to the top.
+const ROLL_FRAMES: [u8; 4] = [0b00001000, 0b01001000, 0b01000001, 0b00000001];
+
+/// Bit that lights the colon, kept on while the digits transition.
+const COLON_BIT: u8 = 0b10000000;
+
+/// Pairs of segments that swap places when a digit is rotated by 180°: `a`
+/// and `d`, `b` and `e`, `c` and `f`. The `g` segment stays in place.
+const FLIPPED_SEGMENTS: [(u8, u8); 3] = [(0, 3), (1, 4), (2, 5)];
+
+/// Addresses of the digits of the common 6-digit TM1637 modules, from left to
+/// right, since they are wired in two reversed groups of three.
+#[cfg(feature = "six-digit-hour")]
+const SIX_DIGIT_ADDRESSES: [u8; 6] = [2, 1, 0, 5, 4, 3];
+
+/// Rotates a frame by 180°, for displays mounted upside down.
+///
+/// The digits are reversed and their segments rotated, and the colon and the
+/// dots stay where they are, e.g. the colon stays on the second digit of a
+/// 4-digit frame, since it sits in the middle of the display.
+fn flip_frame(frame: &[u8]) -> Vec<u8> {
+    let mut flipped = vec![0u8; frame.len()];
+
+    for (digit, &segments) in flipped.iter_mut().rev().zip(frame) {
+        *digit = segments & 0b01000000;
+        for (a, b) in FLIPPED_SEGMENTS {
+            *digit |= (((segments >> a) & 1) << b) | (((segments >> b) & 1) << a);
+        }
+    }
+
+    for (digit, &segments) in flipped.iter_mut().zip(frame) {
+        *digit |= segments & COLON_BIT;
+    }
+
+    flipped
+}
+
+/// Represents the body of a brightness change request.
+///
+/// ## Example
+/// ```rust
+/// let brightness_request: BrightnessRequest = serde_json::from_str("{\"brightness\":5}").unwrap();
+/// ```
+#[derive(Clone, serde::Deserialize)]
+pub struct BrightnessRequest {
+    pub brightness: u8,
+}
+
 /// A thread-safe shared [`SevenSegmentDisplay`] using `Arc<Mutex<...>>`.
 pub type SharedSevenSegmentDisplay<'a, CLK, DIO> = Arc<Mutex<SevenSegmentDisplay<'a, CLK, DIO>>>;
 
+/// A TM1637 driven by the pins of a [SevenSegmentDisplay].
+type Tm1637<'a, CLK, DIO> =
+    TM1637<'a, PinDriver<'a, CLK, Output>, PinDriver<'a, DIO, InputOutput>, Ets>;
+
+/// The controller chip of a [SevenSegmentDisplay].
+///
+/// The `CLK` and `DIO` pins are only used by the TM1637, the other controllers
+/// keep the pin types of the group so every display has the same type.
+enum Driver<'a, CLK: OutputPin, DIO: IOPin> {
+    Tm1637(Tm1637<'a, CLK, DIO>),
+    #[cfg(feature = "six-digit-hour")]
+    Tm1637SixDigit(Tm1637<'a, CLK, DIO>),
+    #[cfg(feature = "ht16k33")]
+    Ht16k33(Ht16k33<'a>),
+    #[cfg(feature = "max7219")]
+    Max7219(Max7219<'a>),
+}
+
+impl<CLK, DIO> Driver<'_, CLK, DIO>
+where
+    CLK: OutputPin,
+    DIO: IOPin,
+{
+    fn init(&mut self) -> Result<(), AppError> {
+        match self {
+            Driver::Tm1637(tm1637) => Ok(tm1637.init()?),
+            #[cfg(feature = "six-digit-hour")]
+            Driver::Tm1637SixDigit(tm1637) => Ok(tm1637.init()?),
+            #[cfg(feature = "ht16k33")]
+            Driver::Ht16k33(ht16k33) => ht16k33.init(),
+            #[cfg(feature = "max7219")]
+            Driver::Max7219(max7219) => max7219.init(),
+        }
+    }
+
+    fn clear(&mut self) -> Result<(), AppError> {
+        match self {
+            Driver::Tm1637(tm1637) => Ok(tm1637.clear()?),
+            #[cfg(feature = "six-digit-hour")]
+            Driver::Tm1637SixDigit(_) => self.print_raw(0, &[0; 6]),
+            #[cfg(feature = "ht16k33")]
+            Driver::Ht16k33(ht16k33) => ht16k33.clear(),
+            #[cfg(feature = "max7219")]
+            Driver::Max7219(max7219) => max7219.clear(),
+        }
+    }
+
+    fn print_raw(&mut self, position: u8, segments: &[u8]) -> Result<(), AppError> {
+        match self {
+            Driver::Tm1637(tm1637) => Ok(tm1637.print_raw(position, segments)?),
+            #[cfg(feature = "six-digit-hour")]
+            Driver::Tm1637SixDigit(tm1637) => {
+                for (&address, &value) in SIX_DIGIT_ADDRESSES
+                    .iter()
+                    .skip(position as usize)
+                    .zip(segments)
+                {
+                    tm1637.print_raw(address, &[value])?;
+                }
+
+                Ok(())
+            }
+            #[cfg(feature = "ht16k33")]
+            Driver::Ht16k33(ht16k33) => ht16k33.print_raw(position, segments),
+            #[cfg(feature = "max7219")]
+            Driver::Max7219(max7219) => max7219.print_raw(position, segments),
+        }
+    }
+
+    /// Returns the number of digits of the display.
+    fn digits(&self) -> usize {
+        #[cfg(feature = "six-digit-hour")]
+        if let Driver::Tm1637SixDigit(_) = self {
+            return SIX_DIGIT_ADDRESSES.len();
+        }
+
+        #[cfg(feature = "max7219")]
+        if let Driver::Max7219(_) = self {
+            return max7219::DIGITS;
+        }
+
+        4
+    }
+
+    fn set_brightness(&mut self, level: u8) -> Result<(), AppError> {
+        match self {
+            Driver::Tm1637(tm1637) => Ok(tm1637.set_brightness(level)?),
+            #[cfg(feature = "six-digit-hour")]
+            Driver::Tm1637SixDigit(tm1637) => Ok(tm1637.set_brightness(level)?),
+            #[cfg(feature = "ht16k33")]
+            Driver::Ht16k33(ht16k33) => ht16k33.set_brightness(level),
+            #[cfg(feature = "max7219")]
+            Driver::Max7219(max7219) => max7219.set_brightness(level),
+        }
+    }
+}
+
 /// Centralizes the logic for controlling a seven-segment display.
 pub struct SevenSegmentDisplay<'a, CLK: OutputPin, DIO: IOPin> {
-    tm1637: TM1637<'a, PinDriver<'a, CLK, Output>, PinDriver<'a, DIO, InputOutput>, Ets>,
+    driver: Driver<'a, CLK, DIO>,
+    brightness: u8,
+    transition: DigitTransition,
+    last_frame: Vec<u8>,
 }
 
 /// Groups together the shared instances of the seven-segment displays.
+///
+/// The displays are only reachable through the group, so every drawing goes
+/// through the [DisplayRoles](display_roles::DisplayRoles).
 pub struct DisplayGroup<'a, CLK: OutputPin, DateDIO: IOPin, YearDIO: IOPin, HourDIO: IOPin> {
-    pub date: SharedSevenSegmentDisplay<'a, CLK, DateDIO>,
-    pub year: SharedSevenSegmentDisplay<'a, CLK, YearDIO>,
-    pub hour: SharedSevenSegmentDisplay<'a, CLK, HourDIO>,
+    date: SharedSevenSegmentDisplay<'a, CLK, DateDIO>,
+    year: SharedSevenSegmentDisplay<'a, CLK, YearDIO>,
+    hour: SharedSevenSegmentDisplay<'a, CLK, HourDIO>,
 }
 
 /// A type alias for a thread-safe, shared group of seven-segment displays.
 pub type SharedDisplayGroup<'a, CLK, DateDIO, YearDIO, HourDIO> =
     Arc<Mutex<DisplayGroup<'static, CLK, DateDIO, YearDIO, HourDIO>>>;
 
+/// Runs `$body` with `$display` bound to the locked display of a group at the
+/// given [DisplayPosition], since the displays are of different types.
+macro_rules! with_display {
+    ($group:expr, $position:expr, |$display:ident| $body:expr) => {
+        match $position {
+            DisplayPosition::First => {
+                #[allow(unused_mut)]
+                let mut $display = $group.date.lock().unwrap();
+                $body
+            }
+            DisplayPosition::Second => {
+                #[allow(unused_mut)]
+                let mut $display = $group.year.lock().unwrap();
+                $body
+            }
+            DisplayPosition::Third => {
+                #[allow(unused_mut)]
+                let mut $display = $group.hour.lock().unwrap();
+                $body
+            }
+        }
+    };
+}
+
+impl<CLK, DateDIO, YearDIO, HourDIO> DisplayGroup<'_, CLK, DateDIO, YearDIO, HourDIO>
+where
+    CLK: OutputPin,
+    DateDIO: IOPin,
+    YearDIO: IOPin,
+    HourDIO: IOPin,
+{
+    /// Creates a new [SharedDisplayGroup] from the displays wired to the date,
+    /// year and hour pins.
+    ///
+    /// ## Example
+    /// ```rust
+    /// let display_group = DisplayGroup::new(date_display, year_display, hour_display);
+    /// ```
+    pub fn new(
+        date: SharedSevenSegmentDisplay<'static, CLK, DateDIO>,
+        year: SharedSevenSegmentDisplay<'static, CLK, YearDIO>,
+        hour: SharedSevenSegmentDisplay<'static, CLK, HourDIO>,
+    ) -> SharedDisplayGroup<'static, CLK, DateDIO, YearDIO, HourDIO> {
+        Arc::new(Mutex::new(DisplayGroup { date, year, hour }))
+    }
+
+    /// Sets the brightness level (0-7) of every display in the group.
+    ///
+    /// The displays share the CLK line, so a short delay is added between
+    /// them.
+    pub fn set_brightness(&self, level: u8) -> Result<(), AppError> {
+        self.date.lock().unwrap().set_brightness(level)?;
+        FreeRtos::delay_ms(200);
+
+        self.year.lock().unwrap().set_brightness(level)?;
+        FreeRtos::delay_ms(200);
+
+        self.hour.lock().unwrap().set_brightness(level)?;
+
+        Ok(())
+    }
+
+    /// Returns the current brightness level of the group, as reported by the
+    /// hour display.
+    pub fn brightness(&self) -> u8 {
+        self.hour.lock().unwrap().brightness()
+    }
+
+    /// Writes the same 4-byte message to every display in the group.
+    pub fn write_all(&self, message: [u8; 4]) -> Result<(), AppError> {
+        self.date.lock().unwrap().write(&message)?;
+        self.year.lock().unwrap().write(&message)?;
+        self.hour.lock().unwrap().write(&message)?;
+
+        Ok(())
+    }
+
+    /// Plays a boot animation on every display in the group.
+    ///
+    /// Every animation except [BootAnimation::Blank] settles on the "init"
+    /// message, which stays until the time is shown.
+    ///
+    /// ## Example
+    /// ```rust
+    /// display_group
+    ///     .lock()
+    ///     .unwrap()
+    ///     .play_boot_animation(BootAnimation::Cascade)?;
+    /// ```
+    pub fn play_boot_animation(&self, animation: BootAnimation) -> Result<(), AppError> {
+        match animation {
+            BootAnimation::Init => {}
+            BootAnimation::Blank => return self.write_all([0; 4]),
+            BootAnimation::Cascade => {
+                let mut frame = [0u8; 4];
+
+                for (digit, segment) in (0..4).flat_map(|digit| (0..7).map(move |s| (digit, s))) {
+                    frame[digit] |= 1 << segment;
+                    self.write_all(frame)?;
+                    FreeRtos::delay_ms(CASCADE_FRAME_DELAY);
+                }
+            }
+            BootAnimation::Flicker => {
+                for (on, off) in FLICKER_TIMINGS {
+                    self.write_all([0xFF; 4])?;
+                    FreeRtos::delay_ms(on);
+                    self.write_all([0; 4])?;
+                    FreeRtos::delay_ms(off);
+                }
+            }
+        }
+
+        self.write_all(DisplayMessage::Init.as_bytes())
+    }
+
+    /// Scrolls a text once from right to left across the 12 digits of the
+    /// group, treating the date, year and hour displays as a single line.
+    ///
+    /// The displays are left blank at the end, so the caller is expected to
+    /// render the time again with [DisplayGroup::refresh].
+    ///
+    /// ## Example
+    /// ```rust
+    /// display_group
+    ///     .lock()
+    ///     .unwrap()
+    ///     .scroll_text("CLOCKS +1H TONIGHT")?;
+    /// ```
+    pub fn scroll_text(&self, text: &str) -> Result<(), AppError> {
+        let blank = std::iter::repeat(0).take(GROUP_DIGITS);
+        let segments: Vec<u8> = blank
+            .clone()
+            .chain(text.chars().map(encode_char))
+            .chain(blank)
+            .collect();
+
+        for frame in segments.windows(GROUP_DIGITS) {
+            self.write_line(frame)?;
+            FreeRtos::delay_ms(SCROLL_FRAME_DELAY);
+        }
+
+        Ok(())
+    }
+
+    /// Shows a text of up to 12 characters across the digits of the group,
+    /// centered, without scrolling. Longer texts are cut.
+    ///
+    /// ## Example
+    /// ```rust
+    /// display_group.lock().unwrap().show_text("DINNER")?;
+    /// ```
+    pub fn show_text(&self, text: &str) -> Result<(), AppError> {
+        let segments: Vec<u8> = text.chars().take(GROUP_DIGITS).map(encode_char).collect();
+        let padding = (GROUP_DIGITS - segments.len()) / 2;
+
+        let mut frame = [0u8; GROUP_DIGITS];
+        frame[padding..padding + segments.len()].copy_from_slice(&segments);
+
+        self.write_line(&frame)
+    }
+
+    /// Writes 12 digits to the date, year and hour displays, as a single
+    /// line.
+    fn write_line(&self, frame: &[u8]) -> Result<(), AppError> {
+        let mut digits = [[0u8; 4]; 3];
+        for (display, chunk) in digits.iter_mut().zip(frame.chunks_exact(4)) {
+            display.copy_from_slice(chunk);
+        }
+
+        self.write_frames(digits)
+    }
+
+    /// Drives every display in the group at its brightness level, or at the
+    /// level below it when `dimmed`, without changing the stored brightness.
+    ///
+    /// Used by the display task to strobe the displays, see
+    /// [run_display_task](crate::module::display_queue::run_display_task), so
+    /// unlike [DisplayGroup::set_brightness] it adds no delay.
+    pub fn set_duty(&self, dimmed: bool) -> Result<(), AppError> {
+        self.date.lock().unwrap().set_duty(dimmed)?;
+        self.year.lock().unwrap().set_duty(dimmed)?;
+        self.hour.lock().unwrap().set_duty(dimmed)?;
+
+        Ok(())
+    }
+
+    /// Returns the segments currently shown on the date, year and hour
+    /// displays, following the [DisplayRoles](display_roles::DisplayRoles).
+    pub fn frames(&self) -> [[u8; 4]; 3] {
+        let roles = display_roles::get_display_roles();
+
+        [roles.date, roles.year, roles.hour]
+            .map(|position| with_display!(self, position, |display| display.frame()))
+    }
+
+    /// Writes the given segments to the date, year and hour displays, e.g. the
+    /// ones returned by [DisplayGroup::frames].
+    pub fn write_frames(&self, frames: [[u8; 4]; 3]) -> Result<(), AppError> {
+        let roles = display_roles::get_display_roles();
+
+        for (position, frame) in [roles.date, roles.year, roles.hour].into_iter().zip(frames) {
+            with_display!(self, position, |display| display.write(&frame))?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the number of segments currently lit on the displays of the
+    /// group.
+    pub fn lit_segments(&self) -> u32 {
+        self.date.lock().unwrap().lit_segments()
+            + self.year.lock().unwrap().lit_segments()
+            + self.hour.lock().unwrap().lit_segments()
+    }
+
+    /// Sets the [DigitTransition] of each display in the group, following the
+    /// role it shows.
+    pub fn set_transitions(&self, transitions: DigitTransitions) {
+        let roles = display_roles::get_display_roles();
+
+        with_display!(self, roles.date, |display| display
+            .set_transition(transitions.date));
+        with_display!(self, roles.year, |display| display
+            .set_transition(transitions.year));
+        with_display!(self, roles.hour, |display| display
+            .set_transition(transitions.hour));
+    }
+
+    /// Renders the current date, or the weekday, on the display showing the
+    /// date, see [update_display_date](SevenSegmentDisplayService::update_display_date).
+    pub fn update_date(&self) -> Result<(), AppError> {
+        let position = display_roles::get_display_roles().date;
+        with_display!(self, position, |display| display.update_display_date())
+    }
+
+    /// Renders the current weekday on the display showing the date.
+    pub fn update_weekday(&self) -> Result<(), AppError> {
+        let position = display_roles::get_display_roles().date;
+        with_display!(self, position, |display| display.update_display_weekday())
+    }
+
+    /// Shows the low-battery glyph on the date display.
+    pub fn update_low_battery(&self) -> Result<(), AppError> {
+        let position = display_roles::get_display_roles().date;
+        with_display!(self, position, |display| display
+            .update_display_low_battery())
+    }
+
+    /// Renders the room temperature on the display showing the date, see
+    /// [update_display_temperature](SevenSegmentDisplayService::update_display_temperature).
+    pub fn update_temperature(&self, temperature_c: f32) -> Result<(), AppError> {
+        let position = display_roles::get_display_roles().date;
+        with_display!(self, position, |display| display
+            .update_display_temperature(temperature_c))
+    }
+
+    /// Renders the outdoor temperature on the display showing the date, see
+    /// [update_display_outdoor_temperature](SevenSegmentDisplayService::update_display_outdoor_temperature).
+    pub fn update_outdoor_temperature(&self, temperature_c: f32) -> Result<(), AppError> {
+        let position = display_roles::get_display_roles().date;
+        with_display!(self, position, |display| display
+            .update_display_outdoor_temperature(temperature_c))
+    }
+
+    /// Renders the room humidity on the display showing the date, see
+    /// [update_display_humidity](SevenSegmentDisplayService::update_display_humidity).
+    pub fn update_humidity(&self, humidity: f32) -> Result<(), AppError> {
+        let position = display_roles::get_display_roles().date;
+        with_display!(self, position, |display| display
+            .update_display_humidity(humidity))
+    }
+
+    /// Renders a [DateView] on the display showing the date, with the last
+    /// readings of the sensors. The date is shown instead when the reading of
+    /// the view is gone.
+    pub fn update_date_view(&self, view: DateView) -> Result<(), AppError> {
+        match (view, sensor::get_reading()) {
+            (DateView::Weekday, _) => self.update_weekday(),
+            (DateView::Temperature, Some(reading)) => {
+                self.update_temperature(reading.temperature_c)
+            }
+            (
+                DateView::Humidity,
+                Some(sensor::SensorReading {
+                    humidity: Some(humidity),
+                    ..
+                }),
+            ) => self.update_humidity(humidity),
+            (DateView::Outdoor, _) => match weather::get_outdoor_temperature() {
+                Some(temperature_c) => self.update_outdoor_temperature(temperature_c),
+                None => self.update_date(),
+            },
+            (DateView::LowBattery, _) => self.update_low_battery(),
+            _ => self.update_date(),
+        }
+    }
+
+    /// Renders the current year, the seconds or the countdown on the display
+    /// showing the year, see
+    /// [update_display_year](SevenSegmentDisplayService::update_display_year).
+    pub fn update_year(&self) -> Result<(), AppError> {
+        let position = display_roles::get_display_roles().year;
+        with_display!(self, position, |display| display.update_display_year())
+    }
+
+    /// Renders the current time on the display showing the hour, unless the
+    /// stopwatch is on it, see [is_active](stopwatch::is_active).
+    pub fn update_hour<AM: OutputPin, PM: OutputPin>(
+        &self,
+        am_pm_indicator: SharedAmPmIndicator<AM, PM>,
+        hour_format: HourFormat,
+    ) -> Result<(), AppError> {
+        if stopwatch::is_active() {
+            return Ok(());
+        }
+
+        let position = display_roles::get_display_roles().hour;
+        with_display!(self, position, |display| display
+            .update_display_hour(am_pm_indicator, hour_format))
+    }
+
+    /// Renders the time measured by the stopwatch on the display showing the
+    /// hour, see [update_display_stopwatch](SevenSegmentDisplayService::update_display_stopwatch).
+    pub fn update_stopwatch(&self, elapsed: Duration) -> Result<(), AppError> {
+        let position = display_roles::get_display_roles().hour;
+        with_display!(self, position, |display| display
+            .update_display_stopwatch(elapsed))
+    }
+
+    /// Writes a 4-byte message to the display showing the hour, e.g. a
+    /// [DisplayMessage].
+    pub fn write_hour(&self, message: [u8; 4]) -> Result<(), AppError> {
+        let position = display_roles::get_display_roles().hour;
+        with_display!(self, position, |display| display.write(&message))
+    }
+
+    /// Turns the colon of the display showing the hour on or off, see
+    /// [set_colon](SevenSegmentDisplayService::set_colon).
+    pub fn set_colon(&self, on: bool) -> Result<(), AppError> {
+        let position = display_roles::get_display_roles().hour;
+        with_display!(self, position, |display| display.set_colon(on))
+    }
+
+    /// Renders the current date, year and time on the displays of the group.
+    pub fn refresh<AM: OutputPin, PM: OutputPin>(
+        &self,
+        am_pm_indicator: SharedAmPmIndicator<AM, PM>,
+        hour_format: HourFormat,
+    ) -> Result<(), AppError> {
+        self.update_date()?;
+        self.update_year()?;
+        self.update_hour(am_pm_indicator, hour_format)
+    }
+}
+
 impl<CLK, DIO> SevenSegmentDisplay<'_, CLK, DIO>
 where
     CLK: OutputPin,
@@ -57,14 +653,193 @@ where
         clk: CLK,
         dio: DIO,
     ) -> Result<SharedSevenSegmentDisplay<'a, CLK, DIO>, AppError> {
+        Ok(Self::with_driver(Driver::Tm1637(Self::new_tm1637(
+            clk, dio,
+        )?)))
+    }
+
+    /// Creates a new [`SevenSegmentDisplay`] instance driven by a 6-digit
+    /// TM1637 module, which shows the seconds after the hour, e.g. `12:34.56`.
+    ///
+    /// ## Arguments
+    /// - `clk`: The GPIO pin used for the clock signal.
+    /// - `dio`: The GPIO pin used for the data signal.
+    ///
+    /// ## Example
+    /// ```rust
+    /// let display = SevenSegmentDisplay::new_six_digit(clk_pin, dio_pin)
+    ///     .expect("Failed to initialize the display");
+    /// ```
+    #[cfg(feature = "six-digit-hour")]
+    pub fn new_six_digit<'a>(
+        clk: CLK,
+        dio: DIO,
+    ) -> Result<SharedSevenSegmentDisplay<'a, CLK, DIO>, AppError> {
+        Ok(Self::with_driver(Driver::Tm1637SixDigit(Self::new_tm1637(
+            clk, dio,
+        )?)))
+    }
+
+    /// Creates the TM1637 driver of the given pins.
+    fn new_tm1637<'a>(clk: CLK, dio: DIO) -> Result<Tm1637<'a, CLK, DIO>, AppError> {
         let clk = Box::new(PinDriver::output(clk)?);
         let dio = Box::new(PinDriver::input_output(dio)?);
         let delay = Box::new(Ets);
 
-        let tm1637 = TM1637::new(Box::leak(clk), Box::leak(dio), Box::leak(delay));
-        let display = SevenSegmentDisplay { tm1637 };
+        Ok(TM1637::new(
+            Box::leak(clk),
+            Box::leak(dio),
+            Box::leak(delay),
+        ))
+    }
 
-        Ok(SharedSevenSegmentDisplay::new(display.into()))
+    /// Creates a new [`SevenSegmentDisplay`] instance driven by the HT16K33 of
+    /// an Adafruit 4-digit backpack, instead of a TM1637.
+    ///
+    /// ## Arguments
+    /// - `bus`: The I2C bus shared by the backpacks, see
+    ///   [new_bus](super::i2c::new_bus).
+    /// - `address`: The I2C address of the backpack, see
+    ///   [BASE_ADDRESS](super::ht16k33::BASE_ADDRESS).
+    ///
+    /// ## Example
+    /// ```rust
+    /// let display = SevenSegmentDisplay::new_ht16k33(i2c_bus.clone(), ht16k33::BASE_ADDRESS);
+    /// ```
+    #[cfg(feature = "ht16k33")]
+    pub fn new_ht16k33<'a>(
+        bus: SharedI2cBus<'a>,
+        address: u8,
+    ) -> Result<SharedSevenSegmentDisplay<'a, CLK, DIO>, AppError> {
+        Ok(Self::with_driver(Driver::Ht16k33(Ht16k33::new(
+            bus, address,
+        ))))
+    }
+
+    /// Creates a new [`SevenSegmentDisplay`] instance driven by an 8-digit
+    /// MAX7219 module, instead of a TM1637.
+    ///
+    /// The frames are shown on the 4 digits on the left. On the display showing
+    /// the date, the 4 digits on the right show the year, e.g. `21.10.2015`.
+    ///
+    /// ## Arguments
+    /// - `bus`: The SPI bus shared by the modules.
+    /// - `cs`: The GPIO pin selecting the module, e.g. the DIO pin of the
+    ///   TM1637 it replaces.
+    ///
+    /// ## Example
+    /// ```rust
+    /// let display = SevenSegmentDisplay::new_max7219(spi_bus.clone(), date_display_dio);
+    /// ```
+    #[cfg(feature = "max7219")]
+    pub fn new_max7219<'a>(
+        bus: SharedSpiBus<'a>,
+        cs: DIO,
+    ) -> Result<SharedSevenSegmentDisplay<'a, CLK, DIO>, AppError>
+    where
+        DIO: 'a,
+    {
+        Ok(Self::with_driver(Driver::Max7219(Max7219::new(bus, cs)?)))
+    }
+
+    /// Appends the seconds to the digits of the hour on a 6-digit display,
+    /// lighting the dot between the minutes and the seconds.
+    fn with_seconds(&self, digits: [u8; 4]) -> Result<Vec<u8>, AppError> {
+        let mut digits = digits.to_vec();
+
+        if cfg!(feature = "six-digit-hour") && self.driver.digits() >= 6 {
+            let (_, second) = time::get_minute_second()?;
+            digits[3] |= 0b10000000;
+            digits.push(DISPLAY_DIGIT[(second / 10) as usize]);
+            digits.push(DISPLAY_DIGIT[(second % 10) as usize]);
+        }
+
+        Ok(digits)
+    }
+
+    /// Wraps a [Driver] in a new [SharedSevenSegmentDisplay].
+    fn with_driver<'a>(driver: Driver<'a, CLK, DIO>) -> SharedSevenSegmentDisplay<'a, CLK, DIO> {
+        let display = SevenSegmentDisplay {
+            driver,
+            brightness: 0,
+            transition: DigitTransition::default(),
+            last_frame: vec![0; 4],
+        };
+
+        SharedSevenSegmentDisplay::new(display.into())
+    }
+
+    /// Drives the display at its brightness level, or at the level below it
+    /// when `dimmed`, without changing the stored brightness.
+    fn set_duty(&mut self, dimmed: bool) -> Result<(), AppError> {
+        self.driver
+            .set_brightness(self.brightness.saturating_sub(dimmed as u8))
+    }
+
+    /// Returns the segments to send to the display for a frame, rotated by
+    /// 180° when the displays are mounted upside down, or all off while the
+    /// displays are blanked.
+    ///
+    /// The frames are kept upright everywhere else, e.g. in `last_frame`.
+    fn oriented(&self, frame: &[u8]) -> Vec<u8> {
+        if is_blanked() {
+            vec![0; frame.len()]
+        } else if prefs::display_flip::is_display_flipped() {
+            flip_frame(frame)
+        } else {
+            frame.to_vec()
+        }
+    }
+
+    /// Returns the first 4 digits currently shown on the display.
+    fn frame(&self) -> [u8; 4] {
+        let mut frame = [0u8; 4];
+        for (digit, &segments) in frame.iter_mut().zip(&self.last_frame) {
+            *digit = segments;
+        }
+
+        frame
+    }
+
+    /// Returns the number of segments currently lit on the display, none
+    /// while blanked.
+    fn lit_segments(&self) -> u32 {
+        if is_blanked() {
+            return 0;
+        }
+
+        self.last_frame.iter().map(|digit| digit.count_ones()).sum()
+    }
+
+    /// Renders new digits, playing the configured [DigitTransition] on the
+    /// digits that differ from the ones currently shown.
+    ///
+    /// The transition is skipped in photo mode, so the displays never show an
+    /// intermediate frame.
+    fn render(&mut self, digits: &[u8]) -> Result<(), AppError> {
+        let frames: &[u8] = match self.transition {
+            DigitTransition::Instant => &[],
+            DigitTransition::Blank => &[0],
+            DigitTransition::Roll => &ROLL_FRAMES,
+        };
+
+        if digits != self.last_frame && !prefs::photo_mode::is_photo_mode() {
+            let previous = self.last_frame.clone();
+
+            for &pattern in frames {
+                let mut frame = digits.to_vec();
+                for (digit, &old) in frame.iter_mut().zip(&previous) {
+                    if *digit != old {
+                        *digit = pattern | (*digit & COLON_BIT);
+                    }
+                }
+
+                self.write(&frame)?;
+                FreeRtos::delay_ms(TRANSITION_FRAME_DELAY);
+            }
+        }
+
+        self.write(digits)
     }
 }
 
@@ -87,19 +862,20 @@ where
     /// display.init().expect("Failed to initialize the display");
     /// ```
     fn init(&mut self) -> Result<(), AppError> {
-        self.tm1637.init()?;
-        self.tm1637.set_brightness(0)?;
+        self.driver.init()?;
+        self.set_brightness(0)?;
 
-        self.write(DisplayMessage::Init.as_bytes())?;
+        self.write(&DisplayMessage::Init.as_bytes())?;
 
         Ok(())
     }
 
-    /// Writes a 4-byte message to the seven-segment display.
+    /// Writes a message to the seven-segment display, from its first digit.
     ///
     /// ## Arguments
-    /// - `message`: A fixed-size array of 4 bytes representing the digits or
-    ///   characters to display.
+    /// - `message`: The bytes representing the digits or characters to display,
+    ///   usually 4. Digits past the end of the display are dropped, and the
+    ///   digits past the end of the message are left blank.
     ///
     /// ## Returns
     /// - `Ok(())`: If the message is successfully written to the display.
@@ -108,12 +884,16 @@ where
     /// # Example
     /// ```rust
     /// display
-    ///     .write(*b"1234")
+    ///     .write(b"1234")
     ///     .expect("Failed to write to the display");
     /// ```
-    fn write(&mut self, message: [u8; 4]) -> Result<(), AppError> {
-        self.tm1637.clear()?;
-        self.tm1637.print_raw(0, &message)?;
+    fn write(&mut self, message: &[u8]) -> Result<(), AppError> {
+        let message = &message[..message.len().min(self.driver.digits())];
+        let segments = self.oriented(message);
+
+        self.driver.clear()?;
+        self.driver.print_raw(0, &segments)?;
+        self.last_frame = message.to_vec();
 
         Ok(())
     }
@@ -132,7 +912,36 @@ where
     /// display.set_brightness(5).expect("Failed to set brightness");
     /// ```
     fn set_brightness(&mut self, level: u8) -> Result<(), AppError> {
-        self.tm1637.set_brightness(level)?;
+        self.driver.set_brightness(level)?;
+        self.brightness = level;
+
+        Ok(())
+    }
+
+    /// Returns the last brightness level set on the display.
+    fn brightness(&self) -> u8 {
+        self.brightness
+    }
+
+    /// Sets the [DigitTransition] played when the digits of the display change.
+    fn set_transition(&mut self, transition: DigitTransition) {
+        self.transition = transition;
+    }
+
+    /// Turns the colon (the decimal point of the second digit) on or off,
+    /// rewriting only that digit.
+    ///
+    /// The last frame is kept as is, so the next update shows the colon
+    /// again.
+    fn set_colon(&mut self, on: bool) -> Result<(), AppError> {
+        let mut frame = self.last_frame.clone();
+        if on {
+            frame[1] |= COLON_BIT;
+        } else {
+            frame[1] &= !COLON_BIT;
+        }
+        let segments = self.oriented(&frame);
+        self.driver.print_raw(1, &segments[1..2])?;
 
         Ok(())
     }
@@ -163,43 +972,40 @@ where
         am_pm_indicator: SharedAmPmIndicator<AM, PM>,
         hour_format: HourFormat,
     ) -> Result<(), AppError> {
-        let time_24h = time::get_hour_min();
+        let time_24h = time::get_hour_min()?;
+        let leading_zero = prefs::leading_zero::is_leading_zero_enabled();
 
         match hour_format {
             HourFormat::Twelve => {
-                let hour = time_24h[0] * 10 + time_24h[1];
-
-                let display_hour = match hour {
-                    0 => 12,
-                    1..=12 => hour,
-                    _ => hour - 12,
-                };
+                let (display_hour, is_pm) = time::to_12_hour(time_24h[0] * 10 + time_24h[1]);
 
-                let digits = [
-                    DISPLAY_DIGIT[(display_hour / 10) as usize],
-                    DISPLAY_DIGIT[(display_hour % 10) as usize] | 0b10000000,
+                let [tens, units] = encode_two_digits(display_hour, leading_zero);
+                let digits = self.with_seconds([
+                    tens,
+                    units | 0b10000000,
                     DISPLAY_DIGIT[time_24h[2] as usize],
                     DISPLAY_DIGIT[time_24h[3] as usize],
-                ];
+                ])?;
 
-                self.write(digits)?;
+                self.render(&digits)?;
 
-                if hour < 12 {
-                    am_pm_indicator.lock().unwrap().set_am()?;
-                } else {
+                if is_pm {
                     am_pm_indicator.lock().unwrap().set_pm()?;
+                } else {
+                    am_pm_indicator.lock().unwrap().set_am()?;
                 }
             }
 
             HourFormat::TwentyFour => {
-                let digits = [
-                    DISPLAY_DIGIT[time_24h[0] as usize],
-                    DISPLAY_DIGIT[time_24h[1] as usize] | 0b10000000,
+                let [tens, units] = encode_two_digits(time_24h[0] * 10 + time_24h[1], leading_zero);
+                let digits = self.with_seconds([
+                    tens,
+                    units | 0b10000000,
                     DISPLAY_DIGIT[time_24h[2] as usize],
                     DISPLAY_DIGIT[time_24h[3] as usize],
-                ];
+                ])?;
 
-                self.write(digits)?;
+                self.render(&digits)?;
 
                 am_pm_indicator.lock().unwrap().clear()?;
             }
@@ -208,7 +1014,9 @@ where
         Ok(())
     }
 
-    /// Updates the display to show the current year.
+    /// Updates the display to show the current year, the seconds when the
+    /// [SecondsMode] is not [SecondsMode::Off], or else the days left until
+    /// the countdown target while one is set, e.g. ` 142`.
     ///
     /// ## Returns
     /// - `Ok(())`: If the year is successfully retrieved and displayed.
@@ -222,7 +1030,16 @@ where
     ///     .expect("Failed to update year on display");
     /// ```
     fn update_display_year(&mut self) -> Result<(), AppError> {
-        let year = time::get_year();
+        let seconds_mode = prefs::seconds_mode::get_seconds_mode();
+        if seconds_mode != SecondsMode::Off {
+            return self.update_display_seconds(seconds_mode);
+        }
+
+        if let Some(days) = prefs::countdown::days_remaining(time::get_local_date()?) {
+            return self.render(&encode_str(&format!("{days:>4}")));
+        }
+
+        let year = time::get_year()?;
 
         let digits = [
             DISPLAY_DIGIT[year[0] as usize],
@@ -231,12 +1048,45 @@ where
             DISPLAY_DIGIT[year[3] as usize],
         ];
 
-        self.write(digits)?;
+        self.render(&digits)?;
 
         Ok(())
     }
 
-    /// Updates the display to show the current date.
+    /// Updates the display to show the current seconds, e.g. `  42`, or the
+    /// minutes and the seconds, e.g. `07:42`.
+    ///
+    /// ## Arguments
+    /// - `mode` - The [SecondsMode] to show. [SecondsMode::Off] shows the
+    ///   seconds as well.
+    ///
+    /// ## Returns
+    /// - `Ok(())`: If the seconds are successfully displayed.
+    /// - `Err(AppError)`: An error if updating the display fails.
+    fn update_display_seconds(&mut self, mode: SecondsMode) -> Result<(), AppError> {
+        let (minute, second) = time::get_minute_second()?;
+
+        let digits = match mode {
+            SecondsMode::MinutesSeconds => [
+                DISPLAY_DIGIT[(minute / 10) as usize],
+                DISPLAY_DIGIT[(minute % 10) as usize] | COLON_BIT,
+                DISPLAY_DIGIT[(second / 10) as usize],
+                DISPLAY_DIGIT[(second % 10) as usize],
+            ],
+            SecondsMode::Off | SecondsMode::Seconds => [
+                0,
+                0,
+                DISPLAY_DIGIT[(second / 10) as usize],
+                DISPLAY_DIGIT[(second % 10) as usize],
+            ],
+        };
+
+        self.render(&digits)
+    }
+
+    /// Updates the display to show the current date, with the day and the
+    /// month in the order of the current [DateFormat], or the weekday when the
+    /// [WeekdayMode] is [WeekdayMode::Always].
     ///
     /// ## Returns
     /// - `Ok(())`: If the date is successfully retrieved and displayed.
@@ -250,17 +1100,459 @@ where
     ///     .expect("Failed to update date on display");
     /// ```
     fn update_display_date(&mut self) -> Result<(), AppError> {
-        let (day, month) = time::get_day_month();
+        if prefs::weekday_mode::get_weekday_mode() == WeekdayMode::Always {
+            return self.update_display_weekday();
+        }
+
+        let (day, month) = time::get_day_month()?;
+
+        let (first, second) = match prefs::date_format::get_date_format() {
+            DateFormat::DayMonth => (day, month),
+            DateFormat::MonthDay => (month, day),
+        };
 
+        // Only the first number can lose its leading zero, the second one
+        // stays aligned like the minutes
+        let [first_tens, first_units] =
+            encode_two_digits(first, prefs::leading_zero::is_leading_zero_enabled());
+        let [second_tens, second_units] = encode_two_digits(second, true);
         let digits = [
-            DISPLAY_DIGIT[(day / 10) as usize],
-            DISPLAY_DIGIT[(day % 10) as usize] | 0b10000000,
-            DISPLAY_DIGIT[(month / 10) as usize],
-            DISPLAY_DIGIT[(month % 10) as usize],
+            first_tens,
+            first_units | 0b10000000,
+            second_tens,
+            second_units,
         ];
 
-        self.write(digits)?;
+        self.render(&digits)?;
+
+        // Displays wider than 4 digits show the full date, e.g. `21.10.2015`
+        if self.driver.digits() >= 8 && !is_blanked() {
+            let year: Vec<u8> = time::get_year()?
+                .into_iter()
+                .map(|digit| DISPLAY_DIGIT[digit as usize])
+                .collect();
+
+            self.driver.print_raw(3, &[digits[3] | COLON_BIT])?;
+            self.driver.print_raw(4, &year)?;
+        }
 
         Ok(())
     }
+
+    /// Updates the display to show the abbreviation of the current weekday,
+    /// e.g. `tuES`.
+    ///
+    /// ## Returns
+    /// - `Ok(())`: If the weekday is successfully displayed.
+    /// - `Err(AppError)`: An error if updating the display fails.
+    fn update_display_weekday(&mut self) -> Result<(), AppError> {
+        self.render(&DisplayMessage::Weekday(time::get_weekday()?).as_bytes())
+    }
+
+    fn update_display_low_battery(&mut self) -> Result<(), AppError> {
+        self.render(&DisplayMessage::LowBattery.as_bytes())
+    }
+
+    /// Updates the display to show a temperature in whole degrees Celsius,
+    /// e.g. `21°C`, or `-12°` below -9°C.
+    ///
+    /// ## Arguments
+    /// - `temperature_c`: The temperature in degrees Celsius.
+    ///
+    /// ## Returns
+    /// - `Ok(())`: If the temperature is successfully displayed.
+    /// - `Err(AppError)`: An error if updating the display fails.
+    fn update_display_temperature(&mut self, temperature_c: f32) -> Result<(), AppError> {
+        let text = format!("{:>2}°C", temperature_c.round() as i32);
+        self.render(&encode_str(&text))
+    }
+
+    /// Updates the display to show an outdoor temperature in whole degrees
+    /// Celsius, without the `C` to tell it from the room temperature, e.g.
+    /// ` 21°`.
+    ///
+    /// ## Arguments
+    /// - `temperature_c`: The temperature in degrees Celsius.
+    ///
+    /// ## Returns
+    /// - `Ok(())`: If the temperature is successfully displayed.
+    /// - `Err(AppError)`: An error if updating the display fails.
+    fn update_display_outdoor_temperature(&mut self, temperature_c: f32) -> Result<(), AppError> {
+        let text = format!("{:>3}°", temperature_c.round() as i32);
+        self.render(&encode_str(&text))
+    }
+
+    /// Updates the display to show the time measured by the stopwatch: the
+    /// minutes and the seconds, e.g. `07:42`, or the hours and the minutes
+    /// from one hour on, e.g. `01:07`, up to `99:59`. A 6-digit display shows
+    /// the hours, the minutes and the seconds.
+    ///
+    /// ## Arguments
+    /// - `elapsed`: The time measured by the stopwatch.
+    ///
+    /// ## Returns
+    /// - `Ok(())`: If the time is successfully displayed.
+    /// - `Err(AppError)`: An error if updating the display fails.
+    fn update_display_stopwatch(&mut self, elapsed: Duration) -> Result<(), AppError> {
+        let total_seconds = elapsed.as_secs();
+        let hours = (total_seconds / 3600).min(99) as u8;
+        let minutes = (total_seconds / 60 % 60) as u8;
+        let seconds = (total_seconds % 60) as u8;
+
+        let pairs = if cfg!(feature = "six-digit-hour") && self.driver.digits() >= 6 {
+            vec![hours, minutes, seconds]
+        } else if hours == 0 {
+            vec![minutes, seconds]
+        } else {
+            vec![hours, minutes]
+        };
+
+        let mut digits: Vec<u8> = pairs
+            .iter()
+            .flat_map(|pair| {
+                [
+                    DISPLAY_DIGIT[(pair / 10) as usize],
+                    DISPLAY_DIGIT[(pair % 10) as usize],
+                ]
+            })
+            .collect();
+        for colon in (1..digits.len() - 1).step_by(2) {
+            digits[colon] |= COLON_BIT;
+        }
+
+        self.render(&digits)
+    }
+
+    /// Updates the display to show a relative humidity in whole percents,
+    /// e.g. `H 45`.
+    ///
+    /// ## Arguments
+    /// - `humidity`: The relative humidity in %.
+    ///
+    /// ## Returns
+    /// - `Ok(())`: If the humidity is successfully displayed.
+    /// - `Err(AppError)`: An error if updating the display fails.
+    fn update_display_humidity(&mut self, humidity: f32) -> Result<(), AppError> {
+        let text = format!("H{:>3}", humidity.round() as u8);
+        self.render(&encode_str(&text))
+    }
+}
+
+/// Returns the job that alternates the date display between its views every
+/// few seconds, see
+/// [get_alternate_interval](prefs::alternate_interval::get_alternate_interval):
+/// the date, the weekday while the [WeekdayMode] is [WeekdayMode::Alternate],
+/// the room temperature and humidity while their display is enabled and the
+/// sensor has a reading of them, the outdoor temperature while the
+/// [WeatherConfig](crate::weather::WeatherConfig) shows it, and an empty
+/// battery while the backup battery is low, see
+/// [get_battery_status](power::battery::get_battery_status).
+///
+/// The date is shown again when nothing else is enabled, and nothing is drawn
+/// in photo mode. Each view is drawn by the display task, see
+/// [DisplayCommand::ShowDateView]. The job is meant to run
+/// [Schedule::EverySecond](crate::scheduler::Schedule::EverySecond), so a new
+/// interval applies right away.
+///
+/// ## Example
+/// ```rust
+/// display_scheduler.add(
+///     "date alternation",
+///     Schedule::EverySecond,
+///     module::display::date_alternation_job(),
+/// );
+/// ```
+pub fn date_alternation_job() -> impl FnMut() + Send {
+    let mut showing = DateView::Date;
+    let mut shown_at = Instant::now();
+
+    move || {
+        let interval = prefs::alternate_interval::get_alternate_interval() as u64;
+        if shown_at.elapsed() < Duration::from_secs(interval) {
+            return;
+        }
+        shown_at = Instant::now();
+
+        let reading = sensor::get_reading();
+        let outdoor_temperature = weather::get_outdoor_temperature();
+        let mut views = vec![DateView::Date];
+
+        if !prefs::photo_mode::is_photo_mode() {
+            if prefs::weekday_mode::get_weekday_mode() == WeekdayMode::Alternate {
+                views.push(DateView::Weekday);
+            }
+            if prefs::temperature_display::is_temperature_display_enabled() && reading.is_some() {
+                views.push(DateView::Temperature);
+            }
+            if prefs::humidity_display::is_humidity_display_enabled()
+                && reading.is_some_and(|reading| reading.humidity.is_some())
+            {
+                views.push(DateView::Humidity);
+            }
+            if weather::get_weather_config().show && outdoor_temperature.is_some() {
+                views.push(DateView::Outdoor);
+            }
+            if power::battery::get_battery_status().is_some_and(|status| status.low) {
+                views.push(DateView::LowBattery);
+            }
+        }
+
+        if (views.len() == 1 && showing == DateView::Date) || display_message::is_message_shown() {
+            return;
+        }
+
+        showing = views
+            .iter()
+            .position(|view| *view == showing)
+            .map_or(DateView::Date, |index| views[(index + 1) % views.len()]);
+
+        display_queue::send(DisplayCommand::ShowDateView(showing));
+    }
+}
+
+/// Returns the job that updates the year display every second while the
+/// [SecondsMode] is not [SecondsMode::Off], so only that display ticks faster
+/// than the minute update loop.
+///
+/// The year is shown again when the mode is turned off. The display is drawn
+/// by the display task, see [DisplayCommand::ShowYear]. The job is meant to
+/// run [Schedule::EverySecond](crate::scheduler::Schedule::EverySecond).
+///
+/// ## Example
+/// ```rust
+/// display_scheduler.add(
+///     "seconds display",
+///     Schedule::EverySecond,
+///     module::display::seconds_display_job(),
+/// );
+/// ```
+pub fn seconds_display_job() -> impl FnMut() + Send {
+    let mut showing_seconds = false;
+
+    move || {
+        let seconds = prefs::seconds_mode::get_seconds_mode() != SecondsMode::Off;
+
+        if (!seconds && !showing_seconds) || display_message::is_message_shown() {
+            return;
+        }
+
+        showing_seconds = seconds;
+
+        display_queue::send(DisplayCommand::ShowYear);
+    }
+}
+
+/// Returns the job that renders the hour display, so a 6-digit display ticks
+/// the seconds between the updates of the update loop. The display is drawn
+/// by the display task, see [DisplayCommand::ShowHour]. It is meant to run
+/// [Schedule::EverySecond](crate::scheduler::Schedule::EverySecond).
+///
+/// ## Example
+/// ```rust
+/// display_scheduler.add(
+///     "hour seconds",
+///     Schedule::EverySecond,
+///     module::display::hour_seconds_job(),
+/// );
+/// ```
+#[cfg(feature = "six-digit-hour")]
+pub fn hour_seconds_job() -> impl FnMut() + Send {
+    move || {
+        if display_message::is_message_shown() {
+            return;
+        }
+
+        display_queue::send(DisplayCommand::ShowHour);
+    }
+}
+
+/// Renders the stopwatch on the hour display every second while it is
+/// active, see [is_active](stopwatch::is_active), and renders the time again
+/// once it is reset or idle for [IDLE_TIMEOUT](stopwatch::IDLE_TIMEOUT).
+///
+/// The AM/PM indicator is turned off while the stopwatch is shown. Runs
+/// forever, so it is meant to be spawned on its own thread.
+///
+/// ## Arguments
+/// - `display_group` - A [SharedDisplayGroup] instance.
+/// - `am_pm_indicator` - A [SharedAmPmIndicator] instance.
+pub fn run_stopwatch<CLK, DateDIO, YearDIO, HourDIO, AM, PM>(
+    display_group: SharedDisplayGroup<'static, CLK, DateDIO, YearDIO, HourDIO>,
+    am_pm_indicator: SharedAmPmIndicator<'static, AM, PM>,
+) where
+    CLK: OutputPin,
+    DateDIO: IOPin,
+    YearDIO: IOPin,
+    HourDIO: IOPin,
+    AM: OutputPin,
+    PM: OutputPin,
+{
+    let mut showing: Option<u64> = None;
+
+    loop {
+        FreeRtos::delay_ms(STOPWATCH_POLL_INTERVAL);
+
+        if display_message::is_message_shown() {
+            showing = None;
+            continue;
+        }
+
+        if !stopwatch::is_active() {
+            if showing.take().is_some() {
+                let hour_format = prefs::hour_format::get_hour_format();
+
+                if let Err(e) = display_group
+                    .lock()
+                    .unwrap()
+                    .update_hour(am_pm_indicator.clone(), hour_format)
+                {
+                    log::error!("Failed to render the time after the stopwatch: {e:?}");
+                }
+            }
+            continue;
+        }
+
+        let elapsed = stopwatch::elapsed();
+        if showing == Some(elapsed.as_secs()) {
+            continue;
+        }
+
+        if showing.is_none() {
+            if let Err(e) = am_pm_indicator.lock().unwrap().clear() {
+                log::error!("Failed to clear the AM/PM indicator: {e:?}");
+            }
+        }
+
+        if let Err(e) = display_group.lock().unwrap().update_stopwatch(elapsed) {
+            log::error!("Failed to update the stopwatch: {e:?}");
+        }
+        showing = Some(elapsed.as_secs());
+    }
+}
+
+/// Returns the job that blinks the colon of the hour display once per second,
+/// as a heartbeat, while the colon blink is enabled.
+///
+/// Only the colon digit is rewritten by the display task, see
+/// [DisplayCommand::SetColon]. The colon is turned back on when the blink is
+/// disabled, and stays on in photo mode. The job is meant to run
+/// [Schedule::EverySecond](crate::scheduler::Schedule::EverySecond) after the
+/// other jobs of the second, as it waits [COLON_ON_TIME] before turning the
+/// colon off.
+///
+/// ## Example
+/// ```rust
+/// display_scheduler.add(
+///     "colon blink",
+///     Schedule::EverySecond,
+///     module::display::colon_blink_job(),
+/// );
+/// ```
+pub fn colon_blink_job() -> impl FnMut() + Send {
+    let mut blinking = false;
+
+    move || {
+        let blink =
+            prefs::colon_blink::is_colon_blink_enabled() && !prefs::photo_mode::is_photo_mode();
+
+        if (!blink && !blinking) || display_message::is_message_shown() || stopwatch::is_active() {
+            return;
+        }
+
+        blinking = blink;
+
+        display_queue::send(DisplayCommand::SetColon(true));
+
+        if blink {
+            FreeRtos::delay_ms(COLON_ON_TIME);
+            display_queue::send(DisplayCommand::SetColon(false));
+        }
+    }
+}
+
+/// Shows the messages queued with
+/// [queue_message](display_message::queue_message) across the displays, and
+/// renders the time again once their duration is over.
+///
+/// Messages of up to 12 characters stay still, longer ones scroll until the
+/// duration is over, at least once. A new message replaces the one shown.
+/// While a message is shown, the other display tasks skip their updates, see
+/// [is_message_shown](display_message::is_message_shown). Runs forever, so it
+/// is meant to be spawned on its own thread.
+///
+/// ## Arguments
+/// - `display_group` - A [SharedDisplayGroup] instance.
+/// - `am_pm_indicator` - A [SharedAmPmIndicator] instance, used to render the
+///   time again.
+pub fn run_display_messages<CLK, DateDIO, YearDIO, HourDIO, AM, PM>(
+    display_group: SharedDisplayGroup<'static, CLK, DateDIO, YearDIO, HourDIO>,
+    am_pm_indicator: SharedAmPmIndicator<'static, AM, PM>,
+) where
+    CLK: OutputPin,
+    DateDIO: IOPin,
+    YearDIO: IOPin,
+    HourDIO: IOPin,
+    AM: OutputPin,
+    PM: OutputPin,
+{
+    loop {
+        FreeRtos::delay_ms(MESSAGE_POLL_INTERVAL);
+
+        let Some(message) = display_message::take_pending_message() else {
+            continue;
+        };
+
+        log::info!(
+            "Showing message '{}' for {}s",
+            message.text,
+            message.duration
+        );
+        display_message::set_message_shown(true);
+
+        if let Err(e) = show_display_message(&display_group, &am_pm_indicator, &message) {
+            log::error!("Failed to show message: {e:?}");
+        }
+
+        display_message::set_message_shown(display_message::has_pending_message());
+    }
+}
+
+/// Shows a message for its duration, and renders the time again unless
+/// another message is waiting.
+fn show_display_message<CLK, DateDIO, YearDIO, HourDIO, AM, PM>(
+    display_group: &SharedDisplayGroup<'static, CLK, DateDIO, YearDIO, HourDIO>,
+    am_pm_indicator: &SharedAmPmIndicator<'static, AM, PM>,
+    message: &DisplayMessageRequest,
+) -> Result<(), AppError>
+where
+    CLK: OutputPin,
+    DateDIO: IOPin,
+    YearDIO: IOPin,
+    HourDIO: IOPin,
+    AM: OutputPin,
+    PM: OutputPin,
+{
+    let until = Instant::now() + Duration::from_secs(message.duration as u64);
+    let scrolls = message.text.chars().count() > GROUP_DIGITS;
+
+    if !scrolls {
+        display_group.lock().unwrap().show_text(&message.text)?;
+    }
+
+    while Instant::now() < until && !display_message::has_pending_message() {
+        if scrolls {
+            display_group.lock().unwrap().scroll_text(&message.text)?;
+        } else {
+            FreeRtos::delay_ms(MESSAGE_POLL_INTERVAL);
+        }
+    }
+
+    if display_message::has_pending_message() {
+        return Ok(());
+    }
+
+    display_group.lock().unwrap().refresh(
+        am_pm_indicator.clone(),
+        prefs::hour_format::get_hour_format(),
+    )
 }
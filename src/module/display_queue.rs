@@ -0,0 +1,231 @@
+use super::{
+    display::{DateView, SharedDisplayGroup},
+    led::SharedAmPmIndicator,
+};
+use crate::{
+    error::AppError,
+    event::Event,
+    health::watchdog,
+    power,
+    prefs::{
+        boot_animation::BootAnimation, digit_transition::DigitTransitions,
+        hour_format::get_hour_format,
+    },
+};
+use esp_idf_svc::hal::gpio::{IOPin, OutputPin};
+use std::{
+    sync::{
+        atomic::{AtomicU32, AtomicU8, Ordering},
+        mpsc::{self, RecvTimeoutError, SyncSender, TrySendError},
+        Mutex,
+    },
+    time::Duration,
+};
+
+/// Max number of commands waiting for the display task. Commands sent while
+/// the queue is full are dropped, so senders never block.
+const QUEUE_LEN: usize = 16;
+
+/// How often the display task reads the state of the displays while no
+/// command arrives, in milliseconds.
+const STATE_INTERVAL: u64 = 1000;
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable to hold the sender of the display task, once it runs.
+    static ref DISPLAY_QUEUE: Mutex<Option<SyncSender<DisplayCommand>>> = Mutex::new(None);
+}
+
+/// Brightness of the displays, as last read by the display task.
+static BRIGHTNESS: AtomicU8 = AtomicU8::new(0);
+
+/// Number of segments lit on the displays, as last read by the display task.
+static LIT_SEGMENTS: AtomicU32 = AtomicU32::new(0);
+
+/// Represents a drawing requested to the display task, see
+/// [run_display_task].
+#[derive(Clone, Debug)]
+pub enum DisplayCommand {
+    /// Renders the current date, year and time on the displays.
+    ShowTime,
+    /// Scrolls a text once across the displays, then renders the time again.
+    ShowMessage(String),
+    /// Writes a 4-byte message, e.g. a
+    /// [DisplayMessage](crate::util::messages::DisplayMessage), to the display
+    /// showing the hour.
+    ShowStatus([u8; 4]),
+    /// Sets the brightness level (0-7) of every display.
+    SetBrightness(u8),
+    /// Plays a boot animation, then renders the time again.
+    Animate(BootAnimation),
+    /// Sets the [DigitTransitions] of the displays.
+    SetTransitions(DigitTransitions),
+    /// Draws the current digits again, e.g. after the displays are flipped.
+    Redraw,
+    /// Shows a [DateView] on the display showing the date, e.g. the weekday.
+    ShowDateView(DateView),
+    /// Renders the display showing the year again, e.g. to tick the seconds.
+    ShowYear,
+    /// Renders the display showing the hour again, e.g. to tick the seconds of
+    /// a 6-digit display.
+    ShowHour,
+    /// Turns the colon of the display showing the hour on or off.
+    SetColon(bool),
+}
+
+/// Queues a command for the display task without waiting for it to be drawn.
+///
+/// The command is dropped, with a warning, when the queue is full or the
+/// display task is not running yet.
+///
+/// ## Example
+/// ```rust
+/// display_queue::send(DisplayCommand::SetBrightness(5));
+/// ```
+pub fn send(command: DisplayCommand) {
+    let queue = DISPLAY_QUEUE.lock().unwrap();
+
+    let Some(sender) = queue.as_ref() else {
+        log::warn!("Display task not running, dropping {command:?}");
+        return;
+    };
+
+    match sender.try_send(command) {
+        Ok(()) => {}
+        Err(TrySendError::Full(command)) => {
+            log::warn!("Display queue full, dropping {command:?}")
+        }
+        Err(TrySendError::Disconnected(command)) => {
+            log::warn!("Display task stopped, dropping {command:?}")
+        }
+    }
+}
+
+/// Renders the time again once it is synchronized with SNTP, from the
+/// [Event]s of the event bus, see [subscribe](crate::event::subscribe).
+pub fn on_event(event: &Event) {
+    if *event == Event::SntpSynced {
+        send(DisplayCommand::ShowTime);
+    }
+}
+
+/// Returns the brightness level of the displays, as last read by the display
+/// task.
+pub fn brightness() -> u8 {
+    BRIGHTNESS.load(Ordering::Relaxed)
+}
+
+/// Returns the number of segments lit on the displays, as last read by the
+/// display task.
+pub fn lit_segments() -> u32 {
+    LIT_SEGMENTS.load(Ordering::Relaxed)
+}
+
+/// Draws the [DisplayCommand] sent with [send], one after the other, so the
+/// HTTP handlers and the SNTP callback never lock the displays nor wait for
+/// them.
+///
+/// Between commands, the brightness and the lit segments of the displays are
+/// read again every [STATE_INTERVAL] milliseconds, for [brightness] and
+/// [lit_segments], and the task watchdog is fed. While the low-power display
+/// mode is enabled, the displays are also strobed between their brightness
+/// level and the level below it every [STROBE_PERIOD](power::STROBE_PERIOD),
+/// which averages to a duty cycle between the two levels. Runs forever, so it
+/// is meant to be spawned on its own thread.
+///
+/// ## Arguments
+/// - `display_group` - A [SharedDisplayGroup] instance.
+/// - `am_pm_indicator` - A [SharedAmPmIndicator] instance.
+pub fn run_display_task<CLK, DateDIO, YearDIO, HourDIO, AM, PM>(
+    display_group: SharedDisplayGroup<'static, CLK, DateDIO, YearDIO, HourDIO>,
+    am_pm_indicator: SharedAmPmIndicator<'static, AM, PM>,
+) where
+    CLK: OutputPin,
+    DateDIO: IOPin,
+    YearDIO: IOPin,
+    HourDIO: IOPin,
+    AM: OutputPin,
+    PM: OutputPin,
+{
+    let (sender, receiver) = mpsc::sync_channel(QUEUE_LEN);
+    *DISPLAY_QUEUE.lock().unwrap() = Some(sender);
+
+    let watchdog = watchdog::watch_current_task()
+        .inspect_err(|e| log::error!("Failed to watch the display task: {e:?}"))
+        .ok();
+
+    let mut dimmed = false;
+
+    loop {
+        if let Some(watchdog) = &watchdog {
+            watchdog.feed();
+        }
+
+        let strobe = power::strobe_enabled(BRIGHTNESS.load(Ordering::Relaxed));
+        let timeout = if strobe || dimmed {
+            power::STROBE_PERIOD
+        } else {
+            Duration::from_millis(STATE_INTERVAL)
+        };
+
+        match receiver.recv_timeout(timeout) {
+            Ok(command) => {
+                if let Err(e) = draw(&display_group, &am_pm_indicator, &command) {
+                    log::error!("Failed to draw {command:?}: {e:?}");
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {}
+            Err(RecvTimeoutError::Disconnected) => return,
+        }
+
+        let display_group = display_group.lock().unwrap();
+
+        // Restore the brightness level once the strobe stops
+        if strobe || dimmed {
+            dimmed = strobe && !dimmed;
+            if let Err(e) = display_group.set_duty(dimmed) {
+                log::error!("Failed to strobe the displays: {e:?}");
+            }
+        }
+
+        BRIGHTNESS.store(display_group.brightness(), Ordering::Relaxed);
+        LIT_SEGMENTS.store(display_group.lit_segments(), Ordering::Relaxed);
+    }
+}
+
+/// Draws a single [DisplayCommand] on the displays of the group.
+fn draw<CLK, DateDIO, YearDIO, HourDIO, AM, PM>(
+    display_group: &SharedDisplayGroup<'static, CLK, DateDIO, YearDIO, HourDIO>,
+    am_pm_indicator: &SharedAmPmIndicator<'static, AM, PM>,
+    command: &DisplayCommand,
+) -> Result<(), AppError>
+where
+    CLK: OutputPin,
+    DateDIO: IOPin,
+    YearDIO: IOPin,
+    HourDIO: IOPin,
+    AM: OutputPin,
+    PM: OutputPin,
+{
+    let display_group = display_group.lock().unwrap();
+
+    match command {
+        DisplayCommand::ShowTime => {}
+        DisplayCommand::ShowMessage(text) => display_group.scroll_text(text)?,
+        DisplayCommand::ShowStatus(message) => return display_group.write_hour(*message),
+        DisplayCommand::SetBrightness(level) => return display_group.set_brightness(*level),
+        DisplayCommand::Animate(animation) => display_group.play_boot_animation(*animation)?,
+        DisplayCommand::SetTransitions(transitions) => {
+            display_group.set_transitions(*transitions);
+            return Ok(());
+        }
+        DisplayCommand::Redraw => return display_group.write_frames(display_group.frames()),
+        DisplayCommand::ShowDateView(view) => return display_group.update_date_view(*view),
+        DisplayCommand::ShowYear => return display_group.update_year(),
+        DisplayCommand::ShowHour => {
+            return display_group.update_hour(am_pm_indicator.clone(), get_hour_format())
+        }
+        DisplayCommand::SetColon(on) => return display_group.set_colon(*on),
+    }
+
+    display_group.refresh(am_pm_indicator.clone(), get_hour_format())
+}
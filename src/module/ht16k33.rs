@@ -0,0 +1,104 @@
+use super::i2c::SharedI2cBus;
+use crate::error::AppError;
+use esp_idf_svc::hal::delay::BLOCK;
+
+/// Address of a backpack without any address jumper bridged. Bridging `A0`,
+/// `A1` or `A2` adds 1, 2 or 4 to it.
+pub const BASE_ADDRESS: u8 = 0x70;
+
+/// Command that turns the internal oscillator on.
+const OSCILLATOR_ON: u8 = 0x21;
+
+/// Command that turns the display on, without blinking.
+const DISPLAY_ON: u8 = 0x81;
+
+/// Command that sets the dimming level (0-15) in its lower bits.
+const DIMMING: u8 = 0xE0;
+
+/// Bit of a digit that lights the colon on a TM1637, moved to the colon
+/// position of the backpack.
+const COLON_BIT: u8 = 0b10000000;
+
+/// Value of the colon position that lights both dots of the colon.
+const COLON_ON: u8 = 0x02;
+
+/// Drives the HT16K33 of an Adafruit 4-digit seven-segment backpack.
+///
+/// The digits use the same segment bits as a TM1637, so the frames of the
+/// displays are sent as they are, except for the colon, which has its own
+/// position on the backpack.
+pub struct Ht16k33<'a> {
+    bus: SharedI2cBus<'a>,
+    address: u8,
+    digits: [u8; 4],
+}
+
+impl<'a> Ht16k33<'a> {
+    /// Creates a new [Ht16k33] for the backpack at the given address.
+    pub fn new(bus: SharedI2cBus<'a>, address: u8) -> Self {
+        Self {
+            bus,
+            address,
+            digits: [0; 4],
+        }
+    }
+
+    /// Turns the oscillator and the display on, and clears the digits.
+    pub fn init(&mut self) -> Result<(), AppError> {
+        self.send(&[OSCILLATOR_ON])?;
+        self.send(&[DISPLAY_ON])?;
+
+        self.clear()
+    }
+
+    /// Turns every segment off.
+    pub fn clear(&mut self) -> Result<(), AppError> {
+        self.digits = [0; 4];
+        self.flush()
+    }
+
+    /// Writes the segments of the digits starting at `position`, like the
+    /// `print_raw` of a TM1637.
+    pub fn print_raw(&mut self, position: u8, segments: &[u8]) -> Result<(), AppError> {
+        for (digit, &value) in self.digits.iter_mut().skip(position as usize).zip(segments) {
+            *digit = value;
+        }
+
+        self.flush()
+    }
+
+    /// Sets the brightness level (0-7), spread over the 16 dimming levels of
+    /// the HT16K33.
+    pub fn set_brightness(&mut self, level: u8) -> Result<(), AppError> {
+        self.send(&[DIMMING | (level.min(7) * 2 + 1)])
+    }
+
+    /// Sends the digits to the display RAM of the backpack, where each digit
+    /// takes two bytes and the colon sits between the second and the third
+    /// digit.
+    fn flush(&mut self) -> Result<(), AppError> {
+        let [first, second, third, fourth] = self.digits;
+        let colon = if second & COLON_BIT != 0 { COLON_ON } else { 0 };
+
+        self.send(&[
+            0x00,
+            first,
+            0,
+            second & !COLON_BIT,
+            0,
+            colon,
+            0,
+            third,
+            0,
+            fourth,
+            0,
+        ])
+    }
+
+    /// Writes raw bytes to the backpack.
+    fn send(&self, bytes: &[u8]) -> Result<(), AppError> {
+        self.bus.lock().unwrap().write(self.address, bytes, BLOCK)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,36 @@
+use crate::error::AppError;
+use esp_idf_svc::hal::{
+    gpio::{InputPin, OutputPin},
+    i2c::{I2c, I2cConfig, I2cDriver},
+    peripheral::Peripheral,
+    units::Hertz,
+};
+use std::sync::{Arc, Mutex};
+
+/// Clock frequency of the I2C bus shared by the HT16K33 backpacks and the
+/// BME280.
+const BUS_FREQUENCY: Hertz = Hertz(400_000);
+
+/// A type alias for a thread-safe I2C bus, shared by every device on it.
+pub type SharedI2cBus<'a> = Arc<Mutex<I2cDriver<'a>>>;
+
+/// Creates the I2C bus the HT16K33 backpacks and the BME280 are wired to.
+///
+/// ## Example
+/// ```rust
+/// let i2c_bus = i2c::new_bus(
+///     peripherals.i2c0,
+///     peripherals.pins.gpio21,
+///     peripherals.pins.gpio22,
+/// )?;
+/// ```
+pub fn new_bus<'a>(
+    i2c: impl Peripheral<P = impl I2c> + 'a,
+    sda: impl Peripheral<P = impl InputPin + OutputPin> + 'a,
+    scl: impl Peripheral<P = impl InputPin + OutputPin> + 'a,
+) -> Result<SharedI2cBus<'a>, AppError> {
+    let config = I2cConfig::new().baudrate(BUS_FREQUENCY);
+    let driver = I2cDriver::new(i2c, sda, scl, &config)?;
+
+    Ok(Arc::new(Mutex::new(driver)))
+}
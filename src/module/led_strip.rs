@@ -1,10 +1,18 @@
 use crate::{
     error::AppError,
-    service::led_strip::LedStripService,
-    theme::{AppTheme, Theme},
+    event::{self, Event},
+    nvs::SharedAppStorage,
+    prefs::{boot_animation::BootLedEffect, wake_light},
+    presence,
+    service::{app_storage::AppStoragePrefsService, led_strip::LedStripService},
+    theme::{self, AppTheme, Theme},
+    time,
 };
 use esp_idf_svc::hal::{delay::FreeRtos, gpio::OutputPin, peripheral::Peripheral, rmt::RmtChannel};
-use std::sync::{Arc, Mutex};
+use std::{
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
 use ws2812_esp32_rmt_driver::{Ws2812Esp32Rmt, RGB8};
 
 /// Type alias for a shared [LedStrip] instance.
@@ -13,6 +21,59 @@ use ws2812_esp32_rmt_driver::{Ws2812Esp32Rmt, RGB8};
 /// LED strip.
 pub type SharedLedStrip = Arc<Mutex<LedStrip<'static>>>;
 
+/// The brightness multiplier applied to the theme colors.
+const THEME_BRIGHTNESS: f32 = 0.25;
+
+/// Number of steps of the [BootLedEffect::Fade] effect.
+const FADE_STEPS: u32 = 25;
+
+/// Delay in milliseconds between the frames of the boot effects.
+const EFFECT_FRAME_DELAY: u32 = 40;
+
+/// Number of steps to dim the strip down, and back up, on every hour strike
+/// pulse.
+const STRIKE_STEPS: u32 = 10;
+
+/// Pause in milliseconds between two hour strike pulses.
+const STRIKE_PAUSE: u32 = 400;
+
+/// The colors the wake light goes through, from the start of the sunrise to
+/// the wake time: dark red, orange and warm white.
+const SUNRISE_COLORS: [RGB8; 3] = [
+    RGB8 { r: 40, g: 0, b: 0 },
+    RGB8 {
+        r: 255,
+        g: 70,
+        b: 0,
+    },
+    RGB8 {
+        r: 255,
+        g: 170,
+        b: 90,
+    },
+];
+
+/// The color the strip blinks with while the clock waits for Wi-Fi
+/// credentials.
+const PROVISIONING_COLOR: RGB8 = RGB8 { r: 0, g: 0, b: 64 };
+
+/// How long the strip stays on, and off, on every provisioning blink, in
+/// milliseconds.
+const PROVISIONING_BLINK_DELAY: u32 = 500;
+
+/// How often the wake light moves the sunrise forward.
+pub const SUNRISE_STEP_INTERVAL: Duration = Duration::from_secs(5);
+
+/// A zone of the LED strip, split in thirds like the themes.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LedZone {
+    All,
+    Bottom,
+    Middle,
+    Top,
+}
+
 impl AppTheme for LedStrip<'_> {
     /// Sets the LED strip to a predefined color theme.
     ///
@@ -22,165 +83,306 @@ impl AppTheme for LedStrip<'_> {
     /// ## Returns
     /// A `Result` indicating success or an [`AppError`] on failure.
     fn apply_theme(&mut self, theme: &Theme) -> Result<(), AppError> {
-        let brightness = 0.25;
-
-        let mut data: Vec<RGB8> = Vec::with_capacity(self.num_leds as usize);
-
-        match theme {
-            Theme::Original => {
-                let red = RGB8 {
-                    r: (255.0 * brightness) as u8,
-                    g: (0.0 * brightness) as u8,
-                    b: (0.0 * brightness) as u8,
-                };
-                let yellow = RGB8 {
-                    r: (160.0 * brightness) as u8,
-                    g: (160.0 * brightness) as u8,
-                    b: (0.0 * brightness) as u8,
-                };
-
-                let center_start = self.num_leds / 3;
-                let center_end = 2 * self.num_leds / 3;
-
-                for i in 0..self.num_leds {
-                    if i >= center_start && i < center_end {
-                        data.push(yellow);
-                    } else {
-                        data.push(red);
-                    }
-                }
+        let data = self.theme_data(theme, THEME_BRIGHTNESS);
+
+        for _ in 0..5 {
+            self.ws2812.lock().unwrap().write_nocopy(data.clone())?;
+            FreeRtos::delay_ms(1);
+        }
+        Ok(())
+    }
+}
+
+impl LedStrip<'_> {
+    /// Generates the colors of every LED for a theme at the given brightness
+    /// multiplier, dimmed by the [dimming](LedStrip::set_dimming) factor.
+    fn theme_data(&self, theme: &Theme, brightness: f32) -> Vec<RGB8> {
+        theme::theme_colors(theme, self.num_leds, brightness * self.dimming)
+            .into_iter()
+            .map(|[r, g, b]| RGB8::new(r, g, b))
+            .collect()
+    }
+
+    /// Returns the range of LEDs of a [LedZone].
+    fn zone_range(&self, zone: LedZone) -> std::ops::Range<usize> {
+        let num_leds = self.num_leds as usize;
+        let bottom_end = num_leds / 3;
+        let middle_end = 2 * num_leds / 3;
+
+        match zone {
+            LedZone::All => 0..num_leds,
+            LedZone::Bottom => 0..bottom_end,
+            LedZone::Middle => bottom_end..middle_end,
+            LedZone::Top => middle_end..num_leds,
+        }
+    }
+
+    /// Applies a theme with some of its zones overridden by other colors, e.g.
+    /// the ones of a [Rule](crate::rules::Rule). The colors are dimmed like the
+    /// theme colors, and later zones win where they overlap.
+    ///
+    /// ## Arguments
+    /// - `theme`: The [Theme] shown outside of the overridden zones.
+    /// - `colors`: The zones to override and their colors.
+    ///
+    /// ## Returns
+    /// A `Result` indicating success or an [`AppError`] on failure.
+    ///
+    /// ## Example
+    /// ```rust
+    /// led_strip.apply_zone_colors(&Theme::Original, &[(LedZone::Top, RGB8::new(255, 0, 0))])?;
+    /// ```
+    pub fn apply_zone_colors(
+        &mut self,
+        theme: &Theme,
+        colors: &[(LedZone, RGB8)],
+    ) -> Result<(), AppError> {
+        let mut data = self.theme_data(theme, THEME_BRIGHTNESS);
+
+        let brightness = THEME_BRIGHTNESS * self.dimming;
+
+        for &(zone, color) in colors {
+            let color = RGB8::new(
+                (color.r as f32 * brightness) as u8,
+                (color.g as f32 * brightness) as u8,
+                (color.b as f32 * brightness) as u8,
+            );
+
+            for led in &mut data[self.zone_range(zone)] {
+                *led = color;
             }
+        }
+
+        self.ws2812.lock().unwrap().write_nocopy(data)?;
+
+        Ok(())
+    }
+
+    /// Lights every LED with the same color, as is, without the theme
+    /// brightness or the dimming.
+    pub fn fill(&mut self, color: RGB8) -> Result<(), AppError> {
+        let data = vec![color; self.num_leds as usize];
+        self.ws2812.lock().unwrap().write_nocopy(data)?;
+
+        Ok(())
+    }
+
+    /// Returns the sum of every color channel (0-255) of the strip while the
+    /// given theme is applied, used to estimate its current draw.
+    pub fn color_levels(&self, theme: &Theme) -> u32 {
+        self.theme_data(theme, THEME_BRIGHTNESS)
+            .iter()
+            .map(|color| color.r as u32 + color.g as u32 + color.b as u32)
+            .sum()
+    }
 
-            Theme::Hoverboard => {
-                let vibrant_yellow = RGB8::new(
-                    (255.0 * brightness) as u8,
-                    (255.0 * brightness) as u8,
-                    (0.0 * brightness) as u8,
-                );
-                let shocking_pink = RGB8::new(
-                    (255.0 * brightness) as u8,
-                    (20.0 * brightness) as u8,
-                    (147.0 * brightness) as u8,
-                );
-                let lime_green = RGB8::new(
-                    (50.0 * brightness) as u8,
-                    (205.0 * brightness) as u8,
-                    (50.0 * brightness) as u8,
-                );
-
-                let bottom_end = self.num_leds / 3;
-                let middle_end = 2 * self.num_leds / 3;
-
-                for i in 0..self.num_leds {
-                    if i < bottom_end {
-                        data.push(vibrant_yellow);
-                    } else if i < middle_end {
-                        data.push(shocking_pink);
-                    } else {
-                        data.push(lime_green);
-                    }
+    /// Plays a boot effect that ends with the given theme applied.
+    ///
+    /// ## Arguments
+    /// - `effect`: The [BootLedEffect] to play.
+    /// - `theme`: The [Theme] shown by the effect and applied at the end.
+    ///
+    /// ## Returns
+    /// A `Result` indicating success or an [`AppError`] on failure.
+    ///
+    /// ## Example
+    /// ```rust
+    /// led_strip.play_boot_effect(BootLedEffect::Fade, &Theme::Original)?;
+    /// ```
+    pub fn play_boot_effect(
+        &mut self,
+        effect: BootLedEffect,
+        theme: &Theme,
+    ) -> Result<(), AppError> {
+        match effect {
+            BootLedEffect::Off => {}
+            BootLedEffect::Fade => {
+                for step in 0..=FADE_STEPS {
+                    let brightness = THEME_BRIGHTNESS * step as f32 / FADE_STEPS as f32;
+                    let data = self.theme_data(theme, brightness);
+                    self.ws2812.lock().unwrap().write_nocopy(data)?;
+                    FreeRtos::delay_ms(EFFECT_FRAME_DELAY);
                 }
             }
+            BootLedEffect::Sweep => {
+                let full = self.theme_data(theme, THEME_BRIGHTNESS);
+                let mut data = vec![RGB8 { r: 0, g: 0, b: 0 }; full.len()];
 
-            Theme::Plutonium => {
-                let caution_yellow = RGB8::new(
-                    (255.0 * brightness) as u8,
-                    (255.0 * brightness) as u8,
-                    (0.0 * brightness) as u8,
-                );
-                let radioactive_green = RGB8::new(
-                    (124.0 * brightness) as u8,
-                    (252.0 * brightness) as u8,
-                    (0.0 * brightness) as u8,
-                );
-                let lead_gray = RGB8::new(
-                    (119.0 * brightness) as u8,
-                    (136.0 * brightness) as u8,
-                    (153.0 * brightness) as u8,
-                );
-
-                let bottom_end = self.num_leds / 3;
-                let middle_end = 2 * self.num_leds / 3;
-
-                for i in 0..self.num_leds {
-                    if i < bottom_end {
-                        data.push(caution_yellow);
-                    } else if i < middle_end {
-                        data.push(radioactive_green);
-                    } else {
-                        data.push(lead_gray);
-                    }
+                for (led, color) in full.iter().enumerate() {
+                    data[led] = *color;
+                    self.ws2812.lock().unwrap().write_nocopy(data.clone())?;
+                    FreeRtos::delay_ms(EFFECT_FRAME_DELAY);
                 }
             }
+        }
 
-            Theme::OldWest => {
-                let bronze = RGB8::new(
-                    (205.0 * brightness) as u8,
-                    (127.0 * brightness) as u8,
-                    (50.0 * brightness) as u8,
-                );
-                let steam_white = RGB8::new(
-                    (245.0 * brightness) as u8,
-                    (245.0 * brightness) as u8,
-                    (245.0 * brightness) as u8,
-                );
-                let sepia = RGB8::new(
-                    (112.0 * brightness) as u8,
-                    (66.0 * brightness) as u8,
-                    (20.0 * brightness) as u8,
-                );
-
-                let bottom_end = self.num_leds / 3;
-                let middle_end = 2 * self.num_leds / 3;
-
-                for i in 0..self.num_leds {
-                    if i < bottom_end {
-                        data.push(bronze);
-                    } else if i < middle_end {
-                        data.push(steam_white);
-                    } else {
-                        data.push(sepia);
-                    }
-                }
+        self.apply_theme(theme)
+    }
+
+    /// Strikes the hour visually, dimming the theme colors down and back up
+    /// once per pulse.
+    ///
+    /// ## Arguments
+    /// - `count`: The number of pulses, usually the hour from 1 to 12.
+    /// - `theme`: The [Theme] pulsed by the strike and applied at the end.
+    ///
+    /// ## Returns
+    /// A `Result` indicating success or an [`AppError`] on failure.
+    ///
+    /// ## Example
+    /// ```rust
+    /// led_strip.play_hour_strike(3, &Theme::Original)?;
+    /// ```
+    pub fn play_hour_strike(&mut self, count: u8, theme: &Theme) -> Result<(), AppError> {
+        for _ in 0..count {
+            for step in (0..STRIKE_STEPS).rev().chain(1..=STRIKE_STEPS) {
+                let brightness = THEME_BRIGHTNESS * step as f32 / STRIKE_STEPS as f32;
+                let data = self.theme_data(theme, brightness);
+                self.ws2812.lock().unwrap().write_nocopy(data)?;
+                FreeRtos::delay_ms(EFFECT_FRAME_DELAY);
             }
 
-            Theme::Cafe80s => {
-                let turquoise = RGB8::new(
-                    (64.0 * brightness) as u8,
-                    (224.0 * brightness) as u8,
-                    (208.0 * brightness) as u8,
-                );
-                let neon_pink = RGB8::new(
-                    (255.0 * brightness) as u8,
-                    (105.0 * brightness) as u8,
-                    (180.0 * brightness) as u8,
-                );
-                let electric_purple = RGB8::new(
-                    (128.0 * brightness) as u8,
-                    (0.0 * brightness) as u8,
-                    (128.0 * brightness) as u8,
-                );
-
-                let bottom_end = self.num_leds / 3;
-                let middle_end = 2 * self.num_leds / 3;
-
-                for i in 0..self.num_leds {
-                    if i < bottom_end {
-                        data.push(turquoise);
-                    } else if i < middle_end {
-                        data.push(neon_pink);
-                    } else {
-                        data.push(electric_purple);
-                    }
+            FreeRtos::delay_ms(STRIKE_PAUSE);
+        }
+
+        self.apply_theme(theme)
+    }
+
+    /// Sets the factor the theme and rule colors are dimmed by, from `0.0` to
+    /// `1.0`, e.g. after sunset. It takes effect the next time they are
+    /// applied.
+    pub fn set_dimming(&mut self, dimming: f32) {
+        self.dimming = dimming.clamp(0.0, 1.0);
+    }
+
+    /// Lights every LED with the color of a sunrise, blending the
+    /// [SUNRISE_COLORS] while its brightness ramps up from dark to full.
+    ///
+    /// ## Arguments
+    /// - `progress`: How far the sunrise is, from `0.0` to `1.0`.
+    ///
+    /// ## Returns
+    /// A `Result` indicating success or an [`AppError`] on failure.
+    ///
+    /// ## Example
+    /// ```rust
+    /// led_strip.apply_sunrise(0.5)?;
+    /// ```
+    pub fn apply_sunrise(&mut self, progress: f32) -> Result<(), AppError> {
+        let progress = progress.clamp(0.0, 1.0);
+        let position = progress * (SUNRISE_COLORS.len() - 1) as f32;
+        let index = (position as usize).min(SUNRISE_COLORS.len() - 2);
+        let blend = position - index as f32;
+
+        let (from, to) = (SUNRISE_COLORS[index], SUNRISE_COLORS[index + 1]);
+        let channel = |from: u8, to: u8| {
+            let level = from as f32 + (to as f32 - from as f32) * blend;
+            (level * progress.max(0.05)) as u8
+        };
+        let color = RGB8::new(
+            channel(from.r, to.r),
+            channel(from.g, to.g),
+            channel(from.b, to.b),
+        );
+
+        let data = vec![color; self.num_leds as usize];
+        self.ws2812.lock().unwrap().write_nocopy(data)?;
+
+        Ok(())
+    }
+}
+
+/// Blinks the whole LED strip with the [PROVISIONING_COLOR] to show that the
+/// clock waits for Wi-Fi credentials, e.g. in the captive portal.
+///
+/// Runs until the clock restarts with the received credentials, so it is
+/// meant to be spawned on its own thread.
+///
+/// ## Arguments
+/// - `led_strip` - A [SharedLedStrip] instance.
+///
+/// ## Example
+/// ```rust
+/// std::thread::spawn(move || module::led_strip::run_provisioning_blink(led_strip));
+/// ```
+pub fn run_provisioning_blink(led_strip: SharedLedStrip) {
+    for on in [true, false].into_iter().cycle() {
+        let result = if on {
+            led_strip
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .fill(PROVISIONING_COLOR)
+        } else {
+            led_strip
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .turn_off()
+        };
+
+        if let Err(e) = result {
+            log::error!("Failed to blink the LED strip: {e:?}");
+            return;
+        }
+
+        FreeRtos::delay_ms(PROVISIONING_BLINK_DELAY);
+    }
+}
+
+/// Plays the sunrise of the [WakeLight](wake_light::WakeLight) on the LED
+/// strip before the wake time, see
+/// [apply_sunrise](LedStrip::apply_sunrise), and applies the theme again once
+/// it is over.
+///
+/// The sunrise is skipped in away mode, see [is_away](presence::is_away).
+/// This returns a [Scheduler](crate::scheduler::Scheduler) job, meant to run
+/// every [SUNRISE_STEP_INTERVAL].
+///
+/// ## Arguments
+/// - `led_strip` - A [SharedLedStrip] instance.
+/// - `storage` - A [SharedAppStorage] instance used to read the theme.
+pub fn wake_light_job(led_strip: SharedLedStrip, storage: SharedAppStorage) -> impl FnMut() + Send {
+    let mut lit = false;
+
+    move || {
+        let progress = wake_light::get_wake_light()
+            .filter(|_| !presence::is_away())
+            .and_then(|wake_light| wake_light.progress(time::get_second_of_day().ok()?));
+
+        let result = match progress {
+            Some(progress) => {
+                if !lit {
+                    log::info!("Wake light started");
+                    event::publish(Event::AlarmFired);
                 }
+                lit = true;
+
+                led_strip
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .apply_sunrise(progress)
             }
+            None if lit => {
+                log::info!("Wake light over, applying the theme again");
+                lit = false;
+
+                let theme = storage
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .get_maybe_theme()
+                    .unwrap_or(None)
+                    .unwrap_or_default();
+
+                led_strip
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .apply_theme(&theme)
+            }
+            None => return,
         };
 
-        for _ in 0..5 {
-            self.ws2812.lock().unwrap().write_nocopy(data.clone())?;
-            FreeRtos::delay_ms(1);
+        if let Err(e) = result {
+            log::error!("Failed to update the wake light: {e:?}");
         }
-        Ok(())
     }
 }
 
@@ -188,6 +390,9 @@ impl AppTheme for LedStrip<'_> {
 pub struct LedStrip<'a> {
     ws2812: Arc<Mutex<Ws2812Esp32Rmt<'a>>>,
     pub num_leds: u8,
+    /// Factor the theme and rule colors are dimmed by, see
+    /// [set_dimming](LedStrip::set_dimming).
+    dimming: f32,
 }
 
 impl LedStrip<'_> {
@@ -216,6 +421,7 @@ impl LedStrip<'_> {
         let led_strip = LedStrip {
             ws2812: Arc::new(Mutex::new(ws2812)),
             num_leds,
+            dimming: 1.0,
         };
         Ok(led_strip)
     }
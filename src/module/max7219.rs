@@ -0,0 +1,134 @@
+use crate::error::AppError;
+use esp_idf_svc::hal::{
+    gpio::{AnyIOPin, OutputPin},
+    peripheral::Peripheral,
+    spi::{
+        config::{Config, DriverConfig},
+        SpiAnyPins, SpiDeviceDriver, SpiDriver,
+    },
+    units::Hertz,
+};
+use std::sync::Arc;
+
+/// Number of digits of a MAX7219 module.
+pub const DIGITS: usize = 8;
+
+/// Clock frequency of the SPI bus shared by the modules.
+const BUS_FREQUENCY: Hertz = Hertz(1_000_000);
+
+/// Register of the first digit. The first digit is the rightmost one on the
+/// common 8-digit modules.
+const DIGIT_REGISTER: u8 = 0x01;
+
+/// Register selecting which digits are BCD decoded. Every digit is sent as
+/// raw segments.
+const DECODE_MODE_REGISTER: u8 = 0x09;
+
+/// Register of the dimming level (0-15).
+const INTENSITY_REGISTER: u8 = 0x0A;
+
+/// Register of the number of digits scanned, minus one.
+const SCAN_LIMIT_REGISTER: u8 = 0x0B;
+
+/// Register that takes the module out of shutdown when set to 1.
+const SHUTDOWN_REGISTER: u8 = 0x0C;
+
+/// Register that lights every segment when set to 1.
+const DISPLAY_TEST_REGISTER: u8 = 0x0F;
+
+/// Bit of a digit that lights the decimal point, the same on a TM1637 and on a
+/// MAX7219.
+const DP_BIT: u8 = 0b10000000;
+
+/// A type alias for the SPI bus shared by the MAX7219 modules, each with its
+/// own CS pin.
+pub type SharedSpiBus<'a> = Arc<SpiDriver<'a>>;
+
+/// Creates the SPI bus the MAX7219 modules are wired to.
+///
+/// ## Example
+/// ```rust
+/// let spi_bus = max7219::new_bus(
+///     peripherals.spi2,
+///     peripherals.pins.gpio16,
+///     peripherals.pins.gpio13,
+/// )?;
+/// ```
+pub fn new_bus<'a, SPI: SpiAnyPins>(
+    spi: impl Peripheral<P = SPI> + 'a,
+    sclk: impl Peripheral<P = impl OutputPin> + 'a,
+    mosi: impl Peripheral<P = impl OutputPin> + 'a,
+) -> Result<SharedSpiBus<'a>, AppError> {
+    let driver = SpiDriver::new(spi, sclk, mosi, AnyIOPin::none(), &DriverConfig::default())?;
+
+    Ok(Arc::new(driver))
+}
+
+/// Converts the segments of a TM1637 digit (`a` in bit 0 to `g` in bit 6)
+/// to the segments of a MAX7219 digit (`g` in bit 0 to `a` in bit 6).
+fn to_max7219_segments(segments: u8) -> u8 {
+    let reversed = (segments & !DP_BIT).reverse_bits() >> 1;
+
+    reversed | (segments & DP_BIT)
+}
+
+/// Drives an 8-digit MAX7219 seven-segment module.
+///
+/// Modules have no colon, so the colon of the frames lights the decimal point
+/// of the second digit instead, e.g. `12.34`.
+pub struct Max7219<'a> {
+    device: SpiDeviceDriver<'a, SharedSpiBus<'a>>,
+}
+
+impl<'a> Max7219<'a> {
+    /// Creates a new [Max7219] for the module selected by the given CS pin.
+    pub fn new(
+        bus: SharedSpiBus<'a>,
+        cs: impl Peripheral<P = impl OutputPin> + 'a,
+    ) -> Result<Self, AppError> {
+        let config = Config::new().baudrate(BUS_FREQUENCY);
+        let device = SpiDeviceDriver::new(bus, Some(cs), &config)?;
+
+        Ok(Self { device })
+    }
+
+    /// Takes the module out of shutdown, scanning every digit as raw
+    /// segments, and clears the digits.
+    pub fn init(&mut self) -> Result<(), AppError> {
+        self.send(DISPLAY_TEST_REGISTER, 0)?;
+        self.send(DECODE_MODE_REGISTER, 0)?;
+        self.send(SCAN_LIMIT_REGISTER, DIGITS as u8 - 1)?;
+        self.send(SHUTDOWN_REGISTER, 1)?;
+
+        self.clear()
+    }
+
+    /// Turns every segment off.
+    pub fn clear(&mut self) -> Result<(), AppError> {
+        self.print_raw(0, &[0; DIGITS])
+    }
+
+    /// Writes the segments of the digits starting at `position`, counted from
+    /// the left, like the `print_raw` of a TM1637.
+    pub fn print_raw(&mut self, position: u8, segments: &[u8]) -> Result<(), AppError> {
+        for (digit, &value) in (position as usize..DIGITS).zip(segments) {
+            let register = DIGIT_REGISTER + (DIGITS - 1 - digit) as u8;
+            self.send(register, to_max7219_segments(value))?;
+        }
+
+        Ok(())
+    }
+
+    /// Sets the brightness level (0-7), spread over the 16 intensity levels of
+    /// the MAX7219.
+    pub fn set_brightness(&mut self, level: u8) -> Result<(), AppError> {
+        self.send(INTENSITY_REGISTER, level.min(7) * 2 + 1)
+    }
+
+    /// Writes a value to a register of the module.
+    fn send(&mut self, register: u8, value: u8) -> Result<(), AppError> {
+        self.device.write(&[register, value])?;
+
+        Ok(())
+    }
+}
@@ -1,3 +1,14 @@
 pub mod display;
+pub mod display_queue;
+#[cfg(feature = "ht16k33")]
+pub mod ht16k33;
+#[cfg(any(feature = "ht16k33", feature = "bme280"))]
+pub mod i2c;
 pub mod led;
 pub mod led_strip;
+#[cfg(feature = "max7219")]
+pub mod max7219;
+pub mod relay;
+pub mod sensor;
+#[cfg(feature = "touch")]
+pub mod touch;
@@ -0,0 +1,262 @@
+use crate::{board_config, board_config::BoardConfig, error::AppError, event::Event};
+use esp_idf_svc::hal::gpio::{AnyOutputPin, Output, PinDriver};
+use serde::{Deserialize, Serialize};
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Longest time the relay can be switched on for, in seconds.
+pub const MAX_RELAY_DURATION: u32 = 24 * 60 * 60;
+
+/// How often the relay checks if it must switch off.
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable to hold the relay configuration.
+    static ref RELAY_CONFIG: Mutex<RelayConfig> = Mutex::new(RelayConfig::default());
+
+    /// A global, thread-safe static variable to hold the relay, once its pin is taken.
+    static ref RELAY: Mutex<Option<Relay>> = Mutex::new(None);
+}
+
+/// Represents the output pin that switches a relay, e.g. for a lamp or a
+/// coffee maker.
+///
+/// ## Example
+/// ```rust
+/// let relay_config: RelayConfig =
+///     serde_json::from_str("{\"pin\":2,\"active_low\":true,\"alarm_duration\":600}").unwrap();
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RelayConfig {
+    /// The GPIO driving the relay, or `None` to disable it.
+    pub pin: Option<u8>,
+    /// Whether the relay switches on when the pin is low, as most relay
+    /// modules do.
+    pub active_low: bool,
+    /// How long the relay stays on when the alarm fires, in seconds. `0`
+    /// leaves it off.
+    pub alarm_duration: u32,
+}
+
+impl Default for RelayConfig {
+    fn default() -> Self {
+        Self {
+            pin: None,
+            active_low: false,
+            alarm_duration: 15 * 60,
+        }
+    }
+}
+
+impl RelayConfig {
+    /// Checks that the pin can drive an output without clashing with the
+    /// pins of the board, and that the alarm duration is at most
+    /// [MAX_RELAY_DURATION].
+    pub fn is_valid(&self, board: &BoardConfig) -> bool {
+        self.pin.map_or(true, |pin| board.is_free_output_pin(pin))
+            && self.alarm_duration <= MAX_RELAY_DURATION
+    }
+}
+
+/// Represents what a [RelayRequest] does to the relay.
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RelaySwitch {
+    On,
+    Off,
+    Toggle,
+}
+
+/// Represents the body of a request switching the relay by hand.
+///
+/// ## Example
+/// ```rust
+/// let relay_request: RelayRequest =
+///     serde_json::from_str("{\"state\":\"on\",\"duration\":600}").unwrap();
+/// ```
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct RelayRequest {
+    pub state: RelaySwitch,
+    /// Seconds after which the relay switches off by itself, up to
+    /// [MAX_RELAY_DURATION]. Ignored when switching it off.
+    #[serde(default)]
+    pub duration: Option<u32>,
+}
+
+/// Represents the state of the relay returned by the `/gpio` endpoint.
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct RelayStatus {
+    /// Whether a relay pin is configured.
+    pub enabled: bool,
+    /// Whether the relay is on.
+    pub on: bool,
+    /// Seconds left before the relay switches off by itself, if it does.
+    pub remaining_secs: Option<u64>,
+}
+
+/// Represents the relay, once its pin is taken.
+struct Relay {
+    pin: PinDriver<'static, AnyOutputPin, Output>,
+    active_low: bool,
+    on: bool,
+    /// When the relay switches off by itself, if it does.
+    off_at: Option<Instant>,
+}
+
+impl Relay {
+    /// Drives the pin, honoring [RelayConfig::active_low].
+    fn set(&mut self, on: bool) -> Result<(), AppError> {
+        if on != self.active_low {
+            self.pin.set_high()?;
+        } else {
+            self.pin.set_low()?;
+        }
+        self.on = on;
+
+        Ok(())
+    }
+}
+
+/// Retrieves the relay configuration in a thread-safe way.
+pub fn get_relay_config() -> RelayConfig {
+    *RELAY_CONFIG.lock().unwrap()
+}
+
+/// Updates the relay configuration in a thread-safe way. A new pin is only
+/// taken by [init_relay], at boot.
+pub fn set_relay_config(new_config: RelayConfig) {
+    let mut config_guard = RELAY_CONFIG.lock().unwrap();
+    *config_guard = new_config;
+}
+
+/// Takes the pin of the relay and switches it off.
+///
+/// ## Arguments
+/// - `config` - The [RelayConfig] with the pin, which must be valid for the
+///   board, see [RelayConfig::is_valid].
+///
+/// ## Returns
+/// - `Err(AppError)`: If no pin is set or it can't be configured.
+///
+/// ## Example
+/// ```rust
+/// relay::init_relay(relay::get_relay_config())?;
+/// ```
+pub fn init_relay(config: RelayConfig) -> Result<(), AppError> {
+    let Some(pin) = config.pin else {
+        return Err(AppError::Config("No relay pin set".to_string()));
+    };
+
+    let mut relay = Relay {
+        pin: PinDriver::output(unsafe { board_config::output_pin(pin) })?,
+        active_low: config.active_low,
+        on: false,
+        off_at: None,
+    };
+    relay.set(false)?;
+
+    *RELAY.lock().unwrap() = Some(relay);
+    log::info!("Relay ready on GPIO{pin}");
+
+    Ok(())
+}
+
+/// Switches the relay on or off. A `duration` switches it off by itself once
+/// elapsed, see [relay_job], while `None` keeps it in the new state.
+///
+/// ## Returns
+/// - `Err(AppError)`: If no relay is configured or the pin can't be driven.
+///
+/// ## Example
+/// ```rust
+/// relay::switch(true, Some(Duration::from_secs(600)))?;
+/// ```
+pub fn switch(on: bool, duration: Option<Duration>) -> Result<(), AppError> {
+    let mut relay_guard = RELAY.lock().unwrap();
+    let Some(relay) = relay_guard.as_mut() else {
+        return Err(AppError::Config("No relay configured".to_string()));
+    };
+
+    relay.set(on)?;
+    relay.off_at = duration
+        .filter(|_| on)
+        .map(|duration| Instant::now() + duration);
+
+    log::info!(
+        "Relay switched {} for {duration:?}",
+        if on { "on" } else { "off" }
+    );
+
+    Ok(())
+}
+
+/// Switches the relay to the other state, see [switch].
+pub fn toggle(duration: Option<Duration>) -> Result<(), AppError> {
+    switch(!get_status().on, duration)
+}
+
+/// Retrieves the state of the relay.
+pub fn get_status() -> RelayStatus {
+    match RELAY.lock().unwrap().as_ref() {
+        Some(relay) => RelayStatus {
+            enabled: true,
+            on: relay.on,
+            remaining_secs: relay
+                .off_at
+                .map(|off_at| off_at.saturating_duration_since(Instant::now()).as_secs()),
+        },
+        None => RelayStatus {
+            enabled: false,
+            on: false,
+            remaining_secs: None,
+        },
+    }
+}
+
+/// Switches the relay on for the [alarm duration](RelayConfig::alarm_duration)
+/// when the alarm fires, see [subscribe](crate::event::subscribe).
+pub fn on_event(event: &Event) {
+    if !matches!(event, Event::AlarmFired) {
+        return;
+    }
+
+    let alarm_duration = get_relay_config().alarm_duration;
+    if alarm_duration == 0 || !get_status().enabled {
+        return;
+    }
+
+    switch(true, Some(Duration::from_secs(alarm_duration as u64)))
+        .inspect_err(|e| log::error!("Failed to switch the relay on for the alarm: {e:?}"))
+        .ok();
+}
+
+/// Returns the job that switches the relay off once its duration elapsed. It
+/// is meant to run every [CHECK_INTERVAL].
+///
+/// ## Example
+/// ```rust
+/// scheduler.add(
+///     "relay",
+///     Schedule::Every(module::relay::CHECK_INTERVAL),
+///     module::relay::relay_job(),
+/// );
+/// ```
+pub fn relay_job() -> impl FnMut() + Send {
+    move || {
+        let mut relay_guard = RELAY.lock().unwrap();
+        let Some(relay) = relay_guard.as_mut() else {
+            return;
+        };
+
+        if relay.off_at.is_some_and(|off_at| Instant::now() >= off_at) {
+            relay.off_at = None;
+            match relay.set(false) {
+                Ok(()) => log::info!("Relay switched off after its duration"),
+                Err(e) => log::error!("Failed to switch the relay off: {e:?}"),
+            }
+        }
+    }
+}
@@ -0,0 +1,275 @@
+use super::{record_pressure, set_reading, SensorReading, PRESSURE_SAMPLES};
+use crate::{error::AppError, module::i2c::SharedI2cBus};
+use esp_idf_svc::hal::delay::{FreeRtos, BLOCK};
+use std::time::{Duration, Instant};
+
+/// Address of a BME280 with its `SDO` pin tied to ground. It is `0x77` with
+/// `SDO` tied to `VDDIO`.
+pub const ADDRESS: u8 = 0x76;
+
+/// How often the sensor is read.
+pub const READ_INTERVAL: Duration = Duration::from_secs(15);
+
+/// How often a pressure sample is recorded for the pressure trend, so the
+/// [PRESSURE_SAMPLES] span 3 hours.
+const PRESSURE_SAMPLE_INTERVAL: Duration =
+    Duration::from_secs(3 * 60 * 60 / (PRESSURE_SAMPLES as u64 - 1));
+
+/// Register holding the chip ID, [CHIP_ID] on a BME280.
+const CHIP_ID_REGISTER: u8 = 0xD0;
+
+/// Chip ID of a BME280. A BMP280, which has no humidity, answers `0x58`.
+const CHIP_ID: u8 = 0x60;
+
+/// Register of the first block of calibration data, `dig_T1` to `dig_H1`.
+const CALIBRATION_REGISTER: u8 = 0x88;
+
+/// Register of the second block of calibration data, `dig_H2` to `dig_H6`.
+const HUMIDITY_CALIBRATION_REGISTER: u8 = 0xE1;
+
+/// Register of the humidity oversampling.
+const CTRL_HUM_REGISTER: u8 = 0xF2;
+
+/// Register of the temperature and pressure oversampling, and of the mode.
+const CTRL_MEAS_REGISTER: u8 = 0xF4;
+
+/// Register of the first byte of the measurements, the pressure MSB.
+const DATA_REGISTER: u8 = 0xF7;
+
+/// Oversampling x1 of the humidity.
+const CTRL_HUM: u8 = 0b001;
+
+/// Oversampling x1 of the temperature and the pressure, in forced mode, so a
+/// single measurement is taken every time it is written.
+const CTRL_MEAS_FORCED: u8 = 0b00100101;
+
+/// How long a forced measurement takes at oversampling x1, in milliseconds.
+const MEASUREMENT_TIME: u32 = 10;
+
+/// The calibration data burnt into every BME280, used to compensate the raw
+/// measurements.
+#[derive(Clone, Copy, Debug)]
+struct Calibration {
+    t1: u16,
+    t2: i16,
+    t3: i16,
+    p1: u16,
+    p2: i16,
+    p3: i16,
+    p4: i16,
+    p5: i16,
+    p6: i16,
+    p7: i16,
+    p8: i16,
+    p9: i16,
+    h1: u8,
+    h2: i16,
+    h3: u8,
+    h4: i16,
+    h5: i16,
+    h6: i8,
+}
+
+impl Calibration {
+    /// Parses the two blocks of calibration data, see the BME280 datasheet.
+    fn parse(block: &[u8; 26], humidity_block: &[u8; 7]) -> Self {
+        let u16_at = |i: usize| u16::from_le_bytes([block[i], block[i + 1]]);
+        let i16_at = |i: usize| i16::from_le_bytes([block[i], block[i + 1]]);
+
+        Self {
+            t1: u16_at(0),
+            t2: i16_at(2),
+            t3: i16_at(4),
+            p1: u16_at(6),
+            p2: i16_at(8),
+            p3: i16_at(10),
+            p4: i16_at(12),
+            p5: i16_at(14),
+            p6: i16_at(16),
+            p7: i16_at(18),
+            p8: i16_at(20),
+            p9: i16_at(22),
+            h1: block[25],
+            h2: i16::from_le_bytes([humidity_block[0], humidity_block[1]]),
+            h3: humidity_block[2],
+            h4: ((humidity_block[3] as i8 as i16) << 4) | (humidity_block[4] & 0x0F) as i16,
+            h5: ((humidity_block[5] as i8 as i16) << 4) | (humidity_block[4] >> 4) as i16,
+            h6: humidity_block[6] as i8,
+        }
+    }
+
+    /// Compensates the raw measurements with the floating-point formulas of
+    /// the datasheet.
+    ///
+    /// ## Returns
+    /// A [SensorReading] with the temperature in °C, the humidity in % and the
+    /// pressure in hPa.
+    fn compensate(
+        &self,
+        raw_temperature: i32,
+        raw_pressure: i32,
+        raw_humidity: i32,
+    ) -> SensorReading {
+        let (adc_t, adc_p, adc_h) = (
+            raw_temperature as f64,
+            raw_pressure as f64,
+            raw_humidity as f64,
+        );
+
+        let var1 = (adc_t / 16384.0 - self.t1 as f64 / 1024.0) * self.t2 as f64;
+        let var2 = (adc_t / 131072.0 - self.t1 as f64 / 8192.0).powi(2) * self.t3 as f64;
+        let t_fine = var1 + var2;
+        let temperature_c = t_fine / 5120.0;
+
+        let mut var1 = t_fine / 2.0 - 64000.0;
+        let mut var2 = var1 * var1 * self.p6 as f64 / 32768.0;
+        var2 += var1 * self.p5 as f64 * 2.0;
+        var2 = var2 / 4.0 + self.p4 as f64 * 65536.0;
+        var1 = (self.p3 as f64 * var1 * var1 / 524288.0 + self.p2 as f64 * var1) / 524288.0;
+        var1 = (1.0 + var1 / 32768.0) * self.p1 as f64;
+        let pressure_pa = if var1 == 0.0 {
+            0.0
+        } else {
+            let pressure = (1048576.0 - adc_p - var2 / 4096.0) * 6250.0 / var1;
+            let var1 = self.p9 as f64 * pressure * pressure / 2147483648.0;
+            let var2 = pressure * self.p8 as f64 / 32768.0;
+            pressure + (var1 + var2 + self.p7 as f64) / 16.0
+        };
+
+        let var_h = t_fine - 76800.0;
+        let var_h = (adc_h - (self.h4 as f64 * 64.0 + self.h5 as f64 / 16384.0 * var_h))
+            * (self.h2 as f64 / 65536.0
+                * (1.0
+                    + self.h6 as f64 / 67108864.0
+                        * var_h
+                        * (1.0 + self.h3 as f64 / 67108864.0 * var_h)));
+        let humidity = var_h * (1.0 - self.h1 as f64 * var_h / 524288.0);
+
+        SensorReading {
+            temperature_c: temperature_c as f32,
+            humidity: Some(humidity.clamp(0.0, 100.0) as f32),
+            pressure_hpa: Some((pressure_pa / 100.0) as f32),
+        }
+    }
+}
+
+/// Reads a BME280 temperature, humidity and pressure sensor over I2C.
+pub struct Bme280 {
+    bus: SharedI2cBus<'static>,
+    address: u8,
+    calibration: Calibration,
+}
+
+impl Bme280 {
+    /// Creates a new [Bme280] instance, checking the chip ID and reading the
+    /// calibration data of the sensor.
+    ///
+    /// ## Arguments
+    /// - `bus`: The I2C bus the sensor is wired to.
+    /// - `address`: The I2C address of the sensor, see [ADDRESS].
+    ///
+    /// ## Returns
+    /// A `Result` containing the [Bme280] on success, or an [AppError] if the
+    /// sensor doesn't answer or isn't a BME280.
+    ///
+    /// ## Example
+    /// ```rust
+    /// let sensor = Bme280::new(i2c_bus.clone(), bme280::ADDRESS)?;
+    /// ```
+    pub fn new(bus: SharedI2cBus<'static>, address: u8) -> Result<Self, AppError> {
+        let mut chip_id = [0u8];
+        bus.lock()
+            .unwrap()
+            .write_read(address, &[CHIP_ID_REGISTER], &mut chip_id, BLOCK)?;
+
+        if chip_id[0] != CHIP_ID {
+            return Err(AppError::Sensor(format!(
+                "Unexpected BME280 chip ID: {:#04x}",
+                chip_id[0]
+            )));
+        }
+
+        let mut block = [0u8; 26];
+        let mut humidity_block = [0u8; 7];
+        {
+            let mut bus = bus.lock().unwrap();
+            bus.write_read(address, &[CALIBRATION_REGISTER], &mut block, BLOCK)?;
+            bus.write_read(
+                address,
+                &[HUMIDITY_CALIBRATION_REGISTER],
+                &mut humidity_block,
+                BLOCK,
+            )?;
+            bus.write(address, &[CTRL_HUM_REGISTER, CTRL_HUM], BLOCK)?;
+        }
+
+        Ok(Self {
+            bus,
+            address,
+            calibration: Calibration::parse(&block, &humidity_block),
+        })
+    }
+
+    /// Takes a forced measurement and compensates it.
+    pub fn read(&mut self) -> Result<SensorReading, AppError> {
+        self.bus.lock().unwrap().write(
+            self.address,
+            &[CTRL_MEAS_REGISTER, CTRL_MEAS_FORCED],
+            BLOCK,
+        )?;
+
+        FreeRtos::delay_ms(MEASUREMENT_TIME);
+
+        let mut data = [0u8; 8];
+        self.bus
+            .lock()
+            .unwrap()
+            .write_read(self.address, &[DATA_REGISTER], &mut data, BLOCK)?;
+
+        let raw_pressure =
+            ((data[0] as i32) << 12) | ((data[1] as i32) << 4) | ((data[2] as i32) >> 4);
+        let raw_temperature =
+            ((data[3] as i32) << 12) | ((data[4] as i32) << 4) | ((data[5] as i32) >> 4);
+        let raw_humidity = ((data[6] as i32) << 8) | data[7] as i32;
+
+        Ok(self
+            .calibration
+            .compensate(raw_temperature, raw_pressure, raw_humidity))
+    }
+}
+
+/// Returns the job that reads the sensor, updating the global
+/// [SensorReading], and records a pressure sample every
+/// [PRESSURE_SAMPLE_INTERVAL] for the [PressureTrend](super::PressureTrend).
+/// It is meant to run every [READ_INTERVAL].
+///
+/// Failed readings are logged and the last good reading is kept.
+///
+/// ## Arguments
+/// - `sensor` - The [Bme280] to read.
+///
+/// ## Example
+/// ```rust
+/// scheduler.add(
+///     "bme280",
+///     Schedule::Every(module::sensor::bme280::READ_INTERVAL),
+///     module::sensor::bme280::bme280_job(sensor),
+/// );
+/// ```
+pub fn bme280_job(mut sensor: Bme280) -> impl FnMut() + Send {
+    let mut last_sample: Option<Instant> = None;
+
+    move || match sensor.read() {
+        Ok(reading) => {
+            set_reading(reading);
+
+            if last_sample.map_or(true, |last| last.elapsed() >= PRESSURE_SAMPLE_INTERVAL) {
+                if let Some(pressure_hpa) = reading.pressure_hpa {
+                    record_pressure(pressure_hpa);
+                }
+                last_sample = Some(Instant::now());
+            }
+        }
+        Err(e) => log::warn!("Failed to read the BME280: {e:?}"),
+    }
+}
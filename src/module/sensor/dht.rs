@@ -0,0 +1,156 @@
+use super::{set_reading, SensorReading};
+use crate::error::AppError;
+use esp_idf_svc::{
+    hal::{
+        delay::Ets,
+        gpio::{AnyIOPin, InputOutput, Level, PinDriver, Pull},
+        interrupt,
+    },
+    sys::esp_timer_get_time,
+};
+use std::time::Duration;
+
+/// How often the sensor is read. A DHT22 can't be read more than once every
+/// 2 seconds, and needs about as long after power-on.
+pub const READ_INTERVAL: Duration = Duration::from_secs(15);
+
+/// How long the data line is held low to wake the sensor up, in µs.
+const START_SIGNAL_US: u32 = 1_200;
+
+/// Longest a level of the data line lasts while the sensor answers, in µs.
+const TIMEOUT_US: i64 = 100;
+
+/// Length of a high pulse over which a bit is a 1, in µs. A 0 lasts about
+/// 27µs and a 1 about 70µs.
+const ONE_THRESHOLD_US: i64 = 48;
+
+/// Bit of the temperature that tells it is below zero.
+const NEGATIVE_BIT: u16 = 0x8000;
+
+/// Reads a DHT22 (AM2302) temperature and humidity sensor, over its
+/// single-wire protocol.
+pub struct Dht22 {
+    pin: PinDriver<'static, AnyIOPin, InputOutput>,
+}
+
+impl Dht22 {
+    /// Creates a new [Dht22] instance, releasing the data line.
+    ///
+    /// ## Arguments
+    /// - `pin`: The GPIO pin wired to the data line of the sensor.
+    ///
+    /// ## Returns
+    /// A `Result` containing the [Dht22] on success, or an [AppError] on
+    /// failure.
+    ///
+    /// ## Example
+    /// ```rust
+    /// let sensor = Dht22::new(unsafe { board_config::io_pin(board.dht22) })?;
+    /// ```
+    pub fn new(pin: AnyIOPin) -> Result<Self, AppError> {
+        let mut pin = PinDriver::input_output_od(pin)?;
+        pin.set_pull(Pull::Up)?;
+        pin.set_high()?;
+
+        Ok(Self { pin })
+    }
+
+    /// Reads the sensor, checking the checksum of the received data.
+    pub fn read(&mut self) -> Result<SensorReading, AppError> {
+        let data = self.read_data()?;
+
+        let checksum = data[..4]
+            .iter()
+            .fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+        if checksum != data[4] {
+            return Err(AppError::Sensor("Invalid DHT22 checksum".to_string()));
+        }
+
+        let humidity = u16::from_be_bytes([data[0], data[1]]) as f32 / 10.0;
+        let raw_temperature = u16::from_be_bytes([data[2], data[3]]);
+        let temperature_c = (raw_temperature & !NEGATIVE_BIT) as f32 / 10.0;
+
+        Ok(SensorReading {
+            temperature_c: if raw_temperature & NEGATIVE_BIT != 0 {
+                -temperature_c
+            } else {
+                temperature_c
+            },
+            humidity: Some(humidity),
+            pressure_hpa: None,
+        })
+    }
+
+    /// Sends the start signal and receives the 40 bits of data of the sensor:
+    /// the humidity, the temperature and the checksum.
+    ///
+    /// The bits are told apart by the length of their high pulse, so the
+    /// interrupts are disabled while they are received.
+    fn read_data(&mut self) -> Result<[u8; 5], AppError> {
+        self.pin.set_low()?;
+        Ets::delay_us(START_SIGNAL_US);
+
+        interrupt::free(|| {
+            self.pin.set_high()?;
+
+            // The sensor answers with a low and a high pulse of 80µs each
+            self.wait_for(Level::Low)?;
+            self.wait_for(Level::High)?;
+            self.wait_for(Level::Low)?;
+
+            let mut data = [0u8; 5];
+            for bit in 0..40 {
+                self.wait_for(Level::High)?;
+                if self.wait_for(Level::Low)? > ONE_THRESHOLD_US {
+                    data[bit / 8] |= 1 << (7 - bit % 8);
+                }
+            }
+
+            Ok(data)
+        })
+    }
+
+    /// Waits until the data line is at the given level.
+    ///
+    /// ## Returns
+    /// How long the wait took in µs, or an [AppError] if the sensor doesn't
+    /// answer within [TIMEOUT_US].
+    fn wait_for(&self, level: Level) -> Result<i64, AppError> {
+        let start = unsafe { esp_timer_get_time() };
+
+        loop {
+            let elapsed = unsafe { esp_timer_get_time() } - start;
+
+            if self.pin.get_level() == level {
+                return Ok(elapsed);
+            }
+
+            if elapsed > TIMEOUT_US {
+                return Err(AppError::Sensor("DHT22 not answering".to_string()));
+            }
+        }
+    }
+}
+
+/// Returns the job that reads the sensor, updating the global
+/// [SensorReading]. It is meant to run every [READ_INTERVAL].
+///
+/// Failed readings are logged and the last good reading is kept.
+///
+/// ## Arguments
+/// - `sensor` - The [Dht22] to read.
+///
+/// ## Example
+/// ```rust
+/// scheduler.add(
+///     "dht22",
+///     Schedule::Every(module::sensor::dht::READ_INTERVAL),
+///     module::sensor::dht::dht_job(sensor),
+/// );
+/// ```
+pub fn dht_job(mut sensor: Dht22) -> impl FnMut() + Send {
+    move || match sensor.read() {
+        Ok(reading) => set_reading(reading),
+        Err(e) => log::warn!("Failed to read the DHT22: {e:?}"),
+    }
+}
@@ -0,0 +1,118 @@
+#[cfg(feature = "bme280")]
+pub mod bme280;
+#[cfg(feature = "dht22")]
+pub mod dht;
+
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex},
+};
+
+/// Number of pressure samples kept to compute the [PressureTrend]: one every
+/// 10 minutes over 3 hours, plus the current one.
+pub const PRESSURE_SAMPLES: usize = 19;
+
+/// Change of pressure over 3 hours, in hPa, from which the pressure is
+/// considered rising or falling.
+const PRESSURE_TREND_THRESHOLD: f32 = 1.0;
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable that holds the last reading of the room sensor, if one is wired.
+    pub static ref SENSOR_READING: Arc<Mutex<Option<SensorReading>>> = Arc::new(Mutex::new(None));
+
+    /// A global, thread-safe static variable that holds the pressure samples of the last 3 hours, oldest first.
+    pub static ref PRESSURE_HISTORY: Arc<Mutex<VecDeque<f32>>> =
+        Arc::new(Mutex::new(VecDeque::with_capacity(PRESSURE_SAMPLES)));
+}
+
+/// Represents a reading of the room sensor.
+///
+/// ## Example
+/// ```json
+/// {"temperature_c":21.4,"humidity":45.2,"pressure_hpa":1013.2}
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct SensorReading {
+    pub temperature_c: f32,
+    /// The relative humidity in %, on sensors that measure it.
+    pub humidity: Option<f32>,
+    /// The barometric pressure in hPa, on sensors that measure it.
+    pub pressure_hpa: Option<f32>,
+}
+
+/// Represents how the barometric pressure changed over the last 3 hours.
+/// Falling pressure usually announces worse weather.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PressureTrend {
+    Rising,
+    Steady,
+    Falling,
+}
+
+/// Represents the environment returned by the `/api/environment` endpoint.
+///
+/// ## Example
+/// ```json
+/// {"reading":{"temperature_c":21.4,"humidity":45.2,"pressure_hpa":1013.2},"pressure_trend":"falling"}
+/// ```
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct Environment {
+    pub reading: Option<SensorReading>,
+    /// `None` until 3 hours of pressure samples are recorded.
+    pub pressure_trend: Option<PressureTrend>,
+}
+
+/// Retrieves the last reading of the room sensor in a thread-safe way, or
+/// `None` when no sensor is wired or it couldn't be read yet.
+pub fn get_reading() -> Option<SensorReading> {
+    *SENSOR_READING.lock().unwrap()
+}
+
+/// Updates the global sensor reading in a thread-safe way.
+#[cfg(any(feature = "dht22", feature = "bme280"))]
+fn set_reading(reading: SensorReading) {
+    let mut sensor_reading_guard = SENSOR_READING.lock().unwrap();
+    *sensor_reading_guard = Some(reading);
+}
+
+/// Adds a pressure sample to the [PRESSURE_HISTORY], dropping the samples
+/// older than 3 hours.
+#[cfg(feature = "bme280")]
+fn record_pressure(pressure_hpa: f32) {
+    let mut history = PRESSURE_HISTORY.lock().unwrap();
+
+    if history.len() == PRESSURE_SAMPLES {
+        history.pop_front();
+    }
+    history.push_back(pressure_hpa);
+}
+
+/// Retrieves how the pressure changed over the last 3 hours, or `None` when
+/// there aren't 3 hours of samples yet.
+pub fn get_pressure_trend() -> Option<PressureTrend> {
+    let history = PRESSURE_HISTORY.lock().unwrap();
+
+    if history.len() < PRESSURE_SAMPLES {
+        return None;
+    }
+
+    let change = history.back()? - history.front()?;
+
+    Some(if change >= PRESSURE_TREND_THRESHOLD {
+        PressureTrend::Rising
+    } else if change <= -PRESSURE_TREND_THRESHOLD {
+        PressureTrend::Falling
+    } else {
+        PressureTrend::Steady
+    })
+}
+
+/// Retrieves the last reading and the pressure trend, see [Environment].
+pub fn get_environment() -> Environment {
+    Environment {
+        reading: get_reading(),
+        pressure_trend: get_pressure_trend(),
+    }
+}
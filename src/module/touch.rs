@@ -0,0 +1,145 @@
+use crate::{
+    audio::{self, Melody},
+    error::AppError,
+    module::{
+        display_queue::{self, DisplayCommand},
+        led_strip::SharedLedStrip,
+    },
+    nvs::SharedAppStorage,
+    power::auto_dim,
+    prefs::{self, boot_animation::BootAnimation, touch_action::TouchAction},
+};
+use esp_idf_svc::{
+    hal::gpio::Gpio15,
+    sys::{
+        esp, touch_pad_config, touch_pad_filter_start, touch_pad_init, touch_pad_read_filtered,
+        touch_pad_t, touch_pad_t_TOUCH_PAD_NUM3,
+    },
+};
+use std::time::Duration;
+
+/// The touch channel of `GPIO15`.
+const TOUCH_CHANNEL: touch_pad_t = touch_pad_t_TOUCH_PAD_NUM3;
+
+/// Period of the IIR filter smoothing the readings, in milliseconds.
+const FILTER_PERIOD_MS: u32 = 10;
+
+/// Number of readings averaged at start-up to find the untouched level.
+const CALIBRATION_SAMPLES: u32 = 20;
+
+/// Share of the untouched level, in %, under which the pad counts as touched.
+/// The reading of the ESP32 touch pads drops when a finger is near.
+const TOUCH_THRESHOLD_PERCENT: u32 = 80;
+
+/// How often the touch pad is read.
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Represents a touch pad, e.g. a piece of copper tape hidden behind the
+/// enclosure, wired to `GPIO15`.
+pub struct TouchPad {
+    _pin: Gpio15,
+    /// The reading under which the pad counts as touched.
+    threshold: u16,
+}
+
+impl TouchPad {
+    /// Starts the touch peripheral and calibrates the pad, which must not be
+    /// touched meanwhile.
+    ///
+    /// ## Arguments
+    /// - `pin` - `GPIO15`, the only pin of touch channel 3.
+    ///
+    /// ## Returns
+    /// - `Err(AppError)`: If the touch peripheral can't be started.
+    ///
+    /// ## Example
+    /// ```rust
+    /// let touch_pad = TouchPad::new(peripherals.pins.gpio15)?;
+    /// ```
+    pub fn new(pin: Gpio15) -> Result<Self, AppError> {
+        esp!(unsafe { touch_pad_init() })?;
+        esp!(unsafe { touch_pad_config(TOUCH_CHANNEL, 0) })?;
+        esp!(unsafe { touch_pad_filter_start(FILTER_PERIOD_MS) })?;
+
+        // Let the filter settle before reading the untouched level
+        std::thread::sleep(Duration::from_millis(10 * FILTER_PERIOD_MS as u64));
+
+        let mut total = 0;
+        for _ in 0..CALIBRATION_SAMPLES {
+            total += read_channel()? as u32;
+            std::thread::sleep(Duration::from_millis(FILTER_PERIOD_MS as u64));
+        }
+
+        let untouched = total / CALIBRATION_SAMPLES;
+        let threshold = (untouched * TOUCH_THRESHOLD_PERCENT / 100) as u16;
+        log::info!("Touch pad calibrated: untouched at {untouched}, touched under {threshold}");
+
+        Ok(Self {
+            _pin: pin,
+            threshold,
+        })
+    }
+
+    /// Whether a finger is on the pad.
+    pub fn is_touched(&self) -> Result<bool, AppError> {
+        Ok(read_channel()? < self.threshold)
+    }
+}
+
+/// Reads the filtered level of the touch channel.
+fn read_channel() -> Result<u16, AppError> {
+    let mut value = 0;
+    esp!(unsafe { touch_pad_read_filtered(TOUCH_CHANNEL, &mut value) })?;
+
+    Ok(value)
+}
+
+/// Watches the touch pad and runs the [TouchAction] set in the prefs on every
+/// tap: switching the night mode, see
+/// [toggle_night_mode](auto_dim::toggle_night_mode), or playing the time
+/// travel animation. Holding the finger on the pad counts as a single tap.
+///
+/// Runs forever, so it is meant to be spawned on its own thread.
+///
+/// ## Arguments
+/// - `touch_pad` - The [TouchPad] to watch.
+/// - `led_strip` - A [SharedLedStrip] instance.
+/// - `storage` - A [SharedAppStorage] instance used to read the theme.
+///
+/// ## Returns
+/// - `Err(AppError)`: If the touch pad can't be read.
+pub fn run_touch_pad(
+    touch_pad: TouchPad,
+    led_strip: SharedLedStrip,
+    storage: SharedAppStorage,
+) -> Result<(), AppError> {
+    let mut was_touched = false;
+
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+
+        let touched = touch_pad.is_touched()?;
+        if touched == was_touched {
+            continue;
+        }
+        was_touched = touched;
+
+        if !touched {
+            continue;
+        }
+
+        match prefs::touch_action::get_touch_action() {
+            TouchAction::Off => {}
+            TouchAction::NightMode => {
+                auto_dim::toggle_night_mode(&led_strip, &storage)
+                    .inspect_err(|e| log::error!("Failed to switch the night mode: {e:?}"))
+                    .ok();
+            }
+            TouchAction::TimeTravel => {
+                log::info!("Touch pad tapped. Time travel!");
+                display_queue::send(DisplayCommand::Animate(BootAnimation::Flicker));
+                audio::play(Melody::Theme);
+            }
+        }
+    }
+}
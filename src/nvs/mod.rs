@@ -1,10 +1,12 @@
 use crate::{error::AppError, nvs::prefs::PREFS_NAMESPACE};
+use bttf_core::blob::BlobStore;
 use esp_idf_svc::nvs::{EspNvs, EspNvsPartition, NvsDefault};
 use std::sync::{Arc, Mutex};
 use tz::TZ_NAMESPACE;
 use wifi::WIFI_NAMESPACE;
 
 pub mod prefs;
+pub mod settings;
 pub mod tz;
 pub mod wifi;
 
@@ -49,12 +51,36 @@ impl AppStorage {
             Err(e) => panic!("Could't get prefs namespace {e:?}"),
         };
 
-        let app_storage = Self {
+        let mut app_storage = Self {
             wifi_nvs,
             tz_nvs,
             prefs_nvs,
         };
 
+        // Move the settings saved by the previous firmwares into the settings
+        // blob
+        app_storage
+            .migrate_legacy_settings()
+            .inspect_err(|e| log::error!("Failed to migrate the legacy settings: {e:?}"))
+            .ok();
+
         Ok(SharedAppStorage::new(app_storage.into()))
     }
 }
+
+/// Stores the blobs of [bttf_core::blob], such as the
+/// [Settings](settings::Settings), in the prefs namespace.
+impl BlobStore for AppStorage {
+    fn get_blob<'a>(&mut self, key: &str, buf: &'a mut [u8]) -> Result<Option<&'a [u8]>, String> {
+        self.prefs_nvs
+            .get_raw(key, buf)
+            .map_err(|e| format!("Couldn't get key '{key}' because: {e:?}"))
+    }
+
+    fn set_blob(&mut self, key: &str, data: &[u8]) -> Result<(), String> {
+        self.prefs_nvs
+            .set_raw(key, data)
+            .map(|_| ())
+            .map_err(|e| format!("Couldn't set key '{key}' because: {e:?}"))
+    }
+}
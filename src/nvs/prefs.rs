@@ -1,7 +1,33 @@
 use super::AppStorage;
 use crate::{
-    error::AppError, prefs::hour_format::HourFormat, service::app_storage::AppStoragePrefsService,
+    board_config::BoardConfig,
+    error::AppError,
+    module::relay::RelayConfig,
+    power::auto_dim::AutoDimConfig,
+    prefs::{
+        boot_animation::{BootAnimation, BootLedEffect},
+        countdown,
+        custom_messages::CustomMessages,
+        date_format::DateFormat,
+        digit_transition::DigitTransitions,
+        display_roles::DisplayRoles,
+        hour_format::HourFormat,
+        log_level::LogLevels,
+        seconds_mode::SecondsMode,
+        touch_action::TouchAction,
+        trackers::Trackers,
+        wake_light::WakeLight,
+        weekday_mode::WeekdayMode,
+        wifi_power_save::WifiPowerSave,
+    },
+    presence::PresenceConfig,
+    rules::{macros::Macros, Rules},
+    service::app_storage::{AppStoragePrefsService, AppStorageSettingsService},
+    theme::Theme,
+    weather::WeatherConfig,
 };
+use chrono::NaiveDate;
+use postcard::{from_bytes, to_vec};
 
 /// The namespace used in NVS to store all user preferences.
 pub const PREFS_NAMESPACE: &str = "prefs_ns";
@@ -9,27 +35,1199 @@ pub const PREFS_NAMESPACE: &str = "prefs_ns";
 impl AppStoragePrefsService for AppStorage {
     /// Saves the user's selected hour format setting to NVS.
     fn save_hour_format(&mut self, hour_format: HourFormat) -> Result<(), AppError> {
-        let key_hour_format: &str = "hour_format";
-        let hour_format_data: u8 = hour_format as u8;
+        self.update_settings(|settings| settings.set_hour_format(hour_format))
+    }
+
+    /// Saves the user's selected date format setting to NVS.
+    fn save_date_format(&mut self, date_format: DateFormat) -> Result<(), AppError> {
+        let key_date_format: &str = "date_format";
+        let date_format_data: u8 = date_format as u8;
+
+        match self.prefs_nvs.set_u8(key_date_format, date_format_data) {
+            Ok(_) => log::info!("Key '{key_date_format}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_date_format}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves the date format setting from NVS.
+    fn get_maybe_date_format(&mut self) -> Result<Option<DateFormat>, String> {
+        let key_date_format = "date_format";
+
+        match self.prefs_nvs.get_u8(key_date_format) {
+            Ok(Some(date_format_value)) => Ok(Some(DateFormat::from(date_format_value))),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!(
+                "Couldn't get key '{key_date_format}' because: {e:?}",
+            )),
+        }
+    }
+
+    /// Saves the user's selected LED strip theme to NVS.
+    fn save_theme(&mut self, theme: Theme) -> Result<(), AppError> {
+        self.update_settings(|settings| settings.set_theme(theme))
+    }
+
+    /// Retrieves the LED strip theme from NVS.
+    fn get_maybe_theme(&mut self) -> Result<Option<Theme>, String> {
+        Ok(self.get_settings()?.theme())
+    }
+
+    /// Saves the brightness of the displays set from the web portal to NVS.
+    fn save_brightness(&mut self, brightness: u8) -> Result<(), AppError> {
+        self.update_settings(|settings| settings.set_brightness(brightness))
+    }
+
+    /// Saves the user's selected boot animation to NVS.
+    fn save_boot_animation(&mut self, boot_animation: BootAnimation) -> Result<(), AppError> {
+        let key_boot_animation: &str = "boot_anim";
+        let boot_animation_data: u8 = boot_animation as u8;
+
+        match self
+            .prefs_nvs
+            .set_u8(key_boot_animation, boot_animation_data)
+        {
+            Ok(_) => log::info!("Key '{key_boot_animation}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_boot_animation}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves the boot animation from NVS.
+    fn get_maybe_boot_animation(&mut self) -> Result<Option<BootAnimation>, String> {
+        let key_boot_animation = "boot_anim";
+
+        match self.prefs_nvs.get_u8(key_boot_animation) {
+            Ok(Some(boot_animation_value)) => Ok(Some(BootAnimation::from(boot_animation_value))),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!(
+                "Couldn't get key '{key_boot_animation}' because: {e:?}",
+            )),
+        }
+    }
+
+    /// Saves the user's selected boot LED effect to NVS.
+    fn save_boot_led_effect(&mut self, boot_led_effect: BootLedEffect) -> Result<(), AppError> {
+        let key_boot_led_effect: &str = "boot_led";
+        let boot_led_effect_data: u8 = boot_led_effect as u8;
+
+        match self
+            .prefs_nvs
+            .set_u8(key_boot_led_effect, boot_led_effect_data)
+        {
+            Ok(_) => log::info!("Key '{key_boot_led_effect}' updated in NVS."),
+            Err(e) => {
+                log::error!("Key '{key_boot_led_effect}' could not be updated in NVS: {e:?}",)
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves the boot LED effect from NVS.
+    fn get_maybe_boot_led_effect(&mut self) -> Result<Option<BootLedEffect>, String> {
+        let key_boot_led_effect = "boot_led";
+
+        match self.prefs_nvs.get_u8(key_boot_led_effect) {
+            Ok(Some(boot_led_effect_value)) => Ok(Some(BootLedEffect::from(boot_led_effect_value))),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!(
+                "Couldn't get key '{key_boot_led_effect}' because: {e:?}",
+            )),
+        }
+    }
+
+    /// Saves the URL the nightly settings snapshot is sent to in NVS.
+    fn save_snapshot_url(&mut self, url: String) -> Result<(), AppError> {
+        let key_snapshot_url: &str = "snapshot_url";
+
+        match self.prefs_nvs.set_str(key_snapshot_url, &url) {
+            Ok(_) => log::info!("Key '{key_snapshot_url}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_snapshot_url}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves the settings snapshot URL from NVS.
+    fn get_maybe_snapshot_url(&mut self) -> Result<Option<String>, String> {
+        let key_snapshot_url = "snapshot_url";
+        let mut snapshot_url_data = [0u8; 256];
+
+        match self
+            .prefs_nvs
+            .get_str(key_snapshot_url, &mut snapshot_url_data)
+        {
+            Ok(Some(snapshot_url)) => Ok(Some(snapshot_url.to_string())),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!(
+                "Couldn't get key '{key_snapshot_url}' because: {e:?}",
+            )),
+        }
+    }
+
+    /// Deletes the settings snapshot URL from NVS, disabling the nightly
+    /// snapshot.
+    fn delete_snapshot_url(&mut self) -> Result<(), AppError> {
+        let key_snapshot_url: &str = "snapshot_url";
+
+        match self.prefs_nvs.remove(key_snapshot_url) {
+            Ok(_) => log::info!("Key '{key_snapshot_url}' deleted from NVS."),
+            Err(e) => log::error!("Key '{key_snapshot_url}' could not be deleted from NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Saves the webhook URL to NVS.
+    fn save_webhook_url(&mut self, url: String) -> Result<(), AppError> {
+        let key_webhook_url: &str = "webhook_url";
+
+        match self.prefs_nvs.set_str(key_webhook_url, &url) {
+            Ok(_) => log::info!("Key '{key_webhook_url}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_webhook_url}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves the webhook URL from NVS.
+    fn get_maybe_webhook_url(&mut self) -> Result<Option<String>, String> {
+        let key_webhook_url = "webhook_url";
+        let mut webhook_url_data = [0u8; 256];
+
+        match self
+            .prefs_nvs
+            .get_str(key_webhook_url, &mut webhook_url_data)
+        {
+            Ok(Some(webhook_url)) => Ok(Some(webhook_url.to_string())),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!(
+                "Couldn't get key '{key_webhook_url}' because: {e:?}",
+            )),
+        }
+    }
+
+    /// Deletes the webhook URL from NVS, disabling the notifications.
+    fn delete_webhook_url(&mut self) -> Result<(), AppError> {
+        let key_webhook_url: &str = "webhook_url";
+
+        match self.prefs_nvs.remove(key_webhook_url) {
+            Ok(_) => log::info!("Key '{key_webhook_url}' deleted from NVS."),
+            Err(e) => log::error!("Key '{key_webhook_url}' could not be deleted from NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Saves whether the DST pre-announcement is enabled to NVS.
+    fn save_dst_notice(&mut self, enabled: bool) -> Result<(), AppError> {
+        let key_dst_notice: &str = "dst_notice";
+
+        match self.prefs_nvs.set_u8(key_dst_notice, enabled as u8) {
+            Ok(_) => log::info!("Key '{key_dst_notice}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_dst_notice}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves whether the DST pre-announcement is enabled from NVS.
+    fn get_maybe_dst_notice(&mut self) -> Result<Option<bool>, String> {
+        let key_dst_notice = "dst_notice";
+
+        match self.prefs_nvs.get_u8(key_dst_notice) {
+            Ok(Some(dst_notice_value)) => Ok(Some(dst_notice_value != 0)),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!(
+                "Couldn't get key '{key_dst_notice}' because: {e:?}",
+            )),
+        }
+    }
+
+    /// Saves whether the astronomical events are shown to NVS.
+    fn save_astro_events(&mut self, enabled: bool) -> Result<(), AppError> {
+        let key_astro_events: &str = "astro_events";
+
+        match self.prefs_nvs.set_u8(key_astro_events, enabled as u8) {
+            Ok(_) => log::info!("Key '{key_astro_events}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_astro_events}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves whether the astronomical events are shown from NVS.
+    fn get_maybe_astro_events(&mut self) -> Result<Option<bool>, String> {
+        let key_astro_events = "astro_events";
+
+        match self.prefs_nvs.get_u8(key_astro_events) {
+            Ok(Some(astro_events_value)) => Ok(Some(astro_events_value != 0)),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!(
+                "Couldn't get key '{key_astro_events}' because: {e:?}",
+            )),
+        }
+    }
+
+    /// Saves the custom messages of the display rotation to NVS.
+    fn save_custom_messages(&mut self, custom_messages: CustomMessages) -> Result<(), AppError> {
+        let key_custom_messages: &str = "messages";
+
+        let custom_messages_data = to_vec::<CustomMessages, 192>(&custom_messages)
+            .map_err(|e| AppError::Config(format!("Failed to serialize messages: {e:?}")))?;
+
+        match self
+            .prefs_nvs
+            .set_raw(key_custom_messages, &custom_messages_data)
+        {
+            Ok(_) => log::info!("Key '{key_custom_messages}' updated in NVS."),
+            Err(e) => {
+                log::error!("Key '{key_custom_messages}' could not be updated in NVS: {e:?}",)
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves the custom messages of the display rotation from NVS.
+    fn get_maybe_custom_messages(&mut self) -> Result<Option<CustomMessages>, String> {
+        let key_custom_messages = "messages";
+        let mut custom_messages_data = [0u8; 192];
+
+        match self
+            .prefs_nvs
+            .get_raw(key_custom_messages, &mut custom_messages_data)
+        {
+            Ok(Some(custom_messages_bytes)) => from_bytes::<CustomMessages>(custom_messages_bytes)
+                .map(Some)
+                .map_err(|e| format!("Failed to deserialize custom messages: {e:?}")),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!(
+                "Couldn't get key '{key_custom_messages}' because: {e:?}",
+            )),
+        }
+    }
+
+    /// Saves the "days since" trackers of the display rotation to NVS.
+    fn save_trackers(&mut self, trackers: Trackers) -> Result<(), AppError> {
+        let key_trackers: &str = "trackers";
+
+        let trackers_data = to_vec::<Trackers, 192>(&trackers)
+            .map_err(|e| AppError::Config(format!("Failed to serialize trackers: {e:?}")))?;
+
+        match self.prefs_nvs.set_raw(key_trackers, &trackers_data) {
+            Ok(_) => log::info!("Key '{key_trackers}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_trackers}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves the "days since" trackers of the display rotation from NVS.
+    fn get_maybe_trackers(&mut self) -> Result<Option<Trackers>, String> {
+        let key_trackers = "trackers";
+        let mut trackers_data = [0u8; 192];
+
+        match self.prefs_nvs.get_raw(key_trackers, &mut trackers_data) {
+            Ok(Some(trackers_bytes)) => from_bytes::<Trackers>(trackers_bytes)
+                .map(Some)
+                .map_err(|e| format!("Failed to deserialize trackers: {e:?}")),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!("Couldn't get key '{key_trackers}' because: {e:?}",)),
+        }
+    }
+
+    /// Saves the rules evaluated every minute to NVS.
+    fn save_rules(&mut self, rules: Rules) -> Result<(), AppError> {
+        let key_rules: &str = "rules";
+
+        let rules_data = to_vec::<Rules, 1024>(&rules)
+            .map_err(|e| AppError::Config(format!("Failed to serialize rules: {e:?}")))?;
+
+        match self.prefs_nvs.set_raw(key_rules, &rules_data) {
+            Ok(_) => log::info!("Key '{key_rules}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_rules}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves the rules evaluated every minute from NVS.
+    fn get_maybe_rules(&mut self) -> Result<Option<Rules>, String> {
+        let key_rules = "rules";
+        let mut rules_data = [0u8; 1024];
+
+        match self.prefs_nvs.get_raw(key_rules, &mut rules_data) {
+            Ok(Some(rules_bytes)) => from_bytes::<Rules>(rules_bytes)
+                .map(Some)
+                .map_err(|e| format!("Failed to deserialize rules: {e:?}")),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!("Couldn't get key '{key_rules}' because: {e:?}",)),
+        }
+    }
+
+    /// Saves the macros run by the rules to NVS.
+    fn save_macros(&mut self, macros: Macros) -> Result<(), AppError> {
+        let key_macros: &str = "macros";
+
+        let macros_data = to_vec::<Macros, 512>(&macros)
+            .map_err(|e| AppError::Config(format!("Failed to serialize macros: {e:?}")))?;
+
+        match self.prefs_nvs.set_raw(key_macros, &macros_data) {
+            Ok(_) => log::info!("Key '{key_macros}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_macros}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves the macros run by the rules from NVS.
+    fn get_maybe_macros(&mut self) -> Result<Option<Macros>, String> {
+        let key_macros = "macros";
+        let mut macros_data = [0u8; 512];
+
+        match self.prefs_nvs.get_raw(key_macros, &mut macros_data) {
+            Ok(Some(macros_bytes)) => from_bytes::<Macros>(macros_bytes)
+                .map(Some)
+                .map_err(|e| format!("Failed to deserialize macros: {e:?}")),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!("Couldn't get key '{key_macros}' because: {e:?}",)),
+        }
+    }
+
+    /// Saves whether the visual hour strike is enabled to NVS.
+    fn save_hour_strike(&mut self, enabled: bool) -> Result<(), AppError> {
+        let key_hour_strike: &str = "hour_strike";
+
+        match self.prefs_nvs.set_u8(key_hour_strike, enabled as u8) {
+            Ok(_) => log::info!("Key '{key_hour_strike}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_hour_strike}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves whether the visual hour strike is enabled from NVS.
+    fn get_maybe_hour_strike(&mut self) -> Result<Option<bool>, String> {
+        let key_hour_strike = "hour_strike";
+
+        match self.prefs_nvs.get_u8(key_hour_strike) {
+            Ok(Some(hour_strike_value)) => Ok(Some(hour_strike_value != 0)),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!(
+                "Couldn't get key '{key_hour_strike}' because: {e:?}",
+            )),
+        }
+    }
+
+    /// Saves whether single-digit hours and dates are shown with a leading
+    /// zero to NVS.
+    fn save_leading_zero(&mut self, enabled: bool) -> Result<(), AppError> {
+        let key_leading_zero: &str = "leading_zero";
+
+        match self.prefs_nvs.set_u8(key_leading_zero, enabled as u8) {
+            Ok(_) => log::info!("Key '{key_leading_zero}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_leading_zero}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves whether single-digit hours and dates are shown with a leading
+    /// zero from NVS.
+    fn get_maybe_leading_zero(&mut self) -> Result<Option<bool>, String> {
+        let key_leading_zero = "leading_zero";
+
+        match self.prefs_nvs.get_u8(key_leading_zero) {
+            Ok(Some(leading_zero_value)) => Ok(Some(leading_zero_value != 0)),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!(
+                "Couldn't get key '{key_leading_zero}' because: {e:?}",
+            )),
+        }
+    }
+
+    /// Saves whether the colon of the hour display blinks to NVS.
+    fn save_colon_blink(&mut self, enabled: bool) -> Result<(), AppError> {
+        let key_colon_blink: &str = "colon_blink";
+
+        match self.prefs_nvs.set_u8(key_colon_blink, enabled as u8) {
+            Ok(_) => log::info!("Key '{key_colon_blink}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_colon_blink}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves whether the colon of the hour display blinks from NVS.
+    fn get_maybe_colon_blink(&mut self) -> Result<Option<bool>, String> {
+        let key_colon_blink = "colon_blink";
+
+        match self.prefs_nvs.get_u8(key_colon_blink) {
+            Ok(Some(colon_blink_value)) => Ok(Some(colon_blink_value != 0)),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!(
+                "Couldn't get key '{key_colon_blink}' because: {e:?}",
+            )),
+        }
+    }
+
+    /// Saves whether the date display alternates with the room temperature to
+    /// NVS.
+    fn save_temperature_display(&mut self, enabled: bool) -> Result<(), AppError> {
+        let key_temperature_display: &str = "temp_display";
+
+        match self
+            .prefs_nvs
+            .set_u8(key_temperature_display, enabled as u8)
+        {
+            Ok(_) => log::info!("Key '{key_temperature_display}' updated in NVS."),
+            Err(e) => {
+                log::error!("Key '{key_temperature_display}' could not be updated in NVS: {e:?}",)
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves whether the date display alternates with the room temperature
+    /// from NVS.
+    fn get_maybe_temperature_display(&mut self) -> Result<Option<bool>, String> {
+        let key_temperature_display = "temp_display";
+
+        match self.prefs_nvs.get_u8(key_temperature_display) {
+            Ok(Some(temperature_display_value)) => Ok(Some(temperature_display_value != 0)),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!(
+                "Couldn't get key '{key_temperature_display}' because: {e:?}",
+            )),
+        }
+    }
+
+    /// Saves whether the date display alternates with the room humidity to
+    /// NVS.
+    fn save_humidity_display(&mut self, enabled: bool) -> Result<(), AppError> {
+        let key_humidity_display: &str = "hum_display";
+
+        match self.prefs_nvs.set_u8(key_humidity_display, enabled as u8) {
+            Ok(_) => log::info!("Key '{key_humidity_display}' updated in NVS."),
+            Err(e) => {
+                log::error!("Key '{key_humidity_display}' could not be updated in NVS: {e:?}",)
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves whether the date display alternates with the room humidity
+    /// from NVS.
+    fn get_maybe_humidity_display(&mut self) -> Result<Option<bool>, String> {
+        let key_humidity_display = "hum_display";
+
+        match self.prefs_nvs.get_u8(key_humidity_display) {
+            Ok(Some(humidity_display_value)) => Ok(Some(humidity_display_value != 0)),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!(
+                "Couldn't get key '{key_humidity_display}' because: {e:?}",
+            )),
+        }
+    }
+
+    /// Saves how many seconds each view of the date display is shown while it
+    /// alternates to NVS.
+    fn save_alternate_interval(&mut self, seconds: u8) -> Result<(), AppError> {
+        let key_alternate_interval: &str = "alt_interval";
+
+        match self.prefs_nvs.set_u8(key_alternate_interval, seconds) {
+            Ok(_) => log::info!("Key '{key_alternate_interval}' updated in NVS."),
+            Err(e) => {
+                log::error!("Key '{key_alternate_interval}' could not be updated in NVS: {e:?}",)
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves how many seconds each view of the date display is shown while
+    /// it alternates from NVS.
+    fn get_maybe_alternate_interval(&mut self) -> Result<Option<u8>, String> {
+        let key_alternate_interval = "alt_interval";
+
+        match self.prefs_nvs.get_u8(key_alternate_interval) {
+            Ok(alternate_interval) => Ok(alternate_interval),
+            Err(e) => Err(format!(
+                "Couldn't get key '{key_alternate_interval}' because: {e:?}",
+            )),
+        }
+    }
+
+    /// Saves how many seconds without motion the clock waits before it is
+    /// blanked at night to NVS.
+    fn save_motion_timeout(&mut self, seconds: u16) -> Result<(), AppError> {
+        let key_motion_timeout: &str = "motion_timeout";
+
+        match self.prefs_nvs.set_u16(key_motion_timeout, seconds) {
+            Ok(_) => log::info!("Key '{key_motion_timeout}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_motion_timeout}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves how many seconds without motion the clock waits before it is
+    /// blanked at night from NVS.
+    fn get_maybe_motion_timeout(&mut self) -> Result<Option<u16>, String> {
+        let key_motion_timeout = "motion_timeout";
+
+        match self.prefs_nvs.get_u16(key_motion_timeout) {
+            Ok(motion_timeout) => Ok(motion_timeout),
+            Err(e) => Err(format!(
+                "Couldn't get key '{key_motion_timeout}' because: {e:?}",
+            )),
+        }
+    }
+
+    /// Saves the volume of the melodies to NVS.
+    fn save_volume(&mut self, volume: u8) -> Result<(), AppError> {
+        let key_volume: &str = "volume";
+
+        match self.prefs_nvs.set_u8(key_volume, volume) {
+            Ok(_) => log::info!("Key '{key_volume}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_volume}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves the volume of the melodies from NVS.
+    fn get_maybe_volume(&mut self) -> Result<Option<u8>, String> {
+        let key_volume = "volume";
+
+        match self.prefs_nvs.get_u8(key_volume) {
+            Ok(volume) => Ok(volume),
+            Err(e) => Err(format!("Couldn't get key '{key_volume}' because: {e:?}",)),
+        }
+    }
+
+    /// Saves the time at which power was lost to NVS.
+    fn save_last_time(&mut self, timestamp: i64) -> Result<(), AppError> {
+        let key_last_time: &str = "last_time";
+
+        match self.prefs_nvs.set_i64(key_last_time, timestamp) {
+            Ok(_) => log::info!("Key '{key_last_time}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_last_time}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves the time at which power was lost from NVS.
+    fn get_maybe_last_time(&mut self) -> Result<Option<i64>, String> {
+        let key_last_time = "last_time";
+
+        match self.prefs_nvs.get_i64(key_last_time) {
+            Ok(last_time) => Ok(last_time),
+            Err(e) => Err(format!("Couldn't get key '{key_last_time}' because: {e:?}",)),
+        }
+    }
+
+    /// Deletes the time at which power was lost from NVS.
+    fn delete_last_time(&mut self) -> Result<(), AppError> {
+        let key_last_time: &str = "last_time";
+
+        match self.prefs_nvs.remove(key_last_time) {
+            Ok(_) => log::info!("Key '{key_last_time}' deleted from NVS."),
+            Err(e) => log::error!("Key '{key_last_time}' could not be deleted from NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
 
-        match self.prefs_nvs.set_u8(key_hour_format, hour_format_data) {
-            Ok(_) => log::info!("Key '{key_hour_format}' updated in NVS."),
-            Err(e) => log::error!("Key '{key_hour_format}' could not be updated in NVS: {e:?}",),
+    /// Saves whether the low-power display mode is enabled to NVS.
+    fn save_low_power(&mut self, enabled: bool) -> Result<(), AppError> {
+        let key_low_power: &str = "low_power";
+
+        match self.prefs_nvs.set_u8(key_low_power, enabled as u8) {
+            Ok(_) => log::info!("Key '{key_low_power}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_low_power}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves whether the low-power display mode is enabled from NVS.
+    fn get_maybe_low_power(&mut self) -> Result<Option<bool>, String> {
+        let key_low_power = "low_power";
+
+        match self.prefs_nvs.get_u8(key_low_power) {
+            Ok(Some(low_power_value)) => Ok(Some(low_power_value != 0)),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!("Couldn't get key '{key_low_power}' because: {e:?}",)),
+        }
+    }
+
+    /// Saves whether the power-save mode is enabled to NVS.
+    fn save_power_save(&mut self, enabled: bool) -> Result<(), AppError> {
+        let key_power_save: &str = "power_save";
+
+        match self.prefs_nvs.set_u8(key_power_save, enabled as u8) {
+            Ok(_) => log::info!("Key '{key_power_save}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_power_save}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves whether the power-save mode is enabled from NVS.
+    fn get_maybe_power_save(&mut self) -> Result<Option<bool>, String> {
+        let key_power_save = "power_save";
+
+        match self.prefs_nvs.get_u8(key_power_save) {
+            Ok(Some(power_save_value)) => Ok(Some(power_save_value != 0)),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!(
+                "Couldn't get key '{key_power_save}' because: {e:?}",
+            )),
+        }
+    }
+
+    /// Saves how much the Wi-Fi radio sleeps to NVS.
+    fn save_wifi_power_save(&mut self, mode: WifiPowerSave) -> Result<(), AppError> {
+        let key_wifi_power_save: &str = "wifi_ps";
+
+        match self.prefs_nvs.set_u8(key_wifi_power_save, mode as u8) {
+            Ok(_) => log::info!("Key '{key_wifi_power_save}' updated in NVS."),
+            Err(e) => {
+                log::error!("Key '{key_wifi_power_save}' could not be updated in NVS: {e:?}",)
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves how much the Wi-Fi radio sleeps from NVS.
+    fn get_maybe_wifi_power_save(&mut self) -> Result<Option<WifiPowerSave>, String> {
+        let key_wifi_power_save = "wifi_ps";
+
+        match self.prefs_nvs.get_u8(key_wifi_power_save) {
+            Ok(Some(mode)) => Ok(Some(WifiPowerSave::from(mode))),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!(
+                "Couldn't get key '{key_wifi_power_save}' because: {e:?}",
+            )),
+        }
+    }
+
+    /// Saves whether the LAN time server is enabled to NVS.
+    fn save_ntp_server(&mut self, enabled: bool) -> Result<(), AppError> {
+        let key_ntp_server: &str = "ntp_server";
+
+        match self.prefs_nvs.set_u8(key_ntp_server, enabled as u8) {
+            Ok(_) => log::info!("Key '{key_ntp_server}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_ntp_server}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves whether the LAN time server is enabled from NVS.
+    fn get_maybe_ntp_server(&mut self) -> Result<Option<bool>, String> {
+        let key_ntp_server = "ntp_server";
+
+        match self.prefs_nvs.get_u8(key_ntp_server) {
+            Ok(Some(ntp_server_value)) => Ok(Some(ntp_server_value != 0)),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!(
+                "Couldn't get key '{key_ntp_server}' because: {e:?}",
+            )),
+        }
+    }
+
+    /// Saves the digit transition of each display to NVS.
+    fn save_digit_transitions(&mut self, transitions: DigitTransitions) -> Result<(), AppError> {
+        let key_transitions: &str = "transitions";
+
+        let transitions_data = to_vec::<DigitTransitions, 8>(&transitions).map_err(|e| {
+            AppError::Config(format!("Failed to serialize digit transitions: {e:?}"))
+        })?;
+
+        match self.prefs_nvs.set_raw(key_transitions, &transitions_data) {
+            Ok(_) => log::info!("Key '{key_transitions}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_transitions}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves the digit transition of each display from NVS.
+    fn get_maybe_digit_transitions(&mut self) -> Result<Option<DigitTransitions>, String> {
+        let key_transitions = "transitions";
+        let mut transitions_data = [0u8; 8];
+
+        match self
+            .prefs_nvs
+            .get_raw(key_transitions, &mut transitions_data)
+        {
+            Ok(Some(transitions_bytes)) => from_bytes::<DigitTransitions>(transitions_bytes)
+                .map(Some)
+                .map_err(|e| format!("Failed to deserialize digit transitions: {e:?}")),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!(
+                "Couldn't get key '{key_transitions}' because: {e:?}",
+            )),
+        }
+    }
+
+    /// Saves how the weekday is shown on the date display to NVS.
+    fn save_weekday_mode(&mut self, mode: WeekdayMode) -> Result<(), AppError> {
+        let key_weekday_mode: &str = "weekday_mode";
+
+        match self.prefs_nvs.set_u8(key_weekday_mode, mode as u8) {
+            Ok(_) => log::info!("Key '{key_weekday_mode}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_weekday_mode}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves how the weekday is shown on the date display from NVS.
+    fn get_maybe_weekday_mode(&mut self) -> Result<Option<WeekdayMode>, String> {
+        let key_weekday_mode = "weekday_mode";
+
+        match self.prefs_nvs.get_u8(key_weekday_mode) {
+            Ok(Some(mode)) => Ok(Some(WeekdayMode::from(mode))),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!(
+                "Couldn't get key '{key_weekday_mode}' because: {e:?}",
+            )),
+        }
+    }
+
+    /// Saves what a tap on the touch pad does to NVS.
+    fn save_touch_action(&mut self, action: TouchAction) -> Result<(), AppError> {
+        let key_touch_action: &str = "touch_action";
+
+        match self.prefs_nvs.set_u8(key_touch_action, action as u8) {
+            Ok(_) => log::info!("Key '{key_touch_action}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_touch_action}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves what a tap on the touch pad does from NVS.
+    fn get_maybe_touch_action(&mut self) -> Result<Option<TouchAction>, String> {
+        let key_touch_action = "touch_action";
+
+        match self.prefs_nvs.get_u8(key_touch_action) {
+            Ok(Some(action)) => Ok(Some(TouchAction::from(action))),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!(
+                "Couldn't get key '{key_touch_action}' because: {e:?}",
+            )),
+        }
+    }
+
+    /// Saves what the year display shows to NVS.
+    fn save_seconds_mode(&mut self, mode: SecondsMode) -> Result<(), AppError> {
+        self.update_settings(|settings| settings.set_seconds_mode(mode))
+    }
+
+    /// Saves the minute of the day the daily summary is scrolled at to NVS.
+    fn save_daily_summary(&mut self, minute_of_day: u16) -> Result<(), AppError> {
+        let key_daily_summary: &str = "daily_summary";
+
+        match self.prefs_nvs.set_u16(key_daily_summary, minute_of_day) {
+            Ok(_) => log::info!("Key '{key_daily_summary}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_daily_summary}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves the minute of the day the daily summary is scrolled at from
+    /// NVS.
+    fn get_maybe_daily_summary(&mut self) -> Result<Option<u16>, String> {
+        let key_daily_summary = "daily_summary";
+
+        match self.prefs_nvs.get_u16(key_daily_summary) {
+            Ok(minute_of_day) => Ok(minute_of_day),
+            Err(e) => Err(format!(
+                "Couldn't get key '{key_daily_summary}' because: {e:?}",
+            )),
+        }
+    }
+
+    /// Deletes the daily summary time from NVS, disabling the summary.
+    fn delete_daily_summary(&mut self) -> Result<(), AppError> {
+        let key_daily_summary: &str = "daily_summary";
+
+        match self.prefs_nvs.remove(key_daily_summary) {
+            Ok(_) => log::info!("Key '{key_daily_summary}' deleted from NVS."),
+            Err(e) => {
+                log::error!("Key '{key_daily_summary}' could not be deleted from NVS: {e:?}",)
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Saves the target date of the countdown to NVS, formatted as
+    /// `YYYY-MM-DD`.
+    fn save_countdown(&mut self, target: NaiveDate) -> Result<(), AppError> {
+        let key_countdown: &str = "countdown";
+
+        let target = target.format(countdown::DATE_FORMAT).to_string();
+        match self.prefs_nvs.set_str(key_countdown, &target) {
+            Ok(_) => log::info!("Key '{key_countdown}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_countdown}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves the target date of the countdown from NVS.
+    fn get_maybe_countdown(&mut self) -> Result<Option<NaiveDate>, String> {
+        let key_countdown = "countdown";
+        let mut countdown_data = [0u8; 16];
+
+        match self.prefs_nvs.get_str(key_countdown, &mut countdown_data) {
+            Ok(Some(target)) => NaiveDate::parse_from_str(target, countdown::DATE_FORMAT)
+                .map(Some)
+                .map_err(|e| format!("Invalid countdown target '{target}': {e}")),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!("Couldn't get key '{key_countdown}' because: {e:?}",)),
+        }
+    }
+
+    /// Deletes the target date of the countdown from NVS, disabling the
+    /// countdown.
+    fn delete_countdown(&mut self) -> Result<(), AppError> {
+        let key_countdown: &str = "countdown";
+
+        match self.prefs_nvs.remove(key_countdown) {
+            Ok(_) => log::info!("Key '{key_countdown}' deleted from NVS."),
+            Err(e) => log::error!("Key '{key_countdown}' could not be deleted from NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Saves the wake time and the sunrise duration of the wake light to NVS.
+    fn save_wake_light(&mut self, wake_light: WakeLight) -> Result<(), AppError> {
+        let key_wake_light: &str = "wake_light";
+
+        let wake_light_data = to_vec::<WakeLight, 8>(&wake_light)
+            .map_err(|e| AppError::Config(format!("Failed to serialize wake light: {e:?}")))?;
+
+        match self.prefs_nvs.set_raw(key_wake_light, &wake_light_data) {
+            Ok(_) => log::info!("Key '{key_wake_light}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_wake_light}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves the wake light from NVS.
+    fn get_maybe_wake_light(&mut self) -> Result<Option<WakeLight>, String> {
+        let key_wake_light = "wake_light";
+        let mut wake_light_data = [0u8; 8];
+
+        match self.prefs_nvs.get_raw(key_wake_light, &mut wake_light_data) {
+            Ok(Some(data)) => from_bytes::<WakeLight>(data)
+                .map(Some)
+                .map_err(|e| format!("Failed to deserialize wake light: {e:?}")),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!(
+                "Couldn't get key '{key_wake_light}' because: {e:?}",
+            )),
+        }
+    }
+
+    /// Deletes the wake light from NVS, disabling it.
+    fn delete_wake_light(&mut self) -> Result<(), AppError> {
+        let key_wake_light: &str = "wake_light";
+
+        match self.prefs_nvs.remove(key_wake_light) {
+            Ok(_) => log::info!("Key '{key_wake_light}' deleted from NVS."),
+            Err(e) => log::error!("Key '{key_wake_light}' could not be deleted from NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Saves the phone watched by the presence service to NVS.
+    fn save_presence(&mut self, config: PresenceConfig) -> Result<(), AppError> {
+        let key_presence: &str = "presence";
+
+        let presence_data = to_vec::<PresenceConfig, 16>(&config)
+            .map_err(|e| AppError::Config(format!("Failed to serialize presence config: {e:?}")))?;
+
+        match self.prefs_nvs.set_raw(key_presence, &presence_data) {
+            Ok(_) => log::info!("Key '{key_presence}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_presence}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves the phone watched by the presence service from NVS.
+    fn get_maybe_presence(&mut self) -> Result<Option<PresenceConfig>, String> {
+        let key_presence = "presence";
+        let mut presence_data = [0u8; 16];
+
+        match self.prefs_nvs.get_raw(key_presence, &mut presence_data) {
+            Ok(Some(presence_bytes)) => from_bytes::<PresenceConfig>(presence_bytes)
+                .map(Some)
+                .map_err(|e| format!("Failed to deserialize presence config: {e:?}")),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!("Couldn't get key '{key_presence}' because: {e:?}",)),
+        }
+    }
+
+    /// Saves the location and interval of the weather client to NVS.
+    fn save_weather(&mut self, config: WeatherConfig) -> Result<(), AppError> {
+        let key_weather: &str = "weather";
+
+        let weather_data = to_vec::<WeatherConfig, 16>(&config)
+            .map_err(|e| AppError::Config(format!("Failed to serialize weather config: {e:?}")))?;
+
+        match self.prefs_nvs.set_raw(key_weather, &weather_data) {
+            Ok(_) => log::info!("Key '{key_weather}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_weather}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves the location and interval of the weather client from NVS.
+    fn get_maybe_weather(&mut self) -> Result<Option<WeatherConfig>, String> {
+        let key_weather = "weather";
+        let mut weather_data = [0u8; 16];
+
+        match self.prefs_nvs.get_raw(key_weather, &mut weather_data) {
+            Ok(Some(weather_bytes)) => from_bytes::<WeatherConfig>(weather_bytes)
+                .map(Some)
+                .map_err(|e| format!("Failed to deserialize weather config: {e:?}")),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!("Couldn't get key '{key_weather}' because: {e:?}",)),
+        }
+    }
+
+    /// Saves the location and the night brightness of the auto-dimming to NVS.
+    fn save_auto_dim(&mut self, config: AutoDimConfig) -> Result<(), AppError> {
+        self.update_settings(|settings| settings.set_auto_dim(config))
+    }
+
+    /// Saves whether the displays are mounted upside down to NVS.
+    fn save_display_flip(&mut self, flipped: bool) -> Result<(), AppError> {
+        let key_display_flip: &str = "display_flip";
+
+        match self.prefs_nvs.set_u8(key_display_flip, flipped as u8) {
+            Ok(_) => log::info!("Key '{key_display_flip}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_display_flip}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves whether the displays are mounted upside down from NVS.
+    fn get_maybe_display_flip(&mut self) -> Result<Option<bool>, String> {
+        let key_display_flip = "display_flip";
+
+        match self.prefs_nvs.get_u8(key_display_flip) {
+            Ok(Some(display_flip_value)) => Ok(Some(display_flip_value != 0)),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!(
+                "Couldn't get key '{key_display_flip}' because: {e:?}",
+            )),
+        }
+    }
+
+    /// Saves the physical display showing each role to NVS.
+    fn save_display_roles(&mut self, roles: DisplayRoles) -> Result<(), AppError> {
+        let key_roles: &str = "display_roles";
+
+        let roles_data = to_vec::<DisplayRoles, 8>(&roles)
+            .map_err(|e| AppError::Config(format!("Failed to serialize display roles: {e:?}")))?;
+
+        match self.prefs_nvs.set_raw(key_roles, &roles_data) {
+            Ok(_) => log::info!("Key '{key_roles}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_roles}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves the physical display showing each role from NVS.
+    fn get_maybe_display_roles(&mut self) -> Result<Option<DisplayRoles>, String> {
+        let key_roles = "display_roles";
+        let mut roles_data = [0u8; 8];
+
+        match self.prefs_nvs.get_raw(key_roles, &mut roles_data) {
+            Ok(Some(roles_bytes)) => from_bytes::<DisplayRoles>(roles_bytes)
+                .map(Some)
+                .map_err(|e| format!("Failed to deserialize display roles: {e:?}")),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!("Couldn't get key '{key_roles}' because: {e:?}",)),
+        }
+    }
+
+    /// Saves the message of the last panic of the firmware to NVS.
+    fn save_last_panic(&mut self, message: String) -> Result<(), AppError> {
+        let key_last_panic: &str = "last_panic";
+
+        match self.prefs_nvs.set_str(key_last_panic, &message) {
+            Ok(_) => log::info!("Key '{key_last_panic}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_last_panic}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves the message of the last panic of the firmware from NVS.
+    fn get_maybe_last_panic(&mut self) -> Result<Option<String>, String> {
+        let key_last_panic = "last_panic";
+        let mut last_panic_data = [0u8; 256];
+
+        match self.prefs_nvs.get_str(key_last_panic, &mut last_panic_data) {
+            Ok(Some(last_panic)) => Ok(Some(last_panic.to_string())),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!(
+                "Couldn't get key '{key_last_panic}' because: {e:?}",
+            )),
+        }
+    }
+
+    /// Saves the number of resets in a row that happened soon after boot to
+    /// NVS.
+    fn save_crash_count(&mut self, count: u8) -> Result<(), AppError> {
+        let key_crash_count: &str = "crash_count";
+
+        match self.prefs_nvs.set_u8(key_crash_count, count) {
+            Ok(_) => log::info!("Key '{key_crash_count}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_crash_count}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves the number of resets in a row that happened soon after boot
+    /// from NVS.
+    fn get_maybe_crash_count(&mut self) -> Result<Option<u8>, String> {
+        let key_crash_count = "crash_count";
+
+        match self.prefs_nvs.get_u8(key_crash_count) {
+            Ok(Some(count)) => Ok(Some(count)),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!(
+                "Couldn't get key '{key_crash_count}' because: {e:?}",
+            )),
+        }
+    }
+
+    /// Saves the log levels set per tag to NVS.
+    fn save_log_levels(&mut self, log_levels: LogLevels) -> Result<(), AppError> {
+        let key_log_levels: &str = "log_levels";
+
+        let log_levels_data = to_vec::<LogLevels, 384>(&log_levels)
+            .map_err(|e| AppError::Config(format!("Failed to serialize log levels: {e:?}")))?;
+
+        match self.prefs_nvs.set_raw(key_log_levels, &log_levels_data) {
+            Ok(_) => log::info!("Key '{key_log_levels}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_log_levels}' could not be updated in NVS: {e:?}",),
         };
 
         Ok(())
     }
 
-    /// Retrieves the hour format setting from NVS.
-    fn get_maybe_hour_format(&mut self) -> Result<Option<HourFormat>, String> {
-        let key_hour_format = "hour_format";
+    /// Retrieves the log levels set per tag from NVS.
+    fn get_maybe_log_levels(&mut self) -> Result<Option<LogLevels>, String> {
+        let key_log_levels = "log_levels";
+        let mut log_levels_data = [0u8; 384];
 
-        match self.prefs_nvs.get_u8(key_hour_format) {
-            Ok(Some(hour_format_value)) => Ok(Some(HourFormat::from(hour_format_value))),
+        match self.prefs_nvs.get_raw(key_log_levels, &mut log_levels_data) {
+            Ok(Some(log_levels_bytes)) => from_bytes::<LogLevels>(log_levels_bytes)
+                .map(Some)
+                .map_err(|e| format!("Failed to deserialize log levels: {e:?}")),
             Ok(None) => Ok(None),
             Err(e) => Err(format!(
-                "Couldn't get key '{key_hour_format}' because: {e:?}",
+                "Couldn't get key '{key_log_levels}' because: {e:?}",
             )),
         }
     }
+
+    /// Saves the GPIOs of the board to NVS.
+    fn save_board(&mut self, config: BoardConfig) -> Result<(), AppError> {
+        let key_board: &str = "board";
+
+        let board_data = to_vec::<BoardConfig, 32>(&config)
+            .map_err(|e| AppError::Config(format!("Failed to serialize board config: {e:?}")))?;
+
+        match self.prefs_nvs.set_raw(key_board, &board_data) {
+            Ok(_) => log::info!("Key '{key_board}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_board}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves the GPIOs of the board from NVS.
+    fn get_maybe_board(&mut self) -> Result<Option<BoardConfig>, String> {
+        let key_board = "board";
+        let mut board_data = [0u8; 32];
+
+        match self.prefs_nvs.get_raw(key_board, &mut board_data) {
+            Ok(Some(board_bytes)) => from_bytes::<BoardConfig>(board_bytes)
+                .map(Some)
+                .map_err(|e| format!("Failed to deserialize board config: {e:?}")),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!("Couldn't get key '{key_board}' because: {e:?}",)),
+        }
+    }
+
+    /// Deletes the GPIOs of the board from NVS, so the default pins apply
+    /// again.
+    fn delete_board(&mut self) -> Result<(), AppError> {
+        let key_board: &str = "board";
+
+        match self.prefs_nvs.remove(key_board) {
+            Ok(_) => log::info!("Key '{key_board}' deleted from NVS."),
+            Err(e) => log::error!("Key '{key_board}' could not be deleted from NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Saves the relay configuration to NVS.
+    fn save_relay(&mut self, config: RelayConfig) -> Result<(), AppError> {
+        let key_relay: &str = "relay";
+
+        let relay_data = to_vec::<RelayConfig, 16>(&config)
+            .map_err(|e| AppError::Config(format!("Failed to serialize relay config: {e:?}")))?;
+
+        match self.prefs_nvs.set_raw(key_relay, &relay_data) {
+            Ok(_) => log::info!("Key '{key_relay}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_relay}' could not be updated in NVS: {e:?}",),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves the relay configuration from NVS.
+    fn get_maybe_relay(&mut self) -> Result<Option<RelayConfig>, String> {
+        let key_relay = "relay";
+        let mut relay_data = [0u8; 16];
+
+        match self.prefs_nvs.get_raw(key_relay, &mut relay_data) {
+            Ok(Some(relay_bytes)) => from_bytes::<RelayConfig>(relay_bytes)
+                .map(Some)
+                .map_err(|e| format!("Failed to deserialize relay config: {e:?}")),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!("Couldn't get key '{key_relay}' because: {e:?}",)),
+        }
+    }
 }
@@ -0,0 +1,188 @@
+use super::AppStorage;
+use crate::{
+    error::AppError,
+    power::auto_dim::AutoDimConfig,
+    prefs::{hour_format::HourFormat, seconds_mode::SecondsMode},
+    service::app_storage::AppStorageSettingsService,
+    theme::Theme,
+};
+use bttf_core::blob;
+use postcard::from_bytes;
+use serde::{Deserialize, Serialize};
+
+/// The key of the [Settings] blob in the prefs namespace.
+const KEY_SETTINGS: &str = "settings";
+
+/// Max size of the serialized [Settings], enough for the longest timezone.
+const SETTINGS_LEN: usize = 192;
+
+/// The version of the layout of the [Settings] blob, saved as its first byte.
+///
+/// Bump it whenever a field is added, removed or reordered, and decode the
+/// previous layout in [decode_settings], since postcard isn't self-describing.
+const SETTINGS_VERSION: u8 = 1;
+
+/// Represents the settings stored together in a single NVS blob, instead of a
+/// key each, so they are read at boot in one go.
+///
+/// Each setting is `None` until it is first saved, so the defaults of the
+/// build still apply. Enums are stored as their `u8` value, like the other
+/// preferences, and read and written with the typed accessors.
+///
+/// ## Example
+/// ```rust
+/// app_storage.update_settings(|settings| settings.set_theme(Theme::Plutonium))?;
+/// let theme = app_storage.get_settings()?.theme().unwrap_or_default();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Settings {
+    timezone: Option<String>,
+    hour_format: Option<u8>,
+    theme: Option<u8>,
+    brightness: Option<u8>,
+    seconds_mode: Option<u8>,
+    auto_dim: Option<AutoDimConfig>,
+}
+
+impl Settings {
+    /// The timezone, or `None` for the `DEFAULT_TIMEZONE` of the build.
+    pub fn timezone(&self) -> Option<&str> {
+        self.timezone.as_deref()
+    }
+
+    pub fn set_timezone(&mut self, timezone: Option<String>) {
+        self.timezone = timezone;
+    }
+
+    pub fn hour_format(&self) -> Option<HourFormat> {
+        self.hour_format.map(HourFormat::from)
+    }
+
+    pub fn set_hour_format(&mut self, hour_format: HourFormat) {
+        self.hour_format = Some(hour_format as u8);
+    }
+
+    pub fn theme(&self) -> Option<Theme> {
+        self.theme.map(Theme::from)
+    }
+
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = Some(theme as u8);
+    }
+
+    /// The brightness level (0-7) of the displays set from the web portal.
+    pub fn brightness(&self) -> Option<u8> {
+        self.brightness
+    }
+
+    pub fn set_brightness(&mut self, brightness: u8) {
+        self.brightness = Some(brightness);
+    }
+
+    /// What the year display shows.
+    pub fn seconds_mode(&self) -> Option<SecondsMode> {
+        self.seconds_mode.map(SecondsMode::from)
+    }
+
+    pub fn set_seconds_mode(&mut self, mode: SecondsMode) {
+        self.seconds_mode = Some(mode as u8);
+    }
+
+    /// The night mode, see [AutoDimConfig].
+    pub fn auto_dim(&self) -> Option<AutoDimConfig> {
+        self.auto_dim
+    }
+
+    pub fn set_auto_dim(&mut self, config: AutoDimConfig) {
+        self.auto_dim = Some(config);
+    }
+}
+
+/// Decodes the [Settings] saved with the layout of the given version.
+fn decode_settings(version: u8, bytes: &[u8]) -> Result<Settings, String> {
+    match version {
+        SETTINGS_VERSION => from_bytes(bytes).map_err(|e| e.to_string()),
+        _ => Err(format!("Unknown settings version {version}")),
+    }
+}
+
+impl AppStorageSettingsService for AppStorage {
+    /// Retrieves the [Settings] from NVS, or the defaults when none are
+    /// saved yet.
+    fn get_settings(&mut self) -> Result<Settings, String> {
+        blob::load_versioned::<Settings, SETTINGS_LEN>(self, KEY_SETTINGS, decode_settings)
+            .map(Option::unwrap_or_default)
+    }
+
+    /// Saves the [Settings] to NVS, replacing the previous ones.
+    fn save_settings(&mut self, settings: &Settings) -> Result<(), AppError> {
+        blob::save_versioned::<Settings, SETTINGS_LEN>(
+            self,
+            KEY_SETTINGS,
+            SETTINGS_VERSION,
+            settings,
+        )
+        .map_err(AppError::Config)?;
+
+        log::info!("Key '{KEY_SETTINGS}' updated in NVS.");
+        Ok(())
+    }
+
+    /// Reads the [Settings], changes them and saves them back to NVS.
+    fn update_settings(&mut self, update: impl FnOnce(&mut Settings)) -> Result<(), AppError> {
+        let mut settings = self.get_settings().map_err(AppError::Config)?;
+        update(&mut settings);
+
+        self.save_settings(&settings)
+    }
+
+    /// Deletes the [Settings] from NVS, so the defaults of the build apply
+    /// again.
+    fn delete_settings(&mut self) -> Result<(), AppError> {
+        match self.prefs_nvs.remove(KEY_SETTINGS) {
+            Ok(_) => log::info!("Key '{KEY_SETTINGS}' deleted from NVS."),
+            Err(e) => log::error!("Key '{KEY_SETTINGS}' could not be deleted from NVS: {e:?}"),
+        };
+
+        Ok(())
+    }
+}
+
+impl AppStorage {
+    /// Moves the settings saved with a key each by the previous firmwares into
+    /// the [Settings] blob, once, so an update keeps them.
+    pub(super) fn migrate_legacy_settings(&mut self) -> Result<(), AppError> {
+        if self.prefs_nvs.contains(KEY_SETTINGS)? {
+            return Ok(());
+        }
+
+        let mut settings = Settings::default();
+
+        let mut timezone_data = [0u8; 100];
+        if let Ok(Some(timezone)) = self.tz_nvs.get_str("tz_info", &mut timezone_data) {
+            settings.set_timezone(Some(timezone.to_string()));
+        }
+
+        settings.hour_format = self.prefs_nvs.get_u8("hour_format").unwrap_or(None);
+        settings.theme = self.prefs_nvs.get_u8("theme").unwrap_or(None);
+        settings.seconds_mode = self.prefs_nvs.get_u8("seconds_mode").unwrap_or(None);
+
+        let mut auto_dim_data = [0u8; 16];
+        if let Ok(Some(auto_dim_bytes)) = self.prefs_nvs.get_raw("auto_dim", &mut auto_dim_data) {
+            settings.auto_dim = from_bytes::<AutoDimConfig>(auto_dim_bytes).ok();
+        }
+
+        // The legacy keys are only removed once the blob is written, so a
+        // failed write leaves them for the next boot to migrate again.
+        self.save_settings(&settings)?;
+
+        self.tz_nvs.remove("tz_info")?;
+        for key in ["hour_format", "theme", "seconds_mode", "auto_dim"] {
+            self.prefs_nvs.remove(key)?;
+        }
+
+        log::info!("Migrated the legacy settings to the '{KEY_SETTINGS}' blob: {settings:?}");
+
+        Ok(())
+    }
+}
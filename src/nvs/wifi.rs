@@ -1,5 +1,12 @@
 use super::AppStorage;
-use crate::{error::AppError, service::app_storage::AppStorageWifiService, wifi::WifiCredentials};
+use crate::{
+    error::AppError,
+    service::app_storage::AppStorageWifiService,
+    wifi::{
+        enterprise::{EnterpriseConfig, MAX_CA_CERT_LEN},
+        AuthMode, DisconnectReason, StaticIpConfig, WifiCredentials,
+    },
+};
 use postcard::{from_bytes, to_vec};
 
 pub const WIFI_NAMESPACE: &str = "wifi_ns";
@@ -111,4 +118,382 @@ impl AppStorageWifiService for AppStorage {
 
         Ok(())
     }
+
+    /// Saves the static IP configuration to NVS storage.
+    ///
+    /// ## Arguments
+    /// - `static_ip` - The [StaticIpConfig] to apply to the station netif on
+    ///   the next boot.
+    ///
+    /// ## Behavior
+    /// Stores the serialized configuration under the key `static_ip`.
+    /// If the operation succeeds, logs a success message; otherwise, logs an
+    /// error message.
+    fn save_static_ip(&mut self, static_ip: StaticIpConfig) -> Result<(), AppError> {
+        let key_static_ip: &str = "static_ip";
+
+        match self.wifi_nvs.set_raw(
+            key_static_ip,
+            &to_vec::<StaticIpConfig, 32>(&static_ip).unwrap(),
+        ) {
+            Ok(_) => log::info!("Key {key_static_ip} updated"),
+            Err(e) => log::error!("key {key_static_ip} not updated {e:?}"),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves the stored static IP configuration from NVS, if available.
+    ///
+    /// ## Returns
+    /// - `Ok(Some(StaticIpConfig))` - If a configuration is found and
+    ///   successfully deserialized.
+    /// - `Ok(None)` - If no configuration is stored, meaning DHCP is used.
+    /// - `Err(String)` - If an error occurs during retrieval or
+    ///   deserialization.
+    fn get_maybe_static_ip(&mut self) -> Result<Option<StaticIpConfig>, String> {
+        let key_static_ip = "static_ip";
+        let mut key_static_ip_data = [0u8; 32];
+
+        match self
+            .wifi_nvs
+            .get_raw(key_static_ip, &mut key_static_ip_data)
+        {
+            Ok(Some(static_ip_bytes)) => from_bytes::<StaticIpConfig>(static_ip_bytes)
+                .map(Some)
+                .map_err(|e| format!("Failed to deserialize static IP configuration: {e:?}")),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!("Couldn't get key {key_static_ip} because {e:?}")),
+        }
+    }
+
+    /// Deletes the stored static IP configuration from NVS, reverting the
+    /// station to DHCP on the next boot.
+    fn delete_static_ip(&mut self) -> Result<(), AppError> {
+        let key_static_ip: &str = "static_ip";
+
+        match self.wifi_nvs.remove(key_static_ip) {
+            Ok(_) => log::info!("Key {key_static_ip} deleted"),
+            Err(e) => log::error!("key {key_static_ip} not deleted {e:?}"),
+        };
+
+        Ok(())
+    }
+
+    /// Saves whether the stored network hides its SSID to NVS storage.
+    ///
+    /// ## Arguments
+    /// - `hidden` - `true` if the network doesn't broadcast its SSID.
+    ///
+    /// ## Behavior
+    /// Stores the flag under the key `hidden_ssid`, next to the credentials.
+    /// If the operation succeeds, logs a success message; otherwise, logs an
+    /// error message.
+    fn save_hidden_ssid(&mut self, hidden: bool) -> Result<(), AppError> {
+        let key_hidden_ssid: &str = "hidden_ssid";
+
+        match self.wifi_nvs.set_u8(key_hidden_ssid, hidden as u8) {
+            Ok(_) => log::info!("Key {key_hidden_ssid} updated"),
+            Err(e) => log::error!("key {key_hidden_ssid} not updated {e:?}"),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves whether the stored network hides its SSID from NVS storage.
+    ///
+    /// ## Returns
+    /// - `Ok(Some(bool))` if the flag is stored.
+    /// - `Ok(None)` if the key does not exist.
+    /// - `Err(String)` if the value can't be read.
+    fn get_maybe_hidden_ssid(&mut self) -> Result<Option<bool>, String> {
+        let key_hidden_ssid = "hidden_ssid";
+
+        match self.wifi_nvs.get_u8(key_hidden_ssid) {
+            Ok(Some(hidden)) => Ok(Some(hidden != 0)),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!("Couldn't get key {key_hidden_ssid} because {e:?}")),
+        }
+    }
+
+    /// Saves the security of the stored network to NVS storage.
+    ///
+    /// ## Arguments
+    /// - `auth_mode` - The [AuthMode] of the network.
+    ///
+    /// ## Behavior
+    /// Stores the mode under the key `auth_mode`, next to the credentials.
+    /// If the operation succeeds, logs a success message; otherwise, logs an
+    /// error message.
+    fn save_auth_mode(&mut self, auth_mode: AuthMode) -> Result<(), AppError> {
+        let key_auth_mode: &str = "auth_mode";
+
+        match self.wifi_nvs.set_u8(key_auth_mode, auth_mode as u8) {
+            Ok(_) => log::info!("Key {key_auth_mode} updated"),
+            Err(e) => log::error!("key {key_auth_mode} not updated {e:?}"),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves the security of the stored network from NVS storage.
+    ///
+    /// ## Returns
+    /// - `Ok(Some(AuthMode))` if the mode is stored.
+    /// - `Ok(None)` if the key does not exist.
+    /// - `Err(String)` if the value can't be read.
+    fn get_maybe_auth_mode(&mut self) -> Result<Option<AuthMode>, String> {
+        let key_auth_mode = "auth_mode";
+
+        match self.wifi_nvs.get_u8(key_auth_mode) {
+            Ok(Some(auth_mode)) => Ok(Some(AuthMode::from(auth_mode))),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!("Couldn't get key {key_auth_mode} because {e:?}")),
+        }
+    }
+
+    /// Saves the Wi-Fi country code to NVS storage.
+    ///
+    /// ## Arguments
+    /// - `country_code` - A two-letter country code, e.g. `DE`.
+    ///
+    /// ## Behavior
+    /// Stores the country code under the key `country_code`.
+    /// If the operation succeeds, logs a success message; otherwise, logs an
+    /// error message.
+    fn save_country_code(&mut self, country_code: String) -> Result<(), AppError> {
+        let key_country_code: &str = "country_code";
+
+        match self.wifi_nvs.set_str(key_country_code, &country_code) {
+            Ok(_) => log::info!("Key {key_country_code} updated"),
+            Err(e) => log::error!("key {key_country_code} not updated {e:?}"),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves the Wi-Fi country code from NVS storage.
+    ///
+    /// ## Returns
+    /// - `Ok(Some(String))` if a country code is stored.
+    /// - `Ok(None)` if the key does not exist.
+    /// - `Err(String)` if the value can't be read.
+    fn get_maybe_country_code(&mut self) -> Result<Option<String>, String> {
+        let key_country_code = "country_code";
+        let mut key_country_code_data = [0u8; 8];
+
+        match self
+            .wifi_nvs
+            .get_str(key_country_code, &mut key_country_code_data)
+        {
+            Ok(Some(country_code)) => Ok(Some(country_code.to_string())),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!("Couldn't get key {key_country_code} because {e:?}")),
+        }
+    }
+
+    /// Deletes the Wi-Fi country code from NVS, so the `WIFI_COUNTRY_CODE` of
+    /// the build applies again.
+    fn delete_country_code(&mut self) -> Result<(), AppError> {
+        let key_country_code: &str = "country_code";
+
+        match self.wifi_nvs.remove(key_country_code) {
+            Ok(_) => log::info!("Key {key_country_code} deleted"),
+            Err(e) => log::error!("key {key_country_code} not deleted {e:?}"),
+        };
+
+        Ok(())
+    }
+
+    /// Saves the reason of the connection failure that sent the device back
+    /// to provisioning, so the captive portal can explain it.
+    ///
+    /// ## Arguments
+    /// - `reason` - The [DisconnectReason] of the failure.
+    ///
+    /// ## Behavior
+    /// Stores the reason under the key `wifi_failure`.
+    /// If the operation succeeds, logs a success message; otherwise, logs an
+    /// error message.
+    fn save_wifi_failure(&mut self, reason: DisconnectReason) -> Result<(), AppError> {
+        let key_wifi_failure: &str = "wifi_failure";
+
+        match self.wifi_nvs.set_u8(key_wifi_failure, reason as u8) {
+            Ok(_) => log::info!("Key '{key_wifi_failure}' updated in NVS."),
+            Err(e) => log::error!("Key '{key_wifi_failure}' could not be updated in NVS: {e:?}"),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves the reason of the last connection failure from NVS storage.
+    ///
+    /// ## Returns
+    /// - `Ok(Some(DisconnectReason))` if a failure was saved.
+    /// - `Ok(None)` if the key does not exist.
+    /// - `Err(String)` if the value can't be read.
+    fn get_maybe_wifi_failure(&mut self) -> Result<Option<DisconnectReason>, String> {
+        let key_wifi_failure = "wifi_failure";
+
+        match self.wifi_nvs.get_u8(key_wifi_failure) {
+            Ok(Some(reason)) => Ok(Some(DisconnectReason::from(reason))),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!(
+                "Couldn't get key '{key_wifi_failure}' because: {e:?}"
+            )),
+        }
+    }
+
+    /// Deletes the reason of the last connection failure from NVS storage.
+    fn delete_wifi_failure(&mut self) -> Result<(), AppError> {
+        let key_wifi_failure: &str = "wifi_failure";
+
+        match self.wifi_nvs.remove(key_wifi_failure) {
+            Ok(_) => log::info!("Key {key_wifi_failure} deleted"),
+            Err(e) => log::error!("key {key_wifi_failure} not deleted {e:?}"),
+        };
+
+        Ok(())
+    }
+
+    /// Saves a pending WPS request to NVS storage.
+    ///
+    /// ## Behavior
+    /// Stores the request under the key `wps_request`, so it survives the
+    /// restart into WPS provisioning.
+    /// If the operation succeeds, logs a success message; otherwise, logs an
+    /// error message.
+    fn save_wps_request(&mut self) -> Result<(), AppError> {
+        let key_wps_request: &str = "wps_request";
+
+        match self.wifi_nvs.set_u8(key_wps_request, 1) {
+            Ok(_) => log::info!("Key {key_wps_request} updated"),
+            Err(e) => log::error!("key {key_wps_request} not updated {e:?}"),
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves whether WPS provisioning was requested from NVS storage.
+    ///
+    /// ## Returns
+    /// - `Ok(Some(bool))` if a request is stored.
+    /// - `Ok(None)` if the key does not exist.
+    /// - `Err(String)` if the value can't be read.
+    fn get_maybe_wps_request(&mut self) -> Result<Option<bool>, String> {
+        let key_wps_request = "wps_request";
+
+        match self.wifi_nvs.get_u8(key_wps_request) {
+            Ok(Some(requested)) => Ok(Some(requested != 0)),
+            Ok(None) => Ok(None),
+            Err(e) => Err(format!("Couldn't get key {key_wps_request} because {e:?}")),
+        }
+    }
+
+    /// Deletes the pending WPS request from NVS storage.
+    fn delete_wps_request(&mut self) -> Result<(), AppError> {
+        let key_wps_request: &str = "wps_request";
+
+        match self.wifi_nvs.remove(key_wps_request) {
+            Ok(_) => log::info!("Key {key_wps_request} deleted"),
+            Err(e) => log::error!("key {key_wps_request} not deleted {e:?}"),
+        };
+
+        Ok(())
+    }
+
+    /// Saves the WPA2-Enterprise credentials to NVS storage.
+    ///
+    /// ## Arguments
+    /// - `enterprise` - The [EnterpriseConfig] of the stored network.
+    ///
+    /// ## Behavior
+    /// Stores the serialized method, identity and username under the key
+    /// `eap_config`, and the optional CA certificate, which is too big for the
+    /// same blob, under the key `eap_ca_cert`.
+    /// If the operation succeeds, logs a success message; otherwise, logs an
+    /// error message.
+    fn save_enterprise_config(&mut self, enterprise: EnterpriseConfig) -> Result<(), AppError> {
+        let key_eap_config: &str = "eap_config";
+        let key_eap_ca_cert: &str = "eap_ca_cert";
+
+        let ca_cert = enterprise.ca_cert.clone();
+        let key_eap_config_data = EnterpriseConfig {
+            ca_cert: None,
+            ..enterprise
+        };
+
+        match to_vec::<EnterpriseConfig, 160>(&key_eap_config_data) {
+            Ok(bytes) => match self.wifi_nvs.set_raw(key_eap_config, &bytes) {
+                Ok(_) => log::info!("Key {key_eap_config} updated"),
+                Err(e) => log::error!("key {key_eap_config} not updated {e:?}"),
+            },
+            Err(e) => log::error!("key {key_eap_config} not serialized {e:?}"),
+        };
+
+        match ca_cert {
+            Some(ca_cert) => match self.wifi_nvs.set_str(key_eap_ca_cert, &ca_cert) {
+                Ok(_) => log::info!("Key {key_eap_ca_cert} updated"),
+                Err(e) => log::error!("key {key_eap_ca_cert} not updated {e:?}"),
+            },
+            None => {
+                self.wifi_nvs.remove(key_eap_ca_cert).ok();
+            }
+        };
+
+        Ok(())
+    }
+
+    /// Retrieves the stored WPA2-Enterprise credentials from NVS, if
+    /// available.
+    ///
+    /// ## Returns
+    /// - `Ok(Some(EnterpriseConfig))` - If the stored network uses
+    ///   WPA2-Enterprise.
+    /// - `Ok(None)` - If the stored network uses a regular password.
+    /// - `Err(String)` - If an error occurs during retrieval or
+    ///   deserialization.
+    fn get_maybe_enterprise_config(&mut self) -> Result<Option<EnterpriseConfig>, String> {
+        let key_eap_config = "eap_config";
+        let key_eap_ca_cert = "eap_ca_cert";
+        let mut key_eap_config_data = [0u8; 160];
+
+        let enterprise = match self
+            .wifi_nvs
+            .get_raw(key_eap_config, &mut key_eap_config_data)
+        {
+            Ok(Some(eap_config_bytes)) => from_bytes::<EnterpriseConfig>(eap_config_bytes)
+                .map_err(|e| format!("Failed to deserialize enterprise configuration: {e:?}"))?,
+            Ok(None) => return Ok(None),
+            Err(e) => return Err(format!("Couldn't get key {key_eap_config} because {e:?}")),
+        };
+
+        let mut key_eap_ca_cert_data = vec![0u8; MAX_CA_CERT_LEN + 1];
+
+        let ca_cert = match self
+            .wifi_nvs
+            .get_str(key_eap_ca_cert, &mut key_eap_ca_cert_data)
+        {
+            Ok(ca_cert) => ca_cert.map(|ca_cert| ca_cert.to_string()),
+            Err(e) => return Err(format!("Couldn't get key {key_eap_ca_cert} because {e:?}")),
+        };
+
+        Ok(Some(EnterpriseConfig {
+            ca_cert,
+            ..enterprise
+        }))
+    }
+
+    /// Deletes the stored WPA2-Enterprise credentials from NVS, for networks
+    /// that use a regular password.
+    fn delete_enterprise_config(&mut self) -> Result<(), AppError> {
+        for key in ["eap_config", "eap_ca_cert"] {
+            match self.wifi_nvs.remove(key) {
+                Ok(_) => log::info!("Key {key} deleted"),
+                Err(e) => log::error!("key {key} not deleted {e:?}"),
+            };
+        }
+
+        Ok(())
+    }
 }
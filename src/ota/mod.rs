@@ -0,0 +1,210 @@
+use crate::{
+    error::AppError,
+    health::{self, watchdog, HealthState, Subsystem},
+};
+use esp_idf_svc::{
+    io::{Read, Write},
+    ota::{EspOta, EspOtaUpdate, Slot, SlotState},
+};
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// How long a new firmware has, from its first boot, to answer on the web
+/// portal before it is rolled back.
+pub const VALIDATION_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// How often the health of a new firmware is checked, see
+/// [first_boot_check_job].
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Size of the chunks the uploaded firmware is written in.
+const CHUNK_LEN: usize = 4096;
+
+/// Represents an app partition, as returned by the `/api/ota` endpoint.
+#[derive(Clone, Debug, Serialize)]
+pub struct SlotInfo {
+    pub label: String,
+    /// `valid`, `unverified` while the first boot is checked, or `invalid`
+    /// once rolled back.
+    pub state: String,
+    /// The version of the firmware in the partition, if any.
+    pub version: Option<String>,
+}
+
+impl From<Slot> for SlotInfo {
+    fn from(slot: Slot) -> Self {
+        Self {
+            label: slot.label.to_string(),
+            state: format!("{:?}", slot.state).to_lowercase(),
+            version: slot.firmware.map(|firmware| firmware.version.to_string()),
+        }
+    }
+}
+
+/// Represents the OTA status returned by the `/api/ota` endpoint.
+#[derive(Clone, Debug, Serialize)]
+pub struct OtaStatus {
+    /// The partition the firmware runs from.
+    pub running: SlotInfo,
+    /// The partition the next upload is written to.
+    pub update: SlotInfo,
+    /// The partition of the last firmware rolled back, if any.
+    pub last_invalid: Option<SlotInfo>,
+}
+
+/// Retrieves the [OtaStatus] of the app partitions.
+pub fn get_status() -> Result<OtaStatus, AppError> {
+    let ota = EspOta::new()?;
+
+    Ok(OtaStatus {
+        running: ota.get_running_slot()?.into(),
+        update: ota.get_update_slot()?.into(),
+        last_invalid: ota.get_last_invalid_slot()?.map(SlotInfo::from),
+    })
+}
+
+/// Writes a firmware image to the partition that is not running, and boots
+/// it on the next restart.
+///
+/// The new firmware is checked on its first boot by [first_boot_check_job],
+/// and the bootloader goes back to the previous one if it isn't validated.
+///
+/// ## Arguments
+/// - `body` - The firmware image, e.g. the body of `POST /api/ota`.
+/// - `len` - The size of the image.
+///
+/// ## Returns
+/// - `Err(AppError)`: If the image is incomplete or invalid, in which case the
+///   running firmware keeps booting.
+///
+/// ## Example
+/// ```rust
+/// let len = request.content_len().unwrap_or(0) as usize;
+/// ota::write_update(&mut request, len)?;
+/// ```
+pub fn write_update<R>(body: &mut R, len: usize) -> Result<(), AppError>
+where
+    R: Read,
+    AppError: From<R::Error>,
+{
+    let mut ota = EspOta::new()?;
+    let mut update = ota.initiate_update()?;
+
+    log::info!("Writing a {len} bytes firmware update...");
+
+    if let Err(e) = copy_image(body, &mut update, len) {
+        log::error!("Firmware update failed: {e:?}");
+        update.abort()?;
+        return Err(e);
+    }
+
+    update.complete()?;
+    log::info!("Firmware update written, it boots on the next restart");
+
+    Ok(())
+}
+
+/// Copies `len` bytes of the image to the update partition, in [CHUNK_LEN]
+/// chunks.
+fn copy_image<R>(body: &mut R, update: &mut EspOtaUpdate<'_>, len: usize) -> Result<(), AppError>
+where
+    R: Read,
+    AppError: From<R::Error>,
+{
+    let mut buf = vec![0u8; CHUNK_LEN];
+    let mut written = 0;
+
+    while written < len {
+        let read = body.read(&mut buf)?;
+        if read == 0 {
+            return Err(AppError::Server(format!(
+                "Firmware image truncated at {written} of {len} bytes"
+            )));
+        }
+
+        update.write_all(&buf[..read])?;
+        written += read;
+    }
+
+    Ok(())
+}
+
+/// Returns the job that checks a firmware booting for the first time after an
+/// update, and rolls it back if it doesn't come up. It is meant to run every
+/// [CHECK_INTERVAL].
+///
+/// The firmware is marked valid once the web portal answers over the loopback
+/// interface, so it keeps booting. The Wi-Fi isn't required, so a router that
+/// is offline at the first boot doesn't roll back a good firmware. Otherwise,
+/// after [VALIDATION_TIMEOUT], it is marked invalid and the device restarts
+/// into the previous firmware. A reset before that, e.g. a panic, rolls it
+/// back too.
+///
+/// The running slot is checked when the job is created, and the job does
+/// nothing when the firmware was already validated. The [EspOta] is only
+/// taken while the job runs, so `/api/ota` keeps working meanwhile.
+///
+/// ## Example
+/// ```rust
+/// scheduler.add(
+///     "first boot check",
+///     Schedule::Every(ota::CHECK_INTERVAL),
+///     ota::first_boot_check_job(),
+/// );
+/// ```
+pub fn first_boot_check_job() -> impl FnMut() + Send {
+    let mut pending = match EspOta::new().and_then(|ota| ota.get_running_slot()) {
+        Ok(slot) if matches!(slot.state, SlotState::Unverified) => {
+            log::warn!(
+                "First boot of the firmware in '{}', validating it...",
+                slot.label
+            );
+            true
+        }
+        Ok(_) => false,
+        Err(e) => {
+            log::error!("Failed to check the running firmware: {e:?}");
+            false
+        }
+    };
+    let started = Instant::now();
+
+    move || {
+        if !pending {
+            return;
+        }
+
+        let healthy = is_healthy();
+        if !healthy && started.elapsed() < VALIDATION_TIMEOUT {
+            return;
+        }
+
+        let mut ota = match EspOta::new() {
+            Ok(ota) => ota,
+            Err(e) => {
+                log::error!("Failed to check the running firmware: {e:?}");
+                return;
+            }
+        };
+
+        if healthy {
+            match ota.mark_running_slot_valid() {
+                Ok(()) => log::info!("Firmware validated, the update is kept"),
+                Err(e) => log::error!("Failed to validate the firmware: {e:?}"),
+            }
+            pending = false;
+            return;
+        }
+
+        log::error!("Firmware not healthy after {VALIDATION_TIMEOUT:?}, rolling back...");
+        let e = ota.mark_running_slot_invalid_and_reboot();
+        log::error!("Failed to roll back the firmware: {e:?}");
+        pending = false;
+    }
+}
+
+/// Whether the web portal started and answers over the loopback interface.
+fn is_healthy() -> bool {
+    health::get_state(Subsystem::WebPortal) == Some(HealthState::Ok)
+        && watchdog::check_http_server().is_ok()
+}
@@ -0,0 +1,228 @@
+use crate::{
+    error::AppError,
+    module::{
+        display_queue::{self, DisplayCommand},
+        led_strip::SharedLedStrip,
+    },
+    nvs::SharedAppStorage,
+    prefs, presence,
+    service::app_storage::AppStoragePrefsService,
+    theme::AppTheme,
+    time::{self, sun},
+    weather::WeatherLocation,
+};
+use serde::{Deserialize, Serialize};
+use std::sync::{
+    atomic::{AtomicBool, AtomicU8, Ordering},
+    Arc, Mutex, PoisonError,
+};
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable to hold the auto-dimming configuration.
+    pub static ref AUTO_DIM_CONFIG: Arc<Mutex<AutoDimConfig>> =
+        Arc::new(Mutex::new(AutoDimConfig::default()));
+}
+
+/// Whether the displays and the LED strip are dimmed for the night.
+static DIMMED: AtomicBool = AtomicBool::new(false);
+
+/// Whether the night mode was switched by hand, see [toggle_night_mode]. The
+/// [auto_dim_job] leaves it as it is until the next sunrise or sunset.
+static MANUAL: AtomicBool = AtomicBool::new(false);
+
+/// The brightness of the displays before they were dimmed, restored when the
+/// night mode ends.
+static DAY_BRIGHTNESS: AtomicU8 = AtomicU8::new(0);
+
+/// Represents where the sunrise and the sunset are computed for, and how much
+/// the displays and the LED strip are dimmed between them.
+///
+/// ## Example
+/// ```rust
+/// let config: AutoDimConfig = serde_json::from_str(
+///     "{\"location\":{\"latitude\":34.14,\"longitude\":-118.35},\"night_brightness\":1,\"night_led\":30}",
+/// )
+/// .unwrap();
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoDimConfig {
+    /// The location, or `None` to disable the auto-dimming.
+    pub location: Option<WeatherLocation>,
+    /// The brightness level (0-7) of the displays after sunset. Displays that
+    /// are already dimmer are left as they are.
+    pub night_brightness: u8,
+    /// The brightness of the LED strip after sunset, in % of the day one.
+    pub night_led: u8,
+}
+
+impl Default for AutoDimConfig {
+    fn default() -> Self {
+        Self {
+            location: None,
+            night_brightness: 1,
+            night_led: 30,
+        }
+    }
+}
+
+impl AutoDimConfig {
+    /// Checks that the location is on Earth and that the night brightness of
+    /// the displays and the LED strip are within range.
+    pub fn is_valid(&self) -> bool {
+        self.night_brightness <= 7
+            && self.night_led <= 100
+            && self.location.map_or(true, |location| {
+                (-90.0..=90.0).contains(&location.latitude)
+                    && (-180.0..=180.0).contains(&location.longitude)
+            })
+    }
+}
+
+/// Retrieves the current auto-dimming configuration in a thread-safe way.
+pub fn get_auto_dim_config() -> AutoDimConfig {
+    *AUTO_DIM_CONFIG.lock().unwrap()
+}
+
+/// Updates the global auto-dimming configuration in a thread-safe way.
+pub fn set_auto_dim_config(config: AutoDimConfig) {
+    let mut config_guard = AUTO_DIM_CONFIG.lock().unwrap();
+    *config_guard = config;
+}
+
+/// Retrieves whether the displays and the LED strip are dimmed for the
+/// night.
+pub fn is_dimmed() -> bool {
+    DIMMED.load(Ordering::Relaxed)
+}
+
+/// Returns a [Scheduler](crate::scheduler::Scheduler) job that dims the
+/// displays and the LED strip at sunset and restores them at sunrise, at the
+/// location of the [AutoDimConfig]. It is meant to run every minute.
+///
+/// The brightness of the displays at sunset is restored at sunrise. The
+/// switch waits while the away mode or photo mode hold the brightness, and
+/// the displays are restored when the auto-dimming is disabled. A night mode
+/// switched by hand is kept until the next sunrise or sunset.
+///
+/// ## Arguments
+/// - `led_strip` - A [SharedLedStrip] instance.
+/// - `storage` - A [SharedAppStorage] instance used to read the theme.
+pub fn auto_dim_job(led_strip: SharedLedStrip, storage: SharedAppStorage) -> impl FnMut() + Send {
+    let mut was_night = None;
+
+    move || {
+        let config = get_auto_dim_config();
+        let night = config.location.is_some_and(|location| {
+            !sun::is_daylight(time::get_timestamp(), location.latitude, location.longitude)
+        });
+
+        if was_night
+            .replace(night)
+            .is_some_and(|was_night| was_night != night)
+        {
+            MANUAL.store(false, Ordering::Relaxed);
+        }
+
+        if MANUAL.load(Ordering::Relaxed)
+            || night == is_dimmed()
+            || presence::is_away()
+            || prefs::photo_mode::is_photo_mode()
+        {
+            return;
+        }
+
+        if night {
+            log::info!("The sun has set, dimming the displays and the LED strip");
+        } else {
+            log::info!("The sun has risen, restoring the displays and the LED strip");
+        }
+
+        if let Err(e) = switch_night(night, &led_strip, &storage) {
+            log::error!("Failed to switch the auto-dimming: {e:?}");
+        }
+    }
+}
+
+/// Switches the night mode by hand, e.g. from the touch pad, dimming the
+/// displays and the LED strip as at sunset, or restoring them.
+///
+/// The [auto_dim_job] keeps it until the next sunrise or sunset. Nothing
+/// changes while the away mode or photo mode hold the brightness.
+///
+/// ## Arguments
+/// - `led_strip` - A [SharedLedStrip] instance.
+/// - `storage` - A [SharedAppStorage] instance used to read the theme.
+///
+/// ## Returns
+/// - `Err(AppError)`: If the theme can't be applied to the LED strip.
+pub fn toggle_night_mode(
+    led_strip: &SharedLedStrip,
+    storage: &SharedAppStorage,
+) -> Result<(), AppError> {
+    if presence::is_away() || prefs::photo_mode::is_photo_mode() {
+        log::warn!("The brightness is held by the away mode or the photo mode");
+        return Ok(());
+    }
+
+    let night = !is_dimmed();
+    log::info!("Night mode switched {}", if night { "on" } else { "off" });
+
+    MANUAL.store(true, Ordering::Relaxed);
+    switch_night(night, led_strip, storage)
+}
+
+/// Dims the displays and the LED strip for the night, keeping the brightness
+/// of the day, or restores them.
+fn switch_night(
+    night: bool,
+    led_strip: &SharedLedStrip,
+    storage: &SharedAppStorage,
+) -> Result<(), AppError> {
+    let result = if night {
+        let config = get_auto_dim_config();
+        let day_brightness = display_queue::brightness();
+        DAY_BRIGHTNESS.store(day_brightness, Ordering::Relaxed);
+
+        switch_brightness(
+            led_strip,
+            storage,
+            day_brightness.min(config.night_brightness),
+            config.night_led,
+        )
+    } else {
+        let day_brightness = DAY_BRIGHTNESS.load(Ordering::Relaxed);
+        switch_brightness(led_strip, storage, day_brightness, 100)
+    };
+
+    DIMMED.store(night, Ordering::Relaxed);
+
+    result
+}
+
+/// Sets the brightness of the displays and of the LED strip, and applies the
+/// theme again so the LED strip picks it up, unless the wake light plays.
+fn switch_brightness(
+    led_strip: &SharedLedStrip,
+    storage: &SharedAppStorage,
+    brightness: u8,
+    led_percent: u8,
+) -> Result<(), AppError> {
+    display_queue::send(DisplayCommand::SetBrightness(brightness));
+
+    let mut led_strip = led_strip.lock().unwrap_or_else(PoisonError::into_inner);
+    led_strip.set_dimming(led_percent as f32 / 100.0);
+
+    if time::get_second_of_day().is_ok_and(prefs::wake_light::is_active) {
+        return Ok(());
+    }
+
+    let theme = storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_theme()
+        .unwrap_or(None)
+        .unwrap_or_default();
+
+    led_strip.apply_theme(&theme)
+}
@@ -0,0 +1,220 @@
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "battery")]
+use crate::{
+    error::AppError,
+    module::display_queue::{self, DisplayCommand},
+    prefs,
+};
+#[cfg(feature = "battery")]
+use esp_idf_svc::hal::{
+    adc::{
+        attenuation::DB_11,
+        oneshot::{config::AdcChannelConfig, AdcChannelDriver, AdcDriver},
+        ADC1,
+    },
+    gpio::Gpio34,
+};
+#[cfg(feature = "battery")]
+use std::time::Duration;
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable that holds the last battery reading, if a battery is monitored.
+    pub static ref BATTERY_STATUS: Arc<Mutex<Option<BatteryStatus>>> = Arc::new(Mutex::new(None));
+}
+
+/// Battery voltage in mV considered empty (0%).
+#[cfg(feature = "battery")]
+const EMPTY_MV: u32 = 3300;
+
+/// Battery voltage in mV considered full (100%).
+#[cfg(feature = "battery")]
+const FULL_MV: u32 = 4200;
+
+/// Charge percentage under which the battery is considered low.
+#[cfg(feature = "battery")]
+const LOW_BATTERY_PERCENT: u8 = 15;
+
+/// Charge percentage the battery must reach again before it is no longer
+/// considered low, so the brightness doesn't bounce around the threshold.
+#[cfg(feature = "battery")]
+const RECOVERED_PERCENT: u8 = LOW_BATTERY_PERCENT + 5;
+
+/// How often the low-battery warning is scrolled, in minutes.
+const WARNING_INTERVAL: u32 = 10;
+
+/// The text scrolled on the displays while the battery is low.
+const LOW_BATTERY_MESSAGE: &str = "LO BAT";
+
+/// Ratio of the voltage divider between the battery and the ADC pin, e.g. two
+/// equal resistors halve the battery voltage.
+#[cfg(feature = "battery")]
+const DIVIDER_RATIO: u32 = 2;
+
+/// Number of ADC samples averaged on every reading.
+#[cfg(feature = "battery")]
+const SAMPLES: u32 = 16;
+
+/// How often the battery voltage is read.
+#[cfg(feature = "battery")]
+pub const READ_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Represents a battery reading.
+///
+/// ## Example
+/// ```json
+/// {"voltage_mv":3912,"percent":68,"low":false}
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct BatteryStatus {
+    pub voltage_mv: u32,
+    pub percent: u8,
+    pub low: bool,
+}
+
+impl BatteryStatus {
+    /// Builds a reading from the battery voltage, estimating the charge
+    /// linearly between [EMPTY_MV] and [FULL_MV].
+    ///
+    /// `was_low` keeps a low battery low until it recovers past a margin.
+    #[cfg(feature = "battery")]
+    fn from_voltage(voltage_mv: u32, was_low: bool) -> Self {
+        let percent = (voltage_mv.clamp(EMPTY_MV, FULL_MV) - EMPTY_MV) * 100 / (FULL_MV - EMPTY_MV);
+        let percent = percent as u8;
+
+        let low = if was_low {
+            percent < RECOVERED_PERCENT
+        } else {
+            percent < LOW_BATTERY_PERCENT
+        };
+
+        Self {
+            voltage_mv,
+            percent,
+            low,
+        }
+    }
+}
+
+/// Retrieves the last battery reading in a thread-safe way, or `None` when no
+/// battery is monitored.
+pub fn get_battery_status() -> Option<BatteryStatus> {
+    *BATTERY_STATUS.lock().unwrap()
+}
+
+/// Updates the global battery reading in a thread-safe way.
+#[cfg(feature = "battery")]
+fn set_battery_status(status: BatteryStatus) {
+    let mut battery_status_guard = BATTERY_STATUS.lock().unwrap();
+    *battery_status_guard = Some(status);
+}
+
+/// Returns the low-battery warning if the battery is low and the warning is
+/// due in the given minute of the day.
+pub fn get_due_warning(minute_of_day: u32) -> Option<String> {
+    match get_battery_status() {
+        Some(status) if status.low && minute_of_day % WARNING_INTERVAL == 0 => {
+            Some(LOW_BATTERY_MESSAGE.to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Reads the battery voltage through a voltage divider on `GPIO34`.
+#[cfg(feature = "battery")]
+pub struct BatteryMonitor {
+    channel: AdcChannelDriver<'static, Gpio34, AdcDriver<'static, ADC1>>,
+}
+
+#[cfg(feature = "battery")]
+impl BatteryMonitor {
+    /// Creates a new [BatteryMonitor] instance.
+    ///
+    /// ## Arguments
+    /// - `adc`: The ADC1 peripheral.
+    /// - `pin`: The ADC pin connected to the voltage divider.
+    ///
+    /// ## Returns
+    /// A `Result` containing the [BatteryMonitor] on success, or an
+    /// [AppError] on failure.
+    ///
+    /// ## Example
+    /// ```rust
+    /// let battery = BatteryMonitor::new(peripherals.adc1, peripherals.pins.gpio34)?;
+    /// ```
+    pub fn new(adc: ADC1, pin: Gpio34) -> Result<Self, AppError> {
+        let config = AdcChannelConfig {
+            attenuation: DB_11,
+            ..Default::default()
+        };
+
+        let channel = AdcChannelDriver::new(AdcDriver::new(adc)?, pin, &config)?;
+
+        Ok(Self { channel })
+    }
+
+    /// Reads the battery voltage in mV, averaging several samples.
+    pub fn read_voltage(&mut self) -> Result<u32, AppError> {
+        let mut total = 0;
+        for _ in 0..SAMPLES {
+            total += self.channel.read()? as u32;
+        }
+
+        Ok(total / SAMPLES * DIVIDER_RATIO)
+    }
+}
+
+/// Returns the job that reads the battery, updating the global
+/// [BatteryStatus]. It is meant to run every [READ_INTERVAL].
+///
+/// When the battery becomes low the displays are dimmed to the lowest
+/// brightness, and the previous level is restored once it recovers.
+///
+/// ## Arguments
+/// - `battery` - The [BatteryMonitor] to read.
+///
+/// ## Example
+/// ```rust
+/// scheduler.add(
+///     "battery",
+///     Schedule::Every(power::battery::READ_INTERVAL),
+///     power::battery::battery_job(battery),
+/// );
+/// ```
+#[cfg(feature = "battery")]
+pub fn battery_job(mut battery: BatteryMonitor) -> impl FnMut() + Send {
+    let mut saved_brightness = None;
+
+    move || match battery.read_voltage() {
+        Ok(voltage_mv) => {
+            let was_low = get_battery_status().is_some_and(|status| status.low);
+            let status = BatteryStatus::from_voltage(voltage_mv, was_low);
+            set_battery_status(status);
+
+            if status.low != was_low {
+                log::warn!(
+                    "Battery {} ({} mV, {}%)",
+                    if status.low { "low" } else { "recovered" },
+                    status.voltage_mv,
+                    status.percent
+                );
+            }
+
+            if !prefs::photo_mode::is_photo_mode() {
+                match (status.low, saved_brightness) {
+                    (true, None) => {
+                        saved_brightness = Some(display_queue::brightness());
+                        display_queue::send(DisplayCommand::SetBrightness(0));
+                    }
+                    (false, Some(brightness)) => {
+                        saved_brightness = None;
+                        display_queue::send(DisplayCommand::SetBrightness(brightness));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Err(e) => log::error!("Failed to read the battery voltage: {e:?}"),
+    }
+}
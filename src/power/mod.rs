@@ -0,0 +1,137 @@
+use crate::prefs;
+use battery::BatteryStatus;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+pub mod auto_dim;
+pub mod battery;
+pub mod power_loss;
+pub mod resume;
+pub mod sleep;
+
+/// How long each of the two strobed brightness levels is held. One FreeRTOS
+/// tick with the default 100 Hz tick rate.
+pub const STROBE_PERIOD: Duration = Duration::from_millis(10);
+
+/// Fraction of time the TM1637 drives the segments at each brightness level
+/// (0-7), from its datasheet pulse widths.
+const DISPLAY_DUTY: [f32; 8] = [
+    1.0 / 16.0,
+    2.0 / 16.0,
+    4.0 / 16.0,
+    10.0 / 16.0,
+    11.0 / 16.0,
+    12.0 / 16.0,
+    13.0 / 16.0,
+    14.0 / 16.0,
+];
+
+/// Estimated current in mA drawn by a lit segment at full duty.
+const SEGMENT_MA: f32 = 2.5;
+
+/// Estimated current in mA drawn by each TM1637 controller, segments off.
+const DISPLAY_IDLE_MA: f32 = 2.0;
+
+/// Number of seven-segment displays of the clock.
+const DISPLAY_COUNT: f32 = 3.0;
+
+/// Estimated current in mA drawn by a WS2812 color channel at full level.
+const LED_CHANNEL_MA: f32 = 20.0;
+
+/// Estimated current in mA drawn by each WS2812 LED, all channels off.
+const LED_IDLE_MA: f32 = 1.0;
+
+/// Estimated current in mA drawn by the ESP32 with Wi-Fi connected.
+const BOARD_MA: f32 = 80.0;
+
+/// Estimated current in mA drawn by the ESP32 with Wi-Fi connected, in
+/// power-save mode.
+const BOARD_POWER_SAVE_MA: f32 = 30.0;
+
+/// Represents the body of a `POST /api/power` request, switching the
+/// low-power display mode and the power-save mode. A mode left out is kept as
+/// is.
+///
+/// ## Example
+/// ```rust
+/// let power_request: PowerRequest = serde_json::from_str("{\"low_power\":true}").unwrap();
+/// ```
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct PowerRequest {
+    pub low_power: Option<bool>,
+    pub power_save: Option<bool>,
+}
+
+/// Represents the current estimate returned by the `/api/power` endpoint.
+///
+/// The figures are estimates computed from the lit segments, the brightness
+/// and the theme colors, not measurements.
+///
+/// ## Example
+/// ```json
+/// {"low_power":true,"power_save":false,"display_ma":38.1,"led_strip_ma":40.2,"board_ma":80.0,"total_ma":158.3,"battery":null}
+/// ```
+#[derive(Clone, Debug, Serialize)]
+pub struct PowerReport {
+    pub low_power: bool,
+    /// Whether the power-save mode is enabled, see
+    /// [apply_power_save](sleep::apply_power_save).
+    pub power_save: bool,
+    pub display_ma: f32,
+    pub led_strip_ma: f32,
+    pub board_ma: f32,
+    pub total_ma: f32,
+    /// The last battery reading, on builds with the `battery` feature.
+    pub battery: Option<BatteryStatus>,
+}
+
+impl PowerReport {
+    /// Estimates the current drawn by the clock.
+    ///
+    /// ## Arguments
+    /// - `lit_segments`: The number of segments lit on all displays.
+    /// - `brightness`: The brightness level (0-7) of the displays.
+    /// - `num_leds`: The number of LEDs of the strip.
+    /// - `led_levels`: The sum of every color channel (0-255) of the strip.
+    pub fn estimate(lit_segments: u32, brightness: u8, num_leds: u8, led_levels: u32) -> Self {
+        let low_power = strobe_enabled(brightness);
+        let level = brightness.min(7) as usize;
+
+        let duty = if low_power {
+            (DISPLAY_DUTY[level] + DISPLAY_DUTY[level - 1]) / 2.0
+        } else {
+            DISPLAY_DUTY[level]
+        };
+
+        let display_ma = DISPLAY_IDLE_MA * DISPLAY_COUNT + lit_segments as f32 * SEGMENT_MA * duty;
+        let led_strip_ma =
+            LED_IDLE_MA * num_leds as f32 + led_levels as f32 / 255.0 * LED_CHANNEL_MA;
+
+        let power_save = prefs::power_save::is_power_save();
+        let board_ma = if power_save {
+            BOARD_POWER_SAVE_MA
+        } else {
+            BOARD_MA
+        };
+
+        Self {
+            low_power,
+            power_save,
+            display_ma,
+            led_strip_ma,
+            board_ma,
+            total_ma: display_ma + led_strip_ma + board_ma,
+            battery: battery::get_battery_status(),
+        }
+    }
+}
+
+/// Tells whether the displays should be strobed at the given brightness, by
+/// the display task, see
+/// [run_display_task](crate::module::display_queue::run_display_task).
+///
+/// The strobe needs a level below the current one and pauses in photo mode,
+/// where the brightness must stay constant.
+pub fn strobe_enabled(brightness: u8) -> bool {
+    prefs::low_power::is_low_power() && !prefs::photo_mode::is_photo_mode() && brightness > 0
+}
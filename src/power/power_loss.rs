@@ -0,0 +1,103 @@
+use crate::{
+    error::AppError, nvs::SharedAppStorage, service::app_storage::AppStoragePrefsService, time,
+};
+#[cfg(feature = "power-loss")]
+use esp_idf_svc::hal::{
+    gpio::{InputPin, InterruptType, PinDriver, Pull},
+    peripheral::Peripheral,
+    task::notification::Notification,
+};
+#[cfg(feature = "power-loss")]
+use std::{num::NonZeroU32, sync::PoisonError};
+
+/// Timestamps before this one (2024-01-01) mean the clock was never set.
+const MIN_VALID_TIMESTAMP: i64 = 1_704_067_200;
+
+/// Persists the volatile state that would be lost on a power cut.
+///
+/// Only the current time is saved for now, so the clock can show an
+/// approximate time when power comes back, before SNTP syncs.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the state.
+#[cfg(feature = "power-loss")]
+pub fn save_state(storage: &SharedAppStorage) -> Result<(), AppError> {
+    let timestamp = time::get_timestamp();
+
+    if timestamp >= MIN_VALID_TIMESTAMP {
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_last_time(timestamp)?;
+    }
+
+    Ok(())
+}
+
+/// Restores the state saved by [save_state] before a power cut, if any.
+///
+/// The saved time is only applied if the system time wasn't set yet, and is
+/// removed afterwards so a later boot doesn't go back to it.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to read the state.
+///
+/// ## Example
+/// ```rust
+/// power::power_loss::restore_state(&app_storage)?;
+/// ```
+pub fn restore_state(storage: &SharedAppStorage) -> Result<(), AppError> {
+    let mut storage = storage.lock().unwrap_or_else(PoisonError::into_inner);
+
+    if let Some(timestamp) = storage.get_maybe_last_time().unwrap_or(None) {
+        if time::get_timestamp() < MIN_VALID_TIMESTAMP {
+            time::set_system_time(timestamp);
+            log::info!("Restored the time saved before the power loss");
+        }
+
+        storage.delete_last_time()?;
+    }
+
+    Ok(())
+}
+
+/// Watches a power-good signal from the power supply and saves the volatile
+/// state with [save_state] as soon as it drops.
+///
+/// The pin is expected to stay high while the supply is fine. The bulk
+/// capacitance of the supply must keep the ESP32 alive for the few
+/// milliseconds the NVS write takes. Runs forever, so it is meant to be
+/// spawned on its own thread.
+///
+/// ## Arguments
+/// - `pin` - The GPIO connected to the power-good signal.
+/// - `storage` - A [SharedAppStorage] instance used to save the state.
+#[cfg(feature = "power-loss")]
+pub fn run_power_loss_monitor(
+    pin: impl Peripheral<P = impl InputPin> + 'static,
+    storage: SharedAppStorage,
+) -> Result<(), AppError> {
+    let mut power_good = PinDriver::input(pin)?;
+    power_good.set_pull(Pull::Floating)?;
+    power_good.set_interrupt_type(InterruptType::NegEdge)?;
+
+    let notification = Notification::new();
+    let notifier = notification.notifier();
+
+    // Safety: the callback only notifies this task, which is ISR-safe
+    unsafe {
+        power_good.subscribe(move || {
+            notifier.notify_and_yield(NonZeroU32::MIN);
+        })?;
+    }
+
+    loop {
+        power_good.enable_interrupt()?;
+        notification.wait(esp_idf_svc::hal::delay::BLOCK);
+
+        log::warn!("Power loss detected, saving state");
+        if let Err(e) = save_state(&storage) {
+            log::error!("Failed to save state on power loss: {e:?}");
+        }
+    }
+}
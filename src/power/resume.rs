@@ -0,0 +1,105 @@
+use esp_idf_svc::sys::{
+    esp_reset_reason, esp_reset_reason_t_ESP_RST_BROWNOUT, esp_reset_reason_t_ESP_RST_INT_WDT,
+    esp_reset_reason_t_ESP_RST_PANIC, esp_reset_reason_t_ESP_RST_TASK_WDT,
+    esp_reset_reason_t_ESP_RST_WDT,
+};
+use std::{mem::MaybeUninit, ptr::addr_of_mut};
+
+/// Marks a [ResumeState] written by this firmware, as the RTC memory holds
+/// garbage after a power-on.
+const RESUME_MAGIC: u32 = 0xB77F_2015;
+
+/// Represents what is shown on the displays, kept in RTC slow memory so it
+/// survives watchdog and brown-out resets.
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct ResumeState {
+    magic: u32,
+    /// The segments of the date, year and hour displays.
+    pub frames: [[u8; 4]; 3],
+    /// The brightness level (0-7) of the displays.
+    pub brightness: u8,
+    checksum: u32,
+}
+
+impl ResumeState {
+    fn new(frames: [[u8; 4]; 3], brightness: u8) -> Self {
+        let mut state = Self {
+            magic: RESUME_MAGIC,
+            frames,
+            brightness,
+            checksum: 0,
+        };
+        state.checksum = state.compute_checksum();
+        state
+    }
+
+    fn compute_checksum(&self) -> u32 {
+        self.frames
+            .iter()
+            .flatten()
+            .chain(std::iter::once(&self.brightness))
+            .fold(self.magic, |checksum, &byte| {
+                checksum.rotate_left(5) ^ byte as u32
+            })
+    }
+
+    fn is_valid(&self) -> bool {
+        self.magic == RESUME_MAGIC
+            && self.checksum == self.compute_checksum()
+            && self.brightness <= 7
+    }
+}
+
+/// The last saved state. `.rtc_noinit` is left untouched by the bootloader on
+/// every reset, unlike `.rtc.data`, which is reloaded on a watchdog reset.
+#[link_section = ".rtc_noinit"]
+static mut RESUME_STATE: MaybeUninit<ResumeState> = MaybeUninit::uninit();
+
+/// Saves what is shown on the displays to RTC slow memory.
+///
+/// ## Arguments
+/// - `frames` - The segments of the date, year and hour displays.
+/// - `brightness` - The brightness level (0-7) of the displays.
+pub fn save(frames: [[u8; 4]; 3], brightness: u8) {
+    // Safety: only written from the display update thread, and only read by
+    // `take_after_reset` at boot, before that thread is spawned
+    unsafe {
+        addr_of_mut!(RESUME_STATE)
+            .write_volatile(MaybeUninit::new(ResumeState::new(frames, brightness)));
+    }
+}
+
+/// Returns the state saved before the last reset, if the chip was reset by a
+/// watchdog, a panic or a brown-out, and clears it.
+///
+/// After any other reset, such as a power-on, `None` is returned and the boot
+/// goes on as usual.
+///
+/// ## Example
+/// ```rust
+/// if let Some(state) = power::resume::take_after_reset() {
+///     display_group.lock().unwrap().write_frames(state.frames)?;
+/// }
+/// ```
+pub fn take_after_reset() -> Option<ResumeState> {
+    #[allow(non_upper_case_globals)]
+    let unexpected_reset = matches!(
+        unsafe { esp_reset_reason() },
+        esp_reset_reason_t_ESP_RST_BROWNOUT
+            | esp_reset_reason_t_ESP_RST_PANIC
+            | esp_reset_reason_t_ESP_RST_INT_WDT
+            | esp_reset_reason_t_ESP_RST_TASK_WDT
+            | esp_reset_reason_t_ESP_RST_WDT
+    );
+
+    // Safety: every bit pattern is a valid `ResumeState`, as it only holds
+    // integers, and the checksum rejects a garbage one
+    let state = unsafe {
+        let state = addr_of_mut!(RESUME_STATE).read_volatile().assume_init();
+        addr_of_mut!(RESUME_STATE).write_volatile(MaybeUninit::zeroed());
+        state
+    };
+
+    (unexpected_reset && state.is_valid()).then_some(state)
+}
@@ -0,0 +1,46 @@
+use crate::{error::AppError, prefs::wifi_power_save::WifiPowerSave};
+use esp_idf_svc::sys::{esp, esp_pm_config_t, esp_pm_configure, CONFIG_ESP_DEFAULT_CPU_FREQ_MHZ};
+
+/// Lowest CPU frequency in MHz while the power-save mode is enabled, the
+/// frequency of the crystal, which Wi-Fi still works with.
+const MIN_CPU_FREQ_MHZ: i32 = 40;
+
+/// Enables or disables the power-save mode.
+///
+/// ## Behavior
+/// - While enabled, the CPU scales down to [MIN_CPU_FREQ_MHZ] and enters light
+///   sleep whenever every task waits, e.g. between two minute updates. The
+///   timers and the Wi-Fi wake it up again, so the web portal stays reachable.
+/// - The CPU only enters light sleep while Wi-Fi is in modem sleep, i.e. the
+///   [WifiPowerSave] is not [WifiPowerSave::None].
+/// - While disabled, the CPU runs at full speed.
+///
+/// ## Returns
+/// - `Err(AppError)`: If the power management can't be configured, e.g. the
+///   firmware was built without `CONFIG_PM_ENABLE`.
+///
+/// ## Example
+/// ```rust
+/// power::sleep::apply_power_save(prefs::power_save::is_power_save())?;
+/// ```
+pub fn apply_power_save(enabled: bool) -> Result<(), AppError> {
+    let max_freq_mhz = CONFIG_ESP_DEFAULT_CPU_FREQ_MHZ as i32;
+    let config = esp_pm_config_t {
+        max_freq_mhz,
+        min_freq_mhz: if enabled {
+            MIN_CPU_FREQ_MHZ
+        } else {
+            max_freq_mhz
+        },
+        light_sleep_enable: enabled,
+    };
+
+    esp!(unsafe { esp_pm_configure(&config as *const esp_pm_config_t as *const _) })?;
+
+    log::info!(
+        "Power-save mode {}",
+        if enabled { "enabled" } else { "disabled" }
+    );
+
+    Ok(())
+}
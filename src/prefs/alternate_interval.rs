@@ -0,0 +1,46 @@
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+
+/// Default number of seconds each view of the date display is shown while it
+/// alternates.
+pub const DEFAULT_ALTERNATE_INTERVAL: u8 = 4;
+
+/// Shortest number of seconds a view of the date display can be shown.
+pub const MIN_ALTERNATE_INTERVAL: u8 = 2;
+
+/// Longest number of seconds a view of the date display can be shown.
+pub const MAX_ALTERNATE_INTERVAL: u8 = 60;
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable that holds how many seconds each view of the date display is shown while it alternates.
+    pub static ref ALTERNATE_INTERVAL: Arc<Mutex<u8>> = Arc::new(Mutex::new(DEFAULT_ALTERNATE_INTERVAL));
+}
+
+/// Retrieves how many seconds each view of the date display is shown while it
+/// alternates, in a thread-safe way.
+///
+/// See [date_alternation_job](crate::module::display::date_alternation_job).
+pub fn get_alternate_interval() -> u8 {
+    *ALTERNATE_INTERVAL.lock().unwrap()
+}
+
+/// Updates the global alternation interval in a thread-safe way, clamped
+/// between [MIN_ALTERNATE_INTERVAL] and [MAX_ALTERNATE_INTERVAL].
+pub fn set_alternate_interval(seconds: u8) {
+    let mut alternate_interval_guard = ALTERNATE_INTERVAL.lock().unwrap();
+    *alternate_interval_guard = seconds.clamp(MIN_ALTERNATE_INTERVAL, MAX_ALTERNATE_INTERVAL);
+}
+
+/// Represents the body of a request changing how many seconds each view of the
+/// date display is shown, from [MIN_ALTERNATE_INTERVAL] to
+/// [MAX_ALTERNATE_INTERVAL].
+///
+/// ## Example
+/// ```rust
+/// let alternate_interval_request: AlternateIntervalRequest =
+///     serde_json::from_str("{\"interval\":8}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct AlternateIntervalRequest {
+    pub interval: u8,
+}
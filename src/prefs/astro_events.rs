@@ -0,0 +1,31 @@
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable that tells whether the astronomical events are shown in the display rotation.
+    pub static ref ASTRO_EVENTS: Arc<Mutex<bool>> = Arc::new(Mutex::new(false));
+}
+
+/// Retrieves whether the astronomical events are shown in a thread-safe way.
+pub fn is_astro_events_enabled() -> bool {
+    *ASTRO_EVENTS.lock().unwrap()
+}
+
+/// Updates the global astronomical events flag in a thread-safe way.
+pub fn set_astro_events(enabled: bool) {
+    let mut astro_events_guard = ASTRO_EVENTS.lock().unwrap();
+    *astro_events_guard = enabled;
+}
+
+/// Represents the body of a request enabling or disabling the astronomical
+/// events of the display rotation.
+///
+/// ## Example
+/// ```rust
+/// let astro_events_request: AstroEventsRequest =
+///     serde_json::from_str("{\"astro_events\":true}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct AstroEventsRequest {
+    pub astro_events: bool,
+}
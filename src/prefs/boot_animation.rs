@@ -0,0 +1,79 @@
+use serde::Deserialize;
+
+/// Represents the animation played on the seven-segment displays at boot.
+#[derive(Default, Copy, Clone, Debug)]
+pub enum BootAnimation {
+    /// Shows the plain "init" message.
+    #[default]
+    Init = 0,
+    /// Lights up the segments one by one across the digits.
+    Cascade = 1,
+    /// Flickers all segments like the time circuits powering up.
+    Flicker = 2,
+    /// Leaves the displays blank.
+    Blank = 3,
+}
+
+/// Allows converting a u8 integer into a [`BootAnimation`] enum.
+impl From<u8> for BootAnimation {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => BootAnimation::Init,
+            1 => BootAnimation::Cascade,
+            2 => BootAnimation::Flicker,
+            3 => BootAnimation::Blank,
+            _ => BootAnimation::default(),
+        }
+    }
+}
+
+/// Represents the body of a boot animation change request, with the value of
+/// a [BootAnimation].
+///
+/// ## Example
+/// ```rust
+/// let boot_animation_request: BootAnimationRequest =
+///     serde_json::from_str("{\"boot_animation\":2}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct BootAnimationRequest {
+    pub boot_animation: u8,
+}
+
+/// Represents the effect played on the LED strip at boot, before the theme is
+/// shown.
+#[derive(Default, Copy, Clone, Debug)]
+pub enum BootLedEffect {
+    /// Shows the theme right away.
+    #[default]
+    Off = 0,
+    /// Fades the theme in from black.
+    Fade = 1,
+    /// Lights up the LEDs of the theme one by one.
+    Sweep = 2,
+}
+
+/// Allows converting a u8 integer into a [`BootLedEffect`] enum.
+impl From<u8> for BootLedEffect {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => BootLedEffect::Off,
+            1 => BootLedEffect::Fade,
+            2 => BootLedEffect::Sweep,
+            _ => BootLedEffect::default(),
+        }
+    }
+}
+
+/// Represents the body of a boot LED effect change request, with the value of
+/// a [BootLedEffect].
+///
+/// ## Example
+/// ```rust
+/// let boot_led_effect_request: BootLedEffectRequest =
+///     serde_json::from_str("{\"boot_led_effect\":1}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct BootLedEffectRequest {
+    pub boot_led_effect: u8,
+}
@@ -0,0 +1,34 @@
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable that tells whether the colon of the hour display blinks.
+    pub static ref COLON_BLINK: Arc<Mutex<bool>> = Arc::new(Mutex::new(false));
+}
+
+/// Retrieves whether the colon of the hour display blinks every second in a
+/// thread-safe way.
+///
+/// See [colon_blink_job](crate::module::display::colon_blink_job).
+pub fn is_colon_blink_enabled() -> bool {
+    *COLON_BLINK.lock().unwrap()
+}
+
+/// Updates the global colon blink flag in a thread-safe way.
+pub fn set_colon_blink(enabled: bool) {
+    let mut colon_blink_guard = COLON_BLINK.lock().unwrap();
+    *colon_blink_guard = enabled;
+}
+
+/// Represents the body of a request enabling or disabling the blinking colon
+/// of the hour display.
+///
+/// ## Example
+/// ```rust
+/// let colon_blink_request: ColonBlinkRequest =
+///     serde_json::from_str("{\"colon_blink\":true}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct ColonBlinkRequest {
+    pub colon_blink: bool,
+}
@@ -0,0 +1,58 @@
+use chrono::NaiveDate;
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+
+/// The format of the countdown target date, e.g. `2025-10-21`.
+pub const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Max number of days the year display can count down.
+const MAX_DAYS: i64 = 9999;
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable to hold the target date of the countdown, if enabled.
+    pub static ref COUNTDOWN: Arc<Mutex<Option<NaiveDate>>> = Arc::new(Mutex::new(None));
+}
+
+/// Retrieves the target date of the countdown, or `None` when it is disabled,
+/// in a thread-safe way.
+pub fn get_countdown() -> Option<NaiveDate> {
+    *COUNTDOWN.lock().unwrap()
+}
+
+/// Updates the global countdown target date in a thread-safe way.
+pub fn set_countdown(target: Option<NaiveDate>) {
+    let mut countdown_guard = COUNTDOWN.lock().unwrap();
+    *countdown_guard = target;
+}
+
+/// Returns the number of days left until the countdown target, capped at
+/// [MAX_DAYS] so it fits the year display.
+///
+/// Returns `None` when the countdown is disabled or its target has passed, so
+/// the year display goes back to the year the day after. It is `0` on the
+/// target date itself.
+///
+/// ## Example
+/// ```rust
+/// if let Some(days) = days_remaining(time::get_local_date()) {
+///     log::info!("{days} days to go");
+/// }
+/// ```
+pub fn days_remaining(today: NaiveDate) -> Option<u16> {
+    let days = (get_countdown()? - today).num_days();
+
+    (days >= 0).then(|| days.min(MAX_DAYS) as u16)
+}
+
+/// Represents the body of a countdown change request, with the target date in
+/// the [DATE_FORMAT], or `null` to disable the countdown.
+///
+/// ## Example
+/// ```rust
+/// let countdown_request: CountdownRequest =
+///     serde_json::from_str("{\"date\":\"2025-10-21\"}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct CountdownRequest {
+    pub date: Option<String>,
+}
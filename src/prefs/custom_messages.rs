@@ -0,0 +1,98 @@
+use serde::{Deserialize, Serialize};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+
+/// Max number of custom messages stored in NVS.
+pub const MAX_MESSAGES: usize = 5;
+
+/// Max length of a custom message, in characters.
+pub const MAX_MESSAGE_LEN: usize = 24;
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable to hold the custom messages shown in the display rotation.
+    pub static ref CUSTOM_MESSAGES: Arc<Mutex<CustomMessages>> =
+        Arc::new(Mutex::new(CustomMessages::default()));
+}
+
+/// Index of the next custom message in the rotation.
+static NEXT_MESSAGE: AtomicUsize = AtomicUsize::new(0);
+
+/// Represents the custom messages shown in the display rotation.
+///
+/// ## Example
+/// ```rust
+/// let custom_messages: CustomMessages =
+///     serde_json::from_str("{\"interval\":15,\"messages\":[\"GARAGE\"]}").unwrap();
+/// ```
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CustomMessages {
+    /// How often a message is scrolled, in minutes. `0` disables the rotation.
+    pub interval: u8,
+    pub messages: Vec<String>,
+}
+
+/// Represents a request to add or replace a custom message.
+#[derive(Clone, Deserialize)]
+pub struct CustomMessageRequest {
+    pub text: String,
+}
+
+/// Represents a request to change how often a custom message is scrolled, in
+/// minutes, see [CustomMessages::interval].
+///
+/// ## Example
+/// ```rust
+/// let message_interval_request: MessageIntervalRequest =
+///     serde_json::from_str("{\"interval\":15}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct MessageIntervalRequest {
+    pub interval: u8,
+}
+
+/// Checks that a custom message fits the displays and NVS.
+pub fn is_valid_message(text: &str) -> bool {
+    !text.trim().is_empty() && text.len() <= MAX_MESSAGE_LEN && text.is_ascii()
+}
+
+/// Retrieves the current custom messages in a thread-safe way.
+pub fn get_custom_messages() -> CustomMessages {
+    CUSTOM_MESSAGES.lock().unwrap().clone()
+}
+
+/// Updates the global custom messages in a thread-safe way.
+pub fn set_custom_messages(new_custom_messages: CustomMessages) {
+    let mut custom_messages_guard = CUSTOM_MESSAGES.lock().unwrap();
+    *custom_messages_guard = new_custom_messages;
+}
+
+/// Returns the next custom message of the rotation, if one is due.
+///
+/// A message is due every `interval` minutes of the day. Messages are shown
+/// one at a time, in order.
+///
+/// ## Arguments
+/// - `minute_of_day` - The current local time in minutes since midnight.
+///
+/// ## Example
+/// ```rust
+/// if let Some(message) = get_due_message(now.hour() * 60 + now.minute()) {
+///     display_group.lock().unwrap().scroll_text(&message)?;
+/// }
+/// ```
+pub fn get_due_message(minute_of_day: u32) -> Option<String> {
+    let custom_messages = CUSTOM_MESSAGES.lock().unwrap();
+
+    if custom_messages.interval == 0
+        || custom_messages.messages.is_empty()
+        || minute_of_day % custom_messages.interval as u32 != 0
+    {
+        return None;
+    }
+
+    let index = NEXT_MESSAGE.fetch_add(1, Ordering::Relaxed) % custom_messages.messages.len();
+
+    custom_messages.messages.get(index).cloned()
+}
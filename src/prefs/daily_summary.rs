@@ -0,0 +1,70 @@
+use super::date_format::{self, DateFormat};
+use crate::time::astro;
+use chrono::{Datelike, NaiveDate};
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable to hold the minute of the day the summary is scrolled at, if enabled.
+    pub static ref DAILY_SUMMARY: Arc<Mutex<Option<u16>>> = Arc::new(Mutex::new(None));
+}
+
+/// Retrieves the minute of the day the summary is scrolled at, or `None`
+/// when it is disabled, in a thread-safe way.
+pub fn get_daily_summary() -> Option<u16> {
+    *DAILY_SUMMARY.lock().unwrap()
+}
+
+/// Updates the global daily summary time in a thread-safe way.
+pub fn set_daily_summary(minute_of_day: Option<u16>) {
+    let mut daily_summary_guard = DAILY_SUMMARY.lock().unwrap();
+    *daily_summary_guard = minute_of_day;
+}
+
+/// Builds the summary of a day: the weekday and the date, in the order of the
+/// current [DateFormat], followed by the next astronomical event of the week,
+/// if any, e.g. `TUE 14 OCT FULL MOON IN 3D`.
+pub fn summary_message(today: NaiveDate) -> String {
+    let weekday = today.weekday().to_string().to_uppercase();
+    let month = today.format("%b").to_string().to_uppercase();
+
+    let mut summary = match date_format::get_date_format() {
+        DateFormat::DayMonth => format!("{weekday} {} {month}", today.day()),
+        DateFormat::MonthDay => format!("{weekday} {month} {}", today.day()),
+    };
+
+    if let Some(event) = astro::get_upcoming_event() {
+        summary = format!("{summary} {event}");
+    }
+
+    summary
+}
+
+/// Returns the summary of the day, if it is due.
+///
+/// The summary is due once a day, at the minute set in prefs.
+///
+/// ## Example
+/// ```rust
+/// if let Some(message) = get_due_summary(minute_of_day, time::get_local_date()) {
+///     display_group.lock().unwrap().scroll_text(&message)?;
+/// }
+/// ```
+pub fn get_due_summary(minute_of_day: u32, today: NaiveDate) -> Option<String> {
+    let summary_minute = get_daily_summary()?;
+
+    (minute_of_day == summary_minute as u32).then(|| summary_message(today))
+}
+
+/// Represents the body of a daily summary change request, with the local time
+/// as `HH:MM`, or `null` to disable the summary.
+///
+/// ## Example
+/// ```rust
+/// let daily_summary_request: DailySummaryRequest =
+///     serde_json::from_str("{\"time\":\"07:30\"}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct DailySummaryRequest {
+    pub time: Option<String>,
+}
@@ -0,0 +1,57 @@
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable to hold the current date format setting.
+    pub static ref DATE_FORMAT: Arc<Mutex<Option<DateFormat>>> = Arc::new(Mutex::new(None));
+}
+
+/// Represents the order of the day and the month on the date display.
+#[derive(Default, Copy, Clone)]
+pub enum DateFormat {
+    /// Shows the date as `DD.MM`, e.g. `21.10`.
+    #[default]
+    DayMonth = 0,
+    /// Shows the date as `MM.DD`, e.g. `10.21`.
+    MonthDay = 1,
+}
+
+/// Allows converting a u8 integer into a [`DateFormat`] enum.
+impl From<u8> for DateFormat {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => DateFormat::DayMonth,
+            1 => DateFormat::MonthDay,
+            _ => DateFormat::default(),
+        }
+    }
+}
+
+/// Retrieves the current global date format setting in a thread-safe way.
+pub fn get_date_format() -> DateFormat {
+    let date_format_guard = DATE_FORMAT.lock().unwrap();
+
+    match &*date_format_guard {
+        Some(date_format) => *date_format,
+        None => DateFormat::default(),
+    }
+}
+
+/// Updates the global date format setting in a thread-safe way.
+pub fn set_date_format(new_date_format: DateFormat) {
+    let mut date_format_guard = DATE_FORMAT.lock().unwrap();
+    *date_format_guard = Some(new_date_format);
+}
+
+/// Represents the body of a date format change request, with `0` for
+/// [DateFormat::DayMonth] and `1` for [DateFormat::MonthDay].
+///
+/// ## Example
+/// ```rust
+/// let date_format_request: DateFormatRequest =
+///     serde_json::from_str("{\"date_format\":1}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct DateFormatRequest {
+    pub date_format: u8,
+}
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable that holds the digit transition of each display.
+    pub static ref DIGIT_TRANSITIONS: Arc<Mutex<DigitTransitions>> =
+        Arc::new(Mutex::new(DigitTransitions::default()));
+}
+
+/// Retrieves the current [DigitTransitions] in a thread-safe way.
+pub fn get_digit_transitions() -> DigitTransitions {
+    *DIGIT_TRANSITIONS.lock().unwrap()
+}
+
+/// Updates the global [DigitTransitions] in a thread-safe way.
+pub fn set_digit_transitions(transitions: DigitTransitions) {
+    let mut transitions_guard = DIGIT_TRANSITIONS.lock().unwrap();
+    *transitions_guard = transitions;
+}
+
+/// Represents the transition played on the digits that change when a display
+/// is updated, e.g. every minute.
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DigitTransition {
+    /// Shows the new digits right away.
+    #[default]
+    Instant = 0,
+    /// Blanks the changing digits briefly.
+    Blank = 1,
+    /// Rolls the changing digits up through intermediate segment patterns.
+    Roll = 2,
+}
+
+/// Allows converting a u8 integer into a [`DigitTransition`] enum.
+impl From<u8> for DigitTransition {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => DigitTransition::Instant,
+            1 => DigitTransition::Blank,
+            2 => DigitTransition::Roll,
+            _ => DigitTransition::default(),
+        }
+    }
+}
+
+/// Represents the [DigitTransition] selected for each display.
+#[derive(Default, Copy, Clone, Debug, Serialize, Deserialize)]
+pub struct DigitTransitions {
+    pub date: DigitTransition,
+    pub year: DigitTransition,
+    pub hour: DigitTransition,
+}
+
+/// Represents the body of a digit transition change request, with the value
+/// of the [DigitTransition] of each display to change.
+///
+/// ## Example
+/// ```rust
+/// let digit_transition_request: DigitTransitionRequest =
+///     serde_json::from_str("{\"hour\":2}").unwrap();
+/// ```
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct DigitTransitionRequest {
+    pub date: Option<u8>,
+    pub year: Option<u8>,
+    pub hour: Option<u8>,
+}
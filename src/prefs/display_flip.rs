@@ -0,0 +1,32 @@
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable that tells whether the displays are mounted upside down.
+    pub static ref DISPLAY_FLIP: Arc<Mutex<bool>> = Arc::new(Mutex::new(false));
+}
+
+/// Retrieves whether the displays are mounted rotated by 180°, so the digits
+/// are rendered upside down and in reversed order, in a thread-safe way.
+pub fn is_display_flipped() -> bool {
+    *DISPLAY_FLIP.lock().unwrap()
+}
+
+/// Updates the global display flip flag in a thread-safe way.
+pub fn set_display_flip(flipped: bool) {
+    let mut display_flip_guard = DISPLAY_FLIP.lock().unwrap();
+    *display_flip_guard = flipped;
+}
+
+/// Represents the body of a request turning the displays upside down or
+/// upright.
+///
+/// ## Example
+/// ```rust
+/// let display_flip_request: DisplayFlipRequest =
+///     serde_json::from_str("{\"display_flip\":true}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct DisplayFlipRequest {
+    pub display_flip: bool,
+}
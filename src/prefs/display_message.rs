@@ -0,0 +1,79 @@
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+
+/// Max length of a message sent to `/display_message`, in characters.
+pub const MAX_DISPLAY_MESSAGE_LEN: usize = 64;
+
+/// Max time a message stays on the displays, in seconds.
+pub const MAX_DISPLAY_MESSAGE_DURATION: u16 = 300;
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable to hold the message waiting to be shown.
+    pub static ref PENDING_MESSAGE: Arc<Mutex<Option<DisplayMessageRequest>>> =
+        Arc::new(Mutex::new(None));
+
+    /// A global, thread-safe static variable that tells whether a message is on the displays.
+    pub static ref MESSAGE_SHOWN: Arc<Mutex<bool>> = Arc::new(Mutex::new(false));
+}
+
+/// Represents a request to show a message on the displays for a while.
+///
+/// ## Example
+/// ```rust
+/// let request: DisplayMessageRequest =
+///     serde_json::from_str("{\"text\":\"DINNER IS READY\",\"duration\":30}").unwrap();
+/// ```
+#[derive(Clone, Debug, Deserialize)]
+pub struct DisplayMessageRequest {
+    pub text: String,
+    /// How long the message is shown, in seconds.
+    #[serde(default = "default_duration")]
+    pub duration: u16,
+}
+
+impl DisplayMessageRequest {
+    /// Checks that the message is readable on the displays and that the
+    /// duration is within [MAX_DISPLAY_MESSAGE_DURATION].
+    pub fn is_valid(&self) -> bool {
+        !self.text.trim().is_empty()
+            && self.text.chars().count() <= MAX_DISPLAY_MESSAGE_LEN
+            && self.duration > 0
+            && self.duration <= MAX_DISPLAY_MESSAGE_DURATION
+    }
+}
+
+fn default_duration() -> u16 {
+    10
+}
+
+/// Queues a message to be shown by
+/// [run_display_messages](crate::module::display::run_display_messages),
+/// replacing the one on the displays, if any.
+pub fn queue_message(message: DisplayMessageRequest) {
+    let mut pending_message_guard = PENDING_MESSAGE.lock().unwrap();
+    *pending_message_guard = Some(message);
+}
+
+/// Takes the message waiting to be shown, if any.
+pub fn take_pending_message() -> Option<DisplayMessageRequest> {
+    PENDING_MESSAGE.lock().unwrap().take()
+}
+
+/// Retrieves whether a message waits to be shown in a thread-safe way.
+pub fn has_pending_message() -> bool {
+    PENDING_MESSAGE.lock().unwrap().is_some()
+}
+
+/// Retrieves whether a message is on the displays in a thread-safe way.
+///
+/// While shown, the time must not be rendered, so the message isn't
+/// overwritten.
+pub fn is_message_shown() -> bool {
+    *MESSAGE_SHOWN.lock().unwrap()
+}
+
+/// Updates the global message flag in a thread-safe way.
+pub fn set_message_shown(shown: bool) {
+    let mut message_shown_guard = MESSAGE_SHOWN.lock().unwrap();
+    *message_shown_guard = shown;
+}
@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable that holds the physical display showing each role.
+    pub static ref DISPLAY_ROLES: Arc<Mutex<DisplayRoles>> =
+        Arc::new(Mutex::new(DisplayRoles::default()));
+}
+
+/// Retrieves the current [DisplayRoles] in a thread-safe way.
+pub fn get_display_roles() -> DisplayRoles {
+    *DISPLAY_ROLES.lock().unwrap()
+}
+
+/// Updates the global [DisplayRoles] in a thread-safe way.
+pub fn set_display_roles(roles: DisplayRoles) {
+    let mut roles_guard = DISPLAY_ROLES.lock().unwrap();
+    *roles_guard = roles;
+}
+
+/// Represents a physical display, by the pins it is wired to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DisplayPosition {
+    /// The display wired to the date DIO pin (`GPIO17`).
+    First = 0,
+    /// The display wired to the year DIO pin (`GPIO19`).
+    Second = 1,
+    /// The display wired to the hour DIO pin (`GPIO26`).
+    Third = 2,
+}
+
+/// Allows converting a u8 integer into a [`DisplayPosition`] enum.
+impl TryFrom<u8> for DisplayPosition {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(DisplayPosition::First),
+            1 => Ok(DisplayPosition::Second),
+            2 => Ok(DisplayPosition::Third),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Represents the [DisplayPosition] showing the date, the year and the hour,
+/// for builders that wired the displays in another order.
+///
+/// The roles are also the order of the displays in a line, e.g. for the
+/// scrolling texts: date, year and hour, from left to right.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisplayRoles {
+    pub date: DisplayPosition,
+    pub year: DisplayPosition,
+    pub hour: DisplayPosition,
+}
+
+impl Default for DisplayRoles {
+    fn default() -> Self {
+        Self {
+            date: DisplayPosition::First,
+            year: DisplayPosition::Second,
+            hour: DisplayPosition::Third,
+        }
+    }
+}
+
+/// Represents the body of a display roles change request, with the value of
+/// the [DisplayPosition] of each role to change.
+///
+/// ## Example
+/// ```rust
+/// let display_roles_request: DisplayRolesRequest =
+///     serde_json::from_str("{\"date\":2,\"year\":1,\"hour\":0}").unwrap();
+/// ```
+#[derive(Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct DisplayRolesRequest {
+    pub date: Option<u8>,
+    pub year: Option<u8>,
+    pub hour: Option<u8>,
+}
+
+impl DisplayRoles {
+    /// Checks that every role is shown on a different display.
+    pub fn is_valid(&self) -> bool {
+        self.date != self.year && self.date != self.hour && self.year != self.hour
+    }
+}
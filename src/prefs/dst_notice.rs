@@ -0,0 +1,31 @@
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable that tells whether the DST pre-announcement is enabled.
+    pub static ref DST_NOTICE: Arc<Mutex<bool>> = Arc::new(Mutex::new(true));
+}
+
+/// Retrieves whether the DST pre-announcement is enabled in a thread-safe way.
+pub fn is_dst_notice_enabled() -> bool {
+    *DST_NOTICE.lock().unwrap()
+}
+
+/// Updates the global DST pre-announcement flag in a thread-safe way.
+pub fn set_dst_notice(enabled: bool) {
+    let mut dst_notice_guard = DST_NOTICE.lock().unwrap();
+    *dst_notice_guard = enabled;
+}
+
+/// Represents the body of a request enabling or disabling the DST
+/// pre-announcement.
+///
+/// ## Example
+/// ```rust
+/// let dst_notice_request: DstNoticeRequest =
+///     serde_json::from_str("{\"dst_notice\":true}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct DstNoticeRequest {
+    pub dst_notice: bool,
+}
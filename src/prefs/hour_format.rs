@@ -1,9 +1,5 @@
-use std::sync::{Arc, Mutex};
-
-lazy_static::lazy_static! {
-    /// A global, thread-safe static variable to hold the current hour format setting.
-    pub static ref HOUR_FORMAT: Arc<Mutex<Option<HourFormat>>> = Arc::new(Mutex::new(None));
-}
+use crate::state;
+use serde::Deserialize;
 
 /// Represents the hour format setting, either 12-hour or 24-hour.
 #[derive(Default, Copy, Clone)]
@@ -24,18 +20,26 @@ impl From<u8> for HourFormat {
     }
 }
 
-/// Retrieves the current global hour format setting in a thread-safe way.
+/// Retrieves the current hour format from the runtime
+/// [Settings](state::Settings).
 pub fn get_hour_format() -> HourFormat {
-    let hour_format_guard = HOUR_FORMAT.lock().unwrap();
-
-    match &*hour_format_guard {
-        Some(hour_format) => *hour_format,
-        None => HourFormat::default(),
-    }
+    state::read_settings(|settings| settings.hour_format)
 }
 
-/// Updates the global hour format setting in a thread-safe way.
+/// Updates the hour format of the runtime [Settings](state::Settings).
 pub fn set_hour_format(new_hour_format: HourFormat) {
-    let mut hour_format_guard = HOUR_FORMAT.lock().unwrap();
-    *hour_format_guard = Some(new_hour_format);
+    state::update_settings(|settings| settings.hour_format = new_hour_format);
+}
+
+/// Represents the body of an hour format change request, with `0` for
+/// [HourFormat::Twelve] and `1` for [HourFormat::TwentyFour].
+///
+/// ## Example
+/// ```rust
+/// let hour_format_request: HourFormatRequest =
+///     serde_json::from_str("{\"hour_format\":0}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct HourFormatRequest {
+    pub hour_format: u8,
 }
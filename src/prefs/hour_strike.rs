@@ -0,0 +1,40 @@
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable that tells whether the visual hour strike is enabled.
+    pub static ref HOUR_STRIKE: Arc<Mutex<bool>> = Arc::new(Mutex::new(false));
+}
+
+/// Retrieves whether the visual hour strike is enabled in a thread-safe way.
+pub fn is_hour_strike_enabled() -> bool {
+    *HOUR_STRIKE.lock().unwrap()
+}
+
+/// Updates the global visual hour strike flag in a thread-safe way.
+pub fn set_hour_strike(enabled: bool) {
+    let mut hour_strike_guard = HOUR_STRIKE.lock().unwrap();
+    *hour_strike_guard = enabled;
+}
+
+/// Returns how many pulses strike the given hour (0-23), from 1 to 12 like a
+/// chiming clock.
+pub fn strike_count(hour: u32) -> u8 {
+    match hour % 12 {
+        0 => 12,
+        hour => hour as u8,
+    }
+}
+
+/// Represents the body of a request enabling or disabling the visual hour
+/// strike.
+///
+/// ## Example
+/// ```rust
+/// let hour_strike_request: HourStrikeRequest =
+///     serde_json::from_str("{\"hour_strike\":true}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct HourStrikeRequest {
+    pub hour_strike: bool,
+}
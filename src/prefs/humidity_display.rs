@@ -0,0 +1,34 @@
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable that tells whether the date display alternates with the room humidity.
+    pub static ref HUMIDITY_DISPLAY: Arc<Mutex<bool>> = Arc::new(Mutex::new(false));
+}
+
+/// Retrieves whether the date display alternates with the room humidity in a
+/// thread-safe way.
+///
+/// See [date_alternation_job](crate::module::display::date_alternation_job).
+pub fn is_humidity_display_enabled() -> bool {
+    *HUMIDITY_DISPLAY.lock().unwrap()
+}
+
+/// Updates the global humidity display flag in a thread-safe way.
+pub fn set_humidity_display(enabled: bool) {
+    let mut humidity_display_guard = HUMIDITY_DISPLAY.lock().unwrap();
+    *humidity_display_guard = enabled;
+}
+
+/// Represents the body of a request enabling or disabling the room humidity on
+/// the date display.
+///
+/// ## Example
+/// ```rust
+/// let humidity_display_request: HumidityDisplayRequest =
+///     serde_json::from_str("{\"humidity_display\":true}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct HumidityDisplayRequest {
+    pub humidity_display: bool,
+}
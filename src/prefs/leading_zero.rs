@@ -0,0 +1,33 @@
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable that tells whether single-digit hours and dates are shown with a leading zero.
+    pub static ref LEADING_ZERO: Arc<Mutex<bool>> = Arc::new(Mutex::new(true));
+}
+
+/// Retrieves whether single-digit hours and dates are shown with a leading
+/// zero, e.g. `09:05`, or with a blank digit, e.g. ` 9:05`, in a thread-safe
+/// way.
+pub fn is_leading_zero_enabled() -> bool {
+    *LEADING_ZERO.lock().unwrap()
+}
+
+/// Updates the global leading zero flag in a thread-safe way.
+pub fn set_leading_zero(enabled: bool) {
+    let mut leading_zero_guard = LEADING_ZERO.lock().unwrap();
+    *leading_zero_guard = enabled;
+}
+
+/// Represents the body of a request enabling or disabling the leading zero of
+/// single-digit hours and dates.
+///
+/// ## Example
+/// ```rust
+/// let leading_zero_request: LeadingZeroRequest =
+///     serde_json::from_str("{\"leading_zero\":false}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct LeadingZeroRequest {
+    pub leading_zero: bool,
+}
@@ -0,0 +1,124 @@
+use crate::error::AppError;
+use esp_idf_svc::log::set_target_level;
+use log::LevelFilter;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+/// Max number of log tags whose level is stored in NVS.
+pub const MAX_LOG_TAGS: usize = 8;
+
+/// Max length of a log tag, e.g. `esp_bttf_clock_rs::wifi::station`.
+pub const MAX_LOG_TAG_LEN: usize = 40;
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable to hold the log levels set per tag.
+    pub static ref LOG_LEVELS: Arc<Mutex<LogLevels>> = Arc::new(Mutex::new(LogLevels::default()));
+}
+
+/// Represents the log levels of ESP-IDF, from the quietest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    None = 0,
+    Error = 1,
+    Warn = 2,
+    Info = 3,
+    Debug = 4,
+    Verbose = 5,
+}
+
+impl From<LogLevel> for LevelFilter {
+    fn from(level: LogLevel) -> Self {
+        match level {
+            LogLevel::None => LevelFilter::Off,
+            LogLevel::Error => LevelFilter::Error,
+            LogLevel::Warn => LevelFilter::Warn,
+            LogLevel::Info => LevelFilter::Info,
+            LogLevel::Debug => LevelFilter::Debug,
+            LogLevel::Verbose => LevelFilter::Trace,
+        }
+    }
+}
+
+/// Represents the log level of a tag, which is an ESP-IDF component like
+/// `wifi` or `httpd`, a module of the firmware like
+/// `esp_bttf_clock_rs::wifi::station`, or `*` for every tag.
+///
+/// ## Example
+/// ```rust
+/// let tag_level: TagLogLevel =
+///     serde_json::from_str("{\"tag\":\"wifi\",\"level\":\"verbose\"}").unwrap();
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TagLogLevel {
+    pub tag: String,
+    pub level: LogLevel,
+}
+
+impl TagLogLevel {
+    /// Checks that the tag is not empty and fits NVS.
+    pub fn is_valid(&self) -> bool {
+        !self.tag.trim().is_empty() && self.tag.len() <= MAX_LOG_TAG_LEN && self.tag.is_ascii()
+    }
+
+    /// Applies the level to the ESP-IDF and the Rust loggers.
+    pub fn apply(&self) -> Result<(), AppError> {
+        set_target_level(&self.tag, self.level.into())?;
+
+        Ok(())
+    }
+}
+
+/// Represents the log levels set per tag, applied at boot in their order.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct LogLevels {
+    pub levels: Vec<TagLogLevel>,
+}
+
+impl LogLevels {
+    /// Sets the level of a tag, replacing the previous one. Setting `*` drops
+    /// the other tags, as it overrides them.
+    ///
+    /// ## Returns
+    /// `false` if the tag is new and there are already [MAX_LOG_TAGS] tags.
+    pub fn set(&mut self, tag_level: TagLogLevel) -> bool {
+        if tag_level.tag == "*" {
+            self.levels.clear();
+        }
+
+        match self
+            .levels
+            .iter_mut()
+            .find(|level| level.tag == tag_level.tag)
+        {
+            Some(level) => level.level = tag_level.level,
+            None if self.levels.len() < MAX_LOG_TAGS => self.levels.push(tag_level),
+            None => return false,
+        }
+
+        true
+    }
+
+    /// Applies every level, logging the ones that fail.
+    pub fn apply(&self) {
+        for tag_level in &self.levels {
+            tag_level
+                .apply()
+                .inspect_err(|e| {
+                    log::error!("Failed to set the log level of '{}': {e:?}", tag_level.tag)
+                })
+                .ok();
+        }
+    }
+}
+
+/// Retrieves the log levels set per tag in a thread-safe way.
+pub fn get_log_levels() -> LogLevels {
+    LOG_LEVELS.lock().unwrap().clone()
+}
+
+/// Updates the global log levels in a thread-safe way.
+pub fn set_log_levels(new_log_levels: LogLevels) {
+    let mut log_levels_guard = LOG_LEVELS.lock().unwrap();
+    *log_levels_guard = new_log_levels;
+}
@@ -0,0 +1,22 @@
+use std::sync::{Arc, Mutex};
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable that tells whether the low-power display mode is enabled.
+    pub static ref LOW_POWER: Arc<Mutex<bool>> = Arc::new(Mutex::new(false));
+}
+
+/// Retrieves whether the low-power display mode is enabled in a thread-safe
+/// way.
+///
+/// While enabled, the displays are strobed between their brightness level and
+/// the level below it by the display task, see
+/// [run_display_task](crate::module::display_queue::run_display_task).
+pub fn is_low_power() -> bool {
+    *LOW_POWER.lock().unwrap()
+}
+
+/// Updates the global low-power display mode flag in a thread-safe way.
+pub fn set_low_power(enabled: bool) {
+    let mut low_power_guard = LOW_POWER.lock().unwrap();
+    *low_power_guard = enabled;
+}
@@ -1 +1,31 @@
+pub mod alternate_interval;
+pub mod astro_events;
+pub mod boot_animation;
+pub mod colon_blink;
+pub mod countdown;
+pub mod custom_messages;
+pub mod daily_summary;
+pub mod date_format;
+pub mod digit_transition;
+pub mod display_flip;
+pub mod display_message;
+pub mod display_roles;
+pub mod dst_notice;
 pub mod hour_format;
+pub mod hour_strike;
+pub mod humidity_display;
+pub mod leading_zero;
+pub mod log_level;
+pub mod low_power;
+pub mod motion_timeout;
+pub mod ntp_server;
+pub mod photo_mode;
+pub mod power_save;
+pub mod seconds_mode;
+pub mod temperature_display;
+pub mod touch_action;
+pub mod trackers;
+pub mod volume;
+pub mod wake_light;
+pub mod weekday_mode;
+pub mod wifi_power_save;
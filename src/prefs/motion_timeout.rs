@@ -0,0 +1,45 @@
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+
+/// Default number of seconds without motion before the clock is blanked at
+/// night.
+pub const DEFAULT_MOTION_TIMEOUT: u16 = 120;
+
+/// Shortest number of seconds without motion before the clock is blanked.
+pub const MIN_MOTION_TIMEOUT: u16 = 10;
+
+/// Longest number of seconds without motion before the clock is blanked.
+pub const MAX_MOTION_TIMEOUT: u16 = 3600;
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable that holds how many seconds without motion the clock waits before it is blanked at night.
+    pub static ref MOTION_TIMEOUT: Arc<Mutex<u16>> = Arc::new(Mutex::new(DEFAULT_MOTION_TIMEOUT));
+}
+
+/// Retrieves how many seconds without motion the clock waits before it is
+/// blanked at night, in a thread-safe way.
+///
+/// See [run_motion_sensor](crate::presence::motion::run_motion_sensor).
+pub fn get_motion_timeout() -> u16 {
+    *MOTION_TIMEOUT.lock().unwrap()
+}
+
+/// Updates the global motion timeout in a thread-safe way, clamped between
+/// [MIN_MOTION_TIMEOUT] and [MAX_MOTION_TIMEOUT].
+pub fn set_motion_timeout(seconds: u16) {
+    let mut motion_timeout_guard = MOTION_TIMEOUT.lock().unwrap();
+    *motion_timeout_guard = seconds.clamp(MIN_MOTION_TIMEOUT, MAX_MOTION_TIMEOUT);
+}
+
+/// Represents the body of a motion timeout change request, in seconds from
+/// [MIN_MOTION_TIMEOUT] to [MAX_MOTION_TIMEOUT].
+///
+/// ## Example
+/// ```rust
+/// let motion_timeout_request: MotionTimeoutRequest =
+///     serde_json::from_str("{\"timeout\":300}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct MotionTimeoutRequest {
+    pub timeout: u16,
+}
@@ -0,0 +1,33 @@
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable that tells whether the clock serves NTP on the LAN.
+    pub static ref NTP_SERVER: Arc<Mutex<bool>> = Arc::new(Mutex::new(false));
+}
+
+/// Retrieves whether the LAN time server is enabled in a thread-safe way.
+///
+/// While enabled, the clock answers NTP requests once it is synced, see
+/// [run_ntp_server](crate::server::ntp::run_ntp_server).
+pub fn is_ntp_server() -> bool {
+    *NTP_SERVER.lock().unwrap()
+}
+
+/// Updates the global LAN time server flag in a thread-safe way.
+pub fn set_ntp_server(enabled: bool) {
+    let mut ntp_server_guard = NTP_SERVER.lock().unwrap();
+    *ntp_server_guard = enabled;
+}
+
+/// Represents the body of a request enabling or disabling the LAN time
+/// server.
+///
+/// ## Example
+/// ```rust
+/// let ntp_server_request: NtpServerRequest = serde_json::from_str("{\"enabled\":true}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct NtpServerRequest {
+    pub enabled: bool,
+}
@@ -0,0 +1,38 @@
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable that tells whether photo mode is active.
+    pub static ref PHOTO_MODE: Arc<Mutex<bool>> = Arc::new(Mutex::new(false));
+}
+
+/// The display brightness used while photo mode is active. The TM1637 drives
+/// the segments with the highest duty cycle at this level, which minimizes
+/// banding on camera sensors.
+pub const PHOTO_MODE_BRIGHTNESS: u8 = 7;
+
+/// Retrieves whether photo mode is active in a thread-safe way.
+///
+/// While active, brightness stays constant and animations or blinking effects
+/// must pause.
+pub fn is_photo_mode() -> bool {
+    *PHOTO_MODE.lock().unwrap()
+}
+
+/// Updates the global photo mode flag in a thread-safe way.
+pub fn set_photo_mode(enabled: bool) {
+    let mut photo_mode_guard = PHOTO_MODE.lock().unwrap();
+    *photo_mode_guard = enabled;
+}
+
+/// Represents the body of a request enabling or disabling photo mode.
+///
+/// ## Example
+/// ```rust
+/// let photo_mode_request: PhotoModeRequest =
+///     serde_json::from_str("{\"photo_mode\":true}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct PhotoModeRequest {
+    pub photo_mode: bool,
+}
@@ -0,0 +1,20 @@
+use std::sync::{Arc, Mutex};
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable that tells whether the power-save mode is enabled.
+    pub static ref POWER_SAVE: Arc<Mutex<bool>> = Arc::new(Mutex::new(false));
+}
+
+/// Retrieves whether the power-save mode is enabled in a thread-safe way.
+///
+/// While enabled, the CPU sleeps whenever it is idle and Wi-Fi stays in modem
+/// sleep, see [apply_power_save](crate::power::sleep::apply_power_save).
+pub fn is_power_save() -> bool {
+    *POWER_SAVE.lock().unwrap()
+}
+
+/// Updates the global power-save mode flag in a thread-safe way.
+pub fn set_power_save(enabled: bool) {
+    let mut power_save_guard = POWER_SAVE.lock().unwrap();
+    *power_save_guard = enabled;
+}
@@ -0,0 +1,55 @@
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable that holds what the year display shows.
+    pub static ref SECONDS_MODE: Arc<Mutex<SecondsMode>> = Arc::new(Mutex::new(SecondsMode::default()));
+}
+
+/// Retrieves the current [SecondsMode] in a thread-safe way.
+pub fn get_seconds_mode() -> SecondsMode {
+    *SECONDS_MODE.lock().unwrap()
+}
+
+/// Updates the global [SecondsMode] in a thread-safe way.
+pub fn set_seconds_mode(mode: SecondsMode) {
+    let mut mode_guard = SECONDS_MODE.lock().unwrap();
+    *mode_guard = mode;
+}
+
+/// Represents what the year display shows.
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SecondsMode {
+    /// Shows the year.
+    #[default]
+    Off = 0,
+    /// Shows the seconds, e.g. `  42`.
+    Seconds = 1,
+    /// Shows the minutes and the seconds, e.g. `07:42`.
+    MinutesSeconds = 2,
+}
+
+/// Allows converting a u8 integer into a [`SecondsMode`] enum.
+impl From<u8> for SecondsMode {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => SecondsMode::Off,
+            1 => SecondsMode::Seconds,
+            2 => SecondsMode::MinutesSeconds,
+            _ => SecondsMode::default(),
+        }
+    }
+}
+
+/// Represents the body of a [SecondsMode] change request, with the value of
+/// the mode.
+///
+/// ## Example
+/// ```rust
+/// let seconds_mode_request: SecondsModeRequest =
+///     serde_json::from_str("{\"seconds_mode\":1}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct SecondsModeRequest {
+    pub seconds_mode: u8,
+}
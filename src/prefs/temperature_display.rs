@@ -0,0 +1,34 @@
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable that tells whether the date display alternates with the room temperature.
+    pub static ref TEMPERATURE_DISPLAY: Arc<Mutex<bool>> = Arc::new(Mutex::new(false));
+}
+
+/// Retrieves whether the date display alternates with the room temperature in
+/// a thread-safe way.
+///
+/// See [date_alternation_job](crate::module::display::date_alternation_job).
+pub fn is_temperature_display_enabled() -> bool {
+    *TEMPERATURE_DISPLAY.lock().unwrap()
+}
+
+/// Updates the global temperature display flag in a thread-safe way.
+pub fn set_temperature_display(enabled: bool) {
+    let mut temperature_display_guard = TEMPERATURE_DISPLAY.lock().unwrap();
+    *temperature_display_guard = enabled;
+}
+
+/// Represents the body of a request enabling or disabling the room temperature
+/// on the date display.
+///
+/// ## Example
+/// ```rust
+/// let temperature_display_request: TemperatureDisplayRequest =
+///     serde_json::from_str("{\"temperature_display\":true}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct TemperatureDisplayRequest {
+    pub temperature_display: bool,
+}
@@ -0,0 +1,56 @@
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable that holds what a tap on the touch pad does.
+    pub static ref TOUCH_ACTION: Arc<Mutex<TouchAction>> = Arc::new(Mutex::new(TouchAction::default()));
+}
+
+/// Retrieves the current [TouchAction] in a thread-safe way.
+pub fn get_touch_action() -> TouchAction {
+    *TOUCH_ACTION.lock().unwrap()
+}
+
+/// Updates the global [TouchAction] in a thread-safe way.
+pub fn set_touch_action(action: TouchAction) {
+    let mut action_guard = TOUCH_ACTION.lock().unwrap();
+    *action_guard = action;
+}
+
+/// Represents what a tap on the touch pad does.
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TouchAction {
+    /// Ignores the taps.
+    Off = 0,
+    /// Dims the displays and the LED strip for the night, or restores them.
+    #[default]
+    NightMode = 1,
+    /// Plays the time travel animation, flickering the displays like the time
+    /// circuits powering up.
+    TimeTravel = 2,
+}
+
+/// Allows converting a u8 integer into a [`TouchAction`] enum.
+impl From<u8> for TouchAction {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => TouchAction::Off,
+            1 => TouchAction::NightMode,
+            2 => TouchAction::TimeTravel,
+            _ => TouchAction::default(),
+        }
+    }
+}
+
+/// Represents the body of a touch action change request, with the value of a
+/// [TouchAction].
+///
+/// ## Example
+/// ```rust
+/// let touch_action_request: TouchActionRequest =
+///     serde_json::from_str("{\"touch_action\":2}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct TouchActionRequest {
+    pub touch_action: u8,
+}
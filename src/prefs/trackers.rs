@@ -0,0 +1,121 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+
+/// Max number of "days since" trackers stored in NVS.
+pub const MAX_TRACKERS: usize = 5;
+
+/// Max length of a tracker name, in characters.
+pub const MAX_TRACKER_NAME_LEN: usize = 16;
+
+/// The format of the tracker dates, e.g. `2025-01-31`.
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable to hold the "days since" trackers shown in the display rotation.
+    pub static ref TRACKERS: Arc<Mutex<Trackers>> = Arc::new(Mutex::new(Trackers::default()));
+}
+
+/// Index of the next tracker in the rotation.
+static NEXT_TRACKER: AtomicUsize = AtomicUsize::new(0);
+
+/// Represents a "days since" tracker, e.g. days since quitting smoking.
+///
+/// ## Example
+/// ```rust
+/// let tracker: Tracker =
+///     serde_json::from_str("{\"name\":\"NO SMOKE\",\"since\":\"2025-01-31\"}").unwrap();
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Tracker {
+    pub name: String,
+    /// The start date, formatted as `YYYY-MM-DD`.
+    pub since: String,
+}
+
+/// Represents a request to change how often a "days since" tracker is
+/// scrolled, in minutes, see [Trackers::interval].
+///
+/// ## Example
+/// ```rust
+/// let tracker_interval_request: TrackerIntervalRequest =
+///     serde_json::from_str("{\"interval\":30}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct TrackerIntervalRequest {
+    pub interval: u8,
+}
+
+/// Represents the "days since" trackers shown in the display rotation.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Trackers {
+    /// How often a tracker is scrolled, in minutes. `0` disables the rotation.
+    pub interval: u8,
+    pub trackers: Vec<Tracker>,
+}
+
+impl Tracker {
+    /// Checks that the name fits the displays and NVS, and that the start date
+    /// is a valid date.
+    pub fn is_valid(&self) -> bool {
+        !self.name.trim().is_empty()
+            && self.name.len() <= MAX_TRACKER_NAME_LEN
+            && self.name.is_ascii()
+            && NaiveDate::parse_from_str(&self.since, DATE_FORMAT).is_ok()
+    }
+
+    /// Returns the number of whole days between the start date and `today`.
+    /// Start dates in the future count as `0`.
+    pub fn days_since(&self, today: NaiveDate) -> i64 {
+        NaiveDate::parse_from_str(&self.since, DATE_FORMAT)
+            .map(|since| (today - since).num_days().max(0))
+            .unwrap_or(0)
+    }
+}
+
+/// Retrieves the current trackers in a thread-safe way.
+pub fn get_trackers() -> Trackers {
+    TRACKERS.lock().unwrap().clone()
+}
+
+/// Updates the global trackers in a thread-safe way.
+pub fn set_trackers(new_trackers: Trackers) {
+    let mut trackers_guard = TRACKERS.lock().unwrap();
+    *trackers_guard = new_trackers;
+}
+
+/// Returns the text of the next tracker of the rotation, if one is due.
+///
+/// A tracker is due every `interval` minutes of the day. Its name is scrolled
+/// before the count, e.g. `NO SMOKE 123`.
+///
+/// ## Arguments
+/// - `minute_of_day` - The current local time in minutes since midnight.
+/// - `today` - The current local date.
+///
+/// ## Example
+/// ```rust
+/// if let Some(text) = get_due_tracker(time::get_minute_of_day(), time::get_local_date()) {
+///     display_group.lock().unwrap().scroll_text(&text)?;
+/// }
+/// ```
+pub fn get_due_tracker(minute_of_day: u32, today: NaiveDate) -> Option<String> {
+    let trackers = TRACKERS.lock().unwrap();
+
+    if trackers.interval == 0
+        || trackers.trackers.is_empty()
+        || minute_of_day % trackers.interval as u32 != 0
+    {
+        return None;
+    }
+
+    let index = NEXT_TRACKER.fetch_add(1, Ordering::Relaxed) % trackers.trackers.len();
+
+    trackers
+        .trackers
+        .get(index)
+        .map(|tracker| format!("{} {}", tracker.name, tracker.days_since(today)))
+}
@@ -0,0 +1,38 @@
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+
+/// Default volume of the melodies.
+pub const DEFAULT_VOLUME: u8 = 2;
+
+/// Loudest volume of the melodies. `0` mutes them.
+pub const MAX_VOLUME: u8 = 4;
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable that holds the volume of the melodies, from `0` (muted) to [MAX_VOLUME].
+    pub static ref VOLUME: Arc<Mutex<u8>> = Arc::new(Mutex::new(DEFAULT_VOLUME));
+}
+
+/// Retrieves the volume of the melodies in a thread-safe way.
+///
+/// See [play](crate::audio::play).
+pub fn get_volume() -> u8 {
+    *VOLUME.lock().unwrap()
+}
+
+/// Updates the global volume in a thread-safe way, capped at [MAX_VOLUME].
+pub fn set_volume(volume: u8) {
+    let mut volume_guard = VOLUME.lock().unwrap();
+    *volume_guard = volume.min(MAX_VOLUME);
+}
+
+/// Represents the body of a volume change request, from `0` (muted) to
+/// [MAX_VOLUME].
+///
+/// ## Example
+/// ```rust
+/// let volume_request: VolumeRequest = serde_json::from_str("{\"volume\":3}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct VolumeRequest {
+    pub volume: u8,
+}
@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+/// Shortest sunrise of the wake light, in minutes.
+pub const MIN_WAKE_DURATION: u8 = 15;
+
+/// Longest sunrise of the wake light, in minutes.
+pub const MAX_WAKE_DURATION: u8 = 30;
+
+/// How long the strip stays at full warm white after the wake time, in
+/// seconds, before the theme is applied again.
+const WAKE_HOLD: u32 = 30 * 60;
+
+/// Number of seconds in a day.
+const SECONDS_PER_DAY: u32 = 24 * 60 * 60;
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable to hold the wake light, if enabled.
+    pub static ref WAKE_LIGHT: Arc<Mutex<Option<WakeLight>>> = Arc::new(Mutex::new(None));
+}
+
+/// Represents the sunrise played on the LED strip before a wake time.
+///
+/// ## Example
+/// ```rust
+/// // Ramp up from 6:10 to 6:30
+/// let wake_light = WakeLight {
+///     minute_of_day: 6 * 60 + 30,
+///     duration: 20,
+/// };
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WakeLight {
+    /// The wake time, in minutes since midnight, when the sunrise ends.
+    pub minute_of_day: u16,
+    /// How long the sunrise lasts, in minutes.
+    pub duration: u8,
+}
+
+impl WakeLight {
+    /// Checks that the wake time is within a day and that the duration is
+    /// between [MIN_WAKE_DURATION] and [MAX_WAKE_DURATION] minutes.
+    pub fn is_valid(&self) -> bool {
+        self.minute_of_day < 24 * 60
+            && (MIN_WAKE_DURATION..=MAX_WAKE_DURATION).contains(&self.duration)
+    }
+
+    /// Returns how far the sunrise is at the given local time, from `0.0` at
+    /// its start to `1.0` at the wake time, staying at `1.0` for a while
+    /// after it.
+    ///
+    /// Returns `None` outside of the sunrise and the hold after it.
+    ///
+    /// ## Arguments
+    /// - `second_of_day` - The current local time in seconds since midnight.
+    pub fn progress(&self, second_of_day: u32) -> Option<f32> {
+        let duration = self.duration as u32 * 60;
+        let start = (self.minute_of_day as u32 * 60 + SECONDS_PER_DAY - duration) % SECONDS_PER_DAY;
+        let elapsed = (second_of_day + SECONDS_PER_DAY - start) % SECONDS_PER_DAY;
+
+        (elapsed < duration + WAKE_HOLD).then(|| (elapsed as f32 / duration as f32).min(1.0))
+    }
+}
+
+/// Retrieves the wake light, or `None` when it is disabled, in a thread-safe
+/// way.
+pub fn get_wake_light() -> Option<WakeLight> {
+    *WAKE_LIGHT.lock().unwrap()
+}
+
+/// Updates the global wake light in a thread-safe way.
+pub fn set_wake_light(wake_light: Option<WakeLight>) {
+    let mut wake_light_guard = WAKE_LIGHT.lock().unwrap();
+    *wake_light_guard = wake_light;
+}
+
+/// Retrieves whether the wake light is on the LED strip at the given local
+/// time, see [WakeLight::progress].
+///
+/// While active, the other LED strip effects must pause, so the sunrise isn't
+/// overwritten.
+pub fn is_active(second_of_day: u32) -> bool {
+    get_wake_light().is_some_and(|wake_light| wake_light.progress(second_of_day).is_some())
+}
+
+/// Represents the body of a wake light change request, with the local wake
+/// time as `HH:MM`, or `null` to disable the wake light, and the sunrise
+/// duration in minutes, [MIN_WAKE_DURATION] when omitted.
+///
+/// ## Example
+/// ```rust
+/// let wake_light_request: WakeLightRequest =
+///     serde_json::from_str("{\"time\":\"06:30\",\"duration\":20}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct WakeLightRequest {
+    pub time: Option<String>,
+    pub duration: Option<u8>,
+}
@@ -0,0 +1,55 @@
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable that holds how the weekday is shown on the date display.
+    pub static ref WEEKDAY_MODE: Arc<Mutex<WeekdayMode>> = Arc::new(Mutex::new(WeekdayMode::default()));
+}
+
+/// Retrieves the current [WeekdayMode] in a thread-safe way.
+pub fn get_weekday_mode() -> WeekdayMode {
+    *WEEKDAY_MODE.lock().unwrap()
+}
+
+/// Updates the global [WeekdayMode] in a thread-safe way.
+pub fn set_weekday_mode(mode: WeekdayMode) {
+    let mut mode_guard = WEEKDAY_MODE.lock().unwrap();
+    *mode_guard = mode;
+}
+
+/// Represents how the weekday is shown on the date display.
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WeekdayMode {
+    /// Shows the date only.
+    #[default]
+    Off = 0,
+    /// Shows the weekday instead of the date.
+    Always = 1,
+    /// Alternates between the date and the weekday every few seconds.
+    Alternate = 2,
+}
+
+/// Allows converting a u8 integer into a [`WeekdayMode`] enum.
+impl From<u8> for WeekdayMode {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => WeekdayMode::Off,
+            1 => WeekdayMode::Always,
+            2 => WeekdayMode::Alternate,
+            _ => WeekdayMode::default(),
+        }
+    }
+}
+
+/// Represents the body of a [WeekdayMode] change request, with the value of
+/// the mode.
+///
+/// ## Example
+/// ```rust
+/// let weekday_mode_request: WeekdayModeRequest =
+///     serde_json::from_str("{\"weekday_mode\":2}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct WeekdayModeRequest {
+    pub weekday_mode: u8,
+}
@@ -0,0 +1,61 @@
+use serde::Deserialize;
+use std::sync::{Arc, Mutex};
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable that holds the power save mode of Wi-Fi.
+    pub static ref WIFI_POWER_SAVE: Arc<Mutex<WifiPowerSave>> = Arc::new(Mutex::new(WifiPowerSave::default()));
+}
+
+/// Retrieves the current [WifiPowerSave] in a thread-safe way.
+///
+/// See [set_power_save](crate::wifi::set_power_save).
+pub fn get_wifi_power_save() -> WifiPowerSave {
+    *WIFI_POWER_SAVE.lock().unwrap()
+}
+
+/// Updates the global [WifiPowerSave] in a thread-safe way.
+pub fn set_wifi_power_save(mode: WifiPowerSave) {
+    let mut mode_guard = WIFI_POWER_SAVE.lock().unwrap();
+    *mode_guard = mode;
+}
+
+/// Represents how much the Wi-Fi radio sleeps while connected, trading the
+/// response time of the web portal for a lower consumption.
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq)]
+pub enum WifiPowerSave {
+    /// The radio never sleeps. The fastest responses, and the highest
+    /// consumption. The CPU can't enter light sleep either.
+    None = 0,
+    /// The radio wakes for every beacon of the router, the default of
+    /// ESP-IDF.
+    #[default]
+    MinModem = 1,
+    /// The radio only wakes every few beacons, so the web portal may feel
+    /// laggy.
+    MaxModem = 2,
+}
+
+/// Allows converting a u8 integer into a [`WifiPowerSave`] enum.
+impl From<u8> for WifiPowerSave {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => WifiPowerSave::None,
+            1 => WifiPowerSave::MinModem,
+            2 => WifiPowerSave::MaxModem,
+            _ => WifiPowerSave::default(),
+        }
+    }
+}
+
+/// Represents the body of a Wi-Fi power save change request, with the value
+/// of a [WifiPowerSave].
+///
+/// ## Example
+/// ```rust
+/// let wifi_power_save_request: WifiPowerSaveRequest =
+///     serde_json::from_str("{\"wifi_power_save\":2}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct WifiPowerSaveRequest {
+    pub wifi_power_save: u8,
+}
@@ -0,0 +1,241 @@
+use crate::{
+    error::AppError,
+    module::{
+        display_queue::{self, DisplayCommand},
+        led_strip::SharedLedStrip,
+    },
+    nvs::SharedAppStorage,
+    service::{app_storage::AppStoragePrefsService, led_strip::LedStripService},
+    theme::AppTheme,
+};
+use esp_idf_svc::ping::{Configuration as PingConfiguration, EspPing};
+use serde::{Deserialize, Serialize};
+use std::{
+    net::Ipv4Addr,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "pir")]
+pub mod motion;
+
+/// How often the phone is pinged.
+pub const PING_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Number of echo requests sent on every check. Phones in standby skip some
+/// of them, so one reply is enough.
+const PING_COUNT: u32 = 4;
+
+/// Max number of minutes without a reply before the away mode is enabled.
+pub const MAX_AWAY_AFTER: u16 = 24 * 60;
+
+/// Brightness of the displays in away mode.
+const AWAY_BRIGHTNESS: u8 = 0;
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable to hold the presence configuration.
+    pub static ref PRESENCE_CONFIG: Arc<Mutex<PresenceConfig>> =
+        Arc::new(Mutex::new(PresenceConfig::default()));
+
+    /// A global, thread-safe static variable to hold the presence of the phone.
+    static ref PRESENCE: Mutex<PresenceStatus> = Mutex::new(PresenceStatus::default());
+}
+
+/// Whether the displays and the LED strip are blanked for lack of motion, see
+/// [run_motion_sensor](motion::run_motion_sensor).
+static IDLE: AtomicBool = AtomicBool::new(false);
+
+/// Represents the phone watched by the presence service, and how long it may
+/// be absent before the clock enables the away mode.
+///
+/// ## Example
+/// ```rust
+/// let config: PresenceConfig =
+///     serde_json::from_str("{\"ip\":\"192.168.1.30\",\"away_after\":15}").unwrap();
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PresenceConfig {
+    /// IP address of the phone, or `None` to disable the presence service.
+    pub ip: Option<Ipv4Addr>,
+    /// Minutes without a reply before the away mode is enabled.
+    pub away_after: u16,
+}
+
+impl Default for PresenceConfig {
+    fn default() -> Self {
+        Self {
+            ip: None,
+            away_after: 15,
+        }
+    }
+}
+
+impl PresenceConfig {
+    /// Checks that the absence delay is between 1 minute and
+    /// [MAX_AWAY_AFTER].
+    pub fn is_valid(&self) -> bool {
+        (1..=MAX_AWAY_AFTER).contains(&self.away_after)
+    }
+}
+
+/// Represents the presence of the phone, as reported by `/api/presence`.
+#[derive(Clone, Copy, Debug, Default, Serialize)]
+pub struct PresenceStatus {
+    /// Whether the phone replied to the last check.
+    pub present: bool,
+    /// Whether the away mode is enabled.
+    pub away: bool,
+    /// Seconds since the last reply of the phone, if it replied since boot.
+    pub last_seen_secs: Option<u64>,
+    #[serde(skip)]
+    last_seen: Option<Instant>,
+}
+
+/// Retrieves the current presence configuration in a thread-safe way.
+pub fn get_presence_config() -> PresenceConfig {
+    *PRESENCE_CONFIG.lock().unwrap()
+}
+
+/// Updates the global presence configuration in a thread-safe way.
+pub fn set_presence_config(config: PresenceConfig) {
+    let mut config_guard = PRESENCE_CONFIG.lock().unwrap();
+    *config_guard = config;
+}
+
+/// Retrieves the current presence of the phone in a thread-safe way.
+pub fn get_presence_status() -> PresenceStatus {
+    let mut status = *PRESENCE.lock().unwrap();
+    status.last_seen_secs = status.last_seen.map(|instant| instant.elapsed().as_secs());
+
+    status
+}
+
+/// Retrieves whether the away mode is enabled in a thread-safe way.
+///
+/// While enabled, the displays are dimmed and the LED strip is off, so the
+/// tasks drawing on the LED strip must pause.
+pub fn is_away() -> bool {
+    PRESENCE.lock().unwrap().away
+}
+
+/// Retrieves whether the displays and the LED strip are blanked because no
+/// one moved in the room for a while, at night. Always `false` on builds
+/// without the `pir` feature.
+///
+/// While idle, the tasks drawing on the LED strip must pause.
+pub fn is_idle() -> bool {
+    IDLE.load(Ordering::Relaxed)
+}
+
+/// Returns the job that pings the phone, enabling the away mode once it has
+/// not replied for the configured number of minutes, and restoring the
+/// displays and the LED strip as soon as it replies again. It is meant to run
+/// every [PING_INTERVAL].
+///
+/// The away mode is also left when the presence service is disabled.
+///
+/// ## Arguments
+/// - `led_strip` - A [SharedLedStrip] instance.
+/// - `storage` - A [SharedAppStorage] instance used to read the theme.
+///
+/// ## Example
+/// ```rust
+/// scheduler.add(
+///     "presence",
+///     Schedule::Every(presence::PING_INTERVAL),
+///     presence::presence_job(led_strip.clone(), app_storage.clone()),
+/// );
+/// ```
+pub fn presence_job(led_strip: SharedLedStrip, storage: SharedAppStorage) -> impl FnMut() + Send {
+    let mut saved_brightness = 0;
+    let mut ping = EspPing::default();
+    let started = Instant::now();
+
+    move || {
+        let config = get_presence_config();
+
+        let away = match config.ip {
+            Some(ip) => {
+                let present = ping_phone(&mut ping, ip)
+                    .inspect_err(|e| log::warn!("Failed to ping the phone: {e:?}"))
+                    .unwrap_or(false);
+
+                let mut status = PRESENCE.lock().unwrap();
+                status.present = present;
+                if present {
+                    status.last_seen = Some(Instant::now());
+                }
+
+                let absent_for = status.last_seen.unwrap_or(started).elapsed();
+                absent_for >= Duration::from_secs(config.away_after as u64 * 60)
+            }
+            None => false,
+        };
+
+        if away == is_away() {
+            return;
+        }
+
+        let result = if away {
+            log::info!("The phone is away, enabling the away mode");
+            saved_brightness = display_queue::brightness();
+            enter_away_mode(&led_strip)
+        } else {
+            log::info!("The phone is back, leaving the away mode");
+            leave_away_mode(&led_strip, &storage, saved_brightness)
+        };
+
+        if let Err(e) = result {
+            log::error!("Failed to switch the away mode: {e:?}");
+        }
+
+        PRESENCE.lock().unwrap().away = away;
+    }
+}
+
+/// Sends [PING_COUNT] echo requests to the phone, and tells whether it
+/// replied to any of them.
+fn ping_phone(ping: &mut EspPing, ip: Ipv4Addr) -> Result<bool, AppError> {
+    let summary = ping.ping(
+        ip,
+        &PingConfiguration {
+            count: PING_COUNT,
+            ..Default::default()
+        },
+    )?;
+
+    Ok(summary.received > 0)
+}
+
+/// Dims the displays and turns the LED strip off.
+fn enter_away_mode(led_strip: &SharedLedStrip) -> Result<(), AppError> {
+    display_queue::send(DisplayCommand::SetBrightness(AWAY_BRIGHTNESS));
+    led_strip
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .turn_off()
+}
+
+/// Restores the brightness of the displays and the theme of the LED strip.
+fn leave_away_mode(
+    led_strip: &SharedLedStrip,
+    storage: &SharedAppStorage,
+    brightness: u8,
+) -> Result<(), AppError> {
+    let theme = storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_theme()
+        .unwrap_or(None)
+        .unwrap_or_default();
+
+    display_queue::send(DisplayCommand::SetBrightness(brightness));
+    led_strip
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .apply_theme(&theme)
+}
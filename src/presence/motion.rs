@@ -0,0 +1,140 @@
+use crate::{
+    error::AppError,
+    module::{
+        display,
+        display_queue::{self, DisplayCommand},
+        led_strip::SharedLedStrip,
+    },
+    nvs::SharedAppStorage,
+    power::auto_dim,
+    prefs,
+    presence::{is_idle, IDLE},
+    service::{app_storage::AppStoragePrefsService, led_strip::LedStripService},
+    time,
+};
+use esp_idf_svc::hal::gpio::{Gpio36, Input, PinDriver};
+use std::{
+    sync::{atomic::Ordering, PoisonError},
+    time::{Duration, Instant},
+};
+
+/// How often the PIR sensor is read.
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Represents a PIR motion sensor, e.g. an HC-SR501, whose output goes high
+/// while it sees motion, wired to `GPIO36`.
+pub struct MotionSensor {
+    pin: PinDriver<'static, Gpio36, Input>,
+}
+
+impl MotionSensor {
+    /// Creates a new [MotionSensor].
+    ///
+    /// ## Arguments
+    /// - `pin` - `GPIO36`, an input-only pin without a pull resistor, which the
+    ///   sensor drives itself.
+    ///
+    /// ## Returns
+    /// - `Err(AppError)`: If the pin can't be configured.
+    ///
+    /// ## Example
+    /// ```rust
+    /// let sensor = MotionSensor::new(peripherals.pins.gpio36)?;
+    /// ```
+    pub fn new(pin: Gpio36) -> Result<Self, AppError> {
+        Ok(Self {
+            pin: PinDriver::input(pin)?,
+        })
+    }
+
+    /// Whether the sensor currently sees motion.
+    pub fn is_motion(&self) -> bool {
+        self.pin.is_high()
+    }
+}
+
+/// Blanks the displays and turns the LED strip off at night, once no motion
+/// was seen for the [motion timeout](prefs::motion_timeout), and shows them
+/// again as soon as someone enters the room.
+///
+/// The clock is only blanked while the night mode dims it, see
+/// [is_dimmed](auto_dim::is_dimmed), and never in photo mode. It is shown
+/// again when the night mode ends. Runs forever, so it is meant to be spawned
+/// on its own thread.
+///
+/// ## Arguments
+/// - `sensor` - The [MotionSensor] to watch.
+/// - `led_strip` - A [SharedLedStrip] instance.
+/// - `storage` - A [SharedAppStorage] instance used to read the theme.
+pub fn run_motion_sensor(
+    sensor: MotionSensor,
+    led_strip: SharedLedStrip,
+    storage: SharedAppStorage,
+) {
+    let mut last_motion = Instant::now();
+
+    loop {
+        std::thread::sleep(POLL_INTERVAL);
+
+        if sensor.is_motion() {
+            last_motion = Instant::now();
+        }
+
+        let timeout = Duration::from_secs(prefs::motion_timeout::get_motion_timeout() as u64);
+        let idle = auto_dim::is_dimmed()
+            && !prefs::photo_mode::is_photo_mode()
+            && last_motion.elapsed() >= timeout;
+
+        if idle == is_idle() {
+            continue;
+        }
+
+        let result = if idle {
+            log::info!("No motion for {timeout:?}, blanking the displays and the LED strip");
+            blank(&led_strip)
+        } else {
+            log::info!("Motion seen, showing the displays and the LED strip");
+            show(&led_strip, &storage)
+        };
+
+        if let Err(e) = result {
+            log::error!("Failed to switch the motion blanking: {e:?}");
+        }
+
+        IDLE.store(idle, Ordering::Relaxed);
+    }
+}
+
+/// Blanks the displays and turns the LED strip off.
+fn blank(led_strip: &SharedLedStrip) -> Result<(), AppError> {
+    display::set_blanked(true);
+    display_queue::send(DisplayCommand::Redraw);
+
+    led_strip
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .turn_off()
+}
+
+/// Shows the displays again and applies the theme to the LED strip, unless
+/// the wake light plays.
+fn show(led_strip: &SharedLedStrip, storage: &SharedAppStorage) -> Result<(), AppError> {
+    display::set_blanked(false);
+    display_queue::send(DisplayCommand::ShowTime);
+
+    if time::get_second_of_day().is_ok_and(prefs::wake_light::is_active) {
+        return Ok(());
+    }
+
+    let theme = storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_theme()
+        .unwrap_or(None)
+        .unwrap_or_default();
+
+    led_strip
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .apply_theme(&theme)
+}
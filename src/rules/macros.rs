@@ -0,0 +1,190 @@
+use crate::{
+    error::AppError,
+    event::{self, Event},
+    module::{
+        display_queue::{self, DisplayCommand},
+        led_strip::SharedLedStrip,
+    },
+    nvs::SharedAppStorage,
+    prefs::{self, display_message::DisplayMessageRequest},
+    service::app_storage::AppStoragePrefsService,
+    theme::{AppTheme, Theme},
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    str::FromStr,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+/// Max number of macros stored in NVS.
+pub const MAX_MACROS: usize = 4;
+
+/// Max number of actions of a single macro.
+pub const MAX_MACRO_ACTIONS: usize = 4;
+
+/// Max length of a macro name, in characters.
+pub const MAX_MACRO_NAME_LEN: usize = 16;
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable to hold the macros run by the rules.
+    pub static ref MACROS: Arc<Mutex<Macros>> = Arc::new(Mutex::new(Macros::default()));
+}
+
+/// An action of a [Macro].
+///
+/// ## Example
+/// ```rust
+/// let actions: Vec<MacroAction> = serde_json::from_str(
+///     "[{\"theme\":\"cafe80s\"},{\"brightness\":7},{\"message\":{\"text\":\"PARTY\",\"duration\":30}}]",
+/// )
+/// .unwrap();
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MacroAction {
+    /// Applies and saves a [Theme], by name. Kept as a string, since the
+    /// themes are deserialized from their names only.
+    Theme(String),
+    /// Sets the brightness (0-7) of the displays. Skipped in photo mode.
+    Brightness(u8),
+    /// Shows a message on the displays, like `/display_message`.
+    Message { text: String, duration: u16 },
+}
+
+/// Represents a named list of actions, run by a [Rule](super::Rule) when it
+/// starts matching or via `/api/macros/run`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Macro {
+    pub name: String,
+    pub actions: Vec<MacroAction>,
+}
+
+/// Represents the macros stored in NVS.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Macros {
+    pub macros: Vec<Macro>,
+}
+
+/// Represents a request to run a macro, by name.
+///
+/// ## Example
+/// ```rust
+/// let macro_run_request: MacroRunRequest = serde_json::from_str("{\"name\":\"party\"}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct MacroRunRequest {
+    pub name: String,
+}
+
+impl MacroAction {
+    /// Checks that the theme exists, the brightness is in range and the
+    /// message fits the displays.
+    pub fn is_valid(&self) -> bool {
+        match self {
+            MacroAction::Theme(theme) => Theme::from_str(theme).is_ok(),
+            MacroAction::Brightness(level) => *level <= 7,
+            MacroAction::Message { text, duration } => DisplayMessageRequest {
+                text: text.clone(),
+                duration: *duration,
+            }
+            .is_valid(),
+        }
+    }
+}
+
+impl Macro {
+    /// Checks that the name fits NVS and that the macro has between 1 and
+    /// [MAX_MACRO_ACTIONS] valid actions.
+    pub fn is_valid(&self) -> bool {
+        !self.name.trim().is_empty()
+            && self.name.len() <= MAX_MACRO_NAME_LEN
+            && self.name.is_ascii()
+            && (1..=MAX_MACRO_ACTIONS).contains(&self.actions.len())
+            && self.actions.iter().all(MacroAction::is_valid)
+    }
+}
+
+impl Macros {
+    /// Checks that there are at most [MAX_MACROS] macros, that every macro is
+    /// valid and that their names are unique.
+    pub fn is_valid(&self) -> bool {
+        self.macros.len() <= MAX_MACROS
+            && self.macros.iter().all(Macro::is_valid)
+            && self
+                .macros
+                .iter()
+                .enumerate()
+                .all(|(i, a)| self.macros[..i].iter().all(|b| a.name != b.name))
+    }
+
+    /// Returns the macro with the given name, if any.
+    pub fn find(&self, name: &str) -> Option<&Macro> {
+        self.macros.iter().find(|macro_| macro_.name == name)
+    }
+}
+
+/// Retrieves the current macros in a thread-safe way.
+pub fn get_macros() -> Macros {
+    MACROS.lock().unwrap().clone()
+}
+
+/// Updates the global macros in a thread-safe way.
+pub fn set_macros(new_macros: Macros) {
+    let mut macros_guard = MACROS.lock().unwrap();
+    *macros_guard = new_macros;
+}
+
+/// Runs the actions of a macro in order, stopping at the first one that
+/// fails.
+///
+/// ## Arguments
+/// - `macro_` - The [Macro] to run.
+/// - `led_strip` - A [SharedLedStrip] instance.
+/// - `storage` - A [SharedAppStorage] instance used to save the theme.
+///
+/// ## Example
+/// ```rust
+/// if let Some(party) = get_macros().find("party") {
+///     run_macro(party, &led_strip, &storage)?;
+/// }
+/// ```
+pub fn run_macro(
+    macro_: &Macro,
+    led_strip: &SharedLedStrip,
+    storage: &SharedAppStorage,
+) -> Result<(), AppError> {
+    log::info!("Running macro '{}'", macro_.name);
+
+    for action in &macro_.actions {
+        match action {
+            MacroAction::Theme(theme) => {
+                let theme = Theme::from_str(theme).map_err(AppError::Config)?;
+
+                led_strip
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .apply_theme(&theme)?;
+                storage
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .save_theme(theme)?;
+                event::publish(Event::ThemeChanged(theme));
+            }
+            MacroAction::Brightness(level) => {
+                if prefs::photo_mode::is_photo_mode() {
+                    log::warn!("Brightness is locked while photo mode is active");
+                } else {
+                    display_queue::send(DisplayCommand::SetBrightness(*level));
+                }
+            }
+            MacroAction::Message { text, duration } => {
+                prefs::display_message::queue_message(DisplayMessageRequest {
+                    text: text.clone(),
+                    duration: *duration,
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
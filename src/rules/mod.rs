@@ -0,0 +1,467 @@
+use crate::{
+    error::AppError,
+    module::{
+        led_strip::{LedZone, SharedLedStrip},
+        sensor::{self, PressureTrend},
+    },
+    nvs::SharedAppStorage,
+    power::battery,
+    prefs::wake_light,
+    presence,
+    service::app_storage::AppStoragePrefsService,
+    time,
+};
+use chrono::{NaiveTime, Weekday};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex, PoisonError},
+};
+use ws2812_esp32_rmt_driver::RGB8;
+
+pub mod macros;
+
+/// Max number of rules stored in NVS.
+pub const MAX_RULES: usize = 8;
+
+/// Max length of a rule name, in characters.
+pub const MAX_RULE_NAME_LEN: usize = 16;
+
+/// Max number of conditions, and of zone colors, of a single rule.
+pub const MAX_RULE_ITEMS: usize = 4;
+
+/// The format of the times of a [Condition::Time], e.g. `21:30`.
+const TIME_FORMAT: &str = "%H:%M";
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable to hold the rules evaluated every minute.
+    pub static ref RULES: Arc<Mutex<Rules>> = Arc::new(Mutex::new(Rules::default()));
+
+    /// A global, thread-safe static variable to hold the Unix timestamp of the last time each
+    /// rule started matching, by rule name. Kept in RAM only, to spare the NVS writes.
+    pub static ref LAST_FIRED: Arc<Mutex<HashMap<String, i64>>> = Arc::new(Mutex::new(HashMap::new()));
+}
+
+/// A condition of a [Rule].
+///
+/// ## Example
+/// ```rust
+/// let conditions: Vec<Condition> = serde_json::from_str(
+///     "[{\"time\":{\"from\":\"21:30\",\"to\":\"22:00\"}},{\"weekdays\":[0,1,2,3,6]},{\"battery_below\":20},{\"pressure_trend\":\"falling\"}]",
+/// )
+/// .unwrap();
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Condition {
+    /// Matches from `from` until `to`, formatted as `HH:MM`, wrapping past
+    /// midnight when `to` is earlier than `from`.
+    Time { from: String, to: String },
+    /// Matches on the given weekdays, from `0` (Monday) to `6` (Sunday).
+    Weekdays(Vec<u8>),
+    /// Matches while the backup battery is below the given percentage. Never
+    /// matches on builds without the `battery` feature.
+    BatteryBelow(u8),
+    /// Matches while the barometric pressure follows the given trend over the
+    /// last 3 hours. Never matches on builds without the `bme280` feature.
+    PressureTrend(PressureTrend),
+}
+
+/// The color of a [LedZone], formatted as `#rrggbb`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ZoneColor {
+    pub zone: LedZone,
+    pub color: String,
+}
+
+/// Represents a rule that colors zones of the LED strip while all of its
+/// conditions match, e.g. a "bed soon" traffic light, and can run a
+/// [Macro](macros::Macro) when it starts matching.
+///
+/// ## Example
+/// ```rust
+/// let rule: Rule = serde_json::from_str(
+///     "{\"name\":\"BED SOON\",\"conditions\":[{\"time\":{\"from\":\"21:30\",\"to\":\"22:00\"}}],\"colors\":[{\"zone\":\"all\",\"color\":\"#ff8000\"}]}",
+/// )
+/// .unwrap();
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rule {
+    pub name: String,
+    /// Disabled rules are kept but never evaluated.
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub conditions: Vec<Condition>,
+    #[serde(default)]
+    pub colors: Vec<ZoneColor>,
+    /// The name of the macro to run when the rule starts matching.
+    #[serde(default)]
+    pub run_macro: Option<String>,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Represents the rules evaluated every minute. The first rule that matches
+/// wins, so they are ordered by priority.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Rules {
+    pub rules: Vec<Rule>,
+}
+
+/// Represents a request to enable or disable a rule, by name.
+///
+/// ## Example
+/// ```rust
+/// let rule_enable_request: RuleEnableRequest =
+///     serde_json::from_str("{\"name\":\"BED SOON\",\"enabled\":false}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct RuleEnableRequest {
+    pub name: String,
+    pub enabled: bool,
+}
+
+/// The state the conditions of the rules are evaluated against.
+#[derive(Clone, Copy, Debug)]
+pub struct RuleContext {
+    pub time: NaiveTime,
+    pub weekday: Weekday,
+    pub battery_percent: Option<u8>,
+    pub pressure_trend: Option<PressureTrend>,
+}
+
+impl RuleContext {
+    /// Reads the current local time, weekday, battery charge and pressure
+    /// trend.
+    ///
+    /// ## Returns
+    /// An [AppError::Timezone] if the timezone is unknown.
+    pub fn now() -> Result<Self, AppError> {
+        let minute_of_day = time::get_minute_of_day()?;
+
+        Ok(Self {
+            time: NaiveTime::from_hms_opt(minute_of_day / 60, minute_of_day % 60, 0)
+                .unwrap_or_default(),
+            weekday: time::get_weekday()?,
+            battery_percent: battery::get_battery_status().map(|status| status.percent),
+            pressure_trend: sensor::get_pressure_trend(),
+        })
+    }
+}
+
+impl Condition {
+    /// Checks that the times, weekdays and percentage are in range.
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Condition::Time { from, to } => parse_time(from).is_some() && parse_time(to).is_some(),
+            Condition::Weekdays(days) => !days.is_empty() && days.iter().all(|&day| day < 7),
+            Condition::BatteryBelow(percent) => *percent <= 100,
+            Condition::PressureTrend(_) => true,
+        }
+    }
+
+    /// Returns whether the condition matches the given [RuleContext].
+    pub fn matches(&self, context: &RuleContext) -> bool {
+        match self {
+            Condition::Time { from, to } => {
+                let (Some(from), Some(to)) = (parse_time(from), parse_time(to)) else {
+                    return false;
+                };
+
+                if from <= to {
+                    context.time >= from && context.time < to
+                } else {
+                    context.time >= from || context.time < to
+                }
+            }
+            Condition::Weekdays(days) => {
+                days.contains(&(context.weekday.num_days_from_monday() as u8))
+            }
+            Condition::BatteryBelow(percent) => context
+                .battery_percent
+                .is_some_and(|battery_percent| battery_percent < *percent),
+            Condition::PressureTrend(trend) => context.pressure_trend == Some(*trend),
+        }
+    }
+}
+
+impl Rule {
+    /// Checks that the name fits NVS, that the rule has between 1 and
+    /// [MAX_RULE_ITEMS] valid conditions, and up to [MAX_RULE_ITEMS] valid
+    /// zone colors. A rule without colors must run a macro.
+    pub fn is_valid(&self) -> bool {
+        !self.name.trim().is_empty()
+            && self.name.len() <= MAX_RULE_NAME_LEN
+            && self.name.is_ascii()
+            && (1..=MAX_RULE_ITEMS).contains(&self.conditions.len())
+            && self.colors.len() <= MAX_RULE_ITEMS
+            && (!self.colors.is_empty() || self.run_macro.is_some())
+            && self
+                .run_macro
+                .as_ref()
+                .map_or(true, |name| name.len() <= macros::MAX_MACRO_NAME_LEN)
+            && self.conditions.iter().all(Condition::is_valid)
+            && self
+                .colors
+                .iter()
+                .all(|zone_color| parse_color(&zone_color.color).is_some())
+    }
+
+    /// Returns whether every condition of the rule matches the given
+    /// [RuleContext].
+    pub fn matches(&self, context: &RuleContext) -> bool {
+        self.conditions
+            .iter()
+            .all(|condition| condition.matches(context))
+    }
+
+    /// Returns the zone colors of the rule, skipping the invalid ones.
+    pub fn zone_colors(&self) -> Vec<(LedZone, RGB8)> {
+        self.colors
+            .iter()
+            .filter_map(|zone_color| {
+                parse_color(&zone_color.color).map(|color| (zone_color.zone, color))
+            })
+            .collect()
+    }
+}
+
+impl Rules {
+    /// Checks that there are at most [MAX_RULES] rules, that every rule is
+    /// valid and that their names are unique.
+    pub fn is_valid(&self) -> bool {
+        self.rules.len() <= MAX_RULES
+            && self.rules.iter().all(Rule::is_valid)
+            && self
+                .rules
+                .iter()
+                .enumerate()
+                .all(|(i, a)| self.rules[..i].iter().all(|b| a.name != b.name))
+    }
+
+    /// Returns the first enabled rule with colors that matches the given
+    /// [RuleContext], if any.
+    pub fn find_match(&self, context: &RuleContext) -> Option<&Rule> {
+        self.rules
+            .iter()
+            .find(|rule| rule.enabled && !rule.colors.is_empty() && rule.matches(context))
+    }
+
+    /// Evaluates every rule against the given [RuleContext] without applying
+    /// anything, to debug them.
+    pub fn dry_run(&self, context: &RuleContext) -> DryRun {
+        let last_fired = LAST_FIRED.lock().unwrap();
+
+        DryRun {
+            time: context.time.format(TIME_FORMAT).to_string(),
+            weekday: context.weekday.num_days_from_monday() as u8,
+            battery_percent: context.battery_percent,
+            active: self.find_match(context).map(|rule| rule.name.clone()),
+            rules: self
+                .rules
+                .iter()
+                .map(|rule| RuleEvaluation {
+                    name: rule.name.clone(),
+                    enabled: rule.enabled,
+                    matches: rule.matches(context),
+                    run_macro: rule.run_macro.clone(),
+                    last_fired: last_fired.get(&rule.name).copied(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// The result of a [Rules::dry_run], returned by `/api/rules/dry_run`.
+///
+/// ## Example
+/// ```json
+/// {"time":"21:45","weekday":4,"battery_percent":null,"active":"BED SOON","rules":[{"name":"BED SOON","enabled":true,"matches":true,"run_macro":null,"last_fired":1735677900}]}
+/// ```
+#[derive(Clone, Debug, Serialize)]
+pub struct DryRun {
+    pub time: String,
+    pub weekday: u8,
+    pub battery_percent: Option<u8>,
+    /// The rule whose colors would be applied.
+    pub active: Option<String>,
+    pub rules: Vec<RuleEvaluation>,
+}
+
+/// The evaluation of a single rule in a [DryRun].
+#[derive(Clone, Debug, Serialize)]
+pub struct RuleEvaluation {
+    pub name: String,
+    pub enabled: bool,
+    pub matches: bool,
+    pub run_macro: Option<String>,
+    /// When the rule last started matching, as a Unix timestamp.
+    pub last_fired: Option<i64>,
+}
+
+impl RuleContext {
+    /// Builds a context from the current one, overriding the time (`HH:MM`),
+    /// the weekday (`0` is Monday) or the battery percentage.
+    ///
+    /// ## Returns
+    /// `None` if an override is out of range.
+    pub fn with_overrides(
+        time: Option<&str>,
+        weekday: Option<u8>,
+        battery_percent: Option<u8>,
+    ) -> Option<Self> {
+        let mut context = Self::now();
+
+        if let Some(time) = time {
+            context.time = parse_time(time)?;
+        }
+        if let Some(weekday) = weekday {
+            context.weekday = Weekday::try_from(weekday).ok()?;
+        }
+        if let Some(battery_percent) = battery_percent {
+            context.battery_percent = Some(battery_percent.min(100));
+        }
+
+        Some(context)
+    }
+}
+
+/// Parses a time formatted as `HH:MM`.
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(value, TIME_FORMAT).ok()
+}
+
+/// Parses a color formatted as `#rrggbb`.
+fn parse_color(value: &str) -> Option<RGB8> {
+    let hex = value.strip_prefix('#')?;
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+
+    let channel = |range: std::ops::Range<usize>| u8::from_str_radix(&hex[range], 16).ok();
+
+    Some(RGB8::new(channel(0..2)?, channel(2..4)?, channel(4..6)?))
+}
+
+/// Retrieves the current rules in a thread-safe way.
+pub fn get_rules() -> Rules {
+    RULES.lock().unwrap().clone()
+}
+
+/// Updates the global rules in a thread-safe way.
+pub fn set_rules(new_rules: Rules) {
+    let mut rules_guard = RULES.lock().unwrap();
+    *rules_guard = new_rules;
+}
+
+/// Returns a [Scheduler](crate::scheduler::Scheduler) job that evaluates the
+/// rules, coloring the zones of the LED strip with the first rule that
+/// matches. It is meant to run at the start of every minute.
+///
+/// ## Behavior
+/// - Only enabled rules are evaluated.
+/// - When a rule starts matching, its last-fired timestamp is updated and its
+///   macro, if any, is run once.
+/// - The theme is applied again once no rule with colors matches anymore.
+///
+/// ## Arguments
+/// - `led_strip` - A [SharedLedStrip] instance.
+/// - `storage` - A [SharedAppStorage] instance used to read the theme.
+pub fn rules_job(led_strip: SharedLedStrip, storage: SharedAppStorage) -> impl FnMut() + Send {
+    let mut active_rule: Option<String> = None;
+    let mut matching = HashSet::new();
+
+    move || {
+        let rules = get_rules();
+        let context = match RuleContext::now() {
+            Ok(context) => context,
+            Err(e) => {
+                log::warn!("Failed to evaluate the rules: {e}");
+                return;
+            }
+        };
+
+        // Fire the rules that start matching
+        let now_matching: HashSet<String> = rules
+            .rules
+            .iter()
+            .filter(|rule| rule.enabled && rule.matches(&context))
+            .map(|rule| rule.name.clone())
+            .collect();
+
+        for rule in rules
+            .rules
+            .iter()
+            .filter(|rule| now_matching.contains(&rule.name) && !matching.contains(&rule.name))
+        {
+            log::info!("Rule '{}' fired", rule.name);
+            LAST_FIRED
+                .lock()
+                .unwrap()
+                .insert(rule.name.clone(), time::get_timestamp());
+
+            let Some(macro_name) = &rule.run_macro else {
+                continue;
+            };
+
+            match macros::get_macros().find(macro_name) {
+                Some(macro_) => {
+                    if let Err(e) = macros::run_macro(macro_, &led_strip, &storage) {
+                        log::error!("Failed to run macro '{macro_name}': {e:?}");
+                    }
+                }
+                None => log::warn!("Rule '{}' runs a missing macro '{macro_name}'", rule.name),
+            }
+        }
+
+        matching = now_matching;
+
+        // Leave the LED strip off in away mode or while blanked for lack of
+        // motion, and to the wake light while it plays, the theme is applied
+        // again when they are over
+        if presence::is_away()
+            || presence::is_idle()
+            || time::get_second_of_day().is_ok_and(wake_light::is_active)
+        {
+            active_rule = None;
+            return;
+        }
+
+        // Color the zones with the first rule that matches
+        let matched = rules.find_match(&context);
+
+        if matched.is_none() && active_rule.is_none() {
+            return;
+        }
+
+        let theme = storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_maybe_theme()
+            .unwrap_or(None)
+            .unwrap_or_default();
+
+        let result = match matched {
+            Some(rule) => led_strip
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .apply_zone_colors(&theme, &rule.zone_colors()),
+            None => {
+                log::info!("No rule matches anymore, applying the theme again");
+
+                led_strip
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .apply_zone_colors(&theme, &[])
+            }
+        };
+
+        if let Err(e) = result {
+            log::error!("Failed to apply the rule colors: {e:?}");
+        }
+
+        active_rule = matched.map(|rule| rule.name.clone());
+    }
+}
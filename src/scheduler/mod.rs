@@ -0,0 +1,174 @@
+use crate::{event::Event, time};
+use std::{
+    sync::atomic::{AtomicU32, Ordering},
+    time::{Duration, Instant},
+};
+
+/// How long to wait before scheduling a [Schedule::Daily] job again while the
+/// timezone is unknown.
+const RETRY_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// Number of SNTP synchronizations since boot, so every [Scheduler] knows when
+/// to schedule its jobs following the clock again, see [on_event].
+static SNTP_SYNCS: AtomicU32 = AtomicU32::new(0);
+
+/// Represents when a job of the [Scheduler] runs.
+#[derive(Clone, Copy, Debug)]
+pub enum Schedule {
+    /// Right away, then every given interval.
+    Every(Duration),
+    /// At the start of every second, e.g. to tick the seconds on a display.
+    EverySecond,
+    /// At the start of every minute.
+    EveryMinute,
+    /// Every day at the start of the given local hour, from `0` to `23`, from
+    /// the first SNTP sync on.
+    Daily(u32),
+}
+
+impl Schedule {
+    /// Returns when a job with this schedule runs next, after `now`.
+    fn next_run(&self, now: Instant) -> Instant {
+        match self {
+            Schedule::Every(interval) => now + *interval,
+            Schedule::EverySecond => now + time::calculate_time_until_next_second(),
+            Schedule::EveryMinute => now + time::calculate_time_until_next_minute(),
+            Schedule::Daily(hour) => match time::calculate_time_until_hour(*hour) {
+                Ok(wait_time) => now + wait_time,
+                Err(e) => {
+                    log::warn!("Failed to schedule a daily job: {e}");
+                    now + RETRY_INTERVAL
+                }
+            },
+        }
+    }
+}
+
+/// Schedules the jobs following the clock again once SNTP syncs, from the
+/// [Event]s of the event bus, see [subscribe](crate::event::subscribe).
+///
+/// Until the first sync the clock is still in 1970, so a [Schedule::Daily]
+/// job would otherwise wait for the hour of a wrong day. Until then, the
+/// [Schedule::Daily] jobs don't run at all.
+pub fn on_event(event: &Event) {
+    if *event == Event::SntpSynced {
+        SNTP_SYNCS.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// A job registered in the [Scheduler].
+struct Job {
+    name: &'static str,
+    schedule: Schedule,
+    run: Box<dyn FnMut() + Send>,
+    next_run: Instant,
+}
+
+/// Runs the periodic jobs of the firmware, e.g. the rules every minute or the
+/// settings snapshot every night, one after the other from a single task,
+/// instead of a thread and its stack per job.
+///
+/// Jobs must return quickly, as a slow job delays the others.
+///
+/// ## Example
+/// ```rust
+/// let mut scheduler = Scheduler::new();
+/// scheduler.add(
+///     "weather",
+///     Schedule::Every(Duration::from_secs(60)),
+///     weather::weather_job(),
+/// );
+/// std::thread::spawn(move || scheduler.run());
+/// ```
+#[derive(Default)]
+pub struct Scheduler {
+    jobs: Vec<Job>,
+    /// The [SNTP_SYNCS] the jobs were last scheduled for.
+    sntp_syncs: u32,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a job, run by [run](Scheduler::run) at its [Schedule].
+    ///
+    /// ## Arguments
+    /// - `name` - The name of the job, for the logs.
+    /// - `schedule` - When the job runs.
+    /// - `job` - The job, which keeps its state between two runs.
+    pub fn add(
+        &mut self,
+        name: &'static str,
+        schedule: Schedule,
+        job: impl FnMut() + Send + 'static,
+    ) {
+        let now = Instant::now();
+        let next_run = match schedule {
+            Schedule::Every(_) => now,
+            _ => schedule.next_run(now),
+        };
+
+        log::info!("Scheduled job '{name}' {schedule:?}");
+
+        self.jobs.push(Job {
+            name,
+            schedule,
+            run: Box::new(job),
+            next_run,
+        });
+    }
+
+    /// Runs every job that is due, then sleeps until the next one is. Runs
+    /// forever, so it is meant to be spawned on its own thread.
+    pub fn run(mut self) {
+        loop {
+            self.reschedule_after_sync();
+
+            for job in &mut self.jobs {
+                if job.next_run > Instant::now() {
+                    continue;
+                }
+
+                if matches!(job.schedule, Schedule::Daily(_)) && self.sntp_syncs == 0 {
+                    log::debug!("Job '{}' waits for the first SNTP sync", job.name);
+                    job.next_run = Instant::now() + RETRY_INTERVAL;
+                    continue;
+                }
+
+                log::debug!("Running job '{}'", job.name);
+                (job.run)();
+                job.next_run = job.schedule.next_run(Instant::now());
+            }
+
+            let wait_time = self
+                .jobs
+                .iter()
+                .map(|job| job.next_run.saturating_duration_since(Instant::now()))
+                .min()
+                .unwrap_or(RETRY_INTERVAL);
+
+            std::thread::sleep(wait_time);
+        }
+    }
+
+    /// Schedules the jobs following the clock again when SNTP synced since
+    /// they were last scheduled. The [Schedule::Every] jobs keep their
+    /// interval, as it doesn't depend on the time of day.
+    fn reschedule_after_sync(&mut self) {
+        let sntp_syncs = SNTP_SYNCS.load(Ordering::Relaxed);
+        if sntp_syncs == self.sntp_syncs {
+            return;
+        }
+        self.sntp_syncs = sntp_syncs;
+
+        let now = Instant::now();
+        for job in &mut self.jobs {
+            if !matches!(job.schedule, Schedule::Every(_)) {
+                job.next_run = job.schedule.next_run(now);
+                log::debug!("Rescheduled job '{}' after the SNTP sync", job.name);
+            }
+        }
+    }
+}
@@ -1,19 +1,39 @@
-use super::create_server;
+use super::{dns_responder::get_dns_clients, server_configuration};
 use crate::{
     error::AppError,
-    wifi::{WifiCredentials, WIFI_CREDENTIALS},
+    state,
+    util::query::QueryParams,
+    wifi::{
+        ap::{provisioning_timeout, try_credentials, AP_IP_ADDRESS, RESULT_DELAY},
+        get_connection_attempt, get_disconnect_reason, set_connection_attempt, AuthModeRequest,
+        ConnectionAttempt, DisconnectReason, EnterpriseRequest, HiddenSsidRequest,
+        ProvisioningStatus, StaticIpRequest, WifiCredentials, AUTH_MODE, ENTERPRISE_CONFIG,
+        HIDDEN_SSID, STATIC_IP_CONFIG,
+    },
 };
 use embedded_svc::http::Headers;
 use esp_idf_svc::{
     http::{
-        server::{EspHttpConnection, Request},
+        server::{Configuration as ServerConfiguration, EspHttpConnection, EspHttpServer, Request},
         Method,
     },
     io::{Read, Write},
+    wifi::{BlockingWifi, EspWifi},
 };
+use std::time::{Duration, Instant};
+
+/// Max payload length, leaving room for the CA certificate of a
+/// WPA2-Enterprise network
+const MAX_LEN: usize = 4096;
 
-/// Max payload length
-const MAX_LEN: usize = 128;
+/// The page shown after the plain HTML form is sent, as the browser can't
+/// poll the result without JavaScript.
+static FORM_SENT_HTML: &str = "<!DOCTYPE html><html><head><meta charset=\"UTF-8\" />\
+<meta name=\"viewport\" content=\"width=device-width, initial-scale=1.0\" />\
+<title>Captive Portal</title></head><body><h1>BTTF CLOCK</h1>\
+<p>Connecting... The clock restarts once the network accepts the credentials.</p>\
+<p>If the clock doesn't restart within a minute, join its network again and \
+<a href=\"/\">retry</a>.</p></body></html>";
 
 static CAPTIVE_PORTAL_HTML: &str = include_str!("../../web/captive_portal/dist/index.html");
 static CAPTIVE_PORTAL_CSS: &str = include_str!("../../web/captive_portal/dist/assets/index.css");
@@ -28,24 +48,55 @@ static CAPTIVE_PORTAL_JS: &str = include_str!("../../web/captive_portal/dist/ass
 /// - Serves an HTML page at the root (`"/"`) URL to allow users to enter Wi-Fi
 ///   credentials.
 /// - Accepts a JSON payload via `POST /set_config` containing Wi-Fi
-///   credentials.
-/// - Stores the received credentials in the [WIFI_CREDENTIALS] global variable.
-/// - Waits until valid credentials are received before exiting.
-/// - Supports automatic redirection to the captive portal page.
+///   credentials, or the same fields form-encoded by the plain HTML form of the
+///   page, for mini-browsers that block JavaScript, see [form_to_json].
+/// - Stores the received credentials in the runtime
+///   [Settings](state::Settings).
+/// - Stores the optional `static_ip` object of the payload in the
+///   [STATIC_IP_CONFIG] global variable.
+/// - Stores the optional `hidden` flag of the payload, for networks that don't
+///   broadcast their SSID, in the [HIDDEN_SSID] global variable.
+/// - Stores the optional `auth_mode` of the payload, for open and WPA3-only
+///   networks, in the [AUTH_MODE] global variable.
+/// - Stores the optional `enterprise` object of the payload, for
+///   WPA2-Enterprise networks, in the [ENTERPRISE_CONFIG] global variable.
+/// - Tries the received credentials while the Access Point stays up, see
+///   [try_credentials], and waits for new ones when the network can't be
+///   joined.
+/// - Reports the failure that sent the device back to provisioning, e.g. a
+///   rejected password, and the [ConnectionAttempt] via `GET /status`.
+/// - Lists the DNS requests counted per client via `GET /dns`, see
+///   [get_dns_clients].
+/// - Keeps serving for [RESULT_DELAY] once the credentials are accepted, so the
+///   page can show the new IP address, before exiting.
+/// - Gives up when no credentials are received within the
+///   [provisioning_timeout], counted again after every failed attempt.
+/// - Supports automatic redirection to the captive portal page, answering the
+///   known connectivity checks with the page and redirecting any other `GET` to
+///   it, so new probes of the phones open the sign-in sheet too.
+///
+/// ## Arguments
+/// - `wifi` - The [BlockingWifi] instance running the Access Point.
 ///
 /// ## Returns
-/// - `Ok(())` if the portal is successfully initialized and credentials are
-///   received.
+/// - `Ok(true)` if the network accepted the credentials.
+/// - `Ok(false)` if the [provisioning_timeout] elapsed first.
 /// - `Err(AppError)` if server creation fails.
 ///
 /// ## Example
 /// ```rust
-/// if let Err(e) = start_captive_portal() {
-///     eprintln!("Failed to start captive portal: {e:?}");
+/// match start_captive_portal(&mut wifi) {
+///     Ok(true) => log::info!("Credentials received"),
+///     Ok(false) => log::warn!("No credentials received in time"),
+///     Err(e) => log::error!("Failed to start captive portal: {e:?}"),
 /// }
 /// ```
-pub fn start_captive_portal() -> Result<(), AppError> {
-    let mut server = create_server()?;
+pub fn start_captive_portal(wifi: &mut BlockingWifi<EspWifi<'static>>) -> Result<bool, AppError> {
+    // Wildcard URIs, for the catch-all redirection below
+    let mut server = EspHttpServer::new(&ServerConfiguration {
+        uri_match_wildcard: true,
+        ..server_configuration()
+    })?;
 
     let config_page = move |request: Request<&'_ mut EspHttpConnection<'_>>| {
         request
@@ -72,6 +123,32 @@ pub fn start_captive_portal() -> Result<(), AppError> {
         Ok(())
     })?;
 
+    // Why the device is provisioning again, if it was connected before
+    server.fn_handler::<AppError, _>("/status", Method::Get, |request| {
+        let status = ProvisioningStatus {
+            last_failure: get_disconnect_reason(),
+            attempt: get_connection_attempt(),
+        };
+        let status_json =
+            serde_json::to_string(&status).map_err(|e| AppError::Server(e.to_string()))?;
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(status_json.as_bytes())?;
+        Ok(())
+    })?;
+
+    // Which devices query the DNS responder, to tell why a sign-in sheet
+    // doesn't open
+    server.fn_handler::<AppError, _>("/dns", Method::Get, |request| {
+        let clients_json = serde_json::json!({ "clients": get_dns_clients() }).to_string();
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(clients_json.as_bytes())?;
+        Ok(())
+    })?;
+
     // Captive Portal Routes
 
     // Generic
@@ -99,13 +176,69 @@ pub fn start_captive_portal() -> Result<(), AppError> {
             return Ok(());
         }
 
+        let is_form = req.content_type().is_some_and(|content_type| {
+            content_type.starts_with("application/x-www-form-urlencoded")
+        });
+
         let mut buf = vec![0; len];
         req.read_exact(&mut buf).expect("Error in 'read_exact()'");
-        let mut resp = req.into_ok_response()?;
+
+        if is_form {
+            buf = form_to_json(&QueryParams::from_form(&String::from_utf8_lossy(&buf)));
+        }
+
+        let mut resp = if is_form {
+            req.into_response(200, None, &[("Content-Type", "text/html; charset=utf-8")])?
+        } else {
+            req.into_ok_response()?
+        };
 
         if let Ok(form) = serde_json::from_slice::<WifiCredentials>(&buf) {
-            let mut credentials = WIFI_CREDENTIALS.lock().unwrap();
-            *credentials = Some(form.clone());
+            // Forget the options of a previous, failed attempt
+            *STATIC_IP_CONFIG.lock().unwrap() = None;
+            *ENTERPRISE_CONFIG.lock().unwrap() = None;
+
+            if let Ok(StaticIpRequest {
+                static_ip: Some(static_ip),
+            }) = serde_json::from_slice::<StaticIpRequest>(&buf)
+            {
+                if static_ip.is_valid() {
+                    *STATIC_IP_CONFIG.lock().unwrap() = Some(static_ip);
+                } else {
+                    log::warn!("Ignoring invalid static IP configuration: {static_ip:?}");
+                }
+            }
+
+            if let Ok(HiddenSsidRequest { hidden }) =
+                serde_json::from_slice::<HiddenSsidRequest>(&buf)
+            {
+                *HIDDEN_SSID.lock().unwrap() = hidden;
+            }
+
+            if let Ok(AuthModeRequest { auth_mode }) =
+                serde_json::from_slice::<AuthModeRequest>(&buf)
+            {
+                *AUTH_MODE.lock().unwrap() = auth_mode;
+            }
+
+            if let Ok(EnterpriseRequest {
+                enterprise: Some(enterprise),
+            }) = serde_json::from_slice::<EnterpriseRequest>(&buf)
+            {
+                if enterprise.is_valid() {
+                    *ENTERPRISE_CONFIG.lock().unwrap() = Some(enterprise);
+                } else {
+                    log::warn!("Ignoring invalid enterprise configuration");
+                }
+            }
+
+            set_connection_attempt(ConnectionAttempt::Connecting);
+            state::update_settings(|settings| settings.wifi_credentials = Some(form.clone()));
+
+            if is_form {
+                resp.write_all(FORM_SENT_HTML.as_bytes())?;
+                return Ok(());
+            }
 
             write!(
                 resp,
@@ -120,9 +253,104 @@ pub fn start_captive_portal() -> Result<(), AppError> {
         Ok(())
     })?;
 
-    while WIFI_CREDENTIALS.lock().unwrap().is_none() {
-        std::thread::sleep(std::time::Duration::from_millis(500));
+    // Any other page, e.g. a connectivity check not listed above. Registered
+    // last, as the first matching handler wins
+    let portal_url = format!("http://{AP_IP_ADDRESS}/");
+    server.fn_handler::<AppError, _>("/*", Method::Get, move |request| {
+        log::debug!("Redirecting {} to the captive portal", request.uri());
+
+        request.into_response(302, Some("Found"), &[("Location", &portal_url)])?;
+        Ok(())
+    })?;
+
+    let timeout = provisioning_timeout();
+    let mut waiting_since = Instant::now();
+
+    loop {
+        let Some(credentials) = state::read_settings(|settings| settings.wifi_credentials.clone())
+        else {
+            if let Some(timeout) = timeout.filter(|timeout| waiting_since.elapsed() >= *timeout) {
+                log::warn!("No Wi-Fi credentials received in {timeout:?}");
+                return Ok(false);
+            }
+
+            std::thread::sleep(Duration::from_millis(500));
+            continue;
+        };
+
+        let enterprise = ENTERPRISE_CONFIG.lock().unwrap().clone();
+        let static_ip = STATIC_IP_CONFIG.lock().unwrap().clone();
+
+        match try_credentials(
+            wifi,
+            &credentials,
+            *HIDDEN_SSID.lock().unwrap(),
+            *AUTH_MODE.lock().unwrap(),
+            enterprise.as_ref(),
+            static_ip.as_ref(),
+        ) {
+            Ok(ip) => {
+                set_connection_attempt(ConnectionAttempt::Connected { ip });
+                std::thread::sleep(RESULT_DELAY);
+
+                return Ok(true);
+            }
+            Err(e) => {
+                let reason = get_disconnect_reason().unwrap_or(DisconnectReason::Other);
+                log::warn!("Failed to connect to {}: {e:?}", credentials.ssid);
+
+                set_connection_attempt(ConnectionAttempt::Failed { reason });
+                state::update_settings(|settings| settings.wifi_credentials = None);
+                waiting_since = Instant::now();
+            }
+        }
     }
+}
+
+/// Converts the fields of the plain HTML form of the captive portal to the
+/// JSON payload sent by its script, so both go through the same parsing.
+///
+/// The form has the fields `ssid`, `password`, `hidden` (a checkbox),
+/// `auth_mode`, `static_ip`, `gateway`, `mask`, `dns`, `eap_method`,
+/// `eap_identity` and `eap_username`. Empty fields are left out, and the CA
+/// certificate of an enterprise network can't be sent this way.
+///
+/// ## Example
+/// ```rust
+/// let json = form_to_json(&QueryParams::from_form("ssid=MyNetwork&password=12345678"));
+/// ```
+fn form_to_json(form: &QueryParams) -> Vec<u8> {
+    let field = |key| {
+        form.get(key)
+            .map(str::trim)
+            .filter(|value| !value.is_empty())
+    };
+
+    let static_ip = field("static_ip").map(|ip| {
+        serde_json::json!({
+            "ip": ip,
+            "gateway": field("gateway"),
+            "mask": field("mask").and_then(|mask| mask.parse::<u8>().ok()).unwrap_or(24),
+            "dns": field("dns"),
+        })
+    });
+
+    let enterprise = field("eap_method").map(|method| {
+        serde_json::json!({
+            "method": method,
+            "identity": field("eap_identity").unwrap_or_default(),
+            "username": field("eap_username").unwrap_or_default(),
+        })
+    });
 
-    Ok(())
+    serde_json::json!({
+        "ssid": field("ssid"),
+        "password": form.get("password").unwrap_or_default(),
+        "hidden": form.get("hidden").is_some(),
+        "auth_mode": field("auth_mode").unwrap_or("auto"),
+        "static_ip": static_ip,
+        "enterprise": enterprise,
+    })
+    .to_string()
+    .into_bytes()
 }
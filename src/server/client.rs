@@ -0,0 +1,96 @@
+use crate::error::AppError;
+use embedded_svc::http::client::Client;
+use esp_idf_svc::{
+    http::{
+        client::{Configuration as ClientConfiguration, EspHttpConnection},
+        Method,
+    },
+    io::{Read, Write},
+    sys::esp_crt_bundle_attach,
+};
+
+/// Creates an HTTP client that can also reach HTTPS servers, using the
+/// certificate bundle shipped with ESP-IDF.
+fn create_client() -> Result<Client<EspHttpConnection>, AppError> {
+    let client_configuration = ClientConfiguration {
+        crt_bundle_attach: Some(esp_crt_bundle_attach),
+        ..Default::default()
+    };
+
+    Ok(Client::wrap(EspHttpConnection::new(&client_configuration)?))
+}
+
+/// Sends a JSON body to the given URL with a `POST` request.
+///
+/// ## Arguments
+/// - `url`: The `http://` or `https://` URL to send the body to.
+/// - `body`: The serialized JSON body.
+///
+/// ## Returns
+/// - `Ok(u16)`: The HTTP status code of the response.
+/// - `Err(AppError)`: If the server can't be reached.
+///
+/// ## Example
+/// ```rust
+/// let status = post_json("http://192.168.0.10/backup", b"{}")?;
+/// ```
+pub fn post_json(url: &str, body: &[u8]) -> Result<u16, AppError> {
+    let mut client = create_client()?;
+
+    let content_length = body.len().to_string();
+    let headers = [
+        ("Content-Type", "application/json"),
+        ("Content-Length", content_length.as_str()),
+    ];
+
+    let mut request = client.post(url, &headers)?;
+    request.write_all(body)?;
+    request.flush()?;
+
+    let response = request.submit()?;
+
+    Ok(response.status())
+}
+
+/// Fetches the body of the given URL with a `GET` request.
+///
+/// ## Arguments
+/// - `url`: The `http://` or `https://` URL to fetch.
+/// - `headers`: Extra request headers, such as `Authorization`.
+/// - `max_len`: The max size of the body. Longer bodies are an error.
+///
+/// ## Returns
+/// - `Ok((u16, Vec<u8>))`: The HTTP status code and the body of the response.
+/// - `Err(AppError)`: If the server can't be reached or the body is too long.
+///
+/// ## Example
+/// ```rust
+/// let (status, body) = get("http://bttf.local/api/config", &[], 1024)?;
+/// ```
+pub fn get(
+    url: &str,
+    headers: &[(&str, &str)],
+    max_len: usize,
+) -> Result<(u16, Vec<u8>), AppError> {
+    let mut client = create_client()?;
+    let mut response = client.request(Method::Get, url, headers)?.submit()?;
+    let status = response.status();
+
+    let mut body = Vec::new();
+    let mut buf = [0u8; 256];
+    loop {
+        let len = response.read(&mut buf)?;
+        if len == 0 {
+            break;
+        }
+
+        if body.len() + len > max_len {
+            return Err(AppError::Server(format!(
+                "Response from {url} is longer than {max_len} bytes"
+            )));
+        }
+        body.extend_from_slice(&buf[..len]);
+    }
+
+    Ok((status, body))
+}
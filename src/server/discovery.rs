@@ -0,0 +1,221 @@
+use crate::error::AppError;
+use esp_idf_svc::mdns::EspMdns;
+use serde::Serialize;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+
+/// The hostname of the clock, reachable at `http://bttf.local`.
+pub const HOSTNAME: &str = "bttf";
+
+/// UDP port of the discovery responder, see [run_discovery_responder].
+const DISCOVERY_PORT: &str = env!("DISCOVERY_PORT");
+
+/// Port used when [DISCOVERY_PORT] is not a valid port.
+const DEFAULT_DISCOVERY_PORT: u16 = 32123;
+
+/// The datagram the companion apps broadcast to discover the clocks.
+pub const DISCOVERY_REQUEST: &[u8] = b"BTTF_DISCOVER";
+
+/// The version of the firmware, from `Cargo.toml`.
+pub const FIRMWARE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// The version of the HTTP API, raised when a route changes in a way that
+/// breaks the companion apps.
+pub const API_VERSION: u32 = 1;
+
+/// The optional features the firmware was built with.
+pub const FEATURES: &[&str] = &[
+    #[cfg(feature = "sd-card")]
+    "sd-card",
+    #[cfg(feature = "battery")]
+    "battery",
+    #[cfg(feature = "power-loss")]
+    "power-loss",
+    #[cfg(feature = "wps")]
+    "wps",
+    #[cfg(feature = "ht16k33")]
+    "ht16k33",
+    #[cfg(feature = "max7219")]
+    "max7219",
+    #[cfg(feature = "six-digit-hour")]
+    "six-digit-hour",
+    #[cfg(feature = "stopwatch-button")]
+    "stopwatch-button",
+    #[cfg(feature = "touch")]
+    "touch",
+    #[cfg(feature = "pir")]
+    "pir",
+    #[cfg(feature = "audio")]
+    "audio",
+    #[cfg(feature = "dht22")]
+    "dht22",
+    #[cfg(feature = "bme280")]
+    "bme280",
+    #[cfg(feature = "tzdb")]
+    "tzdb",
+];
+
+/// Represents what the companion apps need to tell the clocks of a network
+/// apart, without probing their HTTP endpoints.
+///
+/// ## Example
+/// ```json
+/// {"name":"BTTF Clock A1B2C3","version":"0.1.0","api":1,"features":["battery","tzdb"]}
+/// ```
+#[derive(Clone, Debug, Serialize)]
+pub struct DeviceInfo {
+    /// The name of the clock, unique on the network, see [device_name].
+    pub name: String,
+    /// See [FIRMWARE_VERSION].
+    pub version: &'static str,
+    /// See [API_VERSION].
+    pub api: u32,
+    /// See [FEATURES].
+    pub features: &'static [&'static str],
+}
+
+impl DeviceInfo {
+    /// Creates the [DeviceInfo] of the clock with the given MAC address.
+    pub fn new(mac: [u8; 6]) -> Self {
+        Self {
+            name: device_name(mac),
+            version: FIRMWARE_VERSION,
+            api: API_VERSION,
+            features: FEATURES,
+        }
+    }
+}
+
+/// Represents the reply of the discovery responder, the [DeviceInfo] of the
+/// clock with its address on the network.
+///
+/// ## Example
+/// ```json
+/// {"ip":"192.168.1.42","name":"BTTF Clock A1B2C3","version":"0.1.0","api":1,"features":["tzdb"]}
+/// ```
+#[derive(Clone, Debug, Serialize)]
+pub struct DiscoveryReply<'a> {
+    pub ip: Ipv4Addr,
+    #[serde(flatten)]
+    pub info: &'a DeviceInfo,
+}
+
+/// Names the clock after the end of its MAC address, e.g.
+/// `BTTF Clock A1B2C3`, so clocks on the same network can be told apart.
+pub fn device_name(mac: [u8; 6]) -> String {
+    format!("BTTF Clock {:02X}{:02X}{:02X}", mac[3], mac[4], mac[5])
+}
+
+/// Advertises the web portal over mDNS as `http://bttf.local`, with TXT
+/// records describing the clock: `name`, `version`, `api` and `features`,
+/// the latter separated by commas.
+///
+/// ## Arguments
+/// - `mdns` - The [EspMdns] instance.
+/// - `info` - The [DeviceInfo] of the clock.
+///
+/// ## Returns
+/// - `Err(AppError)`: If the hostname or the service can't be registered.
+///
+/// ## Example
+/// ```rust
+/// let mut mdns = EspMdns::take()?;
+/// discovery::register_mdns(&mut mdns, &DeviceInfo::new(mac))?;
+/// ```
+pub fn register_mdns(mdns: &mut EspMdns, info: &DeviceInfo) -> Result<(), AppError> {
+    mdns.set_hostname(HOSTNAME)?;
+    mdns.set_instance_name(&info.name)?;
+
+    let api = info.api.to_string();
+    let features = info.features.join(",");
+    let txt = [
+        ("name", info.name.as_str()),
+        ("version", info.version),
+        ("api", api.as_str()),
+        ("features", features.as_str()),
+    ];
+    mdns.add_service(None, "_http", "_tcp", 80, &txt)?;
+
+    log::info!("mDNS service registered: {txt:?}");
+
+    Ok(())
+}
+
+/// Reads the port of the discovery responder from [DISCOVERY_PORT], falling
+/// back to [DEFAULT_DISCOVERY_PORT] with a warning if it is not a port.
+fn discovery_port() -> u16 {
+    match DISCOVERY_PORT.parse::<u16>() {
+        Ok(port) if port > 0 => port,
+        _ => {
+            log::warn!("Invalid DISCOVERY_PORT '{DISCOVERY_PORT}', using {DEFAULT_DISCOVERY_PORT}");
+            DEFAULT_DISCOVERY_PORT
+        }
+    }
+}
+
+/// Returns the address of the clock as seen by a client, i.e. the one of the
+/// interface that routes to it.
+fn local_ip_for(client: SocketAddr) -> Result<Ipv4Addr, AppError> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0))?;
+    socket.connect(client)?;
+
+    match socket.local_addr()?.ip() {
+        IpAddr::V4(ip) => Ok(ip),
+        IpAddr::V6(_) => Err(AppError::Config("No IPv4 address".to_string())),
+    }
+}
+
+/// Answers a discovery request, see [run_discovery_responder].
+fn reply_discovery(
+    socket: &UdpSocket,
+    client: SocketAddr,
+    info: &DeviceInfo,
+) -> Result<(), AppError> {
+    let reply = DiscoveryReply {
+        ip: local_ip_for(client)?,
+        info,
+    };
+    let reply_json = serde_json::to_vec(&reply).map_err(|e| AppError::Server(e.to_string()))?;
+
+    socket.send_to(&reply_json, client)?;
+    log::debug!("Answered the discovery request of {client}");
+
+    Ok(())
+}
+
+/// Answers the [DISCOVERY_REQUEST] datagrams broadcast on the
+/// [DISCOVERY_PORT] with a [DiscoveryReply] sent back to the client, for
+/// networks where mDNS is filtered. Other datagrams are ignored, while a
+/// trailing newline after the request is accepted.
+///
+/// Runs forever, so it is meant to be spawned on its own thread.
+///
+/// ## Arguments
+/// - `info` - The [DeviceInfo] of the clock.
+///
+/// ## Example
+/// ```rust
+/// std::thread::spawn(move || server::discovery::run_discovery_responder(device_info));
+/// ```
+pub fn run_discovery_responder(info: DeviceInfo) {
+    let port = discovery_port();
+    let socket = super::bind_udp(port, "discovery responder");
+
+    let mut buffer = [0; 64];
+    loop {
+        let (length, client) = match socket.recv_from(&mut buffer) {
+            Ok(received) => received,
+            Err(e) => {
+                log::warn!("Failed to receive a discovery request: {e:?}");
+                continue;
+            }
+        };
+
+        if !buffer[..length].starts_with(DISCOVERY_REQUEST) {
+            continue;
+        }
+
+        if let Err(e) = reply_discovery(&socket, client, &info) {
+            log::warn!("Failed to answer the discovery request of {client}: {e:?}");
+        }
+    }
+}
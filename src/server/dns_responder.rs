@@ -1,15 +1,91 @@
 use crate::error::AppError;
+use bttf_core::dns;
+use serde::Serialize;
 use std::{
     io,
-    net::{Ipv4Addr, SocketAddrV4, UdpSocket},
+    net::{IpAddr, Ipv4Addr, SocketAddrV4, UdpSocket},
+    sync::Mutex,
     time::Duration,
 };
 
-/// A DNS responder that listens for DNS requests and responds with predefined
-/// DNS responses.
+/// Max size of a DNS message over UDP, without EDNS.
+const MAX_PACKET_LEN: usize = 512;
+
+/// Seconds the clients may cache the answers.
+const DNS_TTL_SECS: &str = env!("DNS_TTL_SECS");
+
+/// Whether every queried name is logged, `true` or `false`.
+const DNS_LOG_QUERIES: &str = env!("DNS_LOG_QUERIES");
+
+/// Max number of clients whose requests are counted, a few more than can
+/// join the Access Point at once.
+const MAX_DNS_CLIENTS: usize = 16;
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable to hold the requests counted per client.
+    static ref DNS_CLIENTS: Mutex<Vec<DnsClientStats>> = Mutex::new(Vec::new());
+}
+
+/// Represents the requests of a client of the [DnsResponder], returned by
+/// the captive portal `/dns` endpoint.
+///
+/// A phone whose sign-in sheet never opens either sends no request at all,
+/// e.g. it uses a private DNS server, or queries a name that isn't answered.
+#[derive(Clone, Debug, Serialize)]
+pub struct DnsClientStats {
+    pub ip: Ipv4Addr,
+    /// Number of requests received from the client.
+    pub requests: u32,
+    /// The last name queried by the client, e.g. `captive.apple.com`.
+    pub last_name: Option<String>,
+}
+
+/// Retrieves the requests counted per client since the responder started.
+pub fn get_dns_clients() -> Vec<DnsClientStats> {
+    DNS_CLIENTS.lock().unwrap().clone()
+}
+
+/// Counts a request of a client, keeping the name it queried. Clients beyond
+/// [MAX_DNS_CLIENTS] are not counted.
+fn count_request(ip: Ipv4Addr, name: Option<String>) {
+    let mut clients = DNS_CLIENTS.lock().unwrap();
+
+    match clients.iter_mut().find(|client| client.ip == ip) {
+        Some(client) => {
+            client.requests = client.requests.saturating_add(1);
+            client.last_name = name.or(client.last_name.take());
+        }
+        None if clients.len() < MAX_DNS_CLIENTS => clients.push(DnsClientStats {
+            ip,
+            requests: 1,
+            last_name: name,
+        }),
+        None => {}
+    }
+}
+
+/// Reads the TTL of the answers from [DNS_TTL_SECS], falling back to
+/// [DEFAULT_TTL_SECS](dns::DEFAULT_TTL_SECS) with a warning if it is not a
+/// number.
+fn dns_ttl_secs() -> u32 {
+    DNS_TTL_SECS.parse::<u32>().unwrap_or_else(|_| {
+        log::warn!(
+            "Invalid DNS_TTL_SECS '{DNS_TTL_SECS}', using {}",
+            dns::DEFAULT_TTL_SECS
+        );
+        dns::DEFAULT_TTL_SECS
+    })
+}
+
+/// A DNS responder that answers every `A` query with the IP address of the
+/// Access Point, see [dns::respond].
 pub struct DnsResponder {
-    response_footer: [u8; 16],
+    ip_address: Ipv4Addr,
     udp_socket: UdpSocket,
+    /// Seconds the clients may cache the answers, see [DNS_TTL_SECS].
+    ttl_secs: u32,
+    /// Whether every queried name is logged, see [DNS_LOG_QUERIES].
+    log_queries: bool,
 }
 
 impl DnsResponder {
@@ -17,9 +93,8 @@ impl DnsResponder {
     /// the UDP socket.
     ///
     /// This function creates a [UdpSocket] bound to the given `ip_address` and
-    /// sets a read timeout for the socket. It also sets up a predefined
-    /// response footer, which includes the IP address to be used in DNS
-    /// responses.
+    /// sets a read timeout for the socket. The same address is used in the
+    /// answers, cached by the clients for [DNS_TTL_SECS] seconds.
     ///
     /// ## Arguments
     /// - `ip_address` - The IPv4 address to bind the DNS server to.
@@ -37,26 +112,25 @@ impl DnsResponder {
         let udp_socket = UdpSocket::bind(SocketAddrV4::new(ip_address, 53))?;
         udp_socket.set_read_timeout(Some(Duration::from_millis(10)))?;
 
-        let mut response_footer = [
-            0xc0, 0x0c, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x0a, 0x00, 0x04, 0x00, 0x00,
-            0x00, 0x00,
-        ];
-        response_footer[12..].copy_from_slice(&ip_address.octets());
-
         Ok(Self {
-            response_footer,
+            ip_address,
             udp_socket,
+            ttl_secs: dns_ttl_secs(),
+            log_queries: DNS_LOG_QUERIES == "true",
         })
     }
 
-    /// Handles incoming DNS requests by reading the request and sending a
-    /// predefined response.
+    /// Handles incoming DNS requests by parsing the request and sending the
+    /// response built by [dns::respond].
     ///
-    /// This function listens for DNS requests on the bound UDP socket,
-    /// processes the requests, and sends a response back to the requesting
-    /// client. The response includes the predefined IP address configured
-    /// in the [`DnsResponder`] instance. If the packet size exceeds 100
-    /// bytes, a warning is logged.
+    /// This function listens for DNS requests on the bound UDP socket, and
+    /// answers `A` queries with the IP address configured in the
+    /// [`DnsResponder`] instance. Other record types get an empty answer and
+    /// unsupported queries an error code, while packets that aren't queries
+    /// are ignored.
+    ///
+    /// Every request is counted per client, see [get_dns_clients], and the
+    /// queried name is logged when [DNS_LOG_QUERIES] is `true`.
     ///
     /// ## Returns
     /// Returns `Ok(())` if the request is successfully processed and responded
@@ -67,19 +141,31 @@ impl DnsResponder {
     /// dns_responder.handle_requests()?;
     /// ```
     pub fn handle_requests(&mut self) -> Result<(), AppError> {
-        let mut buffer = [0; 128];
+        let mut buffer = [0; MAX_PACKET_LEN];
         match self.udp_socket.recv_from(&mut buffer) {
             Ok((length, client_addr)) => {
-                if length > 100 {
-                    log::warn!("Received DNS request with an invalid packet size: {length}");
-                } else {
-                    buffer[2] |= 0x80;
-                    buffer[3] |= 0x80;
-                    buffer[7] = 0x01;
-                    let total_len = length + self.response_footer.len();
-                    buffer[length..total_len].copy_from_slice(&self.response_footer);
-                    self.udp_socket
-                        .send_to(&buffer[0..total_len], client_addr)?;
+                let question = dns::parse_question(&buffer[..length]);
+
+                if self.log_queries {
+                    match &question {
+                        Some(question) => log::info!(
+                            "DNS query for {} (type {}) from {client_addr}",
+                            question.name,
+                            question.qtype
+                        ),
+                        None => log::info!("DNS packet of {length} bytes from {client_addr}"),
+                    }
+                }
+
+                if let IpAddr::V4(client_ip) = client_addr.ip() {
+                    count_request(client_ip, question.map(|question| question.name));
+                }
+
+                match dns::respond(&buffer[..length], self.ip_address, self.ttl_secs) {
+                    Some(response) => {
+                        self.udp_socket.send_to(&response, client_addr)?;
+                    }
+                    None => log::warn!("Ignoring DNS packet of {length} bytes from {client_addr}"),
                 }
                 Ok(())
             }
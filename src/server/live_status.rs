@@ -0,0 +1,143 @@
+use crate::{
+    error::AppError,
+    health::{self, HealthState, Subsystem},
+    module::sensor,
+    nvs::SharedAppStorage,
+    power::battery::{self, BatteryStatus},
+    prefs,
+    service::app_storage::AppStoragePrefsService,
+    theme::Theme,
+    time,
+};
+use embedded_svc::ws::FrameType;
+use esp_idf_svc::http::server::ws::{EspHttpWsConnection, EspHttpWsDetachedSender};
+use serde::Serialize;
+use std::{
+    sync::{Mutex, PoisonError},
+    time::Duration,
+};
+
+/// How often the clock state is checked for changes.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// The WebSocket clients that receive the live status updates.
+static LIVE_CLIENTS: Mutex<Vec<EspHttpWsDetachedSender>> = Mutex::new(Vec::new());
+
+/// Represents the clock state pushed to the Web portal.
+///
+/// ## Example
+/// ```json
+/// {"time":"10:04","timezone":"America/Sao_Paulo","theme":"original","wifi":"ok","photo_mode":false,"temperature_c":21.4,"humidity":45.2,"battery":{"voltage_mv":3912,"percent":68,"low":false}}
+/// ```
+#[derive(Clone, PartialEq, Serialize)]
+struct LiveStatus {
+    time: String,
+    timezone: String,
+    theme: Theme,
+    wifi: Option<HealthState>,
+    photo_mode: bool,
+    /// The room temperature, on builds with a sensor that has a reading.
+    temperature_c: Option<f32>,
+    /// The room humidity, on builds with a sensor that measures it.
+    humidity: Option<f32>,
+    /// The backup battery reading, on builds with the `battery` feature.
+    battery: Option<BatteryStatus>,
+}
+
+impl LiveStatus {
+    /// Reads the current clock state.
+    fn current(storage: &SharedAppStorage) -> Self {
+        // The time is `--:--` while the timezone is unknown, so it can still
+        // be fixed from the web portal
+        let time = time::get_hour_min()
+            .map(|time| format!("{}{}:{}{}", time[0], time[1], time[2], time[3]))
+            .unwrap_or_else(|_| "--:--".to_string());
+        let reading = sensor::get_reading();
+
+        Self {
+            time,
+            timezone: time::tz::get_timezone(),
+            theme: storage
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .get_maybe_theme()
+                .unwrap_or(None)
+                .unwrap_or_default(),
+            wifi: health::get_state(Subsystem::Wifi),
+            photo_mode: prefs::photo_mode::is_photo_mode(),
+            temperature_c: reading.map(|reading| reading.temperature_c),
+            humidity: reading.and_then(|reading| reading.humidity),
+            battery: battery::get_battery_status(),
+        }
+    }
+
+    /// Serializes the status as a WebSocket text frame payload.
+    fn to_json(&self) -> Vec<u8> {
+        serde_json::to_vec(self).unwrap_or_default()
+    }
+}
+
+/// Handles the WebSocket connections of the live status endpoint.
+///
+/// New clients are sent the current status right away and then receive every
+/// change pushed by [live_status_job]. Frames sent by the browser are ignored.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to read the theme.
+///
+/// ## Returns
+/// A closure that handles the WebSocket events of a connection.
+pub fn live_status(
+    storage: SharedAppStorage,
+) -> impl Fn(&mut EspHttpWsConnection) -> Result<(), AppError> + Send + Sync + 'static {
+    move |ws: &mut EspHttpWsConnection| {
+        if ws.is_new() {
+            let mut sender = ws.create_detached_sender()?;
+            sender.send(
+                FrameType::Text(false),
+                &LiveStatus::current(&storage).to_json(),
+            )?;
+
+            LIVE_CLIENTS.lock().unwrap().push(sender);
+            log::info!("Live status client {} connected", ws.session());
+        } else if ws.is_closed() {
+            log::info!("Live status client {} disconnected", ws.session());
+        } else {
+            let mut buf = [0u8; 64];
+            ws.recv(&mut buf)?;
+        }
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Returns the job that checks the clock state and pushes it to every
+/// connected client when it changes. Clients that can't be reached anymore
+/// are dropped. It is meant to run every [POLL_INTERVAL].
+///
+/// ## Example
+/// ```rust
+/// scheduler.add(
+///     "live status",
+///     Schedule::Every(server::live_status::POLL_INTERVAL),
+///     server::live_status::live_status_job(app_storage.clone()),
+/// );
+/// ```
+pub fn live_status_job(storage: SharedAppStorage) -> impl FnMut() + Send {
+    let mut last_status: Option<LiveStatus> = None;
+
+    move || {
+        let status = LiveStatus::current(&storage);
+        if last_status.as_ref() == Some(&status) {
+            return;
+        }
+
+        let payload = status.to_json();
+        LIVE_CLIENTS
+            .lock()
+            .unwrap()
+            .retain_mut(|sender| sender.send(FrameType::Text(false), &payload).is_ok());
+
+        last_status = Some(status);
+    }
+}
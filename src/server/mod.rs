@@ -1,13 +1,34 @@
 use crate::error::AppError;
 use esp_idf_svc::http::server::{Configuration as ServerConfiguration, EspHttpServer};
+use std::{
+    net::{Ipv4Addr, UdpSocket},
+    time::Duration,
+};
 
 pub mod captive_portal;
+pub mod client;
+pub mod discovery;
 pub mod dns_responder;
+pub mod live_status;
+pub mod ntp;
 pub mod web_portal;
 
-/// Need lots of stack to parse JSON
+/// Need lots of stack to parse JSON. Check the stack left to the `httpd` task
+/// in `/api/diagnostics` after changing it.
 const STACK_SIZE: usize = 10240;
 
+/// Leaves room for the live status WebSocket clients, which keep their socket
+/// open, next to the regular requests.
+const MAX_OPEN_SOCKETS: usize = 7;
+
+/// Leaves room for every route of the web portal, listed by `/api`, since
+/// the default only fits 32 handlers.
+const MAX_URI_HANDLERS: usize = 96;
+
+/// How long to wait before binding a UDP server again, e.g. while every socket
+/// is taken.
+const BIND_RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
 /// Initializes and starts an HTTP server.
 ///
 /// This function creates a new instance of the [EspHttpServer] using the
@@ -23,10 +44,50 @@ const STACK_SIZE: usize = 10240;
 /// let server = start_server().expect("Failed to start HTTP server");
 /// ```
 pub fn create_server() -> Result<EspHttpServer<'static>, AppError> {
-    let server_configuration = ServerConfiguration {
+    Ok(EspHttpServer::new(&server_configuration())?)
+}
+
+/// Returns the configuration of the HTTP servers of the firmware, see
+/// [create_server].
+fn server_configuration() -> ServerConfiguration {
+    ServerConfiguration {
         stack_size: STACK_SIZE,
+        max_open_sockets: MAX_OPEN_SOCKETS,
+        max_uri_handlers: MAX_URI_HANDLERS,
         ..Default::default()
-    };
+    }
+}
 
-    Ok(EspHttpServer::new(&server_configuration)?)
+/// Binds a UDP socket to the given port on every interface, for the UDP
+/// servers of the firmware.
+///
+/// A failure is logged and the socket is bound again every
+/// [BIND_RETRY_INTERVAL], so a server started while every socket is taken, see
+/// `CONFIG_LWIP_MAX_SOCKETS` in `sdkconfig.defaults`, comes up once one is
+/// freed.
+///
+/// ## Arguments
+/// - `port` - The UDP port to listen on.
+/// - `name` - The name of the server, for the logs.
+///
+/// ## Example
+/// ```rust
+/// let socket = server::bind_udp(123, "NTP server");
+/// ```
+pub fn bind_udp(port: u16, name: &str) -> UdpSocket {
+    loop {
+        match UdpSocket::bind((Ipv4Addr::UNSPECIFIED, port)) {
+            Ok(socket) => {
+                log::info!("{name} listening on UDP port {port}");
+                return socket;
+            }
+            Err(e) => {
+                log::error!(
+                    "Failed to bind the {name} to UDP port {port}, retrying in {}s: {e:?}",
+                    BIND_RETRY_INTERVAL.as_secs()
+                );
+                std::thread::sleep(BIND_RETRY_INTERVAL);
+            }
+        }
+    }
 }
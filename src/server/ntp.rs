@@ -0,0 +1,127 @@
+use crate::{event::Event, prefs};
+use bttf_core::ntp;
+use serde::Serialize;
+use std::{
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Mutex,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// The standard NTP port, which the clients can't be told to change.
+const NTP_PORT: u16 = 123;
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable to hold when the clock was last synced with SNTP, since the Unix epoch.
+    static ref LAST_SYNC: Mutex<Option<Duration>> = Mutex::new(None);
+}
+
+/// Number of NTP requests answered since boot.
+static ANSWERED_REQUESTS: AtomicU32 = AtomicU32::new(0);
+
+/// Represents the state of the LAN time server.
+///
+/// ## Example
+/// ```json
+/// {"enabled":true,"synced":true,"last_sync":1700000000,"stratum":3,"answered":42}
+/// ```
+#[derive(Clone, Debug, Serialize)]
+pub struct NtpServerStatus {
+    /// Whether the LAN time server is enabled, see
+    /// [is_ntp_server](prefs::ntp_server::is_ntp_server).
+    pub enabled: bool,
+    /// Whether the clock was synced with SNTP since boot, without which no
+    /// request is answered.
+    pub synced: bool,
+    /// When the clock was last synced, as a Unix timestamp.
+    pub last_sync: Option<u64>,
+    /// The stratum announced to the clients.
+    pub stratum: u8,
+    /// The number of requests answered since boot.
+    pub answered: u32,
+}
+
+/// Returns the time since the Unix epoch, as kept by the system clock.
+fn now() -> Duration {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+/// Returns the current [NtpServerStatus].
+pub fn get_status() -> NtpServerStatus {
+    let last_sync = *LAST_SYNC.lock().unwrap();
+
+    NtpServerStatus {
+        enabled: prefs::ntp_server::is_ntp_server(),
+        synced: last_sync.is_some(),
+        last_sync: last_sync.map(|since_epoch| since_epoch.as_secs()),
+        stratum: ntp::STRATUM,
+        answered: ANSWERED_REQUESTS.load(Ordering::Relaxed),
+    }
+}
+
+/// Remembers when the clock was last synced with SNTP, the reference time of
+/// the answers, from the [Event]s of the event bus, see
+/// [subscribe](crate::event::subscribe).
+pub fn on_event(event: &Event) {
+    if *event == Event::SntpSynced {
+        *LAST_SYNC.lock().unwrap() = Some(now());
+    }
+}
+
+/// Answers the NTP requests received on [NTP_PORT], so other devices of the
+/// LAN, e.g. gadgets on a VLAN without Internet access, can sync from the
+/// clock.
+///
+/// ## Behavior
+/// - Requests are only answered while the LAN time server is enabled, see
+///   [is_ntp_server](prefs::ntp_server::is_ntp_server), and once the clock was
+///   synced with SNTP. Otherwise they are dropped, so the clients try their
+///   other servers instead of syncing to a wrong time.
+/// - The clock announces [STRATUM](ntp::STRATUM), as it has no reference clock
+///   of its own.
+///
+/// Runs forever, so it is meant to be spawned on its own thread.
+///
+/// ## Example
+/// ```rust
+/// std::thread::spawn(server::ntp::run_ntp_server);
+/// ```
+pub fn run_ntp_server() {
+    let socket = super::bind_udp(NTP_PORT, "NTP server");
+
+    let mut buffer = [0; ntp::PACKET_LEN * 2];
+    loop {
+        let (length, client) = match socket.recv_from(&mut buffer) {
+            Ok(received) => received,
+            Err(e) => {
+                log::warn!("Failed to receive an NTP request: {e:?}");
+                continue;
+            }
+        };
+        let received = now();
+
+        if !prefs::ntp_server::is_ntp_server() {
+            continue;
+        }
+
+        let Some(last_sync) = *LAST_SYNC.lock().unwrap() else {
+            log::debug!("Ignoring the NTP request of {client}, not synced yet");
+            continue;
+        };
+
+        let Some(response) = ntp::respond(&buffer[..length], last_sync, received, now()) else {
+            continue;
+        };
+
+        match socket.send_to(&response, client) {
+            Ok(_) => {
+                ANSWERED_REQUESTS.fetch_add(1, Ordering::Relaxed);
+                log::debug!("Answered the NTP request of {client}");
+            }
+            Err(e) => log::warn!("Failed to answer the NTP request of {client}: {e:?}"),
+        }
+    }
+}
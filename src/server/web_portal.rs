@@ -1,45 +1,151 @@
+use super::{live_status, ntp};
 use crate::{
-    error::AppError,
+    audio::{self, AudioRequest},
+    board_config::BoardConfig,
+    config::{
+        self,
+        clone::{CloneRequest, MAX_CONFIG_LEN},
+        factory_reset::{self, FactoryResetRequest},
+        snapshot::{self, SnapshotRequest},
+        DeviceConfig,
+    },
+    error::{self, AppError},
+    event::{self, Event},
+    health::{self, Subsystem},
     module::{
-        display::SharedDisplayGroup,
-        led::SharedAmPmIndicator,
-        led_strip::{LedStrip, SharedLedStrip},
+        display::BrightnessRequest,
+        display_queue::{self, DisplayCommand},
+        led_strip::SharedLedStrip,
+        relay::{self, RelayConfig, RelayRequest, RelaySwitch},
+        sensor,
     },
     nvs::SharedAppStorage,
-    prefs::{self, hour_format::get_hour_format},
-    service::{
-        app_storage::{AppStoragePrefsService, AppStorageTzService, AppStorageWifiService},
-        display::SevenSegmentDisplayService,
+    ota,
+    power::{
+        self,
+        auto_dim::{self, AutoDimConfig},
+        PowerReport, PowerRequest,
+    },
+    prefs::{
+        self,
+        alternate_interval::{self, AlternateIntervalRequest},
+        astro_events::AstroEventsRequest,
+        boot_animation::{
+            BootAnimation, BootAnimationRequest, BootLedEffect, BootLedEffectRequest,
+        },
+        colon_blink::ColonBlinkRequest,
+        countdown::CountdownRequest,
+        custom_messages::{
+            self, CustomMessageRequest, CustomMessages, MessageIntervalRequest, MAX_MESSAGES,
+        },
+        daily_summary::DailySummaryRequest,
+        date_format::DateFormatRequest,
+        digit_transition::{self, DigitTransition, DigitTransitionRequest},
+        display_flip::DisplayFlipRequest,
+        display_message::{
+            DisplayMessageRequest, MAX_DISPLAY_MESSAGE_DURATION, MAX_DISPLAY_MESSAGE_LEN,
+        },
+        display_roles::{self, DisplayPosition, DisplayRolesRequest},
+        dst_notice::DstNoticeRequest,
+        hour_format::HourFormatRequest,
+        hour_strike::HourStrikeRequest,
+        humidity_display::HumidityDisplayRequest,
+        leading_zero::LeadingZeroRequest,
+        log_level::{self, TagLogLevel, MAX_LOG_TAGS},
+        motion_timeout::{self, MotionTimeoutRequest},
+        ntp_server::NtpServerRequest,
+        photo_mode::PhotoModeRequest,
+        seconds_mode::{SecondsMode, SecondsModeRequest},
+        temperature_display::TemperatureDisplayRequest,
+        touch_action::{TouchAction, TouchActionRequest},
+        trackers::{self, Tracker, TrackerIntervalRequest, Trackers, MAX_TRACKERS},
+        volume::{self, VolumeRequest},
+        wake_light::{WakeLight, WakeLightRequest},
+        weekday_mode::{WeekdayMode, WeekdayModeRequest},
+        wifi_power_save::{WifiPowerSave, WifiPowerSaveRequest},
     },
-    theme::{AppTheme, Theme},
-    time::{self, tz::TimezoneRequest},
-    util::messages::DisplayMessage,
+    presence::{self, PresenceConfig},
+    rules::{
+        self,
+        macros::{self, MacroRunRequest, Macros, MAX_MACROS, MAX_MACRO_ACTIONS},
+        RuleContext, RuleEnableRequest, Rules, MAX_RULES, MAX_RULE_ITEMS,
+    },
+    service::app_storage::{AppStoragePrefsService, AppStorageWifiService},
+    state::AppState,
+    theme::{AppTheme, Theme, ThemeInfo, ThemeRequest},
+    time::{self, astro, stopwatch::StopwatchRequest, sun, tz::TimezoneRequest},
+    util::{messages::DisplayMessage, query::QueryParams},
+    weather::{self, WeatherConfig},
+    webhook::{self, Notification, WebhookRequest},
+    wifi::{self, CountryCodeRequest, NetworkSettings, StaticIpRequest},
 };
-use chrono_tz::Tz;
+use chrono::{NaiveDate, NaiveTime, Timelike};
+use embedded_svc::http::Headers;
 use esp_idf_svc::{
-    hal::{
-        delay::FreeRtos,
-        gpio::{IOPin, OutputPin},
-    },
+    hal::gpio::{IOPin, OutputPin},
     http::{
         server::{EspHttpConnection, EspHttpServer, Request},
         Method,
     },
-    io::Write,
+    io::{Read, Write},
     sntp::{EspSntp, SyncStatus},
-    sys::{esp_restart, esp_wifi_disconnect, sntp_restart},
+    sys::{esp_restart, sntp_restart},
 };
+use serde::Serialize;
 use std::{
-    str::FromStr,
-    sync::{Arc, Mutex},
+    panic::AssertUnwindSafe,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Mutex, PoisonError,
+    },
 };
 
+/// Max length of the body of `POST /api/rules`.
+const MAX_RULES_LEN: usize = 4096;
+
+/// Max length of the body of `POST /api/macros`.
+const MAX_MACROS_LEN: usize = 2048;
+
+/// Number of upcoming events returned by `/api/astro`.
+const ASTRO_TABLE_LEN: usize = 8;
+
+/// Number of handlers that panicked since boot, exposed in `/metrics`.
+static HANDLER_PANICS: AtomicU32 = AtomicU32::new(0);
+
 static WEB_PORTAL_HTML: &str = include_str!("../../web/web_portal/dist/index.html");
 static WEB_PORTAL_CSS: &str = include_str!("../../web/web_portal/dist/assets/index.css");
 static WEB_PORTAL_JS: &str = include_str!("../../web/web_portal/dist/assets/js/index.js");
 
+/// Responds with the given status and a JSON error body like
+/// `{"error":"Invalid theme"}`.
+fn error_response(
+    request: Request<&mut EspHttpConnection<'_>>,
+    status: u16,
+    message: &str,
+) -> Result<(), AppError> {
+    let error_json = serde_json::json!({ "error": message }).to_string();
+
+    request
+        .into_response(status, None, &[("Content-Type", "application/json")])?
+        .write_all(error_json.as_bytes())?;
+
+    Ok(())
+}
+
+/// Describes a registered route, as listed by `/api`.
+#[derive(Clone, Debug, Serialize)]
+pub struct RouteInfo {
+    pub uri: &'static str,
+    pub method: String,
+    /// Names of the query parameters read by the handler.
+    pub query: &'static [&'static str],
+    /// Name of the JSON type expected in the body, if any.
+    pub body: Option<&'static str>,
+}
+
 pub struct WebPortal {
     server: EspHttpServer<'static>,
+    routes: Vec<RouteInfo>,
 }
 
 impl WebPortal {
@@ -48,113 +154,814 @@ impl WebPortal {
             server: super::create_server().inspect_err(|e| {
                 log::error!("Failed to start HTTP server: {e:#?}");
             })?,
+            routes: Vec::new(),
         })
     }
 
-    pub fn create_routes<
-        CLK: OutputPin,
-        DateDIO: IOPin,
-        YearDIO: IOPin,
-        HourDIO: IOPin,
-        AM: OutputPin,
-        PM: OutputPin,
-    >(
+    /// Registers a handler and records it, so it is listed by `/api`.
+    ///
+    /// Every route but the `GET` ones changes the state of the clock, so they
+    /// all require the admin token, see
+    /// [is_authorized](config::clone::is_authorized).
+    ///
+    /// A panic in the handler is caught and answered with a 500 response,
+    /// instead of restarting the firmware, and counted in `/metrics`. The
+    /// handlers hold the [SharedAppStorage] and the LED strip across fallible
+    /// calls, so every lock of these two recovers from the poisoning a caught
+    /// panic leaves behind. The displays are only reached through the
+    /// [display_queue], and the globals of the prefs are only locked to read
+    /// or replace a value, so no handler can poison them.
+    ///
+    /// ## Arguments
+    /// - `uri` - The URI of the route.
+    /// - `method` - The HTTP [Method] of the route.
+    /// - `query` - Names of the query parameters read by the handler.
+    /// - `body` - Name of the JSON type expected in the body, if any.
+    /// - `handler` - The handler of the route.
+    fn route<F>(
         &mut self,
-        display_group: SharedDisplayGroup<'static, CLK, DateDIO, YearDIO, HourDIO>,
-        am_pm_indicator: SharedAmPmIndicator<'static, AM, PM>,
-        led_strip: LedStrip<'static>,
-        app_storage: SharedAppStorage,
-        sntp: EspSntp<'static>,
-        wifi_ssid: String,
-    ) -> Result<(), AppError> {
-        self.server
-            .fn_handler("/", Method::Get, web_portal())
-            .inspect_err(|&e| {
-                log::error!("Failed to register web portal handler: {e:#?}");
-            })?;
+        uri: &'static str,
+        method: Method,
+        query: &'static [&'static str],
+        body: Option<&'static str>,
+        handler: F,
+    ) -> Result<(), AppError>
+    where
+        F: for<'r> Fn(Request<&mut EspHttpConnection<'r>>) -> Result<(), AppError> + Send + 'static,
+    {
+        self.routes.push(RouteInfo {
+            uri,
+            method: format!("{method:?}").to_uppercase(),
+            query,
+            body,
+        });
 
-        self.server
-            .fn_handler("/assets/index.css", Method::Get, web_portal_css())
-            .inspect_err(|&e| {
-                log::error!("Failed to serve CSS: {e:#?}");
-            })?;
+        let handler = move |request: Request<&mut EspHttpConnection<'_>>| {
+            if method != Method::Get
+                && !config::clone::is_authorized(request.header("Authorization"))
+            {
+                log::warn!("{method:?} {uri} refused, invalid admin token");
+                return error_response(request, 401, "Invalid admin token");
+            }
 
-        self.server
-            .fn_handler("/assets/js/index.js", Method::Get, web_portal_js())
-            .inspect_err(|&e| {
-                log::error!("Failed to serve JS: {e:#?}");
-            })?;
+            error::panic::catch(AssertUnwindSafe(|| handler(request))).unwrap_or_else(|payload| {
+                let panics = HANDLER_PANICS.fetch_add(1, Ordering::Relaxed) + 1;
+                let message = payload
+                    .downcast_ref::<&str>()
+                    .copied()
+                    .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+                    .unwrap_or("unknown panic");
+
+                log::error!(
+                    "Handler of {method:?} {uri} panicked ({panics} since boot): {message}"
+                );
+                Err(AppError::Server(format!("Handler panicked: {message}")))
+            })
+        };
 
         self.server
-            .fn_handler("/get_status", Method::Get, get_status(wifi_ssid))
+            .fn_handler(uri, method, handler)
             .inspect_err(|&e| {
-                log::error!("Failed to register get_status handler: {e:#?}");
+                log::error!("Failed to register {method:?} {uri} handler: {e:#?}");
             })?;
 
+        Ok(())
+    }
+
+    /// Registers the few routes served in safe mode, see
+    /// [run_safe_mode](health::safe_mode::run_safe_mode): the health report,
+    /// the firmware update, the factory reset and leaving safe mode.
+    pub fn create_safe_mode_routes(&mut self, storage: SharedAppStorage) -> Result<(), AppError> {
+        self.route("/", Method::Get, &[], None, safe_mode_page())?;
+
+        self.route("/api/health", Method::Get, &[], None, get_health())?;
+
+        self.route("/api/safe_mode", Method::Get, &[], None, get_safe_mode())?;
+
+        self.route("/api/ota", Method::Get, &[], None, get_ota())?;
+
+        self.route("/api/ota", Method::Post, &[], None, upload_ota())?;
+
+        self.route(
+            "/api/safe_mode/exit",
+            Method::Post,
+            &[],
+            None,
+            exit_safe_mode(storage.clone()),
+        )?;
+
+        self.route(
+            "/factory_reset/token",
+            Method::Post,
+            &[],
+            None,
+            factory_reset_token(),
+        )?;
+
+        self.route(
+            "/factory_reset",
+            Method::Post,
+            &[],
+            Some("FactoryResetRequest"),
+            factory_reset(storage),
+        )?;
+
+        let mut routes = self.routes.clone();
+        routes.push(RouteInfo {
+            uri: "/api",
+            method: "GET".to_string(),
+            query: &[],
+            body: None,
+        });
+        self.route("/api", Method::Get, &[], None, api_index(routes))?;
+
+        Ok(())
+    }
+
+    pub fn create_routes<CLK: OutputPin, DateDIO: IOPin, YearDIO: IOPin, HourDIO: IOPin>(
+        &mut self,
+        state: AppState<CLK, DateDIO, YearDIO, HourDIO>,
+        sntp: EspSntp<'static>,
+        wifi_ssid: String,
+    ) -> Result<(), AppError> {
+        let AppState {
+            led_strip,
+            storage: app_storage,
+            ..
+        } = state.clone();
+
+        self.route("/", Method::Get, &[], None, web_portal())?;
+
+        self.route(
+            "/assets/index.css",
+            Method::Get,
+            &[],
+            None,
+            web_portal_css(),
+        )?;
+
+        self.route(
+            "/assets/js/index.js",
+            Method::Get,
+            &[],
+            None,
+            web_portal_js(),
+        )?;
+
+        self.route("/get_status", Method::Get, &[], None, get_status(wifi_ssid))?;
+
         self.server
-            .fn_handler(
-                "/set_theme",
-                Method::Get,
-                set_theme(Arc::new(Mutex::new(led_strip))),
-            )
+            .ws_handler("/api/live", live_status::live_status(app_storage.clone()))
             .inspect_err(|&e| {
-                log::error!("Failed to register set_theme handler: {e:#?}");
+                log::error!("Failed to register live_status handler: {e:#?}");
             })?;
+        self.routes.push(RouteInfo {
+            uri: "/api/live",
+            method: "WEBSOCKET".to_string(),
+            query: &[],
+            body: None,
+        });
 
-        self.server
-            .fn_handler(
-                "/set_timezone",
+        self.route("/api/health", Method::Get, &[], None, get_health())?;
+
+        self.route("/api/ota", Method::Get, &[], None, get_ota())?;
+
+        self.route("/api/ota", Method::Post, &[], None, upload_ota())?;
+
+        self.route("/metrics", Method::Get, &[], None, get_metrics())?;
+
+        self.route(
+            "/api/diagnostics",
+            Method::Get,
+            &[],
+            None,
+            get_diagnostics(),
+        )?;
+
+        self.route("/api/log_level", Method::Get, &[], None, get_log_level())?;
+
+        self.route(
+            "/api/log_level",
+            Method::Post,
+            &[],
+            Some("TagLogLevel"),
+            set_log_level(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/api/environment",
+            Method::Get,
+            &[],
+            None,
+            get_environment(),
+        )?;
+
+        self.route(
+            "/set_theme",
+            Method::Post,
+            &[],
+            Some("ThemeRequest"),
+            set_theme(led_strip.clone(), app_storage.clone()),
+        )?;
+
+        self.route(
+            "/api/timezones",
+            Method::Get,
+            &["prefix"],
+            None,
+            get_timezones(),
+        )?;
+
+        self.route("/api/themes", Method::Get, &[], None, get_themes())?;
+
+        self.route(
+            "/set_timezone",
+            Method::Post,
+            &[],
+            Some("TimezoneRequest"),
+            set_timezone(state.clone()),
+        )?;
+
+        self.route(
+            "/set_static_ip",
+            Method::Post,
+            &[],
+            Some("StaticIpRequest"),
+            set_static_ip(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/api/config",
+            Method::Get,
+            &[],
+            None,
+            get_config(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/api/network",
+            Method::Get,
+            &[],
+            None,
+            get_network(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/api/network",
+            Method::Post,
+            &[],
+            Some("CountryCodeRequest"),
+            set_country_code(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/api/config",
+            Method::Post,
+            &[],
+            Some("DeviceConfig"),
+            import_config(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/api/clone_from",
+            Method::Post,
+            &[],
+            Some("CloneRequest"),
+            clone_from(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/api/snapshot",
+            Method::Post,
+            &[],
+            Some("SnapshotRequest"),
+            set_snapshot(app_storage.clone()),
+        )?;
+
+        self.route("/api/webhook", Method::Get, &[], None, get_webhook())?;
+
+        self.route(
+            "/api/webhook",
+            Method::Post,
+            &[],
+            Some("WebhookRequest"),
+            set_webhook(app_storage.clone()),
+        )?;
+
+        self.route("/api/webhook/test", Method::Post, &[], None, test_webhook())?;
+
+        #[cfg(feature = "sd-card")]
+        {
+            self.route("/api/sd_card", Method::Get, &[], None, get_sd_card())?;
+
+            self.route(
+                "/api/sd_card/export",
                 Method::Post,
-                set_timezone(app_storage.clone()),
-            )
-            .inspect_err(|&e| {
-                log::error!("Failed to register set_timezone handler: {e:#?}");
-            })?;
+                &[],
+                None,
+                export_to_sd_card(app_storage.clone()),
+            )?;
+        }
 
-        self.server
-            .fn_handler(
-                "/set_hour_format",
-                Method::Get,
-                set_hour_format(app_storage.clone()),
-            )
-            .inspect_err(|&e| {
-                log::error!("Failed to register set_hour_format handler: {e:#?}");
-            })?;
+        self.route(
+            "/set_leading_zero",
+            Method::Post,
+            &[],
+            Some("LeadingZeroRequest"),
+            set_leading_zero(app_storage.clone()),
+        )?;
 
-        self.server
-            .fn_handler(
-                "/factory_reset",
-                Method::Get,
-                factory_reset(app_storage.clone()),
-            )
-            .inspect_err(|&e| {
-                log::error!("Failed to register sync_time handler: {e:#?}");
-            })?;
+        self.route(
+            "/set_hour_format",
+            Method::Post,
+            &[],
+            Some("HourFormatRequest"),
+            set_hour_format(state.clone()),
+        )?;
 
-        self.server
-            .fn_handler(
-                "/set_brightness",
-                Method::Get,
-                set_brightness(display_group.clone()),
-            )
-            .inspect_err(|&e| {
-                log::error!("Failed to register set_brightness handler: {e:#?}");
-            })?;
+        self.route(
+            "/set_date_format",
+            Method::Post,
+            &[],
+            Some("DateFormatRequest"),
+            set_date_format(app_storage.clone()),
+        )?;
 
-        self.server
-            .fn_handler(
-                "/sync_time",
-                Method::Get,
-                sync_time(display_group, am_pm_indicator.clone(), sntp),
-            )
-            .inspect_err(|&e| {
-                log::error!("Failed to register sync_time handler: {e:#?}");
-            })?;
+        self.route(
+            "/set_weekday_mode",
+            Method::Post,
+            &[],
+            Some("WeekdayModeRequest"),
+            set_weekday_mode(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/set_seconds_mode",
+            Method::Post,
+            &[],
+            Some("SecondsModeRequest"),
+            set_seconds_mode(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/set_motion_timeout",
+            Method::Post,
+            &[],
+            Some("MotionTimeoutRequest"),
+            set_motion_timeout(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/set_touch_action",
+            Method::Post,
+            &[],
+            Some("TouchActionRequest"),
+            set_touch_action(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/factory_reset/token",
+            Method::Post,
+            &[],
+            None,
+            factory_reset_token(),
+        )?;
+
+        self.route(
+            "/factory_reset",
+            Method::Post,
+            &[],
+            Some("FactoryResetRequest"),
+            factory_reset(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/set_brightness",
+            Method::Post,
+            &[],
+            Some("BrightnessRequest"),
+            set_brightness(app_storage.clone()),
+        )?;
+
+        self.route("/api/photo_mode", Method::Get, &[], None, get_photo_mode())?;
+
+        self.route(
+            "/api/photo_mode",
+            Method::Post,
+            &[],
+            Some("PhotoModeRequest"),
+            set_photo_mode(),
+        )?;
+
+        self.route("/api/stopwatch", Method::Get, &[], None, get_stopwatch())?;
+
+        self.route(
+            "/api/stopwatch",
+            Method::Post,
+            &[],
+            Some("StopwatchRequest"),
+            control_stopwatch(),
+        )?;
+
+        self.route("/api/audio", Method::Get, &[], None, get_audio())?;
+
+        self.route(
+            "/api/audio",
+            Method::Post,
+            &[],
+            Some("AudioRequest"),
+            play_audio(),
+        )?;
+
+        self.route(
+            "/set_volume",
+            Method::Post,
+            &[],
+            Some("VolumeRequest"),
+            set_volume(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/api/power",
+            Method::Get,
+            &[],
+            None,
+            get_power(led_strip.clone(), app_storage.clone()),
+        )?;
+
+        self.route(
+            "/api/power",
+            Method::Post,
+            &[],
+            Some("PowerRequest"),
+            set_power(led_strip.clone(), app_storage.clone()),
+        )?;
+
+        self.route(
+            "/set_wifi_power_save",
+            Method::Post,
+            &[],
+            Some("WifiPowerSaveRequest"),
+            set_wifi_power_save(app_storage.clone()),
+        )?;
+
+        self.route("/api/ntp_server", Method::Get, &[], None, get_ntp_server())?;
+
+        self.route(
+            "/api/ntp_server",
+            Method::Post,
+            &[],
+            Some("NtpServerRequest"),
+            set_ntp_server(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/set_digit_transition",
+            Method::Post,
+            &[],
+            Some("DigitTransitionRequest"),
+            set_digit_transition(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/set_display_flip",
+            Method::Post,
+            &[],
+            Some("DisplayFlipRequest"),
+            set_display_flip(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/set_display_roles",
+            Method::Post,
+            &[],
+            Some("DisplayRolesRequest"),
+            set_display_roles(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/set_boot_animation",
+            Method::Post,
+            &[],
+            Some("BootAnimationRequest"),
+            set_boot_animation(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/set_boot_led_effect",
+            Method::Post,
+            &[],
+            Some("BootLedEffectRequest"),
+            set_boot_led_effect(led_strip.clone(), app_storage.clone()),
+        )?;
+
+        self.route("/api/messages", Method::Get, &[], None, get_messages())?;
+
+        self.route(
+            "/api/messages",
+            Method::Post,
+            &[],
+            Some("CustomMessageRequest"),
+            add_message(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/api/messages",
+            Method::Put,
+            &["idx"],
+            Some("CustomMessageRequest"),
+            update_message(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/api/messages",
+            Method::Delete,
+            &["idx"],
+            None,
+            delete_message(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/api/messages/interval",
+            Method::Post,
+            &[],
+            Some("MessageIntervalRequest"),
+            set_message_interval(app_storage.clone()),
+        )?;
+
+        self.route("/api/trackers", Method::Get, &[], None, get_trackers())?;
+
+        self.route(
+            "/api/trackers",
+            Method::Post,
+            &[],
+            Some("Tracker"),
+            add_tracker(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/api/trackers",
+            Method::Put,
+            &["idx"],
+            Some("Tracker"),
+            update_tracker(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/api/trackers",
+            Method::Delete,
+            &["idx"],
+            None,
+            delete_tracker(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/api/trackers/interval",
+            Method::Post,
+            &[],
+            Some("TrackerIntervalRequest"),
+            set_tracker_interval(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/set_colon_blink",
+            Method::Post,
+            &[],
+            Some("ColonBlinkRequest"),
+            set_colon_blink(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/set_temperature_display",
+            Method::Post,
+            &[],
+            Some("TemperatureDisplayRequest"),
+            set_temperature_display(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/set_humidity_display",
+            Method::Post,
+            &[],
+            Some("HumidityDisplayRequest"),
+            set_humidity_display(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/set_alternate_interval",
+            Method::Post,
+            &[],
+            Some("AlternateIntervalRequest"),
+            set_alternate_interval(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/set_hour_strike",
+            Method::Post,
+            &[],
+            Some("HourStrikeRequest"),
+            set_hour_strike(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/set_dst_notice",
+            Method::Post,
+            &[],
+            Some("DstNoticeRequest"),
+            set_dst_notice(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/set_astro_events",
+            Method::Post,
+            &[],
+            Some("AstroEventsRequest"),
+            set_astro_events(app_storage.clone()),
+        )?;
+
+        self.route("/api/astro", Method::Get, &[], None, get_astro_events())?;
+
+        self.route(
+            "/set_daily_summary",
+            Method::Post,
+            &[],
+            Some("DailySummaryRequest"),
+            set_daily_summary(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/set_wake_light",
+            Method::Post,
+            &[],
+            Some("WakeLightRequest"),
+            set_wake_light(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/set_countdown",
+            Method::Post,
+            &[],
+            Some("CountdownRequest"),
+            set_countdown(app_storage.clone()),
+        )?;
+
+        self.route("/api/presence", Method::Get, &[], None, get_presence())?;
+
+        self.route(
+            "/api/presence",
+            Method::Post,
+            &[],
+            Some("PresenceConfig"),
+            set_presence(app_storage.clone()),
+        )?;
+
+        self.route("/api/weather", Method::Get, &[], None, get_weather())?;
+
+        self.route(
+            "/api/weather",
+            Method::Post,
+            &[],
+            Some("WeatherConfig"),
+            set_weather(app_storage.clone()),
+        )?;
+
+        self.route("/api/auto_dim", Method::Get, &[], None, get_auto_dim())?;
+
+        self.route(
+            "/api/auto_dim",
+            Method::Post,
+            &[],
+            Some("AutoDimConfig"),
+            set_auto_dim(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/api/board",
+            Method::Get,
+            &[],
+            None,
+            get_board(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/api/board",
+            Method::Post,
+            &[],
+            Some("BoardConfig"),
+            set_board(app_storage.clone()),
+        )?;
+
+        self.route("/gpio", Method::Get, &[], None, get_gpio())?;
+
+        self.route("/gpio", Method::Post, &[], Some("RelayRequest"), set_gpio())?;
+
+        self.route("/api/relay", Method::Get, &[], None, get_relay())?;
+
+        self.route(
+            "/api/relay",
+            Method::Post,
+            &[],
+            Some("RelayConfig"),
+            set_relay(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/api/time_phrase",
+            Method::Get,
+            &["lang"],
+            None,
+            get_time_phrase(),
+        )?;
+
+        self.route("/api/rules", Method::Get, &[], None, get_rules())?;
+
+        self.route(
+            "/api/rules",
+            Method::Post,
+            &[],
+            Some("Rules"),
+            set_rules(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/api/rules/enable",
+            Method::Post,
+            &[],
+            Some("RuleEnableRequest"),
+            set_rule_enabled(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/api/rules/dry_run",
+            Method::Get,
+            &["time", "weekday", "battery"],
+            None,
+            dry_run_rules(),
+        )?;
+
+        self.route("/api/macros", Method::Get, &[], None, get_macros())?;
+
+        self.route(
+            "/api/macros",
+            Method::Post,
+            &[],
+            Some("Macros"),
+            set_macros(app_storage.clone()),
+        )?;
+
+        self.route(
+            "/api/macros/run",
+            Method::Post,
+            &[],
+            Some("MacroRunRequest"),
+            run_macro(led_strip.clone(), app_storage.clone()),
+        )?;
+
+        self.route(
+            "/display_message",
+            Method::Post,
+            &[],
+            Some("DisplayMessageRequest"),
+            display_message(),
+        )?;
+
+        self.route("/sync_time", Method::Post, &[], None, sync_time(sntp))?;
+
+        // Registered last, so it lists every route above and itself.
+        let mut routes = self.routes.clone();
+        routes.push(RouteInfo {
+            uri: "/api",
+            method: "GET".to_string(),
+            query: &[],
+            body: None,
+        });
+        self.route("/api", Method::Get, &[], None, api_index(routes))?;
 
         Ok(())
     }
 }
 
+/// Lists the registered routes, their methods and expected parameters, like
+/// `{"routes":[{"uri":"/set_hour_format","method":"GET","query":["hour_format"
+/// ],"body":null}]}`, so integrators can discover what the running firmware
+/// supports.
+///
+/// ## Arguments
+/// - `routes` - The [RouteInfo] of every registered route.
+pub fn api_index(
+    routes: Vec<RouteInfo>,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let routes_json = serde_json::json!({ "routes": routes }).to_string();
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(routes_json.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
 /// Generates the web portal page response for the HTTP request.
 ///
 /// This function returns a closure that handles the HTTP request for the
@@ -198,7 +1005,8 @@ pub fn web_portal_js() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result
 }
 
 /// Returns the current status of the system including Wi-Fi SSID, Timezone and
-/// actual time.
+/// actual time, plus the battery charge on builds with the `battery` feature
+/// and the displays that keep failing to update.
 ///
 /// ## Returns
 /// A closure that handles the HTTP request and returns an HTML response with
@@ -208,330 +1016,4202 @@ pub fn get_status(
 ) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
     move |request: Request<&mut EspHttpConnection<'_>>| {
         let timezone = time::tz::get_timezone();
-        let time = time::get_hour_min();
+        let time = time::get_hour_min()?;
         let wifi_ssid = wifi_ssid.as_str();
 
-        let status_html = format!(
+        let mut status_html = format!(
             "<p><strong>Wi-Fi SSID:</strong> {wifi_ssid}</p>
         <p><strong>Time Zone:</strong> {timezone}</p>
         <p><strong>Current Time:</strong> {}{}:{}{}</p>",
             time[0], time[1], time[2], time[3]
         );
 
+        if let Some(battery) = power::battery::get_battery_status() {
+            status_html.push_str(&format!(
+                "\n        <p><strong>Battery:</strong> {}% ({:.2} V){}</p>",
+                battery.percent,
+                battery.voltage_mv as f32 / 1000.0,
+                if battery.low { " - low" } else { "" }
+            ));
+        }
+
+        if let Some(diagnostics) = health::diagnostics::get_diagnostics() {
+            status_html.push_str(&format!(
+                "\n        <p><strong>Free Heap:</strong> {} KB (min {} KB, largest block {} KB)</p>",
+                diagnostics.free_heap / 1024,
+                diagnostics.min_free_heap / 1024,
+                diagnostics.largest_free_block / 1024
+            ));
+        }
+
+        for subsystem in [
+            Subsystem::DateDisplay,
+            Subsystem::YearDisplay,
+            Subsystem::HourDisplay,
+        ] {
+            let failures = health::get_consecutive_failures(subsystem);
+            if failures > 0 {
+                status_html.push_str(&format!(
+                    "\n        <p><strong>{subsystem:?}:</strong> failing, {failures} updates failed in a row</p>"
+                ));
+            }
+        }
+
         request.into_ok_response()?.write(status_html.as_bytes())?;
 
         Ok::<(), AppError>(())
     }
 }
 
-/// Sets the timezone based on the timezone data from the request body.
-///
-/// This function extracts the timezone information from the incoming request,
-/// validates the format, and updates the timezone accordingly. It also
-/// saves the timezone data in NVS and responds with a success message.
+/// Returns the health of every subsystem as JSON.
 ///
-/// ## Arguments
-/// - `tz_nvs` - A [Mutex] wrapping the [SharedAppStorage] instance for storing
-///   the timezone data.
+/// The report is built from the health registry, so it only lists the
+/// subsystems that have reported at least once.
 ///
 /// ## Returns
-/// A closure that handles the HTTP request, validates the timezone, updates the
-/// system timezone, saves the data in NVS, and responds with a success message.
-pub fn set_timezone(
-    storage: SharedAppStorage,
-) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
-    move |mut request: Request<&mut EspHttpConnection<'_>>| {
-        let mut buf = [0u8; 128];
-        let len = request.read(&mut buf)?;
-        let buf = &buf[..len];
+/// A closure that handles the HTTP request and returns a JSON response like
+/// `{"status":"degraded","subsystems":[{"subsystem":"sntp","state":"degraded","
+/// last_error":"..."}]}`.
+pub fn get_health() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let report = serde_json::to_string(&health::get_report())
+            .map_err(|e| AppError::Server(e.to_string()))?;
 
-        let timezone_data: TimezoneRequest = match serde_json::from_slice(buf) {
-            Ok(data) => data,
-            Err(_) => {
-                log::error!("Invalid JSON format");
-                request.into_status_response(400)?;
-                return Err(AppError::Server("Invalid request".to_string()));
-            }
-        };
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(report.as_bytes())?;
 
-        if Tz::from_str(&timezone_data.timezone).is_err() {
-            log::error!("Invalid timezone: {}", timezone_data.timezone);
-            request.into_status_response(400)?;
-            return Err(AppError::Server("Invalid request".to_string()));
-        }
+        Ok::<(), AppError>(())
+    }
+}
 
-        storage
-            .lock()
-            .unwrap()
-            .save_timezone(timezone_data.clone())?;
-        time::tz::set_timezone(timezone_data.timezone);
+/// Explains why the firmware is in safe mode, in place of the web portal.
+///
+/// ## Returns
+/// A closure that handles the HTTP request and returns a plain text page with
+/// the crash count, the last panic and how to leave safe mode.
+pub fn safe_mode_page() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let last_panic =
+            error::panic::get_last_panic().unwrap_or_else(|| "none recorded".to_string());
+
+        let page = format!(
+            "SAFE MODE\n\n\
+             The clock restarted {} times in a row, so only the Wi-Fi and this page were started.\n\
+             Last panic: {last_panic}\n\n\
+             GET /api/health for the health report.\n\
+             POST /api/safe_mode/exit to clear the count and restart normally.\n\
+             Power the clock off and on to retry.\n",
+            health::safe_mode::get_crash_count()
+        );
 
         request
-            .into_ok_response()?
-            .write("Timezone changed!".as_bytes())?;
+            .into_response(200, None, &[("Content-Type", "text/plain")])?
+            .write_all(page.as_bytes())?;
 
         Ok::<(), AppError>(())
     }
 }
 
-/// Sets the hour format (12h or 24h).
-///
-/// This function extracts the hour format value from the URL query parameter
-/// (`0` for 12h, `1` for 24h). It validates the value, updates the
-/// application's in-memory state for immediate effect, and saves the preference
-/// to NVS for persistence across restarts.
-///
-/// ## Arguments
-/// - `storage` - A [SharedAppStorage] instance used to save the hour format
-///   setting to NVS.
+/// Returns whether the firmware booted into safe mode, and the number of
+/// resets in a row counted at boot.
 ///
 /// ## Returns
-/// A closure that handles the HTTP request, validates the hour format from the
-/// URL, updates both the runtime state and persistent storage, and responds
-/// with a success message.
-pub fn set_hour_format(
-    storage: SharedAppStorage,
-) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+/// A closure that handles the HTTP request and returns a JSON response like
+/// `{"safe_mode":true,"crash_count":5,"crash_limit":5}`.
+pub fn get_safe_mode() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
     move |request: Request<&mut EspHttpConnection<'_>>| {
-        let url = request.uri();
-
-        if let Some(start) = url.find('?') {
-            let hour_format_value = &url[start + 1..];
-            if let Ok(hour_format) = hour_format_value.parse::<u8>() {
-                if (0..=1).contains(&hour_format) {
-                    storage
-                        .lock()
-                        .unwrap()
-                        .save_hour_format(hour_format.into())?;
-                    prefs::hour_format::set_hour_format(hour_format.into());
-                } else {
-                    log::warn!("Invalid hour_format: '{hour_format}'");
-                    return Err(AppError::Server("Invalid request".to_string()));
-                }
-            }
-        }
+        let safe_mode_json = serde_json::json!({
+            "safe_mode": health::safe_mode::is_safe_mode(),
+            "crash_count": health::safe_mode::get_crash_count(),
+            "crash_limit": health::safe_mode::CRASH_LIMIT,
+        })
+        .to_string();
 
         request
-            .into_ok_response()?
-            .write("Hour format changed!".as_bytes())?;
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(safe_mode_json.as_bytes())?;
 
         Ok::<(), AppError>(())
     }
 }
 
-/// Creates an HTTP handler that performs a factory reset by deleting Wi-Fi
-/// credentials and restarting the device.
+/// Clears the crash count and restarts, so the firmware boots normally.
 ///
-/// ## Behavior
-/// - Deletes the stored Wi-Fi credentials from NVS.
-/// - Deletes the stored Timezone settings from NVS.
-/// - Disconnects from the current Wi-Fi network.
-/// - Restarts the ESP32 device.
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to clear the crash count.
 ///
 /// ## Returns
-/// - A closure that can be used as an HTTP request handler.
-/// - This function does not return control after execution, as the device
-///   restarts.
-pub fn factory_reset(
+/// A closure that handles the HTTP request, clears the crash count and
+/// restarts the device once the response is sent.
+pub fn exit_safe_mode(
     storage: SharedAppStorage,
 ) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
-    move |_: Request<&mut EspHttpConnection<'_>>| {
-        storage.lock().unwrap().delete_wifi_credentials()?;
-        storage.lock().unwrap().delete_timezone()?;
-        log::info!("Factory reset initiated!");
-        log::info!("Restarting...");
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_crash_count(0)?;
+        log::info!("Leaving safe mode");
 
-        unsafe {
-            esp_wifi_disconnect();
-            esp_restart();
-        }
+        request
+            .into_ok_response()?
+            .write("Leaving safe mode! Restarting...".as_bytes())?;
+
+        // Restart once the response has reached the browser
+        std::thread::spawn(|| {
+            std::thread::sleep(std::time::Duration::from_millis(500));
+
+            unsafe {
+                esp_restart();
+            }
+        });
+
+        Ok::<(), AppError>(())
     }
 }
 
-/// Sets the brightness of the display based on the request URL.
+/// Returns the app partitions, the firmware they hold and whether the last
+/// update was rolled back, as JSON.
 ///
-/// This function extracts the brightness value from the URL query parameters
-/// and updates the display's brightness accordingly. The brightness value must
-/// be between 0 and 7.
+/// ## Returns
+/// A closure that handles the HTTP request and returns a JSON response like
+/// `{"running":{"label":"ota_1","state":"valid","version":"0.1.0"},"update":{..
+/// .},"last_invalid":null}`.
+pub fn get_ota() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let ota_json = serde_json::to_string(&ota::get_status()?)
+            .map_err(|e| AppError::Server(e.to_string()))?;
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(ota_json.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Writes the firmware image in the request body to the other app partition
+/// and restarts into it, e.g. `curl -H "Authorization: Bearer secret"
+/// --data-binary @esp-bttf-clock-rs.bin http://bttf.local/api/ota`.
+///
+/// The upload requires the admin token, checked by
+/// [route](WebPortal::route), and is refused altogether when the firmware was
+/// built without one, see [has_admin_token](config::clone::has_admin_token).
+///
+/// ## Returns
+/// A closure that handles the HTTP request, writes the firmware, responds with
+/// a success message and restarts the device. The new firmware is rolled
+/// back if it doesn't come up, see [ota::first_boot_check_job].
+pub fn upload_ota() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        if !config::clone::has_admin_token() {
+            log::warn!("Firmware upload refused, no admin token configured");
+            return error_response(
+                request,
+                403,
+                "Firmware upload disabled, build with an ADMIN_TOKEN",
+            );
+        }
+
+        let len = match request.content_len() {
+            Some(len) if len > 0 => len as usize,
+            _ => return error_response(request, 411, "Missing firmware image"),
+        };
+
+        if let Err(e) = ota::write_update(&mut request, len) {
+            return error_response(request, 400, &format!("Firmware update failed: {e}"));
+        }
+
+        request
+            .into_ok_response()?
+            .write("Firmware updated! Restarting...".as_bytes())?;
+
+        // Restart once the response has reached the browser
+        std::thread::spawn(|| {
+            std::thread::sleep(std::time::Duration::from_millis(500));
+
+            unsafe {
+                esp_restart();
+            }
+        });
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Returns the last reading of the room sensor and the pressure trend as JSON.
+///
+/// ## Returns
+/// A closure that handles the HTTP request and returns a JSON response like
+/// `{"reading":{"temperature_c":21.4,"humidity":45.2,"pressure_hpa":1013.2},"
+/// pressure_trend":"falling"}`.
+pub fn get_environment() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let environment = serde_json::to_string(&sensor::get_environment())
+            .map_err(|e| AppError::Server(e.to_string()))?;
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(environment.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Returns the presence configuration and the presence of the phone as JSON.
+///
+/// ## Returns
+/// A closure that handles the HTTP request and returns a JSON response like
+/// `{"config":{"ip":"192.168.1.30","away_after":15},"status":{"present":true,"
+/// away":false,"last_seen_secs":42}}`.
+pub fn get_presence() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let presence_json = serde_json::json!({
+            "config": presence::get_presence_config(),
+            "status": presence::get_presence_status(),
+        })
+        .to_string();
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(presence_json.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets the phone watched by the presence service, e.g.
+/// `{"ip":"192.168.1.30","away_after":15}`, or `{"ip":null}` to disable it.
+///
+/// The configuration takes effect on the next check and is saved in NVS.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the setting.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, validates and saves the
+/// configuration, and responds with a success message, or with a JSON error
+/// and a `400` status for an invalid configuration.
+pub fn set_presence(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 128];
+        let len = request.read(&mut buf)?;
+
+        let config = match serde_json::from_slice::<PresenceConfig>(&buf[..len]) {
+            Ok(config) => config,
+            Err(_) => {
+                log::warn!("Invalid JSON format");
+                return error_response(request, 400, "Invalid JSON");
+            }
+        };
+
+        if !config.is_valid() {
+            log::warn!("Invalid presence config: {config:?}");
+            return error_response(
+                request,
+                400,
+                &format!(
+                    "away_after must be between 1 and {} minutes",
+                    presence::MAX_AWAY_AFTER
+                ),
+            );
+        }
+
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_presence(config)?;
+        presence::set_presence_config(config);
+        log::info!("Presence config changed to {config:?}");
+
+        request
+            .into_ok_response()?
+            .write("Presence config changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Returns the weather configuration and the last outdoor temperature as JSON.
+///
+/// ## Returns
+/// A closure that handles the HTTP request and returns a JSON response like
+/// `{"config":{"location":{"latitude":34.14,"longitude":-118.35},"interval":30,
+/// "show":true},"temperature_c":21.3}`.
+pub fn get_weather() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let weather_json = serde_json::json!({
+            "config": weather::get_weather_config(),
+            "temperature_c": weather::get_outdoor_temperature(),
+        })
+        .to_string();
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(weather_json.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets the location and interval of the weather client, e.g.
+/// `{"location":{"latitude":34.14,"longitude":-118.35},"interval":30,"show":
+/// true}`, or `{"location":null}` to disable it.
+///
+/// A new location is fetched within a minute, and the configuration is saved
+/// in NVS.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the setting.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, validates and saves the
+/// configuration, and responds with a success message, or with a JSON error
+/// and a `400` status for an invalid configuration.
+pub fn set_weather(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 128];
+        let len = request.read(&mut buf)?;
+
+        let config = match serde_json::from_slice::<WeatherConfig>(&buf[..len]) {
+            Ok(config) => config,
+            Err(_) => {
+                log::warn!("Invalid JSON format");
+                return error_response(request, 400, "Invalid JSON");
+            }
+        };
+
+        if !config.is_valid() {
+            log::warn!("Invalid weather config: {config:?}");
+            return error_response(
+                request,
+                400,
+                &format!(
+                    "The location must be valid coordinates and the interval between 1 and {} minutes",
+                    weather::MAX_WEATHER_INTERVAL
+                ),
+            );
+        }
+
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_weather(config)?;
+        weather::set_weather_config(config);
+        log::info!("Weather config changed to {config:?}");
+
+        request
+            .into_ok_response()?
+            .write("Weather config changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Returns the auto-dimming configuration, whether the displays are dimmed and
+/// today's sunrise and sunset at its location as JSON.
+///
+/// ## Returns
+/// A closure that handles the HTTP request and returns a JSON response like
+/// `{"config":{"location":{"latitude":34.14,"longitude":-118.35},
+/// "night_brightness":1,"night_led":30},"dimmed":false,"sunrise":"06:52",
+/// "sunset":"18:31"}`. The sunrise and the sunset are `null` without a
+/// location, or on days the sun doesn't rise or set.
+pub fn get_auto_dim() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let config = auto_dim::get_auto_dim_config();
+        let sun_times = config.location.map(|location| {
+            sun::local_sun_times(time::get_timestamp(), location.latitude, location.longitude)
+        });
+
+        let auto_dim_json = serde_json::json!({
+            "config": config,
+            "dimmed": auto_dim::is_dimmed(),
+            "sunrise": sun_times.as_ref().and_then(|times| times.sunrise.clone()),
+            "sunset": sun_times.as_ref().and_then(|times| times.sunset.clone()),
+        })
+        .to_string();
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(auto_dim_json.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets the location and the night brightness of the auto-dimming, e.g.
+/// `{"location":{"latitude":34.14,"longitude":-118.35},"night_brightness":1,
+/// "night_led":30}`, or `{"location":null}` to disable it.
+///
+/// The change is picked up within a minute, and the configuration is saved in
+/// NVS.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the setting.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, validates and saves the
+/// configuration, and responds with a success message, or with a JSON error
+/// and a `400` status for an invalid configuration.
+pub fn set_auto_dim(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 128];
+        let len = request.read(&mut buf)?;
+
+        let config = match serde_json::from_slice::<AutoDimConfig>(&buf[..len]) {
+            Ok(config) => config,
+            Err(_) => {
+                log::warn!("Invalid JSON format");
+                return error_response(request, 400, "Invalid JSON");
+            }
+        };
+
+        if !config.is_valid() {
+            log::warn!("Invalid auto-dimming config: {config:?}");
+            return error_response(
+                request,
+                400,
+                "The location must be valid coordinates, night_brightness between 0 and 7 and night_led between 0 and 100",
+            );
+        }
+
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_auto_dim(config)?;
+        auto_dim::set_auto_dim_config(config);
+        log::info!("Auto-dimming config changed to {config:?}");
+
+        request
+            .into_ok_response()?
+            .write("Auto-dimming config changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Returns the GPIOs of the board saved in NVS as JSON, or the defaults when
+/// none are saved.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to read the setting.
+///
+/// ## Returns
+/// A closure that handles the HTTP request and returns a JSON response like
+/// `{"display_clk":16,"date_display_dio":17,...,"power_good":35}`.
+pub fn get_board(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let config = storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_maybe_board()
+            .map_err(AppError::Config)?
+            .unwrap_or_default();
+
+        let board_json =
+            serde_json::to_string(&config).map_err(|e| AppError::Server(e.to_string()))?;
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(board_json.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets the GPIOs of the board, e.g. `{"led_strip":4,"hour_display_dio":25}`.
+/// The pins left out keep their defaults.
+///
+/// The pins are only taken at boot, so the change takes effect on the next
+/// restart.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the setting.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, validates and saves the
+/// configuration, and responds with a success message, or with a JSON error
+/// and a `400` status for an invalid configuration.
+pub fn set_board(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 512];
+        let len = request.read(&mut buf)?;
+
+        let config = match serde_json::from_slice::<BoardConfig>(&buf[..len]) {
+            Ok(config) => config,
+            Err(_) => {
+                log::warn!("Invalid JSON format");
+                return error_response(request, 400, "Invalid JSON");
+            }
+        };
+
+        if !config.is_valid() {
+            log::warn!("Invalid board config: {config:?}");
+            return error_response(
+                request,
+                400,
+                "Every pin must be a different usable GPIO, and only power_good may be input only",
+            );
+        }
+
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_board(config)?;
+        log::info!("Board config changed to {config:?}");
+
+        request
+            .into_ok_response()?
+            .write("Board config changed! Restart to apply.".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Returns the current local time as a spoken phrase, for smart speakers that
+/// announce the time of the clock.
+///
+/// The optional `lang` query parameter selects the language of the phrase,
+/// see [PHRASE_LANGUAGES](time::PHRASE_LANGUAGES). It defaults to English.
+///
+/// ## Returns
+/// A closure that handles the HTTP request and returns a JSON response like
+/// `{"time":"21:15","language":"en","phrase":"quarter past nine in the
+/// evening"}`.
+pub fn get_time_phrase() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let query = QueryParams::from_uri(request.uri());
+        let language = query.get("lang").unwrap_or("en").to_string();
+
+        if !time::PHRASE_LANGUAGES.contains(&language.as_str()) {
+            return error_response(request, 400, "Unsupported language");
+        }
+
+        let minute_of_day = time::get_minute_of_day()?;
+        let (hour, minute) = (minute_of_day / 60, minute_of_day % 60);
+
+        let phrase_json = serde_json::json!({
+            "time": format!("{hour:02}:{minute:02}"),
+            "language": language,
+            "phrase": time::time_phrase(hour, minute),
+        })
+        .to_string();
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(phrase_json.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Returns the counters of the firmware and the heap gauges of the last
+/// [diagnostics](health::diagnostics) sample in the Prometheus text format,
+/// like `http_handler_panics_total 0`.
+///
+/// ## Returns
+/// A closure that handles the HTTP request and returns the metrics.
+pub fn get_metrics() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let mut metrics = format!(
+            "# HELP http_handler_panics_total HTTP handlers that panicked since boot.\n\
+             # TYPE http_handler_panics_total counter\n\
+             http_handler_panics_total {}\n",
+            HANDLER_PANICS.load(Ordering::Relaxed)
+        );
+
+        if let Some(diagnostics) = health::diagnostics::get_diagnostics() {
+            metrics.push_str(&format!(
+                "# HELP heap_free_bytes Free heap at the last sample.\n\
+                 # TYPE heap_free_bytes gauge\n\
+                 heap_free_bytes {}\n\
+                 # HELP heap_largest_free_block_bytes Largest free block at the last sample.\n\
+                 # TYPE heap_largest_free_block_bytes gauge\n\
+                 heap_largest_free_block_bytes {}\n",
+                diagnostics.free_heap, diagnostics.largest_free_block
+            ));
+        }
+
+        request
+            .into_response(200, None, &[("Content-Type", "text/plain; version=0.0.4")])?
+            .write_all(metrics.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Returns the free heap, the largest free block and the stack left to every
+/// task at the last sample, as JSON.
+///
+/// ## Returns
+/// A closure that handles the HTTP request and returns a JSON response like
+/// `{"free_heap":98304,"min_free_heap":81920,"largest_free_block":65536,"tasks"
+/// :[{"name":"httpd","stack_high_water_mark":2712},...]}`, or a 503 error
+/// before the first sample.
+pub fn get_diagnostics() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let Some(diagnostics) = health::diagnostics::get_diagnostics() else {
+            return error_response(request, 503, "No diagnostics sampled yet");
+        };
+
+        let diagnostics_json =
+            serde_json::to_string(&diagnostics).map_err(|e| AppError::Server(e.to_string()))?;
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(diagnostics_json.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Returns the log levels set per tag as JSON.
+///
+/// ## Returns
+/// A closure that handles the HTTP request and returns a JSON response like
+/// `{"levels":[{"tag":"wifi","level":"verbose"}]}`.
+pub fn get_log_level() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let log_levels_json = serde_json::to_string(&log_level::get_log_levels())
+            .map_err(|e| AppError::Server(e.to_string()))?;
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(log_levels_json.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Changes the log level of a tag at runtime and saves it in NVS, so it is
+/// applied again at boot.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the log levels.
+///
+/// ## Returns
+/// A closure that handles the HTTP request with a JSON [TagLogLevel] body,
+/// like `{"tag":"wifi","level":"verbose"}`, and responds with a success
+/// message.
+pub fn set_log_level(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 128];
+        let len = request.read(&mut buf)?;
+
+        let tag_level = match serde_json::from_slice::<TagLogLevel>(&buf[..len]) {
+            Ok(tag_level) if tag_level.is_valid() => tag_level,
+            _ => {
+                log::warn!("Invalid log level request");
+                return error_response(request, 400, "Invalid tag or level");
+            }
+        };
+
+        let mut log_levels = log_level::get_log_levels();
+        if !log_levels.set(tag_level.clone()) {
+            return error_response(
+                request,
+                400,
+                &format!("Only {MAX_LOG_TAGS} tags can have their own level"),
+            );
+        }
+
+        tag_level.apply()?;
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_log_levels(log_levels.clone())?;
+        log_level::set_log_levels(log_levels);
+        log::info!(
+            "Log level of '{}' changed to {:?}",
+            tag_level.tag,
+            tag_level.level
+        );
+
+        request
+            .into_ok_response()?
+            .write("Log level changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Returns the IANA names of the supported timezones as a JSON array.
+///
+/// The optional `prefix` query parameter (e.g. `/api/timezones?prefix=Europe/`)
+/// limits the list to the names starting with it, ignoring case.
+///
+/// ## Returns
+/// A closure that handles the HTTP request and returns a JSON response like
+/// `["Europe/Amsterdam","Europe/Andorra",...]`.
+pub fn get_timezones() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let query = QueryParams::from_uri(request.uri());
+        let prefix = query.get_or_bare("prefix").unwrap_or("");
+
+        let timezones = serde_json::to_string(&time::tz::list_timezones(prefix))
+            .map_err(|e| AppError::Server(e.to_string()))?;
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(timezones.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets the timezone based on the timezone data from the request body.
+///
+/// This function extracts the timezone information from the incoming request,
+/// validates the format, and updates the timezone accordingly. It also
+/// saves the timezone data in NVS, renders the new local time on the displays
+/// right away and responds with a success message.
+///
+/// ## Arguments
+/// - `state` - The [AppState] used to save and apply the timezone.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, validates the timezone, updates the
+/// system timezone, saves the data in NVS, and responds with a success message.
+pub fn set_timezone<CLK: OutputPin, DateDIO: IOPin, YearDIO: IOPin, HourDIO: IOPin>(
+    state: AppState<CLK, DateDIO, YearDIO, HourDIO>,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 128];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let timezone_data: TimezoneRequest = match serde_json::from_slice(buf) {
+            Ok(data) => data,
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                request.into_status_response(400)?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        if !time::tz::is_valid_timezone(&timezone_data.timezone) {
+            log::error!("Invalid timezone: {}", timezone_data.timezone);
+            request.into_status_response(400)?;
+            return Err(AppError::Server("Invalid request".to_string()));
+        }
+
+        state.set_timezone(timezone_data.timezone)?;
+
+        // Render the new local time, date and year right away instead of at the
+        // next minute tick. DST transitions fall on a minute boundary, where the
+        // update loop renders them already
+        display_queue::send(DisplayCommand::ShowTime);
+
+        request
+            .into_ok_response()?
+            .write("Timezone changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets or clears the static IP configuration of the station interface.
+///
+/// This function parses a [StaticIpRequest] from the request body. A
+/// `static_ip` object is validated and saved in NVS, while a `null` value
+/// removes the stored configuration so the station goes back to DHCP. The
+/// change takes effect on the next restart.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to persist the
+///   configuration.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, validates the configuration, saves
+/// it in NVS, and responds with a success message.
+pub fn set_static_ip(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 256];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let static_ip_data: StaticIpRequest = match serde_json::from_slice(buf) {
+            Ok(data) => data,
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                request.into_status_response(400)?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        match static_ip_data.static_ip {
+            Some(static_ip) => {
+                if !static_ip.is_valid() {
+                    log::error!("Invalid static IP configuration: {static_ip:?}");
+                    request.into_status_response(400)?;
+                    return Err(AppError::Server("Invalid request".to_string()));
+                }
+
+                storage
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .save_static_ip(static_ip)?;
+            }
+            None => storage
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .delete_static_ip()?,
+        }
+
+        request
+            .into_ok_response()?
+            .write("Static IP updated! Restart to apply.".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Returns the network settings of the station as JSON [NetworkSettings].
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to read the settings.
+///
+/// ## Returns
+/// A closure that handles the HTTP request and returns the static IP
+/// configuration and the Wi-Fi country code.
+pub fn get_network(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let network = {
+            let mut storage = storage.lock().unwrap_or_else(PoisonError::into_inner);
+
+            NetworkSettings {
+                static_ip: storage.get_maybe_static_ip().unwrap_or(None),
+                country_code: storage
+                    .get_maybe_country_code()
+                    .unwrap_or(None)
+                    .unwrap_or(wifi::DEFAULT_COUNTRY_CODE.to_string()),
+            }
+        };
+        let network_json =
+            serde_json::to_string(&network).map_err(|e| AppError::Server(e.to_string()))?;
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(network_json.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets the Wi-Fi country code, e.g. `{"country_code":"DE"}`.
+///
+/// The code is applied to the driver right away and saved in NVS, so it is
+/// applied again before connecting on every boot.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to persist the country
+///   code.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, validates and applies the country
+/// code, and responds with a success message, or with a JSON error and a `400`
+/// status for an invalid code.
+pub fn set_country_code(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+
+        let country_code = match serde_json::from_slice::<CountryCodeRequest>(&buf[..len]) {
+            Ok(data) => data.country_code.to_ascii_uppercase(),
+            Err(_) => {
+                log::warn!("Invalid JSON format");
+                return error_response(request, 400, "Invalid JSON");
+            }
+        };
+
+        if !wifi::is_valid_country_code(&country_code) {
+            log::warn!("Invalid country code: '{country_code}'");
+            return error_response(request, 400, "Invalid country code");
+        }
+
+        wifi::set_country_code(&country_code)?;
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_country_code(country_code.clone())?;
+
+        request
+            .into_ok_response()?
+            .write(format!("Country code set to {country_code}!").as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Returns the stored settings as a JSON [DeviceConfig], without secrets.
+///
+/// Responds with `401` unless the request carries the admin token of the
+/// clock, see [is_authorized](config::clone::is_authorized), so only the
+/// clocks knowing it can clone the settings.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to read the settings.
+///
+/// ## Returns
+/// A closure that handles the HTTP request and returns the settings as JSON.
+pub fn get_config(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        if !config::clone::is_authorized(request.header("Authorization")) {
+            log::warn!("Settings export refused, invalid admin token");
+            return error_response(request, 401, "Invalid admin token");
+        }
+
+        let config = DeviceConfig::export(&storage)?.without_secrets();
+        let config = serde_json::to_string(&config).map_err(|e| AppError::Server(e.to_string()))?;
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(config.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Imports a JSON [DeviceConfig] from the request body, such as a settings
+/// snapshot taken from another board.
+///
+/// The body is read until its end, and refused with a 413 response when it is
+/// longer than [MAX_CONFIG_LEN] bytes.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the settings.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, validates and saves the settings,
+/// and responds with a success message.
+pub fn import_config(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut body = Vec::new();
+        let mut buf = [0u8; 256];
+        loop {
+            let len = request.read(&mut buf)?;
+            if len == 0 {
+                break;
+            }
+
+            if body.len() + len > MAX_CONFIG_LEN {
+                log::warn!("Config import longer than {MAX_CONFIG_LEN} bytes");
+                return error_response(request, 413, "Request too big");
+            }
+            body.extend_from_slice(&buf[..len]);
+        }
+
+        let config: DeviceConfig = match serde_json::from_slice(&body) {
+            Ok(data) => data,
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                request.into_status_response(400)?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        if let Err(e) = config.apply(&storage) {
+            log::error!("Failed to import config: {e}");
+            request.into_status_response(400)?;
+            return Err(e);
+        }
+
+        request
+            .into_ok_response()?
+            .write("Config imported! Restart to apply.".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Clones the settings of another clock on the LAN and restarts.
+///
+/// This function parses a [CloneRequest] like
+/// `{"host":"bttf-2.local","token":"secret"}`, where the token is the admin
+/// token of that clock if it has one, pulls the settings export of that clock,
+/// saves it in NVS and restarts to apply it.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the settings.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, clones the settings, responds with
+/// a success message and restarts the device.
+pub fn clone_from(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 256];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let (host, token) = match serde_json::from_slice::<CloneRequest>(buf) {
+            Ok(data) if config::clone::is_valid_host(&data.host) => (data.host, data.token),
+            Ok(data) => {
+                log::warn!("Invalid clone host: '{}'", data.host);
+                error_response(request, 400, "Invalid host")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        if let Err(e) = config::clone::clone_from(&host, token.as_deref(), &storage) {
+            log::error!("Failed to clone settings from {host}: {e}");
+            request
+                .into_status_response(502)?
+                .write(format!("Failed to clone settings: {e}").as_bytes())?;
+            return Err(e);
+        }
+
+        request
+            .into_ok_response()?
+            .write(format!("Settings cloned from {host}! Restarting...").as_bytes())?;
+
+        // Restart once the response has reached the browser
+        std::thread::spawn(|| {
+            std::thread::sleep(std::time::Duration::from_millis(500));
+
+            unsafe {
+                esp_restart();
+            }
+        });
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets or clears the URL the nightly settings snapshot is sent to.
+///
+/// This function parses a [SnapshotRequest] from the request body. A `url` is
+/// validated and saved in NVS, while a `null` value disables the snapshot.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the URL.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, saves or removes the URL, and
+/// responds with a success message.
+pub fn set_snapshot(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 384];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let snapshot_data: SnapshotRequest = match serde_json::from_slice(buf) {
+            Ok(data) => data,
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                request.into_status_response(400)?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        match snapshot_data.url {
+            Some(url) => {
+                if !snapshot::is_valid_url(&url) {
+                    log::error!("Invalid snapshot URL: {url}");
+                    error_response(request, 400, "Only HTTP(S) snapshot URLs are supported")?;
+                    return Err(AppError::Server("Invalid request".to_string()));
+                }
+
+                storage
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .save_snapshot_url(url)?;
+            }
+            None => storage
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .delete_snapshot_url()?,
+        }
+
+        request
+            .into_ok_response()?
+            .write("Snapshot URL updated!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Returns the webhook URL in JSON format, e.g.
+/// `{"url":"https://ntfy.sh/my-bttf-clock"}`, or `{"url":null}` when the
+/// notifications are disabled.
+pub fn get_webhook() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let webhook_json = serde_json::json!({ "url": webhook::get_webhook_url() }).to_string();
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(webhook_json.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets or clears the URL the notifications are sent to.
+///
+/// This function parses a [WebhookRequest] from the request body. A `url` is
+/// validated, applied and saved in NVS, while a `null` value disables the
+/// notifications.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the URL.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, saves or removes the URL, and
+/// responds with a success message.
+pub fn set_webhook(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 384];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let webhook_data: WebhookRequest = match serde_json::from_slice(buf) {
+            Ok(data) => data,
+            Err(_) => return error_response(request, 400, "Invalid JSON format"),
+        };
+
+        match webhook_data.url {
+            Some(url) => {
+                if !webhook::is_valid_url(&url) {
+                    return error_response(request, 400, &format!("Invalid webhook URL: {url}"));
+                }
+
+                storage
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .save_webhook_url(url.clone())?;
+                webhook::set_webhook_url(Some(url));
+            }
+            None => {
+                storage
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .delete_webhook_url()?;
+                webhook::set_webhook_url(None);
+            }
+        }
+
+        request
+            .into_ok_response()?
+            .write("Webhook URL updated!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sends a `test` notification to the webhook URL right away, so the URL can
+/// be checked from the web portal.
+///
+/// ## Returns
+/// A closure that handles the HTTP request and responds with `409` if no URL
+/// is set, or `502` if the webhook failed.
+pub fn test_webhook() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        if webhook::get_webhook_url().is_none() {
+            return error_response(request, 409, "No webhook URL set");
+        }
+
+        if let Err(e) = webhook::send(&Notification::new("test", "Test notification")) {
+            return error_response(request, 502, &format!("Webhook failed: {e}"));
+        }
+
+        request
+            .into_ok_response()?
+            .write("Webhook sent!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Returns the state of the relay in JSON format, e.g.
+/// `{"enabled":true,"on":true,"remaining_secs":600}`.
+pub fn get_gpio() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let status_json = serde_json::to_string(&relay::get_status())
+            .map_err(|e| AppError::Server(e.to_string()))?;
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(status_json.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Switches the relay on or off from the request body.
+///
+/// This function parses a [RelayRequest] like
+/// `{"state":"on","duration":600}`, where the state is `on`, `off` or
+/// `toggle` and the optional duration is the number of seconds after which the
+/// relay switches off by itself (up to a day).
+///
+/// ## Returns
+/// A closure that handles the HTTP request and returns the new state as JSON,
+/// like `{"enabled":true,"on":true,"remaining_secs":600}`, or a JSON error with
+/// a `400` status for an invalid request and `409` if no relay is configured.
+pub fn set_gpio() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let relay_request = match serde_json::from_slice::<RelayRequest>(buf) {
+            Ok(data) => data,
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        let duration = match relay_request.duration {
+            Some(seconds) if (1..=relay::MAX_RELAY_DURATION).contains(&seconds) => {
+                Some(std::time::Duration::from_secs(seconds as u64))
+            }
+            Some(seconds) => {
+                log::warn!("Invalid relay duration: {seconds}");
+                return error_response(request, 400, "Invalid duration");
+            }
+            None => None,
+        };
+
+        if !relay::get_status().enabled {
+            return error_response(request, 409, "No relay configured");
+        }
+
+        match relay_request.state {
+            RelaySwitch::On => relay::switch(true, duration)?,
+            RelaySwitch::Off => relay::switch(false, None)?,
+            RelaySwitch::Toggle => relay::toggle(duration)?,
+        }
+
+        let status_json = serde_json::to_string(&relay::get_status())
+            .map_err(|e| AppError::Server(e.to_string()))?;
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(status_json.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Returns the relay configuration in JSON format, e.g.
+/// `{"pin":2,"active_low":true,"alarm_duration":900}`.
+pub fn get_relay() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let relay_json = serde_json::to_string(&relay::get_relay_config())
+            .map_err(|e| AppError::Server(e.to_string()))?;
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(relay_json.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets the relay configuration, e.g.
+/// `{"pin":2,"active_low":true,"alarm_duration":900}`. The fields left out
+/// keep their defaults.
+///
+/// The pin must not be used by the board, see [BoardConfig]. It is only taken
+/// at boot, so a new pin takes effect on the next restart, while the alarm
+/// duration applies right away.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to read the board and save
+///   the setting.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, validates and saves the
+/// configuration, and responds with a success message, or with a JSON error
+/// and a `400` status for an invalid configuration.
+pub fn set_relay(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 256];
+        let len = request.read(&mut buf)?;
+
+        let config = match serde_json::from_slice::<RelayConfig>(&buf[..len]) {
+            Ok(config) => config,
+            Err(_) => {
+                log::warn!("Invalid JSON format");
+                return error_response(request, 400, "Invalid JSON");
+            }
+        };
+
+        let board = storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_maybe_board()
+            .map_err(AppError::Config)?
+            .unwrap_or_default();
+
+        if !config.is_valid(&board) {
+            log::warn!("Invalid relay config: {config:?}");
+            return error_response(
+                request,
+                400,
+                "The pin must be a usable output GPIO not used by the board",
+            );
+        }
+
+        let pin_changed = config.pin != relay::get_relay_config().pin;
+
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_relay(config)?;
+        relay::set_relay_config(config);
+        log::info!("Relay config changed to {config:?}");
+
+        let message = if pin_changed {
+            "Relay config changed! Restart to apply the pin."
+        } else {
+            "Relay config changed!"
+        };
+
+        request.into_ok_response()?.write(message.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Returns whether an SD card is mounted and the logs are written to it, e.g.
+/// `{"mounted":true,"logging":true}`, on builds with the `sd-card` feature.
+#[cfg(feature = "sd-card")]
+pub fn get_sd_card() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let sd_card_json = serde_json::json!({
+            "mounted": config::sd_card::is_mounted(),
+            "logging": health::log_file::is_logging(),
+        })
+        .to_string();
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(sd_card_json.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Exports the stored settings, without secrets, to `config.export.json` on
+/// the SD card, on builds with the `sd-card` feature. Renamed to
+/// `config.json`, the file is imported by another clock at boot.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to read the settings.
+///
+/// ## Returns
+/// A closure that handles the HTTP request and responds with a success
+/// message, or with a JSON error and a `409` status if no SD card is mounted.
+#[cfg(feature = "sd-card")]
+pub fn export_to_sd_card(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        if !config::sd_card::is_mounted() {
+            return error_response(request, 409, "No SD card mounted");
+        }
+
+        config::sd_card::export_config(&storage)?;
+
+        request
+            .into_ok_response()?
+            .write("Settings exported to the SD card!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets the hour format (12h or 24h).
+///
+/// This function parses a [HourFormatRequest] like `{"hour_format":0}` from
+/// the request body (`0` for 12h, `1` for 24h). It validates the value,
+/// updates the application's in-memory state for immediate effect, and saves
+/// the preference to NVS for persistence across restarts. The hour display and
+/// the AM/PM LEDs are rendered again right away in the new format.
+///
+/// ## Arguments
+/// - `state` - The [AppState] used to save and apply the hour format.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, validates the hour format from the
+/// body, updates both the runtime state and persistent storage, and responds
+/// with a success message.
+pub fn set_hour_format<CLK: OutputPin, DateDIO: IOPin, YearDIO: IOPin, HourDIO: IOPin>(
+    state: AppState<CLK, DateDIO, YearDIO, HourDIO>,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let hour_format = match serde_json::from_slice::<HourFormatRequest>(buf) {
+            Ok(data) if data.hour_format <= 1 => data.hour_format,
+            Ok(data) => {
+                log::warn!("Invalid hour_format: '{}'", data.hour_format);
+                error_response(request, 400, "Hour format must be 0 or 1")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        state.set_hour_format(hour_format.into())?;
+
+        display_queue::send(DisplayCommand::ShowTime);
+        log::info!(
+            "Hour format changed to {}",
+            if hour_format == 1 { "24h" } else { "12h" }
+        );
+
+        request
+            .into_ok_response()?
+            .write("Hour format changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets whether single-digit hours and dates are shown with a leading zero.
+///
+/// This function parses a [LeadingZeroRequest] like `{"leading_zero":false}`
+/// from the request body (`false` for a blank digit, e.g. ` 9:05`, `true` for
+/// a leading zero, e.g. `09:05`), updates the in-memory flag, saves it to NVS
+/// and renders the displays again right away.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the setting.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, updates the setting, and responds
+/// with a success message.
+pub fn set_leading_zero(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let enabled = match serde_json::from_slice::<LeadingZeroRequest>(buf) {
+            Ok(data) => data.leading_zero,
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_leading_zero(enabled)?;
+        prefs::leading_zero::set_leading_zero(enabled);
+
+        display_queue::send(DisplayCommand::ShowTime);
+
+        log::info!("Leading zero changed to '{enabled}'");
+
+        request
+            .into_ok_response()?
+            .write("Leading zero changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets the date format (`DD.MM` or `MM.DD`).
+///
+/// This function parses a [DateFormatRequest] like `{"date_format":1}` from
+/// the request body (`0` for day first, `1` for month first). It validates the
+/// value, updates the application's in-memory state, saves the preference to
+/// NVS and renders the date display again right away.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the date format
+///   setting to NVS.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, validates the date format from the
+/// URL, updates both the runtime state and persistent storage, and responds
+/// with a success message.
+pub fn set_date_format(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let date_format = match serde_json::from_slice::<DateFormatRequest>(buf) {
+            Ok(data) if data.date_format <= 1 => data.date_format,
+            Ok(data) => {
+                log::warn!("Invalid date_format: '{}'", data.date_format);
+                error_response(request, 400, "Date format must be 0 or 1")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_date_format(date_format.into())?;
+        prefs::date_format::set_date_format(date_format.into());
+
+        display_queue::send(DisplayCommand::ShowTime);
+
+        log::info!(
+            "Date format changed to {}",
+            if date_format == 1 { "MM.DD" } else { "DD.MM" }
+        );
+
+        request
+            .into_ok_response()?
+            .write("Date format changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets how the weekday is shown on the date display.
+///
+/// This function parses a [WeekdayModeRequest] like `{"weekday_mode":2}` from
+/// the request body (`0` for the date only, `1` for the weekday only, `2` to
+/// alternate between them), saves it to NVS and renders the date display
+/// again.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the setting.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, updates the mode, and responds
+/// with a success message.
+pub fn set_weekday_mode(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let weekday_mode = match serde_json::from_slice::<WeekdayModeRequest>(buf) {
+            Ok(data) if data.weekday_mode <= 2 => WeekdayMode::from(data.weekday_mode),
+            Ok(data) => {
+                log::warn!("Invalid weekday_mode: '{}'", data.weekday_mode);
+                error_response(request, 400, "Weekday mode must be between 0 and 2")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_weekday_mode(weekday_mode)?;
+        prefs::weekday_mode::set_weekday_mode(weekday_mode);
+
+        display_queue::send(DisplayCommand::ShowTime);
+
+        log::info!("Weekday mode changed to '{weekday_mode:?}'");
+
+        request
+            .into_ok_response()?
+            .write("Weekday mode changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets how many seconds without motion the clock waits before it is
+/// blanked at night, on builds with the `pir` feature.
+///
+/// This function parses a [MotionTimeoutRequest] like `{"timeout":300}` from
+/// the request body (from `10` to `3600` seconds), updates the in-memory
+/// setting for immediate effect and saves it to NVS.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the setting.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, updates the timeout, and responds
+/// with a success message.
+pub fn set_motion_timeout(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let timeout = match serde_json::from_slice::<MotionTimeoutRequest>(buf) {
+            Ok(data)
+                if (motion_timeout::MIN_MOTION_TIMEOUT..=motion_timeout::MAX_MOTION_TIMEOUT)
+                    .contains(&data.timeout) =>
+            {
+                data.timeout
+            }
+            Ok(data) => {
+                log::warn!("Invalid motion timeout: '{}'", data.timeout);
+                error_response(
+                    request,
+                    400,
+                    &format!(
+                        "Motion timeout must be between {} and {} seconds",
+                        motion_timeout::MIN_MOTION_TIMEOUT,
+                        motion_timeout::MAX_MOTION_TIMEOUT
+                    ),
+                )?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_motion_timeout(timeout)?;
+        motion_timeout::set_motion_timeout(timeout);
+        log::info!("Motion timeout changed to {timeout} seconds");
+
+        request
+            .into_ok_response()?
+            .write("Motion timeout changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets what a tap on the touch pad does.
+///
+/// This function parses a [TouchActionRequest] like `{"touch_action":2}` from
+/// the request body (`0` to ignore the taps, `1` to switch the night mode, `2`
+/// to play the time travel animation) and saves it to NVS. It applies to the
+/// next tap.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the setting.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, updates the action, and responds
+/// with a success message.
+pub fn set_touch_action(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let touch_action = match serde_json::from_slice::<TouchActionRequest>(buf) {
+            Ok(data) if data.touch_action <= 2 => TouchAction::from(data.touch_action),
+            Ok(data) => {
+                log::warn!("Invalid touch_action: '{}'", data.touch_action);
+                error_response(request, 400, "Touch action must be 0, 1 or 2")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_touch_action(touch_action)?;
+        prefs::touch_action::set_touch_action(touch_action);
+
+        log::info!("Touch action changed to '{touch_action:?}'");
+
+        request
+            .into_ok_response()?
+            .write("Touch action changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets what the year display shows.
+///
+/// This function parses a [SecondsModeRequest] like `{"seconds_mode":1}` from
+/// the request body (`0` for the year, `1` for the seconds, `2` for the
+/// minutes and the seconds), saves it to NVS and renders the year display
+/// again.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the setting.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, updates the mode, and responds
+/// with a success message.
+pub fn set_seconds_mode(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let seconds_mode = match serde_json::from_slice::<SecondsModeRequest>(buf) {
+            Ok(data) if data.seconds_mode <= 2 => SecondsMode::from(data.seconds_mode),
+            Ok(data) => {
+                log::warn!("Invalid seconds_mode: '{}'", data.seconds_mode);
+                error_response(request, 400, "Seconds mode must be between 0 and 2")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_seconds_mode(seconds_mode)?;
+        prefs::seconds_mode::set_seconds_mode(seconds_mode);
+
+        display_queue::send(DisplayCommand::ShowTime);
+
+        log::info!("Seconds mode changed to '{seconds_mode:?}'");
+
+        request
+            .into_ok_response()?
+            .write("Seconds mode changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Issues the confirmation token required by [factory_reset].
+///
+/// The token expires after
+/// [TOKEN_LIFETIME](config::factory_reset::TOKEN_LIFETIME) and replaces any
+/// previous one.
+///
+/// ## Returns
+/// A closure that handles the HTTP request and returns a JSON response like
+/// `{"token":"3f2a9c41d07be865","expires_in":60}`.
+pub fn factory_reset_token() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError>
+{
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let token_json = serde_json::json!({
+            "token": factory_reset::issue_token(),
+            "expires_in": factory_reset::TOKEN_LIFETIME.as_secs(),
+        })
+        .to_string();
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(token_json.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Creates an HTTP handler that performs a factory reset, confirmed with a
+/// [FactoryResetRequest] holding a token from [factory_reset_token].
+///
+/// ## Behavior
+/// - Responds with `400` if the body is invalid, and with `403` if the token is
+///   wrong or expired.
+/// - Otherwise, responds right away, counts down on the hour display, then
+///   deletes the stored Wi-Fi credentials, timezone, static IP, snapshot URL
+///   and webhook URL and restarts the ESP32 device, see
+///   [factory_reset](config::factory_reset::factory_reset).
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to delete the settings.
+///
+/// ## Returns
+/// A closure that can be used as an HTTP request handler.
+pub fn factory_reset(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 128];
+        let len = request.read(&mut buf)?;
+
+        let token = match serde_json::from_slice::<FactoryResetRequest>(&buf[..len]) {
+            Ok(data) => data.token,
+            Err(_) => {
+                log::warn!("Invalid JSON format");
+                return error_response(request, 400, "Invalid JSON");
+            }
+        };
+
+        if !factory_reset::take_token(&token) {
+            log::warn!("Factory reset rejected, the token is wrong or expired");
+            return error_response(request, 403, "Wrong or expired token");
+        }
+
+        let reset_storage = storage.clone();
+        std::thread::Builder::new()
+            .stack_size(4096)
+            .spawn(move || {
+                factory_reset::factory_reset(&reset_storage)
+                    .inspect_err(|e| log::error!("Factory reset failed: {e:?}"))
+            })?;
+
+        request.into_ok_response()?.write(
+            format!(
+                "Factory reset in {} seconds!",
+                factory_reset::COUNTDOWN_SECONDS
+            )
+            .as_bytes(),
+        )?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets the brightness of every display from the request body.
+///
+/// This function parses a [BrightnessRequest] like `{"brightness":5}`, saves
+/// it to NVS so it is restored at boot, and updates the brightness of the
+/// displays. The brightness value must be between 0 and 7.
+///
+/// ## Behavior
+/// - Responds with `400` and a JSON error body if the request is invalid.
+/// - Responds with `409` while photo mode is active, as it locks the
+///   brightness.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the brightness.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, updates the brightness, and returns
+/// a success message.
+pub fn set_brightness(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let brightness = match serde_json::from_slice::<BrightnessRequest>(buf) {
+            Ok(data) if data.brightness <= 7 => data.brightness,
+            Ok(data) => {
+                log::warn!("Invalid brightness: {}", data.brightness);
+                error_response(request, 400, "Brightness must be between 0 and 7")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        if prefs::photo_mode::is_photo_mode() {
+            log::warn!("Brightness is locked while photo mode is active");
+            error_response(
+                request,
+                409,
+                "Brightness is locked while photo mode is active",
+            )?;
+            return Err(AppError::Server("Photo mode active".to_string()));
+        }
+
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_brightness(brightness)?;
+        display_queue::send(DisplayCommand::SetBrightness(brightness));
+        log::info!("Brightness updated to level {brightness}");
+
+        request
+            .into_ok_response()?
+            .write("Brightness Updated!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Returns whether photo mode is active in JSON format, e.g.
+/// `{"photo_mode":true}`.
+pub fn get_photo_mode() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let photo_mode_json = format!("{{\"photo_mode\":{}}}", prefs::photo_mode::is_photo_mode());
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(photo_mode_json.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Toggles photo mode, which keeps the displays flicker-free for filming.
+///
+/// This function parses a [PhotoModeRequest] like `{"photo_mode":true}` from
+/// the request body, which enables or disables the mode.
+///
+/// ## Behavior
+/// - When enabled, the current brightness is saved and every display is driven
+///   at [PHOTO_MODE_BRIGHTNESS](prefs::photo_mode::PHOTO_MODE_BRIGHTNESS), the
+///   level with the highest TM1637 duty cycle, to avoid banding.
+/// - While enabled, brightness changes are rejected and animations pause.
+/// - When disabled, the saved brightness is restored.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, updates photo mode, and responds
+/// with its current state as JSON.
+pub fn set_photo_mode() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    let saved_brightness = Mutex::new(0u8);
+
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let enabled = match serde_json::from_slice::<PhotoModeRequest>(buf) {
+            Ok(data) => data.photo_mode,
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        if enabled != prefs::photo_mode::is_photo_mode() {
+            let brightness = if enabled {
+                *saved_brightness.lock().unwrap() = display_queue::brightness();
+                prefs::photo_mode::PHOTO_MODE_BRIGHTNESS
+            } else {
+                *saved_brightness.lock().unwrap()
+            };
+            display_queue::send(DisplayCommand::SetBrightness(brightness));
+
+            prefs::photo_mode::set_photo_mode(enabled);
+            log::info!(
+                "Photo mode {}",
+                if enabled { "enabled" } else { "disabled" }
+            );
+        }
+
+        let photo_mode_json = format!("{{\"photo_mode\":{}}}", prefs::photo_mode::is_photo_mode());
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(photo_mode_json.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Returns the [StopwatchStatus](time::stopwatch::StopwatchStatus) in JSON
+/// format, e.g. `{"active":true,"running":false,"elapsed_ms":462000}`.
+pub fn get_stopwatch() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let stopwatch_json = serde_json::to_string(&time::stopwatch::get_status())
+            .map_err(|e| AppError::Server(e.to_string()))?;
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(stopwatch_json.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Starts, stops or resets the stopwatch on the hour display.
+///
+/// This function parses a [StopwatchRequest] like `{"action":"start"}` from
+/// the request body, with the action `start`, `stop` or `reset`. The hour
+/// display goes back to the clock once the stopwatch is reset, or stopped for
+/// [IDLE_TIMEOUT](time::stopwatch::IDLE_TIMEOUT).
+///
+/// ## Returns
+/// A closure that handles the HTTP request, applies the action, and responds
+/// with the [StopwatchStatus](time::stopwatch::StopwatchStatus) as JSON.
+pub fn control_stopwatch() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let action = match serde_json::from_slice::<StopwatchRequest>(buf) {
+            Ok(data) => data.action,
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        time::stopwatch::apply(action);
+        log::info!("Stopwatch {action:?}");
+
+        let stopwatch_json = serde_json::to_string(&time::stopwatch::get_status())
+            .map_err(|e| AppError::Server(e.to_string()))?;
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(stopwatch_json.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Returns the [AudioStatus](audio::AudioStatus) in JSON format, e.g.
+/// `{"enabled":true,"volume":2}`.
+pub fn get_audio() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let audio_json = serde_json::to_string(&audio::get_status())
+            .map_err(|e| AppError::Server(e.to_string()))?;
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(audio_json.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Plays a stored melody, on builds with the `audio` feature.
+///
+/// This function parses an [AudioRequest] like `{"melody":"theme"}` from the
+/// request body, with the melody `alarm` or `theme`.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, queues the melody, and responds
+/// with the [AudioStatus](audio::AudioStatus) as JSON, or with a JSON error
+/// and a `409` status if the firmware has no audio output.
+pub fn play_audio() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let melody = match serde_json::from_slice::<AudioRequest>(buf) {
+            Ok(data) => data.melody,
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        if !audio::get_status().enabled {
+            return error_response(
+                request,
+                409,
+                "No audio output, build with the audio feature",
+            );
+        }
+
+        audio::play(melody);
+
+        let audio_json = serde_json::to_string(&audio::get_status())
+            .map_err(|e| AppError::Server(e.to_string()))?;
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(audio_json.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets the volume of the melodies, on builds with the `audio` feature.
+///
+/// This function parses a [VolumeRequest] like `{"volume":3}` from the request
+/// body (from `0`, muted, to `4`), updates the in-memory setting for immediate
+/// effect and saves it to NVS.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the setting.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, updates the volume, and responds
+/// with a success message.
+pub fn set_volume(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let level = match serde_json::from_slice::<VolumeRequest>(buf) {
+            Ok(data) if data.volume <= volume::MAX_VOLUME => data.volume,
+            Ok(data) => {
+                log::warn!("Invalid volume: '{}'", data.volume);
+                error_response(
+                    request,
+                    400,
+                    &format!("Volume must be between 0 and {}", volume::MAX_VOLUME),
+                )?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_volume(level)?;
+        volume::set_volume(level);
+        log::info!("Volume changed to {level}");
+
+        request
+            .into_ok_response()?
+            .write("Volume changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Responds with a [PowerReport] as JSON, estimated from the displays, the
+/// LED strip and the theme.
+fn power_report_response(
+    request: Request<&mut EspHttpConnection<'_>>,
+    led_strip: &SharedLedStrip,
+    storage: &SharedAppStorage,
+) -> Result<(), AppError> {
+    let theme = storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_maybe_theme()
+        .unwrap_or(None)
+        .unwrap_or_default();
+
+    let lit_segments = display_queue::lit_segments();
+    let brightness = display_queue::brightness();
+
+    let (num_leds, led_levels) = {
+        let led_strip = led_strip.lock().unwrap_or_else(PoisonError::into_inner);
+        (led_strip.num_leds, led_strip.color_levels(&theme))
+    };
+
+    let report = PowerReport::estimate(lit_segments, brightness, num_leds, led_levels);
+    let report_json =
+        serde_json::to_string(&report).map_err(|e| AppError::Server(e.to_string()))?;
+
+    request
+        .into_response(200, None, &[("Content-Type", "application/json")])?
+        .write_all(report_json.as_bytes())?;
+
+    Ok(())
+}
+
+/// Reports the estimated current draw of the clock.
+///
+/// ## Arguments
+/// - `led_strip` - A [SharedLedStrip] instance.
+/// - `storage` - A [SharedAppStorage] instance used to read the theme.
+///
+/// ## Returns
+/// A closure that handles the HTTP request and responds with a [PowerReport]
+/// as JSON.
+pub fn get_power(
+    led_strip: SharedLedStrip,
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        power_report_response(request, &led_strip, &storage)
+    }
+}
+
+/// Enables or disables the low-power display mode and the power-save mode, and
+/// reports the estimated current draw of the clock.
+///
+/// This function parses a [PowerRequest] like
+/// `{"low_power":true,"power_save":false}` from the request body. A mode left
+/// out is kept as is.
+///
+/// ## Behavior
+/// - While the low-power display mode is enabled, the displays are strobed
+///   between their brightness level and the level below it, see
+///   [run_display_task](crate::module::display_queue::run_display_task).
+/// - The strobe pauses in photo mode and at brightness 0.
+/// - While the power-save mode is enabled, the CPU sleeps whenever it is idle
+///   and Wi-Fi stays in modem sleep, see
+///   [apply_power_save](crate::power::sleep::apply_power_save).
+/// - The settings are saved to NVS.
+///
+/// ## Arguments
+/// - `led_strip` - A [SharedLedStrip] instance.
+/// - `storage` - A [SharedAppStorage] instance used to save the setting and
+///   read the theme.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, updates the mode, and responds
+/// with a [PowerReport] as JSON.
+pub fn set_power(
+    led_strip: SharedLedStrip,
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let power_request = match serde_json::from_slice::<PowerRequest>(buf) {
+            Ok(data) => data,
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        if let Some(enabled) = power_request.low_power {
+            storage
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .save_low_power(enabled)?;
+            prefs::low_power::set_low_power(enabled);
+            log::info!(
+                "Low-power display mode {}",
+                if enabled { "enabled" } else { "disabled" }
+            );
+        }
+
+        if let Some(enabled) = power_request.power_save {
+            power::sleep::apply_power_save(enabled)?;
+            storage
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .save_power_save(enabled)?;
+            prefs::power_save::set_power_save(enabled);
+        }
+
+        power_report_response(request, &led_strip, &storage)
+    }
+}
+
+/// Sets how much the Wi-Fi radio sleeps while connected.
+///
+/// This function parses a [WifiPowerSaveRequest] like `{"wifi_power_save":2}`
+/// from the request body (`0` never sleeps, `1` wakes for every beacon, `2`
+/// only wakes every few beacons), applies it to the driver and saves it to
+/// NVS.
+///
+/// ## Behavior
+/// - `2` lowers the consumption the most, but the web portal may answer with a
+///   lag.
+/// - `0` answers the fastest, but keeps the CPU out of light sleep in
+///   power-save mode, see
+///   [apply_power_save](crate::power::sleep::apply_power_save).
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the setting.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, updates the mode, and responds
+/// with a success message.
+pub fn set_wifi_power_save(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let wifi_power_save = match serde_json::from_slice::<WifiPowerSaveRequest>(buf) {
+            Ok(data) if data.wifi_power_save <= 2 => WifiPowerSave::from(data.wifi_power_save),
+            Ok(data) => {
+                log::warn!("Invalid wifi_power_save: '{}'", data.wifi_power_save);
+                error_response(request, 400, "Wi-Fi power save must be 0, 1 or 2")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        wifi::set_power_save(wifi_power_save)?;
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_wifi_power_save(wifi_power_save)?;
+        prefs::wifi_power_save::set_wifi_power_save(wifi_power_save);
+
+        request
+            .into_ok_response()?
+            .write("Wi-Fi power save changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Returns the state of the LAN time server in JSON format, e.g.
+/// `{"enabled":true,"synced":true,"last_sync":1700000000,"stratum":3,"answered"
+/// :42}`.
+pub fn get_ntp_server() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let status_json = serde_json::to_string(&ntp::get_status())
+            .map_err(|e| AppError::Server(e.to_string()))?;
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(status_json.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Enables or disables the LAN time server, and reports its state.
+///
+/// This function parses a [NtpServerRequest] like `{"enabled":true}` from the
+/// request body.
+///
+/// ## Behavior
+/// - While enabled, the clock answers NTP requests on UDP port 123 once it is
+///   synced with SNTP, see [run_ntp_server](ntp::run_ntp_server).
+/// - The setting is saved to NVS.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the setting.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, updates the setting, and responds
+/// with an [NtpServerStatus](ntp::NtpServerStatus) as JSON.
+pub fn set_ntp_server(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let enabled = match serde_json::from_slice::<NtpServerRequest>(buf) {
+            Ok(data) => data.enabled,
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_ntp_server(enabled)?;
+        prefs::ntp_server::set_ntp_server(enabled);
+        log::info!(
+            "LAN time server {}",
+            if enabled { "enabled" } else { "disabled" }
+        );
+
+        let status_json = serde_json::to_string(&ntp::get_status())
+            .map_err(|e| AppError::Server(e.to_string()))?;
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(status_json.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets the transition played when the digits of a display change.
+///
+/// This function parses a [DigitTransitionRequest] like `{"hour":2}` from the
+/// request body, applies the transition to the displays it names and saves
+/// the transitions of every display to NVS.
+///
+/// ## Behavior
+/// - The display is one of `date`, `year` or `hour`.
+/// - The transition is `0` (instant), `1` (brief blank) or `2` (roll-up).
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the setting.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, updates the transition, and
+/// responds with a success message.
+pub fn set_digit_transition(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let transition_request = match serde_json::from_slice::<DigitTransitionRequest>(buf) {
+            Ok(data) => data,
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        let mut transitions = digit_transition::get_digit_transitions();
+        let mut changed = false;
+
+        for (display, value, transition) in [
+            ("date", transition_request.date, &mut transitions.date),
+            ("year", transition_request.year, &mut transitions.year),
+            ("hour", transition_request.hour, &mut transitions.hour),
+        ] {
+            let Some(value) = value else {
+                continue;
+            };
+
+            if value > 2 {
+                log::warn!("Invalid digit transition of the {display} display: '{value}'");
+                error_response(request, 400, "Transition must be between 0 and 2")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+            *transition = DigitTransition::from(value);
+            changed = true;
+        }
+
+        if !changed {
+            log::warn!("Digit transition request without a display");
+            error_response(request, 400, "Missing display")?;
+            return Err(AppError::Server("Invalid request".to_string()));
+        }
+
+        display_queue::send(DisplayCommand::SetTransitions(transitions));
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_digit_transitions(transitions)?;
+        digit_transition::set_digit_transitions(transitions);
+        log::info!("Digit transitions changed to {transitions:?}");
+
+        request
+            .into_ok_response()?
+            .write("Digit transition changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets whether the displays are mounted rotated by 180°.
+///
+/// This function parses a [DisplayFlipRequest] like `{"display_flip":true}`
+/// from the request body (`false` upright, `true` upside down), updates the
+/// in-memory flag, saves it to NVS and draws the current digits again right
+/// away.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the setting.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, updates the setting, and responds
+/// with a success message.
+pub fn set_display_flip(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let flipped = match serde_json::from_slice::<DisplayFlipRequest>(buf) {
+            Ok(data) => data.display_flip,
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_display_flip(flipped)?;
+        prefs::display_flip::set_display_flip(flipped);
+
+        display_queue::send(DisplayCommand::Redraw);
+
+        log::info!("Display flip changed to '{flipped}'");
+
+        request
+            .into_ok_response()?
+            .write("Display flip changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets the physical display showing the date, the year and the hour, for
+/// displays wired in another order.
+///
+/// This function parses a [DisplayRolesRequest] like
+/// `{"date":2,"year":1,"hour":0}` from the request body, applies it, saves it
+/// to NVS and renders the displays again right away.
+///
+/// ## Behavior
+/// - The position is `0`, `1` or `2`, the display wired to the date, year or
+///   hour pins.
+/// - Missing roles keep their position, but every role must end up on a
+///   different display.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the setting.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, updates the roles, and responds
+/// with a success message.
+pub fn set_display_roles(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let roles_request = match serde_json::from_slice::<DisplayRolesRequest>(buf) {
+            Ok(data) => data,
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        let mut roles = display_roles::get_display_roles();
+
+        for (role, value, position) in [
+            ("date", roles_request.date, &mut roles.date),
+            ("year", roles_request.year, &mut roles.year),
+            ("hour", roles_request.hour, &mut roles.hour),
+        ] {
+            let Some(value) = value else {
+                continue;
+            };
+
+            match DisplayPosition::try_from(value) {
+                Ok(value) => *position = value,
+                Err(()) => {
+                    log::warn!("Invalid position of the {role} display: '{value}'");
+                    error_response(request, 400, "Position must be between 0 and 2")?;
+                    return Err(AppError::Server("Invalid request".to_string()));
+                }
+            }
+        }
+
+        if !roles.is_valid() {
+            return error_response(request, 400, "Every role needs its own display");
+        }
+
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_display_roles(roles)?;
+        display_roles::set_display_roles(roles);
+
+        display_queue::send(DisplayCommand::SetTransitions(
+            digit_transition::get_digit_transitions(),
+        ));
+        display_queue::send(DisplayCommand::ShowTime);
+
+        log::info!("Display roles changed to {roles:?}");
+
+        request
+            .into_ok_response()?
+            .write("Display roles changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Synchronizes the system time using SNTP and updates the display with the
+/// sync message.
+///
+/// This function restarts the SNTP synchronization process and responds right
+/// away. The time is rendered again by the SNTP callback once the
+/// synchronization is finished, see [get_sntp](time::sntp::get_sntp).
+///
+/// ## Arguments
+/// - `sntp` - An instance of [EspSntp] used to synchronize the time.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, restarts the synchronization, and
+/// returns a success message.
+pub fn sync_time(
+    sntp: EspSntp<'static>,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        if sntp.get_sync_status() == SyncStatus::InProgress {
+            log::info!("SNTP synchronization already in progress");
+        } else {
+            unsafe {
+                sntp_restart();
+            }
+
+            log::info!("Synchronizing with SNTP Server");
+            display_queue::send(DisplayCommand::ShowStatus(DisplayMessage::Sync.as_bytes()));
+        }
+
+        request
+            .into_ok_response()?
+            .write("Time sync started!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Returns the rules that color the LED strip zones as JSON.
+///
+/// ## Returns
+/// A closure that handles the HTTP request and returns a JSON response like
+/// `{"rules":[{"name":"BED SOON","conditions":[...],"colors":[...]}]}`.
+pub fn get_rules() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let rules = serde_json::to_string(&rules::get_rules())
+            .map_err(|e| AppError::Server(e.to_string()))?;
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(rules.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Replaces the rules that color the LED strip zones.
+///
+/// This function parses [Rules] from the request body, validates them, and
+/// saves them. They are evaluated at the start of every minute, see
+/// [rules_job](rules::rules_job).
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the rules.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, saves the rules, and responds with
+/// a success message.
+pub fn set_rules(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let len = request.content_len().unwrap_or(0) as usize;
+
+        if len > MAX_RULES_LEN {
+            return error_response(request, 413, "Request too big");
+        }
+
+        let mut buf = vec![0; len];
+        request
+            .read_exact(&mut buf)
+            .map_err(|e| AppError::Server(format!("Failed to read the rules: {e:?}")))?;
+
+        let new_rules: Rules = match serde_json::from_slice(&buf) {
+            Ok(new_rules) => new_rules,
+            Err(_) => return error_response(request, 400, "Invalid JSON"),
+        };
+
+        if !new_rules.is_valid() {
+            return error_response(
+                request,
+                400,
+                &format!(
+                    "Up to {MAX_RULES} rules with a unique name, 1 to {MAX_RULE_ITEMS} valid \
+                     conditions and up to {MAX_RULE_ITEMS} colors or a macro are allowed"
+                ),
+            );
+        }
+
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_rules(new_rules.clone())?;
+        rules::set_rules(new_rules);
+
+        request
+            .into_ok_response()?
+            .write("Rules saved!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Enables or disables a rule, by name.
+///
+/// This function parses a [RuleEnableRequest] like
+/// `{"name":"BED SOON","enabled":false}` from the request body and saves the
+/// rules. Disabled rules are kept but never evaluated.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the rules.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, updates the rule, and responds with
+/// a success message.
+pub fn set_rule_enabled(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 128];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let RuleEnableRequest { name, enabled } =
+            match serde_json::from_slice::<RuleEnableRequest>(buf) {
+                Ok(data) => data,
+                Err(_) => return error_response(request, 400, "Invalid JSON format"),
+            };
+
+        let mut new_rules = rules::get_rules();
+
+        let Some(rule) = new_rules.rules.iter_mut().find(|rule| rule.name == name) else {
+            return error_response(request, 404, "Rule not found");
+        };
+
+        rule.enabled = enabled;
+        log::info!(
+            "Rule '{name}' {}",
+            if enabled { "enabled" } else { "disabled" }
+        );
+
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_rules(new_rules.clone())?;
+        rules::set_rules(new_rules);
+
+        request
+            .into_ok_response()?
+            .write("Rule updated!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Evaluates the rules without applying them, to debug them.
+///
+/// The current time, weekday and battery percentage can be overridden with the
+/// `time` (`HH:MM`), `weekday` (`0` is Monday) and `battery` URL query
+/// parameters, e.g. `/api/rules/dry_run?time=18:00&weekday=4`. No colors are
+/// applied and no macros are run.
+///
+/// ## Returns
+/// A closure that handles the HTTP request and returns a JSON response like
+/// `{"time":"18:00","weekday":4,"battery_percent":null,"active":null,"rules":[.
+/// ..]}`, see [DryRun](rules::DryRun).
+pub fn dry_run_rules() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let query = QueryParams::from_uri(request.uri());
+
+        let weekday = match query.get("weekday").map(str::parse::<u8>) {
+            Some(Ok(weekday)) => Some(weekday),
+            Some(Err(_)) => return error_response(request, 400, "Invalid weekday"),
+            None => None,
+        };
+        let battery = match query.get("battery").map(str::parse::<u8>) {
+            Some(Ok(battery)) => Some(battery),
+            Some(Err(_)) => return error_response(request, 400, "Invalid battery percentage"),
+            None => None,
+        };
+        let Some(context) = RuleContext::with_overrides(query.get("time"), weekday, battery) else {
+            return error_response(request, 400, "Invalid time or weekday");
+        };
+
+        let dry_run = serde_json::to_string(&rules::get_rules().dry_run(&context))
+            .map_err(|e| AppError::Server(e.to_string()))?;
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(dry_run.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Returns the macros run by the rules as JSON.
+///
+/// ## Returns
+/// A closure that handles the HTTP request and returns a JSON response like
+/// `{"macros":[{"name":"party","actions":[{"theme":"cafe80s"}]}]}`.
+pub fn get_macros() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let macros = serde_json::to_string(&macros::get_macros())
+            .map_err(|e| AppError::Server(e.to_string()))?;
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(macros.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Replaces the macros run by the rules.
+///
+/// This function parses [Macros] from the request body, validates them, and
+/// saves them.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the macros.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, saves the macros, and responds
+/// with a success message.
+pub fn set_macros(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let len = request.content_len().unwrap_or(0) as usize;
+
+        if len > MAX_MACROS_LEN {
+            return error_response(request, 413, "Request too big");
+        }
+
+        let mut buf = vec![0; len];
+        request
+            .read_exact(&mut buf)
+            .map_err(|e| AppError::Server(format!("Failed to read the macros: {e:?}")))?;
+
+        let new_macros: Macros = match serde_json::from_slice(&buf) {
+            Ok(new_macros) => new_macros,
+            Err(_) => return error_response(request, 400, "Invalid JSON"),
+        };
+
+        if !new_macros.is_valid() {
+            return error_response(
+                request,
+                400,
+                &format!(
+                    "Up to {MAX_MACROS} macros with a unique name and 1 to {MAX_MACRO_ACTIONS} \
+                     valid actions are allowed"
+                ),
+            );
+        }
+
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_macros(new_macros.clone())?;
+        macros::set_macros(new_macros);
+
+        request
+            .into_ok_response()?
+            .write("Macros saved!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Runs a macro right away, by name.
+///
+/// This function parses a [MacroRunRequest] like `{"name":"party"}` from the
+/// request body and runs the actions of the macro, see
+/// [run_macro](macros::run_macro).
+///
+/// ## Arguments
+/// - `led_strip` - A [SharedLedStrip] instance.
+/// - `storage` - A [SharedAppStorage] instance used to save the theme.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, runs the macro, and responds with a
+/// success message.
+pub fn run_macro(
+    led_strip: SharedLedStrip,
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> + Send {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let name = match serde_json::from_slice::<MacroRunRequest>(buf) {
+            Ok(data) => data.name,
+            Err(_) => return error_response(request, 400, "Invalid JSON format"),
+        };
+
+        let Some(macro_) = macros::get_macros().find(&name).cloned() else {
+            return error_response(request, 404, "Macro not found");
+        };
+
+        macros::run_macro(&macro_, &led_strip, &storage)?;
+
+        request.into_ok_response()?.write("Macro run!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Shows a message on the displays for a while.
+///
+/// This function parses a [DisplayMessageRequest] from the request body, e.g.
+/// `{"text":"DINNER IS READY","duration":30}`, and queues it. The message
+/// scrolls if it doesn't fit the 12 digits, and the time is shown again after
+/// `duration` seconds, see
+/// [run_display_messages](crate::module::display::run_display_messages).
+///
+/// ## Returns
+/// A closure that handles the HTTP request, queues the message, and responds
+/// with a success message.
+pub fn display_message() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 256];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let message: DisplayMessageRequest = match serde_json::from_slice(buf) {
+            Ok(message) if message.is_valid() => message,
+            _ => {
+                return error_response(
+                    request,
+                    400,
+                    &format!(
+                        "The text must have 1 to {MAX_DISPLAY_MESSAGE_LEN} characters and the \
+                         duration 1 to {MAX_DISPLAY_MESSAGE_DURATION} seconds"
+                    ),
+                );
+            }
+        };
+
+        prefs::display_message::queue_message(message);
+
+        request
+            .into_ok_response()?
+            .write("Message queued!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Returns every available LED strip theme as a JSON array.
+///
+/// ## Returns
+/// A closure that handles the HTTP request and returns a JSON response like
+/// `[{"name":"original","label":"Original"},...]`.
+pub fn get_themes() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let themes: Vec<ThemeInfo> = Theme::ALL.into_iter().map(ThemeInfo::from).collect();
+        let themes_json =
+            serde_json::to_string(&themes).map_err(|e| AppError::Server(e.to_string()))?;
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(themes_json.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Creates an HTTP handler that changes the LED strip theme based on the
+/// request body.
+///
+/// ## Arguments
+/// - Parses a [ThemeRequest] like `{"theme":"plutonium"}` from the body. Any
+///   spelling of a [Theme] variant name is accepted, see [Theme::from_str].
+/// - Sets the LED strip color based on the provided theme value.
+/// - Saves the theme in NVS so it is restored on the next boot.
+/// - Responds with `"Theme Updated!"` if successful.
+/// - Responds with `400` and a JSON error body if the theme is invalid.
+///
+/// ## Returns
+/// - A closure that acts as an HTTP request handler.
+pub fn set_theme(
+    led_strip: SharedLedStrip,
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let theme = match serde_json::from_slice::<ThemeRequest>(buf) {
+            Ok(data) => data.theme,
+            Err(_) => {
+                log::warn!("Invalid theme request");
+                error_response(request, 400, "Invalid theme")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        led_strip
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .apply_theme(&theme)
+            .inspect(|_| health::report_ok(Subsystem::LedStrip))
+            .inspect_err(|e| health::report_degraded(Subsystem::LedStrip, e))?;
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_theme(theme)?;
+        log::info!("Theme changed to '{theme}'");
+        event::publish(Event::ThemeChanged(theme));
+
+        request
+            .into_ok_response()?
+            .write("Theme Updated!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets the boot animation of the displays and previews it.
+///
+/// This function parses a [BootAnimationRequest] like `{"boot_animation":2}`
+/// from the request body (`0` init, `1` cascade, `2` flicker, `3` blank),
+/// saves it to NVS, plays it on every display and then renders the current
+/// time again.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the animation.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, saves and previews the animation,
+/// and responds with a success message.
+pub fn set_boot_animation(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let boot_animation = match serde_json::from_slice::<BootAnimationRequest>(buf) {
+            Ok(data) if data.boot_animation <= 3 => BootAnimation::from(data.boot_animation),
+            Ok(data) => {
+                log::warn!("Invalid boot_animation: '{}'", data.boot_animation);
+                error_response(request, 400, "Boot animation must be between 0 and 3")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_boot_animation(boot_animation)?;
+
+        if !prefs::photo_mode::is_photo_mode() {
+            display_queue::send(DisplayCommand::Animate(boot_animation));
+        }
+
+        log::info!("Boot animation changed to '{boot_animation:?}'");
+
+        request
+            .into_ok_response()?
+            .write("Boot animation changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets the boot effect of the LED strip and previews it.
+///
+/// This function parses a [BootLedEffectRequest] like `{"boot_led_effect":1}`
+/// from the request body (`0` off, `1` fade, `2` sweep), saves it to NVS and
+/// plays it with the stored theme.
+///
+/// ## Arguments
+/// - `led_strip` - A [SharedLedStrip] instance.
+/// - `storage` - A [SharedAppStorage] instance used to save the effect.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, saves and previews the effect, and
+/// responds with a success message.
+pub fn set_boot_led_effect(
+    led_strip: SharedLedStrip,
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let boot_led_effect = match serde_json::from_slice::<BootLedEffectRequest>(buf) {
+            Ok(data) if data.boot_led_effect <= 2 => BootLedEffect::from(data.boot_led_effect),
+            Ok(data) => {
+                log::warn!("Invalid boot_led_effect: '{}'", data.boot_led_effect);
+                error_response(request, 400, "Boot LED effect must be 0, 1 or 2")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        let theme = {
+            let mut storage = storage.lock().unwrap_or_else(PoisonError::into_inner);
+            storage.save_boot_led_effect(boot_led_effect)?;
+            storage
+                .get_maybe_theme()
+                .unwrap_or(None)
+                .unwrap_or_default()
+        };
+
+        if !prefs::photo_mode::is_photo_mode() {
+            led_strip
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .play_boot_effect(boot_led_effect, &theme)?;
+        }
+
+        log::info!("Boot LED effect changed to '{boot_led_effect:?}'");
+
+        request
+            .into_ok_response()?
+            .write("Boot LED effect changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Enables or disables the DST pre-announcement.
+///
+/// This function parses a [DstNoticeRequest] like `{"dst_notice":true}` from
+/// the request body, updates the in-memory flag for immediate effect and saves
+/// it to NVS.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the setting.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, updates the setting, and responds
+/// with a success message.
+pub fn set_dst_notice(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let enabled = match serde_json::from_slice::<DstNoticeRequest>(buf) {
+            Ok(data) => data.dst_notice,
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_dst_notice(enabled)?;
+        prefs::dst_notice::set_dst_notice(enabled);
+        log::info!("DST notice changed to '{enabled}'");
+
+        request
+            .into_ok_response()?
+            .write("DST notice changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets the time the daily summary is scrolled at, or disables it.
+///
+/// This function parses a [DailySummaryRequest] like `{"time":"07:30"}` from
+/// the request body, or `{"time":null}` to disable the summary, updates the
+/// in-memory setting and saves it to NVS.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the setting.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, updates the setting, and responds
+/// with a success message.
+pub fn set_daily_summary(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let time = match serde_json::from_slice::<DailySummaryRequest>(buf) {
+            Ok(data) => data.time,
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        let minute_of_day = match &time {
+            None => None,
+            Some(value) => match NaiveTime::parse_from_str(value, "%H:%M") {
+                Ok(time) => Some((time.hour() * 60 + time.minute()) as u16),
+                Err(_) => {
+                    log::warn!("Invalid daily summary time: '{value}'");
+                    error_response(request, 400, "Invalid time")?;
+                    return Err(AppError::Server("Invalid request".to_string()));
+                }
+            },
+        };
+
+        match minute_of_day {
+            Some(minute_of_day) => storage
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .save_daily_summary(minute_of_day)?,
+            None => storage
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .delete_daily_summary()?,
+        }
+        prefs::daily_summary::set_daily_summary(minute_of_day);
+        log::info!("Daily summary changed to {time:?}");
+
+        request
+            .into_ok_response()?
+            .write("Daily summary changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets the wake time the LED strip plays a sunrise before, or disables the
+/// wake light.
+///
+/// This function parses a [WakeLightRequest] like
+/// `{"time":"06:30","duration":20}` from the request body, or `{"time":null}`
+/// to disable the wake light, updates the in-memory setting and saves it to
+/// NVS. The duration defaults to the shortest one, see
+/// [MIN_WAKE_DURATION](prefs::wake_light::MIN_WAKE_DURATION).
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the setting.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, updates the setting, and responds
+/// with a success message.
+pub fn set_wake_light(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let data = match serde_json::from_slice::<WakeLightRequest>(buf) {
+            Ok(data) => data,
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        let wake_light = match &data.time {
+            None => None,
+            Some(value) => {
+                let time = match NaiveTime::parse_from_str(value, "%H:%M") {
+                    Ok(time) => time,
+                    Err(_) => {
+                        log::warn!("Invalid wake light time: '{value}'");
+                        error_response(request, 400, "Invalid time")?;
+                        return Err(AppError::Server("Invalid request".to_string()));
+                    }
+                };
+
+                let wake_light = WakeLight {
+                    minute_of_day: (time.hour() * 60 + time.minute()) as u16,
+                    duration: data
+                        .duration
+                        .unwrap_or(prefs::wake_light::MIN_WAKE_DURATION),
+                };
+
+                if !wake_light.is_valid() {
+                    log::warn!("Invalid wake light: {wake_light:?}");
+                    error_response(
+                        request,
+                        400,
+                        &format!(
+                            "Duration must be between {} and {} minutes",
+                            prefs::wake_light::MIN_WAKE_DURATION,
+                            prefs::wake_light::MAX_WAKE_DURATION
+                        ),
+                    )?;
+                    return Err(AppError::Server("Invalid request".to_string()));
+                }
+
+                Some(wake_light)
+            }
+        };
+
+        match wake_light {
+            Some(wake_light) => storage
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .save_wake_light(wake_light)?,
+            None => storage
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .delete_wake_light()?,
+        }
+        prefs::wake_light::set_wake_light(wake_light);
+        log::info!("Wake light changed to {wake_light:?}");
+
+        request
+            .into_ok_response()?
+            .write("Wake light changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets the target date the year display counts the days down to, or
+/// disables the countdown.
+///
+/// This function parses a [CountdownRequest] like `{"date":"2025-10-21"}`
+/// from the request body, or `{"date":null}` to disable the countdown, updates
+/// the in-memory setting and saves it to NVS. The year display picks it up on
+/// its next minute.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the setting.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, updates the setting, and responds
+/// with a success message.
+pub fn set_countdown(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let date = match serde_json::from_slice::<CountdownRequest>(buf) {
+            Ok(data) => data.date,
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        let target = match &date {
+            None => None,
+            Some(value) => match NaiveDate::parse_from_str(value, prefs::countdown::DATE_FORMAT) {
+                Ok(date) => Some(date),
+                Err(_) => {
+                    log::warn!("Invalid countdown date: '{value}'");
+                    error_response(request, 400, "Invalid date")?;
+                    return Err(AppError::Server("Invalid request".to_string()));
+                }
+            },
+        };
+
+        match target {
+            Some(target) => storage
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .save_countdown(target)?,
+            None => storage
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .delete_countdown()?,
+        }
+        prefs::countdown::set_countdown(target);
+        log::info!("Countdown changed to {target:?}");
+
+        request
+            .into_ok_response()?
+            .write("Countdown changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Enables or disables the astronomical events of the display rotation.
+///
+/// This function parses an [AstroEventsRequest] like `{"astro_events":true}`
+/// from the request body, updates the in-memory flag for immediate effect and
+/// saves it to NVS.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the setting.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, updates the setting, and responds
+/// with a success message.
+pub fn set_astro_events(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let enabled = match serde_json::from_slice::<AstroEventsRequest>(buf) {
+            Ok(data) => data.astro_events,
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_astro_events(enabled)?;
+        prefs::astro_events::set_astro_events(enabled);
+        log::info!("Astronomical events changed to '{enabled}'");
+
+        request
+            .into_ok_response()?
+            .write("Astronomical events changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Returns the upcoming astronomical events as JSON.
+///
+/// ## Returns
+/// A closure that handles the HTTP request and returns a JSON response like
+/// `{"events":[{"event":"full_moon","timestamp":1706205240},...]}`, with the
+/// next [ASTRO_TABLE_LEN] new and full moons, equinoxes and solstices.
+pub fn get_astro_events() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let events = astro::event_table(time::get_timestamp(), ASTRO_TABLE_LEN);
+        let events_json = serde_json::json!({ "events": events }).to_string();
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(events_json.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Saves the custom messages in NVS and updates the in-memory rotation.
+fn save_custom_messages(
+    storage: &SharedAppStorage,
+    messages: CustomMessages,
+) -> Result<(), AppError> {
+    storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .save_custom_messages(messages.clone())?;
+    custom_messages::set_custom_messages(messages);
+
+    Ok(())
+}
+
+/// Returns the custom messages of the display rotation as JSON.
+///
+/// ## Returns
+/// A closure that handles the HTTP request and returns a JSON response like
+/// `{"interval":15,"messages":["GARAGE","HI MOM"]}`.
+pub fn get_messages() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let messages = serde_json::to_string(&custom_messages::get_custom_messages())
+            .map_err(|e| AppError::Server(e.to_string()))?;
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(messages.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Adds a custom message to the display rotation.
+///
+/// This function parses a [CustomMessageRequest] from the request body,
+/// validates it, and appends it to the stored messages, up to
+/// [MAX_MESSAGES].
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the messages.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, adds the message, and responds
+/// with a success message.
+pub fn add_message(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 128];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let message_data: CustomMessageRequest = match serde_json::from_slice(buf) {
+            Ok(data) if custom_messages::is_valid_message(&data.text) => data,
+            _ => {
+                log::error!("Invalid custom message");
+                request.into_status_response(400)?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        let mut messages = custom_messages::get_custom_messages();
+        if messages.messages.len() >= MAX_MESSAGES {
+            log::warn!("Custom message slots are full");
+            request.into_status_response(409)?;
+            return Err(AppError::Server("Message slots are full".to_string()));
+        }
+
+        messages.messages.push(message_data.text);
+        save_custom_messages(&storage, messages)?;
+
+        request
+            .into_ok_response()?
+            .write("Message added!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Replaces a custom message of the display rotation.
+///
+/// This function extracts the message index from the URL query parameter, e.g.
+/// `/api/messages?0`, and parses a [CustomMessageRequest] from the request
+/// body.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the messages.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, replaces the message, and responds
+/// with a success message.
+pub fn update_message(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let index = QueryParams::from_uri(request.uri())
+            .get_or_bare("idx")
+            .and_then(|index| index.parse::<usize>().ok());
+
+        let mut buf = [0u8; 128];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let mut messages = custom_messages::get_custom_messages();
+        let message_data = serde_json::from_slice::<CustomMessageRequest>(buf);
+
+        let (index, message_data) = match (index, message_data) {
+            (Some(index), Ok(data))
+                if index < messages.messages.len()
+                    && custom_messages::is_valid_message(&data.text) =>
+            {
+                (index, data)
+            }
+            _ => {
+                log::error!("Invalid custom message update");
+                request.into_status_response(400)?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        messages.messages[index] = message_data.text;
+        save_custom_messages(&storage, messages)?;
+
+        request
+            .into_ok_response()?
+            .write("Message updated!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Removes a custom message from the display rotation.
+///
+/// This function extracts the message index from the URL query parameter, e.g.
+/// `/api/messages?0`.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the messages.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, removes the message, and responds
+/// with a success message.
+pub fn delete_message(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let index = QueryParams::from_uri(request.uri())
+            .get_or_bare("idx")
+            .and_then(|index| index.parse::<usize>().ok());
+
+        let mut messages = custom_messages::get_custom_messages();
+
+        match index {
+            Some(index) if index < messages.messages.len() => {
+                messages.messages.remove(index);
+            }
+            _ => {
+                log::warn!("Invalid custom message index: {index:?}");
+                request.into_status_response(400)?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        }
+
+        save_custom_messages(&storage, messages)?;
+
+        request
+            .into_ok_response()?
+            .write("Message deleted!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets how often a custom message is scrolled.
+///
+/// This function parses a [MessageIntervalRequest] like `{"interval":15}` from
+/// the request body, with the interval in minutes (`0` disables the rotation,
+/// up to `240`).
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the messages.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, updates the interval, and responds
+/// with a success message.
+pub fn set_message_interval(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let interval = match serde_json::from_slice::<MessageIntervalRequest>(buf) {
+            Ok(data) if data.interval <= 240 => data.interval,
+            Ok(data) => {
+                log::warn!("Invalid message interval: '{}'", data.interval);
+                error_response(request, 400, "Interval must be at most 240 minutes")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        let mut messages = custom_messages::get_custom_messages();
+        messages.interval = interval;
+        save_custom_messages(&storage, messages)?;
+        log::info!("Custom message interval changed to {interval} minutes");
+
+        request
+            .into_ok_response()?
+            .write("Message interval changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Saves the trackers in NVS and updates the in-memory rotation.
+fn save_trackers(storage: &SharedAppStorage, trackers: Trackers) -> Result<(), AppError> {
+    storage
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .save_trackers(trackers.clone())?;
+    trackers::set_trackers(trackers);
+
+    Ok(())
+}
+
+/// Returns the "days since" trackers of the display rotation as JSON.
+///
+/// ## Returns
+/// A closure that handles the HTTP request and returns a JSON response like
+/// `{"interval":60,"trackers":[{"name":"NO SMOKE","since":"2025-01-31"}]}`.
+pub fn get_trackers() -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |request: Request<&mut EspHttpConnection<'_>>| {
+        let trackers = serde_json::to_string(&trackers::get_trackers())
+            .map_err(|e| AppError::Server(e.to_string()))?;
+
+        request
+            .into_response(200, None, &[("Content-Type", "application/json")])?
+            .write_all(trackers.as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Adds a "days since" tracker to the display rotation.
+///
+/// This function parses a [Tracker] from the request body, validates it, and
+/// appends it to the stored trackers, up to [MAX_TRACKERS].
 ///
 /// ## Arguments
-/// - `display_group` - A [SharedDisplayGroup] instance.
+/// - `storage` - A [SharedAppStorage] instance used to save the trackers.
 ///
 /// ## Returns
-/// A closure that handles the HTTP request, updates the brightness, and returns
-/// a success message.
-pub fn set_brightness<'a, CLK, DateDIO, YearDIO, HourDIO>(
-    display_group: SharedDisplayGroup<'static, CLK, DateDIO, YearDIO, HourDIO>,
-) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> + Send + 'a
-where
-    CLK: OutputPin + 'a,
-    DateDIO: IOPin + 'a,
-    YearDIO: IOPin + 'a,
-    HourDIO: IOPin + 'a,
-{
-    move |request: Request<&mut EspHttpConnection<'_>>| {
-        let url = request.uri();
-
-        if let Some(start) = url.find('?') {
-            let brightness_value = &url[start + 1..];
-            if let Ok(brightness) = brightness_value.parse::<u8>() {
-                if (0..=7).contains(&brightness) {
-                    display_group
-                        .lock()
-                        .unwrap()
-                        .date
-                        .lock()
-                        .unwrap()
-                        .set_brightness(brightness)?;
-                    FreeRtos::delay_ms(200);
-
-                    display_group
-                        .lock()
-                        .unwrap()
-                        .year
-                        .lock()
-                        .unwrap()
-                        .set_brightness(brightness)?;
-                    FreeRtos::delay_ms(200);
-
-                    display_group
-                        .lock()
-                        .unwrap()
-                        .hour
-                        .lock()
-                        .unwrap()
-                        .set_brightness(brightness)?;
-                    log::info!("Brightness updated to level {brightness}");
-                }
+/// A closure that handles the HTTP request, adds the tracker, and responds
+/// with a success message.
+pub fn add_tracker(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 128];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let tracker: Tracker = match serde_json::from_slice::<Tracker>(buf) {
+            Ok(data) if data.is_valid() => data,
+            _ => {
+                log::error!("Invalid tracker");
+                request.into_status_response(400)?;
+                return Err(AppError::Server("Invalid request".to_string()));
             }
+        };
+
+        let mut trackers = trackers::get_trackers();
+        if trackers.trackers.len() >= MAX_TRACKERS {
+            log::warn!("Tracker slots are full");
+            request.into_status_response(409)?;
+            return Err(AppError::Server("Tracker slots are full".to_string()));
         }
 
+        trackers.trackers.push(tracker);
+        save_trackers(&storage, trackers)?;
+
         request
             .into_ok_response()?
-            .write("Brightness Updated!".as_bytes())?;
+            .write("Tracker added!".as_bytes())?;
 
         Ok::<(), AppError>(())
     }
 }
 
-/// Synchronizes the system time using SNTP and updates the display with the
-/// sync message.
+/// Replaces a "days since" tracker, e.g. to reset its start date to today.
 ///
-/// This function restarts the SNTP synchronization process, waits for
-/// completion, and updates the display with the current time once
-/// synchronization is finished.
+/// This function extracts the tracker index from the URL query parameter, e.g.
+/// `/api/trackers?0`, and parses a [Tracker] from the request body.
 ///
 /// ## Arguments
-/// - `display_group` - A [SharedDisplayGroup] instance.
-/// - `am_pm_indicator` - A [SharedAmPmIndicator] instance.
-/// - `sntp` - An instance of [EspSntp] used to synchronize the time.
+/// - `storage` - A [SharedAppStorage] instance used to save the trackers.
 ///
 /// ## Returns
-/// A closure that handles the HTTP request, synchronizes the time, updates the
-/// display, and returns a success message.
-pub fn sync_time<'a, CLK, DateDIO, YearDIO, HourDIO, AM, PM>(
-    display_group: SharedDisplayGroup<'static, CLK, DateDIO, YearDIO, HourDIO>,
-    am_pm_indicator: SharedAmPmIndicator<'a, AM, PM>,
-    sntp: EspSntp<'static>,
-) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> + Send + 'a
-where
-    CLK: OutputPin + 'a,
-    DateDIO: IOPin + 'a,
-    YearDIO: IOPin + 'a,
-    HourDIO: IOPin + 'a,
-    AM: OutputPin,
-    PM: OutputPin,
-{
+/// A closure that handles the HTTP request, replaces the tracker, and responds
+/// with a success message.
+pub fn update_tracker(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let index = QueryParams::from_uri(request.uri())
+            .get_or_bare("idx")
+            .and_then(|index| index.parse::<usize>().ok());
+
+        let mut buf = [0u8; 128];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let mut trackers = trackers::get_trackers();
+        let tracker = serde_json::from_slice::<Tracker>(buf);
+
+        let (index, tracker) = match (index, tracker) {
+            (Some(index), Ok(data)) if index < trackers.trackers.len() && data.is_valid() => {
+                (index, data)
+            }
+            _ => {
+                log::error!("Invalid tracker update");
+                request.into_status_response(400)?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        trackers.trackers[index] = tracker;
+        save_trackers(&storage, trackers)?;
+
+        request
+            .into_ok_response()?
+            .write("Tracker updated!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Removes a "days since" tracker from the display rotation.
+///
+/// This function extracts the tracker index from the URL query parameter, e.g.
+/// `/api/trackers?0`.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the trackers.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, removes the tracker, and responds
+/// with a success message.
+pub fn delete_tracker(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
     move |request: Request<&mut EspHttpConnection<'_>>| {
-        let sync_message = DisplayMessage::Sync.as_bytes();
+        let index = QueryParams::from_uri(request.uri())
+            .get_or_bare("idx")
+            .and_then(|index| index.parse::<usize>().ok());
 
-        unsafe {
-            sntp_restart();
+        let mut trackers = trackers::get_trackers();
+
+        match index {
+            Some(index) if index < trackers.trackers.len() => {
+                trackers.trackers.remove(index);
+            }
+            _ => {
+                log::warn!("Invalid tracker index: {index:?}");
+                request.into_status_response(400)?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
         }
 
-        log::info!("Synchronizing with SNTP Server");
+        save_trackers(&storage, trackers)?;
 
-        display_group
-            .lock()
-            .unwrap()
-            .hour
-            .lock()
-            .unwrap()
-            .write(sync_message)?;
+        request
+            .into_ok_response()?
+            .write("Tracker deleted!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Sets how often a "days since" tracker is scrolled.
+///
+/// This function parses a [TrackerIntervalRequest] like `{"interval":30}` from
+/// the request body, with the interval in minutes (`0` disables the rotation,
+/// up to `240`).
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the trackers.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, updates the interval, and responds
+/// with a success message.
+pub fn set_tracker_interval(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let interval = match serde_json::from_slice::<TrackerIntervalRequest>(buf) {
+            Ok(data) if data.interval <= 240 => data.interval,
+            Ok(data) => {
+                log::warn!("Invalid tracker interval: '{}'", data.interval);
+                error_response(request, 400, "Interval must be at most 240 minutes")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        let mut trackers = trackers::get_trackers();
+        trackers.interval = interval;
+        save_trackers(&storage, trackers)?;
+        log::info!("Tracker interval changed to {interval} minutes");
 
-        while sntp.get_sync_status() != SyncStatus::Completed {}
+        request
+            .into_ok_response()?
+            .write("Tracker interval changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Enables or disables the blinking colon of the hour display.
+///
+/// This function parses a [ColonBlinkRequest] like `{"colon_blink":true}` from
+/// the request body, updates the in-memory flag for immediate effect and saves
+/// it to NVS.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the setting.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, updates the setting, and responds
+/// with a success message.
+pub fn set_colon_blink(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
 
-        let hour_format = get_hour_format();
+        let enabled = match serde_json::from_slice::<ColonBlinkRequest>(buf) {
+            Ok(data) => data.colon_blink,
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
 
-        display_group
+        storage
             .lock()
-            .unwrap()
-            .hour
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_colon_blink(enabled)?;
+        prefs::colon_blink::set_colon_blink(enabled);
+        log::info!("Colon blink changed to '{enabled}'");
+
+        request
+            .into_ok_response()?
+            .write("Colon blink changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Enables or disables the room temperature on the date display, alternating
+/// with the date.
+///
+/// This function parses a [TemperatureDisplayRequest] like
+/// `{"temperature_display":true}` from the request body, updates the in-memory
+/// flag for immediate effect and saves it to NVS.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the setting.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, updates the setting, and responds
+/// with a success message.
+pub fn set_temperature_display(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let enabled = match serde_json::from_slice::<TemperatureDisplayRequest>(buf) {
+            Ok(data) => data.temperature_display,
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
+
+        storage
             .lock()
-            .unwrap()
-            .update_display_hour(am_pm_indicator.clone(), hour_format)?;
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_temperature_display(enabled)?;
+        prefs::temperature_display::set_temperature_display(enabled);
+        log::info!("Temperature display changed to '{enabled}'");
+
+        request
+            .into_ok_response()?
+            .write("Temperature display changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Enables or disables the room humidity on the date display, alternating
+/// with the date.
+///
+/// This function parses a [HumidityDisplayRequest] like
+/// `{"humidity_display":true}` from the request body, updates the in-memory
+/// flag for immediate effect and saves it to NVS.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the setting.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, updates the setting, and responds
+/// with a success message.
+pub fn set_humidity_display(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let enabled = match serde_json::from_slice::<HumidityDisplayRequest>(buf) {
+            Ok(data) => data.humidity_display,
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
 
-        log::info!("Time sync completed!");
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_humidity_display(enabled)?;
+        prefs::humidity_display::set_humidity_display(enabled);
+        log::info!("Humidity display changed to '{enabled}'");
 
         request
             .into_ok_response()?
-            .write("Time synced successfully!".as_bytes())?;
+            .write("Humidity display changed!".as_bytes())?;
 
         Ok::<(), AppError>(())
     }
 }
 
-/// Creates an HTTP handler that changes the LED strip theme based on a query
-/// parameter.
+/// Sets how many seconds each view of the date display is shown while it
+/// alternates, e.g. between the date, the weekday and the temperature.
+///
+/// This function parses an [AlternateIntervalRequest] like `{"interval":8}`
+/// from the request body (from `2` to `60` seconds), updates the in-memory
+/// setting for immediate effect and saves it to NVS.
 ///
 /// ## Arguments
-/// - Reads the requested theme from the URL query parameter.
-/// - Sets the LED strip color based on the provided theme value.
-/// - Responds with `"Theme Updated!"` if successful.
-/// - Returns an error if the theme value is invalid.
+/// - `storage` - A [SharedAppStorage] instance used to save the setting.
 ///
 /// ## Returns
-/// - A closure that acts as an HTTP request handler.
-pub fn set_theme(
-    led_strip: SharedLedStrip,
+/// A closure that handles the HTTP request, updates the interval, and responds
+/// with a success message.
+pub fn set_alternate_interval(
+    storage: SharedAppStorage,
 ) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
-    move |request: Request<&mut EspHttpConnection<'_>>| {
-        let url = request.uri();
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
 
-        if let Some(start) = url.find('?') {
-            let theme_value = &url[start + 1..];
+        let interval = match serde_json::from_slice::<AlternateIntervalRequest>(buf) {
+            Ok(data)
+                if (alternate_interval::MIN_ALTERNATE_INTERVAL
+                    ..=alternate_interval::MAX_ALTERNATE_INTERVAL)
+                    .contains(&data.interval) =>
+            {
+                data.interval
+            }
+            Ok(data) => {
+                log::warn!("Invalid alternate interval: '{}'", data.interval);
+                error_response(
+                    request,
+                    400,
+                    &format!(
+                        "Interval must be between {} and {} seconds",
+                        alternate_interval::MIN_ALTERNATE_INTERVAL,
+                        alternate_interval::MAX_ALTERNATE_INTERVAL
+                    ),
+                )?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
+            }
+        };
 
-            match theme_value {
-                "original" => {
-                    led_strip.lock().unwrap().apply_theme(&Theme::Original)?;
-                    log::info!("Theme changed to 'Original'");
-                }
-                "hoverboard" => {
-                    led_strip.lock().unwrap().apply_theme(&Theme::Hoverboard)?;
-                    log::info!("Theme changed to 'Hoverboard'");
-                }
-                "plutonium" => {
-                    led_strip.lock().unwrap().apply_theme(&Theme::Plutonium)?;
-                    log::info!("Theme changed to 'Plutonium'");
-                }
-                "oldwest" => {
-                    led_strip.lock().unwrap().apply_theme(&Theme::OldWest)?;
-                    log::info!("Theme changed to 'OldWest'");
-                }
-                "cafe80s" => {
-                    led_strip.lock().unwrap().apply_theme(&Theme::Cafe80s)?;
-                    log::info!("Theme changed to 'Cafe80s'");
-                }
-                _ => {
-                    log::warn!("Invalid theme: '{theme_value}'");
-                    return Err(AppError::Server("Invalid request".to_string()));
-                }
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_alternate_interval(interval)?;
+        alternate_interval::set_alternate_interval(interval);
+        log::info!("Alternate interval changed to {interval} seconds");
+
+        request
+            .into_ok_response()?
+            .write("Alternate interval changed!".as_bytes())?;
+
+        Ok::<(), AppError>(())
+    }
+}
+
+/// Enables or disables the visual hour strike of the LED strip.
+///
+/// This function parses a [HourStrikeRequest] like `{"hour_strike":true}` from
+/// the request body, updates the in-memory flag for immediate effect and saves
+/// it to NVS.
+///
+/// ## Arguments
+/// - `storage` - A [SharedAppStorage] instance used to save the setting.
+///
+/// ## Returns
+/// A closure that handles the HTTP request, updates the setting, and responds
+/// with a success message.
+pub fn set_hour_strike(
+    storage: SharedAppStorage,
+) -> impl Fn(Request<&mut EspHttpConnection<'_>>) -> Result<(), AppError> {
+    move |mut request: Request<&mut EspHttpConnection<'_>>| {
+        let mut buf = [0u8; 64];
+        let len = request.read(&mut buf)?;
+        let buf = &buf[..len];
+
+        let enabled = match serde_json::from_slice::<HourStrikeRequest>(buf) {
+            Ok(data) => data.hour_strike,
+            Err(_) => {
+                log::error!("Invalid JSON format");
+                error_response(request, 400, "Invalid JSON format")?;
+                return Err(AppError::Server("Invalid request".to_string()));
             }
-        }
+        };
+
+        storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_hour_strike(enabled)?;
+        prefs::hour_strike::set_hour_strike(enabled);
+        log::info!("Hour strike changed to '{enabled}'");
 
         request
             .into_ok_response()?
-            .write("Theme Updated!".as_bytes())?;
+            .write("Hour strike changed!".as_bytes())?;
 
         Ok::<(), AppError>(())
     }
@@ -1,7 +1,42 @@
 use crate::{
-    error::AppError, prefs::hour_format::HourFormat, time::tz::TimezoneRequest,
-    wifi::WifiCredentials,
+    board_config::BoardConfig,
+    error::AppError,
+    module::relay::RelayConfig,
+    nvs::settings::Settings,
+    power::auto_dim::AutoDimConfig,
+    prefs::{
+        boot_animation::{BootAnimation, BootLedEffect},
+        custom_messages::CustomMessages,
+        date_format::DateFormat,
+        digit_transition::DigitTransitions,
+        display_roles::DisplayRoles,
+        hour_format::HourFormat,
+        log_level::LogLevels,
+        seconds_mode::SecondsMode,
+        touch_action::TouchAction,
+        trackers::Trackers,
+        wake_light::WakeLight,
+        weekday_mode::WeekdayMode,
+        wifi_power_save::WifiPowerSave,
+    },
+    presence::PresenceConfig,
+    rules::{macros::Macros, Rules},
+    theme::Theme,
+    time::tz::TimezoneRequest,
+    weather::WeatherConfig,
+    wifi::{
+        enterprise::EnterpriseConfig, AuthMode, DisconnectReason, StaticIpConfig, WifiCredentials,
+    },
 };
+use chrono::NaiveDate;
+
+/// Defines services for managing the [Settings] stored together in NVS.
+pub trait AppStorageSettingsService {
+    fn get_settings(&mut self) -> Result<Settings, String>;
+    fn save_settings(&mut self, settings: &Settings) -> Result<(), AppError>;
+    fn update_settings(&mut self, update: impl FnOnce(&mut Settings)) -> Result<(), AppError>;
+    fn delete_settings(&mut self) -> Result<(), AppError>;
+}
 
 /// Defines services for managing timezone settings in NVS.
 pub trait AppStorageTzService {
@@ -15,10 +50,118 @@ pub trait AppStorageWifiService {
     fn save_wifi_credentials(&mut self, ssid: String, password: String);
     fn get_maybe_wifi_credentials(&mut self) -> Result<Option<WifiCredentials>, String>;
     fn delete_wifi_credentials(&mut self) -> Result<(), AppError>;
+    fn save_static_ip(&mut self, static_ip: StaticIpConfig) -> Result<(), AppError>;
+    fn get_maybe_static_ip(&mut self) -> Result<Option<StaticIpConfig>, String>;
+    fn delete_static_ip(&mut self) -> Result<(), AppError>;
+    fn save_hidden_ssid(&mut self, hidden: bool) -> Result<(), AppError>;
+    fn get_maybe_hidden_ssid(&mut self) -> Result<Option<bool>, String>;
+    fn save_auth_mode(&mut self, auth_mode: AuthMode) -> Result<(), AppError>;
+    fn get_maybe_auth_mode(&mut self) -> Result<Option<AuthMode>, String>;
+    fn save_enterprise_config(&mut self, enterprise: EnterpriseConfig) -> Result<(), AppError>;
+    fn get_maybe_enterprise_config(&mut self) -> Result<Option<EnterpriseConfig>, String>;
+    fn delete_enterprise_config(&mut self) -> Result<(), AppError>;
+    fn save_country_code(&mut self, country_code: String) -> Result<(), AppError>;
+    fn get_maybe_country_code(&mut self) -> Result<Option<String>, String>;
+    fn delete_country_code(&mut self) -> Result<(), AppError>;
+    fn save_wifi_failure(&mut self, reason: DisconnectReason) -> Result<(), AppError>;
+    fn get_maybe_wifi_failure(&mut self) -> Result<Option<DisconnectReason>, String>;
+    fn delete_wifi_failure(&mut self) -> Result<(), AppError>;
+    fn save_wps_request(&mut self) -> Result<(), AppError>;
+    fn get_maybe_wps_request(&mut self) -> Result<Option<bool>, String>;
+    fn delete_wps_request(&mut self) -> Result<(), AppError>;
 }
 
-/// Defines services for managing hour format in NVS.
+/// Defines services for managing user preferences in NVS.
 pub trait AppStoragePrefsService {
     fn save_hour_format(&mut self, hour_format: HourFormat) -> Result<(), AppError>;
-    fn get_maybe_hour_format(&mut self) -> Result<Option<HourFormat>, String>;
+    fn save_date_format(&mut self, date_format: DateFormat) -> Result<(), AppError>;
+    fn get_maybe_date_format(&mut self) -> Result<Option<DateFormat>, String>;
+    fn save_theme(&mut self, theme: Theme) -> Result<(), AppError>;
+    fn get_maybe_theme(&mut self) -> Result<Option<Theme>, String>;
+    fn save_brightness(&mut self, brightness: u8) -> Result<(), AppError>;
+    fn save_boot_animation(&mut self, boot_animation: BootAnimation) -> Result<(), AppError>;
+    fn get_maybe_boot_animation(&mut self) -> Result<Option<BootAnimation>, String>;
+    fn save_boot_led_effect(&mut self, boot_led_effect: BootLedEffect) -> Result<(), AppError>;
+    fn get_maybe_boot_led_effect(&mut self) -> Result<Option<BootLedEffect>, String>;
+    fn save_snapshot_url(&mut self, url: String) -> Result<(), AppError>;
+    fn get_maybe_snapshot_url(&mut self) -> Result<Option<String>, String>;
+    fn delete_snapshot_url(&mut self) -> Result<(), AppError>;
+    fn save_webhook_url(&mut self, url: String) -> Result<(), AppError>;
+    fn get_maybe_webhook_url(&mut self) -> Result<Option<String>, String>;
+    fn delete_webhook_url(&mut self) -> Result<(), AppError>;
+    fn save_dst_notice(&mut self, enabled: bool) -> Result<(), AppError>;
+    fn get_maybe_dst_notice(&mut self) -> Result<Option<bool>, String>;
+    fn save_astro_events(&mut self, enabled: bool) -> Result<(), AppError>;
+    fn get_maybe_astro_events(&mut self) -> Result<Option<bool>, String>;
+    fn save_custom_messages(&mut self, custom_messages: CustomMessages) -> Result<(), AppError>;
+    fn get_maybe_custom_messages(&mut self) -> Result<Option<CustomMessages>, String>;
+    fn save_trackers(&mut self, trackers: Trackers) -> Result<(), AppError>;
+    fn get_maybe_trackers(&mut self) -> Result<Option<Trackers>, String>;
+    fn save_rules(&mut self, rules: Rules) -> Result<(), AppError>;
+    fn get_maybe_rules(&mut self) -> Result<Option<Rules>, String>;
+    fn save_macros(&mut self, macros: Macros) -> Result<(), AppError>;
+    fn get_maybe_macros(&mut self) -> Result<Option<Macros>, String>;
+    fn save_hour_strike(&mut self, enabled: bool) -> Result<(), AppError>;
+    fn get_maybe_hour_strike(&mut self) -> Result<Option<bool>, String>;
+    fn save_leading_zero(&mut self, enabled: bool) -> Result<(), AppError>;
+    fn get_maybe_leading_zero(&mut self) -> Result<Option<bool>, String>;
+    fn save_colon_blink(&mut self, enabled: bool) -> Result<(), AppError>;
+    fn get_maybe_colon_blink(&mut self) -> Result<Option<bool>, String>;
+    fn save_temperature_display(&mut self, enabled: bool) -> Result<(), AppError>;
+    fn get_maybe_temperature_display(&mut self) -> Result<Option<bool>, String>;
+    fn save_humidity_display(&mut self, enabled: bool) -> Result<(), AppError>;
+    fn get_maybe_humidity_display(&mut self) -> Result<Option<bool>, String>;
+    fn save_alternate_interval(&mut self, seconds: u8) -> Result<(), AppError>;
+    fn get_maybe_alternate_interval(&mut self) -> Result<Option<u8>, String>;
+    fn save_motion_timeout(&mut self, seconds: u16) -> Result<(), AppError>;
+    fn get_maybe_motion_timeout(&mut self) -> Result<Option<u16>, String>;
+    fn save_volume(&mut self, volume: u8) -> Result<(), AppError>;
+    fn get_maybe_volume(&mut self) -> Result<Option<u8>, String>;
+    fn save_last_time(&mut self, timestamp: i64) -> Result<(), AppError>;
+    fn get_maybe_last_time(&mut self) -> Result<Option<i64>, String>;
+    fn delete_last_time(&mut self) -> Result<(), AppError>;
+    fn save_low_power(&mut self, enabled: bool) -> Result<(), AppError>;
+    fn get_maybe_low_power(&mut self) -> Result<Option<bool>, String>;
+    fn save_power_save(&mut self, enabled: bool) -> Result<(), AppError>;
+    fn get_maybe_power_save(&mut self) -> Result<Option<bool>, String>;
+    fn save_wifi_power_save(&mut self, mode: WifiPowerSave) -> Result<(), AppError>;
+    fn get_maybe_wifi_power_save(&mut self) -> Result<Option<WifiPowerSave>, String>;
+    fn save_ntp_server(&mut self, enabled: bool) -> Result<(), AppError>;
+    fn get_maybe_ntp_server(&mut self) -> Result<Option<bool>, String>;
+    fn save_digit_transitions(&mut self, transitions: DigitTransitions) -> Result<(), AppError>;
+    fn get_maybe_digit_transitions(&mut self) -> Result<Option<DigitTransitions>, String>;
+    fn save_weekday_mode(&mut self, mode: WeekdayMode) -> Result<(), AppError>;
+    fn get_maybe_weekday_mode(&mut self) -> Result<Option<WeekdayMode>, String>;
+    fn save_touch_action(&mut self, action: TouchAction) -> Result<(), AppError>;
+    fn get_maybe_touch_action(&mut self) -> Result<Option<TouchAction>, String>;
+    fn save_seconds_mode(&mut self, mode: SecondsMode) -> Result<(), AppError>;
+    fn save_daily_summary(&mut self, minute_of_day: u16) -> Result<(), AppError>;
+    fn get_maybe_daily_summary(&mut self) -> Result<Option<u16>, String>;
+    fn delete_daily_summary(&mut self) -> Result<(), AppError>;
+    fn save_countdown(&mut self, target: NaiveDate) -> Result<(), AppError>;
+    fn get_maybe_countdown(&mut self) -> Result<Option<NaiveDate>, String>;
+    fn delete_countdown(&mut self) -> Result<(), AppError>;
+    fn save_wake_light(&mut self, wake_light: WakeLight) -> Result<(), AppError>;
+    fn get_maybe_wake_light(&mut self) -> Result<Option<WakeLight>, String>;
+    fn delete_wake_light(&mut self) -> Result<(), AppError>;
+    fn save_presence(&mut self, config: PresenceConfig) -> Result<(), AppError>;
+    fn get_maybe_presence(&mut self) -> Result<Option<PresenceConfig>, String>;
+    fn save_weather(&mut self, config: WeatherConfig) -> Result<(), AppError>;
+    fn get_maybe_weather(&mut self) -> Result<Option<WeatherConfig>, String>;
+    fn save_auto_dim(&mut self, config: AutoDimConfig) -> Result<(), AppError>;
+    fn save_display_flip(&mut self, flipped: bool) -> Result<(), AppError>;
+    fn get_maybe_display_flip(&mut self) -> Result<Option<bool>, String>;
+    fn save_display_roles(&mut self, roles: DisplayRoles) -> Result<(), AppError>;
+    fn get_maybe_display_roles(&mut self) -> Result<Option<DisplayRoles>, String>;
+    fn save_last_panic(&mut self, message: String) -> Result<(), AppError>;
+    fn get_maybe_last_panic(&mut self) -> Result<Option<String>, String>;
+    fn save_crash_count(&mut self, count: u8) -> Result<(), AppError>;
+    fn get_maybe_crash_count(&mut self) -> Result<Option<u8>, String>;
+    fn save_log_levels(&mut self, log_levels: LogLevels) -> Result<(), AppError>;
+    fn get_maybe_log_levels(&mut self) -> Result<Option<LogLevels>, String>;
+    fn save_board(&mut self, config: BoardConfig) -> Result<(), AppError>;
+    fn get_maybe_board(&mut self) -> Result<Option<BoardConfig>, String>;
+    fn delete_board(&mut self) -> Result<(), AppError>;
+    fn save_relay(&mut self, config: RelayConfig) -> Result<(), AppError>;
+    fn get_maybe_relay(&mut self) -> Result<Option<RelayConfig>, String>;
 }
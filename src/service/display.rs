@@ -1,16 +1,33 @@
-use crate::{error::AppError, module::led::SharedAmPmIndicator, prefs::hour_format::HourFormat};
+use crate::{
+    error::AppError,
+    module::led::SharedAmPmIndicator,
+    prefs::{
+        digit_transition::DigitTransition, hour_format::HourFormat, seconds_mode::SecondsMode,
+    },
+};
 use esp_idf_svc::hal::gpio::OutputPin;
+use std::time::Duration;
 
 /// Defines the service for controlling a 7-segment display.
 pub trait SevenSegmentDisplayService {
     fn init(&mut self) -> Result<(), AppError>;
-    fn write(&mut self, message: [u8; 4]) -> Result<(), AppError>;
+    fn write(&mut self, message: &[u8]) -> Result<(), AppError>;
     fn set_brightness(&mut self, level: u8) -> Result<(), AppError>;
+    fn brightness(&self) -> u8;
+    fn set_transition(&mut self, transition: DigitTransition);
+    fn set_colon(&mut self, on: bool) -> Result<(), AppError>;
     fn update_display_hour<AM: OutputPin, PM: OutputPin>(
         &mut self,
         am_pm_indicator: SharedAmPmIndicator<AM, PM>,
         hour_format: HourFormat,
     ) -> Result<(), AppError>;
     fn update_display_year(&mut self) -> Result<(), AppError>;
+    fn update_display_seconds(&mut self, mode: SecondsMode) -> Result<(), AppError>;
     fn update_display_date(&mut self) -> Result<(), AppError>;
+    fn update_display_weekday(&mut self) -> Result<(), AppError>;
+    fn update_display_low_battery(&mut self) -> Result<(), AppError>;
+    fn update_display_temperature(&mut self, temperature_c: f32) -> Result<(), AppError>;
+    fn update_display_humidity(&mut self, humidity: f32) -> Result<(), AppError>;
+    fn update_display_outdoor_temperature(&mut self, temperature_c: f32) -> Result<(), AppError>;
+    fn update_display_stopwatch(&mut self, elapsed: Duration) -> Result<(), AppError>;
 }
@@ -0,0 +1,134 @@
+use crate::{
+    error::AppError,
+    module::{display::SharedDisplayGroup, led_strip::SharedLedStrip},
+    nvs::SharedAppStorage,
+    prefs::hour_format::HourFormat,
+    service::app_storage::{AppStoragePrefsService, AppStorageTzService},
+    time::{self, tz::TimezoneRequest},
+    wifi::WifiCredentials,
+};
+use esp_idf_svc::hal::gpio::{IOPin, OutputPin};
+use std::sync::{Mutex, PoisonError};
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable to hold the runtime settings, see [read_settings] and [update_settings].
+    static ref SETTINGS: Mutex<Settings> = Mutex::new(Settings::default());
+}
+
+/// Represents the runtime settings read from NVS at boot, or received by a
+/// provisioning flow, and changed from the web portal or the serial protocol.
+#[derive(Clone, Default)]
+pub struct Settings {
+    /// The credentials received by a provisioning flow, `None` until then.
+    pub wifi_credentials: Option<WifiCredentials>,
+    /// The timezone, or `None` for the `DEFAULT_TIMEZONE` of the build.
+    pub timezone: Option<String>,
+    pub hour_format: HourFormat,
+}
+
+/// Reads the runtime settings in a thread-safe way.
+///
+/// ## Example
+/// ```rust
+/// let hour_format = state::read_settings(|settings| settings.hour_format);
+/// ```
+pub fn read_settings<R>(read: impl FnOnce(&Settings) -> R) -> R {
+    read(&SETTINGS.lock().unwrap())
+}
+
+/// Updates the runtime settings in a thread-safe way, holding the lock for
+/// the whole update so it is never seen halfway.
+///
+/// ## Example
+/// ```rust
+/// state::update_settings(|settings| settings.hour_format = HourFormat::Twelve);
+/// ```
+pub fn update_settings<R>(update: impl FnOnce(&mut Settings) -> R) -> R {
+    update(&mut SETTINGS.lock().unwrap())
+}
+
+/// Owns the handles to the displays, the LED strip and the storage, and
+/// changes the runtime settings together with NVS, so the web portal
+/// handlers don't each pair them by hand.
+///
+/// ## Example
+/// ```rust
+/// let state = AppState::new(
+///     display_group.clone(),
+///     led_strip.clone(),
+///     app_storage.clone(),
+/// );
+/// state.set_hour_format(HourFormat::Twelve)?;
+/// ```
+pub struct AppState<CLK, DateDIO, YearDIO, HourDIO>
+where
+    CLK: OutputPin,
+    DateDIO: IOPin,
+    YearDIO: IOPin,
+    HourDIO: IOPin,
+{
+    pub display_group: SharedDisplayGroup<'static, CLK, DateDIO, YearDIO, HourDIO>,
+    pub led_strip: SharedLedStrip,
+    pub storage: SharedAppStorage,
+}
+
+impl<CLK, DateDIO, YearDIO, HourDIO> Clone for AppState<CLK, DateDIO, YearDIO, HourDIO>
+where
+    CLK: OutputPin,
+    DateDIO: IOPin,
+    YearDIO: IOPin,
+    HourDIO: IOPin,
+{
+    fn clone(&self) -> Self {
+        Self {
+            display_group: self.display_group.clone(),
+            led_strip: self.led_strip.clone(),
+            storage: self.storage.clone(),
+        }
+    }
+}
+
+impl<CLK, DateDIO, YearDIO, HourDIO> AppState<CLK, DateDIO, YearDIO, HourDIO>
+where
+    CLK: OutputPin,
+    DateDIO: IOPin,
+    YearDIO: IOPin,
+    HourDIO: IOPin,
+{
+    pub fn new(
+        display_group: SharedDisplayGroup<'static, CLK, DateDIO, YearDIO, HourDIO>,
+        led_strip: SharedLedStrip,
+        storage: SharedAppStorage,
+    ) -> Self {
+        Self {
+            display_group,
+            led_strip,
+            storage,
+        }
+    }
+
+    /// Saves the timezone in NVS and switches the time functions to it, see
+    /// [set_timezone](time::tz::set_timezone).
+    pub fn set_timezone(&self, timezone: String) -> Result<(), AppError> {
+        self.storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_timezone(TimezoneRequest {
+                timezone: timezone.clone(),
+            })?;
+        time::tz::set_timezone(timezone);
+
+        Ok(())
+    }
+
+    /// Saves the hour format in NVS and uses it from the next render.
+    pub fn set_hour_format(&self, hour_format: HourFormat) -> Result<(), AppError> {
+        self.storage
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .save_hour_format(hour_format)?;
+        update_settings(|settings| settings.hour_format = hour_format);
+
+        Ok(())
+    }
+}
@@ -1,17 +1,37 @@
-/// Represents the different visual themes available for the LED strip.
-#[derive(Default)]
-pub enum Theme {
-    /// Inspired by the official logo.
-    #[default]
-    Original,
-    /// Inspired by Marty's iconic hoverboard.
-    Hoverboard,
-    /// Referencing the DeLorean's original fuel.
-    Plutonium,
-    /// Evokes the rustic, sepia-toned era of Back to the Future Part III.
-    OldWest,
-    /// A retro, neon-soaked palette reminiscent of the 2015 "Cafe 80s" diner.
-    Cafe80s,
+use serde::{Deserialize, Serialize};
+
+pub use bttf_core::theme::{theme_colors, Theme};
+
+/// Represents an entry of the `/api/themes` listing.
+///
+/// ## Example
+/// ```json
+/// {"name":"oldwest","label":"Old West"}
+/// ```
+#[derive(Clone, Serialize)]
+pub struct ThemeInfo {
+    pub name: &'static str,
+    pub label: &'static str,
+}
+
+impl From<Theme> for ThemeInfo {
+    fn from(theme: Theme) -> Self {
+        Self {
+            name: theme.name(),
+            label: theme.label(),
+        }
+    }
+}
+
+/// Represents the body of a theme change request.
+///
+/// ## Example
+/// ```rust
+/// let theme_request: ThemeRequest = serde_json::from_str("{\"theme\":\"plutonium\"}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct ThemeRequest {
+    pub theme: Theme,
 }
 
 /// Defines the capability for a component to apply a visual theme.
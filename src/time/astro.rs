@@ -0,0 +1,337 @@
+use super::tz;
+use crate::prefs::astro_events::is_astro_events_enabled;
+use chrono::{DateTime, Datelike, NaiveDate, Timelike};
+use serde::Serialize;
+
+/// The minute of every hour at which the next astronomical event is shown, so
+/// it doesn't collide with the DST notice at the top of the hour.
+const NOTICE_MINUTE: u32 = 30;
+
+/// How far ahead an event is announced, in days. A new or full moon is always
+/// less than 15 days away, so one of them is always announced.
+const LOOKAHEAD_DAYS: i64 = 7;
+
+/// The Julian Day of the Unix epoch.
+const UNIX_EPOCH_JD: f64 = 2440587.5;
+
+/// The mean length of a lunation, in days.
+const SYNODIC_MONTH: f64 = 29.530588861;
+
+/// The mean lunations per year, used to find the lunation number of a date.
+const LUNATIONS_PER_YEAR: f64 = 12.3685;
+
+/// The periodic terms of the new and full moon (Meeus, chapter 49), as
+/// `(new moon coefficient, full moon coefficient, power of E, M, M', F)`,
+/// where the last three are the multiples of each angle in the sine argument.
+const MOON_PHASE_TERMS: [(f64, f64, i32, f64, f64, f64); 24] = [
+    (-0.40720, -0.40614, 0, 0.0, 1.0, 0.0),
+    (0.17241, 0.17302, 1, 1.0, 0.0, 0.0),
+    (0.01608, 0.01614, 0, 0.0, 2.0, 0.0),
+    (0.01039, 0.01043, 0, 0.0, 0.0, 2.0),
+    (0.00739, 0.00734, 1, -1.0, 1.0, 0.0),
+    (-0.00514, -0.00515, 1, 1.0, 1.0, 0.0),
+    (0.00208, 0.00209, 2, 2.0, 0.0, 0.0),
+    (-0.00111, -0.00111, 0, 0.0, 1.0, -2.0),
+    (-0.00057, -0.00057, 0, 0.0, 1.0, 2.0),
+    (0.00056, 0.00056, 1, 1.0, 2.0, 0.0),
+    (-0.00042, -0.00042, 0, 0.0, 3.0, 0.0),
+    (0.00042, 0.00042, 1, 1.0, 0.0, 2.0),
+    (0.00038, 0.00038, 1, 1.0, 0.0, -2.0),
+    (-0.00024, -0.00024, 1, -1.0, 2.0, 0.0),
+    (-0.00007, -0.00007, 0, 2.0, 1.0, 0.0),
+    (0.00004, 0.00004, 0, 0.0, 2.0, -2.0),
+    (0.00004, 0.00004, 0, 3.0, 0.0, 0.0),
+    (0.00003, 0.00003, 0, 1.0, 1.0, -2.0),
+    (0.00003, 0.00003, 0, 0.0, 2.0, 2.0),
+    (-0.00003, -0.00003, 0, 1.0, 1.0, 2.0),
+    (0.00003, 0.00003, 0, -1.0, 1.0, 2.0),
+    (-0.00002, -0.00002, 0, -1.0, 1.0, -2.0),
+    (-0.00002, -0.00002, 0, 1.0, 3.0, 0.0),
+    (0.00002, 0.00002, 0, 0.0, 4.0, 0.0),
+];
+
+/// The periodic terms of the equinoxes and solstices (Meeus, table 27.C), as
+/// `(A, B, C)` for `A * cos(B + C * T)`.
+const SEASON_TERMS: [(f64, f64, f64); 24] = [
+    (485.0, 324.96, 1934.136),
+    (203.0, 337.23, 32964.467),
+    (199.0, 342.08, 20.186),
+    (182.0, 27.85, 445267.112),
+    (156.0, 73.14, 45036.886),
+    (136.0, 171.52, 22518.443),
+    (77.0, 222.54, 65928.934),
+    (74.0, 296.72, 3034.906),
+    (70.0, 243.58, 9037.513),
+    (58.0, 119.81, 33718.147),
+    (52.0, 297.17, 150.678),
+    (50.0, 21.02, 2281.226),
+    (45.0, 247.54, 29929.562),
+    (44.0, 325.15, 31555.956),
+    (29.0, 60.93, 4443.417),
+    (18.0, 155.12, 67555.328),
+    (17.0, 288.79, 4562.452),
+    (16.0, 198.04, 62894.029),
+    (14.0, 199.76, 31436.921),
+    (12.0, 95.39, 14577.848),
+    (12.0, 287.11, 31931.756),
+    (12.0, 320.81, 34777.259),
+    (9.0, 227.73, 1222.114),
+    (8.0, 15.45, 16859.074),
+];
+
+/// Represents an astronomical event shown in the display rotation.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AstroEvent {
+    NewMoon,
+    FullMoon,
+    MarchEquinox,
+    JuneSolstice,
+    SeptemberEquinox,
+    DecemberSolstice,
+}
+
+/// An upcoming [AstroEvent] and when it happens.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct AstroEntry {
+    pub event: AstroEvent,
+    /// When the event happens, as a Unix timestamp.
+    pub timestamp: i64,
+}
+
+impl AstroEvent {
+    /// Returns the text shown on the displays for the event, e.g. `FULL MOON`.
+    pub fn display_text(&self) -> &'static str {
+        match self {
+            AstroEvent::NewMoon => "NEW MOON",
+            AstroEvent::FullMoon => "FULL MOON",
+            AstroEvent::MarchEquinox | AstroEvent::SeptemberEquinox => "EQUINOX",
+            AstroEvent::JuneSolstice | AstroEvent::DecemberSolstice => "SOLSTICE",
+        }
+    }
+}
+
+/// Converts a Julian Ephemeris Day into a Unix timestamp. The difference
+/// between TT and UTC, about a minute, is ignored.
+fn jde_to_timestamp(jde: f64) -> i64 {
+    ((jde - UNIX_EPOCH_JD) * 86400.0).round() as i64
+}
+
+/// Converts a Unix timestamp into a Julian Day.
+fn timestamp_to_jd(timestamp: i64) -> f64 {
+    timestamp as f64 / 86400.0 + UNIX_EPOCH_JD
+}
+
+/// Computes the new moon (`full` false) or full moon (`full` true) of
+/// lunation `k`, counted from the new moon of 2000-01-06, with the method of
+/// Meeus (chapter 49). The result is accurate to about a minute.
+///
+/// ## Returns
+/// The Julian Ephemeris Day of the phase.
+fn moon_phase_jde(k: f64, full: bool) -> f64 {
+    let k = if full { k + 0.5 } else { k };
+    let t = k / 1236.85;
+
+    let mean = 2451550.09766 + SYNODIC_MONTH * k + 0.00015437 * t.powi(2) - 0.000000150 * t.powi(3)
+        + 0.00000000073 * t.powi(4);
+
+    let e = 1.0 - 0.002516 * t - 0.0000074 * t.powi(2);
+    let m =
+        (2.5534 + 29.10535670 * k - 0.0000014 * t.powi(2) - 0.00000011 * t.powi(3)).to_radians();
+    let m_prime = (201.5643 + 385.81693528 * k + 0.0107582 * t.powi(2) + 0.00001238 * t.powi(3)
+        - 0.000000058 * t.powi(4))
+    .to_radians();
+    let f = (160.7108 + 390.67050284 * k - 0.0016118 * t.powi(2) - 0.00000227 * t.powi(3)
+        + 0.000000011 * t.powi(4))
+    .to_radians();
+    let omega =
+        (124.7746 - 1.56375588 * k + 0.0020672 * t.powi(2) + 0.00000215 * t.powi(3)).to_radians();
+
+    let correction: f64 = MOON_PHASE_TERMS
+        .iter()
+        .map(|&(new, full_moon, e_power, m_mul, m_prime_mul, f_mul)| {
+            let coefficient = if full { full_moon } else { new };
+            coefficient * e.powi(e_power) * (m_mul * m + m_prime_mul * m_prime + f_mul * f).sin()
+        })
+        .sum::<f64>()
+        - 0.00017 * omega.sin();
+
+    mean + correction
+}
+
+/// Computes an equinox or solstice of a year between 2000 and 3000, with the
+/// method of Meeus (chapter 27). The result is accurate to about a minute.
+/// Moon phases are treated as the December solstice.
+///
+/// ## Returns
+/// The Julian Ephemeris Day of the event.
+fn season_jde(year: i32, event: AstroEvent) -> f64 {
+    let y = (year as f64 - 2000.0) / 1000.0;
+
+    let [a, b, c, d, e] = match event {
+        AstroEvent::MarchEquinox => [2451623.80984, 365242.37404, 0.05169, -0.00411, -0.00057],
+        AstroEvent::JuneSolstice => [2451716.56767, 365241.62603, 0.00325, 0.00888, -0.00030],
+        AstroEvent::SeptemberEquinox => [2451810.21715, 365242.01767, -0.11575, 0.00337, 0.00078],
+        AstroEvent::DecemberSolstice | AstroEvent::NewMoon | AstroEvent::FullMoon => {
+            [2451900.05952, 365242.74049, -0.06223, -0.00823, 0.00032]
+        }
+    };
+    let mean = a + b * y + c * y.powi(2) + d * y.powi(3) + e * y.powi(4);
+
+    let t = (mean - 2451545.0) / 36525.0;
+    let w = (35999.373 * t - 2.47).to_radians();
+    let delta_lambda = 1.0 + 0.0334 * w.cos() + 0.0007 * (2.0 * w).cos();
+
+    let sum: f64 = SEASON_TERMS
+        .iter()
+        .map(|&(a, b, c)| a * (b + c * t).to_radians().cos())
+        .sum();
+
+    mean + 0.00001 * sum / delta_lambda
+}
+
+/// Returns the next new or full moon after the given Unix timestamp.
+///
+/// ## Example
+/// ```rust
+/// // The full moon of 2024-01-25 17:54 UTC
+/// let full_moon = next_moon_phase(1705000000, true);
+/// ```
+pub fn next_moon_phase(after: i64, full: bool) -> i64 {
+    let years = (timestamp_to_jd(after) - 2451550.09766) / 365.25;
+    let mut k = (years * LUNATIONS_PER_YEAR).floor() - 1.0;
+
+    loop {
+        let timestamp = jde_to_timestamp(moon_phase_jde(k, full));
+        if timestamp > after {
+            return timestamp;
+        }
+        k += 1.0;
+    }
+}
+
+/// Returns the next equinox or solstice of the given kind after the given
+/// Unix timestamp.
+///
+/// ## Example
+/// ```rust
+/// // The June solstice of 2024-06-20 20:51 UTC
+/// let solstice = next_season(1704067200, AstroEvent::JuneSolstice);
+/// ```
+pub fn next_season(after: i64, event: AstroEvent) -> i64 {
+    let year = DateTime::from_timestamp(after, 0)
+        .map(|date| date.year())
+        .unwrap_or(2000);
+
+    (year..)
+        .map(|year| jde_to_timestamp(season_jde(year, event)))
+        .find(|&timestamp| timestamp > after)
+        .unwrap_or(after)
+}
+
+/// Builds the table of upcoming events after the given Unix timestamp, in
+/// chronological order.
+///
+/// ## Arguments
+/// - `after` - The Unix timestamp to start from.
+/// - `count` - How many events to return.
+///
+/// ## Example
+/// ```rust
+/// let table = event_table(time::get_timestamp(), 6);
+/// ```
+pub fn event_table(after: i64, count: usize) -> Vec<AstroEntry> {
+    let mut table = Vec::with_capacity(count);
+    let mut from = after;
+
+    while table.len() < count {
+        let entry = [
+            AstroEvent::NewMoon,
+            AstroEvent::FullMoon,
+            AstroEvent::MarchEquinox,
+            AstroEvent::JuneSolstice,
+            AstroEvent::SeptemberEquinox,
+            AstroEvent::DecemberSolstice,
+        ]
+        .into_iter()
+        .map(|event| AstroEntry {
+            event,
+            timestamp: match event {
+                AstroEvent::NewMoon => next_moon_phase(from, false),
+                AstroEvent::FullMoon => next_moon_phase(from, true),
+                season => next_season(from, season),
+            },
+        })
+        .min_by_key(|entry| entry.timestamp)
+        .expect("There is always an upcoming event");
+
+        from = entry.timestamp;
+        table.push(entry);
+    }
+
+    table
+}
+
+/// Builds the text of an event, e.g. `FULL MOON IN 3D`, or `FULL MOON TODAY`
+/// when it happens on the current local date.
+pub fn event_message(event: AstroEvent, days: i64) -> String {
+    match days {
+        0 => format!("{} TODAY", event.display_text()),
+        days => format!("{} IN {days}D", event.display_text()),
+    }
+}
+
+/// Returns the number of local days between `today` and the event.
+fn days_until(entry: &AstroEntry, today: NaiveDate) -> Option<i64> {
+    let date = tz::to_local(DateTime::from_timestamp(entry.timestamp, 0)?).ok()?;
+
+    Some((date.date_naive() - today).num_days())
+}
+
+/// Checks whether the next astronomical event should be shown now.
+///
+/// The event is due at half past every hour, when it happens within
+/// [LOOKAHEAD_DAYS] local days, if it is enabled in prefs.
+///
+/// ## Returns
+/// - `Some(String)`: The event text to scroll, e.g. `FULL MOON IN 3D`.
+/// - `None`: If no event is due.
+///
+/// ## Example
+/// ```rust
+/// if let Some(message) = time::astro::get_due_event() {
+///     display_group.lock().unwrap().scroll_text(&message)?;
+/// }
+/// ```
+pub fn get_due_event() -> Option<String> {
+    if !is_astro_events_enabled() {
+        return None;
+    }
+
+    let now = tz::now_local().ok()?;
+
+    if now.minute() != NOTICE_MINUTE {
+        return None;
+    }
+
+    get_upcoming_event()
+}
+
+/// Returns the text of the next astronomical event, e.g. `FULL MOON IN 3D`,
+/// when it happens within [LOOKAHEAD_DAYS] local days, whether the events
+/// are enabled in prefs or not.
+///
+/// ## Example
+/// ```rust
+/// let summary = time::astro::get_upcoming_event().unwrap_or_default();
+/// ```
+pub fn get_upcoming_event() -> Option<String> {
+    let now = tz::now_local().ok()?;
+
+    // An event earlier today is still announced as today's
+    let start_of_day = now.timestamp() - now.num_seconds_from_midnight() as i64;
+    let entry = event_table(start_of_day, 1).into_iter().next()?;
+    let days = days_until(&entry, now.date_naive())?;
+
+    (days <= LOOKAHEAD_DAYS).then(|| event_message(entry.event, days))
+}
@@ -0,0 +1,72 @@
+use super::tz;
+use crate::prefs::dst_notice::is_dst_notice_enabled;
+use chrono::{DateTime, Duration, Timelike, Utc};
+use std::time::SystemTime;
+
+/// The local hours at which the DST pre-announcement is shown, once per hour.
+const NOTICE_HOURS: std::ops::RangeInclusive<u32> = 18..=22;
+
+/// How far ahead to look for a DST transition. From the last notice at 22:00,
+/// this covers the whole night.
+const LOOKAHEAD_HOURS: i64 = 12;
+
+/// Returns how much the UTC offset of the current timezone changes within the
+/// next [LOOKAHEAD_HOURS], using the transition rules of the time backend.
+///
+/// ## Returns
+/// - `Some(seconds)`: The offset change, positive when the clocks go forward.
+/// - `None`: If there is no DST transition coming up.
+pub fn get_upcoming_shift() -> Option<i32> {
+    let now_utc: DateTime<Utc> = SystemTime::now().into();
+
+    let offset_at = |time: DateTime<Utc>| {
+        tz::offset_at(time)
+            .ok()
+            .map(|offset| offset.local_minus_utc())
+    };
+
+    let shift = offset_at(now_utc + Duration::hours(LOOKAHEAD_HOURS))? - offset_at(now_utc)?;
+
+    (shift != 0).then_some(shift)
+}
+
+/// Builds the reminder text for an offset change, e.g. `CLOCKS +1H TONIGHT`.
+pub fn notice_message(shift: i32) -> String {
+    let sign = if shift > 0 { '+' } else { '-' };
+    let shift = shift.unsigned_abs();
+
+    if shift % 3600 == 0 {
+        format!("CLOCKS {sign}{}H TONIGHT", shift / 3600)
+    } else {
+        format!("CLOCKS {sign}{}M TONIGHT", shift / 60)
+    }
+}
+
+/// Checks whether the DST pre-announcement should be shown now.
+///
+/// The notice is due at the top of every hour between 18:00 and 22:00 on the
+/// evening before a DST transition, if it is enabled in prefs.
+///
+/// ## Returns
+/// - `Some(String)`: The reminder text to scroll.
+/// - `None`: If no notice is due.
+///
+/// ## Example
+/// ```rust
+/// if let Some(message) = time::dst::get_due_notice() {
+///     display_group.lock().unwrap().scroll_text(&message)?;
+/// }
+/// ```
+pub fn get_due_notice() -> Option<String> {
+    if !is_dst_notice_enabled() {
+        return None;
+    }
+
+    let now = tz::now_local().ok()?;
+
+    if now.minute() != 0 || !NOTICE_HOURS.contains(&now.hour()) {
+        return None;
+    }
+
+    get_upcoming_shift().map(notice_message)
+}
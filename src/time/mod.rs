@@ -1,105 +1,206 @@
-use chrono::{DateTime, Datelike, Timelike, Utc};
-use std::{
-    str::FromStr,
-    time::{Duration, SystemTime},
-};
+use crate::error::AppError;
+use chrono::{DateTime, Datelike, NaiveDate, Timelike, Utc, Weekday};
+use std::time::{Duration, SystemTime};
 
+pub mod astro;
+pub mod dst;
+#[cfg(feature = "posix-tz")]
+pub mod posix_tz;
 pub mod sntp;
+pub mod stopwatch;
+pub mod sun;
 pub mod tz;
 
+pub use bttf_core::time::{time_phrase, to_12_hour, PHRASE_LANGUAGES};
+
 /// Retrieves the current time formatted as a vector of digits representing the
 /// hour and minute.
 ///
-/// This function converts the current UTC time to the [TIMEZONE],
+/// This function converts the current UTC time to the current timezone,
 /// and then extracts the hour and minute components as a vector of 4 digits.
 ///
 /// ## Returns
 /// A vector of 4 bytes representing the hour and minute, where each byte is a
-/// digit.
+/// digit, or an [AppError::Timezone] if the timezone is unknown.
 ///
 /// ## Example
 /// ```rust
-/// let time = get_time();
+/// let time = get_hour_min()?;
 /// ```
-pub fn get_hour_min() -> Vec<u8> {
-    let timezone = tz::get_timezone();
-    let now_utc: DateTime<Utc> = SystemTime::now().into();
-    let now =
-        now_utc.with_timezone(&chrono_tz::Tz::from_str(&timezone).expect("Error reading Timezone"));
-    let hour = now.hour();
-    let minute = now.minute();
-
-    let time_digits: [u8; 4] = [
-        (hour / 10) as u8,
-        (hour % 10) as u8,
-        (minute / 10) as u8,
-        (minute % 10) as u8,
-    ];
-
-    time_digits.into()
+pub fn get_hour_min() -> Result<Vec<u8>, AppError> {
+    let now = tz::now_local()?;
+
+    Ok(bttf_core::time::hour_min_digits(now.hour(), now.minute()).into())
 }
 
 /// Retrieves the current year as a 4-digit vector.
 ///
 /// ## Returns
-/// A `Vec<u8>` with 4 bytes, each representing a digit of the year.
+/// A `Vec<u8>` with 4 bytes, each representing a digit of the year, or an
+/// [AppError::Timezone] if the timezone is unknown.
 ///
 /// ## Example
 /// ```rust
-/// let year_digits = get_year();
+/// let year_digits = get_year()?;
 /// println!("Year digits: {year_digits:?}");
 /// ```
-pub fn get_year() -> Vec<u8> {
-    let timezone = tz::get_timezone();
-    let now_utc: DateTime<Utc> = SystemTime::now().into();
-    let now =
-        now_utc.with_timezone(&chrono_tz::Tz::from_str(&timezone).expect("Error reading Timezone"));
-    let year = now.year();
-
-    let year_digits: [u8; 4] = [
-        ((year / 1000) % 10) as u8,
-        ((year / 100) % 10) as u8,
-        ((year / 10) % 10) as u8,
-        (year % 10) as u8,
-    ];
-
-    year_digits.into()
+pub fn get_year() -> Result<Vec<u8>, AppError> {
+    let now = tz::now_local()?;
+
+    Ok(bttf_core::time::year_digits(now.year()).into())
+}
+
+/// Retrieves the current minute and second.
+///
+/// ## Returns
+/// A tuple `(minute, second)` where both are `u8`, or an
+/// [AppError::Timezone] if the timezone is unknown.
+///
+/// ## Example
+/// ```rust
+/// let (minute, second) = get_minute_second()?;
+/// ```
+pub fn get_minute_second() -> Result<(u8, u8), AppError> {
+    let now = tz::now_local()?;
+
+    Ok((now.minute() as u8, now.second() as u8))
 }
 
 /// Retrieves the current day of the month and month number.
 ///
 /// ## Returns
-/// A tuple `(day, month)` where both are `u8`.
+/// A tuple `(day, month)` where both are `u8`, or an [AppError::Timezone] if
+/// the timezone is unknown.
 ///
 /// ## Example
 /// ```rust
-/// let (day, month) = get_day_month();
+/// let (day, month) = get_day_month()?;
 /// println!("Day: {day}, Month: {month}");
 /// ```
-pub fn get_day_month() -> (u8, u8) {
-    let timezone = tz::get_timezone();
-    let now_utc: DateTime<Utc> = SystemTime::now().into();
-    let now =
-        now_utc.with_timezone(&chrono_tz::Tz::from_str(&timezone).expect("Error reading Timezone"));
+pub fn get_day_month() -> Result<(u8, u8), AppError> {
+    let now = tz::now_local()?;
 
     let day = now.day() as u8;
     let month = now.month() as u8;
 
-    (day, month)
+    Ok((day, month))
+}
+
+/// Retrieves the current local time as minutes since midnight.
+///
+/// ## Example
+/// ```rust
+/// let minute_of_day = get_minute_of_day()?;
+/// ```
+pub fn get_minute_of_day() -> Result<u32, AppError> {
+    let now = tz::now_local()?;
+
+    Ok(now.hour() * 60 + now.minute())
+}
+
+/// Retrieves the current local time as seconds since midnight.
+///
+/// ## Example
+/// ```rust
+/// let second_of_day = get_second_of_day()?;
+/// ```
+pub fn get_second_of_day() -> Result<u32, AppError> {
+    let now = tz::now_local()?;
+
+    Ok(now.num_seconds_from_midnight())
+}
+
+/// Retrieves the current local date.
+///
+/// ## Example
+/// ```rust
+/// let today = get_local_date()?;
+/// ```
+pub fn get_local_date() -> Result<NaiveDate, AppError> {
+    let now = tz::now_local()?;
+
+    Ok(now.date_naive())
+}
+
+/// Retrieves the current local weekday.
+///
+/// ## Example
+/// ```rust
+/// let weekday = get_weekday()?;
+/// ```
+pub fn get_weekday() -> Result<Weekday, AppError> {
+    get_local_date().map(|date| date.weekday())
 }
 
 /// Calculates the time remaining until the next minute.
 ///
 /// Returns a [`Duration`] representing the time to wait until the next exact
-/// minute.
+/// minute. Every UTC offset is a whole number of minutes, so the minutes
+/// start at the same time in every timezone and the UTC time is enough.
 pub fn calculate_time_until_next_minute() -> Duration {
-    let timezone = tz::get_timezone();
     let now_utc: DateTime<Utc> = SystemTime::now().into();
-    let now_local =
-        now_utc.with_timezone(&chrono_tz::Tz::from_str(&timezone).expect("Error reading Timezone"));
 
-    let current_seconds = now_local.second();
+    let current_seconds = now_utc.second();
     let seconds_to_wait = 60 - current_seconds;
 
     Duration::new(seconds_to_wait as u64, 0)
 }
+
+/// Calculates the time remaining until the next second.
+///
+/// Returns a [`Duration`] representing the time to wait until the next exact
+/// second.
+pub fn calculate_time_until_next_second() -> Duration {
+    let now_utc: DateTime<Utc> = SystemTime::now().into();
+
+    Duration::from_nanos(1_000_000_000 - now_utc.timestamp_subsec_nanos() as u64 % 1_000_000_000)
+}
+
+/// Calculates the time remaining until the next occurrence of the given local
+/// hour.
+///
+/// ## Arguments
+/// - `hour` - The local hour to wait for, from `0` to `23`.
+///
+/// ## Returns
+/// A [`Duration`] until `hour:00:00` today, or tomorrow if that time has
+/// already passed, or an [AppError::Timezone] if the timezone is unknown.
+///
+/// ## Example
+/// ```rust
+/// let wait_time = calculate_time_until_hour(3)?;
+/// ```
+pub fn calculate_time_until_hour(hour: u32) -> Result<Duration, AppError> {
+    let now_local = tz::now_local()?.naive_local();
+
+    let mut target = now_local
+        .date()
+        .and_hms_opt(hour, 0, 0)
+        .expect("Invalid hour");
+    if target <= now_local {
+        target += chrono::Duration::days(1);
+    }
+
+    Ok((target - now_local).to_std().unwrap_or_default())
+}
+
+/// Returns the current system time as seconds since the Unix epoch.
+pub fn get_timestamp() -> i64 {
+    let now_utc: DateTime<Utc> = SystemTime::now().into();
+    now_utc.timestamp()
+}
+
+/// Sets the system time, e.g. to show an approximate time before SNTP syncs.
+///
+/// ## Arguments
+/// - `timestamp` - The new time, in seconds since the Unix epoch.
+pub fn set_system_time(timestamp: i64) {
+    let time = esp_idf_svc::sys::timeval {
+        tv_sec: timestamp,
+        tv_usec: 0,
+    };
+
+    unsafe {
+        esp_idf_svc::sys::settimeofday(&time, std::ptr::null());
+    }
+}
@@ -0,0 +1,170 @@
+use chrono::{DateTime, FixedOffset, NaiveDate, Utc};
+use esp_idf_svc::sys::{localtime_r, time_t, tm, tzset};
+use std::sync::Mutex;
+
+/// The IANA names known by the `posix-tz` backend, with their POSIX TZ
+/// strings. Any other timezone can be set as a raw POSIX TZ string, e.g.
+/// `CET-1CEST,M3.5.0,M10.5.0/3`.
+pub const POSIX_TIMEZONES: &[(&str, &str)] = &[
+    ("Africa/Cairo", "EET-2EEST,M4.5.5/0,M10.5.4/24"),
+    ("Africa/Johannesburg", "SAST-2"),
+    ("Africa/Lagos", "WAT-1"),
+    ("Africa/Nairobi", "EAT-3"),
+    ("America/Anchorage", "AKST9AKDT,M3.2.0,M11.1.0"),
+    ("America/Argentina/Buenos_Aires", "<-03>3"),
+    ("America/Bogota", "<-05>5"),
+    ("America/Caracas", "<-04>4"),
+    ("America/Chicago", "CST6CDT,M3.2.0,M11.1.0"),
+    ("America/Denver", "MST7MDT,M3.2.0,M11.1.0"),
+    ("America/Halifax", "AST4ADT,M3.2.0,M11.1.0"),
+    ("America/Lima", "<-05>5"),
+    ("America/Los_Angeles", "PST8PDT,M3.2.0,M11.1.0"),
+    ("America/Mexico_City", "CST6"),
+    ("America/New_York", "EST5EDT,M3.2.0,M11.1.0"),
+    ("America/Phoenix", "MST7"),
+    ("America/Santiago", "<-04>4<-03>,M9.1.6/24,M4.1.6/24"),
+    ("America/Sao_Paulo", "<-03>3"),
+    ("America/St_Johns", "NST3:30NDT,M3.2.0,M11.1.0"),
+    ("America/Toronto", "EST5EDT,M3.2.0,M11.1.0"),
+    ("America/Vancouver", "PST8PDT,M3.2.0,M11.1.0"),
+    ("Asia/Bangkok", "<+07>-7"),
+    ("Asia/Dhaka", "<+06>-6"),
+    ("Asia/Dubai", "<+04>-4"),
+    ("Asia/Hong_Kong", "HKT-8"),
+    ("Asia/Jakarta", "WIB-7"),
+    ("Asia/Jerusalem", "IST-2IDT,M3.4.4/26,M10.5.0"),
+    ("Asia/Karachi", "PKT-5"),
+    ("Asia/Kathmandu", "<+0545>-5:45"),
+    ("Asia/Kolkata", "IST-5:30"),
+    ("Asia/Manila", "PST-8"),
+    ("Asia/Seoul", "KST-9"),
+    ("Asia/Shanghai", "CST-8"),
+    ("Asia/Singapore", "<+08>-8"),
+    ("Asia/Taipei", "CST-8"),
+    ("Asia/Tehran", "<+0330>-3:30"),
+    ("Asia/Tokyo", "JST-9"),
+    ("Atlantic/Reykjavik", "GMT0"),
+    ("Australia/Adelaide", "ACST-9:30ACDT,M10.1.0,M4.1.0/3"),
+    ("Australia/Brisbane", "AEST-10"),
+    ("Australia/Darwin", "ACST-9:30"),
+    ("Australia/Melbourne", "AEST-10AEDT,M10.1.0,M4.1.0/3"),
+    ("Australia/Perth", "AWST-8"),
+    ("Australia/Sydney", "AEST-10AEDT,M10.1.0,M4.1.0/3"),
+    ("Europe/Amsterdam", "CET-1CEST,M3.5.0,M10.5.0/3"),
+    ("Europe/Athens", "EET-2EEST,M3.5.0/3,M10.5.0/4"),
+    ("Europe/Berlin", "CET-1CEST,M3.5.0,M10.5.0/3"),
+    ("Europe/Brussels", "CET-1CEST,M3.5.0,M10.5.0/3"),
+    ("Europe/Bucharest", "EET-2EEST,M3.5.0/3,M10.5.0/4"),
+    ("Europe/Budapest", "CET-1CEST,M3.5.0,M10.5.0/3"),
+    ("Europe/Copenhagen", "CET-1CEST,M3.5.0,M10.5.0/3"),
+    ("Europe/Dublin", "IST-1GMT0,M10.5.0,M3.5.0/1"),
+    ("Europe/Helsinki", "EET-2EEST,M3.5.0/3,M10.5.0/4"),
+    ("Europe/Istanbul", "<+03>-3"),
+    ("Europe/Kyiv", "EET-2EEST,M3.5.0/3,M10.5.0/4"),
+    ("Europe/Lisbon", "WET0WEST,M3.5.0/1,M10.5.0"),
+    ("Europe/London", "GMT0BST,M3.5.0/1,M10.5.0"),
+    ("Europe/Madrid", "CET-1CEST,M3.5.0,M10.5.0/3"),
+    ("Europe/Moscow", "MSK-3"),
+    ("Europe/Oslo", "CET-1CEST,M3.5.0,M10.5.0/3"),
+    ("Europe/Paris", "CET-1CEST,M3.5.0,M10.5.0/3"),
+    ("Europe/Prague", "CET-1CEST,M3.5.0,M10.5.0/3"),
+    ("Europe/Rome", "CET-1CEST,M3.5.0,M10.5.0/3"),
+    ("Europe/Stockholm", "CET-1CEST,M3.5.0,M10.5.0/3"),
+    ("Europe/Vienna", "CET-1CEST,M3.5.0,M10.5.0/3"),
+    ("Europe/Warsaw", "CET-1CEST,M3.5.0,M10.5.0/3"),
+    ("Europe/Zurich", "CET-1CEST,M3.5.0,M10.5.0/3"),
+    ("Pacific/Auckland", "NZST-12NZDT,M9.5.0,M4.1.0/3"),
+    ("Pacific/Honolulu", "HST10"),
+    ("UTC", "UTC0"),
+];
+
+lazy_static::lazy_static! {
+    /// The POSIX TZ string last applied to the libc, also held while the libc converts a time so no other thread applies another one in between.
+    static ref APPLIED_TZ: Mutex<Option<String>> = Mutex::new(None);
+}
+
+/// Resolves a timezone to its POSIX TZ string, either through
+/// [POSIX_TIMEZONES] or by accepting it as a raw POSIX TZ string.
+///
+/// ## Returns
+/// `None` if the timezone isn't in [POSIX_TIMEZONES] and doesn't look like a
+/// POSIX TZ string.
+///
+/// ## Example
+/// ```rust
+/// assert_eq!(to_posix("Europe/London"), Some("GMT0BST,M3.5.0/1,M10.5.0"));
+/// assert_eq!(to_posix("<+0545>-5:45"), Some("<+0545>-5:45"));
+/// ```
+pub fn to_posix(timezone: &str) -> Option<&str> {
+    if let Some((_, posix)) = POSIX_TIMEZONES.iter().find(|(name, _)| *name == timezone) {
+        return Some(posix);
+    }
+
+    is_posix_string(timezone).then_some(timezone)
+}
+
+/// Checks that a string starts like a POSIX TZ string: a name of at least 3
+/// letters, or a quoted name like `<+0545>`, followed by an offset.
+///
+/// The rest is left to the libc, which falls back to UTC on what it can't
+/// parse.
+fn is_posix_string(timezone: &str) -> bool {
+    let offset = match timezone.strip_prefix('<') {
+        Some(quoted) => quoted
+            .find('>')
+            .filter(|end| *end >= 3)
+            .map(|end| &quoted[end + 1..]),
+        None => {
+            let name_len = timezone
+                .chars()
+                .take_while(|c| c.is_ascii_alphabetic())
+                .count();
+            (name_len >= 3).then(|| &timezone[name_len..])
+        }
+    };
+
+    offset
+        .and_then(|offset| offset.trim_start_matches(['+', '-']).chars().next())
+        .is_some_and(|c| c.is_ascii_digit())
+}
+
+/// Returns the UTC offset of a POSIX TZ string at the given time, letting the
+/// libc apply its DST rules.
+///
+/// The `TZ` environment variable is only set, and `tzset` called, when the
+/// POSIX TZ string changes.
+///
+/// ## Arguments
+/// - `posix` - A POSIX TZ string, see [to_posix].
+/// - `utc` - The time to get the offset at.
+///
+/// ## Returns
+/// `None` if the libc can't convert the time.
+pub fn offset_at(posix: &str, utc: DateTime<Utc>) -> Option<FixedOffset> {
+    let mut applied_tz = APPLIED_TZ.lock().unwrap();
+    if applied_tz.as_deref() != Some(posix) {
+        std::env::set_var("TZ", posix);
+        unsafe { tzset() };
+        *applied_tz = Some(posix.to_string());
+    }
+
+    let timestamp = utc.timestamp() as time_t;
+    let mut local: tm = unsafe { std::mem::zeroed() };
+    if unsafe { localtime_r(&timestamp, &mut local) }.is_null() {
+        return None;
+    }
+    drop(applied_tz);
+
+    let local_time = NaiveDate::from_ymd_opt(
+        local.tm_year + 1900,
+        (local.tm_mon + 1) as u32,
+        local.tm_mday as u32,
+    )?
+    .and_hms_opt(
+        local.tm_hour as u32,
+        local.tm_min as u32,
+        local.tm_sec as u32,
+    )?;
+
+    FixedOffset::east_opt((local_time.and_utc().timestamp() - utc.timestamp()) as i32)
+}
@@ -1,11 +1,23 @@
-use crate::error::AppError;
-use esp_idf_svc::sntp::{EspSntp, SyncStatus};
+use crate::{
+    error::AppError,
+    event::{self, Event},
+};
+use esp_idf_svc::sntp::{EspSntp, SntpConf, SyncStatus};
+use std::time::{Duration, Instant};
+
+/// How long the first synchronization may take before an
+/// [Event::SntpFailed] is published.
+pub const SYNC_TIMEOUT: Duration = Duration::from_secs(60);
 
 /// Initializes and returns an SNTP client with the default configuration.
 ///
 /// This function creates and returns an instance of the [EspSntp] client, which
 /// is used to synchronize the device's time with a network time server.
 ///
+/// Every time a synchronization completes, an [Event::SntpSynced] is
+/// published, so the callback never waits for its subscribers, e.g. the
+/// display task rendering the time again.
+///
 /// ## Returns
 /// - `Ok(EspSntp)`: The successfully created SNTP client instance.
 /// - `Err(AppError)`: If there is an error during the SNTP client creation.
@@ -15,11 +27,17 @@ use esp_idf_svc::sntp::{EspSntp, SyncStatus};
 /// let sntp = get_sntp().expect("Failed to initialize SNTP client");
 /// ```
 pub fn get_sntp() -> Result<EspSntp<'static>, AppError> {
-    Ok(EspSntp::new_default()?)
+    Ok(EspSntp::new_with_callback(&SntpConf::default(), |_| {
+        event::publish(Event::SntpSynced)
+    })?)
 }
 
 /// Synchronizes the device's time with an SNTP server.
 ///
+/// Waits until the first synchronization completes. If it takes longer than
+/// [SYNC_TIMEOUT], an [Event::SntpFailed] is published once, and it keeps
+/// waiting.
+///
 /// ## Arguments
 /// - `sntp`: A reference to the [Sntp] client that manages the synchronization
 ///   process.
@@ -35,7 +53,19 @@ pub fn get_sntp() -> Result<EspSntp<'static>, AppError> {
 /// ```
 pub fn init_sntp(sntp: &EspSntp<'static>) -> Result<(), AppError> {
     log::info!("Synchronizing with SNTP Server...");
-    while sntp.get_sync_status() != SyncStatus::Completed {}
+
+    let started = Instant::now();
+    let mut failed = false;
+    while sntp.get_sync_status() != SyncStatus::Completed {
+        if !failed && started.elapsed() > SYNC_TIMEOUT {
+            log::warn!("Time not synchronized after {SYNC_TIMEOUT:?}, still waiting...");
+            event::publish(Event::SntpFailed);
+            failed = true;
+        }
+
+        std::thread::sleep(Duration::from_millis(100));
+    }
+
     log::info!("Time Sync Completed");
 
     Ok(())
@@ -0,0 +1,193 @@
+use serde::{Deserialize, Serialize};
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+#[cfg(feature = "stopwatch-button")]
+use crate::error::AppError;
+#[cfg(feature = "stopwatch-button")]
+use esp_idf_svc::hal::gpio::{AnyInputPin, PinDriver, Pull};
+
+/// How long a stopped stopwatch stays on the hour display without being
+/// used, before the display goes back to the clock.
+pub const IDLE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// How long the button has to be held to reset the stopwatch.
+#[cfg(feature = "stopwatch-button")]
+const LONG_PRESS: Duration = Duration::from_secs(1);
+
+/// How often the button is polled.
+#[cfg(feature = "stopwatch-button")]
+const BUTTON_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable to hold the stopwatch.
+    static ref STOPWATCH: Mutex<Stopwatch> = Mutex::new(Stopwatch::default());
+}
+
+/// Represents what can be done with the stopwatch, e.g. from a
+/// [StopwatchRequest].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StopwatchAction {
+    Start,
+    Stop,
+    Reset,
+}
+
+/// Represents the body of a request controlling the stopwatch, with the
+/// action `start`, `stop` or `reset`.
+///
+/// ## Example
+/// ```rust
+/// let stopwatch_request: StopwatchRequest =
+///     serde_json::from_str("{\"action\":\"start\"}").unwrap();
+/// ```
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct StopwatchRequest {
+    pub action: StopwatchAction,
+}
+
+/// The state of the stopwatch.
+#[derive(Default)]
+struct Stopwatch {
+    /// The time elapsed before the last start.
+    elapsed: Duration,
+    /// When the stopwatch was started, while it runs.
+    started: Option<Instant>,
+    /// When the stopwatch was last started or stopped, `None` once it is reset.
+    last_used: Option<Instant>,
+}
+
+impl Stopwatch {
+    fn elapsed(&self) -> Duration {
+        self.elapsed
+            + self
+                .started
+                .map_or(Duration::ZERO, |started| started.elapsed())
+    }
+}
+
+/// Represents the state of the stopwatch returned by the `/api/stopwatch`
+/// endpoint.
+///
+/// ## Example
+/// ```json
+/// {"active":true,"running":true,"elapsed_ms":83250}
+/// ```
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct StopwatchStatus {
+    /// Whether the stopwatch is on the hour display, see [is_active].
+    pub active: bool,
+    pub running: bool,
+    pub elapsed_ms: u64,
+}
+
+/// Starts, stops or resets the stopwatch in a thread-safe way.
+///
+/// Starting a running stopwatch or stopping a stopped one does nothing.
+/// Resetting it also stops it and gives the hour display back to the clock.
+pub fn apply(action: StopwatchAction) {
+    let mut stopwatch = STOPWATCH.lock().unwrap();
+
+    match action {
+        StopwatchAction::Start => {
+            if stopwatch.started.is_none() {
+                stopwatch.started = Some(Instant::now());
+            }
+            stopwatch.last_used = Some(Instant::now());
+        }
+        StopwatchAction::Stop => {
+            if let Some(started) = stopwatch.started.take() {
+                stopwatch.elapsed += started.elapsed();
+            }
+            stopwatch.last_used = Some(Instant::now());
+        }
+        StopwatchAction::Reset => *stopwatch = Stopwatch::default(),
+    }
+}
+
+/// Starts the stopwatch if it is stopped, or stops it if it runs.
+pub fn toggle() {
+    let running = STOPWATCH.lock().unwrap().started.is_some();
+
+    apply(if running {
+        StopwatchAction::Stop
+    } else {
+        StopwatchAction::Start
+    });
+}
+
+/// Retrieves the time measured by the stopwatch in a thread-safe way.
+pub fn elapsed() -> Duration {
+    STOPWATCH.lock().unwrap().elapsed()
+}
+
+/// Retrieves whether the stopwatch is on the hour display: while it runs, and
+/// for [IDLE_TIMEOUT] after it was stopped.
+///
+/// While active, the time must not be rendered on the hour display, so the
+/// stopwatch isn't overwritten.
+pub fn is_active() -> bool {
+    let stopwatch = STOPWATCH.lock().unwrap();
+
+    stopwatch.started.is_some()
+        || stopwatch
+            .last_used
+            .is_some_and(|last_used| last_used.elapsed() < IDLE_TIMEOUT)
+}
+
+/// Retrieves the state of the stopwatch, see [StopwatchStatus].
+pub fn get_status() -> StopwatchStatus {
+    let (running, elapsed) = {
+        let stopwatch = STOPWATCH.lock().unwrap();
+        (stopwatch.started.is_some(), stopwatch.elapsed())
+    };
+
+    StopwatchStatus {
+        active: is_active(),
+        running,
+        elapsed_ms: elapsed.as_millis() as u64,
+    }
+}
+
+/// Watches the stopwatch button (`GPIO14` by default, wired to ground): a short
+/// press starts or stops the stopwatch, and holding it for [LONG_PRESS] resets
+/// it.
+///
+/// Runs forever, so it is meant to be spawned on its own thread.
+///
+/// ## Arguments
+/// - `pin` - The button pin, which is pulled low while pressed.
+///
+/// ## Returns
+/// - `Err(AppError)`: If the pin can't be configured.
+#[cfg(feature = "stopwatch-button")]
+pub fn run_stopwatch_button(pin: AnyInputPin) -> Result<(), AppError> {
+    let mut button = PinDriver::input(pin)?;
+    button.set_pull(Pull::Up)?;
+
+    let mut pressed_for = Duration::ZERO;
+
+    loop {
+        std::thread::sleep(BUTTON_POLL_INTERVAL);
+
+        if button.is_low() {
+            if pressed_for < LONG_PRESS {
+                pressed_for += BUTTON_POLL_INTERVAL;
+
+                if pressed_for >= LONG_PRESS {
+                    log::info!("Stopwatch button held. Resetting the stopwatch");
+                    apply(StopwatchAction::Reset);
+                }
+            }
+            continue;
+        }
+
+        if pressed_for > Duration::ZERO && pressed_for < LONG_PRESS {
+            toggle();
+        }
+        pressed_for = Duration::ZERO;
+    }
+}
@@ -0,0 +1,149 @@
+use super::tz;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+
+/// The Julian Day of the Unix epoch.
+const UNIX_EPOCH_JD: f64 = 2440587.5;
+
+/// The Julian Day of the J2000 epoch, noon of 2000-01-01.
+const J2000_JD: f64 = 2451545.0;
+
+/// Number of seconds in a day.
+const SECONDS_PER_DAY: f64 = 86400.0;
+
+/// Altitude of the center of the sun at sunrise and sunset, in degrees: the
+/// radius of the sun and the atmospheric refraction put it below the horizon.
+const SUNRISE_ALTITUDE: f64 = -0.833;
+
+/// The obliquity of the ecliptic, in degrees.
+const OBLIQUITY: f64 = 23.4397;
+
+/// Represents the sunrise and the sunset of a day, as Unix timestamps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SunTimes {
+    /// The sun rises and sets.
+    Regular { sunrise: i64, sunset: i64 },
+    /// The sun stays above the horizon all day, e.g. in a polar summer.
+    AlwaysUp,
+    /// The sun stays below the horizon all day, e.g. in a polar winter.
+    AlwaysDown,
+}
+
+/// Represents the sunrise and the sunset of a day in local time, e.g. for
+/// `/api/auto_dim`. Both are `None` on days the sun doesn't rise or set.
+///
+/// ## Example
+/// ```json
+/// {"sunrise":"06:52","sunset":"18:31"}
+/// ```
+#[derive(Clone, Debug, Serialize)]
+pub struct LocalSunTimes {
+    pub sunrise: Option<String>,
+    pub sunset: Option<String>,
+}
+
+/// Computes the sunrise and the sunset of a day with the sunrise equation,
+/// accurate to a minute or two away from the poles.
+///
+/// ## Arguments
+/// - `date` - The day, as a date at the given longitude.
+/// - `latitude` - The latitude in decimal degrees, north positive.
+/// - `longitude` - The longitude in decimal degrees, east positive.
+///
+/// ## Example
+/// ```rust
+/// let times = sun_times(
+///     NaiveDate::from_ymd_opt(2015, 10, 21).unwrap(),
+///     34.14,
+///     -118.35,
+/// );
+/// ```
+pub fn sun_times(date: NaiveDate, latitude: f32, longitude: f32) -> SunTimes {
+    let (latitude, longitude) = (latitude as f64, longitude as f64);
+    let j2000 = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
+    let days = (date - j2000).num_days() as f64 + 0.0008;
+
+    let mean_solar_time = days - longitude / 360.0;
+    let anomaly = (357.5291 + 0.98560028 * mean_solar_time).rem_euclid(360.0);
+    let m = anomaly.to_radians();
+    let center = 1.9148 * m.sin() + 0.0200 * (2.0 * m).sin() + 0.0003 * (3.0 * m).sin();
+    let ecliptic_longitude = (anomaly + center + 180.0 + 102.9372)
+        .rem_euclid(360.0)
+        .to_radians();
+
+    let transit =
+        J2000_JD + mean_solar_time + 0.0053 * m.sin() - 0.0069 * (2.0 * ecliptic_longitude).sin();
+    let declination = (ecliptic_longitude.sin() * OBLIQUITY.to_radians().sin()).asin();
+
+    let phi = latitude.to_radians();
+    let cos_hour_angle = (SUNRISE_ALTITUDE.to_radians().sin() - phi.sin() * declination.sin())
+        / (phi.cos() * declination.cos());
+
+    if cos_hour_angle < -1.0 {
+        return SunTimes::AlwaysUp;
+    }
+    if cos_hour_angle > 1.0 {
+        return SunTimes::AlwaysDown;
+    }
+
+    let half_day = cos_hour_angle.acos().to_degrees() / 360.0;
+    let timestamp = |jd: f64| ((jd - UNIX_EPOCH_JD) * SECONDS_PER_DAY).round() as i64;
+
+    SunTimes::Regular {
+        sunrise: timestamp(transit - half_day),
+        sunset: timestamp(transit + half_day),
+    }
+}
+
+/// Returns the date at a longitude at the given time, from the mean solar
+/// time, so the sunrise and the sunset of that date surround its noon.
+fn solar_date(timestamp: i64, longitude: f32) -> NaiveDate {
+    let offset = (longitude as f64 / 360.0 * SECONDS_PER_DAY) as i64;
+
+    DateTime::<Utc>::from_timestamp(timestamp + offset, 0)
+        .unwrap_or_default()
+        .date_naive()
+}
+
+/// Tells whether the sun is up at a location at the given time.
+///
+/// ## Arguments
+/// - `timestamp` - The time, as a Unix timestamp.
+/// - `latitude` - The latitude in decimal degrees, north positive.
+/// - `longitude` - The longitude in decimal degrees, east positive.
+pub fn is_daylight(timestamp: i64, latitude: f32, longitude: f32) -> bool {
+    match sun_times(solar_date(timestamp, longitude), latitude, longitude) {
+        SunTimes::Regular { sunrise, sunset } => (sunrise..sunset).contains(&timestamp),
+        SunTimes::AlwaysUp => true,
+        SunTimes::AlwaysDown => false,
+    }
+}
+
+/// Returns today's sunrise and sunset at a location, in the local time of the
+/// clock, see [LocalSunTimes].
+///
+/// ## Arguments
+/// - `timestamp` - The current time, as a Unix timestamp.
+/// - `latitude` - The latitude in decimal degrees, north positive.
+/// - `longitude` - The longitude in decimal degrees, east positive.
+pub fn local_sun_times(timestamp: i64, latitude: f32, longitude: f32) -> LocalSunTimes {
+    let local_time = |timestamp: i64| {
+        DateTime::<Utc>::from_timestamp(timestamp, 0).map(|time| {
+            tz::to_local(time)
+                .map_or(time.naive_utc(), |time| time.naive_local())
+                .format("%H:%M")
+                .to_string()
+        })
+    };
+
+    match sun_times(solar_date(timestamp, longitude), latitude, longitude) {
+        SunTimes::Regular { sunrise, sunset } => LocalSunTimes {
+            sunrise: local_time(sunrise),
+            sunset: local_time(sunset),
+        },
+        SunTimes::AlwaysUp | SunTimes::AlwaysDown => LocalSunTimes {
+            sunrise: None,
+            sunset: None,
+        },
+    }
+}
@@ -1,4 +1,21 @@
-use std::sync::{Arc, Mutex};
+use crate::{error::AppError, state};
+use chrono::{DateTime, FixedOffset, Utc};
+use std::{sync::Mutex, time::SystemTime};
+
+#[cfg(feature = "tzdb")]
+use chrono::{Offset, TimeZone};
+#[cfg(feature = "tzdb")]
+use std::str::FromStr;
+
+#[cfg(feature = "posix-tz")]
+use super::posix_tz;
+
+/// A timezone resolved by the time backend: a chrono-tz `Tz` with the `tzdb`
+/// feature, or a POSIX TZ string with the `posix-tz` feature.
+#[cfg(feature = "tzdb")]
+type ResolvedTimezone = chrono_tz::Tz;
+#[cfg(feature = "posix-tz")]
+type ResolvedTimezone = String;
 
 /// Represents a request to set or retrieve a timezone.
 ///
@@ -13,11 +30,13 @@ pub struct TimezoneRequest {
 }
 
 lazy_static::lazy_static! {
-    pub static ref TIMEZONE: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+    /// A global, thread-safe static variable that holds the current timezone resolved once by [set_timezone], or `None` if it is unknown.
+    static ref RESOLVED_TIMEZONE: Mutex<Option<ResolvedTimezone>> =
+        Mutex::new(resolve_timezone(env!("DEFAULT_TIMEZONE")));
 }
 
-/// Retrieves the current timezone, either from the global [TIMEZONE] or the
-/// default environment value.
+/// Retrieves the current timezone, either from the runtime
+/// [Settings](state::Settings) or the default environment value.
 ///
 /// ## Returns
 /// A string representing the current timezone.
@@ -27,17 +46,17 @@ lazy_static::lazy_static! {
 /// let timezone = get_timezone();
 /// ```
 pub fn get_timezone() -> String {
-    let timezone = TIMEZONE.lock().unwrap();
-    match &*timezone {
-        Some(tz) => tz.clone(),
-        None => env!("DEFAULT_TIMEZONE").to_string(),
-    }
+    state::read_settings(|settings| settings.timezone.clone())
+        .unwrap_or_else(|| env!("DEFAULT_TIMEZONE").to_string())
 }
 
 /// Sets the global timezone to the provided value.
 ///
-/// This function updates the global [TIMEZONE] variable with the new timezone
-/// value.
+/// This function updates the timezone of the runtime
+/// [Settings](state::Settings), and resolves it once for the time functions.
+/// An unknown timezone, e.g. a corrupted NVS value, is kept so it can be fixed
+/// from the web portal, but makes the time functions return an
+/// [AppError::Timezone].
 ///
 /// ## Arguments
 /// - `new_timezone` - A string representing the new timezone.
@@ -47,6 +66,97 @@ pub fn get_timezone() -> String {
 /// set_timezone("America/New_York".to_string());
 /// ```
 pub fn set_timezone(new_timezone: String) {
-    let mut timezone = TIMEZONE.lock().unwrap();
-    *timezone = Some(new_timezone);
+    let resolved_timezone = resolve_timezone(&new_timezone);
+    if resolved_timezone.is_none() {
+        log::warn!("Unknown timezone: {new_timezone}");
+    }
+
+    state::update_settings(|settings| settings.timezone = Some(new_timezone));
+    *RESOLVED_TIMEZONE.lock().unwrap() = resolved_timezone;
+}
+
+/// Resolves a timezone with the time backend: an IANA name with the `tzdb`
+/// feature, or a name of the built-in table or a POSIX TZ string with the
+/// `posix-tz` feature.
+fn resolve_timezone(timezone: &str) -> Option<ResolvedTimezone> {
+    #[cfg(feature = "tzdb")]
+    return chrono_tz::Tz::from_str(timezone).ok();
+
+    #[cfg(feature = "posix-tz")]
+    return posix_tz::to_posix(timezone).map(str::to_string);
+}
+
+/// Checks whether a timezone is known by the time backend, see
+/// [resolve_timezone].
+pub fn is_valid_timezone(timezone: &str) -> bool {
+    resolve_timezone(timezone).is_some()
+}
+
+/// Returns the UTC offset of the current timezone at the given time, which
+/// changes across DST transitions.
+///
+/// ## Returns
+/// An [AppError::Timezone] if the current timezone is unknown, e.g. a
+/// corrupted NVS value.
+pub fn offset_at(utc: DateTime<Utc>) -> Result<FixedOffset, AppError> {
+    let timezone = RESOLVED_TIMEZONE
+        .lock()
+        .unwrap()
+        .as_ref()
+        .cloned()
+        .ok_or_else(|| AppError::Timezone(format!("Unknown timezone: {}", get_timezone())))?;
+
+    #[cfg(feature = "tzdb")]
+    return Ok(timezone.offset_from_utc_datetime(&utc.naive_utc()).fix());
+
+    #[cfg(feature = "posix-tz")]
+    return posix_tz::offset_at(&timezone, utc)
+        .ok_or_else(|| AppError::Timezone(format!("Failed to convert {utc} to {timezone}")));
+}
+
+/// Converts a UTC time to the local time of the current timezone.
+///
+/// ## Returns
+/// An [AppError::Timezone] if the current timezone is unknown, see
+/// [offset_at].
+///
+/// ## Example
+/// ```rust
+/// let local = to_local(Utc::now())?;
+/// ```
+pub fn to_local(utc: DateTime<Utc>) -> Result<DateTime<FixedOffset>, AppError> {
+    offset_at(utc).map(|offset| utc.with_timezone(&offset))
+}
+
+/// Retrieves the current local time in the current timezone, see
+/// [to_local].
+pub fn now_local() -> Result<DateTime<FixedOffset>, AppError> {
+    to_local(SystemTime::now().into())
+}
+
+/// Lists the names of every timezone known by the time backend, optionally
+/// filtered by a case-insensitive prefix: the IANA names of chrono-tz with the
+/// `tzdb` feature, or the names of the built-in table with the `posix-tz`
+/// feature.
+///
+/// ## Arguments
+/// - `prefix` - Only names starting with it are listed. An empty prefix lists
+///   every timezone.
+///
+/// ## Example
+/// ```rust
+/// let american_timezones = list_timezones("america/");
+/// ```
+pub fn list_timezones(prefix: &str) -> Vec<&'static str> {
+    let prefix = prefix.to_ascii_lowercase();
+
+    #[cfg(feature = "tzdb")]
+    let names = chrono_tz::TZ_VARIANTS.iter().map(|tz| tz.name());
+
+    #[cfg(feature = "posix-tz")]
+    let names = posix_tz::POSIX_TIMEZONES.iter().map(|(name, _)| *name);
+
+    names
+        .filter(|name| name.to_ascii_lowercase().starts_with(&prefix))
+        .collect()
 }
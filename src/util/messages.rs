@@ -1,8 +1,21 @@
+use chrono::Weekday;
+
 /// Enum representing different display messages.
 /// Used to send specific byte patterns to the display.
+///
+/// Only messages with hand-crafted glyphs, like the two-digit `M` and `W`,
+/// belong here. Anything else can be written with
+/// [encode_str](super::encode_str).
 pub enum DisplayMessage {
     Init,
     Sync,
+    /// The abbreviation of a weekday. `M` and `W` take two digits each.
+    Weekday(Weekday),
+    /// Shown while waiting for the router during WPS provisioning.
+    Wps,
+    /// An empty battery, shown on the date display while the backup battery
+    /// is low.
+    LowBattery,
 }
 
 impl DisplayMessage {
@@ -31,6 +44,60 @@ impl DisplayMessage {
                 0b00110111, // n
                 0b00111001, // c
             ],
+            DisplayMessage::Weekday(Weekday::Mon) => [
+                0b00110001, // left half of m
+                0b00000111, // right half of m
+                0b01011100, // o
+                0b01010100, // n
+            ],
+            DisplayMessage::Weekday(Weekday::Tue) => [
+                0b01111000, // t
+                0b00011100, // u
+                0b01111001, // e
+                0b01101101, // s
+            ],
+            DisplayMessage::Weekday(Weekday::Wed) => [
+                0b00111000, // left half of w
+                0b00001110, // right half of w
+                0b01111001, // e
+                0b01011110, // d
+            ],
+            DisplayMessage::Weekday(Weekday::Thu) => [
+                0b01111000, // t
+                0b01110100, // h
+                0b00011100, // u
+                0b01010000, // r
+            ],
+            DisplayMessage::Weekday(Weekday::Fri) => [
+                0b01110001, // f
+                0b01010000, // r
+                0b00000100, // i
+                0b00000000, // blank
+            ],
+            DisplayMessage::Weekday(Weekday::Sat) => [
+                0b01101101, // s
+                0b01110111, // a
+                0b01111000, // t
+                0b00000000, // blank
+            ],
+            DisplayMessage::Weekday(Weekday::Sun) => [
+                0b01101101, // s
+                0b00011100, // u
+                0b01010100, // n
+                0b00000000, // blank
+            ],
+            DisplayMessage::Wps => [
+                0b00111000, // left half of w
+                0b00001110, // right half of w
+                0b01110011, // p
+                0b01101101, // s
+            ],
+            DisplayMessage::LowBattery => [
+                0b00111001, // left side of the battery
+                0b00001001, // top and bottom of the battery
+                0b00001111, // right side of the battery
+                0b01000000, // tip of the battery
+            ],
         }
     }
 }
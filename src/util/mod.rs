@@ -1,15 +1,6 @@
 pub mod messages;
 
-/// A lookup table for displaying digits (0-9) on a 7-segment display.
-pub const DISPLAY_DIGIT: [u8; 10] = [
-    0b00111111, // 0
-    0b00000110, // 1
-    0b01011011, // 2
-    0b01001111, // 3
-    0b01100110, // 4
-    0b01101101, // 5
-    0b01111101, // 6
-    0b00000111, // 7
-    0b01111111, // 8
-    0b01101111, // 9
-];
+pub use bttf_core::{
+    query,
+    segments::{encode_char, encode_str, encode_two_digits, DISPLAY_DIGIT},
+};
@@ -0,0 +1,196 @@
+use crate::{error::AppError, server::client};
+use serde::{Deserialize, Serialize};
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// How often the configuration is checked for a due fetch.
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Max number of minutes between two fetches.
+pub const MAX_WEATHER_INTERVAL: u16 = 24 * 60;
+
+/// Max size of the response of the weather provider.
+const MAX_RESPONSE_LEN: usize = 2048;
+
+/// Number of missed fetches after which the outdoor temperature is considered
+/// stale and no longer shown.
+const STALE_AFTER_FETCHES: u32 = 3;
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable to hold the weather configuration.
+    pub static ref WEATHER_CONFIG: Arc<Mutex<WeatherConfig>> =
+        Arc::new(Mutex::new(WeatherConfig::default()));
+
+    /// A global, thread-safe static variable to hold the last outdoor temperature and when it was fetched.
+    static ref OUTDOOR_TEMPERATURE: Mutex<Option<(f32, Instant)>> = Mutex::new(None);
+}
+
+/// The location the outdoor temperature is fetched for, in decimal degrees.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct WeatherLocation {
+    pub latitude: f32,
+    pub longitude: f32,
+}
+
+/// Represents where and how often the outdoor temperature is fetched from
+/// [Open-Meteo](https://open-meteo.com), which needs no API key, and whether it
+/// is shown on the date display.
+///
+/// ## Example
+/// ```rust
+/// let config: WeatherConfig = serde_json::from_str(
+///     "{\"location\":{\"latitude\":34.14,\"longitude\":-118.35},\"interval\":30,\"show\":true}",
+/// )
+/// .unwrap();
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WeatherConfig {
+    /// The location, or `None` to disable the weather client.
+    pub location: Option<WeatherLocation>,
+    /// Minutes between two fetches.
+    pub interval: u16,
+    /// Whether the date display alternates with the outdoor temperature.
+    pub show: bool,
+}
+
+impl Default for WeatherConfig {
+    fn default() -> Self {
+        Self {
+            location: None,
+            interval: 30,
+            show: false,
+        }
+    }
+}
+
+impl WeatherConfig {
+    /// Checks that the location is on Earth and that the interval is between
+    /// 1 minute and [MAX_WEATHER_INTERVAL].
+    pub fn is_valid(&self) -> bool {
+        (1..=MAX_WEATHER_INTERVAL).contains(&self.interval)
+            && self.location.map_or(true, |location| {
+                (-90.0..=90.0).contains(&location.latitude)
+                    && (-180.0..=180.0).contains(&location.longitude)
+            })
+    }
+}
+
+/// The part of the Open-Meteo response that holds the current weather.
+#[derive(Deserialize)]
+struct OpenMeteoResponse {
+    current: OpenMeteoCurrent,
+}
+
+/// The current weather variables requested from Open-Meteo.
+#[derive(Deserialize)]
+struct OpenMeteoCurrent {
+    temperature_2m: f32,
+}
+
+/// Retrieves the current weather configuration in a thread-safe way.
+pub fn get_weather_config() -> WeatherConfig {
+    *WEATHER_CONFIG.lock().unwrap()
+}
+
+/// Updates the global weather configuration in a thread-safe way.
+pub fn set_weather_config(config: WeatherConfig) {
+    let mut config_guard = WEATHER_CONFIG.lock().unwrap();
+    *config_guard = config;
+}
+
+/// Retrieves the last outdoor temperature in °C, or `None` when the weather
+/// client is disabled or the temperature couldn't be fetched recently.
+pub fn get_outdoor_temperature() -> Option<f32> {
+    let config = get_weather_config();
+    config.location?;
+
+    let stale_after = Duration::from_secs(config.interval as u64 * 60) * STALE_AFTER_FETCHES;
+
+    OUTDOOR_TEMPERATURE
+        .lock()
+        .unwrap()
+        .filter(|(_, fetched)| fetched.elapsed() < stale_after)
+        .map(|(temperature_c, _)| temperature_c)
+}
+
+/// Fetches the current outdoor temperature of a location from Open-Meteo.
+///
+/// ## Returns
+/// - `Ok(f32)`: The temperature in °C, 2 meters above the ground.
+/// - `Err(AppError)`: If Open-Meteo can't be reached or answers with an error.
+///
+/// ## Example
+/// ```rust
+/// let temperature_c = fetch_temperature(WeatherLocation {
+///     latitude: 34.14,
+///     longitude: -118.35,
+/// })?;
+/// ```
+pub fn fetch_temperature(location: WeatherLocation) -> Result<f32, AppError> {
+    let url = format!(
+        "https://api.open-meteo.com/v1/forecast?latitude={}&longitude={}&current=temperature_2m",
+        location.latitude, location.longitude
+    );
+
+    let (status, body) = client::get(&url, &[], MAX_RESPONSE_LEN)?;
+    if status != 200 {
+        return Err(AppError::Server(format!(
+            "Weather request failed with status {status}"
+        )));
+    }
+
+    let response: OpenMeteoResponse =
+        serde_json::from_slice(&body).map_err(|e| AppError::Server(e.to_string()))?;
+
+    Ok(response.current.temperature_2m)
+}
+
+/// Returns a [Scheduler](crate::scheduler::Scheduler) job that fetches the
+/// outdoor temperature every configured number of minutes, and right away when
+/// the location changes, while a location is configured. It is meant to run
+/// every [CHECK_INTERVAL].
+///
+/// Failed fetches are logged and the last temperature is kept until it goes
+/// stale.
+///
+/// ## Example
+/// ```rust
+/// scheduler.add(
+///     "weather",
+///     Schedule::Every(weather::CHECK_INTERVAL),
+///     weather::weather_job(),
+/// );
+/// ```
+pub fn weather_job() -> impl FnMut() + Send {
+    let mut last_fetch: Option<(WeatherLocation, Instant)> = None;
+
+    move || {
+        let config = get_weather_config();
+
+        let Some(location) = config.location else {
+            return;
+        };
+
+        let interval = Duration::from_secs(config.interval as u64 * 60);
+        let due = last_fetch.map_or(true, |(last_location, fetched)| {
+            last_location != location || fetched.elapsed() >= interval
+        });
+
+        if !due {
+            return;
+        }
+
+        match fetch_temperature(location) {
+            Ok(temperature_c) => {
+                log::info!("Outdoor temperature is {temperature_c}°C");
+                *OUTDOOR_TEMPERATURE.lock().unwrap() = Some((temperature_c, Instant::now()));
+            }
+            Err(e) => log::warn!("Failed to fetch the outdoor temperature: {e:?}"),
+        }
+
+        last_fetch = Some((location, Instant::now()));
+    }
+}
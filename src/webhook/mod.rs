@@ -0,0 +1,230 @@
+use crate::{config::snapshot, error::AppError, event::Event, server::client};
+use serde::{Deserialize, Serialize};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, SyncSender, TrySendError},
+        Mutex,
+    },
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Max number of notifications waiting to be sent. Notifications raised while
+/// the queue is full are dropped.
+const QUEUE_LEN: usize = 8;
+
+/// Number of times a notification is sent before it is dropped.
+const MAX_ATTEMPTS: u8 = 10;
+
+/// How long to wait before sending a notification again, e.g. until the
+/// Wi-Fi is back after a `wifi_lost`.
+const RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+lazy_static::lazy_static! {
+    /// A global, thread-safe static variable to hold the webhook URL, if any.
+    static ref WEBHOOK_URL: Mutex<Option<String>> = Mutex::new(None);
+
+    /// A global, thread-safe static variable to hold the sender of the webhook task, once it runs.
+    static ref WEBHOOK_QUEUE: Mutex<Option<SyncSender<Notification>>> = Mutex::new(None);
+}
+
+/// Whether the station is connected, to notify only the first disconnection
+/// of the reconnect attempts.
+static WIFI_CONNECTED: AtomicBool = AtomicBool::new(false);
+
+/// Whether a `wifi_lost` was notified since the station last connected.
+static WIFI_LOST: AtomicBool = AtomicBool::new(false);
+
+/// Represents a request to set or clear the webhook URL.
+///
+/// ## Example
+/// ```rust
+/// let webhook_request: WebhookRequest =
+///     serde_json::from_str("{\"url\":\"https://ntfy.sh/my-bttf-clock\"}").unwrap();
+/// ```
+#[derive(Clone, Deserialize)]
+pub struct WebhookRequest {
+    #[serde(default)]
+    pub url: Option<String>,
+}
+
+/// Represents the JSON body `POST`ed to the webhook URL.
+///
+/// ## Example
+/// ```json
+/// {"event":"wifi_lost","message":"Wi-Fi lost: Beacon timeout","timestamp":1735689600}
+/// ```
+#[derive(Clone, Debug, Serialize)]
+pub struct Notification {
+    /// `boot`, `wifi_lost`, `wifi_recovered`, `sntp_failed`, `alarm` or
+    /// `test`.
+    pub event: &'static str,
+    /// A short text for the push notification.
+    pub message: String,
+    /// When the event happened, in seconds since the Unix epoch. Close to `0`
+    /// if the time was not synchronized yet.
+    pub timestamp: u64,
+}
+
+impl Notification {
+    /// Creates a notification for an event that happened now.
+    pub fn new(event: &'static str, message: impl ToString) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or(0);
+
+        Self {
+            event,
+            message: message.to_string(),
+            timestamp,
+        }
+    }
+}
+
+/// Checks that a webhook URL can be used by the HTTP client and stored in
+/// NVS.
+pub fn is_valid_url(url: &str) -> bool {
+    snapshot::is_valid_url(url)
+}
+
+/// Retrieves the webhook URL in a thread-safe way.
+pub fn get_webhook_url() -> Option<String> {
+    WEBHOOK_URL.lock().unwrap().clone()
+}
+
+/// Updates the webhook URL in a thread-safe way. `None` disables the
+/// notifications.
+pub fn set_webhook_url(new_url: Option<String>) {
+    let mut url_guard = WEBHOOK_URL.lock().unwrap();
+    *url_guard = new_url;
+}
+
+/// Sends a notification to the webhook URL right away.
+///
+/// ## Returns
+/// - `Ok(())`: If the server answered with a `2xx` status.
+/// - `Err(AppError)`: If no URL is set or the request fails.
+///
+/// ## Example
+/// ```rust
+/// webhook::send(&Notification::new("test", "Test notification"))?;
+/// ```
+pub fn send(notification: &Notification) -> Result<(), AppError> {
+    let Some(url) = get_webhook_url() else {
+        return Err(AppError::Config("No webhook URL set".to_string()));
+    };
+
+    let body = serde_json::to_vec(notification).map_err(|e| AppError::Server(e.to_string()))?;
+
+    let status = client::post_json(&url, &body)?;
+    if !(200..300).contains(&status) {
+        return Err(AppError::Server(format!(
+            "Webhook failed with status {status}"
+        )));
+    }
+
+    log::info!("Webhook '{}' sent", notification.event);
+
+    Ok(())
+}
+
+/// Queues a notification for the webhook task, if a webhook URL is set.
+///
+/// The notification is dropped, with a warning, when the queue is full or the
+/// webhook task is not running yet.
+fn notify(notification: Notification) {
+    if get_webhook_url().is_none() {
+        return;
+    }
+
+    let queue = WEBHOOK_QUEUE.lock().unwrap();
+
+    let Some(sender) = queue.as_ref() else {
+        log::warn!("Webhook task not running, dropping {notification:?}");
+        return;
+    };
+
+    match sender.try_send(notification) {
+        Ok(()) => {}
+        Err(TrySendError::Full(notification)) => {
+            log::warn!("Webhook queue full, dropping {notification:?}")
+        }
+        Err(TrySendError::Disconnected(notification)) => {
+            log::warn!("Webhook task stopped, dropping {notification:?}")
+        }
+    }
+}
+
+/// Notifies the boot, the Wi-Fi loss and recovery, the SNTP failure and the
+/// alarm from the [Event]s of the event bus, see
+/// [subscribe](crate::event::subscribe).
+///
+/// Only the first disconnection is notified while the station tries to
+/// reconnect, and `wifi_recovered` only after a `wifi_lost`.
+pub fn on_event(event: &Event) {
+    match event {
+        Event::Booted => notify(Notification::new("boot", "Clock started")),
+        Event::WifiConnected => {
+            WIFI_CONNECTED.store(true, Ordering::Relaxed);
+            if WIFI_LOST.swap(false, Ordering::Relaxed) {
+                notify(Notification::new("wifi_recovered", "Wi-Fi recovered"));
+            }
+        }
+        Event::WifiDisconnected(reason) => {
+            if WIFI_CONNECTED.swap(false, Ordering::Relaxed) {
+                WIFI_LOST.store(true, Ordering::Relaxed);
+                notify(Notification::new(
+                    "wifi_lost",
+                    format!("Wi-Fi lost: {}", reason.description()),
+                ));
+            }
+        }
+        Event::SntpFailed => notify(Notification::new(
+            "sntp_failed",
+            "Time not synchronized with SNTP",
+        )),
+        Event::AlarmFired => notify(Notification::new("alarm", "Alarm fired")),
+        _ => {}
+    }
+}
+
+/// Sends the notifications queued by [on_event] to the webhook URL, one after
+/// the other. Each one is tried up to [MAX_ATTEMPTS] times, every
+/// [RETRY_INTERVAL], so a `wifi_lost` is delivered once the Wi-Fi is back.
+/// Runs forever, so it is meant to be spawned on its own thread.
+///
+/// ## Example
+/// ```rust
+/// std::thread::spawn(webhook::run_webhooks);
+/// ```
+pub fn run_webhooks() {
+    let (sender, receiver) = mpsc::sync_channel(QUEUE_LEN);
+    *WEBHOOK_QUEUE.lock().unwrap() = Some(sender);
+
+    for notification in receiver {
+        for attempt in 1..=MAX_ATTEMPTS {
+            // The URL may be cleared while a notification waits to be sent again
+            if get_webhook_url().is_none() {
+                break;
+            }
+
+            match send(&notification) {
+                Ok(()) => break,
+                Err(e) if attempt == MAX_ATTEMPTS => {
+                    log::error!(
+                        "Dropping webhook '{}' after {MAX_ATTEMPTS} attempts: {e:?}",
+                        notification.event
+                    );
+                }
+                Err(e) => {
+                    log::warn!(
+                        "Failed to send webhook '{}', retrying in {RETRY_INTERVAL:?}: {e:?}",
+                        notification.event
+                    );
+                    std::thread::sleep(RETRY_INTERVAL);
+                }
+            }
+        }
+    }
+}
@@ -1,8 +1,21 @@
-use super::get_wifi;
-use crate::error::AppError;
+use super::{
+    enterprise::{apply_enterprise_config, disable_enterprise, EnterpriseConfig},
+    get_connection_attempt, get_wifi, set_disconnect_reason,
+    station::{client_configuration, get_station_ip, ip_display_text},
+    AuthMode, ConnectionAttempt, StaticIpConfig, WifiCredentials,
+};
+use crate::{
+    error::AppError,
+    module::display::{SharedDisplayGroup, GROUP_DIGITS},
+};
 use esp_idf_svc::{
     eventloop::EspSystemEventLoop,
-    hal::{modem::WifiModemPeripheral, peripheral::Peripheral},
+    hal::{
+        delay::FreeRtos,
+        gpio::{IOPin, OutputPin},
+        modem::WifiModemPeripheral,
+        peripheral::Peripheral,
+    },
     ipv4::{self, Mask, RouterConfiguration, Subnet},
     netif::{EspNetif, NetifConfiguration, NetifStack},
     nvs::EspDefaultNvsPartition,
@@ -11,12 +24,38 @@ use esp_idf_svc::{
         EspWifi, WifiDriver,
     },
 };
-use std::{net::Ipv4Addr, str::FromStr};
+use std::{net::Ipv4Addr, str::FromStr, time::Duration};
 
 pub const AP_IP_ADDRESS: &str = env!("AP_IP_ADDRESS");
 const AP_SSID: &str = env!("AP_SSID");
 const AP_PASSWORD: &str = env!("AP_PASSWORD");
 
+/// Wi-Fi channel of the Access Point, from `1` to `13`. Channels `12` and
+/// `13` also need a [country code](super::set_country_code) that allows them.
+const AP_CHANNEL: &str = env!("AP_CHANNEL");
+
+/// Max number of devices connected to the Access Point at once, from `1` to
+/// `10`.
+const AP_MAX_CONNECTIONS: &str = env!("AP_MAX_CONNECTIONS");
+
+/// Minutes the captive portal waits for credentials before the clock
+/// restarts, `0` to wait forever.
+const PROVISIONING_TIMEOUT_MINS: &str = env!("PROVISIONING_TIMEOUT_MINS");
+
+/// Channel used when [AP_CHANNEL] is not a valid channel.
+const DEFAULT_AP_CHANNEL: u8 = 1;
+
+/// Max connections used when [AP_MAX_CONNECTIONS] is out of range.
+const DEFAULT_AP_MAX_CONNECTIONS: u16 = 4;
+
+/// How long the captive portal stays up after the credentials were accepted,
+/// so the page can show the new IP address before the device restarts.
+pub const RESULT_DELAY: Duration = Duration::from_secs(10);
+
+/// How long each step of the setup hint stays on the displays, in
+/// milliseconds.
+const SETUP_HINT_DELAY: u32 = 2000;
+
 /// Creates and configures an Access Point (AP) mode Wi-Fi instance.
 ///
 /// ## Arguments
@@ -59,7 +98,8 @@ where
 /// - SSID: [AP_SSID]
 /// - Password: [AP_PASSWORD]
 /// - Authentication: WPA2-Personal
-/// - Maximum number of connections: 4
+/// - Channel: [AP_CHANNEL]
+/// - Maximum number of connections: [AP_MAX_CONNECTIONS]
 /// - IP configuration: [AP_IP_ADDRESS]
 ///
 /// ## Arguments
@@ -100,7 +140,8 @@ fn configure_ap(wifi_ap: WifiDriver) -> Result<EspWifi, AppError> {
         ssid: AP_SSID.try_into().unwrap(),
         auth_method: AuthMethod::WPA2Personal,
         password: AP_PASSWORD.try_into().unwrap(),
-        max_connections: 4,
+        channel: ap_channel(),
+        max_connections: ap_max_connections(),
         ..Default::default()
     });
     wifi_ap.set_configuration(&wifi_configuration)?;
@@ -108,6 +149,51 @@ fn configure_ap(wifi_ap: WifiDriver) -> Result<EspWifi, AppError> {
     Ok(wifi_ap)
 }
 
+/// Reads the channel of the Access Point from [AP_CHANNEL], falling back to
+/// [DEFAULT_AP_CHANNEL] with a warning if it is not a channel from `1` to
+/// `13`.
+fn ap_channel() -> u8 {
+    match AP_CHANNEL.parse::<u8>() {
+        Ok(channel) if (1..=13).contains(&channel) => channel,
+        _ => {
+            log::warn!("Invalid AP_CHANNEL '{AP_CHANNEL}', using {DEFAULT_AP_CHANNEL}");
+            DEFAULT_AP_CHANNEL
+        }
+    }
+}
+
+/// Reads the max connections of the Access Point from [AP_MAX_CONNECTIONS],
+/// falling back to [DEFAULT_AP_MAX_CONNECTIONS] with a warning if it is not
+/// from `1` to `10`, the limit of the ESP32.
+fn ap_max_connections() -> u16 {
+    match AP_MAX_CONNECTIONS.parse::<u16>() {
+        Ok(max_connections) if (1..=10).contains(&max_connections) => max_connections,
+        _ => {
+            log::warn!(
+                "Invalid AP_MAX_CONNECTIONS '{AP_MAX_CONNECTIONS}', using {DEFAULT_AP_MAX_CONNECTIONS}"
+            );
+            DEFAULT_AP_MAX_CONNECTIONS
+        }
+    }
+}
+
+/// Reads how long the captive portal waits for credentials from
+/// [PROVISIONING_TIMEOUT_MINS], falling back to 10 minutes with a warning if
+/// it is not a number.
+///
+/// ## Returns
+/// The timeout, or `None` to wait forever.
+pub fn provisioning_timeout() -> Option<Duration> {
+    let minutes = PROVISIONING_TIMEOUT_MINS
+        .parse::<u64>()
+        .unwrap_or_else(|_| {
+            log::warn!("Invalid PROVISIONING_TIMEOUT_MINS '{PROVISIONING_TIMEOUT_MINS}', using 10");
+            10
+        });
+
+    (minutes > 0).then(|| Duration::from_secs(minutes * 60))
+}
+
 /// Starts the Wi-Fi Access Point and waits until the network interface is up.
 ///
 /// ## Parameters
@@ -133,3 +219,134 @@ pub fn start_wifi_ap(wifi: &mut BlockingWifi<EspWifi<'static>>) -> Result<(), Ap
 
     Ok(())
 }
+
+/// Tries the credentials received in the captive portal while the Access
+/// Point stays up, so the result can be reported before they are saved.
+///
+/// The station joins the network next to the Access Point, which then
+/// follows the channel of the router, so the phone showing the portal may
+/// briefly lose it. Once the attempt is done, the station is disconnected
+/// and the Access Point alone is configured again.
+///
+/// ## Arguments
+/// - `wifi` - The [BlockingWifi] instance running the Access Point.
+/// - `credentials` - The [WifiCredentials] of the network.
+/// - `hidden` - Whether the network doesn't broadcast its SSID.
+/// - `auth_mode` - The [AuthMode] of the network.
+/// - `enterprise` - The [EnterpriseConfig] of a WPA2-Enterprise network.
+/// - `static_ip` - The [StaticIpConfig] the station uses after the restart.
+///
+/// ## Returns
+/// - `Ok(Ipv4Addr)`: The IP address of the clock on the network, which is the
+///   static IP address if one is set.
+/// - `Err(AppError)`: If the network can't be joined. The reason is read with
+///   [get_disconnect_reason](super::get_disconnect_reason).
+///
+/// ## Example
+/// ```rust
+/// let ip = try_credentials(wifi, &credentials, false, AuthMode::Auto, None, None)?;
+/// ```
+pub fn try_credentials(
+    wifi: &mut BlockingWifi<EspWifi<'static>>,
+    credentials: &WifiCredentials,
+    hidden: bool,
+    auth_mode: AuthMode,
+    enterprise: Option<&EnterpriseConfig>,
+    static_ip: Option<&StaticIpConfig>,
+) -> Result<Ipv4Addr, AppError> {
+    let ap_configuration = wifi.get_configuration()?;
+    let Some(ap) = ap_configuration.as_ap_conf_ref() else {
+        return Err(AppError::Config("Access Point not configured".to_string()));
+    };
+
+    wifi.set_configuration(&WifiConfiguration::Mixed(
+        client_configuration(
+            &credentials.ssid,
+            &credentials.password,
+            hidden,
+            auth_mode,
+            enterprise.is_some(),
+        ),
+        ap.clone(),
+    ))?;
+
+    if let Some(enterprise) = enterprise {
+        apply_enterprise_config(enterprise, &credentials.password)?;
+    }
+
+    set_disconnect_reason(None);
+
+    let result = wifi
+        .connect()
+        .and_then(|_| wifi.wait_netif_up())
+        .map_err(AppError::from)
+        .and_then(|_| get_station_ip(wifi));
+
+    if result.is_err() {
+        wifi.disconnect().ok();
+
+        if enterprise.is_some() {
+            disable_enterprise()?;
+        }
+
+        wifi.set_configuration(&ap_configuration)?;
+    }
+
+    let ip = result?;
+    log::info!("Connected to {} with {ip}", credentials.ssid);
+
+    Ok(static_ip.map_or(ip, |static_ip| static_ip.ip))
+}
+
+/// Cycles `SETUP`, the SSID of the Access Point and its IP address on the
+/// displays until the captive portal received working Wi-Fi credentials, so
+/// someone looking at the clock knows which network to join to configure it.
+///
+/// Texts that don't fit the 12 digits of the group are scrolled. Runs until
+/// the network accepted the credentials, so it is meant to be spawned on its
+/// own thread.
+///
+/// ## Arguments
+/// - `display_group` - A [SharedDisplayGroup] instance.
+///
+/// ## Example
+/// ```rust
+/// std::thread::spawn(move || wifi::ap::run_setup_hint(display_group));
+/// ```
+pub fn run_setup_hint<CLK, DateDIO, YearDIO, HourDIO>(
+    display_group: SharedDisplayGroup<'static, CLK, DateDIO, YearDIO, HourDIO>,
+) where
+    CLK: OutputPin,
+    DateDIO: IOPin,
+    YearDIO: IOPin,
+    HourDIO: IOPin,
+{
+    let ap_ip_address = Ipv4Addr::from_str(AP_IP_ADDRESS).expect("Error reading AP_IP_ADDRESS");
+    let steps = [
+        "SETUP".to_string(),
+        AP_SSID.to_string(),
+        ip_display_text(ap_ip_address),
+    ];
+
+    while !matches!(
+        get_connection_attempt(),
+        ConnectionAttempt::Connected { .. }
+    ) {
+        for step in &steps {
+            let display_group = display_group.lock().unwrap();
+
+            let result = if step.chars().count() <= GROUP_DIGITS {
+                display_group.show_text(step)
+            } else {
+                display_group.scroll_text(step)
+            };
+
+            if let Err(e) = result {
+                log::error!("Failed to show the setup hint: {e:?}");
+            }
+
+            drop(display_group);
+            FreeRtos::delay_ms(SETUP_HINT_DELAY);
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use crate::error::AppError;
+use esp_idf_svc::sys::{
+    esp, esp_eap_client_set_ca_cert, esp_eap_client_set_identity, esp_eap_client_set_password,
+    esp_eap_client_set_ttls_phase2_method, esp_eap_client_set_username,
+    esp_eap_ttls_phase2_types_ESP_EAP_TTLS_PHASE2_MSCHAPV2, esp_wifi_sta_enterprise_disable,
+    esp_wifi_sta_enterprise_enable,
+};
+use serde::{Deserialize, Serialize};
+use std::ffi::CString;
+
+/// Max length of a PEM encoded CA certificate, which is stored as an NVS
+/// string.
+pub const MAX_CA_CERT_LEN: usize = 3072;
+
+/// The outer EAP method of a WPA2-Enterprise network.
+#[derive(Default, Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EapMethod {
+    /// Protected EAP, with MSCHAPv2 inside the tunnel.
+    #[default]
+    Peap,
+    /// Tunneled TLS, with MSCHAPv2 inside the tunnel.
+    Ttls,
+}
+
+/// Represents the credentials of a WPA2-Enterprise network, as used by
+/// university and office networks.
+///
+/// The password is kept in [WifiCredentials](super::WifiCredentials) together
+/// with the SSID.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnterpriseConfig {
+    #[serde(default)]
+    pub method: EapMethod,
+    /// The outer, usually anonymous, identity. The username is used when it
+    /// is empty.
+    #[serde(default)]
+    pub identity: String,
+    pub username: String,
+    /// An optional PEM encoded CA certificate used to validate the server.
+    /// The server isn't validated without it.
+    #[serde(default)]
+    pub ca_cert: Option<String>,
+}
+
+impl EnterpriseConfig {
+    /// Checks that a username is set and that the CA certificate, if any,
+    /// looks like a PEM certificate that fits in NVS.
+    pub fn is_valid(&self) -> bool {
+        if self.username.trim().is_empty() {
+            return false;
+        }
+
+        match &self.ca_cert {
+            Some(ca_cert) => {
+                ca_cert.len() <= MAX_CA_CERT_LEN && ca_cert.contains("-----BEGIN CERTIFICATE-----")
+            }
+            None => true,
+        }
+    }
+}
+
+/// Applies the WPA2-Enterprise credentials to the station through the
+/// `esp_eap_client` API.
+///
+/// Must be called after the station configuration is set and before it
+/// connects.
+///
+/// ## Arguments
+/// - `config` - The [EnterpriseConfig] of the network.
+/// - `password` - The password of the user.
+///
+/// ## Returns
+/// - `Ok(())`: If every credential was applied.
+/// - `Err(AppError)`: If a credential is rejected by the EAP client.
+///
+/// ## Example
+/// ```rust
+/// apply_enterprise_config(&config, &credentials.password)?;
+/// ```
+pub fn apply_enterprise_config(config: &EnterpriseConfig, password: &str) -> Result<(), AppError> {
+    let identity = if config.identity.trim().is_empty() {
+        &config.username
+    } else {
+        &config.identity
+    };
+
+    unsafe {
+        esp!(esp_eap_client_set_identity(
+            identity.as_ptr(),
+            identity.len() as i32
+        ))?;
+        esp!(esp_eap_client_set_username(
+            config.username.as_ptr(),
+            config.username.len() as i32
+        ))?;
+        esp!(esp_eap_client_set_password(
+            password.as_ptr(),
+            password.len() as i32
+        ))?;
+
+        if config.method == EapMethod::Ttls {
+            esp!(esp_eap_client_set_ttls_phase2_method(
+                esp_eap_ttls_phase2_types_ESP_EAP_TTLS_PHASE2_MSCHAPV2
+            ))?;
+        }
+
+        if let Some(ca_cert) = &config.ca_cert {
+            // The EAP client keeps the pointer, and mbedTLS needs the PEM
+            // NUL-terminated, including it in the length
+            let ca_cert = CString::new(ca_cert.as_str())
+                .map_err(|e| AppError::Config(format!("Invalid CA certificate: {e}")))?;
+            let ca_cert: &'static [u8] =
+                Box::leak(ca_cert.into_bytes_with_nul().into_boxed_slice());
+
+            esp!(esp_eap_client_set_ca_cert(
+                ca_cert.as_ptr(),
+                ca_cert.len() as i32
+            ))?;
+        } else {
+            log::warn!("No CA certificate set, the server won't be validated");
+        }
+
+        esp!(esp_wifi_sta_enterprise_enable())?;
+    }
+
+    log::info!("WPA2-Enterprise ({:?}) enabled", config.method);
+
+    Ok(())
+}
+
+/// Disables the EAP client enabled by [apply_enterprise_config], so the next
+/// network is joined with its password, e.g. after a failed attempt in the
+/// captive portal.
+pub fn disable_enterprise() -> Result<(), AppError> {
+    esp!(unsafe { esp_wifi_sta_enterprise_disable() })?;
+
+    Ok(())
+}
@@ -1,23 +1,71 @@
-use crate::error::AppError;
+use crate::{
+    error::AppError,
+    event::Event,
+    prefs::{self, wifi_power_save::WifiPowerSave},
+};
+use enterprise::EnterpriseConfig;
 use esp_idf_svc::{
     eventloop::EspSystemEventLoop,
     hal::{modem::WifiModemPeripheral, peripheral::Peripheral},
     nvs::EspDefaultNvsPartition,
-    wifi::WifiDriver,
+    sys::{
+        esp, esp_wifi_set_country_code, esp_wifi_set_ps,
+        wifi_err_reason_t_WIFI_REASON_4WAY_HANDSHAKE_TIMEOUT,
+        wifi_err_reason_t_WIFI_REASON_AUTH_FAIL, wifi_err_reason_t_WIFI_REASON_BEACON_TIMEOUT,
+        wifi_err_reason_t_WIFI_REASON_HANDSHAKE_TIMEOUT, wifi_err_reason_t_WIFI_REASON_NO_AP_FOUND,
+        wifi_err_reason_t_WIFI_REASON_NO_AP_FOUND_IN_AUTHMODE_THRESHOLD,
+        wifi_err_reason_t_WIFI_REASON_NO_AP_FOUND_IN_RSSI_THRESHOLD,
+        wifi_err_reason_t_WIFI_REASON_NO_AP_FOUND_W_COMPATIBLE_SECURITY,
+        wifi_ps_type_t_WIFI_PS_MAX_MODEM, wifi_ps_type_t_WIFI_PS_MIN_MODEM,
+        wifi_ps_type_t_WIFI_PS_NONE,
+    },
+    wifi::{AuthMethod, PmfConfiguration, WifiDriver},
 };
 use serde::{Deserialize, Serialize};
-use std::sync::{Arc, Mutex};
+use std::{
+    ffi::CString,
+    net::Ipv4Addr,
+    sync::{Arc, Mutex},
+};
 
 pub mod ap;
+pub mod enterprise;
+pub mod smartconfig;
 pub mod station;
+pub mod wps;
+
+/// The provisioning method used when no credentials are stored: `ap` for the
+/// captive portal or `smartconfig` for ESP-Touch.
+const PROVISIONING_MODE: &str = env!("PROVISIONING_MODE");
+
+/// The Wi-Fi country code used until one is saved, `01` being the world safe
+/// mode that only allows channels 1 to 11.
+pub const DEFAULT_COUNTRY_CODE: &str = env!("WIFI_COUNTRY_CODE");
 
 lazy_static::lazy_static! {
-    /// Global static reference for storing Wi-Fi credentials.
-    ///
-    /// This global reference uses `lazy_static` to initialize a `Arc<Mutex>` that holds an
-    /// `Option<WifiCredentials>`. It can be used across the application to store and retrieve
-    /// the Wi-Fi credentials in a thread-safe manner.
-    pub static ref WIFI_CREDENTIALS: Arc<Mutex<Option<WifiCredentials>>> = Arc::new(Mutex::new(None));
+    /// Global static reference for storing the optional static IP configuration
+    /// received together with the Wi-Fi credentials.
+    pub static ref STATIC_IP_CONFIG: Arc<Mutex<Option<StaticIpConfig>>> = Arc::new(Mutex::new(None));
+
+    /// Global static reference for whether the network received together with the Wi-Fi
+    /// credentials hides its SSID.
+    pub static ref HIDDEN_SSID: Arc<Mutex<bool>> = Arc::new(Mutex::new(false));
+
+    /// Global static reference for the security of the network received together with the
+    /// Wi-Fi credentials.
+    pub static ref AUTH_MODE: Arc<Mutex<AuthMode>> = Arc::new(Mutex::new(AuthMode::default()));
+
+    /// Global static reference for the optional WPA2-Enterprise credentials received together
+    /// with the Wi-Fi credentials.
+    pub static ref ENTERPRISE_CONFIG: Arc<Mutex<Option<EnterpriseConfig>>> = Arc::new(Mutex::new(None));
+
+    /// Global static reference for the reason of the last station disconnection, or of the
+    /// failure that sent the device back to provisioning.
+    pub static ref DISCONNECT_REASON: Arc<Mutex<Option<DisconnectReason>>> = Arc::new(Mutex::new(None));
+
+    /// Global static reference for the attempt to connect with the credentials received in
+    /// the captive portal.
+    pub static ref CONNECTION_ATTEMPT: Arc<Mutex<ConnectionAttempt>> = Arc::new(Mutex::new(ConnectionAttempt::Idle));
 }
 
 /// Represents Wi-Fi credentials.
@@ -27,6 +75,370 @@ pub struct WifiCredentials {
     pub password: String,
 }
 
+/// Represents a static IPv4 configuration for the station interface.
+///
+/// Used instead of DHCP when the network can't provide a reserved address.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaticIpConfig {
+    pub ip: Ipv4Addr,
+    pub gateway: Ipv4Addr,
+    pub mask: u8,
+    pub dns: Option<Ipv4Addr>,
+}
+
+impl StaticIpConfig {
+    /// Checks that the netmask is a valid prefix length and that the gateway
+    /// belongs to the same subnet as the IP address.
+    pub fn is_valid(&self) -> bool {
+        if self.mask == 0 || self.mask > 32 {
+            return false;
+        }
+
+        let netmask = u32::MAX << (32 - self.mask as u32);
+
+        u32::from(self.ip) & netmask == u32::from(self.gateway) & netmask
+    }
+}
+
+/// Represents a request to set or clear the static IP configuration.
+///
+/// A `null` (or missing) `static_ip` means the station goes back to DHCP.
+///
+/// ## Example
+/// ```rust
+/// let request: StaticIpRequest = serde_json::from_str(
+///     "{\"static_ip\":{\"ip\":\"192.168.1.50\",\"gateway\":\"192.168.1.1\",\"mask\":24,\"dns\":null}}",
+/// )
+/// .unwrap();
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct StaticIpRequest {
+    #[serde(default)]
+    pub static_ip: Option<StaticIpConfig>,
+}
+
+/// Represents the optional `hidden` flag sent together with the Wi-Fi
+/// credentials, for networks that don't broadcast their SSID.
+///
+/// ## Example
+/// ```rust
+/// let request: HiddenSsidRequest =
+///     serde_json::from_str("{\"ssid\":\"MyNetwork\",\"hidden\":true}").unwrap();
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct HiddenSsidRequest {
+    #[serde(default)]
+    pub hidden: bool,
+}
+
+/// Represents the security of the network to connect to, for networks that
+/// aren't WPA2-Personal.
+///
+/// The station accepts the selected security and any stronger one, e.g. a
+/// WPA2/WPA3 network is joined with [AuthMode::Wpa2].
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuthMode {
+    /// An open network without a password, WPA2-Personal or stronger
+    /// otherwise.
+    #[default]
+    Auto = 0,
+    /// An open network, without a password.
+    Open = 1,
+    /// WPA2-Personal, or stronger.
+    Wpa2 = 2,
+    /// WPA3-Personal only, which requires Protected Management Frames.
+    Wpa3 = 3,
+}
+
+/// Allows converting a u8 integer into an [`AuthMode`] enum.
+impl From<u8> for AuthMode {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => AuthMode::Auto,
+            1 => AuthMode::Open,
+            2 => AuthMode::Wpa2,
+            3 => AuthMode::Wpa3,
+            _ => AuthMode::default(),
+        }
+    }
+}
+
+impl AuthMode {
+    /// Returns the weakest [AuthMethod] the station accepts for the network.
+    ///
+    /// ## Arguments
+    /// - `password`: The password of the network, which tells an open network
+    ///   apart in [AuthMode::Auto].
+    pub fn auth_method(self, password: &str) -> AuthMethod {
+        match self {
+            AuthMode::Auto if password.is_empty() => AuthMethod::None,
+            AuthMode::Auto | AuthMode::Wpa2 => AuthMethod::WPA2Personal,
+            AuthMode::Open => AuthMethod::None,
+            AuthMode::Wpa3 => AuthMethod::WPA3Personal,
+        }
+    }
+
+    /// Returns the Protected Management Frames setting of the station, which
+    /// WPA3 requires and the other modes use when the router supports them.
+    pub fn pmf_configuration(self) -> PmfConfiguration {
+        PmfConfiguration::Capable {
+            required: self == AuthMode::Wpa3,
+        }
+    }
+}
+
+/// Represents the optional `auth_mode` sent together with the Wi-Fi
+/// credentials.
+///
+/// ## Example
+/// ```rust
+/// let request: AuthModeRequest =
+///     serde_json::from_str("{\"ssid\":\"MyNetwork\",\"auth_mode\":\"wpa3\"}").unwrap();
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct AuthModeRequest {
+    #[serde(default)]
+    pub auth_mode: AuthMode,
+}
+
+/// Represents the optional `enterprise` object sent together with the Wi-Fi
+/// credentials, for WPA2-Enterprise networks.
+///
+/// ## Example
+/// ```rust
+/// let request: EnterpriseRequest = serde_json::from_str(
+///     "{\"ssid\":\"eduroam\",\"enterprise\":{\"method\":\"peap\",\"identity\":\"\",\"username\":\"jdoe\",\"ca_cert\":null}}",
+/// )
+/// .unwrap();
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct EnterpriseRequest {
+    #[serde(default)]
+    pub enterprise: Option<EnterpriseConfig>,
+}
+
+/// Represents the network settings returned by the `/api/network` endpoint.
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkSettings {
+    pub static_ip: Option<StaticIpConfig>,
+    pub country_code: String,
+}
+
+/// Represents a request to set the Wi-Fi country code.
+///
+/// ## Example
+/// ```rust
+/// let request: CountryCodeRequest = serde_json::from_str("{\"country_code\":\"DE\"}").unwrap();
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct CountryCodeRequest {
+    pub country_code: String,
+}
+
+/// Returns `true` if the code is a two-letter ISO 3166-1 country code in
+/// uppercase, or `01` for the world safe mode.
+pub fn is_valid_country_code(code: &str) -> bool {
+    code == "01" || (code.len() == 2 && code.chars().all(|c| c.is_ascii_uppercase()))
+}
+
+/// Applies a country code to the Wi-Fi driver, which sets the allowed channels
+/// (e.g. 12 and 13 in the EU and Japan) and the TX power limits of the region.
+///
+/// The driver must be initialized, but it may already be started.
+///
+/// ## Arguments
+/// - `code`: A two-letter country code, e.g. `DE`, or `01`.
+///
+/// ## Returns
+/// - `Ok(())`: If the driver accepted the country code.
+/// - `Err(AppError)`: If the code is invalid or the driver rejected it.
+///
+/// ## Example
+/// ```rust
+/// wifi::set_country_code("JP")?;
+/// ```
+pub fn set_country_code(code: &str) -> Result<(), AppError> {
+    if !is_valid_country_code(code) {
+        return Err(AppError::Config(format!("Invalid country code: {code}")));
+    }
+
+    let code = CString::new(code).map_err(|e| AppError::Config(e.to_string()))?;
+    esp!(unsafe { esp_wifi_set_country_code(code.as_ptr(), true) })?;
+
+    log::info!("Wi-Fi country code set to {code:?}");
+
+    Ok(())
+}
+
+/// Sets how much the Wi-Fi radio sleeps while connected.
+///
+/// ## Arguments
+/// - `mode`: The [WifiPowerSave] to apply.
+///
+/// ## Returns
+/// - `Ok(())`: If the driver accepted the mode.
+/// - `Err(AppError)`: If the driver is not started.
+///
+/// ## Example
+/// ```rust
+/// wifi::set_power_save(WifiPowerSave::MaxModem)?;
+/// ```
+pub fn set_power_save(mode: WifiPowerSave) -> Result<(), AppError> {
+    let power_save = match mode {
+        WifiPowerSave::None => wifi_ps_type_t_WIFI_PS_NONE,
+        WifiPowerSave::MinModem => wifi_ps_type_t_WIFI_PS_MIN_MODEM,
+        WifiPowerSave::MaxModem => wifi_ps_type_t_WIFI_PS_MAX_MODEM,
+    };
+
+    esp!(unsafe { esp_wifi_set_ps(power_save) })?;
+
+    log::info!("Wi-Fi power save set to {mode:?}");
+
+    Ok(())
+}
+
+/// Applies the [WifiPowerSave] again once the station connects, see
+/// [subscribe](crate::event::subscribe).
+pub fn on_event(event: &Event) {
+    let mode = prefs::wifi_power_save::get_wifi_power_save();
+
+    if *event == Event::WifiConnected && mode != WifiPowerSave::default() {
+        set_power_save(mode)
+            .inspect_err(|e| log::error!("Failed to set the Wi-Fi power save: {e:?}"))
+            .ok();
+    }
+}
+
+/// Represents why the station lost, or could not establish, its connection.
+///
+/// Built from the reason code of the `StaDisconnected` Wi-Fi event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DisconnectReason {
+    /// The network rejected the password.
+    AuthFailed = 0,
+    /// No access point with the stored SSID was found.
+    NoApFound = 1,
+    /// The access point was found, but its signal is too weak.
+    WeakSignal = 2,
+    /// Any other reason, e.g. the router restarting.
+    Other = 3,
+}
+
+impl DisconnectReason {
+    /// Maps a `wifi_err_reason_t` code to a [DisconnectReason].
+    pub fn from_code(code: u16) -> Self {
+        match code as u32 {
+            wifi_err_reason_t_WIFI_REASON_AUTH_FAIL
+            | wifi_err_reason_t_WIFI_REASON_4WAY_HANDSHAKE_TIMEOUT
+            | wifi_err_reason_t_WIFI_REASON_HANDSHAKE_TIMEOUT => DisconnectReason::AuthFailed,
+            wifi_err_reason_t_WIFI_REASON_NO_AP_FOUND
+            | wifi_err_reason_t_WIFI_REASON_NO_AP_FOUND_W_COMPATIBLE_SECURITY
+            | wifi_err_reason_t_WIFI_REASON_NO_AP_FOUND_IN_AUTHMODE_THRESHOLD => {
+                DisconnectReason::NoApFound
+            }
+            wifi_err_reason_t_WIFI_REASON_BEACON_TIMEOUT
+            | wifi_err_reason_t_WIFI_REASON_NO_AP_FOUND_IN_RSSI_THRESHOLD => {
+                DisconnectReason::WeakSignal
+            }
+            _ => DisconnectReason::Other,
+        }
+    }
+
+    /// Returns the short text scrolled on the displays for this reason.
+    pub fn display_text(&self) -> &'static str {
+        match self {
+            DisconnectReason::AuthFailed => "PASS ERR",
+            DisconnectReason::NoApFound => "NO AP",
+            DisconnectReason::WeakSignal => "LO SIGNAL",
+            DisconnectReason::Other => "NET ERR",
+        }
+    }
+
+    /// Returns a description of the reason, used in the Wi-Fi health.
+    pub fn description(&self) -> &'static str {
+        match self {
+            DisconnectReason::AuthFailed => "The network rejected the password",
+            DisconnectReason::NoApFound => "The network was not found",
+            DisconnectReason::WeakSignal => "The signal of the network is too weak",
+            DisconnectReason::Other => "Disconnected from the network",
+        }
+    }
+}
+
+/// Allows converting a u8 integer into a [`DisconnectReason`] enum.
+impl From<u8> for DisconnectReason {
+    fn from(value: u8) -> Self {
+        match value {
+            0 => DisconnectReason::AuthFailed,
+            1 => DisconnectReason::NoApFound,
+            2 => DisconnectReason::WeakSignal,
+            _ => DisconnectReason::Other,
+        }
+    }
+}
+
+/// Represents the attempt to connect with the credentials received in the
+/// captive portal, which are only saved once it succeeds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+pub enum ConnectionAttempt {
+    /// No credentials were received yet.
+    Idle,
+    /// The credentials are being tried.
+    Connecting,
+    /// The network accepted the credentials, and the device restarts to join
+    /// it with the given IP address.
+    Connected { ip: Ipv4Addr },
+    /// The network could not be joined, so new credentials are awaited.
+    Failed { reason: DisconnectReason },
+}
+
+/// Represents the response of the captive portal `/status` endpoint.
+///
+/// ## Example
+/// ```json
+/// {"last_failure":"auth_failed","attempt":{"state":"connected","ip":"192.168.1.42"}}
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct ProvisioningStatus {
+    /// The failure that sent the device back to provisioning, if any.
+    pub last_failure: Option<DisconnectReason>,
+    /// The attempt to connect with the credentials of the captive portal.
+    pub attempt: ConnectionAttempt,
+}
+
+/// Retrieves the attempt to connect with the credentials of the captive
+/// portal in a thread-safe way.
+pub fn get_connection_attempt() -> ConnectionAttempt {
+    *CONNECTION_ATTEMPT.lock().unwrap()
+}
+
+/// Updates the attempt to connect with the credentials of the captive portal
+/// in a thread-safe way.
+pub fn set_connection_attempt(attempt: ConnectionAttempt) {
+    let mut attempt_guard = CONNECTION_ATTEMPT.lock().unwrap();
+    *attempt_guard = attempt;
+}
+
+/// Retrieves the reason of the last disconnection in a thread-safe way.
+pub fn get_disconnect_reason() -> Option<DisconnectReason> {
+    *DISCONNECT_REASON.lock().unwrap()
+}
+
+/// Updates the reason of the last disconnection in a thread-safe way.
+pub fn set_disconnect_reason(reason: Option<DisconnectReason>) {
+    let mut reason_guard = DISCONNECT_REASON.lock().unwrap();
+    *reason_guard = reason;
+}
+
+/// Returns `true` if the credentials are provisioned via SmartConfig
+/// (ESP-Touch) instead of the captive portal.
+pub fn use_smartconfig() -> bool {
+    PROVISIONING_MODE.eq_ignore_ascii_case("smartconfig")
+}
+
 /// Initializes a [`WifiDriver`] instance with the provided modem, event loop,
 /// and optional NVS partition.
 ///
@@ -0,0 +1,222 @@
+use super::{get_wifi, WifiCredentials};
+use crate::{error::AppError, state};
+use esp_idf_svc::{
+    eventloop::EspSystemEventLoop,
+    hal::{modem::WifiModemPeripheral, peripheral::Peripheral},
+    nvs::EspDefaultNvsPartition,
+    sys::{
+        esp, esp_event_base_t, esp_event_handler_register, esp_event_handler_unregister,
+        esp_smartconfig_set_type, esp_smartconfig_start, esp_smartconfig_stop,
+        smartconfig_event_got_ssid_pswd_t, smartconfig_event_t_SC_EVENT_GOT_SSID_PSWD,
+        smartconfig_event_t_SC_EVENT_SEND_ACK_DONE, smartconfig_start_config_t,
+        smartconfig_type_t_SC_TYPE_ESPTOUCH, ESP_EVENT_ANY_ID, SC_EVENT,
+    },
+    wifi::{
+        AuthMethod, BlockingWifi, ClientConfiguration, Configuration as WifiConfiguration, EspWifi,
+    },
+};
+use std::{
+    ffi::c_void,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+/// How long to wait for the phone to receive the ESP-Touch acknowledgement
+/// after connecting.
+const ACK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Set by the event handler once the acknowledgement was sent to the phone.
+static ACK_DONE: AtomicBool = AtomicBool::new(false);
+
+/// Creates a Wi-Fi station with an empty configuration, ready to receive
+/// credentials via SmartConfig.
+///
+/// ## Arguments
+/// - `modem`: The Wi-Fi modem peripheral to use.
+/// - `sysloop`: The system event loop for managing events.
+/// - `nvs`: Optional NVS partition for storing Wi-Fi settings.
+///
+/// ## Returns
+/// - `Ok(BlockingWifi<EspWifi<'d>>)`: A started Wi-Fi station that isn't
+///   connected to any network.
+/// - `Err(AppError)`: If the Wi-Fi driver fails to initialize or start.
+///
+/// ## Example
+/// ```rust
+/// let mut wifi = get_smartconfig_station(modem, sysloop, nvs)?;
+/// ```
+pub fn get_smartconfig_station<'d, M>(
+    modem: impl Peripheral<P = M> + 'd,
+    sysloop: EspSystemEventLoop,
+    nvs: Option<EspDefaultNvsPartition>,
+) -> Result<BlockingWifi<EspWifi<'d>>, AppError>
+where
+    M: WifiModemPeripheral,
+{
+    let wifi = get_wifi(modem, sysloop.clone(), nvs)?;
+    let mut wifi = EspWifi::wrap(wifi)?;
+    wifi.set_configuration(&WifiConfiguration::Client(ClientConfiguration::default()))?;
+
+    let mut wifi = BlockingWifi::wrap(wifi, sysloop)?;
+    wifi.start()?;
+    log::info!("Wifi started!");
+
+    Ok(wifi)
+}
+
+/// Runs ESP-Touch SmartConfig until credentials are received from the
+/// Espressif app and the station manages to connect with them.
+///
+/// ## Behavior
+/// - Stores the received credentials in the runtime
+///   [Settings](state::Settings), the same path used by the captive portal.
+/// - Connects to the received network so the app gets its acknowledgement.
+/// - If the connection fails, the credentials are discarded and SmartConfig
+///   keeps waiting.
+///
+/// ## Returns
+/// - `Ok(())`: When valid credentials are available in the runtime
+///   [Settings](state::Settings).
+/// - `Err(AppError)`: If SmartConfig can't be started.
+///
+/// ## Example
+/// ```rust
+/// wait_for_smartconfig_credentials(&mut wifi)?;
+/// ```
+pub fn wait_for_smartconfig_credentials(
+    wifi: &mut BlockingWifi<EspWifi<'static>>,
+) -> Result<(), AppError> {
+    unsafe {
+        esp!(esp_event_handler_register(
+            SC_EVENT,
+            ESP_EVENT_ANY_ID,
+            Some(smartconfig_event_handler),
+            std::ptr::null_mut(),
+        ))?;
+    }
+
+    loop {
+        start_smartconfig()?;
+        log::info!("Waiting for SmartConfig credentials...");
+
+        let credentials = loop {
+            if let Some(credentials) =
+                state::read_settings(|settings| settings.wifi_credentials.clone())
+            {
+                break credentials;
+            }
+            std::thread::sleep(Duration::from_millis(500));
+        };
+
+        log::info!(
+            "SmartConfig credentials received for SSID: {}",
+            credentials.ssid
+        );
+
+        match connect(wifi, &credentials) {
+            Ok(_) => {
+                let started = std::time::Instant::now();
+                while !ACK_DONE.load(Ordering::Relaxed) && started.elapsed() < ACK_TIMEOUT {
+                    std::thread::sleep(Duration::from_millis(100));
+                }
+
+                unsafe {
+                    esp_smartconfig_stop();
+                    esp_event_handler_unregister(
+                        SC_EVENT,
+                        ESP_EVENT_ANY_ID,
+                        Some(smartconfig_event_handler),
+                    );
+                }
+
+                log::info!("SmartConfig done!");
+                return Ok(());
+            }
+            Err(e) => {
+                log::error!("Failed to connect with SmartConfig credentials: {e:?}");
+                state::update_settings(|settings| settings.wifi_credentials.take());
+                wifi.disconnect().ok();
+
+                unsafe {
+                    esp_smartconfig_stop();
+                }
+            }
+        }
+    }
+}
+
+/// Starts ESP-Touch with the default configuration.
+fn start_smartconfig() -> Result<(), AppError> {
+    ACK_DONE.store(false, Ordering::Relaxed);
+
+    let config = smartconfig_start_config_t {
+        enable_log: false,
+        ..Default::default()
+    };
+
+    unsafe {
+        esp!(esp_smartconfig_set_type(
+            smartconfig_type_t_SC_TYPE_ESPTOUCH
+        ))?;
+        esp!(esp_smartconfig_start(&config))?;
+    }
+
+    Ok(())
+}
+
+/// Applies the received credentials to the station and connects.
+fn connect(
+    wifi: &mut BlockingWifi<EspWifi<'static>>,
+    credentials: &WifiCredentials,
+) -> Result<(), AppError> {
+    wifi.set_configuration(&WifiConfiguration::Client(ClientConfiguration {
+        ssid: credentials
+            .ssid
+            .as_str()
+            .try_into()
+            .map_err(|_| AppError::Config("Invalid Wi-Fi SSID".to_string()))?,
+        auth_method: AuthMethod::WPA2Personal,
+        password: credentials
+            .password
+            .as_str()
+            .try_into()
+            .map_err(|_| AppError::Config("Invalid Wi-Fi password".to_string()))?,
+        ..Default::default()
+    }))?;
+
+    wifi.connect()?;
+    wifi.wait_netif_up()?;
+    log::info!("Wifi connected!");
+
+    Ok(())
+}
+
+/// Receives the SmartConfig events from the default event loop.
+unsafe extern "C" fn smartconfig_event_handler(
+    _arg: *mut c_void,
+    event_base: esp_event_base_t,
+    event_id: i32,
+    event_data: *mut c_void,
+) {
+    if event_base != SC_EVENT {
+        return;
+    }
+
+    if event_id == smartconfig_event_t_SC_EVENT_GOT_SSID_PSWD as i32 {
+        let data = &*(event_data as *const smartconfig_event_got_ssid_pswd_t);
+
+        let credentials = WifiCredentials {
+            ssid: c_bytes_to_string(&data.ssid),
+            password: c_bytes_to_string(&data.password),
+        };
+        state::update_settings(|settings| settings.wifi_credentials = Some(credentials));
+    } else if event_id == smartconfig_event_t_SC_EVENT_SEND_ACK_DONE as i32 {
+        ACK_DONE.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Converts a NUL-padded C byte array into a `String`.
+fn c_bytes_to_string(bytes: &[u8]) -> String {
+    let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..len]).into_owned()
+}
@@ -1,15 +1,33 @@
-use super::get_wifi;
-use crate::{error::AppError, nvs::SharedAppStorage, service::app_storage::AppStorageWifiService};
+use super::{
+    enterprise::{apply_enterprise_config, EnterpriseConfig},
+    get_disconnect_reason, get_wifi, AuthMode, DisconnectReason, StaticIpConfig, WifiCredentials,
+};
+use crate::{
+    error::AppError,
+    health::{self, HealthState, Subsystem},
+};
 use esp_idf_svc::{
     eventloop::EspSystemEventLoop,
     hal::{modem::WifiModemPeripheral, peripheral::Peripheral},
+    ipv4::{self, ClientSettings, Mask, Subnet},
+    netif::{EspNetif, NetifConfiguration, NetifStack},
     nvs::EspDefaultNvsPartition,
-    sys::esp_restart,
+    sys::{esp, esp_wifi_connect},
     wifi::{
         AuthMethod, BlockingWifi, ClientConfiguration, Configuration as WifiConfiguration, EspWifi,
-        WifiDriver,
+        ScanMethod, ScanSortMethod, WifiDriver,
     },
 };
+use std::{net::Ipv4Addr, time::Duration};
+
+/// How many times the station tries to connect at boot before going offline.
+const WIFI_CONNECT_ATTEMPTS: &str = env!("WIFI_CONNECT_ATTEMPTS");
+
+/// Seconds to wait between two connection attempts at boot.
+const WIFI_RETRY_DELAY_SECS: &str = env!("WIFI_RETRY_DELAY_SECS");
+
+/// How often an offline station tries to reconnect.
+pub const RECONNECT_INTERVAL: Duration = Duration::from_secs(60);
 
 /// Initializes the Wi-Fi station and connects to the specified network.
 ///
@@ -17,8 +35,12 @@ use esp_idf_svc::{
 /// - `modem`: The Wi-Fi modem peripheral to use.
 /// - `sysloop`: The system event loop for managing events.
 /// - `nvs`: Optional NVS partition for storing Wi-Fi credentials.
-/// - `ssid`: The SSID of the Wi-Fi network to connect to.
-/// - `password`: The password for the Wi-Fi network.
+/// - `credentials`: The SSID and password of the Wi-Fi network to connect to.
+/// - `static_ip`: Optional static IP configuration. When `None`, DHCP is used.
+/// - `hidden`: `true` if the network doesn't broadcast its SSID.
+/// - `auth_mode`: The security of the network, see [AuthMode].
+/// - `enterprise`: Optional WPA2-Enterprise credentials. When set, the password
+///   is the one of the enterprise user.
 ///
 /// ## Returns
 /// - `Ok(BlockingWifi<EspWifi<'d>>)`: Returns a [`BlockingWifi`] instance on
@@ -29,26 +51,49 @@ use esp_idf_svc::{
 ///
 /// ## Example
 /// ```rust
-/// let ssid = "MyNetwork".to_string();
-/// let password = "MyPassword".to_string();
-/// let wifi = get_station(modem, sysloop, nvs, ssid, password);
+/// let credentials = WifiCredentials {
+///     ssid: "MyNetwork".to_string(),
+///     password: "MyPassword".to_string(),
+/// };
+/// let wifi = get_station(
+///     modem,
+///     sysloop,
+///     nvs,
+///     credentials,
+///     None,
+///     false,
+///     AuthMode::Auto,
+///     None,
+/// );
 /// match wifi {
 ///     Ok(wifi) => println!("Wi-Fi connected successfully!"),
 ///     Err(e) => eprintln!("Failed to connect to Wi-Fi: {e:?}"),
 /// }
 /// ```
+#[allow(clippy::too_many_arguments)]
 pub fn get_station<'d, M>(
     modem: impl Peripheral<P = M> + 'd,
     sysloop: EspSystemEventLoop,
     nvs: Option<EspDefaultNvsPartition>,
-    ssid: String,
-    password: String,
+    credentials: WifiCredentials,
+    static_ip: Option<StaticIpConfig>,
+    hidden: bool,
+    auth_mode: AuthMode,
+    enterprise: Option<EnterpriseConfig>,
 ) -> Result<BlockingWifi<EspWifi<'d>>, AppError>
 where
     M: WifiModemPeripheral,
 {
     let wifi = get_wifi(modem, sysloop.clone(), nvs)?;
-    let wifi = configure_station(wifi, ssid, password)?;
+    let wifi = configure_station(
+        wifi,
+        credentials.ssid,
+        credentials.password,
+        static_ip,
+        hidden,
+        auth_mode,
+        enterprise,
+    )?;
     let wifi = BlockingWifi::wrap(wifi, sysloop)?;
 
     Ok(wifi)
@@ -61,6 +106,15 @@ where
 /// - `wifi`: The `WifiDriver` instance to configure.
 /// - `ssid`: The SSID of the Wi-Fi network.
 /// - `password`: The password for the Wi-Fi network.
+/// - `static_ip`: Optional static IP configuration applied to the station
+///   netif. When `None`, the default DHCP client netif is used.
+/// - `hidden`: `true` if the network doesn't broadcast its SSID. Every channel
+///   is then probed for the SSID instead of stopping at the first beacon that
+///   matches, since a hidden access point only answers directed probes.
+/// - `auth_mode`: The security of the network, see [AuthMode]. Ignored for
+///   enterprise networks.
+/// - `enterprise`: Optional WPA2-Enterprise credentials, applied through the
+///   EAP client instead of setting `password` in the station configuration.
 ///
 /// ## Returns
 /// - `Ok(EspWifi)`: Returns a configured [`EspWifi`] instance on success. This
@@ -72,7 +126,15 @@ where
 /// let ssid = "MyNetwork".to_string();
 /// let password = "MyPassword".to_string();
 /// let wifi_driver = get_wifi_driver(); // Hypothetical function to get the WifiDriver instance
-/// match configure_station(wifi_driver, ssid, password) {
+/// match configure_station(
+///     wifi_driver,
+///     ssid,
+///     password,
+///     None,
+///     false,
+///     AuthMode::Auto,
+///     None,
+/// ) {
 ///     Ok(wifi) => println!("Wi-Fi configured successfully!"),
 ///     Err(e) => eprintln!("Failed to configure Wi-Fi: {:?}", e),
 /// }
@@ -81,74 +143,201 @@ fn configure_station(
     wifi: WifiDriver,
     ssid: String,
     password: String,
+    static_ip: Option<StaticIpConfig>,
+    hidden: bool,
+    auth_mode: AuthMode,
+    enterprise: Option<EnterpriseConfig>,
 ) -> Result<EspWifi, AppError> {
-    let mut wifi = EspWifi::wrap(wifi)?;
+    let mut wifi = match static_ip {
+        Some(static_ip) => {
+            log::info!("Using static IP configuration: {static_ip:?}");
 
-    let wifi_configuration = WifiConfiguration::Client(ClientConfiguration {
-        ssid: ssid.as_str().try_into().unwrap(),
-        bssid: None,
-        auth_method: AuthMethod::WPA2Personal,
-        password: password.as_str().try_into().unwrap(),
-        channel: None,
-        ..Default::default()
-    });
+            EspWifi::wrap_all(
+                wifi,
+                EspNetif::new_with_conf(&NetifConfiguration {
+                    ip_configuration: Some(ipv4::Configuration::Client(
+                        ipv4::ClientConfiguration::Fixed(ClientSettings {
+                            ip: static_ip.ip,
+                            subnet: Subnet {
+                                gateway: static_ip.gateway,
+                                mask: Mask(static_ip.mask),
+                            },
+                            dns: static_ip.dns.or(Some(static_ip.gateway)),
+                            secondary_dns: None,
+                        }),
+                    )),
+                    ..NetifConfiguration::wifi_default_client()
+                })?,
+                EspNetif::new(NetifStack::Ap)?,
+            )?
+        }
+        None => EspWifi::wrap(wifi)?,
+    };
+
+    let wifi_configuration = WifiConfiguration::Client(client_configuration(
+        &ssid,
+        &password,
+        hidden,
+        auth_mode,
+        enterprise.is_some(),
+    ));
     wifi.set_configuration(&wifi_configuration)?;
 
+    if let Some(enterprise) = enterprise {
+        apply_enterprise_config(&enterprise, &password)?;
+    }
+
     Ok(wifi)
 }
 
+/// Builds the station configuration of a network, see [configure_station]
+/// for the arguments.
+///
+/// `enterprise` tells whether the network is WPA2-Enterprise, whose password
+/// is applied through the EAP client instead.
+pub(super) fn client_configuration(
+    ssid: &str,
+    password: &str,
+    hidden: bool,
+    auth_mode: AuthMode,
+    enterprise: bool,
+) -> ClientConfiguration {
+    ClientConfiguration {
+        ssid: ssid.try_into().unwrap(),
+        bssid: None,
+        auth_method: if enterprise {
+            AuthMethod::WPA2Enterprise
+        } else {
+            auth_mode.auth_method(password)
+        },
+        password: if enterprise {
+            Default::default()
+        } else {
+            password.try_into().unwrap()
+        },
+        channel: None,
+        scan_method: if hidden {
+            ScanMethod::CompleteScan(ScanSortMethod::Signal)
+        } else {
+            ScanMethod::default()
+        },
+        pmf_cfg: auth_mode.pmf_configuration(),
+        ..Default::default()
+    }
+}
+
 /// Starts and connects to a Wi-Fi network using the provided Wi-Fi driver.
 ///
+/// The connection is attempted [WIFI_CONNECT_ATTEMPTS] times, waiting
+/// [WIFI_RETRY_DELAY_SECS] seconds between attempts. The stored credentials
+/// are kept when every attempt fails, as the router may just be restarting,
+/// and the clock goes on offline while [reconnect_job] keeps trying.
+///
+/// The attempts stop early when the network rejects the password, as retrying
+/// can't help. The reason of the last failure is read with
+/// [get_disconnect_reason], which relies on the Wi-Fi event subscription being
+/// active before this function is called.
+///
 /// ## Arguments
 /// - `wifi`: A mutable reference to the [BlockingWifi] driver that manages the
 ///   Wi-Fi connection.
-/// - `nvs`: A mutable reference to the NVS used to store Wi-Fi credentials.
 ///
 /// ## Returns
-/// This function will return an [`AppError`] if any of the following operations
-/// fail:
-/// - Starting or connecting the Wi-Fi.
-/// - Waiting for the network interface to come up.
-/// - Connecting to the Wi-Fi network.
+/// - `Ok(true)`: If the station is connected and its network interface is up.
+/// - `Ok(false)`: If every connection attempt failed.
+/// - `Err(AppError)`: If starting the Wi-Fi or waiting for the network
+///   interface fails.
 ///
 /// ## Example
 /// ```rust
 /// let mut wifi = ...; // A properly initialized wifi driver
-/// let mut nvs = ...;  // A properly initialized NVS
 ///
-/// connect_wifi(&mut wifi, &mut nvs)?;
+/// if !connect_wifi(&mut wifi)? {
+///     log::warn!("Running offline");
+/// }
 /// ```
-///
-/// ## Safety
-/// This function uses `unsafe` to restart the device if the connection process
-/// fails.
-pub fn connect_wifi_or_restart(
-    wifi: &mut BlockingWifi<EspWifi<'static>>,
-    storage: SharedAppStorage,
-) -> Result<(), AppError> {
+pub fn connect_wifi(wifi: &mut BlockingWifi<EspWifi<'static>>) -> Result<bool, AppError> {
     wifi.start()?;
     log::info!("Wifi started!");
 
-    match wifi.connect() {
-        Ok(_) => log::info!("Wifi connected!"),
-        Err(_) => {
-            log::error!("Failed to connect to Wi-Fi! Restarting...");
-            storage.lock().unwrap().delete_wifi_credentials()?;
-            wifi.stop()?;
-            unsafe {
-                esp_restart();
+    let attempts = WIFI_CONNECT_ATTEMPTS.parse::<u32>().unwrap_or(5).max(1);
+    let retry_delay = Duration::from_secs(WIFI_RETRY_DELAY_SECS.parse::<u64>().unwrap_or(5));
+
+    for attempt in 1..=attempts {
+        match wifi.connect() {
+            Ok(_) => {
+                log::info!("Wifi connected!");
+
+                wifi.wait_netif_up()?;
+                log::info!("Wifi netif up!");
+
+                return Ok(true);
             }
-        }
-    };
+            Err(e) => {
+                log::warn!("Failed to connect to Wi-Fi (attempt {attempt}/{attempts}): {e:?}");
 
-    wifi.wait_netif_up()?;
-    log::info!("Wifi netif up!");
+                if get_disconnect_reason() == Some(DisconnectReason::AuthFailed) {
+                    log::error!("The network rejected the password");
+                    break;
+                }
 
-    while !wifi.is_connected()? {
-        let config = wifi.get_configuration()?;
-        log::info!("Waiting for connection... {config:?}");
+                if attempt < attempts {
+                    std::thread::sleep(retry_delay);
+                }
+            }
+        }
     }
-    log::info!("Wifi done!");
 
-    Ok(())
+    log::error!("Could not connect to Wi-Fi, running offline");
+
+    Ok(false)
+}
+
+/// Returns the IP address assigned to the station by the network.
+///
+/// ## Example
+/// ```rust
+/// let ip = get_station_ip(&wifi)?;
+/// log::info!("Web portal at http://{ip}");
+/// ```
+pub fn get_station_ip(wifi: &BlockingWifi<EspWifi<'static>>) -> Result<Ipv4Addr, AppError> {
+    Ok(wifi.wifi().sta_netif().get_ip_info()?.ip)
+}
+
+/// Formats an IP address to be scrolled across the displays, e.g.
+/// `IP 192_168_1_42`.
+///
+/// The seven-segment font has no dot, so the octets are separated with the
+/// bottom segment instead.
+pub fn ip_display_text(ip: Ipv4Addr) -> String {
+    format!("IP {}", ip.to_string().replace('.', "_"))
+}
+
+/// Returns the job that reconnects the station while it is not connected,
+/// both when the boot connection failed and when the connection drops later.
+/// It is meant to run every [RECONNECT_INTERVAL].
+///
+/// The connection state is read from the Wi-Fi health, which is updated by the
+/// Wi-Fi event subscription.
+///
+/// ## Example
+/// ```rust
+/// scheduler.add(
+///     "wifi reconnect",
+///     Schedule::Every(wifi::station::RECONNECT_INTERVAL),
+///     wifi::station::reconnect_job(),
+/// );
+/// ```
+pub fn reconnect_job() -> impl FnMut() + Send {
+    move || {
+        if health::get_state(Subsystem::Wifi) == Some(HealthState::Ok) {
+            return;
+        }
+
+        log::info!("Wi-Fi offline, trying to reconnect...");
+
+        if let Err(e) = esp!(unsafe { esp_wifi_connect() }) {
+            log::warn!("Failed to start a Wi-Fi reconnection: {e:?}");
+        }
+    }
 }
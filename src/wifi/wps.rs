@@ -0,0 +1,243 @@
+use super::WifiCredentials;
+use crate::{
+    error::AppError, nvs::SharedAppStorage, service::app_storage::AppStorageWifiService, state,
+};
+use esp_idf_svc::{
+    sys::{
+        esp, esp_event_base_t, esp_event_handler_register, esp_event_handler_unregister,
+        esp_wifi_get_config, esp_wifi_wps_disable, esp_wifi_wps_enable, esp_wifi_wps_start,
+        esp_wps_config_t, wifi_config_t, wifi_event_sta_wps_er_success_t,
+        wifi_event_t_WIFI_EVENT_STA_WPS_ER_FAILED, wifi_event_t_WIFI_EVENT_STA_WPS_ER_SUCCESS,
+        wifi_event_t_WIFI_EVENT_STA_WPS_ER_TIMEOUT, wifi_interface_t_WIFI_IF_STA,
+        wps_type_t_WPS_TYPE_PBC, ESP_EVENT_ANY_ID, WIFI_EVENT,
+    },
+    wifi::{BlockingWifi, EspWifi},
+};
+use std::{
+    ffi::{c_char, c_void},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        PoisonError,
+    },
+    time::Duration,
+};
+
+#[cfg(feature = "wps")]
+use esp_idf_svc::{
+    hal::gpio::{AnyInputPin, PinDriver, Pull},
+    sys::esp_restart,
+};
+
+/// How long the button has to be held to start WPS.
+#[cfg(feature = "wps")]
+const LONG_PRESS: Duration = Duration::from_secs(3);
+
+/// How long the button has to be held to restore the factory settings
+/// instead.
+#[cfg(feature = "wps")]
+const RESET_PRESS: Duration = Duration::from_secs(10);
+
+/// How often the button is polled.
+#[cfg(feature = "wps")]
+const BUTTON_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Set by the event handler when the router didn't answer in time or the
+/// registration failed.
+static WPS_FAILED: AtomicBool = AtomicBool::new(false);
+
+/// Watches the BOOT button (`GPIO0` by default) and restarts into WPS
+/// provisioning when it is released after being held for [LONG_PRESS], or
+/// restores the factory settings when it is held for [RESET_PRESS], see
+/// [factory_reset](crate::config::factory_reset::factory_reset).
+///
+/// The WPS request is saved in NVS, so it survives the restart and is picked
+/// up by [take_wps_request] at boot. Runs forever, so it is meant to be
+/// spawned on its own thread.
+///
+/// ## Arguments
+/// - `pin` - The BOOT button pin, which is pulled low while pressed.
+/// - `storage` - A [SharedAppStorage] instance used to save the request, or to
+///   delete the settings.
+///
+/// ## Returns
+/// - `Err(AppError)`: If the pin can't be configured.
+#[cfg(feature = "wps")]
+pub fn run_wps_button(pin: AnyInputPin, storage: SharedAppStorage) -> Result<(), AppError> {
+    let mut button = PinDriver::input(pin)?;
+    button.set_pull(Pull::Up)?;
+
+    let mut pressed_for = Duration::ZERO;
+
+    loop {
+        std::thread::sleep(BUTTON_POLL_INTERVAL);
+
+        if button.is_low() {
+            pressed_for += BUTTON_POLL_INTERVAL;
+
+            if pressed_for >= RESET_PRESS {
+                log::warn!("WPS button held. Restoring the factory settings...");
+                crate::config::factory_reset::factory_reset(&storage)?;
+            }
+
+            continue;
+        }
+
+        let held_for = std::mem::take(&mut pressed_for);
+
+        if held_for >= LONG_PRESS {
+            log::warn!("WPS button released. Restarting into WPS...");
+            storage
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .save_wps_request()?;
+
+            unsafe {
+                esp_restart();
+            }
+        }
+    }
+}
+
+/// Returns `true` once if a long press of the WPS button requested WPS
+/// provisioning before the last restart, clearing the request.
+pub fn take_wps_request(storage: &SharedAppStorage) -> bool {
+    let mut storage = storage.lock().unwrap_or_else(PoisonError::into_inner);
+
+    match storage.get_maybe_wps_request() {
+        Ok(Some(true)) => {
+            storage.delete_wps_request().ok();
+            true
+        }
+        _ => false,
+    }
+}
+
+/// Runs WPS in push-button mode until the router sends its credentials.
+///
+/// The WPS button of the router has to be pressed within two minutes.
+///
+/// ## Behavior
+/// - Stores the received credentials in the runtime
+///   [Settings](state::Settings), the same path used by the captive portal and
+///   SmartConfig.
+/// - Gives up when the router doesn't answer in time or the registration fails,
+///   so the device falls back to the regular provisioning.
+///
+/// ## Arguments
+/// - `wifi` - A started station without a network, e.g. from
+///   [get_smartconfig_station](super::smartconfig::get_smartconfig_station).
+///
+/// ## Returns
+/// - `Ok(true)`: When credentials are available in the runtime
+///   [Settings](state::Settings).
+/// - `Ok(false)`: If WPS timed out or failed.
+/// - `Err(AppError)`: If WPS can't be started.
+///
+/// ## Example
+/// ```rust
+/// if !wait_for_wps_credentials(&mut wifi)? {
+///     log::warn!("WPS failed");
+/// }
+/// ```
+pub fn wait_for_wps_credentials(
+    _wifi: &mut BlockingWifi<EspWifi<'static>>,
+) -> Result<bool, AppError> {
+    WPS_FAILED.store(false, Ordering::Relaxed);
+
+    let mut config = esp_wps_config_t {
+        wps_type: wps_type_t_WPS_TYPE_PBC,
+        ..Default::default()
+    };
+    copy_c_str(&mut config.factory_info.manufacturer, "ESPRESSIF");
+    copy_c_str(&mut config.factory_info.model_number, "ESP32");
+    copy_c_str(&mut config.factory_info.model_name, "BTTF CLOCK");
+    copy_c_str(&mut config.factory_info.device_name, "BTTF CLOCK");
+
+    unsafe {
+        esp!(esp_event_handler_register(
+            WIFI_EVENT,
+            ESP_EVENT_ANY_ID,
+            Some(wps_event_handler),
+            std::ptr::null_mut(),
+        ))?;
+        esp!(esp_wifi_wps_enable(&config))?;
+        esp!(esp_wifi_wps_start(0))?;
+    }
+    log::info!("Waiting for WPS credentials...");
+
+    let received = loop {
+        if state::read_settings(|settings| settings.wifi_credentials.is_some()) {
+            break true;
+        }
+        if WPS_FAILED.load(Ordering::Relaxed) {
+            log::error!("WPS timed out or failed");
+            break false;
+        }
+        std::thread::sleep(Duration::from_millis(500));
+    };
+
+    unsafe {
+        esp_wifi_wps_disable();
+        esp_event_handler_unregister(WIFI_EVENT, ESP_EVENT_ANY_ID, Some(wps_event_handler));
+    }
+
+    Ok(received)
+}
+
+/// Receives the WPS events from the default event loop.
+unsafe extern "C" fn wps_event_handler(
+    _arg: *mut c_void,
+    event_base: esp_event_base_t,
+    event_id: i32,
+    event_data: *mut c_void,
+) {
+    if event_base != WIFI_EVENT {
+        return;
+    }
+
+    if event_id == wifi_event_t_WIFI_EVENT_STA_WPS_ER_SUCCESS as i32 {
+        let data = event_data as *const wifi_event_sta_wps_er_success_t;
+
+        let credentials = if !data.is_null() && (*data).ap_cred_cnt > 0 {
+            let ap_cred = &(*data).ap_cred[0];
+
+            WifiCredentials {
+                ssid: c_bytes_to_string(&ap_cred.ssid),
+                password: c_bytes_to_string(&ap_cred.passphrase),
+            }
+        } else {
+            // A single network is applied to the station configuration directly
+            let mut config = wifi_config_t::default();
+            esp_wifi_get_config(wifi_interface_t_WIFI_IF_STA, &mut config);
+
+            WifiCredentials {
+                ssid: c_bytes_to_string(&config.sta.ssid),
+                password: c_bytes_to_string(&config.sta.password),
+            }
+        };
+
+        log::info!("WPS credentials received for SSID: {}", credentials.ssid);
+        state::update_settings(|settings| settings.wifi_credentials = Some(credentials));
+    } else if event_id == wifi_event_t_WIFI_EVENT_STA_WPS_ER_FAILED as i32
+        || event_id == wifi_event_t_WIFI_EVENT_STA_WPS_ER_TIMEOUT as i32
+    {
+        WPS_FAILED.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Copies a string into a NUL-terminated C char array, truncating it if
+/// needed.
+fn copy_c_str(target: &mut [c_char], value: &str) {
+    let len = value.len().min(target.len() - 1);
+
+    for (target, byte) in target.iter_mut().zip(&value.as_bytes()[..len]) {
+        *target = *byte as c_char;
+    }
+    target[len] = 0;
+}
+
+/// Converts a NUL-padded C byte array into a `String`.
+fn c_bytes_to_string(bytes: &[u8]) -> String {
+    let len = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    String::from_utf8_lossy(&bytes[..len]).into_owned()
+}
@@ -1,5 +1,5 @@
 import "./style.css";
-import { connectWiFi } from "./wifi";
+import { connectWiFi, fetchProvisioningStatus } from "./wifi";
 
 const app = document.querySelector<HTMLDivElement>("#app");
 
@@ -9,6 +9,8 @@ if (app) {
     const connectWifiBtn = document.getElementById("connectWifiBtn");
     connectWifiBtn?.addEventListener("click", connectWiFi);
 
+    fetchProvisioningStatus();
+
     // Event listeners to clear error messages on input
     document.getElementById("ssidInput")?.addEventListener("input", () => {
         document.getElementById("ssidError")!.textContent = "";
@@ -24,6 +26,7 @@ function createHTMLContent(): string {
             <h1>BTTF CLOCK</h1>
 
             <h2>Wi-Fi Settings</h2>
+            <p id="statusMessage" class="error-message"></p>
             <div class="row">
                 <input
                     type="text"
@@ -34,6 +37,10 @@ function createHTMLContent(): string {
                     spellcheck="false"
                 />
                 <p id="ssidError" class="error-message"></p>
+                <label class="checkbox-label">
+                    <input type="checkbox" id="hiddenSsidInput" />
+                    Hidden network (type the SSID exactly)
+                </label>
             </div>
             <div class="row">
                 <input
@@ -44,10 +51,93 @@ function createHTMLContent(): string {
                     minlength="8"
                     maxlength="40"
                     spellcheck="false"
-                    required
                 />
                 <p id="passwordError" class="error-message"></p>
             </div>
+            <div class="row">
+                <select id="authModeSelect" title="Security of the network">
+                    <option value="auto">Auto (open without a password)</option>
+                    <option value="open">Open</option>
+                    <option value="wpa2">WPA2-Personal or WPA3</option>
+                    <option value="wpa3">WPA3-Personal only</option>
+                </select>
+            </div>
+
+            <h2>Enterprise Network (optional)</h2>
+            <div class="row">
+                <select id="eapMethodSelect">
+                    <option value="">WPA2-Personal (password only)</option>
+                    <option value="peap">WPA2-Enterprise (PEAP)</option>
+                    <option value="ttls">WPA2-Enterprise (TTLS)</option>
+                </select>
+            </div>
+            <div class="row">
+                <input
+                    type="text"
+                    id="eapUsernameInput"
+                    placeholder="Username"
+                    autocomplete="off"
+                    autocapitalize="off"
+                    spellcheck="false"
+                />
+            </div>
+            <div class="row">
+                <input
+                    type="text"
+                    id="eapIdentityInput"
+                    placeholder="Anonymous identity (optional)"
+                    autocomplete="off"
+                    autocapitalize="off"
+                    spellcheck="false"
+                />
+            </div>
+            <div class="row">
+                <label class="checkbox-label" for="eapCaCertInput">
+                    CA certificate (optional, PEM)
+                </label>
+                <input type="file" id="eapCaCertInput" accept=".pem,.crt,.cer" />
+                <p id="enterpriseError" class="error-message"></p>
+            </div>
+
+            <h2>Static IP (optional)</h2>
+            <div class="row">
+                <input
+                    type="text"
+                    id="staticIpInput"
+                    placeholder="IP address (leave empty for DHCP)"
+                    autocomplete="off"
+                    spellcheck="false"
+                />
+            </div>
+            <div class="row">
+                <input
+                    type="text"
+                    id="gatewayInput"
+                    placeholder="Gateway"
+                    autocomplete="off"
+                    spellcheck="false"
+                />
+            </div>
+            <div class="row">
+                <input
+                    type="number"
+                    id="maskInput"
+                    placeholder="Prefix length (e.g. 24)"
+                    min="1"
+                    max="32"
+                    autocomplete="off"
+                />
+            </div>
+            <div class="row">
+                <input
+                    type="text"
+                    id="dnsInput"
+                    placeholder="DNS (optional)"
+                    autocomplete="off"
+                    spellcheck="false"
+                />
+                <p id="staticIpError" class="error-message"></p>
+            </div>
             <div class="row">
                 <button id="connectWifiBtn">Connect</button>
             </div>
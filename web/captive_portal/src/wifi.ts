@@ -1,11 +1,143 @@
-export function connectWiFi(): void {
+interface StaticIpConfig {
+    ip: string;
+    gateway: string;
+    mask: number;
+    dns: string | null;
+}
+
+function readStaticIp(): StaticIpConfig | null | undefined {
+    const value = (id: string) =>
+        (document.getElementById(id) as HTMLInputElement).value.trim();
+
+    const ip = value("staticIpInput");
+    if (!ip) {
+        return null;
+    }
+
+    const gateway = value("gatewayInput");
+    const mask = Number(value("maskInput") || "24");
+    const dns = value("dnsInput");
+
+    if (!gateway || mask < 1 || mask > 32) {
+        return undefined;
+    }
+
+    return { ip, gateway, mask, dns: dns || null };
+}
+
+interface EnterpriseConfig {
+    method: string;
+    identity: string;
+    username: string;
+    ca_cert: string | null;
+}
+
+/** Max length of the PEM CA certificate accepted by the clock. */
+const MAX_CA_CERT_LENGTH = 3072;
+
+async function readEnterprise(): Promise<EnterpriseConfig | null> {
+    const value = (id: string) =>
+        (document.getElementById(id) as HTMLInputElement).value.trim();
+
+    const method = (document.getElementById("eapMethodSelect") as HTMLSelectElement)
+        .value;
+    if (!method) {
+        return null;
+    }
+
+    const file = (document.getElementById("eapCaCertInput") as HTMLInputElement)
+        .files?.[0];
+    const ca_cert = file ? (await file.text()).trim() : null;
+
+    return {
+        method,
+        identity: value("eapIdentityInput"),
+        username: value("eapUsernameInput"),
+        ca_cert,
+    };
+}
+
+const FAILURE_MESSAGES: Record<string, string> = {
+    auth_failed: "The network rejected the saved password. Please enter it again.",
+    no_ap_found: "The saved network was not found.",
+    weak_signal: "The signal of the saved network was too weak.",
+};
+
+const ATTEMPT_FAILURE_MESSAGES: Record<string, string> = {
+    auth_failed: "The network rejected the password. Please check it and try again.",
+    no_ap_found: "The network was not found. Please check the SSID.",
+    weak_signal: "The signal of the network is too weak. Move the clock closer to the router.",
+};
+
+/** How often the result of the connection attempt is polled, in milliseconds. */
+const ATTEMPT_POLL_INTERVAL = 2000;
+
+type ConnectionAttempt =
+    | { state: "idle" }
+    | { state: "connecting" }
+    | { state: "connected"; ip: string }
+    | { state: "failed"; reason: string };
+
+interface ProvisioningStatus {
+    last_failure: string | null;
+    attempt: ConnectionAttempt;
+}
+
+export function fetchProvisioningStatus(): void {
+    fetch("/status")
+        .then(response => response.json())
+        .then((status: ProvisioningStatus) => {
+            if (status.last_failure) {
+                document.getElementById("statusMessage")!.textContent =
+                    FAILURE_MESSAGES[status.last_failure] ??
+                    "Could not connect to the saved network.";
+            }
+        })
+        .catch(error => console.error("Error:", error));
+}
+
+function pollConnectionAttempt(): void {
+    const statusMessage = document.getElementById("statusMessage")!;
+    const connectWifiBtn = document.getElementById(
+        "connectWifiBtn"
+    ) as HTMLButtonElement;
+
+    fetch("/status")
+        .then(response => response.json())
+        .then((status: ProvisioningStatus) => {
+            const attempt = status.attempt;
+
+            if (attempt.state === "connected") {
+                statusMessage.className = "success-message";
+                statusMessage.textContent =
+                    `Connected! The clock restarts and will be at http://${attempt.ip}`;
+            } else if (attempt.state === "failed") {
+                statusMessage.className = "error-message";
+                statusMessage.textContent =
+                    ATTEMPT_FAILURE_MESSAGES[attempt.reason] ??
+                    "Could not connect to the network. Please try again.";
+                connectWifiBtn.disabled = false;
+            } else {
+                setTimeout(pollConnectionAttempt, ATTEMPT_POLL_INTERVAL);
+            }
+        })
+        // The Access Point may briefly drop while it follows the channel of the
+        // network, so keep polling
+        .catch(() => setTimeout(pollConnectionAttempt, ATTEMPT_POLL_INTERVAL));
+}
+
+export async function connectWiFi(): Promise<void> {
     const ssidInput = document.getElementById("ssidInput") as HTMLInputElement;
     const passwordInput = document.getElementById(
         "passwordInput"
     ) as HTMLInputElement;
 
     const ssid = ssidInput.value.trim();
+    const hidden = (document.getElementById("hiddenSsidInput") as HTMLInputElement)
+        .checked;
     const password = passwordInput.value.trim();
+    const auth_mode = (document.getElementById("authModeSelect") as HTMLSelectElement)
+        .value;
 
     const ssidError = document.getElementById(
         "ssidError"
@@ -18,36 +150,85 @@ export function connectWiFi(): void {
     ssidError.textContent = "";
     passwordError.textContent = "";
 
+    const staticIpError = document.getElementById(
+        "staticIpError"
+    ) as HTMLParagraphElement;
+    staticIpError.textContent = "";
+
+    const enterpriseError = document.getElementById(
+        "enterpriseError"
+    ) as HTMLParagraphElement;
+    enterpriseError.textContent = "";
+
     let hasError = false;
 
+    const enterprise = await readEnterprise();
+    if (enterprise && !enterprise.username) {
+        enterpriseError.textContent = "Enterprise networks require a username.";
+        hasError = true;
+    } else if (
+        enterprise?.ca_cert &&
+        (enterprise.ca_cert.length > MAX_CA_CERT_LENGTH ||
+            !enterprise.ca_cert.includes("-----BEGIN CERTIFICATE-----"))
+    ) {
+        enterpriseError.textContent =
+            "The CA certificate must be a single PEM certificate of up to 3 KB.";
+        hasError = true;
+    }
+
     if (!ssid) {
         ssidError.textContent = "SSID cannot be empty.";
         hasError = true;
     }
 
-    if (!password) {
+    // Open networks have no password, and "auto" treats an empty one as open
+    const passwordRequired = enterprise || auth_mode === "wpa2" || auth_mode === "wpa3";
+
+    if (!password && passwordRequired) {
         passwordError.textContent = "Password cannot be empty.";
         hasError = true;
-    } else if (password.length < 8) {
+    } else if (password && !enterprise && auth_mode !== "open" && password.length < 8) {
         passwordError.textContent =
             "Password must be at least 8 characters long.";
         hasError = true;
     }
 
+    const static_ip = readStaticIp();
+    if (static_ip === undefined) {
+        staticIpError.textContent =
+            "Static IP requires a gateway and a prefix length between 1 and 32.";
+        hasError = true;
+    }
+
     if (hasError) {
         return;
     }
 
+    const statusMessage = document.getElementById("statusMessage")!;
+    const connectWifiBtn = document.getElementById(
+        "connectWifiBtn"
+    ) as HTMLButtonElement;
+
+    statusMessage.className = "info-message";
+    statusMessage.textContent = `Connecting to ${ssid}…`;
+    connectWifiBtn.disabled = true;
+
     fetch("/set_config", {
         method: "POST",
         headers: {
             "Content-Type": "application/json",
         },
-        body: JSON.stringify({ ssid, password }),
+        body: JSON.stringify({ ssid, password, static_ip, hidden, auth_mode, enterprise }),
     })
-        .then((response) => response.json())
+        .then((response) => response.text())
         .then((data) => {
             console.log("Wi-Fi connection attempt response:", data);
+            setTimeout(pollConnectionAttempt, ATTEMPT_POLL_INTERVAL);
         })
-        .catch((error) => console.error("Error:", error));
+        .catch((error) => {
+            console.error("Error:", error);
+            statusMessage.className = "error-message";
+            statusMessage.textContent = "Could not send the settings. Please try again.";
+            connectWifiBtn.disabled = false;
+        });
 }
@@ -0,0 +1,36 @@
+const ADMIN_TOKEN_KEY = "adminToken";
+
+export function installAdminToken(): void {
+    const nativeFetch = window.fetch.bind(window);
+
+    window.fetch = (input: RequestInfo | URL, init: RequestInit = {}) => {
+        const token = localStorage.getItem(ADMIN_TOKEN_KEY);
+        const method = (init.method ?? "GET").toUpperCase();
+
+        if (token && method !== "GET") {
+            const headers = new Headers(init.headers);
+            headers.set("Authorization", `Bearer ${token}`);
+            init = { ...init, headers };
+        }
+
+        return nativeFetch(input, init);
+    };
+}
+
+export function saveAdminToken(): void {
+    const tokenInput = document.getElementById(
+        "adminTokenInput"
+    ) as HTMLInputElement;
+    const messageElement = document.getElementById("message") as HTMLElement;
+    const token = tokenInput.value.trim();
+
+    if (token) {
+        localStorage.setItem(ADMIN_TOKEN_KEY, token);
+    } else {
+        localStorage.removeItem(ADMIN_TOKEN_KEY);
+    }
+
+    tokenInput.value = "";
+    messageElement.innerText = token ? "Admin token saved" : "Admin token cleared";
+    messageElement.className = "message success";
+}
@@ -19,17 +19,230 @@ export function setDisplayBrightness(): void {
         return;
     }
 
-    fetch(`/set_brightness?${brightness}`, {
-        method: "GET",
+    const messageElement = document.getElementById("message") as HTMLElement;
+
+    fetch("/set_brightness", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({ brightness: brightness }),
     })
-        .then((response) => response.text())
+        .then(async (response) => {
+            if (!response.ok) {
+                const { error } = await response.json();
+                throw new Error(error);
+            }
+        })
         .then(() => {
-            const messageElement = document.getElementById(
-                "message"
-            ) as HTMLElement;
             messageElement.innerText = "Brightness set to " + brightness;
         })
         .catch((error) => {
             console.error("Error:", error);
+            messageElement.innerText = `Error: ${error.message}`;
+        });
+}
+
+export function setPhotoMode(): void {
+    const photoModeSwitch = document.getElementById(
+        "photoModeSwitch"
+    ) as HTMLInputElement;
+    const messageElement = document.getElementById("message") as HTMLElement;
+
+    fetch("/api/photo_mode", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({ photo_mode: photoModeSwitch.checked }),
+    })
+        .then((response) => {
+            if (!response.ok) {
+                throw new Error("Failed to set photo mode.");
+            }
+        })
+        .then(() => {
+            messageElement.innerText = photoModeSwitch.checked
+                ? "Photo mode enabled"
+                : "Photo mode disabled";
+            messageElement.className = "message success";
+        })
+        .catch((error) => {
+            console.error("Error:", error);
+            photoModeSwitch.checked = !photoModeSwitch.checked;
+            messageElement.innerText = "Error: Could not set photo mode.";
+            messageElement.className = "message error";
+        });
+}
+
+interface PowerReport {
+    low_power: boolean;
+    power_save: boolean;
+    display_ma: number;
+    led_strip_ma: number;
+    board_ma: number;
+    total_ma: number;
+}
+
+function showPowerReport(report: PowerReport): void {
+    const lowPowerSwitch = document.getElementById(
+        "lowPowerSwitch"
+    ) as HTMLInputElement;
+    const powerEstimate = document.getElementById("powerEstimate") as HTMLElement;
+
+    const powerSaveSwitch = document.getElementById(
+        "powerSaveSwitch"
+    ) as HTMLInputElement;
+
+    lowPowerSwitch.checked = report.low_power;
+    powerSaveSwitch.checked = report.power_save;
+    powerEstimate.innerText =
+        `~${report.total_ma.toFixed(0)} mA (displays ${report.display_ma.toFixed(0)}, ` +
+        `LEDs ${report.led_strip_ma.toFixed(0)}, board ${report.board_ma.toFixed(0)})`;
+}
+
+export function fetchPowerReport(): void {
+    fetch("/api/power")
+        .then((response) => response.json() as Promise<PowerReport>)
+        .then(showPowerReport)
+        .catch((error) => console.error("Error:", error));
+}
+
+export function setLowPower(): void {
+    const lowPowerSwitch = document.getElementById(
+        "lowPowerSwitch"
+    ) as HTMLInputElement;
+    const messageElement = document.getElementById("message") as HTMLElement;
+
+    fetch("/api/power", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({ low_power: lowPowerSwitch.checked }),
+    })
+        .then((response) => {
+            if (!response.ok) {
+                throw new Error("Failed to set low-power mode.");
+            }
+            return response.json() as Promise<PowerReport>;
+        })
+        .then((report) => {
+            showPowerReport(report);
+            messageElement.innerText = report.low_power
+                ? "Low-power mode enabled"
+                : "Low-power mode disabled";
+            messageElement.className = "message success";
+        })
+        .catch((error) => {
+            console.error("Error:", error);
+            lowPowerSwitch.checked = !lowPowerSwitch.checked;
+            messageElement.innerText = "Error: Could not set low-power mode.";
+            messageElement.className = "message error";
+        });
+}
+
+export function setPowerSave(): void {
+    const powerSaveSwitch = document.getElementById(
+        "powerSaveSwitch"
+    ) as HTMLInputElement;
+    const messageElement = document.getElementById("message") as HTMLElement;
+
+    fetch("/api/power", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({ power_save: powerSaveSwitch.checked }),
+    })
+        .then((response) => {
+            if (!response.ok) {
+                throw new Error("Failed to set power-save mode.");
+            }
+            return response.json() as Promise<PowerReport>;
+        })
+        .then((report) => {
+            showPowerReport(report);
+            messageElement.innerText = report.power_save
+                ? "Power-save mode enabled"
+                : "Power-save mode disabled";
+            messageElement.className = "message success";
+        })
+        .catch((error) => {
+            console.error("Error:", error);
+            powerSaveSwitch.checked = !powerSaveSwitch.checked;
+            messageElement.innerText = "Error: Could not set power-save mode.";
+            messageElement.className = "message error";
+        });
+}
+
+export function fetchAutoDim(): void {
+    fetch("/api/auto_dim")
+        .then((response) => response.json())
+        .then(
+            (autoDim: {
+                config: {
+                    location: { latitude: number; longitude: number } | null;
+                    night_brightness: number;
+                    night_led: number;
+                };
+                sunrise: string | null;
+                sunset: string | null;
+            }) => {
+                const location = autoDim.config.location;
+                (document.getElementById("autoDimLatitudeInput") as HTMLInputElement).value =
+                    location ? String(location.latitude) : "";
+                (document.getElementById("autoDimLongitudeInput") as HTMLInputElement).value =
+                    location ? String(location.longitude) : "";
+                (document.getElementById("nightBrightnessInput") as HTMLInputElement).value =
+                    String(autoDim.config.night_brightness);
+                (document.getElementById("nightLedInput") as HTMLInputElement).value =
+                    String(autoDim.config.night_led);
+                (document.getElementById("sunTimes") as HTMLElement).innerText =
+                    autoDim.sunrise && autoDim.sunset
+                        ? `Sunrise ${autoDim.sunrise}, sunset ${autoDim.sunset}`
+                        : "";
+            }
+        )
+        .catch((error) => console.error("Error:", error));
+}
+
+export function setAutoDim(): void {
+    const messageElement = document.getElementById("message") as HTMLElement;
+    const latitude = (
+        document.getElementById("autoDimLatitudeInput") as HTMLInputElement
+    ).value.trim();
+    const longitude = (
+        document.getElementById("autoDimLongitudeInput") as HTMLInputElement
+    ).value.trim();
+    const nightBrightness = Number(
+        (document.getElementById("nightBrightnessInput") as HTMLInputElement).value || "1"
+    );
+    const nightLed = Number(
+        (document.getElementById("nightLedInput") as HTMLInputElement).value || "30"
+    );
+    const location =
+        latitude && longitude
+            ? { latitude: Number(latitude), longitude: Number(longitude) }
+            : null;
+
+    fetch("/api/auto_dim", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({
+            location,
+            night_brightness: nightBrightness,
+            night_led: nightLed,
+        }),
+    })
+        .then(async (response) => {
+            if (!response.ok) {
+                const body = await response.json().catch(() => null);
+                throw new Error(body?.error ?? "Failed to set auto-dimming.");
+            }
+        })
+        .then(() => {
+            messageElement.innerText = location
+                ? "Dimming between sunset and sunrise"
+                : "Auto-dimming disabled";
+            messageElement.className = "message success";
+            fetchAutoDim();
+        })
+        .catch((error) => {
+            console.error("Error:", error);
+            messageElement.innerText = `Error: ${error.message}`;
+            messageElement.className = "message error";
         });
 }
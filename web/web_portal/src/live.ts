@@ -0,0 +1,69 @@
+interface LiveStatus {
+    time: string;
+    timezone: string;
+    theme: string;
+    wifi: string | null;
+    photo_mode: boolean;
+    temperature_c: number | null;
+    humidity: number | null;
+    battery: BatteryStatus | null;
+}
+
+interface BatteryStatus {
+    voltage_mv: number;
+    percent: number;
+    low: boolean;
+}
+
+const RECONNECT_DELAY = 5000;
+
+function renderLiveStatus(status: LiveStatus): void {
+    const set = (id: string, text: string) => {
+        const element = document.getElementById(id);
+        if (element) {
+            element.innerText = text;
+        }
+    };
+
+    set("liveTime", status.time);
+    set("liveTheme", status.theme);
+    set("liveWifi", status.wifi === "ok" ? "Connected" : "Disconnected");
+    set(
+        "liveTemperature",
+        status.temperature_c === null ? "No sensor" : `${status.temperature_c.toFixed(1)} °C`
+    );
+    set("liveHumidity", status.humidity === null ? "No sensor" : `${status.humidity.toFixed(0)} %`);
+    set(
+        "liveBattery",
+        status.battery === null
+            ? "No battery"
+            : `${status.battery.percent} % (${(status.battery.voltage_mv / 1000).toFixed(2)} V)${status.battery.low ? " - low" : ""}`
+    );
+
+    const photoModeSwitch = document.getElementById(
+        "photoModeSwitch"
+    ) as HTMLInputElement | null;
+    if (photoModeSwitch) {
+        photoModeSwitch.checked = status.photo_mode;
+    }
+}
+
+export function connectLiveStatus(): void {
+    const socket = new WebSocket(`ws://${window.location.host}/api/live`);
+
+    socket.addEventListener("message", (event: MessageEvent) => {
+        try {
+            renderLiveStatus(JSON.parse(event.data) as LiveStatus);
+        } catch (error) {
+            console.error("Invalid live status:", error);
+        }
+    });
+
+    socket.addEventListener("close", () => {
+        const wifiElement = document.getElementById("liveWifi");
+        if (wifiElement) {
+            wifiElement.innerText = "Clock unreachable";
+        }
+        setTimeout(connectLiveStatus, RECONNECT_DELAY);
+    });
+}
@@ -1,14 +1,88 @@
 import "./style.css";
-import { setDisplayBrightness } from "./display";
-import { populateTimezoneSelect, setTimezone, syncTime } from "./time";
-import { factoryReset, handlePowerModeChange } from "./sys";
-import { setTheme } from "./theme";
-import { setHourFormat } from "./prefs";
+import { installAdminToken, saveAdminToken } from "./auth";
+import {
+    fetchAutoDim,
+    fetchPowerReport,
+    setAutoDim,
+    setDisplayBrightness,
+    setLowPower,
+    setPowerSave,
+    setPhotoMode,
+} from "./display";
+import {
+    controlStopwatch,
+    populateTimezoneSelect,
+    setTimezone,
+    syncTime,
+} from "./time";
+import {
+    clearSnapshotUrl,
+    cloneFrom,
+    factoryReset,
+    handlePowerModeChange,
+    setSnapshotUrl,
+} from "./sys";
+import { populateThemeSelect, setTheme } from "./theme";
+import {
+    setAlternateInterval,
+    setAstroEvents,
+    setBootAnimation,
+    setBootLedEffect,
+    setColonBlink,
+    setCountdown,
+    setDateFormat,
+    setDigitTransition,
+    setDisplayFlip,
+    setDisplayRoles,
+    setDailySummary,
+    setDstNotice,
+    setHourFormat,
+    setHourStrike,
+    setHumidityDisplay,
+    setLeadingZero,
+    setMotionTimeout,
+    setSecondsMode,
+    setTemperatureDisplay,
+    setTouchAction,
+    setVolume,
+    setWakeLight,
+    setWeekdayMode,
+    setWifiPowerSave,
+} from "./prefs";
 import { fetchStatus } from "./status";
+import { connectLiveStatus } from "./live";
+import {
+    clearStaticIp,
+    fetchNetwork,
+    fetchNtpServer,
+    fetchPresence,
+    setCountryCode,
+    fetchWeather,
+    setNtpServer,
+    setPresence,
+    setStaticIp,
+    setWeather,
+} from "./network";
+import {
+    addCustomMessage,
+    fetchCustomMessages,
+    sendDisplayMessage,
+    setMessageInterval,
+} from "./messages";
+import { addTracker, fetchTrackers, setTrackerInterval } from "./trackers";
+import {
+    dryRunRules,
+    fetchMacros,
+    fetchRules,
+    runMacro,
+    saveMacros,
+    saveRules,
+} from "./rules";
 
 const app = document.querySelector<HTMLDivElement>("#app");
 
 if (app) {
+    installAdminToken();
     app.innerHTML = createHTMLContent();
 
     const setDisplayBrightnessBtn = document.getElementById(
@@ -19,9 +93,79 @@ if (app) {
     const syncTimeBtn = document.getElementById("syncTimeBtn");
     syncTimeBtn?.addEventListener("click", syncTime);
 
+    document
+        .getElementById("startStopwatchBtn")
+        ?.addEventListener("click", () => controlStopwatch("start"));
+    document
+        .getElementById("stopStopwatchBtn")
+        ?.addEventListener("click", () => controlStopwatch("stop"));
+    document
+        .getElementById("resetStopwatchBtn")
+        ?.addEventListener("click", () => controlStopwatch("reset"));
+
     const setTimezoneBtn = document.getElementById("setTimezoneBtn");
     setTimezoneBtn?.addEventListener("click", setTimezone);
 
+    const setStaticIpBtn = document.getElementById("setStaticIpBtn");
+    setStaticIpBtn?.addEventListener("click", setStaticIp);
+
+    const clearStaticIpBtn = document.getElementById("clearStaticIpBtn");
+    clearStaticIpBtn?.addEventListener("click", clearStaticIp);
+
+    const setCountryCodeBtn = document.getElementById("setCountryCodeBtn");
+    setCountryCodeBtn?.addEventListener("click", setCountryCode);
+    const setPresenceBtn = document.getElementById("setPresenceBtn");
+    setPresenceBtn?.addEventListener("click", setPresence);
+
+    const setWeatherBtn = document.getElementById("setWeatherBtn");
+    setWeatherBtn?.addEventListener("click", setWeather);
+
+    const setAutoDimBtn = document.getElementById("setAutoDimBtn");
+    setAutoDimBtn?.addEventListener("click", setAutoDim);
+
+    const setSnapshotUrlBtn = document.getElementById("setSnapshotUrlBtn");
+    setSnapshotUrlBtn?.addEventListener("click", setSnapshotUrl);
+
+    const clearSnapshotUrlBtn = document.getElementById("clearSnapshotUrlBtn");
+    clearSnapshotUrlBtn?.addEventListener("click", clearSnapshotUrl);
+
+    const addCustomMessageBtn = document.getElementById("addCustomMessageBtn");
+    addCustomMessageBtn?.addEventListener("click", addCustomMessage);
+
+    const setMessageIntervalBtn = document.getElementById(
+        "setMessageIntervalBtn"
+    );
+    setMessageIntervalBtn?.addEventListener("click", setMessageInterval);
+
+    const sendDisplayMessageBtn = document.getElementById("sendDisplayMessageBtn");
+    sendDisplayMessageBtn?.addEventListener("click", sendDisplayMessage);
+
+    const saveRulesBtn = document.getElementById("saveRulesBtn");
+    saveRulesBtn?.addEventListener("click", saveRules);
+
+    const dryRunRulesBtn = document.getElementById("dryRunRulesBtn");
+    dryRunRulesBtn?.addEventListener("click", dryRunRules);
+
+    const saveMacrosBtn = document.getElementById("saveMacrosBtn");
+    saveMacrosBtn?.addEventListener("click", saveMacros);
+
+    const runMacroBtn = document.getElementById("runMacroBtn");
+    runMacroBtn?.addEventListener("click", runMacro);
+
+    const addTrackerBtn = document.getElementById("addTrackerBtn");
+    addTrackerBtn?.addEventListener("click", addTracker);
+
+    const setTrackerIntervalBtn = document.getElementById(
+        "setTrackerIntervalBtn"
+    );
+    setTrackerIntervalBtn?.addEventListener("click", setTrackerInterval);
+
+    const saveAdminTokenBtn = document.getElementById("saveAdminTokenBtn");
+    saveAdminTokenBtn?.addEventListener("click", saveAdminToken);
+
+    const cloneFromBtn = document.getElementById("cloneFromBtn");
+    cloneFromBtn?.addEventListener("click", cloneFrom);
+
     const factoryResetBtn = document.getElementById("factoryResetBtn");
     factoryResetBtn?.addEventListener("click", factoryReset);
 
@@ -41,17 +185,114 @@ if (app) {
         "highPowerSwitch"
     ) as HTMLInputElement;
 
+    const photoModeSwitch = document.getElementById(
+        "photoModeSwitch"
+    ) as HTMLInputElement;
+
     brightnessInput.value = "";
     themeSelect.value = "original";
 
     themeSelect.addEventListener("change", setTheme);
+    document
+        .getElementById("bootAnimationSelect")
+        ?.addEventListener("change", setBootAnimation);
+    document
+        .getElementById("bootLedEffectSelect")
+        ?.addEventListener("change", setBootLedEffect);
     hourFormatSwitch.addEventListener("change", setHourFormat);
+    document
+        .getElementById("dateFormatSwitch")
+        ?.addEventListener("change", setDateFormat);
+    document
+        .getElementById("leadingZeroSwitch")
+        ?.addEventListener("change", setLeadingZero);
     highPowerSwitch.addEventListener("change", handlePowerModeChange);
+    photoModeSwitch.addEventListener("change", setPhotoMode);
+    document
+        .getElementById("lowPowerSwitch")
+        ?.addEventListener("change", setLowPower);
+    document
+        .getElementById("powerSaveSwitch")
+        ?.addEventListener("change", setPowerSave);
+    document
+        .getElementById("dstNoticeSwitch")
+        ?.addEventListener("change", setDstNotice);
+    document
+        .getElementById("astroEventsSwitch")
+        ?.addEventListener("change", setAstroEvents);
+    document
+        .getElementById("hourStrikeSwitch")
+        ?.addEventListener("change", setHourStrike);
+    document
+        .getElementById("colonBlinkSwitch")
+        ?.addEventListener("change", setColonBlink);
+    document
+        .getElementById("temperatureDisplaySwitch")
+        ?.addEventListener("change", setTemperatureDisplay);
+    document
+        .getElementById("humidityDisplaySwitch")
+        ?.addEventListener("change", setHumidityDisplay);
+    document
+        .getElementById("displayFlipSwitch")
+        ?.addEventListener("change", setDisplayFlip);
+    document
+        .getElementById("weekdayModeSelect")
+        ?.addEventListener("change", setWeekdayMode);
+    document
+        .getElementById("alternateIntervalSelect")
+        ?.addEventListener("change", setAlternateInterval);
+    document
+        .getElementById("secondsModeSelect")
+        ?.addEventListener("change", setSecondsMode);
+    document
+        .getElementById("wifiPowerSaveSelect")
+        ?.addEventListener("change", setWifiPowerSave);
+    document
+        .getElementById("ntpServerSwitch")
+        ?.addEventListener("change", setNtpServer);
+    document
+        .getElementById("motionTimeoutSelect")
+        ?.addEventListener("change", setMotionTimeout);
+    document
+        .getElementById("touchActionSelect")
+        ?.addEventListener("change", setTouchAction);
+    document
+        .getElementById("volumeSelect")
+        ?.addEventListener("change", setVolume);
+    (["date", "year", "hour"] as const).forEach((display) => {
+        document
+            .getElementById(`${display}TransitionSelect`)
+            ?.addEventListener("change", () => setDigitTransition(display));
+    });
+    document
+        .getElementById("setDisplayRolesBtn")
+        ?.addEventListener("click", setDisplayRoles);
+    document
+        .getElementById("setDailySummaryBtn")
+        ?.addEventListener("click", setDailySummary);
+    document
+        .getElementById("setCountdownBtn")
+        ?.addEventListener("click", setCountdown);
+    document
+        .getElementById("setWakeLightBtn")
+        ?.addEventListener("click", setWakeLight);
 
     populateTimezoneSelect();
+    populateThemeSelect();
+    fetchCustomMessages();
+    fetchNetwork();
+    fetchNtpServer();
+    fetchPresence();
+    fetchWeather();
+    fetchTrackers();
+    fetchRules();
+    fetchMacros();
+    fetchPowerReport();
+    fetchAutoDim();
 
     setInterval(fetchStatus, 30000);
     fetchStatus();
+    connectLiveStatus();
 }
 
 function createHTMLContent(): string {
@@ -82,6 +323,28 @@ function createHTMLContent(): string {
                     </label>
                     <span class="switch-label">24h</span>
                 </div>
+            </div>
+            <div class="row setting-row">
+                <span>Date Format</span>
+                <div class="switch-container">
+                    <span class="switch-label">DD.MM</span>
+                    <label class="switch">
+                        <input type="checkbox" id="dateFormatSwitch">
+                        <span class="slider"></span>
+                    </label>
+                    <span class="switch-label">MM.DD</span>
+                </div>
+            </div>
+            <div class="row setting-row">
+                <span>Leading Zero</span>
+                <div class="switch-container">
+                    <span class="switch-label"> 9:05</span>
+                    <label class="switch">
+                        <input type="checkbox" id="leadingZeroSwitch" checked>
+                        <span class="slider"></span>
+                    </label>
+                    <span class="switch-label">09:05</span>
+                </div>
             </div>
              <div class="row setting-row">
                 <span>High Power Mode</span>
@@ -95,6 +358,261 @@ function createHTMLContent(): string {
                 </div>
             </div>
 
+            <div class="row setting-row">
+                <span>Photo Mode</span>
+                <div class="switch-container">
+                    <span class="switch-label">Off</span>
+                    <label class="switch">
+                        <input type="checkbox" id="photoModeSwitch">
+                        <span class="slider"></span>
+                    </label>
+                    <span class="switch-label">On</span>
+                </div>
+            </div>
+
+            <div class="row setting-row">
+                <span>Low-Power Mode</span>
+                <div class="switch-container">
+                    <span class="switch-label">Off</span>
+                    <label class="switch">
+                        <input type="checkbox" id="lowPowerSwitch">
+                        <span class="slider"></span>
+                    </label>
+                    <span class="switch-label">On</span>
+                </div>
+            </div>
+
+            <div class="row setting-row">
+                <span>Power-Save Mode</span>
+                <div class="switch-container">
+                    <span class="switch-label">Off</span>
+                    <label class="switch">
+                        <input type="checkbox" id="powerSaveSwitch">
+                        <span class="slider"></span>
+                    </label>
+                    <span class="switch-label">On</span>
+                </div>
+            </div>
+            <p><strong>Estimated draw:</strong> <span id="powerEstimate">Loading...</span></p>
+
+            <div class="row setting-row">
+                <span>DST Reminder</span>
+                <div class="switch-container">
+                    <span class="switch-label">Off</span>
+                    <label class="switch">
+                        <input type="checkbox" id="dstNoticeSwitch" checked>
+                        <span class="slider"></span>
+                    </label>
+                    <span class="switch-label">On</span>
+                </div>
+            </div>
+
+            <div class="row setting-row">
+                <span>Moon &amp; Seasons</span>
+                <div class="switch-container">
+                    <span class="switch-label">Off</span>
+                    <label class="switch">
+                        <input type="checkbox" id="astroEventsSwitch">
+                        <span class="slider"></span>
+                    </label>
+                    <span class="switch-label">On</span>
+                </div>
+            </div>
+
+            <div class="row setting-row">
+                <span>Hour Strike Light</span>
+                <div class="switch-container">
+                    <span class="switch-label">Off</span>
+                    <label class="switch">
+                        <input type="checkbox" id="hourStrikeSwitch">
+                        <span class="slider"></span>
+                    </label>
+                    <span class="switch-label">On</span>
+                </div>
+            </div>
+            <div class="row setting-row">
+                <span>Blinking Colon</span>
+                <div class="switch-container">
+                    <span class="switch-label">Off</span>
+                    <label class="switch">
+                        <input type="checkbox" id="colonBlinkSwitch">
+                        <span class="slider"></span>
+                    </label>
+                    <span class="switch-label">On</span>
+                </div>
+            </div>
+            <div class="row setting-row">
+                <span>Room Temperature</span>
+                <div class="switch-container">
+                    <span class="switch-label">Off</span>
+                    <label class="switch">
+                        <input type="checkbox" id="temperatureDisplaySwitch">
+                        <span class="slider"></span>
+                    </label>
+                    <span class="switch-label">On</span>
+                </div>
+            </div>
+            <div class="row setting-row">
+                <span>Room Humidity</span>
+                <div class="switch-container">
+                    <span class="switch-label">Off</span>
+                    <label class="switch">
+                        <input type="checkbox" id="humidityDisplaySwitch">
+                        <span class="slider"></span>
+                    </label>
+                    <span class="switch-label">On</span>
+                </div>
+            </div>
+            <div class="row setting-row">
+                <span>Upside Down</span>
+                <div class="switch-container">
+                    <span class="switch-label">Off</span>
+                    <label class="switch">
+                        <input type="checkbox" id="displayFlipSwitch">
+                        <span class="slider"></span>
+                    </label>
+                    <span class="switch-label">On</span>
+                </div>
+            </div>
+
+            <h2>Auto-Dimming</h2>
+            <div class="row">
+                <input type="number" id="autoDimLatitudeInput" placeholder="Latitude (empty = off)" min="-90" max="90" step="0.01" />
+                <input type="number" id="autoDimLongitudeInput" placeholder="Longitude" min="-180" max="180" step="0.01" />
+            </div>
+            <div class="row">
+                <input type="number" id="nightBrightnessInput" placeholder="Night brightness (0-7)" min="0" max="7" />
+                <input type="number" id="nightLedInput" placeholder="Night LED strip (%)" min="0" max="100" />
+                <button id="setAutoDimBtn">Set Auto-Dimming</button>
+            </div>
+            <p id="sunTimes"></p>
+
+            <h2>Digit Transition</h2>
+            <div class="row">
+                <select id="dateTransitionSelect" title="Date">
+                    <option value="0">Instant</option>
+                    <option value="1">Blank</option>
+                    <option value="2">Roll</option>
+                </select>
+                <select id="yearTransitionSelect" title="Year">
+                    <option value="0">Instant</option>
+                    <option value="1">Blank</option>
+                    <option value="2">Roll</option>
+                </select>
+                <select id="hourTransitionSelect" title="Hour">
+                    <option value="0">Instant</option>
+                    <option value="1">Blank</option>
+                    <option value="2">Roll</option>
+                </select>
+            </div>
+
+            <h2>Display Order</h2>
+            <div class="row">
+                <select id="dateRoleSelect" title="Date">
+                    <option value="0" selected>1st Display (GPIO17)</option>
+                    <option value="1">2nd Display (GPIO19)</option>
+                    <option value="2">3rd Display (GPIO26)</option>
+                </select>
+                <select id="yearRoleSelect" title="Year">
+                    <option value="0">1st Display (GPIO17)</option>
+                    <option value="1" selected>2nd Display (GPIO19)</option>
+                    <option value="2">3rd Display (GPIO26)</option>
+                </select>
+                <select id="hourRoleSelect" title="Hour">
+                    <option value="0">1st Display (GPIO17)</option>
+                    <option value="1">2nd Display (GPIO19)</option>
+                    <option value="2" selected>3rd Display (GPIO26)</option>
+                </select>
+                <button id="setDisplayRolesBtn">Set Order</button>
+            </div>
+
+            <h2>Daily Summary</h2>
+            <div class="row">
+                <input type="time" id="dailySummaryInput" autocomplete="off" title="Leave empty to disable" />
+                <button id="setDailySummaryBtn">Set Time</button>
+            </div>
+
+            <h2>Wake Light</h2>
+            <div class="row">
+                <input type="time" id="wakeLightInput" autocomplete="off" title="Leave empty to disable" />
+                <select id="wakeLightDurationSelect" title="Sunrise duration">
+                    <option value="15">15 min</option>
+                    <option value="20" selected>20 min</option>
+                    <option value="30">30 min</option>
+                </select>
+                <button id="setWakeLightBtn">Set Wake Time</button>
+            </div>
+
+            <h2>Countdown</h2>
+            <div class="row">
+                <input type="date" id="countdownInput" autocomplete="off" title="Leave empty to disable" />
+                <button id="setCountdownBtn">Set Date</button>
+            </div>
+
+            <h2>Stopwatch</h2>
+            <div class="row">
+                <button id="startStopwatchBtn">Start</button>
+                <button id="stopStopwatchBtn">Stop</button>
+                <button id="resetStopwatchBtn">Reset</button>
+            </div>
+
+            <h2>Weekday</h2>
+            <div class="row">
+                <select id="weekdayModeSelect">
+                    <option value="0">Date Only</option>
+                    <option value="1">Weekday Instead of Date</option>
+                    <option value="2">Alternate Date and Weekday</option>
+                </select>
+                <select id="alternateIntervalSelect" title="Alternate every">
+                    <option value="2">Every 2s</option>
+                    <option value="4" selected>Every 4s</option>
+                    <option value="8">Every 8s</option>
+                    <option value="15">Every 15s</option>
+                    <option value="30">Every 30s</option>
+                    <option value="60">Every 60s</option>
+                </select>
+            </div>
+
+            <h2>Year Display</h2>
+            <div class="row">
+                <select id="secondsModeSelect">
+                    <option value="0">Year</option>
+                    <option value="1">Seconds</option>
+                    <option value="2">Minutes:Seconds</option>
+                </select>
+            </div>
+
+            <h2>Motion Sensor</h2>
+            <div class="row">
+                <select id="motionTimeoutSelect" title="Blank the clock at night after no motion for">
+                    <option value="30">After 30s</option>
+                    <option value="60">After 1 min</option>
+                    <option value="120" selected>After 2 min</option>
+                    <option value="300">After 5 min</option>
+                    <option value="900">After 15 min</option>
+                </select>
+            </div>
+
+            <h2>Touch Pad</h2>
+            <div class="row">
+                <select id="touchActionSelect" title="What a tap on the touch pad does">
+                    <option value="0">Off</option>
+                    <option value="1" selected>Night Mode</option>
+                    <option value="2">Time Travel</option>
+                </select>
+            </div>
+
+            <h2>Volume</h2>
+            <div class="row">
+                <select id="volumeSelect" title="Volume of the alarm and the theme">
+                    <option value="0">Muted</option>
+                    <option value="1">1</option>
+                    <option value="2" selected>2</option>
+                    <option value="3">3</option>
+                    <option value="4">4</option>
+                </select>
+            </div>
+
             <h2>Sync Time</h2>
             <div class="row">
                 <button id="syncTimeBtn">Sync Time with SNTP</button>
@@ -111,6 +629,21 @@ function createHTMLContent(): string {
                 </select>
             </div>
 
+            <h2>Boot Animation</h2>
+            <div class="row">
+                <select id="bootAnimationSelect">
+                    <option value="0">Init</option>
+                    <option value="1">Segment Cascade</option>
+                    <option value="2">BTTF Flicker</option>
+                    <option value="3">None</option>
+                </select>
+                <select id="bootLedEffectSelect">
+                    <option value="0">No LED Effect</option>
+                    <option value="1">Fade In</option>
+                    <option value="2">Sweep</option>
+                </select>
+            </div>
+
             <h2>Status</h2>
             <div id="status">
                 <p><strong>SSID:</strong> <span id="ssid">Loading...</span></p>
@@ -120,6 +653,14 @@ function createHTMLContent(): string {
                 </p>
                 <p><strong>Time:</strong> <span id="time">Loading...</span></p>
             </div>
+            <div id="liveStatus">
+                <p><strong>Clock:</strong> <span id="liveTime">--:--</span></p>
+                <p><strong>Theme:</strong> <span id="liveTheme">Loading...</span></p>
+                <p><strong>Wi-Fi:</strong> <span id="liveWifi">Loading...</span></p>
+                <p><strong>Temperature:</strong> <span id="liveTemperature">Loading...</span></p>
+                <p><strong>Humidity:</strong> <span id="liveHumidity">Loading...</span></p>
+                <p><strong>Battery:</strong> <span id="liveBattery">Loading...</span></p>
+            </div>
 
             <h2>Timezone</h2>
             <div class="row">
@@ -127,6 +668,154 @@ function createHTMLContent(): string {
                 <button id="setTimezoneBtn">Set Timezone</button>
             </div>
 
+            <h2>Custom Messages</h2>
+            <div id="customMessageList"></div>
+            <div class="row">
+                <input type="text" id="customMessageInput" placeholder="GARAGE" maxlength="24" autocomplete="off" />
+                <button id="addCustomMessageBtn">Add Message</button>
+            </div>
+            <div class="row">
+                <input type="number" id="messageIntervalInput" placeholder="Every N minutes (0 = off)" min="0" max="240" autocomplete="off" />
+                <button id="setMessageIntervalBtn">Set Interval</button>
+            </div>
+            <div class="row">
+                <input type="text" id="displayMessageInput" placeholder="DINNER IS READY" maxlength="64" autocomplete="off" />
+                <input type="number" id="displayMessageDurationInput" placeholder="Seconds (10)" min="1" max="300" autocomplete="off" />
+                <button id="sendDisplayMessageBtn">Show Now</button>
+            </div>
+
+            <h2>Days Since</h2>
+            <div id="trackerList"></div>
+            <div class="row">
+                <input type="text" id="trackerNameInput" placeholder="NO SMOKE" maxlength="16" autocomplete="off" />
+                <input type="date" id="trackerSinceInput" />
+                <button id="addTrackerBtn">Add Tracker</button>
+            </div>
+            <div class="row">
+                <input type="number" id="trackerIntervalInput" placeholder="Every N minutes (0 = off)" min="0" max="240" autocomplete="off" />
+                <button id="setTrackerIntervalBtn">Set Interval</button>
+            </div>
+
+            <h2>Rules</h2>
+            <div class="row">
+                <textarea id="rulesInput" spellcheck="false" placeholder='{"rules":[]}'></textarea>
+                <button id="saveRulesBtn">Save Rules</button>
+            </div>
+            <div class="row">
+                <input type="time" id="dryRunTimeInput" autocomplete="off" />
+                <select id="dryRunWeekdaySelect">
+                    <option value="">Today</option>
+                    <option value="0">Monday</option>
+                    <option value="1">Tuesday</option>
+                    <option value="2">Wednesday</option>
+                    <option value="3">Thursday</option>
+                    <option value="4">Friday</option>
+                    <option value="5">Saturday</option>
+                    <option value="6">Sunday</option>
+                </select>
+                <button id="dryRunRulesBtn">Dry Run</button>
+            </div>
+            <div class="row">
+                <textarea id="dryRunOutput" spellcheck="false" readonly placeholder="Dry run result"></textarea>
+            </div>
+
+            <h2>Macros</h2>
+            <div class="row">
+                <textarea id="macrosInput" spellcheck="false" placeholder='{"macros":[]}'></textarea>
+                <button id="saveMacrosBtn">Save Macros</button>
+            </div>
+            <div class="row">
+                <input type="text" id="runMacroInput" placeholder="Macro name" maxlength="16" autocomplete="off" />
+                <button id="runMacroBtn">Run Macro</button>
+            </div>
+
+            <h2>Static IP</h2>
+            <div class="row">
+                <input type="text" id="staticIpInput" placeholder="IP address" autocomplete="off" />
+                <input type="text" id="gatewayInput" placeholder="Gateway" autocomplete="off" />
+            </div>
+            <div class="row">
+                <input type="number" id="maskInput" placeholder="Prefix (24)" min="1" max="32" autocomplete="off" />
+                <input type="text" id="dnsInput" placeholder="DNS (optional)" autocomplete="off" />
+            </div>
+            <div class="row">
+                <button id="setStaticIpBtn">Save Static IP</button>
+                <button id="clearStaticIpBtn">Use DHCP</button>
+            </div>
+
+            <h2>Wi-Fi Region</h2>
+            <div class="row">
+                <input type="text" id="countryCodeInput" placeholder="Country code (e.g. DE, 01 = world)" maxlength="2" autocomplete="off" />
+                <button id="setCountryCodeBtn">Set Region</button>
+            </div>
+
+            <h2>Wi-Fi Power Save</h2>
+            <div class="row">
+                <select id="wifiPowerSaveSelect" title="How much the Wi-Fi radio sleeps">
+                    <option value="0">Off (Fastest Portal)</option>
+                    <option value="1" selected>Minimum (Default)</option>
+                    <option value="2">Maximum (Lowest Consumption)</option>
+                </select>
+            </div>
+
+            <h2>LAN Time Server</h2>
+            <div class="row setting-row">
+                <span>Serve NTP</span>
+                <div class="switch-container">
+                    <span class="switch-label">Off</span>
+                    <label class="switch">
+                        <input type="checkbox" id="ntpServerSwitch">
+                        <span class="slider"></span>
+                    </label>
+                    <span class="switch-label">On</span>
+                </div>
+            </div>
+            <p><strong>State:</strong> <span id="ntpServerState">Loading...</span></p>
+
+            <h2>Presence</h2>
+            <div class="row">
+                <input type="text" id="presenceIpInput" placeholder="Phone IP (empty = off)" autocomplete="off" />
+                <input type="number" id="awayAfterInput" placeholder="Away after (min)" min="1" max="1440" />
+                <button id="setPresenceBtn">Set Presence</button>
+            </div>
+
+            <h2>Weather</h2>
+            <div class="row">
+                <input type="number" id="latitudeInput" placeholder="Latitude (empty = off)" min="-90" max="90" step="0.01" />
+                <input type="number" id="longitudeInput" placeholder="Longitude" min="-180" max="180" step="0.01" />
+            </div>
+            <div class="row">
+                <input type="number" id="weatherIntervalInput" placeholder="Every (min)" min="1" max="1440" />
+                <select id="showWeatherSelect">
+                    <option value="0">Hidden</option>
+                    <option value="1">On date display</option>
+                </select>
+                <button id="setWeatherBtn">Set Weather</button>
+            </div>
+
+            <h2>Settings Snapshot</h2>
+            <div class="row">
+                <input type="text" id="snapshotUrlInput" placeholder="http://backup.local/bttf" autocomplete="off" />
+            </div>
+            <div class="row">
+                <button id="setSnapshotUrlBtn">Enable Nightly Snapshot</button>
+                <button id="clearSnapshotUrlBtn">Disable</button>
+            </div>
+            <p><a href="/api/config" download="bttf-config.json">Download current settings</a></p>
+
+            <h2>Admin Token</h2>
+            <div class="row">
+                <input type="password" id="adminTokenInput" placeholder="Admin token of this clock" autocomplete="off" />
+                <button id="saveAdminTokenBtn">Save Token</button>
+            </div>
+
+            <h2>Clone From Another Clock</h2>
+            <div class="row">
+                <input type="text" id="cloneHostInput" placeholder="bttf-2.local" autocomplete="off" />
+                <input type="password" id="cloneTokenInput" placeholder="Admin token (optional)" autocomplete="off" />
+                <button id="cloneFromBtn">Clone Settings</button>
+            </div>
+
             <h2>Factory Reset</h2>
             <div class="row">
                 <button id="factoryResetBtn">Restore Factory Settings</button>
@@ -0,0 +1,169 @@
+interface CustomMessages {
+    interval: number;
+    messages: string[];
+}
+
+function showMessage(text: string, className: string): void {
+    const messageElement = document.getElementById("message") as HTMLElement;
+    messageElement.innerText = text;
+    messageElement.className = `message ${className}`;
+}
+
+function renderMessages(data: CustomMessages): void {
+    const list = document.getElementById("customMessageList") as HTMLElement;
+    const intervalInput = document.getElementById(
+        "messageIntervalInput"
+    ) as HTMLInputElement;
+
+    intervalInput.value = String(data.interval);
+    list.innerHTML = "";
+
+    data.messages.forEach((text, index) => {
+        const row = document.createElement("div");
+        row.className = "row";
+
+        const label = document.createElement("span");
+        label.innerText = text;
+
+        const button = document.createElement("button");
+        button.innerText = "Delete";
+        button.addEventListener("click", () => deleteCustomMessage(index));
+
+        row.append(label, button);
+        list.append(row);
+    });
+}
+
+export function fetchCustomMessages(): void {
+    fetch("/api/messages", { method: "GET" })
+        .then((response) => response.json())
+        .then((data: CustomMessages) => renderMessages(data))
+        .catch((error) => {
+            console.error("Error fetching custom messages:", error);
+        });
+}
+
+export function addCustomMessage(): void {
+    const input = document.getElementById(
+        "customMessageInput"
+    ) as HTMLInputElement;
+    const text = input.value.trim();
+
+    if (!text || text.length > 24) {
+        alert("Messages must have between 1 and 24 characters.");
+        return;
+    }
+
+    fetch("/api/messages", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({ text }),
+    })
+        .then((response) => {
+            if (!response.ok) {
+                throw new Error("Failed to add message.");
+            }
+        })
+        .then(() => {
+            input.value = "";
+            showMessage("Message added", "success");
+            fetchCustomMessages();
+        })
+        .catch((error) => {
+            console.error("Error:", error);
+            showMessage("Error: Could not add message (max. 5).", "error");
+        });
+}
+
+function deleteCustomMessage(index: number): void {
+    fetch(`/api/messages?${index}`, { method: "DELETE" })
+        .then((response) => {
+            if (!response.ok) {
+                throw new Error("Failed to delete message.");
+            }
+        })
+        .then(() => {
+            showMessage("Message deleted", "success");
+            fetchCustomMessages();
+        })
+        .catch((error) => {
+            console.error("Error:", error);
+            showMessage("Error: Could not delete message.", "error");
+        });
+}
+
+export function setMessageInterval(): void {
+    const intervalInput = document.getElementById(
+        "messageIntervalInput"
+    ) as HTMLInputElement;
+    const interval = Number(intervalInput.value);
+
+    if (!Number.isInteger(interval) || interval < 0 || interval > 240) {
+        alert("The interval must be between 0 and 240 minutes.");
+        return;
+    }
+
+    fetch("/api/messages/interval", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({ interval: interval }),
+    })
+        .then(async (response) => {
+            if (!response.ok) {
+                const data = await response.json().catch(() => null);
+                throw new Error(data?.error ?? "Failed to set message interval.");
+            }
+        })
+        .then(() => {
+            showMessage(
+                interval === 0
+                    ? "Message rotation disabled"
+                    : `Messages will scroll every ${interval} minutes`,
+                "success"
+            );
+        })
+        .catch((error) => {
+            console.error("Error:", error);
+            showMessage("Error: Could not set message interval.", "error");
+        });
+}
+
+export function sendDisplayMessage(): void {
+    const textInput = document.getElementById(
+        "displayMessageInput"
+    ) as HTMLInputElement;
+    const durationInput = document.getElementById(
+        "displayMessageDurationInput"
+    ) as HTMLInputElement;
+    const text = textInput.value.trim();
+    const duration = Number(durationInput.value || "10");
+
+    if (!text || text.length > 64) {
+        alert("Messages must have between 1 and 64 characters.");
+        return;
+    }
+
+    if (!Number.isInteger(duration) || duration < 1 || duration > 300) {
+        alert("The duration must be between 1 and 300 seconds.");
+        return;
+    }
+
+    fetch("/display_message", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({ text, duration }),
+    })
+        .then((response) => {
+            if (!response.ok) {
+                throw new Error("Failed to show message.");
+            }
+        })
+        .then(() => {
+            textInput.value = "";
+            showMessage(`Message shown for ${duration} seconds`, "success");
+        })
+        .catch((error) => {
+            console.error("Error:", error);
+            showMessage("Error: Could not show message.", "error");
+        });
+}
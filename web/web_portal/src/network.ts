@@ -0,0 +1,260 @@
+function postStaticIp(body: object, successText: string): void {
+    const messageElement = document.getElementById("message") as HTMLElement;
+
+    fetch("/set_static_ip", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify(body),
+    })
+        .then((response) => {
+            if (!response.ok) {
+                throw new Error("Failed to update static IP.");
+            }
+        })
+        .then(() => {
+            messageElement.innerText = successText;
+            messageElement.className = "message success";
+        })
+        .catch((error) => {
+            console.error("Error:", error);
+            messageElement.innerText = "Error: Could not update static IP.";
+            messageElement.className = "message error";
+        });
+}
+
+export function setStaticIp(): void {
+    const value = (id: string) =>
+        (document.getElementById(id) as HTMLInputElement).value.trim();
+
+    const ip = value("staticIpInput");
+    const gateway = value("gatewayInput");
+    const mask = Number(value("maskInput") || "24");
+    const dns = value("dnsInput");
+
+    if (!ip || !gateway || mask < 1 || mask > 32) {
+        alert("Static IP requires an IP, a gateway and a prefix length (1-32).");
+        return;
+    }
+
+    postStaticIp(
+        { static_ip: { ip, gateway, mask, dns: dns || null } },
+        "Static IP saved. Restart the clock to apply."
+    );
+}
+
+export function clearStaticIp(): void {
+    postStaticIp(
+        { static_ip: null },
+        "Static IP cleared. The clock will use DHCP after a restart."
+    );
+}
+
+export function fetchNetwork(): void {
+    fetch("/api/network")
+        .then((response) => response.json())
+        .then((network: { country_code: string }) => {
+            (
+                document.getElementById("countryCodeInput") as HTMLInputElement
+            ).value = network.country_code;
+        })
+        .catch((error) => console.error("Error:", error));
+}
+
+export function setCountryCode(): void {
+    const messageElement = document.getElementById("message") as HTMLElement;
+    const countryCode = (
+        document.getElementById("countryCodeInput") as HTMLInputElement
+    ).value
+        .trim()
+        .toUpperCase();
+
+    fetch("/api/network", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({ country_code: countryCode }),
+    })
+        .then(async (response) => {
+            if (!response.ok) {
+                const body = await response.json().catch(() => null);
+                throw new Error(body?.error ?? "Failed to set country code.");
+            }
+        })
+        .then(() => {
+            messageElement.innerText = `Wi-Fi region set to ${countryCode}`;
+            messageElement.className = "message success";
+        })
+        .catch((error) => {
+            console.error("Error:", error);
+            messageElement.innerText = `Error: ${error.message}`;
+            messageElement.className = "message error";
+        });
+}
+
+interface NtpServerStatus {
+    enabled: boolean;
+    synced: boolean;
+    last_sync: number | null;
+    stratum: number;
+    answered: number;
+}
+
+function showNtpServerStatus(status: NtpServerStatus): void {
+    (document.getElementById("ntpServerSwitch") as HTMLInputElement).checked =
+        status.enabled;
+    (document.getElementById("ntpServerState") as HTMLElement).innerText =
+        !status.enabled
+            ? "Off"
+            : status.synced
+              ? `Serving stratum ${status.stratum} on UDP port 123, ${status.answered} requests answered`
+              : "Waiting for the first SNTP sync";
+}
+
+export function fetchNtpServer(): void {
+    fetch("/api/ntp_server")
+        .then((response) => response.json() as Promise<NtpServerStatus>)
+        .then(showNtpServerStatus)
+        .catch((error) => console.error("Error:", error));
+}
+
+export function setNtpServer(): void {
+    const ntpServerSwitch = document.getElementById(
+        "ntpServerSwitch"
+    ) as HTMLInputElement;
+    const messageElement = document.getElementById("message") as HTMLElement;
+
+    fetch("/api/ntp_server", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({ enabled: ntpServerSwitch.checked }),
+    })
+        .then((response) => {
+            if (!response.ok) {
+                throw new Error("Failed to set the LAN time server.");
+            }
+            return response.json() as Promise<NtpServerStatus>;
+        })
+        .then((status) => {
+            showNtpServerStatus(status);
+            messageElement.innerText = status.enabled
+                ? "LAN time server enabled"
+                : "LAN time server disabled";
+            messageElement.className = "message success";
+        })
+        .catch((error) => {
+            console.error("Error:", error);
+            ntpServerSwitch.checked = !ntpServerSwitch.checked;
+            messageElement.innerText = "Error: Could not set the LAN time server.";
+            messageElement.className = "message error";
+        });
+}
+
+export function fetchPresence(): void {
+    fetch("/api/presence")
+        .then((response) => response.json())
+        .then((presence: { config: { ip: string | null; away_after: number } }) => {
+            (document.getElementById("presenceIpInput") as HTMLInputElement).value =
+                presence.config.ip ?? "";
+            (document.getElementById("awayAfterInput") as HTMLInputElement).value =
+                String(presence.config.away_after);
+        })
+        .catch((error) => console.error("Error:", error));
+}
+
+export function setPresence(): void {
+    const messageElement = document.getElementById("message") as HTMLElement;
+    const ip = (
+        document.getElementById("presenceIpInput") as HTMLInputElement
+    ).value.trim();
+    const awayAfter = Number(
+        (document.getElementById("awayAfterInput") as HTMLInputElement).value || "15"
+    );
+
+    fetch("/api/presence", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({ ip: ip || null, away_after: awayAfter }),
+    })
+        .then(async (response) => {
+            if (!response.ok) {
+                const body = await response.json().catch(() => null);
+                throw new Error(body?.error ?? "Failed to set presence.");
+            }
+        })
+        .then(() => {
+            messageElement.innerText = ip
+                ? `Away mode after ${awayAfter} min without ${ip}`
+                : "Presence detection disabled";
+            messageElement.className = "message success";
+        })
+        .catch((error) => {
+            console.error("Error:", error);
+            messageElement.innerText = `Error: ${error.message}`;
+            messageElement.className = "message error";
+        });
+}
+
+export function fetchWeather(): void {
+    fetch("/api/weather")
+        .then((response) => response.json())
+        .then(
+            (weather: {
+                config: {
+                    location: { latitude: number; longitude: number } | null;
+                    interval: number;
+                    show: boolean;
+                };
+            }) => {
+                (document.getElementById("latitudeInput") as HTMLInputElement).value =
+                    weather.config.location ? String(weather.config.location.latitude) : "";
+                (document.getElementById("longitudeInput") as HTMLInputElement).value =
+                    weather.config.location ? String(weather.config.location.longitude) : "";
+                (document.getElementById("weatherIntervalInput") as HTMLInputElement).value =
+                    String(weather.config.interval);
+                (document.getElementById("showWeatherSelect") as HTMLSelectElement).value =
+                    weather.config.show ? "1" : "0";
+            }
+        )
+        .catch((error) => console.error("Error:", error));
+}
+
+export function setWeather(): void {
+    const messageElement = document.getElementById("message") as HTMLElement;
+    const latitude = (
+        document.getElementById("latitudeInput") as HTMLInputElement
+    ).value.trim();
+    const longitude = (
+        document.getElementById("longitudeInput") as HTMLInputElement
+    ).value.trim();
+    const interval = Number(
+        (document.getElementById("weatherIntervalInput") as HTMLInputElement).value || "30"
+    );
+    const show =
+        (document.getElementById("showWeatherSelect") as HTMLSelectElement).value === "1";
+    const location =
+        latitude && longitude
+            ? { latitude: Number(latitude), longitude: Number(longitude) }
+            : null;
+
+    fetch("/api/weather", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({ location, interval, show }),
+    })
+        .then(async (response) => {
+            if (!response.ok) {
+                const body = await response.json().catch(() => null);
+                throw new Error(body?.error ?? "Failed to set weather.");
+            }
+        })
+        .then(() => {
+            messageElement.innerText = location
+                ? `Outdoor temperature fetched every ${interval} min`
+                : "Weather disabled";
+            messageElement.className = "message success";
+        })
+        .catch((error) => {
+            console.error("Error:", error);
+            messageElement.innerText = `Error: ${error.message}`;
+            messageElement.className = "message error";
+        });
+}
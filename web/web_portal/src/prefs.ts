@@ -1,27 +1,331 @@
+function postPref(endpoint: string, body: object, label: string, success: string): void {
+    const messageElement = document.getElementById("message") as HTMLElement;
+
+    fetch(`/${endpoint}`, {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify(body),
+    })
+        .then(async (response) => {
+            if (!response.ok) {
+                const data = await response.json().catch(() => null);
+                throw new Error(data?.error ?? `Failed to set ${label}.`);
+            }
+        })
+        .then(() => {
+            messageElement.innerText = success;
+            messageElement.className = "message success";
+        })
+        .catch((error) => {
+            console.error("Error:", error);
+            messageElement.innerText = `Error: Could not set ${label}.`;
+            messageElement.className = "message error";
+        });
+}
+
 export function setHourFormat(): void {
     const hourFormatSwitch = document.getElementById(
         "hourFormatSwitch"
     ) as HTMLInputElement;
-    const messageElement = document.getElementById("message") as HTMLElement;
 
     const value = hourFormatSwitch.checked ? 1 : 0;
     const formatText = hourFormatSwitch.checked ? "24h" : "12h";
 
-    fetch(`/set_hour_format?${value}`, {
-        method: "GET",
+    postPref(
+        "set_hour_format",
+        { hour_format: value },
+        "hour format",
+        `Hour format set to ${formatText}`
+    );
+}
+
+export function setDateFormat(): void {
+    const dateFormatSwitch = document.getElementById(
+        "dateFormatSwitch"
+    ) as HTMLInputElement;
+
+    const value = dateFormatSwitch.checked ? 1 : 0;
+    const formatText = dateFormatSwitch.checked ? "MM.DD" : "DD.MM";
+
+    postPref(
+        "set_date_format",
+        { date_format: value },
+        "date format",
+        `Date format set to ${formatText}`
+    );
+}
+
+export function setBootAnimation(): void {
+    const select = document.getElementById("bootAnimationSelect") as HTMLSelectElement;
+    postPref(
+        "set_boot_animation",
+        { boot_animation: Number(select.value) },
+        "Boot animation",
+        "Boot animation updated"
+    );
+}
+
+export function setWeekdayMode(): void {
+    const select = document.getElementById("weekdayModeSelect") as HTMLSelectElement;
+    postPref(
+        "set_weekday_mode",
+        { weekday_mode: Number(select.value) },
+        "Weekday mode",
+        "Weekday mode updated"
+    );
+}
+
+export function setAlternateInterval(): void {
+    const select = document.getElementById("alternateIntervalSelect") as HTMLSelectElement;
+    postPref(
+        "set_alternate_interval",
+        { interval: Number(select.value) },
+        "Alternate interval",
+        "Alternate interval updated"
+    );
+}
+
+export function setSecondsMode(): void {
+    const select = document.getElementById("secondsModeSelect") as HTMLSelectElement;
+    postPref(
+        "set_seconds_mode",
+        { seconds_mode: Number(select.value) },
+        "Year display",
+        "Year display updated"
+    );
+}
+
+export function setWifiPowerSave(): void {
+    const select = document.getElementById("wifiPowerSaveSelect") as HTMLSelectElement;
+    postPref(
+        "set_wifi_power_save",
+        { wifi_power_save: Number(select.value) },
+        "Wi-Fi power save",
+        "Wi-Fi power save updated"
+    );
+}
+
+export function setMotionTimeout(): void {
+    const select = document.getElementById("motionTimeoutSelect") as HTMLSelectElement;
+    postPref(
+        "set_motion_timeout",
+        { timeout: Number(select.value) },
+        "Motion timeout",
+        "Motion timeout updated"
+    );
+}
+
+export function setTouchAction(): void {
+    const select = document.getElementById("touchActionSelect") as HTMLSelectElement;
+    postPref(
+        "set_touch_action",
+        { touch_action: Number(select.value) },
+        "Touch pad",
+        "Touch pad updated"
+    );
+}
+
+export function setVolume(): void {
+    const select = document.getElementById("volumeSelect") as HTMLSelectElement;
+    postPref(
+        "set_volume",
+        { volume: Number(select.value) },
+        "Volume",
+        "Volume updated"
+    );
+}
+
+export function setBootLedEffect(): void {
+    const select = document.getElementById("bootLedEffectSelect") as HTMLSelectElement;
+    postPref(
+        "set_boot_led_effect",
+        { boot_led_effect: Number(select.value) },
+        "Boot LED effect",
+        "Boot LED effect updated"
+    );
+}
+
+export function setDstNotice(): void {
+    const dstNoticeSwitch = document.getElementById(
+        "dstNoticeSwitch"
+    ) as HTMLInputElement;
+    postPref(
+        "set_dst_notice",
+        { dst_notice: dstNoticeSwitch.checked },
+        "DST reminder",
+        `DST reminder ${dstNoticeSwitch.checked ? "enabled" : "disabled"}`
+    );
+}
+
+export function setAstroEvents(): void {
+    const astroEventsSwitch = document.getElementById(
+        "astroEventsSwitch"
+    ) as HTMLInputElement;
+    postPref(
+        "set_astro_events",
+        { astro_events: astroEventsSwitch.checked },
+        "Astronomical events",
+        `Astronomical events ${astroEventsSwitch.checked ? "enabled" : "disabled"}`
+    );
+}
+
+export function setLeadingZero(): void {
+    const leadingZeroSwitch = document.getElementById(
+        "leadingZeroSwitch"
+    ) as HTMLInputElement;
+    postPref(
+        "set_leading_zero",
+        { leading_zero: leadingZeroSwitch.checked },
+        "Leading zero",
+        `Leading zero ${leadingZeroSwitch.checked ? "enabled" : "disabled"}`
+    );
+}
+
+export function setColonBlink(): void {
+    const colonBlinkSwitch = document.getElementById(
+        "colonBlinkSwitch"
+    ) as HTMLInputElement;
+    postPref(
+        "set_colon_blink",
+        { colon_blink: colonBlinkSwitch.checked },
+        "Blinking colon",
+        `Blinking colon ${colonBlinkSwitch.checked ? "enabled" : "disabled"}`
+    );
+}
+
+export function setTemperatureDisplay(): void {
+    const temperatureDisplaySwitch = document.getElementById(
+        "temperatureDisplaySwitch"
+    ) as HTMLInputElement;
+    postPref(
+        "set_temperature_display",
+        { temperature_display: temperatureDisplaySwitch.checked },
+        "Room temperature",
+        `Room temperature ${temperatureDisplaySwitch.checked ? "enabled" : "disabled"}`
+    );
+}
+
+export function setHumidityDisplay(): void {
+    const humidityDisplaySwitch = document.getElementById(
+        "humidityDisplaySwitch"
+    ) as HTMLInputElement;
+    postPref(
+        "set_humidity_display",
+        { humidity_display: humidityDisplaySwitch.checked },
+        "Room humidity",
+        `Room humidity ${humidityDisplaySwitch.checked ? "enabled" : "disabled"}`
+    );
+}
+
+export function setDisplayFlip(): void {
+    const displayFlipSwitch = document.getElementById(
+        "displayFlipSwitch"
+    ) as HTMLInputElement;
+    postPref(
+        "set_display_flip",
+        { display_flip: displayFlipSwitch.checked },
+        "Upside down",
+        `Upside down ${displayFlipSwitch.checked ? "enabled" : "disabled"}`
+    );
+}
+
+export function setHourStrike(): void {
+    const hourStrikeSwitch = document.getElementById(
+        "hourStrikeSwitch"
+    ) as HTMLInputElement;
+    postPref(
+        "set_hour_strike",
+        { hour_strike: hourStrikeSwitch.checked },
+        "Hour strike",
+        `Hour strike ${hourStrikeSwitch.checked ? "enabled" : "disabled"}`
+    );
+}
+
+export function setDigitTransition(display: "date" | "year" | "hour"): void {
+    const select = document.getElementById(
+        `${display}TransitionSelect`
+    ) as HTMLSelectElement;
+
+    postPref(
+        "set_digit_transition",
+        { [display]: Number(select.value) },
+        "digit transition",
+        `Digit transition of the ${display} display updated`
+    );
+}
+
+export function setDisplayRoles(): void {
+    const [date, year, hour] = (["date", "year", "hour"] as const).map(
+        (role) =>
+            (document.getElementById(`${role}RoleSelect`) as HTMLSelectElement)
+                .value
+    );
+    const messageElement = document.getElementById("message") as HTMLElement;
+
+    fetch("/set_display_roles", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({
+            date: Number(date),
+            year: Number(year),
+            hour: Number(hour),
+        }),
     })
-        .then(response => {
+        .then((response) => {
             if (!response.ok) {
-                throw new Error('Failed to set hour format.');
+                throw new Error("Failed to set display order.");
             }
         })
         .then(() => {
-            messageElement.innerText = `Hour format set to ${formatText}`;
+            messageElement.innerText = "Display order updated";
             messageElement.className = "message success";
         })
-        .catch(error => {
+        .catch((error) => {
             console.error("Error:", error);
-            messageElement.innerText = "Error: Could not set hour format.";
+            messageElement.innerText =
+                "Error: Could not set display order. Every role needs its own display.";
             messageElement.className = "message error";
         });
 }
+
+export function setWakeLight(): void {
+    const time = (
+        document.getElementById("wakeLightInput") as HTMLInputElement
+    ).value;
+    const duration = (
+        document.getElementById("wakeLightDurationSelect") as HTMLSelectElement
+    ).value;
+
+    postPref(
+        "set_wake_light",
+        { time: time || null, duration: Number(duration) },
+        "wake light",
+        time ? `Sunrise of ${duration} min before ${time}` : "Wake light disabled"
+    );
+}
+
+export function setCountdown(): void {
+    const date = (
+        document.getElementById("countdownInput") as HTMLInputElement
+    ).value;
+
+    postPref(
+        "set_countdown",
+        { date: date || null },
+        "countdown",
+        date ? `Counting down to ${date}` : "Countdown disabled"
+    );
+}
+
+export function setDailySummary(): void {
+    const time = (
+        document.getElementById("dailySummaryInput") as HTMLInputElement
+    ).value;
+
+    postPref(
+        "set_daily_summary",
+        { time: time || null },
+        "daily summary",
+        time ? `Daily summary set to ${time}` : "Daily summary disabled"
+    );
+}
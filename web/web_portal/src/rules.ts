@@ -0,0 +1,156 @@
+function showMessage(text: string, className: string): void {
+    const messageElement = document.getElementById("message") as HTMLElement;
+    messageElement.innerText = text;
+    messageElement.className = `message ${className}`;
+}
+
+export function fetchRules(): void {
+    fetch("/api/rules", { method: "GET" })
+        .then((response) => response.json())
+        .then((data) => {
+            const input = document.getElementById(
+                "rulesInput"
+            ) as HTMLTextAreaElement;
+            input.value = JSON.stringify(data, null, 2);
+        })
+        .catch((error) => {
+            console.error("Error fetching rules:", error);
+        });
+}
+
+export function saveRules(): void {
+    const input = document.getElementById("rulesInput") as HTMLTextAreaElement;
+
+    let rules: unknown;
+    try {
+        rules = JSON.parse(input.value);
+    } catch {
+        alert("The rules must be valid JSON.");
+        return;
+    }
+
+    fetch("/api/rules", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify(rules),
+    })
+        .then(async (response) => {
+            if (!response.ok) {
+                const data = await response.json().catch(() => null);
+                throw new Error(data?.error ?? "Failed to save rules.");
+            }
+        })
+        .then(() => {
+            showMessage("Rules saved", "success");
+            fetchRules();
+        })
+        .catch((error) => {
+            console.error("Error:", error);
+            showMessage(`Error: ${error.message}`, "error");
+        });
+}
+
+export function dryRunRules(): void {
+    const time = (document.getElementById("dryRunTimeInput") as HTMLInputElement)
+        .value;
+    const weekday = (
+        document.getElementById("dryRunWeekdaySelect") as HTMLSelectElement
+    ).value;
+
+    const params = new URLSearchParams();
+    if (time) params.set("time", time);
+    if (weekday) params.set("weekday", weekday);
+
+    fetch(`/api/rules/dry_run?${params.toString()}`, { method: "GET" })
+        .then(async (response) => {
+            const data = await response.json().catch(() => null);
+            if (!response.ok) {
+                throw new Error(data?.error ?? "Failed to evaluate the rules.");
+            }
+            return data;
+        })
+        .then((data) => {
+            const output = document.getElementById(
+                "dryRunOutput"
+            ) as HTMLTextAreaElement;
+            output.value = JSON.stringify(data, null, 2);
+        })
+        .catch((error) => {
+            console.error("Error:", error);
+            showMessage(`Error: ${error.message}`, "error");
+        });
+}
+
+export function fetchMacros(): void {
+    fetch("/api/macros", { method: "GET" })
+        .then((response) => response.json())
+        .then((data) => {
+            const input = document.getElementById(
+                "macrosInput"
+            ) as HTMLTextAreaElement;
+            input.value = JSON.stringify(data, null, 2);
+        })
+        .catch((error) => {
+            console.error("Error fetching macros:", error);
+        });
+}
+
+export function saveMacros(): void {
+    const input = document.getElementById("macrosInput") as HTMLTextAreaElement;
+
+    let macros: unknown;
+    try {
+        macros = JSON.parse(input.value);
+    } catch {
+        alert("The macros must be valid JSON.");
+        return;
+    }
+
+    fetch("/api/macros", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify(macros),
+    })
+        .then(async (response) => {
+            if (!response.ok) {
+                const data = await response.json().catch(() => null);
+                throw new Error(data?.error ?? "Failed to save macros.");
+            }
+        })
+        .then(() => {
+            showMessage("Macros saved", "success");
+            fetchMacros();
+        })
+        .catch((error) => {
+            console.error("Error:", error);
+            showMessage(`Error: ${error.message}`, "error");
+        });
+}
+
+export function runMacro(): void {
+    const name = (
+        document.getElementById("runMacroInput") as HTMLInputElement
+    ).value.trim();
+
+    if (!name) {
+        alert("Please enter a macro name.");
+        return;
+    }
+
+    fetch("/api/macros/run", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({ name: name }),
+    })
+        .then(async (response) => {
+            if (!response.ok) {
+                const data = await response.json().catch(() => null);
+                throw new Error(data?.error ?? "Failed to run the macro.");
+            }
+        })
+        .then(() => showMessage(`Macro '${name}' run`, "success"))
+        .catch((error) => {
+            console.error("Error:", error);
+            showMessage(`Error: ${error.message}`, "error");
+        });
+}
@@ -4,16 +4,38 @@ export function factoryReset(): void {
             "Are you sure you want to reset to factory settings? This action cannot be undone."
         )
     ) {
-        fetch("/factory_reset", { method: "GET" })
-            .then((response) => response.text())
-            .then(() => {
-                const messageElement = document.getElementById(
-                    "message"
-                ) as HTMLElement;
-                messageElement.innerText = "Factory reset initiated.";
+        const messageElement = document.getElementById(
+            "message"
+        ) as HTMLElement;
+
+        fetch("/factory_reset/token", { method: "POST" })
+            .then((response) => {
+                if (!response.ok) {
+                    throw new Error("Failed to get a factory reset token.");
+                }
+                return response.json();
+            })
+            .then((data: { token: string }) =>
+                fetch("/factory_reset", {
+                    method: "POST",
+                    headers: { "Content-Type": "application/json" },
+                    body: JSON.stringify({ token: data.token }),
+                })
+            )
+            .then((response) => {
+                if (!response.ok) {
+                    throw new Error("Factory reset rejected.");
+                }
+                return response.text();
+            })
+            .then((text) => {
+                messageElement.innerText = text;
+                messageElement.className = "message success";
             })
             .catch((error) => {
                 console.error("Error initiating factory reset:", error);
+                messageElement.innerText = "Error: Could not start the factory reset.";
+                messageElement.className = "message error";
             });
     }
 }
@@ -33,3 +55,79 @@ export function handlePowerModeChange(): void {
         }
     }
 }
+
+function postSnapshotUrl(url: string | null, successText: string): void {
+    const messageElement = document.getElementById("message") as HTMLElement;
+
+    fetch("/api/snapshot", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({ url }),
+    })
+        .then((response) => {
+            if (!response.ok) {
+                throw new Error("Failed to update snapshot URL.");
+            }
+        })
+        .then(() => {
+            messageElement.innerText = successText;
+            messageElement.className = "message success";
+        })
+        .catch((error) => {
+            console.error("Error:", error);
+            messageElement.innerText = "Error: Could not update snapshot URL.";
+            messageElement.className = "message error";
+        });
+}
+
+export function setSnapshotUrl(): void {
+    const url = (
+        document.getElementById("snapshotUrlInput") as HTMLInputElement
+    ).value.trim();
+
+    if (!url.startsWith("http://") && !url.startsWith("https://")) {
+        alert("The snapshot URL must start with http:// or https://.");
+        return;
+    }
+
+    postSnapshotUrl(url, "Nightly settings snapshot enabled.");
+}
+
+export function clearSnapshotUrl(): void {
+    postSnapshotUrl(null, "Nightly settings snapshot disabled.");
+}
+
+export function cloneFrom(): void {
+    const messageElement = document.getElementById("message") as HTMLElement;
+    const host = (
+        document.getElementById("cloneHostInput") as HTMLInputElement
+    ).value.trim();
+    const token = (
+        document.getElementById("cloneTokenInput") as HTMLInputElement
+    ).value.trim();
+
+    if (!host) {
+        alert("Enter the host name or IP address of the other clock.");
+        return;
+    }
+
+    fetch("/api/clone_from", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({ host: host, token: token || null }),
+    })
+        .then((response) => {
+            if (!response.ok) {
+                throw new Error("Failed to clone settings.");
+            }
+        })
+        .then(() => {
+            messageElement.innerText = `Settings cloned from ${host}. Restarting...`;
+            messageElement.className = "message success";
+        })
+        .catch((error) => {
+            console.error("Error:", error);
+            messageElement.innerText = `Error: Could not clone settings from ${host}.`;
+            messageElement.className = "message error";
+        });
+}
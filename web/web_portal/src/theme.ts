@@ -2,10 +2,17 @@ export function setTheme(): void {
     const theme = (document.getElementById("themeSelect") as HTMLSelectElement)
         .value;
 
-    fetch("/set_theme?" + encodeURIComponent(theme), {
-        method: "GET",
+    fetch("/set_theme", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({ theme: theme }),
     })
-        .then((response) => response.text())
+        .then(async (response) => {
+            if (!response.ok) {
+                const { error } = await response.json();
+                throw new Error(error);
+            }
+        })
         .then(() => {
             const messageElement = document.getElementById(
                 "message"
@@ -14,3 +21,31 @@ export function setTheme(): void {
         })
         .catch((error) => console.error("Error changing theme:", error));
 }
+
+interface ThemeInfo {
+    name: string;
+    label: string;
+}
+
+export function populateThemeSelect(): void {
+    const themeSelect = document.getElementById(
+        "themeSelect"
+    ) as HTMLSelectElement;
+
+    fetch("/api/themes")
+        .then((response) => response.json() as Promise<ThemeInfo[]>)
+        .then((themes) => {
+            const selected = themeSelect.value;
+            themeSelect.innerHTML = "";
+
+            themes.forEach((theme) => {
+                const option = document.createElement("option");
+                option.value = theme.name;
+                option.textContent = theme.label;
+                themeSelect.appendChild(option);
+            });
+
+            themeSelect.value = selected;
+        })
+        .catch((error) => console.error("Error fetching themes:", error));
+}
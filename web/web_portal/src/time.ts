@@ -1,15 +1,14 @@
 import { fetchStatus } from "./status";
-import { timezones } from "./timezones";
 
 export function syncTime(): void {
     fetch("/sync_time", {
-        method: "GET",
+        method: "POST",
     })
         .then((response: Response) => response.text())
         .then(() => {
             const messageElement = document.getElementById("message");
             if (messageElement) {
-                messageElement.innerText = "Time synced successfully!";
+                messageElement.innerText = "Time sync started!";
             }
             fetchStatus();
         })
@@ -46,10 +45,43 @@ export function populateTimezoneSelect(): void {
     ) as HTMLSelectElement;
     timezoneSelect.innerHTML = "";
 
-    timezones.forEach((tz) => {
-        let option = document.createElement("option");
-        option.value = tz;
-        option.textContent = tz;
-        timezoneSelect.appendChild(option);
-    });
+    fetch("/api/timezones")
+        .then((response) => response.json() as Promise<string[]>)
+        .then((timezones) => {
+            timezones.forEach((tz) => {
+                let option = document.createElement("option");
+                option.value = tz;
+                option.textContent = tz;
+                timezoneSelect.appendChild(option);
+            });
+        })
+        .catch((error) => console.error("Error fetching timezones:", error));
+}
+
+export function controlStopwatch(action: "start" | "stop" | "reset"): void {
+    const messageElement = document.getElementById("message") as HTMLElement;
+
+    fetch("/api/stopwatch", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({ action }),
+    })
+        .then((response) => {
+            if (!response.ok) {
+                throw new Error("Failed to control the stopwatch.");
+            }
+            return response.json();
+        })
+        .then((stopwatch: { running: boolean; elapsed_ms: number }) => {
+            const seconds = Math.floor(stopwatch.elapsed_ms / 1000);
+            messageElement.innerText = stopwatch.running
+                ? "Stopwatch running"
+                : `Stopwatch at ${Math.floor(seconds / 60)}:${String(seconds % 60).padStart(2, "0")}`;
+            messageElement.className = "message success";
+        })
+        .catch((error) => {
+            console.error("Error:", error);
+            messageElement.innerText = "Error: Could not control the stopwatch.";
+            messageElement.className = "message error";
+        });
 }
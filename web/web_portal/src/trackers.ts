@@ -0,0 +1,155 @@
+interface Tracker {
+    name: string;
+    since: string;
+}
+
+interface Trackers {
+    interval: number;
+    trackers: Tracker[];
+}
+
+function showMessage(text: string, className: string): void {
+    const messageElement = document.getElementById("message") as HTMLElement;
+    messageElement.innerText = text;
+    messageElement.className = `message ${className}`;
+}
+
+function today(): string {
+    const now = new Date();
+    const pad = (value: number) => String(value).padStart(2, "0");
+    return `${now.getFullYear()}-${pad(now.getMonth() + 1)}-${pad(now.getDate())}`;
+}
+
+function daysSince(since: string): number {
+    const elapsed = new Date().getTime() - new Date(`${since}T00:00:00`).getTime();
+    return Math.max(0, Math.floor(elapsed / 86400000));
+}
+
+function sendTracker(
+    method: string,
+    query: string,
+    body: Tracker | null,
+    successText: string
+): void {
+    fetch(`/api/trackers${query}`, {
+        method,
+        headers: { "Content-Type": "application/json" },
+        body: body ? JSON.stringify(body) : undefined,
+    })
+        .then((response) => {
+            if (!response.ok) {
+                throw new Error("Failed to update trackers.");
+            }
+        })
+        .then(() => {
+            showMessage(successText, "success");
+            fetchTrackers();
+        })
+        .catch((error) => {
+            console.error("Error:", error);
+            showMessage("Error: Could not update trackers.", "error");
+        });
+}
+
+function renderTrackers(data: Trackers): void {
+    const list = document.getElementById("trackerList") as HTMLElement;
+    const intervalInput = document.getElementById(
+        "trackerIntervalInput"
+    ) as HTMLInputElement;
+
+    intervalInput.value = String(data.interval);
+    list.innerHTML = "";
+
+    data.trackers.forEach((tracker, index) => {
+        const row = document.createElement("div");
+        row.className = "row";
+
+        const label = document.createElement("span");
+        label.innerText = `${tracker.name}: ${daysSince(tracker.since)} days`;
+
+        const resetButton = document.createElement("button");
+        resetButton.innerText = "Reset";
+        resetButton.addEventListener("click", () =>
+            sendTracker(
+                "PUT",
+                `?${index}`,
+                { name: tracker.name, since: today() },
+                "Tracker reset"
+            )
+        );
+
+        const deleteButton = document.createElement("button");
+        deleteButton.innerText = "Delete";
+        deleteButton.addEventListener("click", () =>
+            sendTracker("DELETE", `?${index}`, null, "Tracker deleted")
+        );
+
+        row.append(label, resetButton, deleteButton);
+        list.append(row);
+    });
+}
+
+export function fetchTrackers(): void {
+    fetch("/api/trackers", { method: "GET" })
+        .then((response) => response.json())
+        .then((data: Trackers) => renderTrackers(data))
+        .catch((error) => {
+            console.error("Error fetching trackers:", error);
+        });
+}
+
+export function addTracker(): void {
+    const nameInput = document.getElementById(
+        "trackerNameInput"
+    ) as HTMLInputElement;
+    const sinceInput = document.getElementById(
+        "trackerSinceInput"
+    ) as HTMLInputElement;
+
+    const name = nameInput.value.trim();
+    const since = sinceInput.value || today();
+
+    if (!name || name.length > 16) {
+        alert("Tracker names must have between 1 and 16 characters.");
+        return;
+    }
+
+    nameInput.value = "";
+    sendTracker("POST", "", { name, since }, "Tracker added");
+}
+
+export function setTrackerInterval(): void {
+    const intervalInput = document.getElementById(
+        "trackerIntervalInput"
+    ) as HTMLInputElement;
+    const interval = Number(intervalInput.value);
+
+    if (!Number.isInteger(interval) || interval < 0 || interval > 240) {
+        alert("The interval must be between 0 and 240 minutes.");
+        return;
+    }
+
+    fetch("/api/trackers/interval", {
+        method: "POST",
+        headers: { "Content-Type": "application/json" },
+        body: JSON.stringify({ interval: interval }),
+    })
+        .then(async (response) => {
+            if (!response.ok) {
+                const data = await response.json().catch(() => null);
+                throw new Error(data?.error ?? "Failed to set tracker interval.");
+            }
+        })
+        .then(() => {
+            showMessage(
+                interval === 0
+                    ? "Tracker rotation disabled"
+                    : `Trackers will scroll every ${interval} minutes`,
+                "success"
+            );
+        })
+        .catch((error) => {
+            console.error("Error:", error);
+            showMessage("Error: Could not set tracker interval.", "error");
+        });
+}